@@ -0,0 +1,65 @@
+//! Benchmarking for `pallet-app-registry`.
+
+use frame_benchmarking::v2::*;
+
+#[benchmarks]
+mod benchmarks {
+    use crate::{AppUsage, Apps, Call, Config, Pallet, Usage};
+    use frame_benchmarking::v2::*;
+    use frame_support::traits::EnsureOrigin;
+    use sp_app_registry::AppQuota;
+    use sp_runtime::Perbill;
+
+    const SEED: u32 = 0;
+
+    /// Benchmark `register_app` extrinsic with the worst possible conditions:
+    /// - The application is already registered and its quota is updated
+    #[benchmark]
+    fn register_app() -> Result<(), BenchmarkError> {
+        let origin =
+            T::RegistrarOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let who: T::AccountId = account("app", 1, SEED);
+        Apps::<T>::insert(&who, dummy_quota());
+        let quota = AppQuota {
+            bytes_per_block: u32::MAX,
+            bytes_per_day: u64::MAX,
+            fee_discount: Perbill::one(),
+        };
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, who.clone(), quota);
+
+        assert_eq!(Pallet::<T>::app_quota(&who), Some(quota));
+
+        Ok(())
+    }
+
+    /// Benchmark `deregister_app` extrinsic with the worst possible conditions:
+    /// - The application has data usage recorded
+    #[benchmark]
+    fn deregister_app() -> Result<(), BenchmarkError> {
+        let origin =
+            T::RegistrarOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let who: T::AccountId = account("app", 1, SEED);
+        Apps::<T>::insert(&who, dummy_quota());
+        Usage::<T>::insert(&who, AppUsage::default());
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, who.clone());
+
+        assert!(Pallet::<T>::app_quota(&who).is_none());
+        assert!(!Usage::<T>::contains_key(&who));
+
+        Ok(())
+    }
+
+    fn dummy_quota() -> AppQuota {
+        AppQuota {
+            bytes_per_block: 1024,
+            bytes_per_day: 1024 * 1024,
+            fee_discount: Perbill::from_percent(50),
+        }
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,38 @@
+// Copyright (C) 2021 Subspace Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Default weights for the App Registry Pallet
+//! This file was not auto-generated.
+//!
+//! Values are conservative placeholders picked by hand, they were NOT measured. Replace them with
+//! weights generated from benchmarks in `benchmarking.rs` using `subspace-node benchmark pallet`.
+
+use frame_support::traits::Get;
+use frame_support::weights::constants::RocksDbWeight;
+use frame_support::weights::Weight;
+
+impl crate::WeightInfo for () {
+    /// Writes `Apps`
+    fn register_app() -> Weight {
+        Weight::from_parts(15_000_000, 0).saturating_add(RocksDbWeight::get().writes(1))
+    }
+
+    /// Reads and removes `Apps`, removes `Usage`
+    fn deregister_app() -> Weight {
+        Weight::from_parts(20_000_000, 3_600)
+            .saturating_add(RocksDbWeight::get().reads(1))
+            .saturating_add(RocksDbWeight::get().writes(2))
+    }
+}
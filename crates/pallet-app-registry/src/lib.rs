@@ -0,0 +1,242 @@
+// Copyright (C) 2023 Subspace Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pallet for registered applications with data upload quotas and fee discounts.
+//!
+//! Applications are registered by [`RegistrarOrigin`](Config::RegistrarOrigin) and receive
+//! per-block and per-day quotas for data-carrying extrinsics as well as a fee discount for them.
+//! Quotas are enforced by the runtime in the data submission path using
+//! [`Pallet::has_remaining_quota()`] and [`Pallet::note_data_bytes()`], accounts that are not
+//! registered are not affected by this pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![forbid(unsafe_code)]
+#![warn(rust_2018_idioms, missing_debug_implementations)]
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+mod default_weights;
+#[cfg(all(feature = "std", test))]
+mod mock;
+#[cfg(all(feature = "std", test))]
+mod tests;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::sp_runtime::traits::One;
+use frame_support::sp_runtime::Perbill;
+use frame_support::traits::Get;
+use frame_support::weights::Weight;
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use scale_info::TypeInfo;
+use sp_app_registry::{AppQuota, RemainingQuota};
+
+pub trait WeightInfo {
+    fn register_app() -> Weight;
+    fn deregister_app() -> Weight;
+}
+
+/// Data usage of registered application, counters are only valid for the block and day they were
+/// last updated in.
+#[derive(Debug, Default, Copy, Clone, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct AppUsage<BlockNumber> {
+    /// Block number `block_bytes` correspond to
+    block_number: BlockNumber,
+    /// Bytes of data-carrying extrinsics included in `block_number`
+    block_bytes: u32,
+    /// Day (block number divided by blocks per day) `day_bytes` correspond to
+    day: BlockNumber,
+    /// Bytes of data-carrying extrinsics included in `day`
+    day_bytes: u64,
+}
+
+#[frame_support::pallet]
+mod pallet {
+    use super::{AppUsage, WeightInfo};
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+    use sp_app_registry::AppQuota;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// `pallet-app-registry` events
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Origin that can register and deregister applications.
+        type RegistrarOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Number of blocks in a day, used for per-day quotas.
+        #[pallet::constant]
+        type BlocksPerDay: Get<BlockNumberFor<Self>>;
+
+        type WeightInfo: WeightInfo;
+    }
+
+    /// Quotas of registered applications.
+    #[pallet::storage]
+    pub(super) type Apps<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, AppQuota>;
+
+    /// Data usage of registered applications.
+    #[pallet::storage]
+    pub(super) type Usage<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, AppUsage<BlockNumberFor<T>>, ValueQuery>;
+
+    /// Pallet app registry, used for data upload quotas and fee discounts of registered
+    /// applications.
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    /// `pallet-app-registry` events
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Application was registered or its quota was updated.
+        AppRegistered {
+            /// Account of the application.
+            who: T::AccountId,
+            /// New quota of the application.
+            quota: AppQuota,
+        },
+        /// Application was deregistered.
+        AppDeregistered {
+            /// Account of the application.
+            who: T::AccountId,
+        },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Application is not registered.
+        AppNotRegistered,
+        /// Quota doesn't allow any data or its daily limit is below the per-block limit.
+        InvalidQuota,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Register application or update quota of already registered application, data usage
+        /// within the current block and day is preserved.
+        #[pallet::call_index(0)]
+        #[pallet::weight(<T as Config>::WeightInfo::register_app())]
+        pub fn register_app(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+            quota: AppQuota,
+        ) -> DispatchResult {
+            T::RegistrarOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                quota.bytes_per_block > 0
+                    && quota.bytes_per_day >= u64::from(quota.bytes_per_block),
+                Error::<T>::InvalidQuota
+            );
+
+            Apps::<T>::insert(&who, quota);
+
+            Self::deposit_event(Event::AppRegistered { who, quota });
+
+            Ok(())
+        }
+
+        /// Deregister application, its data-carrying extrinsics will no longer be limited or
+        /// discounted.
+        #[pallet::call_index(1)]
+        #[pallet::weight(<T as Config>::WeightInfo::deregister_app())]
+        pub fn deregister_app(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            T::RegistrarOrigin::ensure_origin(origin)?;
+
+            Apps::<T>::take(&who).ok_or(Error::<T>::AppNotRegistered)?;
+            Usage::<T>::remove(&who);
+
+            Self::deposit_event(Event::AppDeregistered { who });
+
+            Ok(())
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Quota of registered application, `None` if account is not registered.
+    pub fn app_quota(who: &T::AccountId) -> Option<AppQuota> {
+        Apps::<T>::get(who)
+    }
+
+    /// Remaining quota of registered application in the current block, `None` if account is not
+    /// registered.
+    pub fn remaining_quota(who: &T::AccountId) -> Option<RemainingQuota> {
+        let quota = Apps::<T>::get(who)?;
+        let usage = Self::current_usage(who);
+
+        Some(RemainingQuota {
+            block_bytes: quota.bytes_per_block.saturating_sub(usage.block_bytes),
+            day_bytes: quota.bytes_per_day.saturating_sub(usage.day_bytes),
+        })
+    }
+
+    /// Whether data-carrying extrinsic of `size` bytes submitted by `who` can ever fit into its
+    /// quota, always `true` for accounts that are not registered.
+    pub fn data_fits_into_quota(who: &T::AccountId, size: u32) -> bool {
+        Apps::<T>::get(who).map_or(true, |quota| {
+            size <= quota.bytes_per_block && u64::from(size) <= quota.bytes_per_day
+        })
+    }
+
+    /// Whether data-carrying extrinsic of `size` bytes submitted by `who` fits into remaining
+    /// quota in the current block, always `true` for accounts that are not registered.
+    pub fn has_remaining_quota(who: &T::AccountId, size: u32) -> bool {
+        Self::remaining_quota(who).map_or(true, |remaining_quota| remaining_quota.fits(size))
+    }
+
+    /// Account for data-carrying extrinsic of `size` bytes submitted by `who` in the current block,
+    /// does nothing for accounts that are not registered.
+    ///
+    /// Must be preceded by [`Self::has_remaining_quota()`] check.
+    pub fn note_data_bytes(who: &T::AccountId, size: u32) {
+        if !Apps::<T>::contains_key(who) {
+            return;
+        }
+
+        let mut usage = Self::current_usage(who);
+        usage.block_bytes = usage.block_bytes.saturating_add(size);
+        usage.day_bytes = usage.day_bytes.saturating_add(u64::from(size));
+        Usage::<T>::insert(who, usage);
+    }
+
+    /// Fee discount for data-carrying extrinsics submitted by `who`, zero for accounts that are
+    /// not registered.
+    pub fn fee_discount(who: &T::AccountId) -> Perbill {
+        Apps::<T>::get(who)
+            .map(|quota| quota.fee_discount)
+            .unwrap_or_default()
+    }
+
+    /// Usage of `who` with counters reset if they were last updated in a different block or day.
+    fn current_usage(who: &T::AccountId) -> AppUsage<BlockNumberFor<T>> {
+        let block_number = frame_system::Pallet::<T>::block_number();
+        let day = block_number / T::BlocksPerDay::get().max(One::one());
+        let mut usage = Usage::<T>::get(who);
+
+        if usage.block_number != block_number {
+            usage.block_number = block_number;
+            usage.block_bytes = 0;
+        }
+        if usage.day != day {
+            usage.day = day;
+            usage.day_bytes = 0;
+        }
+
+        usage
+    }
+}
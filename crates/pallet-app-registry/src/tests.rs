@@ -0,0 +1,135 @@
+use crate::mock::{new_test_ext, AppRegistry, RuntimeEvent, RuntimeOrigin, System, Test};
+use crate::Error;
+use frame_support::{assert_noop, assert_ok};
+use sp_app_registry::{AppQuota, RemainingQuota};
+use sp_runtime::{DispatchError, Perbill};
+
+const APP_ACCOUNT_ID: u64 = 100;
+const OTHER_ACCOUNT_ID: u64 = 101;
+
+const QUOTA: AppQuota = AppQuota {
+    bytes_per_block: 100,
+    bytes_per_day: 250,
+    fee_discount: Perbill::from_percent(20),
+};
+
+#[test]
+fn register_and_deregister() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            AppRegistry::register_app(RuntimeOrigin::signed(APP_ACCOUNT_ID), APP_ACCOUNT_ID, QUOTA),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            AppRegistry::register_app(
+                RuntimeOrigin::root(),
+                APP_ACCOUNT_ID,
+                AppQuota {
+                    bytes_per_day: 99,
+                    ..QUOTA
+                }
+            ),
+            Error::<Test>::InvalidQuota
+        );
+
+        assert_ok!(AppRegistry::register_app(
+            RuntimeOrigin::root(),
+            APP_ACCOUNT_ID,
+            QUOTA
+        ));
+        System::assert_last_event(RuntimeEvent::AppRegistry(
+            crate::Event::<Test>::AppRegistered {
+                who: APP_ACCOUNT_ID,
+                quota: QUOTA,
+            },
+        ));
+        assert_eq!(AppRegistry::app_quota(&APP_ACCOUNT_ID), Some(QUOTA));
+        assert_eq!(
+            AppRegistry::fee_discount(&APP_ACCOUNT_ID),
+            Perbill::from_percent(20)
+        );
+        assert_eq!(
+            AppRegistry::fee_discount(&OTHER_ACCOUNT_ID),
+            Perbill::zero()
+        );
+
+        assert_ok!(AppRegistry::deregister_app(
+            RuntimeOrigin::root(),
+            APP_ACCOUNT_ID
+        ));
+        System::assert_last_event(RuntimeEvent::AppRegistry(
+            crate::Event::<Test>::AppDeregistered {
+                who: APP_ACCOUNT_ID,
+            },
+        ));
+        assert_eq!(AppRegistry::app_quota(&APP_ACCOUNT_ID), None);
+        assert_noop!(
+            AppRegistry::deregister_app(RuntimeOrigin::root(), APP_ACCOUNT_ID),
+            Error::<Test>::AppNotRegistered
+        );
+    });
+}
+
+#[test]
+fn quota_enforcement() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AppRegistry::register_app(
+            RuntimeOrigin::root(),
+            APP_ACCOUNT_ID,
+            QUOTA
+        ));
+
+        // Accounts that are not registered are not limited
+        assert!(AppRegistry::data_fits_into_quota(
+            &OTHER_ACCOUNT_ID,
+            u32::MAX
+        ));
+        assert!(AppRegistry::has_remaining_quota(
+            &OTHER_ACCOUNT_ID,
+            u32::MAX
+        ));
+        AppRegistry::note_data_bytes(&OTHER_ACCOUNT_ID, 1000);
+        assert_eq!(AppRegistry::remaining_quota(&OTHER_ACCOUNT_ID), None);
+
+        assert!(AppRegistry::data_fits_into_quota(&APP_ACCOUNT_ID, 100));
+        assert!(!AppRegistry::data_fits_into_quota(&APP_ACCOUNT_ID, 101));
+
+        assert!(AppRegistry::has_remaining_quota(&APP_ACCOUNT_ID, 60));
+        AppRegistry::note_data_bytes(&APP_ACCOUNT_ID, 60);
+        assert_eq!(
+            AppRegistry::remaining_quota(&APP_ACCOUNT_ID),
+            Some(RemainingQuota {
+                block_bytes: 40,
+                day_bytes: 190,
+            })
+        );
+        // Doesn't fit into the current block anymore
+        assert!(!AppRegistry::has_remaining_quota(&APP_ACCOUNT_ID, 60));
+
+        // Block quota is reset in the next block, but daily usage is retained
+        System::set_block_number(2);
+        assert!(AppRegistry::has_remaining_quota(&APP_ACCOUNT_ID, 100));
+        AppRegistry::note_data_bytes(&APP_ACCOUNT_ID, 100);
+        System::set_block_number(3);
+        AppRegistry::note_data_bytes(&APP_ACCOUNT_ID, 80);
+        System::set_block_number(4);
+        assert_eq!(
+            AppRegistry::remaining_quota(&APP_ACCOUNT_ID),
+            Some(RemainingQuota {
+                block_bytes: 100,
+                day_bytes: 10,
+            })
+        );
+        assert!(!AppRegistry::has_remaining_quota(&APP_ACCOUNT_ID, 11));
+
+        // Daily quota is reset on the next day
+        System::set_block_number(crate::mock::BLOCKS_PER_DAY);
+        assert_eq!(
+            AppRegistry::remaining_quota(&APP_ACCOUNT_ID),
+            Some(RemainingQuota {
+                block_bytes: 100,
+                day_bytes: 250,
+            })
+        );
+    });
+}
@@ -3,6 +3,7 @@
 use super::*;
 use crate::alloc::borrow::ToOwned;
 use crate::domain_registry::DomainConfig;
+use crate::domain_treasury::{domain_treasury_account, fund_domain_treasury};
 use crate::staking::{do_reward_operators, OperatorConfig, OperatorStatus};
 use crate::staking_epoch::{do_finalize_domain_current_epoch, do_finalize_domain_epoch_staking};
 use crate::{DomainBlockNumberFor, Pallet as Domains};
@@ -107,6 +108,7 @@ mod benchmarks {
         let (_, operator_id) = register_helper_operator::<T>(domain_id, minimum_nominator_stake);
         do_finalize_domain_current_epoch::<T>(domain_id)
             .expect("finalize domain staking should success");
+        DomainTreasuryShare::<T>::insert(domain_id, T::MaxDomainTreasuryShare::get());
 
         for i in 0..max_pending_staking_op {
             let nominator = account("nominator", i, SEED);
@@ -139,6 +141,9 @@ mod benchmarks {
 
         #[block]
         {
+            let operator_rewards = fund_domain_treasury::<T>(domain_id, operator_rewards)
+                .expect("fund domain treasury should success");
+
             do_reward_operators::<T>(domain_id, vec![operator_id].into_iter(), operator_rewards)
                 .expect("reward operator should success");
 
@@ -398,6 +403,43 @@ mod benchmarks {
         );
     }
 
+    #[benchmark]
+    fn set_domain_treasury_share() {
+        let domain_id = register_domain::<T>();
+        let domain_owner = DomainRegistry::<T>::get(domain_id)
+            .expect("domain object must exist")
+            .owner_account_id;
+        let share = T::MaxDomainTreasuryShare::get();
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(domain_owner), domain_id, share);
+
+        assert_eq!(DomainTreasuryShare::<T>::get(domain_id), share);
+    }
+
+    /// Benchmark `spend_from_domain_treasury` extrinsic with the worst possible conditions:
+    /// - The beneficiary account does not exist yet
+    #[benchmark]
+    fn spend_from_domain_treasury() {
+        let domain_id = register_domain::<T>();
+        let domain_owner = DomainRegistry::<T>::get(domain_id)
+            .expect("domain object must exist")
+            .owner_account_id;
+        let beneficiary: T::AccountId = account("beneficiary", 1, SEED);
+        let amount = T::Currency::minimum_balance().saturating_mul(BalanceOf::<T>::from(100u32));
+        T::Currency::set_balance(&domain_treasury_account::<T>(domain_id), amount);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(domain_owner),
+            domain_id,
+            beneficiary.clone(),
+            amount,
+        );
+
+        assert_eq!(T::Currency::balance(&beneficiary), amount);
+    }
+
     fn register_runtime<T: Config>() -> RuntimeId {
         let runtime_blob =
             include_bytes!("../res/evm_domain_test_runtime.compact.compressed.wasm").to_vec();
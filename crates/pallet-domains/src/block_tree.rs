@@ -1,9 +1,10 @@
 //! Domain block tree
 
 use crate::{
-    BalanceOf, BlockTree, BlockTreeNodes, Config, ConsensusBlockHash, DomainBlockNumberFor,
-    DomainHashingFor, ExecutionInbox, ExecutionReceiptOf, HeadReceiptExtended, HeadReceiptNumber,
-    InboxedBundleAuthor, LatestConfirmedDomainBlock, Pallet, ReceiptHashFor,
+    BalanceOf, BlockTree, BlockTreeNodes, Config, ConfirmedDomainBlockConsensusNumber,
+    ConsensusBlockHash, DomainBlockNumberFor, DomainHashingFor, ExecutionInbox, ExecutionReceiptOf,
+    HeadReceiptExtended, HeadReceiptNumber, InboxedBundleAuthor, LatestConfirmedDomainBlock,
+    Pallet, ReceiptHashFor,
 };
 use codec::{Decode, Encode};
 use frame_support::{ensure, PalletError};
@@ -377,6 +378,17 @@ pub(crate) fn process_execution_receipt<T: Config>(
                     },
                 );
 
+                ConfirmedDomainBlockConsensusNumber::<T>::insert(
+                    domain_id,
+                    to_prune,
+                    frame_system::Pallet::<T>::current_block_number(),
+                );
+                if let Some(expired) =
+                    to_prune.checked_sub(&T::ConfirmedDomainBlockMappingDepth::get())
+                {
+                    ConfirmedDomainBlockConsensusNumber::<T>::remove(domain_id, expired);
+                }
+
                 return Ok(Some(ConfirmedDomainBlockInfo {
                     domain_block_number: to_prune,
                     operator_ids,
@@ -505,7 +517,8 @@ mod tests {
     use crate::tests::{
         create_dummy_bundle_with_receipts, create_dummy_receipt, extend_block_tree,
         extend_block_tree_from_zero, get_block_tree_node_at, new_test_ext_with_extensions,
-        register_genesis_domain, run_to_block, BlockTreePruningDepth, Test,
+        register_genesis_domain, run_to_block, BlockTreePruningDepth,
+        ConfirmedDomainBlockMappingDepth, Test,
     };
     use frame_support::dispatch::RawOrigin;
     use frame_support::{assert_err, assert_ok};
@@ -661,6 +674,48 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_confirmed_domain_block_consensus_number() {
+        let operator_id = 1u64;
+        let block_tree_pruning_depth = BlockTreePruningDepth::get();
+        let mapping_depth = ConfirmedDomainBlockMappingDepth::get();
+
+        let mut ext = new_test_ext_with_extensions();
+        ext.execute_with(|| {
+            let domain_id = register_genesis_domain(0u128, vec![operator_id]);
+            // Receipt of domain block `n` is submitted in consensus block `n + 1` and it is
+            // confirmed once the receipt `BlockTreePruningDepth` blocks later is submitted
+            let to = block_tree_pruning_depth + mapping_depth * 2;
+            extend_block_tree_from_zero(domain_id, operator_id, to);
+
+            let latest_confirmed = Pallet::<Test>::latest_confirmed_domain_block_number(domain_id);
+            assert_eq!(latest_confirmed, to - block_tree_pruning_depth - 2);
+
+            for domain_block_number in 0..=latest_confirmed {
+                let consensus_number = Pallet::<Test>::confirmed_domain_block_consensus_number(
+                    domain_id,
+                    domain_block_number,
+                );
+                if domain_block_number + mapping_depth > latest_confirmed {
+                    assert_eq!(
+                        consensus_number,
+                        Some((domain_block_number + block_tree_pruning_depth + 1) as u64)
+                    );
+                } else {
+                    // Mappings of older domain blocks are pruned
+                    assert_eq!(consensus_number, None);
+                }
+            }
+            assert_eq!(
+                Pallet::<Test>::confirmed_domain_block_consensus_number(
+                    domain_id,
+                    latest_confirmed + 1
+                ),
+                None
+            );
+        });
+    }
+
     #[test]
     fn test_confirm_current_head_receipt() {
         let creator = 0u128;
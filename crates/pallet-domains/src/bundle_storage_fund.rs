@@ -32,6 +32,7 @@ pub enum Error {
 #[derive(Encode, Decode)]
 pub enum AccountType {
     StorageFund,
+    DomainTreasury,
 }
 
 #[derive(TypeInfo, Debug, Encode, Decode, Clone, PartialEq, Eq, Default)]
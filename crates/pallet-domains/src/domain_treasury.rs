@@ -0,0 +1,223 @@
+//! Domain treasury
+//!
+//! Every domain has a treasury account that receives a configurable share of the domain execution
+//! fee of every confirmed domain block, the rest of the fee goes to operators as usual. Funds of
+//! the treasury can only be spent by the domain owner (or root), which allows domains to fund
+//! operator incentives and development on their own.
+
+use crate::bundle_storage_fund::AccountType;
+use crate::pallet::{DomainRegistry, DomainTreasuryShare};
+use crate::{BalanceOf, Config, Event, Pallet};
+use codec::{Decode, Encode};
+use frame_support::traits::fungible::{Inspect, Mutate};
+use frame_support::traits::tokens::{DepositConsequence, Preservation, Provenance};
+use frame_support::traits::Get;
+use frame_support::{ensure, PalletError};
+use scale_info::TypeInfo;
+use sp_domains::DomainId;
+use sp_runtime::traits::{AccountIdConversion, Zero};
+use sp_runtime::{Perbill, Saturating};
+
+/// Domain treasury specific errors
+#[derive(TypeInfo, Encode, Decode, PalletError, Debug, PartialEq)]
+pub enum Error {
+    DomainNotFound,
+    NotDomainOwner,
+    MintBalance,
+    BalanceTransfer,
+    ShareTooHigh,
+}
+
+/// Return the treasury account of the given domain.
+pub fn domain_treasury_account<T: Config>(domain_id: DomainId) -> T::AccountId {
+    T::PalletId::get().into_sub_account_truncating((AccountType::DomainTreasury, domain_id))
+}
+
+/// Return the balance of the treasury of the given domain.
+pub fn domain_treasury_balance<T: Config>(domain_id: DomainId) -> BalanceOf<T> {
+    T::Currency::balance(&domain_treasury_account::<T>(domain_id))
+}
+
+/// Ensure `maybe_who` is the owner of the domain, `None` stands for root that is allowed to manage
+/// treasuries of all domains.
+fn ensure_domain_owner<T: Config>(
+    maybe_who: Option<T::AccountId>,
+    domain_id: DomainId,
+) -> Result<(), Error> {
+    let domain_obj = DomainRegistry::<T>::get(domain_id).ok_or(Error::DomainNotFound)?;
+    if let Some(who) = maybe_who {
+        ensure!(domain_obj.owner_account_id == who, Error::NotDomainOwner);
+    }
+
+    Ok(())
+}
+
+pub(crate) fn do_set_domain_treasury_share<T: Config>(
+    maybe_who: Option<T::AccountId>,
+    domain_id: DomainId,
+    share: Perbill,
+) -> Result<(), Error> {
+    ensure_domain_owner::<T>(maybe_who, domain_id)?;
+    ensure!(
+        share <= T::MaxDomainTreasuryShare::get(),
+        Error::ShareTooHigh
+    );
+
+    DomainTreasuryShare::<T>::insert(domain_id, share);
+
+    Ok(())
+}
+
+pub(crate) fn do_spend_from_domain_treasury<T: Config>(
+    maybe_who: Option<T::AccountId>,
+    domain_id: DomainId,
+    beneficiary: &T::AccountId,
+    amount: BalanceOf<T>,
+) -> Result<(), Error> {
+    ensure_domain_owner::<T>(maybe_who, domain_id)?;
+
+    T::Currency::transfer(
+        &domain_treasury_account::<T>(domain_id),
+        beneficiary,
+        amount,
+        Preservation::Expendable,
+    )
+    .map_err(|_| Error::BalanceTransfer)?;
+
+    Ok(())
+}
+
+/// Move the domain treasury share of the domain execution fee of a confirmed domain block into the
+/// domain treasury, returns the remaining rewards that go to operators.
+///
+/// If the share can't be deposited (i.e. it is below the existential deposit of a not yet existing
+/// treasury account) the whole fee goes to operators.
+pub(crate) fn fund_domain_treasury<T: Config>(
+    domain_id: DomainId,
+    rewards: BalanceOf<T>,
+) -> Result<BalanceOf<T>, Error> {
+    let treasury_share = DomainTreasuryShare::<T>::get(domain_id).mul_floor(rewards);
+    if treasury_share.is_zero() {
+        return Ok(rewards);
+    }
+
+    let treasury_account = domain_treasury_account::<T>(domain_id);
+    if T::Currency::can_deposit(&treasury_account, treasury_share, Provenance::Minted)
+        != DepositConsequence::Success
+    {
+        return Ok(rewards);
+    }
+
+    T::Currency::mint_into(&treasury_account, treasury_share).map_err(|_| Error::MintBalance)?;
+
+    Pallet::<T>::deposit_event(Event::DomainTreasuryFunded {
+        domain_id,
+        amount: treasury_share,
+    });
+
+    Ok(rewards.saturating_sub(treasury_share))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pallet::Error as PalletError;
+    use crate::tests::{new_test_ext, register_genesis_domain, Test};
+    use frame_support::{assert_err, assert_ok};
+    use frame_system::RawOrigin;
+    use subspace_runtime_primitives::SSC;
+
+    type Domains = crate::Pallet<Test>;
+
+    #[test]
+    fn domain_treasury_funding_and_spending() {
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let domain_id = register_genesis_domain(0, vec![]);
+            let domain_owner = <Test as Config>::SudoId::get();
+            let beneficiary = 100;
+
+            // Without share configured all rewards go to operators
+            assert_eq!(
+                fund_domain_treasury::<Test>(domain_id, 10 * SSC).unwrap(),
+                10 * SSC
+            );
+            assert_eq!(domain_treasury_balance::<Test>(domain_id), 0);
+
+            // Share can't exceed the configured maximum, even for root
+            assert_err!(
+                Domains::set_domain_treasury_share(
+                    RawOrigin::Root.into(),
+                    domain_id,
+                    Perbill::from_percent(60),
+                ),
+                PalletError::<Test>::DomainTreasury(Error::ShareTooHigh)
+            );
+
+            // Only domain owner or root can configure the share
+            assert_err!(
+                Domains::set_domain_treasury_share(
+                    RawOrigin::Signed(beneficiary).into(),
+                    domain_id,
+                    Perbill::from_percent(10),
+                ),
+                PalletError::<Test>::DomainTreasury(Error::NotDomainOwner)
+            );
+            assert_ok!(Domains::set_domain_treasury_share(
+                RawOrigin::Signed(domain_owner).into(),
+                domain_id,
+                Perbill::from_percent(10),
+            ));
+            assert_eq!(
+                DomainTreasuryShare::<Test>::get(domain_id),
+                Perbill::from_percent(10)
+            );
+
+            assert_eq!(
+                fund_domain_treasury::<Test>(domain_id, 10 * SSC).unwrap(),
+                9 * SSC
+            );
+            assert_eq!(domain_treasury_balance::<Test>(domain_id), SSC);
+
+            // Only domain owner or root can spend
+            assert_err!(
+                Domains::spend_from_domain_treasury(
+                    RawOrigin::Signed(beneficiary).into(),
+                    domain_id,
+                    beneficiary,
+                    SSC / 2,
+                ),
+                PalletError::<Test>::DomainTreasury(Error::NotDomainOwner)
+            );
+            assert_ok!(Domains::spend_from_domain_treasury(
+                RawOrigin::Root.into(),
+                domain_id,
+                beneficiary,
+                SSC / 2,
+            ));
+            assert_eq!(domain_treasury_balance::<Test>(domain_id), SSC / 2);
+            assert_eq!(<Test as Config>::Currency::balance(&beneficiary), SSC / 2);
+
+            // Can't spend more than treasury has
+            assert_err!(
+                Domains::spend_from_domain_treasury(
+                    RawOrigin::Root.into(),
+                    domain_id,
+                    beneficiary,
+                    SSC,
+                ),
+                PalletError::<Test>::DomainTreasury(Error::BalanceTransfer)
+            );
+
+            // Unknown domain
+            assert_err!(
+                Domains::set_domain_treasury_share(
+                    RawOrigin::Root.into(),
+                    DomainId::new(100),
+                    Perbill::from_percent(10),
+                ),
+                PalletError::<Test>::DomainTreasury(Error::DomainNotFound)
+            );
+        });
+    }
+}
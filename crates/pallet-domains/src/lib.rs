@@ -27,6 +27,8 @@ mod tests;
 pub mod block_tree;
 mod bundle_storage_fund;
 pub mod domain_registry;
+pub mod domain_treasury;
+pub mod placeholder_weights;
 pub mod runtime_registry;
 mod staking;
 mod staking_epoch;
@@ -134,6 +136,10 @@ mod pallet {
         do_instantiate_domain, do_update_domain_allow_list, DomainConfig, DomainObject,
         Error as DomainRegistryError,
     };
+    use crate::domain_treasury::{
+        do_set_domain_treasury_share, do_spend_from_domain_treasury, Error as DomainTreasuryError,
+    };
+    use crate::placeholder_weights::PlaceholderWeightInfo;
     use crate::runtime_registry::{
         do_register_runtime, do_schedule_runtime_upgrade, do_upgrade_runtimes,
         register_runtime_at_genesis, Error as RuntimeRegistryError, RuntimeObject,
@@ -142,10 +148,10 @@ mod pallet {
     #[cfg(not(feature = "runtime-benchmarks"))]
     use crate::staking::do_reward_operators;
     use crate::staking::{
-        do_deregister_operator, do_nominate_operator, do_register_operator, do_slash_operators,
-        do_switch_operator_domain, do_unlock_funds, do_unlock_operator, do_withdraw_stake, Deposit,
-        DomainEpoch, Error as StakingError, Operator, OperatorConfig, SharePrice, StakingSummary,
-        Withdrawal,
+        do_deregister_operator, do_nominate_operator, do_register_operator,
+        do_rotate_operator_signing_key, do_slash_operators, do_switch_operator_domain,
+        do_unlock_funds, do_unlock_operator, do_withdraw_stake, Deposit, DomainEpoch,
+        Error as StakingError, Operator, OperatorConfig, SharePrice, StakingSummary, Withdrawal,
     };
     use crate::staking_epoch::{do_finalize_domain_current_epoch, Error as StakingEpochError};
     use crate::weights::WeightInfo;
@@ -175,7 +181,7 @@ mod pallet {
         AtLeast32BitUnsigned, BlockNumberProvider, CheckEqual, CheckedAdd, Header as HeaderT,
         MaybeDisplay, One, SimpleBitOps, Zero,
     };
-    use sp_runtime::Saturating;
+    use sp_runtime::{Perbill, Saturating};
     use sp_std::boxed::Box;
     use sp_std::collections::btree_map::BTreeMap;
     use sp_std::collections::btree_set::BTreeSet;
@@ -246,6 +252,11 @@ mod pallet {
         #[pallet::constant]
         type BlockTreePruningDepth: Get<DomainBlockNumberFor<Self>>;
 
+        /// Number of the most recently confirmed domain blocks of each domain for which the consensus
+        /// block that confirmed them is kept in `ConfirmedDomainBlockConsensusNumber`.
+        #[pallet::constant]
+        type ConfirmedDomainBlockMappingDepth: Get<DomainBlockNumberFor<Self>>;
+
         /// The maximum block size limit for all domain.
         #[pallet::constant]
         type MaxDomainBlockSize: Get<u32>;
@@ -267,7 +278,7 @@ mod pallet {
         type DomainInstantiationDeposit: Get<BalanceOf<Self>>;
 
         /// Weight information for extrinsics in this pallet.
-        type WeightInfo: WeightInfo;
+        type WeightInfo: WeightInfo + PlaceholderWeightInfo;
 
         /// Initial domain tx range value.
         #[pallet::constant]
@@ -330,6 +341,10 @@ mod pallet {
 
         /// Minimum balance for each initial domain account
         type MinInitialDomainAccountBalance: Get<BalanceOf<Self>>;
+
+        /// Maximum share of the domain execution fee that can go to the domain treasury.
+        #[pallet::constant]
+        type MaxDomainTreasuryShare: Get<Perbill>;
     }
 
     #[pallet::pallet]
@@ -414,6 +429,12 @@ mod pallet {
     pub(super) type PendingOperatorSwitches<T: Config> =
         StorageMap<_, Identity, DomainId, BTreeSet<OperatorId>, OptionQuery>;
 
+    /// Temporary hold of new signing keys of the operators who decided to rotate their key.
+    /// Once epoch is complete, new keys replace the current signing keys of these operators.
+    #[pallet::storage]
+    pub(super) type PendingOperatorSigningKeyRotations<T: Config> =
+        StorageMap<_, Identity, DomainId, BTreeMap<OperatorId, OperatorPublicKey>, OptionQuery>;
+
     /// Share price for the operator pool at the end of Domain epoch.
     // TODO: currently unbounded storage.
     #[pallet::storage]
@@ -575,6 +596,10 @@ mod pallet {
     pub(super) type LastEpochStakingDistribution<T: Config> =
         StorageMap<_, Identity, DomainId, ElectionVerificationParams<BalanceOf<T>>, OptionQuery>;
 
+    /// Share of the domain execution fee that goes to the domain treasury instead of operators.
+    #[pallet::storage]
+    pub(super) type DomainTreasuryShare<T> = StorageMap<_, Identity, DomainId, Perbill, ValueQuery>;
+
     /// Storage to hold all the domain's latest confirmed block.
     #[pallet::storage]
     pub(super) type LatestConfirmedDomainBlock<T: Config> = StorageMap<
@@ -585,6 +610,20 @@ mod pallet {
         OptionQuery,
     >;
 
+    /// Mapping of confirmed domain block number to the number of the consensus block that confirmed
+    /// it, only the last `ConfirmedDomainBlockMappingDepth` confirmed domain blocks of each domain
+    /// are kept.
+    #[pallet::storage]
+    pub(super) type ConfirmedDomainBlockConsensusNumber<T: Config> = StorageDoubleMap<
+        _,
+        Identity,
+        DomainId,
+        Identity,
+        DomainBlockNumberFor<T>,
+        BlockNumberFor<T>,
+        OptionQuery,
+    >;
+
     #[derive(TypeInfo, Encode, Decode, PalletError, Debug, PartialEq)]
     pub enum BundleError {
         /// Can not find the operator for given operator id.
@@ -701,6 +740,12 @@ mod pallet {
         }
     }
 
+    impl<T> From<DomainTreasuryError> for Error<T> {
+        fn from(err: DomainTreasuryError) -> Self {
+            Error::DomainTreasury(err)
+        }
+    }
+
     #[pallet::error]
     pub enum Error<T> {
         /// Invalid fraud proof.
@@ -717,6 +762,8 @@ mod pallet {
         BlockTree(BlockTreeError),
         /// Bundle storage fund specific errors
         BundleStorageFund(BundleStorageFundError),
+        /// Domain treasury specific errors
+        DomainTreasury(DomainTreasuryError),
     }
 
     /// Reason for slashing an operator
@@ -807,6 +854,16 @@ mod pallet {
         DomainOperatorAllowListUpdated {
             domain_id: DomainId,
         },
+        OperatorSigningKeyRotationScheduled {
+            operator_id: OperatorId,
+            new_signing_key: OperatorPublicKey,
+            effective_domain_epoch: DomainEpoch,
+        },
+        OperatorSigningKeyRotated {
+            operator_id: OperatorId,
+            old_signing_key: OperatorPublicKey,
+            new_signing_key: OperatorPublicKey,
+        },
         OperatorSlashed {
             operator_id: OperatorId,
             reason: SlashedReason<DomainBlockNumberFor<T>, ReceiptHashFor<T>>,
@@ -816,6 +873,19 @@ mod pallet {
             nominator_id: NominatorId<T>,
             amount: BalanceOf<T>,
         },
+        DomainTreasuryShareUpdated {
+            domain_id: DomainId,
+            share: Perbill,
+        },
+        DomainTreasuryFunded {
+            domain_id: DomainId,
+            amount: BalanceOf<T>,
+        },
+        DomainTreasurySpent {
+            domain_id: DomainId,
+            beneficiary: T::AccountId,
+            amount: BalanceOf<T>,
+        },
     }
 
     /// Per-domain state for tx range calculation.
@@ -898,10 +968,16 @@ mod pallet {
                         )
                         .map_err(Error::<T>::from)?;
 
+                        let operator_rewards = crate::domain_treasury::fund_domain_treasury::<T>(
+                            domain_id,
+                            confirmed_block_info.rewards,
+                        )
+                        .map_err(Error::<T>::from)?;
+
                         do_reward_operators::<T>(
                             domain_id,
                             confirmed_block_info.operator_ids.into_iter(),
-                            confirmed_block_info.rewards,
+                            operator_rewards,
                         )
                         .map_err(Error::<T>::from)?;
 
@@ -1304,6 +1380,73 @@ mod pallet {
             });
             Ok(())
         }
+
+        /// Schedules rotation of the operator's signing key.
+        /// New key replaces the current one once the current epoch of the operator's domain is
+        /// complete, until then bundles must still be signed with the current key. Both keys
+        /// should be present in the operator's keystore around the epoch boundary.
+        #[pallet::call_index(14)]
+        #[pallet::weight(Weight::from_all(10_000))]
+        pub fn rotate_operator_signing_key(
+            origin: OriginFor<T>,
+            operator_id: OperatorId,
+            new_signing_key: OperatorPublicKey,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let effective_domain_epoch =
+                do_rotate_operator_signing_key::<T>(who, operator_id, new_signing_key.clone())
+                    .map_err(Error::<T>::from)?;
+
+            Self::deposit_event(Event::OperatorSigningKeyRotationScheduled {
+                operator_id,
+                new_signing_key,
+                effective_domain_epoch,
+            });
+
+            Ok(())
+        }
+
+        /// Set the share of the domain execution fee that goes to the domain treasury, the rest
+        /// goes to operators. Can only be called by the domain owner or root.
+        #[pallet::call_index(15)]
+        #[pallet::weight(T::WeightInfo::set_domain_treasury_share())]
+        pub fn set_domain_treasury_share(
+            origin: OriginFor<T>,
+            domain_id: DomainId,
+            share: Perbill,
+        ) -> DispatchResult {
+            let maybe_who = ensure_signed_or_root(origin)?;
+
+            do_set_domain_treasury_share::<T>(maybe_who, domain_id, share)
+                .map_err(Error::<T>::from)?;
+
+            Self::deposit_event(Event::DomainTreasuryShareUpdated { domain_id, share });
+            Ok(())
+        }
+
+        /// Transfer funds from the domain treasury to the beneficiary. Can only be called by the
+        /// domain owner or root.
+        #[pallet::call_index(16)]
+        #[pallet::weight(T::WeightInfo::spend_from_domain_treasury())]
+        pub fn spend_from_domain_treasury(
+            origin: OriginFor<T>,
+            domain_id: DomainId,
+            beneficiary: T::AccountId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let maybe_who = ensure_signed_or_root(origin)?;
+
+            do_spend_from_domain_treasury::<T>(maybe_who, domain_id, &beneficiary, amount)
+                .map_err(Error::<T>::from)?;
+
+            Self::deposit_event(Event::DomainTreasurySpent {
+                domain_id,
+                beneficiary,
+                amount,
+            });
+            Ok(())
+        }
     }
 
     #[pallet::genesis_config]
@@ -1579,6 +1722,16 @@ impl<T: Config> Pallet<T> {
             .map(|operator| (operator.signing_key, operator.current_total_stake))
     }
 
+    pub fn domain_treasury_balance(domain_id: DomainId) -> BalanceOf<T> {
+        domain_treasury::domain_treasury_balance::<T>(domain_id)
+    }
+
+    pub fn operator_pending_signing_key(operator_id: OperatorId) -> Option<OperatorPublicKey> {
+        let operator = Operators::<T>::get(operator_id)?;
+        PendingOperatorSigningKeyRotations::<T>::get(operator.current_domain_id)?
+            .remove(&operator_id)
+    }
+
     fn check_bundle_duplication(opaque_bundle: &OpaqueBundleOf<T>) -> Result<(), BundleError> {
         // NOTE: it is important to use the hash that not incliude the signature, otherwise
         // the malicious operator may update its `signing_key` (this may support in the future)
@@ -1988,6 +2141,15 @@ impl<T: Config> Pallet<T> {
             .unwrap_or_default()
     }
 
+    /// Returns the number of the consensus block that confirmed the given domain block, `None` if
+    /// the domain block is not confirmed yet or was confirmed too long ago.
+    pub fn confirmed_domain_block_consensus_number(
+        domain_id: DomainId,
+        domain_block_number: DomainBlockNumberFor<T>,
+    ) -> Option<BlockNumberFor<T>> {
+        ConfirmedDomainBlockConsensusNumber::<T>::get(domain_id, domain_block_number)
+    }
+
     /// Returns the domain block limit of the given domain.
     pub fn domain_block_limit(domain_id: DomainId) -> Option<DomainBlockLimit> {
         DomainRegistry::<T>::get(domain_id).map(|domain_obj| DomainBlockLimit {
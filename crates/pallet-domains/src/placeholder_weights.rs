@@ -0,0 +1,53 @@
+//! Placeholder weights for pallet_domains.
+//!
+//! Domain treasury calls were added after weights in [`crate::weights`] were last generated.
+//! Values below are conservative estimates picked by hand, they were NOT measured. Benchmarks
+//! covering them exist, this module must be removed once weights are regenerated with
+//! `subspace-node benchmark pallet`.
+
+use crate::weights::SubstrateWeight;
+use frame_support::traits::Get;
+use frame_support::weights::constants::RocksDbWeight;
+use frame_support::weights::{RuntimeDbWeight, Weight};
+
+/// Weight functions of pallet_domains that are not benchmarked yet.
+pub trait PlaceholderWeightInfo {
+    /// Weight of `set_domain_treasury_share` call
+    fn set_domain_treasury_share() -> Weight;
+    /// Weight of `spend_from_domain_treasury` call
+    fn spend_from_domain_treasury() -> Weight;
+}
+
+/// Reads `DomainRegistry`, writes `DomainTreasuryShare`
+fn set_domain_treasury_share<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+    Weight::from_parts(20_000_000, 4_000)
+        .saturating_add(DbWeight::get().reads(1))
+        .saturating_add(DbWeight::get().writes(1))
+}
+
+/// Reads `DomainRegistry`, transfers between treasury and beneficiary accounts
+fn spend_from_domain_treasury<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+    Weight::from_parts(60_000_000, 6_500)
+        .saturating_add(DbWeight::get().reads(3))
+        .saturating_add(DbWeight::get().writes(2))
+}
+
+impl<T: frame_system::Config> PlaceholderWeightInfo for SubstrateWeight<T> {
+    fn set_domain_treasury_share() -> Weight {
+        set_domain_treasury_share::<T::DbWeight>()
+    }
+
+    fn spend_from_domain_treasury() -> Weight {
+        spend_from_domain_treasury::<T::DbWeight>()
+    }
+}
+
+impl PlaceholderWeightInfo for () {
+    fn set_domain_treasury_share() -> Weight {
+        set_domain_treasury_share::<RocksDbWeight>()
+    }
+
+    fn spend_from_domain_treasury() -> Weight {
+        spend_from_domain_treasury::<RocksDbWeight>()
+    }
+}
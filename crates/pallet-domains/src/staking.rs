@@ -3,8 +3,8 @@
 use crate::bundle_storage_fund::{self, deposit_reserve_for_storage_fund};
 use crate::pallet::{
     Deposits, DomainRegistry, DomainStakingSummary, NextOperatorId, NominatorCount,
-    OperatorIdOwner, OperatorSigningKey, Operators, PendingOperatorSwitches, PendingSlashes,
-    PendingStakingOperationCount, Withdrawals,
+    OperatorIdOwner, OperatorSigningKey, Operators, PendingOperatorSigningKeyRotations,
+    PendingOperatorSwitches, PendingSlashes, PendingStakingOperationCount, Withdrawals,
 };
 use crate::staking_epoch::mint_funds;
 use crate::{
@@ -244,6 +244,7 @@ pub enum Error {
     EpochNotComplete,
     UnlockPeriodNotComplete,
     OperatorNotDeregistered,
+    PendingSigningKeyRotation,
     BundleStorageFund(bundle_storage_fund::Error),
 }
 
@@ -658,6 +659,65 @@ pub(crate) fn do_switch_operator_domain<T: Config>(
     })
 }
 
+/// Schedules rotation of the operator's signing key at the end of the current domain epoch.
+/// Returns the domain epoch starting from which the new key is used.
+pub(crate) fn do_rotate_operator_signing_key<T: Config>(
+    operator_owner: T::AccountId,
+    operator_id: OperatorId,
+    new_signing_key: OperatorPublicKey,
+) -> Result<DomainEpoch, Error> {
+    ensure!(
+        OperatorIdOwner::<T>::get(operator_id) == Some(operator_owner),
+        Error::NotOperatorOwner
+    );
+
+    ensure!(
+        new_signing_key != OperatorPublicKey::from(ZERO_OPERATOR_SIGNING_KEY),
+        Error::InvalidOperatorSigningKey
+    );
+
+    // pending keys of other operators are indexed too, so this also rejects them
+    ensure!(
+        !OperatorSigningKey::<T>::contains_key(new_signing_key.clone()),
+        Error::DuplicateOperatorSigningKey
+    );
+
+    let operator = Operators::<T>::get(operator_id).ok_or(Error::UnknownOperator)?;
+    ensure!(
+        operator.status == OperatorStatus::Registered,
+        Error::OperatorNotRegistered
+    );
+
+    let domain_id = operator.current_domain_id;
+    let current_epoch_index = DomainStakingSummary::<T>::get(domain_id)
+        .ok_or(Error::DomainNotInitialized)?
+        .current_epoch_index;
+    let effective_epoch_index = current_epoch_index
+        .checked_add(One::one())
+        .ok_or(Error::EpochOverflow)?;
+
+    PendingOperatorSigningKeyRotations::<T>::try_mutate(domain_id, |maybe_rotations| {
+        let rotations = maybe_rotations.get_or_insert_with(BTreeMap::new);
+
+        // check if there is any ongoing rotation, if so reject
+        ensure!(
+            !rotations.contains_key(&operator_id),
+            Error::PendingSigningKeyRotation
+        );
+
+        note_pending_staking_operation::<T>(domain_id)?;
+
+        rotations.insert(operator_id, new_signing_key.clone());
+
+        Ok(())
+    })?;
+
+    // index the new key right away so that no other operator can register with it or rotate to it
+    OperatorSigningKey::<T>::insert(new_signing_key, operator_id);
+
+    Ok((domain_id, effective_epoch_index).into())
+}
+
 pub(crate) fn do_deregister_operator<T: Config>(
     operator_owner: T::AccountId,
     operator_id: OperatorId,
@@ -1236,8 +1296,8 @@ pub(crate) mod tests {
     use crate::domain_registry::{DomainConfig, DomainObject};
     use crate::pallet::{
         Config, Deposits, DomainRegistry, DomainStakingSummary, LatestConfirmedDomainBlock,
-        NextOperatorId, NominatorCount, OperatorIdOwner, Operators, PendingOperatorSwitches,
-        PendingSlashes, Withdrawals,
+        NextOperatorId, NominatorCount, OperatorIdOwner, OperatorSigningKey, Operators,
+        PendingOperatorSigningKeyRotations, PendingOperatorSwitches, PendingSlashes, Withdrawals,
     };
     use crate::staking::{
         do_convert_previous_epoch_withdrawal, do_nominate_operator, do_reward_operators,
@@ -1657,6 +1717,142 @@ pub(crate) mod tests {
         });
     }
 
+    #[test]
+    fn rotate_operator_signing_key() {
+        let domain_id = DomainId::new(0);
+        let operator_account = 1;
+        let operator_free_balance = 250 * SSC;
+        let operator_stake = 200 * SSC;
+        let pair = OperatorPair::from_seed(&U256::from(0u32).into());
+        let new_pair = OperatorPair::from_seed(&U256::from(1u32).into());
+
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let (operator_id, _) = register_operator(
+                domain_id,
+                operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                pair.public(),
+                BTreeMap::new(),
+            );
+
+            // only the operator owner can rotate the key
+            let res = Domains::rotate_operator_signing_key(
+                RuntimeOrigin::signed(operator_account + 1),
+                operator_id,
+                new_pair.public(),
+            );
+            assert_err!(res, Error::<Test>::Staking(StakingError::NotOperatorOwner));
+
+            // keys already in use are rejected
+            let res = Domains::rotate_operator_signing_key(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                pair.public(),
+            );
+            assert_err!(
+                res,
+                Error::<Test>::Staking(StakingError::DuplicateOperatorSigningKey)
+            );
+
+            let res = Domains::rotate_operator_signing_key(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                OperatorPublicKey::from(ZERO_OPERATOR_SIGNING_KEY),
+            );
+            assert_err!(
+                res,
+                Error::<Test>::Staking(StakingError::InvalidOperatorSigningKey)
+            );
+
+            let res = Domains::rotate_operator_signing_key(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                new_pair.public(),
+            );
+            assert_ok!(res);
+
+            // current key is still used until the end of the epoch, new key is reserved
+            let operator = Operators::<Test>::get(operator_id).unwrap();
+            assert_eq!(operator.signing_key, pair.public());
+            assert_eq!(
+                OperatorSigningKey::<Test>::get(new_pair.public()),
+                Some(operator_id)
+            );
+            assert_eq!(
+                PendingOperatorSigningKeyRotations::<Test>::get(domain_id).unwrap(),
+                BTreeMap::from_iter(vec![(operator_id, new_pair.public())])
+            );
+
+            // only one rotation per epoch
+            let res = Domains::rotate_operator_signing_key(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                OperatorPair::from_seed(&U256::from(2u32).into()).public(),
+            );
+            assert_err!(
+                res,
+                Error::<Test>::Staking(StakingError::PendingSigningKeyRotation)
+            );
+
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            let operator = Operators::<Test>::get(operator_id).unwrap();
+            assert_eq!(operator.signing_key, new_pair.public());
+            assert_eq!(OperatorSigningKey::<Test>::get(pair.public()), None);
+            assert_eq!(
+                OperatorSigningKey::<Test>::get(new_pair.public()),
+                Some(operator_id)
+            );
+            assert_eq!(
+                PendingOperatorSigningKeyRotations::<Test>::get(domain_id),
+                None
+            );
+        });
+    }
+
+    #[test]
+    fn rotate_deregistered_operator_signing_key() {
+        let domain_id = DomainId::new(0);
+        let operator_account = 1;
+        let operator_free_balance = 250 * SSC;
+        let operator_stake = 200 * SSC;
+        let pair = OperatorPair::from_seed(&U256::from(0u32).into());
+        let new_pair = OperatorPair::from_seed(&U256::from(1u32).into());
+
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let (operator_id, _) = register_operator(
+                domain_id,
+                operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                pair.public(),
+                BTreeMap::new(),
+            );
+
+            assert_ok!(Domains::rotate_operator_signing_key(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                new_pair.public(),
+            ));
+            assert_ok!(Domains::deregister_operator(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+            ));
+
+            // rotation is dropped and the new key is released
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            let operator = Operators::<Test>::get(operator_id).unwrap();
+            assert_eq!(operator.signing_key, pair.public());
+            assert_eq!(OperatorSigningKey::<Test>::get(new_pair.public()), None);
+        });
+    }
+
     #[test]
     fn switch_domain_operator() {
         let old_domain_id = DomainId::new(0);
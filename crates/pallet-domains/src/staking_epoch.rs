@@ -1,8 +1,9 @@
 //! Staking epoch transition for domain
 use crate::bundle_storage_fund::deposit_reserve_for_storage_fund;
 use crate::pallet::{
-    Deposits, DomainStakingSummary, LastEpochStakingDistribution, OperatorIdOwner, Operators,
-    PendingOperatorSwitches, PendingSlashes, PendingStakingOperationCount, Withdrawals,
+    Deposits, DomainStakingSummary, LastEpochStakingDistribution, OperatorIdOwner,
+    OperatorSigningKey, Operators, PendingOperatorSigningKeyRotations, PendingOperatorSwitches,
+    PendingSlashes, PendingStakingOperationCount, Withdrawals,
 };
 use crate::staking::{
     do_convert_previous_epoch_deposits, do_convert_previous_epoch_withdrawal, DomainEpoch,
@@ -22,6 +23,7 @@ use sp_domains::{DomainId, EpochIndex, OperatorId};
 use sp_runtime::traits::{CheckedAdd, CheckedSub, One, Zero};
 use sp_runtime::Saturating;
 use sp_std::collections::btree_map::BTreeMap;
+use sp_std::mem;
 
 #[derive(TypeInfo, Encode, Decode, PalletError, Debug, PartialEq)]
 pub enum Error {
@@ -45,6 +47,9 @@ pub(crate) fn do_finalize_domain_current_epoch<T: Config>(
     // slash the operators
     do_finalize_slashed_operators::<T>(domain_id).map_err(Error::SlashOperator)?;
 
+    // finalize any operator signing key rotations
+    do_finalize_operator_signing_key_rotations::<T>(domain_id);
+
     // finalize any operator switches
     do_finalize_switch_operator_domain::<T>(domain_id)?;
 
@@ -134,6 +139,41 @@ pub(crate) fn operator_take_reward_tax_and_stake<T: Config>(
     .map_err(Error::OperatorRewardStaking)
 }
 
+/// Replace signing keys of all the operators that rotated their key during this epoch.
+/// Rotations of operators that are no longer registered are dropped and their new keys released.
+fn do_finalize_operator_signing_key_rotations<T: Config>(domain_id: DomainId) {
+    let rotations = PendingOperatorSigningKeyRotations::<T>::take(domain_id).unwrap_or_default();
+    for (operator_id, new_signing_key) in rotations {
+        let maybe_old_signing_key = Operators::<T>::mutate(operator_id, |maybe_operator| {
+            let operator = maybe_operator.as_mut()?;
+
+            if operator.status != OperatorStatus::Registered {
+                return None;
+            }
+
+            Some(mem::replace(
+                &mut operator.signing_key,
+                new_signing_key.clone(),
+            ))
+        });
+
+        match maybe_old_signing_key {
+            Some(old_signing_key) => {
+                OperatorSigningKey::<T>::remove(old_signing_key.clone());
+
+                Pallet::<T>::deposit_event(Event::OperatorSigningKeyRotated {
+                    operator_id,
+                    old_signing_key,
+                    new_signing_key,
+                });
+            }
+            None => {
+                OperatorSigningKey::<T>::remove(new_signing_key);
+            }
+        }
+    }
+}
+
 /// Add all the switched operators to new domain as next operators.
 /// Once the new domain's epoch is complete, operators are included in the next epoch.
 fn do_finalize_switch_operator_domain<T: Config>(domain_id: DomainId) -> Result<(), Error> {
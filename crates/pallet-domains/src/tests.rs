@@ -41,7 +41,7 @@ use sp_domains_fraud_proof::{
 use sp_runtime::traits::{
     AccountIdConversion, BlakeTwo256, BlockNumberProvider, Hash as HashT, IdentityLookup, One, Zero,
 };
-use sp_runtime::{BuildStorage, Digest, OpaqueExtrinsic, Saturating};
+use sp_runtime::{BuildStorage, Digest, OpaqueExtrinsic, Perbill, Saturating};
 use sp_state_machine::backend::AsTrieBackend;
 use sp_state_machine::{prove_read, Backend, TrieBackendBuilder};
 use sp_std::sync::Arc;
@@ -114,6 +114,7 @@ parameter_types! {
     pub const DomainInstantiationDeposit: Balance = 100;
     pub const MaxDomainNameLength: u32 = 16;
     pub const BlockTreePruningDepth: u32 = 16;
+    pub const ConfirmedDomainBlockMappingDepth: u32 = 4;
 }
 
 pub struct ConfirmationDepthK;
@@ -186,6 +187,7 @@ parameter_types! {
     pub const DomainChainByteFee: Balance = 1;
     pub const MaxInitialDomainAccounts: u32 = 5;
     pub const MinInitialDomainAccountBalance: Balance = SSC;
+    pub const MaxDomainTreasuryShare: Perbill = Perbill::from_percent(50);
 }
 
 pub struct MockRandomness;
@@ -296,6 +298,7 @@ impl pallet_domains::Config for Test {
     type MaxDomainNameLength = MaxDomainNameLength;
     type Share = Balance;
     type BlockTreePruningDepth = BlockTreePruningDepth;
+    type ConfirmedDomainBlockMappingDepth = ConfirmedDomainBlockMappingDepth;
     type StakeWithdrawalLockingPeriod = StakeWithdrawalLockingPeriod;
     type StakeEpochDuration = StakeEpochDuration;
     type TreasuryAccount = TreasuryAccount;
@@ -309,6 +312,7 @@ impl pallet_domains::Config for Test {
     type DomainsTransfersTracker = MockDomainsTransfersTracker;
     type MaxInitialDomainAccounts = MaxInitialDomainAccounts;
     type MinInitialDomainAccountBalance = MinInitialDomainAccountBalance;
+    type MaxDomainTreasuryShare = MaxDomainTreasuryShare;
 }
 
 pub struct ExtrinsicStorageFees;
@@ -21,11 +21,15 @@
 
 mod default_weights;
 
+use frame_support::sp_runtime::DigestItem;
+use frame_support::sp_std::vec::Vec;
 use frame_support::traits::{Currency, Get};
 use frame_support::weights::Weight;
 use frame_system::pallet_prelude::*;
 pub use pallet::*;
+use sp_consensus_subspace::digests::CompatibleDigestItem;
 use subspace_runtime_primitives::{FindBlockRewardAddress, FindVotingRewardAddresses};
+use subspace_verification::RewardsStatement;
 
 pub trait WeightInfo {
     fn on_initialize() -> Weight;
@@ -79,6 +83,11 @@ mod pallet {
         type OnReward: OnReward<Self::AccountId, BalanceOf<Self>>;
     }
 
+    /// Reward issued to the block author of the current block, used to construct rewards statement
+    /// at the end of the block, always empty between blocks.
+    #[pallet::storage]
+    pub(super) type CurrentBlockReward<T: Config> = StorageValue<_, BalanceOf<T>, OptionQuery>;
+
     /// `pallet-rewards` events
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -115,6 +124,7 @@ impl<T: Config> Pallet<T> {
             let reward = T::BlockReward::get();
             let _imbalance = T::Currency::deposit_creating(&block_author, reward);
             T::OnReward::on_reward(block_author.clone(), reward);
+            CurrentBlockReward::<T>::put(reward);
 
             Self::deposit_event(Event::BlockReward {
                 block_author,
@@ -125,12 +135,25 @@ impl<T: Config> Pallet<T> {
 
     fn do_finalize(_block_number: BlockNumberFor<T>) {
         let reward = T::VoteReward::get();
+        let mut vote_rewards = Vec::new();
 
         for voter in T::FindVotingRewardAddresses::find_voting_reward_addresses() {
             let _imbalance = T::Currency::deposit_creating(&voter, reward);
             T::OnReward::on_reward(voter.clone(), reward);
+            vote_rewards.push((voter.clone(), reward));
 
             Self::deposit_event(Event::VoteReward { voter, reward });
         }
+
+        let block_reward = CurrentBlockReward::<T>::take();
+        // Blocks without rewards don't have rewards statement
+        if block_reward.is_some() || !vote_rewards.is_empty() {
+            frame_system::Pallet::<T>::deposit_log(DigestItem::rewards_statement(
+                &RewardsStatement {
+                    block_reward,
+                    vote_rewards,
+                },
+            ));
+        }
     }
 }
@@ -28,7 +28,7 @@ mod pallet {
     use crate::weights::WeightInfo;
     use frame_support::pallet_prelude::*;
     use frame_system::pallet_prelude::*;
-    use sp_runtime::traits::Zero;
+    use sp_consensus_subspace::MIN_CONFIRMATION_DEPTH_K;
 
     #[pallet::pallet]
     pub struct Pallet<T>(_);
@@ -72,7 +72,8 @@ mod pallet {
         pub enable_balance_transfers: bool,
         /// Whether to enable calls from non-root account
         pub enable_non_root_calls: bool,
-        /// Confirmation depth k to use in the archiving process
+        /// Confirmation depth k to use in the archiving process, can't be less than
+        /// [`MIN_CONFIRMATION_DEPTH_K`]
         pub confirmation_depth_k: BlockNumberFor<T>,
     }
 
@@ -101,8 +102,8 @@ mod pallet {
             } = self;
 
             assert!(
-                !confirmation_depth_k.is_zero(),
-                "ConfirmationDepthK can not be zero"
+                *confirmation_depth_k >= BlockNumberFor::<T>::from(MIN_CONFIRMATION_DEPTH_K),
+                "ConfirmationDepthK can not be less than {MIN_CONFIRMATION_DEPTH_K}"
             );
 
             <EnableDomains<T>>::put(enable_domains);
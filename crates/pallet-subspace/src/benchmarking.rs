@@ -7,17 +7,19 @@ mod benchmarks {
     use crate::{
         AllowAuthoringByAnyone, Call, Config, CurrentSlot, EnableRewards, EnableRewardsAt,
         NextSolutionRangeOverride, Pallet, SegmentCommitment, ShouldAdjustSolutionRange,
-        SolutionRanges,
+        SolutionRangeAdjustmentAlgorithm, SolutionRanges,
     };
     use frame_benchmarking::v2::*;
     use frame_system::pallet_prelude::*;
     use frame_system::{Pallet as System, RawOrigin};
     use sp_consensus_subspace::{
-        EquivocationProof, FarmerPublicKey, FarmerSignature, SignedVote, Vote,
+        EquivocationProof, FarmerPublicKey, FarmerSignature, SignedVote, SolutionRangeAdjustment,
+        Vote,
     };
     use sp_core::crypto::UncheckedFrom;
     use sp_core::Get;
     use sp_runtime::traits::{Block, Header};
+    use sp_runtime::Perbill;
     use sp_std::boxed::Box;
     use sp_std::vec::Vec;
     use subspace_core_primitives::{
@@ -143,6 +145,22 @@ mod benchmarks {
         assert!(Pallet::<T>::root_plot_public_key().is_none());
     }
 
+    #[benchmark]
+    fn set_solution_range_adjustment() {
+        let solution_range_adjustment = SolutionRangeAdjustment::V2 {
+            smoothing_factor: Perbill::from_percent(25),
+            max_adjustment: Perbill::from_percent(50),
+        };
+
+        #[extrinsic_call]
+        _(RawOrigin::Root, solution_range_adjustment);
+
+        assert_eq!(
+            SolutionRangeAdjustmentAlgorithm::<T>::get(),
+            solution_range_adjustment
+        );
+    }
+
     // Create a dummy segment header
     fn create_segment_header(segment_index: SegmentIndex) -> SegmentHeader {
         SegmentHeader::V0 {
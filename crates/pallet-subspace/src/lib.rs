@@ -15,7 +15,7 @@
 // limitations under the License.
 #![doc = include_str!("../README.md")]
 #![cfg_attr(not(feature = "std"), no_std)]
-#![feature(array_chunks, assert_matches, const_option, let_chains, portable_simd)]
+#![feature(array_chunks, assert_matches, const_option, let_chains)]
 #![warn(unused_must_use, unsafe_code, unused_variables, unused_must_use)]
 
 extern crate alloc;
@@ -30,6 +30,7 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod placeholder_weights;
 pub mod weights;
 
 use alloc::string::String;
@@ -49,8 +50,9 @@ use sp_consensus_subspace::consensus::{is_proof_of_time_valid, verify_solution};
 use sp_consensus_subspace::digests::CompatibleDigestItem;
 use sp_consensus_subspace::offence::{OffenceDetails, OffenceError, OnOffenceHandler};
 use sp_consensus_subspace::{
-    EquivocationProof, FarmerPublicKey, FarmerSignature, PotParameters, PotParametersChange,
-    SignedVote, Vote, WrappedPotOutput,
+    EquivocationProof, FarmerPublicKey, FarmerSignature, PendingPotEntropy, PotParameters,
+    PotParametersChange, PotSchedule, SegmentArchivalInfo, SignedVote, SolutionRangeAdjustment,
+    Vote, WrappedPotOutput,
 };
 use sp_runtime::generic::DigestItem;
 use sp_runtime::traits::{BlockNumberProvider, CheckedSub, Hash, One, Zero};
@@ -58,18 +60,18 @@ use sp_runtime::transaction_validity::{
     InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
     TransactionValidityError, ValidTransaction,
 };
-use sp_runtime::DispatchError;
+use sp_runtime::{DispatchError, SaturatedConversion};
 use sp_std::collections::btree_map::BTreeMap;
 use sp_std::prelude::*;
 use subspace_core_primitives::crypto::Scalar;
 use subspace_core_primitives::{
     ArchivedHistorySegment, BlockHash, HistorySize, PieceOffset, PublicKey, RewardSignature,
-    SectorId, SectorIndex, SegmentHeader, SegmentIndex, SlotNumber, SolutionRange,
+    SectorIndex, SegmentHeader, SegmentIndex, SlotNumber, SolutionDistance, SolutionRange,
     REWARD_SIGNING_CONTEXT,
 };
 use subspace_verification::{
-    check_reward_signature, derive_next_solution_range, derive_pot_entropy, PieceCheckParams,
-    VerifySolutionParams,
+    check_reward_signature, derive_next_solution_range, derive_next_solution_range_v2,
+    derive_pot_entropy, PieceCheckParams, VerifySolutionParams,
 };
 
 /// Trigger an era change, if any should take place.
@@ -103,13 +105,18 @@ struct VoteVerificationData {
 pub mod pallet {
     use super::{EraChangeTrigger, VoteVerificationData};
     use crate::equivocation::HandleEquivocation;
+    use crate::placeholder_weights::PlaceholderWeightInfo;
     use crate::weights::WeightInfo;
     use frame_support::pallet_prelude::*;
+    use frame_support::traits::UnixTime;
     use frame_system::pallet_prelude::*;
     use sp_consensus_slots::Slot;
     use sp_consensus_subspace::digests::CompatibleDigestItem;
     use sp_consensus_subspace::inherents::{InherentError, InherentType, INHERENT_IDENTIFIER};
-    use sp_consensus_subspace::{EquivocationProof, FarmerPublicKey, FarmerSignature, SignedVote};
+    use sp_consensus_subspace::{
+        EquivocationProof, FarmerPublicKey, FarmerSignature, SegmentArchivalInfo, SignedVote,
+        SolutionRangeAdjustment,
+    };
     use sp_runtime::DigestItem;
     use sp_std::collections::btree_map::BTreeMap;
     use sp_std::num::NonZeroU32;
@@ -239,8 +246,11 @@ pub mod pallet {
         /// use this pallet's `ValidateUnsigned` in the runtime definition.
         type HandleEquivocation: HandleEquivocation<Self>;
 
+        /// Source of wall-clock time, used to record when segment headers are included.
+        type UnixTime: UnixTime;
+
         /// Weight information for extrinsics in this pallet.
-        type WeightInfo: WeightInfo;
+        type WeightInfo: WeightInfo + PlaceholderWeightInfo;
     }
 
     #[derive(Debug, Default, Encode, Decode, TypeInfo)]
@@ -399,6 +409,11 @@ pub mod pallet {
     #[pallet::storage]
     pub type NextSolutionRangeOverride<T> = StorageValue<_, SolutionRangeOverride>;
 
+    /// Algorithm used to adjust solution range on era change.
+    #[pallet::storage]
+    pub type SolutionRangeAdjustmentAlgorithm<T> =
+        StorageValue<_, SolutionRangeAdjustment, ValueQuery>;
+
     /// Slot at which current era started.
     #[pallet::storage]
     pub type EraStartSlot<T> = StorageValue<_, Slot>;
@@ -417,6 +432,11 @@ pub mod pallet {
         subspace_core_primitives::SegmentCommitment,
     >;
 
+    /// Mapping from segment index to information about inclusion of corresponding segment header.
+    #[pallet::storage]
+    pub(super) type SegmentsArchivalInfo<T> =
+        StorageMap<_, Twox64Concat, SegmentIndex, SegmentArchivalInfo>;
+
     /// Whether the segment headers inherent has been processed in this block (temporary value).
     ///
     /// This value is updated to `true` when processing `store_segment_headers` by a node.
@@ -607,6 +627,24 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Set algorithm used to adjust solution range, takes effect on the next era change.
+        #[pallet::call_index(6)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_solution_range_adjustment())]
+        pub fn set_solution_range_adjustment(
+            origin: OriginFor<T>,
+            solution_range_adjustment: SolutionRangeAdjustment,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            SolutionRangeAdjustmentAlgorithm::<T>::put(solution_range_adjustment);
+            // Deposit algorithm change such that light client can verify next solution range later.
+            frame_system::Pallet::<T>::deposit_log(DigestItem::solution_range_adjustment(
+                solution_range_adjustment,
+            ));
+
+            Ok(())
+        }
     }
 
     #[pallet::inherent]
@@ -704,6 +742,12 @@ impl<T: Config> Pallet<T> {
         HistorySize::from(NonZeroU64::new(number_of_segments).expect("Not zero; qed"))
     }
 
+    /// Information about inclusion of segment header with specified segment index, `None` if not
+    /// included (or included before this information started being tracked).
+    pub fn segment_archival_info(segment_index: SegmentIndex) -> Option<SegmentArchivalInfo> {
+        SegmentsArchivalInfo::<T>::get(segment_index)
+    }
+
     /// Determine whether an era change should take place at this block.
     /// Assumes that initialization has already taken place.
     fn should_era_change(block_number: BlockNumberFor<T>) -> bool {
@@ -730,16 +774,34 @@ impl<T: Config> Pallet<T> {
                 next_solution_range = solution_range_override.solution_range;
                 next_voting_solution_range = solution_range_override.voting_solution_range;
             } else {
-                next_solution_range = derive_next_solution_range(
-                    // If Era start slot is not found it means we have just finished the first era
-                    u64::from(EraStartSlot::<T>::get().unwrap_or_else(GenesisSlot::<T>::get)),
-                    u64::from(current_slot),
-                    slot_probability,
-                    solution_ranges.current,
-                    T::EraDuration::get()
-                        .try_into()
-                        .unwrap_or_else(|_| panic!("Era duration is always within u64; qed")),
-                );
+                // If Era start slot is not found it means we have just finished the first era
+                let era_start_slot =
+                    u64::from(EraStartSlot::<T>::get().unwrap_or_else(GenesisSlot::<T>::get));
+                let era_duration = T::EraDuration::get()
+                    .try_into()
+                    .unwrap_or_else(|_| panic!("Era duration is always within u64; qed"));
+
+                next_solution_range = match SolutionRangeAdjustmentAlgorithm::<T>::get() {
+                    SolutionRangeAdjustment::V1 => derive_next_solution_range(
+                        era_start_slot,
+                        u64::from(current_slot),
+                        slot_probability,
+                        solution_ranges.current,
+                        era_duration,
+                    ),
+                    SolutionRangeAdjustment::V2 {
+                        smoothing_factor,
+                        max_adjustment,
+                    } => derive_next_solution_range_v2(
+                        era_start_slot,
+                        u64::from(current_slot),
+                        slot_probability,
+                        solution_ranges.current,
+                        era_duration,
+                        smoothing_factor,
+                        max_adjustment,
+                    ),
+                };
 
                 next_voting_solution_range = next_solution_range
                     .saturating_mul(u64::from(T::ExpectedVotesPerBlock::get()) + 1);
@@ -1005,11 +1067,17 @@ impl<T: Config> Pallet<T> {
             "Segment headers must be updated only once in the block"
         );
 
+        let archival_info = SegmentArchivalInfo {
+            block_number: frame_system::Pallet::<T>::current_block_number().saturated_into(),
+            timestamp: T::UnixTime::now().as_millis().saturated_into(),
+        };
+
         for segment_header in segment_headers {
             SegmentCommitment::<T>::insert(
                 segment_header.segment_index(),
                 segment_header.segment_commitment(),
             );
+            SegmentsArchivalInfo::<T>::insert(segment_header.segment_index(), archival_info);
             // Deposit global randomness data such that light client can validate blocks later.
             frame_system::Pallet::<T>::deposit_log(DigestItem::segment_commitment(
                 segment_header.segment_index(),
@@ -1175,6 +1243,54 @@ impl<T: Config> Pallet<T> {
         }
     }
 
+    /// Proof of time schedule with current parameters, changes of parameters that didn't take
+    /// effect as of current slot yet and collected entropy that is not scheduled for injection yet
+    pub fn pot_schedule() -> PotSchedule {
+        let current_slot = CurrentSlot::<T>::get();
+        let pot_slot_iterations =
+            PotSlotIterations::<T>::get().expect("Always initialized during genesis; qed");
+
+        let mut scheduled_changes = Vec::new();
+        let mut pending_entropy = Vec::new();
+        for (
+            block_number,
+            PotEntropyValue {
+                target_slot,
+                entropy,
+            },
+        ) in PotEntropy::<T>::get()
+        {
+            match target_slot {
+                // Old values are cleaned up lazily, skip those that already took effect
+                Some(target_slot) => {
+                    if target_slot >= current_slot {
+                        scheduled_changes.push(PotParametersChange {
+                            slot: target_slot,
+                            // TODO: Take adjustment of iterations into account once we have it
+                            slot_iterations: pot_slot_iterations,
+                            entropy,
+                        });
+                    }
+                }
+                None => {
+                    pending_entropy.push(PendingPotEntropy {
+                        block_number: block_number.saturated_into(),
+                        entropy,
+                    });
+                }
+            }
+        }
+
+        PotSchedule {
+            slot_iterations: pot_slot_iterations,
+            scheduled_changes,
+            pending_entropy,
+            entropy_injection_interval: T::PotEntropyInjectionInterval::get().saturated_into(),
+            entropy_injection_lookback_depth: T::PotEntropyInjectionLookbackDepth::get(),
+            entropy_injection_delay: T::PotEntropyInjectionDelay::get(),
+        }
+    }
+
     /// Check if `farmer_public_key` is in block list (due to equivocation)
     pub fn is_in_block_list(farmer_public_key: &FarmerPublicKey) -> bool {
         BlockList::<T>::contains_key(farmer_public_key)
@@ -1487,10 +1603,7 @@ fn check_vote<T: Config>(
         parent_vote_verification_data
     };
 
-    let sector_id = SectorId::new(
-        PublicKey::from(&solution.public_key).hash(),
-        solution.sector_index,
-    );
+    let sector_id = solution.sector_id();
 
     let recent_segments = T::RecentSegments::get();
     let recent_history_fraction = (
@@ -1545,7 +1658,9 @@ fn check_vote<T: Config>(
             .into(),
     ) {
         Ok(solution_distance) => {
-            if solution_distance <= vote_verification_data.solution_range / 2 {
+            if SolutionDistance::from(solution_distance)
+                .is_within(vote_verification_data.solution_range)
+            {
                 debug!(
                     target: "runtime::subspace",
                     "Vote quality is too high"
@@ -22,7 +22,7 @@ use crate::{
     FarmerPublicKey, NormalEraChange,
 };
 use frame_support::parameter_types;
-use frame_support::traits::{ConstU128, ConstU16, ConstU32, ConstU64, OnInitialize};
+use frame_support::traits::{ConstU128, ConstU16, ConstU32, ConstU64, OnInitialize, UnixTime};
 use futures::executor::block_on;
 use rand::Rng;
 use schnorrkel::Keypair;
@@ -40,8 +40,8 @@ use sp_runtime::{BuildStorage, Perbill};
 use sp_weights::Weight;
 use std::marker::PhantomData;
 use std::num::{NonZeroU32, NonZeroU64, NonZeroUsize};
-use std::simd::Simd;
 use std::sync::{Once, OnceLock};
+use std::time::Duration;
 use std::{iter, slice};
 use subspace_archiving::archiver::{Archiver, NewArchivedSegment};
 use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
@@ -185,6 +185,15 @@ parameter_types! {
     pub const ShouldAdjustSolutionRange: bool = false;
 }
 
+/// Block `N` is produced `N` seconds after Unix epoch
+pub struct MockUnixTime;
+
+impl UnixTime for MockUnixTime {
+    fn now() -> Duration {
+        Duration::from_secs(System::block_number())
+    }
+}
+
 impl Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type BlockAuthoringDelay = BlockAuthoringDelay;
@@ -204,6 +213,7 @@ impl Config for Test {
     type EraChangeTrigger = NormalEraChange;
 
     type HandleEquivocation = EquivocationHandler<OffencesSubspace, ReportLongevity>;
+    type UnixTime = MockUnixTime;
 
     type WeightInfo = ();
 }
@@ -409,6 +419,7 @@ pub fn create_archived_segment() -> &'static NewArchivedSegment {
         rand::thread_rng().fill(block.as_mut_slice());
         archiver
             .add_block(block, Default::default(), true)
+            .unwrap()
             .into_iter()
             .next()
             .unwrap()
@@ -502,9 +513,8 @@ pub fn create_signed_vote(
             solution.sector_index,
         );
         let sector_slot_challenge = sector_id.derive_sector_slot_challenge(&global_challenge);
-        let masked_chunk = (Simd::from(solution.chunk.to_bytes())
-            ^ Simd::from(solution.proof_of_space.hash()))
-        .to_array();
+        let masked_chunk =
+            Record::mask_chunk(&solution.chunk.to_bytes(), &solution.proof_of_space.hash());
 
         // Check that solution quality is not too high
         if is_within_solution_range(
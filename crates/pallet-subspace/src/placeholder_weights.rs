@@ -0,0 +1,36 @@
+//! Placeholder weights for pallet_subspace.
+//!
+//! `set_solution_range_adjustment` was added after weights in [`crate::weights`] were last
+//! generated. Values below are conservative estimates picked by hand, they were NOT measured.
+//! Benchmark for it exists, this module must be removed once weights are regenerated with
+//! `subspace-node benchmark pallet`.
+
+use crate::weights::SubstrateWeight;
+use frame_support::traits::Get;
+use frame_support::weights::constants::RocksDbWeight;
+use frame_support::weights::{RuntimeDbWeight, Weight};
+
+/// Weight functions of pallet_subspace that are not benchmarked yet.
+pub trait PlaceholderWeightInfo {
+    /// Weight of `set_solution_range_adjustment` call
+    fn set_solution_range_adjustment() -> Weight;
+}
+
+/// Reads and writes `System::Digest`, writes `SolutionRangeAdjustmentAlgorithm`
+fn set_solution_range_adjustment<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+    Weight::from_parts(15_000_000, 2_000)
+        .saturating_add(DbWeight::get().reads(1))
+        .saturating_add(DbWeight::get().writes(2))
+}
+
+impl<T: frame_system::Config> PlaceholderWeightInfo for SubstrateWeight<T> {
+    fn set_solution_range_adjustment() -> Weight {
+        set_solution_range_adjustment::<T::DbWeight>()
+    }
+}
+
+impl PlaceholderWeightInfo for () {
+    fn set_solution_range_adjustment() -> Weight {
+        set_solution_range_adjustment::<RocksDbWeight>()
+    }
+}
@@ -34,13 +34,17 @@ use frame_system::{EventRecord, Phase};
 use rand::prelude::*;
 use schnorrkel::Keypair;
 use sp_consensus_slots::Slot;
-use sp_consensus_subspace::{FarmerPublicKey, FarmerSignature, PotExtension, SolutionRanges};
+use sp_consensus_subspace::digests::CompatibleDigestItem;
+use sp_consensus_subspace::{
+    FarmerPublicKey, FarmerSignature, PotExtension, PotParametersChange, SegmentArchivalInfo,
+    SolutionRangeAdjustment, SolutionRanges,
+};
 use sp_core::crypto::UncheckedFrom;
 use sp_runtime::traits::{BlockNumberProvider, Header};
 use sp_runtime::transaction_validity::{
     InvalidTransaction, TransactionPriority, TransactionSource, ValidTransaction,
 };
-use sp_runtime::DispatchError;
+use sp_runtime::{DispatchError, PerThing, Perbill};
 use std::assert_matches::assert_matches;
 use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
@@ -131,6 +135,57 @@ fn can_update_solution_range_on_era_change() {
     })
 }
 
+#[test]
+fn can_update_solution_range_with_smoothing_on_era_change() {
+    new_test_ext(allow_all_pot_extension()).execute_with(|| {
+        let keypair = Keypair::generate();
+
+        assert_eq!(<Test as Config>::EraDuration::get(), 4);
+        assert_ok!(Subspace::enable_solution_range_adjustment(
+            RuntimeOrigin::root(),
+            None,
+            None
+        ));
+        let max_adjustment = Perbill::from_percent(10);
+        let solution_range_adjustment = SolutionRangeAdjustment::V2 {
+            smoothing_factor: Perbill::from_percent(50),
+            max_adjustment,
+        };
+        assert_ok!(Subspace::set_solution_range_adjustment(
+            RuntimeOrigin::root(),
+            solution_range_adjustment
+        ));
+        // Change is announced to light clients
+        assert_eq!(
+            System::digest()
+                .logs
+                .iter()
+                .find_map(|log| log.as_solution_range_adjustment()),
+            Some(solution_range_adjustment)
+        );
+
+        // Era edge
+        progress_to_block(&keypair, 4, 1);
+
+        // Blocks were produced on every slot, so solution range has to decrease, but not by more
+        // than allowed in one era
+        let next_solution_range = Subspace::solution_ranges().next.unwrap();
+        assert_eq!(
+            next_solution_range,
+            INITIAL_SOLUTION_RANGE - max_adjustment.mul_floor(INITIAL_SOLUTION_RANGE)
+        );
+
+        // Non-root can't change the algorithm
+        assert_err!(
+            Subspace::set_solution_range_adjustment(
+                RuntimeOrigin::signed(1),
+                SolutionRangeAdjustment::V1
+            ),
+            DispatchError::BadOrigin
+        );
+    })
+}
+
 #[test]
 fn can_override_solution_range_update() {
     new_test_ext(allow_all_pot_extension()).execute_with(|| {
@@ -502,6 +557,14 @@ fn store_segment_header_works() {
                 topics: vec![],
             }]
         );
+        assert_eq!(
+            Subspace::segment_archival_info(SegmentIndex::ZERO),
+            Some(SegmentArchivalInfo {
+                block_number: 1,
+                timestamp: 1_000,
+            })
+        );
+        assert_eq!(Subspace::segment_archival_info(SegmentIndex::ONE), None);
     });
 }
 
@@ -1738,3 +1801,54 @@ fn allow_authoring_by_anyone_works() {
         );
     });
 }
+
+#[test]
+fn pot_schedule() {
+    new_test_ext(allow_all_pot_extension()).execute_with(|| {
+        let keypair = Keypair::generate();
+
+        progress_to_block(&keypair, 4, 1);
+        let pot_schedule = Subspace::pot_schedule();
+        assert_eq!(pot_schedule.entropy_injection_interval, 5);
+        assert_eq!(pot_schedule.entropy_injection_lookback_depth, 2);
+        assert_eq!(pot_schedule.entropy_injection_delay, Slot::from(4));
+        assert!(pot_schedule.scheduled_changes.is_empty());
+        assert!(pot_schedule.pending_entropy.is_empty());
+
+        // Entropy is collected every entropy injection interval
+        progress_to_block(&keypair, 10, 1);
+        let pot_schedule = Subspace::pot_schedule();
+        assert!(pot_schedule.scheduled_changes.is_empty());
+        assert_eq!(
+            pot_schedule
+                .pending_entropy
+                .iter()
+                .map(|pending_entropy| pending_entropy.block_number)
+                .collect::<Vec<_>>(),
+            vec![5, 10]
+        );
+        let entropy = pot_schedule.pending_entropy[0].entropy;
+
+        // Entropy collected lookback depth intervals ago is scheduled for injection
+        progress_to_block(&keypair, 15, 1);
+        let pot_schedule = Subspace::pot_schedule();
+        let expected_change = PotParametersChange {
+            slot: Subspace::current_slot().saturating_add(pot_schedule.entropy_injection_delay),
+            slot_iterations: pot_schedule.slot_iterations,
+            entropy,
+        };
+        assert_eq!(pot_schedule.scheduled_changes, vec![expected_change]);
+        assert_eq!(
+            pot_schedule
+                .pending_entropy
+                .iter()
+                .map(|pending_entropy| pending_entropy.block_number)
+                .collect::<Vec<_>>(),
+            vec![10, 15]
+        );
+        assert_eq!(
+            Subspace::pot_parameters().next_parameters_change(),
+            Some(expected_change)
+        );
+    });
+}
@@ -23,7 +23,7 @@ mod default_weights;
 
 use codec::{Codec, Decode, Encode};
 use frame_support::sp_runtime::traits::Zero;
-use frame_support::sp_runtime::SaturatedConversion;
+use frame_support::sp_runtime::{Perbill, SaturatedConversion};
 use frame_support::traits::{tokens, Currency, Get};
 use frame_support::weights::Weight;
 use frame_system::pallet_prelude::*;
@@ -66,6 +66,7 @@ impl<Balance: Codec + tokens::Balance> Default for BlockTransactionByteFee<Balan
 mod pallet {
     use super::{BalanceOf, BlockTransactionByteFee, CollectedFees, WeightInfo};
     use frame_support::pallet_prelude::*;
+    use frame_support::sp_runtime::Perbill;
     use frame_support::traits::Currency;
     use frame_system::pallet_prelude::*;
     use subspace_runtime_primitives::FindBlockRewardAddress;
@@ -100,6 +101,18 @@ mod pallet {
         /// Whether dynamic cost of storage should be used
         type DynamicCostOfStorage: Get<bool>;
 
+        /// Maximum number of bytes of data-carrying extrinsics that can be included in a single
+        /// block, independently of the remaining block weight and length.
+        #[pallet::constant]
+        type MaxDataBytesPerBlock: Get<u32>;
+
+        /// Portion of compute fee that is rebated for data-carrying extrinsics, whose data is
+        /// stored long-term by the network and already paid for with per-byte storage fee.
+        ///
+        /// Whatever remains of the compute fee after the rebate is accounted as storage fee.
+        #[pallet::constant]
+        type DataFeeRebate: Get<Perbill>;
+
         type WeightInfo: WeightInfo;
     }
 
@@ -131,6 +144,11 @@ mod pallet {
     #[pallet::storage]
     pub(super) type CollectedBlockFees<T: Config> = StorageValue<_, CollectedFees<BalanceOf<T>>>;
 
+    /// Temporary value (cleared at block finalization) which contains the number of bytes of
+    /// data-carrying extrinsics included in the current block so far.
+    #[pallet::storage]
+    pub(super) type BlockDataBytes<T: Config> = StorageValue<_, u32, ValueQuery>;
+
     /// Pallet transaction fees for issuing fees to block authors.
     #[pallet::pallet]
     #[pallet::without_storage_info]
@@ -162,6 +180,16 @@ mod pallet {
             /// Amount of burned tips.
             tips: BalanceOf<T>,
         },
+        /// Part of the fee was rebated for data-carrying extrinsic.
+        #[codec(index = 2)]
+        DataFeeRebated {
+            /// Account that paid the fee and received the rebate.
+            who: T::AccountId,
+            /// Size of data-carrying extrinsic in bytes.
+            data_bytes: u32,
+            /// Amount of rebated fees.
+            rebate: BalanceOf<T>,
+        },
     }
 
     #[pallet::hooks]
@@ -209,6 +237,7 @@ where
             transaction_byte_fee.next = Self::calculate_transaction_byte_fee()
         });
         IsDuringBlockExecution::<T>::take();
+        BlockDataBytes::<T>::take();
 
         let collected_fees = CollectedBlockFees::<T>::take()
             .expect("`CollectedBlockFees` was set in `on_initialize`; qed");
@@ -267,6 +296,48 @@ where
         }
     }
 
+    /// Whether data-carrying extrinsic of `size` bytes can ever fit into a block.
+    pub fn data_fits_into_block(size: u32) -> bool {
+        size <= T::MaxDataBytesPerBlock::get()
+    }
+
+    /// Account for data-carrying extrinsic of `size` bytes in the current block, returns `false`
+    /// without changing anything if doing so would exceed
+    /// [`MaxDataBytesPerBlock`](Config::MaxDataBytesPerBlock).
+    pub fn try_note_data_bytes(size: u32) -> bool {
+        BlockDataBytes::<T>::mutate(
+            |block_data_bytes| match block_data_bytes.checked_add(size) {
+                Some(new_block_data_bytes)
+                    if new_block_data_bytes <= T::MaxDataBytesPerBlock::get() =>
+                {
+                    *block_data_bytes = new_block_data_bytes;
+                    true
+                }
+                _ => false,
+            },
+        )
+    }
+
+    /// Rebate for data-carrying extrinsic that was charged `compute_fee`, see
+    /// [`DataFeeRebate`](Config::DataFeeRebate).
+    pub fn data_fee_rebate(compute_fee: BalanceOf<T>) -> BalanceOf<T> {
+        T::DataFeeRebate::get().mul_floor(compute_fee)
+    }
+
+    /// Record `rebate` that was returned to `who` for data-carrying extrinsic of `data_bytes`
+    /// bytes.
+    pub fn note_data_fee_rebate(who: T::AccountId, data_bytes: u32, rebate: BalanceOf<T>) {
+        if rebate.is_zero() {
+            return;
+        }
+
+        Self::deposit_event(Event::<T>::DataFeeRebated {
+            who,
+            data_bytes,
+            rebate,
+        });
+    }
+
     pub fn note_transaction_fees(
         storage_fee: BalanceOf<T>,
         compute_fee: BalanceOf<T>,
@@ -23,6 +23,7 @@ use futures::channel::mpsc;
 use futures::{future, FutureExt, StreamExt};
 use jsonrpsee::core::{async_trait, Error as JsonRpseeError, RpcResult};
 use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::error::{CallError, ErrorObject};
 use jsonrpsee::types::{SubscriptionEmptyError, SubscriptionResult};
 use jsonrpsee::SubscriptionSink;
 use lru::LruCache;
@@ -38,16 +39,19 @@ use sc_consensus_subspace::slot_worker::{
 };
 use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
 use sc_utils::mpsc::TracingUnboundedSender;
-use sp_api::{ApiError, ProvideRuntimeApi};
+use sp_api::{ApiError, ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_consensus::SyncOracle;
+use sp_consensus_subspace::archival_finality::ArchivalFinalityProof;
+use sp_consensus_subspace::digests::{extract_subspace_digest_items, CompatibleDigestItem};
 use sp_consensus_subspace::{
     ChainConstants, FarmerPublicKey, FarmerSignature, SubspaceApi as SubspaceRuntimeApi,
 };
 use sp_core::crypto::ByteArray;
-use sp_core::H256;
+use sp_core::{Bytes, H256};
 use sp_objects::ObjectsApi;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, One};
+use sp_runtime::SaturatedConversion;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -58,21 +62,29 @@ use std::time::Duration;
 use subspace_archiving::archiver::NewArchivedSegment;
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::{
-    BlockHash, HistorySize, PieceIndex, PublicKey, SegmentHeader, SegmentIndex, SlotNumber,
-    Solution,
+    BlockHash, BlockNumber, HistorySize, PieceIndex, PublicKey, SectorId, SegmentHeader,
+    SegmentIndex, SlotNumber, Solution,
 };
 use subspace_farmer_components::FarmerProtocolInfo;
 use subspace_networking::libp2p::Multiaddr;
+use subspace_proof_of_space::Table;
 use subspace_rpc_primitives::{
-    FarmerAppInfo, RewardSignatureResponse, RewardSigningInfo, SlotInfo, SolutionResponse,
-    MAX_SEGMENT_HEADERS_PER_REQUEST,
+    FarmerAppInfo, FutureHistorySize, PendingPotEntropy, PotParametersChange, PotSchedule,
+    RewardSignatureResponse, RewardSigningInfo, SegmentArchivalInfo, SlotInfo,
+    SolutionCheckOutcome, SolutionInspection, SolutionRangeHistory, SolutionRangeSample,
+    SolutionResponse, FUTURE_HISTORY_SIZE_ERROR_CODE, MAX_SEGMENT_HEADERS_PER_REQUEST,
+    MAX_SOLUTION_RANGE_SAMPLES_PER_REQUEST,
 };
+use subspace_verification::{CheckOutcome, PieceCheckParams};
 use tracing::{debug, error, warn};
 
 /// This is essentially equal to expected number of votes per block, one more is added implicitly by
 /// the fact that channel sender exists
 const SOLUTION_SENDER_CHANNEL_CAPACITY: usize = 9;
 const REWARD_SIGNING_TIMEOUT: Duration = Duration::from_millis(500);
+/// Number of archival finality proofs kept in memory, such that repeated requests from bridges for
+/// the same block are answered without walking the chain again
+const ARCHIVAL_FINALITY_PROOFS_CACHE_SIZE: usize = 100;
 
 /// Provides rpc methods for interacting with Subspace.
 #[rpc(client, server)]
@@ -111,6 +123,18 @@ pub trait SubspaceRpcApi {
     )]
     fn subscribe_archived_segment_header(&self);
 
+    /// Segment headers subscription for external observers (archive mirrors, light clients, etc.).
+    ///
+    /// Yields every new segment header as soon as archiving of the segment completes. Unlike
+    /// [`Self::subscribe_archived_segment_header`], subscribers are not expected to acknowledge
+    /// segment headers and never hold archiving back.
+    #[subscription(
+        name = "subspace_subscribeSegmentHeaders" => "subspace_segment_headers",
+        unsubscribe = "subspace_unsubscribeSegmentHeaders",
+        item = SegmentHeader,
+    )]
+    fn subscribe_segment_headers(&self);
+
     #[method(name = "subspace_segmentHeaders")]
     async fn segment_headers(
         &self,
@@ -128,6 +152,58 @@ pub trait SubspaceRpcApi {
 
     #[method(name = "subspace_lastSegmentHeaders")]
     async fn last_segment_headers(&self, limit: u64) -> RpcResult<Vec<Option<SegmentHeader>>>;
+
+    /// Information about inclusion of segment headers with specified segment indexes into the
+    /// consensus chain (block number and timestamp), `None` for segments that are not known yet
+    #[method(name = "subspace_segmentArchivalInfo", blocking)]
+    fn segment_archival_info(
+        &self,
+        segment_indexes: Vec<SegmentIndex>,
+    ) -> RpcResult<Vec<Option<SegmentArchivalInfo>>>;
+
+    /// Proof of time parameters as of the best block together with scheduled changes of
+    /// parameters and collected entropy that is not scheduled for injection yet
+    #[method(name = "subspace_potSchedule", blocking)]
+    fn pot_schedule(&self) -> RpcResult<PotSchedule>;
+
+    /// Inspect SCALE-encoded solution for the slot, reports outcome of every verification check
+    /// and values computed along the way, useful for triaging solutions rejected by the chain
+    #[method(name = "subspace_inspectSolution", blocking)]
+    fn inspect_solution(
+        &self,
+        slot_info: SlotInfo,
+        encoded_solution: Bytes,
+    ) -> RpcResult<SolutionInspection>;
+
+    /// Solution ranges of canonical blocks sampled every `interval` blocks going back from the best
+    /// block (at most `samples` samples), useful for capacity planning and reward estimation
+    #[method(name = "subspace_solutionRangeHistory", blocking)]
+    fn solution_range_history(
+        &self,
+        samples: u32,
+        interval: BlockNumber,
+    ) -> RpcResult<SolutionRangeHistory>;
+
+    /// Proof of archival finality of the block, SCALE-encoded
+    /// [`ArchivalFinalityProof`](sp_consensus_subspace::archival_finality::ArchivalFinalityProof).
+    ///
+    /// Segment headers in the proof start right after the segment with `trusted_segment_index`
+    /// known to the verifier (or with the genesis segment if `None`). Returns `None` if block is
+    /// not in the canonical chain or not archived yet.
+    #[method(name = "subspace_archivalFinalityProof", blocking)]
+    fn archival_finality_proof(
+        &self,
+        block_hash: H256,
+        trusted_segment_index: Option<SegmentIndex>,
+    ) -> RpcResult<Option<Bytes>>;
+}
+
+fn solution_check_outcome(outcome: CheckOutcome) -> SolutionCheckOutcome {
+    match outcome {
+        CheckOutcome::Passed => SolutionCheckOutcome::Passed,
+        CheckOutcome::Failed => SolutionCheckOutcome::Failed,
+        CheckOutcome::Skipped => SolutionCheckOutcome::Skipped,
+    }
 }
 
 #[derive(Default)]
@@ -192,7 +268,7 @@ where
 }
 
 /// Implements the [`SubspaceRpcApiServer`] trait for interacting with Subspace.
-pub struct SubspaceRpc<Block, Client, SO, AS>
+pub struct SubspaceRpc<PosTable, Block, Client, SO, AS>
 where
     Block: BlockT,
     SO: SyncOracle + Send + Sync + Clone + 'static,
@@ -209,6 +285,8 @@ where
     dsn_bootstrap_nodes: Vec<Multiaddr>,
     segment_headers_store: SegmentHeadersStore<AS>,
     cached_archived_segment: Arc<Mutex<Option<CachedArchivedSegment>>>,
+    #[allow(clippy::type_complexity)]
+    archival_finality_proofs: Arc<Mutex<LruCache<(Block::Hash, Option<SegmentIndex>), Bytes>>>,
     archived_segment_acknowledgement_senders:
         Arc<Mutex<ArchivedSegmentHeaderAcknowledgementSenders>>,
     next_subscription_id: AtomicU64,
@@ -218,7 +296,7 @@ where
     max_pieces_in_sector: u16,
     kzg: Kzg,
     deny_unsafe: DenyUnsafe,
-    _block: PhantomData<Block>,
+    _phantom: PhantomData<(PosTable, Block)>,
 }
 
 /// [`SubspaceRpc`] is used for notifying subscribers about arrival of new slots and for
@@ -228,7 +306,7 @@ where
 /// every subscriber, after which RPC server waits for the same number of
 /// `subspace_submitSolutionResponse` requests with `SolutionResponse` in them or until
 /// timeout is exceeded. The first valid solution for a particular slot wins, others are ignored.
-impl<Block, Client, SO, AS> SubspaceRpc<Block, Client, SO, AS>
+impl<PosTable, Block, Client, SO, AS> SubspaceRpc<PosTable, Block, Client, SO, AS>
 where
     Block: BlockT,
     Client: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
@@ -267,6 +345,9 @@ where
             dsn_bootstrap_nodes: config.dsn_bootstrap_nodes,
             segment_headers_store: config.segment_headers_store,
             cached_archived_segment: Arc::default(),
+            archival_finality_proofs: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(ARCHIVAL_FINALITY_PROOFS_CACHE_SIZE).expect("Not zero; qed"),
+            ))),
             archived_segment_acknowledgement_senders: Arc::default(),
             next_subscription_id: AtomicU64::default(),
             sync_oracle: config.sync_oracle,
@@ -275,14 +356,16 @@ where
             max_pieces_in_sector,
             kzg: config.kzg,
             deny_unsafe: config.deny_unsafe,
-            _block: PhantomData,
+            _phantom: PhantomData,
         })
     }
 }
 
 #[async_trait]
-impl<Block, Client, SO, AS> SubspaceRpcApiServer for SubspaceRpc<Block, Client, SO, AS>
+impl<PosTable, Block, Client, SO, AS> SubspaceRpcApiServer
+    for SubspaceRpc<PosTable, Block, Client, SO, AS>
 where
+    PosTable: Table,
     Block: BlockT,
     Client: ProvideRuntimeApi<Block>
         + HeaderBackend<Block>
@@ -290,7 +373,7 @@ where
         + Send
         + Sync
         + 'static,
-    Client::Api: ObjectsApi<Block>,
+    Client::Api: ObjectsApi<Block> + SubspaceRuntimeApi<Block, FarmerPublicKey>,
     SO: SyncOracle + Send + Sync + Clone + 'static,
     AS: AuxStore + Send + Sync + 'static,
 {
@@ -332,6 +415,35 @@ where
         self.deny_unsafe.check_if_safe()?;
 
         let slot = solution_response.slot_number;
+
+        // Solution for history node doesn't know about yet can't be verified, let farmer know when
+        // to retry instead of silently ignoring it
+        let solution_history_size = solution_response.solution.history_size;
+        if let Some(current_history_size) = self
+            .segment_headers_store
+            .max_segment_index()
+            .map(HistorySize::from)
+        {
+            if solution_history_size > current_history_size {
+                debug!(
+                    %slot,
+                    %solution_history_size,
+                    %current_history_size,
+                    "Solution references future history size, rejecting"
+                );
+
+                let future_history_size = FutureHistorySize {
+                    solution_history_size,
+                    current_history_size,
+                };
+                return Err(JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+                    FUTURE_HISTORY_SIZE_ERROR_CODE,
+                    future_history_size.to_string(),
+                    Some(future_history_size),
+                ))));
+            }
+        }
+
         let mut solution_response_senders = self.solution_response_senders.lock();
 
         let success = solution_response_senders
@@ -379,6 +491,16 @@ where
                     // into data structure `sc-consensus-subspace` expects
                     let forward_solution_fut = async move {
                         while let Some(solution) = response_receiver.next().await {
+                            if let Err(error) = solution.verify_shape() {
+                                warn!(
+                                    slot = %slot_number,
+                                    sector_index = %solution.sector_index,
+                                    %error,
+                                    "Ignoring structurally invalid solution from farmer"
+                                );
+                                continue;
+                            }
+
                             let public_key =
                                 FarmerPublicKey::from_slice(solution.public_key.as_ref())
                                     .expect("Always correct length; qed");
@@ -431,6 +553,8 @@ where
                 global_challenge,
                 solution_range: new_slot_info.solution_range,
                 voting_solution_range: new_slot_info.voting_solution_range,
+                import_queue_depth: new_slot_info.import_queue_depth,
+                best_block_slot_lag: new_slot_info.best_block_slot_lag,
             }
         };
         let stream = self
@@ -642,6 +766,30 @@ where
         Ok(())
     }
 
+    fn subscribe_segment_headers(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+        let stream = self.archived_segment_notification_stream.subscribe().map(
+            |archived_segment_notification| {
+                // Acknowledgement sender is dropped right away, such that archiving doesn't wait
+                // for this subscriber
+                archived_segment_notification
+                    .archived_segment
+                    .segment_header
+            },
+        );
+
+        let fut = async move {
+            sink.pipe_from_stream(stream).await;
+        };
+
+        self.subscription_executor.spawn(
+            "subspace-segment-headers-subscription",
+            Some("rpc"),
+            fut.boxed(),
+        );
+
+        Ok(())
+    }
+
     async fn acknowledge_archived_segment_header(
         &self,
         segment_index: SegmentIndex,
@@ -728,7 +876,7 @@ where
         if requested_piece_index.segment_index() == archived_segment.segment_header.segment_index()
         {
             return Ok(Some(
-                pieces[requested_piece_index.position() as usize].to_vec(),
+                pieces[usize::from(requested_piece_index.position())].to_vec(),
             ));
         }
 
@@ -782,4 +930,402 @@ where
 
         Ok(last_segment_headers)
     }
+
+    fn segment_archival_info(
+        &self,
+        segment_indexes: Vec<SegmentIndex>,
+    ) -> RpcResult<Vec<Option<SegmentArchivalInfo>>> {
+        if segment_indexes.len() > MAX_SEGMENT_HEADERS_PER_REQUEST {
+            return Err(JsonRpseeError::Custom(format!(
+                "segment_indexes length exceed the limit {MAX_SEGMENT_HEADERS_PER_REQUEST}"
+            )));
+        };
+
+        let best_hash = self.client.info().best_hash;
+        let runtime_api = self.client.runtime_api();
+        let supported = runtime_api
+            .has_api_with::<dyn SubspaceRuntimeApi<Block, FarmerPublicKey>, _>(
+                best_hash,
+                |version| version >= 2,
+            )
+            .map_err(|error| JsonRpseeError::Custom(error.to_string()))?;
+        if !supported {
+            return Err(JsonRpseeError::Custom(
+                "Runtime doesn't track segment archival info".to_string(),
+            ));
+        }
+
+        segment_indexes
+            .into_iter()
+            .map(|segment_index| {
+                let maybe_archival_info = runtime_api
+                    .segment_archival_info(best_hash, segment_index)
+                    .map_err(|error| {
+                        error!(
+                            %error,
+                            %segment_index,
+                            "Failed to get segment archival info from runtime API"
+                        );
+                        JsonRpseeError::Custom(error.to_string())
+                    })?;
+
+                Ok(
+                    maybe_archival_info.map(|archival_info| SegmentArchivalInfo {
+                        segment_index,
+                        block_number: archival_info.block_number,
+                        timestamp: archival_info.timestamp,
+                    }),
+                )
+            })
+            .collect()
+    }
+
+    fn pot_schedule(&self) -> RpcResult<PotSchedule> {
+        let best_hash = self.client.info().best_hash;
+        let runtime_api = self.client.runtime_api();
+        let supported = runtime_api
+            .has_api_with::<dyn SubspaceRuntimeApi<Block, FarmerPublicKey>, _>(
+                best_hash,
+                |version| version >= 3,
+            )
+            .map_err(|error| JsonRpseeError::Custom(error.to_string()))?;
+        if !supported {
+            return Err(JsonRpseeError::Custom(
+                "Runtime doesn't expose proof of time schedule".to_string(),
+            ));
+        }
+
+        let pot_schedule = runtime_api.pot_schedule(best_hash).map_err(|error| {
+            error!(%error, "Failed to get proof of time schedule from runtime API");
+            JsonRpseeError::Custom(error.to_string())
+        })?;
+
+        Ok(PotSchedule {
+            slot_iterations: pot_schedule.slot_iterations,
+            scheduled_changes: pot_schedule
+                .scheduled_changes
+                .into_iter()
+                .map(|change| PotParametersChange {
+                    slot: change.slot.into(),
+                    slot_iterations: change.slot_iterations,
+                    entropy: change.entropy,
+                })
+                .collect(),
+            pending_entropy: pot_schedule
+                .pending_entropy
+                .into_iter()
+                .map(|pending_entropy| PendingPotEntropy {
+                    block_number: pending_entropy.block_number,
+                    entropy: pending_entropy.entropy,
+                })
+                .collect(),
+            entropy_injection_interval: pot_schedule.entropy_injection_interval,
+            entropy_injection_lookback_depth: pot_schedule.entropy_injection_lookback_depth,
+            entropy_injection_delay: pot_schedule.entropy_injection_delay.into(),
+        })
+    }
+
+    fn inspect_solution(
+        &self,
+        slot_info: SlotInfo,
+        encoded_solution: Bytes,
+    ) -> RpcResult<SolutionInspection> {
+        self.deny_unsafe.check_if_safe()?;
+
+        let solution =
+            Solution::<FarmerPublicKey, FarmerPublicKey>::decode(&mut encoded_solution.as_ref())
+                .map_err(|error| {
+                    JsonRpseeError::Custom(format!("Failed to decode solution: {error}"))
+                })?;
+
+        let chain_constants = &self.chain_constants;
+        let sector_id = SectorId::new(
+            PublicKey::from(&solution.public_key).hash(),
+            solution.sector_index,
+        );
+        let segment_index = sector_id
+            .derive_piece_index(
+                solution.piece_offset,
+                solution.history_size,
+                self.max_pieces_in_sector,
+                chain_constants.recent_segments(),
+                chain_constants.recent_history_fraction(),
+            )
+            .segment_index();
+
+        // Piece checks are skipped if corresponding segment is not known to this node yet
+        let piece_check_params = self
+            .segment_headers_store
+            .get_segment_header(segment_index)
+            .map(|segment_header| PieceCheckParams {
+                max_pieces_in_sector: self.max_pieces_in_sector,
+                segment_commitment: segment_header.segment_commitment(),
+                recent_segments: chain_constants.recent_segments(),
+                recent_history_fraction: chain_constants.recent_history_fraction(),
+                min_sector_lifetime: chain_constants.min_sector_lifetime(),
+                current_history_size: HistorySize::from(
+                    self.segment_headers_store
+                        .max_segment_index()
+                        .unwrap_or(SegmentIndex::ZERO),
+                ),
+                sector_expiration_check_segment_commitment: solution
+                    .history_size
+                    .sector_expiration_check(chain_constants.min_sector_lifetime())
+                    .and_then(|sector_expiration_check_history_size| {
+                        self.segment_headers_store.get_segment_header(
+                            sector_expiration_check_history_size.segment_index(),
+                        )
+                    })
+                    .map(|segment_header| segment_header.segment_commitment()),
+            });
+
+        let report = subspace_verification::inspect_solution::<PosTable, _, _>(
+            &solution,
+            &slot_info.global_challenge,
+            piece_check_params.as_ref(),
+            &self.kzg,
+        );
+
+        debug!(
+            slot = %slot_info.slot_number,
+            sector_index = %solution.sector_index,
+            ?report,
+            "Inspected solution"
+        );
+
+        let within_solution_range = report.is_within_solution_range(slot_info.solution_range);
+        let within_voting_solution_range =
+            report.is_within_solution_range(slot_info.voting_solution_range);
+
+        Ok(SolutionInspection {
+            valid_for_block: report.is_valid() && within_solution_range,
+            valid_for_vote: report.is_valid() && within_voting_solution_range,
+            sector_id: report.sector_id,
+            sector_slot_challenge: report.sector_slot_challenge,
+            s_bucket_audit_index: report.s_bucket_audit_index,
+            proof_of_space: solution_check_outcome(report.proof_of_space),
+            audit_chunk: report.audit_chunk,
+            solution_distance: report.solution_distance,
+            solution_range: solution_check_outcome(within_solution_range.into()),
+            voting_solution_range: solution_check_outcome(within_voting_solution_range.into()),
+            chunk_witness: solution_check_outcome(report.chunk_witness),
+            piece_offset: solution_check_outcome(report.piece_offset),
+            expiration_history_size: report.expiration_history_size,
+            sector_expiration: solution_check_outcome(report.sector_expiration),
+            piece_index: report.piece_index,
+            piece: solution_check_outcome(report.piece),
+        })
+    }
+
+    fn solution_range_history(
+        &self,
+        samples: u32,
+        interval: BlockNumber,
+    ) -> RpcResult<SolutionRangeHistory> {
+        if samples as usize > MAX_SOLUTION_RANGE_SAMPLES_PER_REQUEST {
+            return Err(JsonRpseeError::Custom(format!(
+                "samples exceed the limit {MAX_SOLUTION_RANGE_SAMPLES_PER_REQUEST}"
+            )));
+        }
+        if interval == 0 {
+            return Err(JsonRpseeError::Custom(
+                "interval must be greater than zero".to_string(),
+            ));
+        }
+
+        let client_error = |error: sp_blockchain::Error| {
+            JsonRpseeError::Custom(format!("Failed to read blockchain data: {error}"))
+        };
+
+        let info = self.client.info();
+        let voting_solution_range = self
+            .client
+            .runtime_api()
+            .solution_ranges(info.best_hash)
+            .map_err(|error| {
+                error!(%error, "Failed to get solution ranges from runtime API");
+                JsonRpseeError::Custom(error.to_string())
+            })?
+            .voting_current;
+
+        let mut solution_range_samples = Vec::with_capacity(samples as usize);
+        let mut block_number = info.best_number.saturated_into::<BlockNumber>();
+        // Genesis block doesn't have solution range in its header
+        while block_number > 0 && solution_range_samples.len() < samples as usize {
+            let Some(block_hash) = self
+                .client
+                .hash(block_number.saturated_into())
+                .map_err(client_error)?
+            else {
+                break;
+            };
+            let Some(header) = self.client.header(block_hash).map_err(client_error)? else {
+                // Header is not available, for example after fast sync
+                break;
+            };
+            let subspace_digest_items = extract_subspace_digest_items::<
+                _,
+                FarmerPublicKey,
+                FarmerPublicKey,
+                FarmerSignature,
+            >(&header)
+            .map_err(|error| {
+                JsonRpseeError::Custom(format!(
+                    "Failed to extract digest items of block {block_number}: {error:?}"
+                ))
+            })?;
+
+            solution_range_samples.push(SolutionRangeSample {
+                block_number,
+                slot_number: subspace_digest_items.pre_digest.slot().into(),
+                solution_range: subspace_digest_items.solution_range,
+            });
+
+            block_number = block_number.saturating_sub(interval);
+        }
+        solution_range_samples.reverse();
+
+        Ok(SolutionRangeHistory {
+            slot_duration: self.chain_constants.slot_duration().as_duration(),
+            slot_probability: self.chain_constants.slot_probability(),
+            max_pieces_in_sector: self.max_pieces_in_sector,
+            voting_solution_range,
+            samples: solution_range_samples,
+        })
+    }
+
+    fn archival_finality_proof(
+        &self,
+        block_hash: H256,
+        trusted_segment_index: Option<SegmentIndex>,
+    ) -> RpcResult<Option<Bytes>> {
+        let block_hash = Block::Hash::decode(&mut block_hash.as_bytes()).map_err(|error| {
+            JsonRpseeError::Custom(format!("Failed to decode block hash: {error}"))
+        })?;
+
+        if let Some(proof) = self
+            .archival_finality_proofs
+            .lock()
+            .get(&(block_hash, trusted_segment_index))
+        {
+            return Ok(Some(proof.clone()));
+        }
+
+        let Some(proof) = self.create_archival_finality_proof(block_hash, trusted_segment_index)?
+        else {
+            return Ok(None);
+        };
+        let proof = Bytes::from(proof.encode());
+
+        self.archival_finality_proofs
+            .lock()
+            .put((block_hash, trusted_segment_index), proof.clone());
+
+        Ok(Some(proof))
+    }
+}
+
+impl<PosTable, Block, Client, SO, AS> SubspaceRpc<PosTable, Block, Client, SO, AS>
+where
+    Block: BlockT,
+    Client: HeaderBackend<Block>,
+    SO: SyncOracle + Send + Sync + Clone + 'static,
+    AS: AuxStore + Send + Sync + 'static,
+{
+    /// Create proof of archival finality for canonical block, returns `None` if block is not
+    /// canonical or was not archived yet
+    fn create_archival_finality_proof(
+        &self,
+        block_hash: Block::Hash,
+        trusted_segment_index: Option<SegmentIndex>,
+    ) -> RpcResult<Option<ArchivalFinalityProof<Block::Header>>> {
+        let client_error = |error: sp_blockchain::Error| {
+            JsonRpseeError::Custom(format!("Failed to read blockchain data: {error}"))
+        };
+
+        let Some(header) = self.client.header(block_hash).map_err(client_error)? else {
+            return Ok(None);
+        };
+        let block_number = *header.number();
+        if self.client.hash(block_number).map_err(client_error)? != Some(block_hash) {
+            return Ok(None);
+        }
+        let block_number = block_number.saturated_into::<BlockNumber>();
+
+        let Some(max_segment_index) = self.segment_headers_store.max_segment_index() else {
+            return Ok(None);
+        };
+        let first_segment_index = trusted_segment_index
+            .map_or(SegmentIndex::ZERO, |segment_index| {
+                segment_index + SegmentIndex::ONE
+            });
+
+        // Collect segment headers until the first segment that fully archived the block
+        let mut segment_headers = Vec::new();
+        let mut block_archived = false;
+        for segment_index in first_segment_index..=max_segment_index {
+            if segment_headers.len() == MAX_SEGMENT_HEADERS_PER_REQUEST {
+                return Err(JsonRpseeError::Custom(format!(
+                    "Proof would exceed the limit of {MAX_SEGMENT_HEADERS_PER_REQUEST} segment \
+                    headers, use more recent trusted segment"
+                )));
+            }
+            let Some(segment_header) = self.segment_headers_store.get_segment_header(segment_index)
+            else {
+                return Ok(None);
+            };
+            let last_archived_block = segment_header.last_archived_block();
+            segment_headers.push(segment_header);
+
+            if block_number < last_archived_block.number
+                || (block_number == last_archived_block.number
+                    && last_archived_block.partial_archived().is_none())
+            {
+                block_archived = true;
+                break;
+            }
+        }
+        if !block_archived {
+            return Ok(None);
+        }
+
+        let last_segment_header = segment_headers
+            .last()
+            .expect("Block is archived, hence there is at least one segment header; qed");
+        let expected_segment_commitment = (
+            last_segment_header.segment_index(),
+            last_segment_header.segment_commitment(),
+        );
+
+        // Walk canonical chain until the block that committed to the last segment header
+        let best_number = self.client.info().best_number;
+        let mut number = *header.number();
+        let mut block_headers = vec![header];
+        while number < best_number {
+            number += One::one();
+
+            let Some(hash) = self.client.hash(number).map_err(client_error)? else {
+                return Ok(None);
+            };
+            let Some(header) = self.client.header(hash).map_err(client_error)? else {
+                return Ok(None);
+            };
+            let commits_to_segment = header
+                .digest()
+                .logs()
+                .iter()
+                .filter_map(|log| log.as_segment_commitment())
+                .any(|segment_commitment| segment_commitment == expected_segment_commitment);
+            block_headers.push(header);
+
+            if commits_to_segment {
+                return Ok(Some(ArchivalFinalityProof {
+                    segment_headers,
+                    block_headers,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
 }
@@ -25,7 +25,8 @@
 //!
 //! Archiving is triggered by block importing notification ([`SubspaceLink::block_importing_notification_stream`])
 //! and tries to archive the block at [`ChainConstants::confirmation_depth_k`](sp_consensus_subspace::ChainConstants::confirmation_depth_k)
-//! depth from the block being imported. Block import will then wait for archiver to acknowledge
+//! depth from the block being imported (only if it is going to become the new best block, such
+//! that archiving follows the best chain). Block import will then wait for archiver to acknowledge
 //! processing, which is necessary for ensuring that when the next block is imported, inherents will
 //! contain segment header of newly archived block (must happen exactly in the next block).
 //!
@@ -46,6 +47,9 @@
 //! [`encode_block`] and [`decode_block`] are symmetric encoding/decoding functions turning
 //! [`SignedBlock`]s into bytes and back.
 
+#[cfg(test)]
+mod tests;
+
 use crate::block_import::BlockImportingNotification;
 use crate::slot_worker::SubspaceSyncOracle;
 use crate::{SubspaceLink, SubspaceNotificationSender};
@@ -55,27 +59,32 @@ use parking_lot::Mutex;
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
 use rayon::prelude::*;
-use rayon::ThreadPoolBuilder;
 use sc_client_api::{AuxStore, Backend as BackendT, BlockBackend, Finalizer, LockImportRun};
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_INFO};
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiError, ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_consensus::SyncOracle;
-use sp_consensus_subspace::{FarmerPublicKey, SubspaceApi, SubspaceJustification};
+use sp_consensus_subspace::{
+    FarmerPublicKey, SubspaceApi, SubspaceJustification, MIN_CONFIRMATION_DEPTH_K,
+};
 use sp_objects::ObjectsApi;
 use sp_runtime::generic::SignedBlock;
-use sp_runtime::traits::{Block as BlockT, CheckedSub, Header, NumberFor, One, Zero};
+use sp_runtime::traits::{
+    AtLeast32BitUnsigned, Block as BlockT, CheckedSub, Header, NumberFor, One, Zero,
+};
 use sp_runtime::{Justifications, Saturating};
 use std::error::Error;
 use std::future::Future;
+use std::num::NonZeroUsize;
 use std::slice;
 use std::sync::atomic::{AtomicU16, Ordering};
 use std::sync::Arc;
-use subspace_archiving::archiver::{Archiver, NewArchivedSegment};
+use subspace_archiving::archiver::{Archiver, ArchiverState, NewArchivedSegment};
 use subspace_core_primitives::crypto::kzg::Kzg;
-use subspace_core_primitives::objects::BlockObjectMapping;
+use subspace_core_primitives::objects::{BlockObjectMapping, ObjectMappingOverflow};
 use subspace_core_primitives::{BlockNumber, RecordedHistorySegment, SegmentHeader, SegmentIndex};
+use subspace_thread_pool::{ThreadPoolConfig, ThreadPoolManager};
 use tracing::{debug, info, warn};
 
 /// This corresponds to default value of `--max-runtime-instances` in Substrate
@@ -90,6 +99,12 @@ const BLOCKS_TO_ARCHIVE_CONCURRENCY: usize = 8;
 /// Substrate and is not worth it right now.
 /// https://github.com/paritytech/substrate/discussions/14359
 pub(crate) const FINALIZATION_DEPTH_IN_SEGMENTS: SegmentIndex = SegmentIndex::new(5);
+/// Aux storage key under which archiver state is persisted
+const ARCHIVER_STATE_KEY: &[u8] = b"archiver-state";
+/// Archiver state is persisted every this many archived blocks (and whenever a new segment is
+/// archived), such that after restart only a few blocks need to be archived again instead of all
+/// blocks since the last archived segment
+const ARCHIVER_STATE_PERSISTENCE_INTERVAL: BlockNumber = 100;
 
 #[derive(Debug)]
 struct SegmentHeadersStoreInner<AS> {
@@ -309,6 +324,14 @@ where
                     calls,
                 )
             })
+            .and_then(|mut block_object_mappings| {
+                apply_object_mapping_limits(
+                    client,
+                    last_archived_block_hash,
+                    &mut block_object_mappings,
+                )?;
+                Ok(block_object_mappings)
+            })
             .unwrap_or_default();
 
         return Ok(Some((
@@ -321,6 +344,46 @@ where
     Ok(None)
 }
 
+/// Apply object mapping limits defined by the runtime to object mapping of the block, returns
+/// details of the overflow if object mapping exceeded limits.
+///
+/// Object mappings of blocks produced with runtimes that predate limits are left intact.
+fn apply_object_mapping_limits<Block, Client>(
+    client: &Client,
+    block_hash: Block::Hash,
+    block_object_mappings: &mut BlockObjectMapping,
+) -> Result<Option<ObjectMappingOverflow>, ApiError>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block>,
+    Client::Api: ObjectsApi<Block>,
+{
+    let runtime_api = client.runtime_api();
+
+    let objects_api_version = runtime_api
+        .api_version::<dyn ObjectsApi<Block>>(block_hash)?
+        .unwrap_or(1);
+    if objects_api_version < 2 {
+        return Ok(None);
+    }
+
+    let object_mapping_limits = runtime_api.object_mapping_limits(block_hash)?;
+    let maybe_object_mapping_overflow = block_object_mappings.apply_limits(&object_mapping_limits);
+
+    if let Some(object_mapping_overflow) = &maybe_object_mapping_overflow {
+        warn!(
+            ?block_hash,
+            objects = %object_mapping_overflow.objects,
+            size = %object_mapping_overflow.size,
+            retained_objects = %object_mapping_overflow.retained_objects,
+            overflow_policy = ?object_mapping_limits.overflow_policy,
+            "Block object mapping exceeds limits"
+        );
+    }
+
+    Ok(maybe_object_mapping_overflow)
+}
+
 /// Derive genesis segment on demand, returns `Ok(None)` in case genesis block was already pruned
 pub fn recreate_genesis_segment<Block, Client>(
     client: &Client,
@@ -346,12 +409,16 @@ where
                 calls,
             )
         })
+        .and_then(|mut block_object_mappings| {
+            apply_object_mapping_limits(client, genesis_hash, &mut block_object_mappings)?;
+            Ok(block_object_mappings)
+        })
         .unwrap_or_default();
 
     let encoded_block = encode_block(signed_block);
 
     let new_archived_segment = Archiver::new(kzg)?
-        .add_block(encoded_block, block_object_mappings, false)
+        .add_block(encoded_block, block_object_mappings, false)?
         .into_iter()
         .next()
         .expect("Genesis block always results in exactly one archived segment; qed");
@@ -369,6 +436,49 @@ where
     best_archived_block: (Block::Hash, NumberFor<Block>),
 }
 
+/// Persist archiver state alongside the last block that was added to the archiver
+fn persist_archiver_state<Block, AS>(
+    aux_store: &AS,
+    block_hash: Block::Hash,
+    block_number: NumberFor<Block>,
+    archiver: &Archiver,
+) -> sp_blockchain::Result<()>
+where
+    Block: BlockT,
+    AS: AuxStore,
+{
+    aux_store.insert_aux(
+        &[(
+            ARCHIVER_STATE_KEY,
+            (block_hash, block_number, archiver.state())
+                .encode()
+                .as_slice(),
+        )],
+        &[],
+    )
+}
+
+/// Read archiver state persisted by [`persist_archiver_state`]
+fn load_archiver_state<Block, AS>(
+    aux_store: &AS,
+) -> sp_blockchain::Result<Option<(Block::Hash, NumberFor<Block>, ArchiverState)>>
+where
+    Block: BlockT,
+    AS: AuxStore,
+{
+    let Some(encoded) = aux_store.get_aux(ARCHIVER_STATE_KEY)? else {
+        return Ok(None);
+    };
+
+    match Decode::decode(&mut encoded.as_slice()) {
+        Ok(archiver_state) => Ok(Some(archiver_state)),
+        Err(error) => {
+            warn!(%error, "Failed to decode persisted archiver state, ignoring");
+            Ok(None)
+        }
+    }
+}
+
 /// Encode block for archiving purposes.
 ///
 /// Only specific Subspace justifications are included in the encoding, determined by result of
@@ -439,6 +549,69 @@ where
     SignedBlock::<Block>::decode(&mut encoded_block)
 }
 
+/// Blocks that need to be archived in response to a block being imported
+#[derive(Debug, PartialEq, Eq)]
+enum BlocksToArchive<Hash, Number> {
+    /// Nothing to archive, chain is not deep enough yet or blocks were already archived
+    Nothing,
+    /// Blocks of the chain being imported that need to be archived, in ascending order
+    Blocks(Vec<(Hash, Number)>),
+    /// Chain being imported doesn't contain the best archived block, meaning reorg happened below
+    /// archiving depth, archived history can't be reverted and archiver must stop
+    ReorgBelowArchivingPoint,
+}
+
+/// Find blocks that need to be archived once block `block_number` with parent `parent_hash` is
+/// imported as the new best block.
+///
+/// Blocks are looked up by walking the ancestry of the block being imported rather than by number,
+/// since at the time of the call the block is not imported yet and blocks by number correspond to
+/// the previous best chain, which might be a different fork. There can be more than one block to
+/// archive if the best chain switched to a longer fork.
+fn find_blocks_to_archive<Hash, Number, ParentHashOf>(
+    parent_hash: Hash,
+    block_number: Number,
+    confirmation_depth_k: Number,
+    (best_archived_block_hash, best_archived_block_number): (Hash, Number),
+    parent_hash_of: ParentHashOf,
+) -> sp_blockchain::Result<BlocksToArchive<Hash, Number>>
+where
+    Hash: Copy + Eq,
+    Number: AtLeast32BitUnsigned + Copy,
+    ParentHashOf: Fn(Hash) -> sp_blockchain::Result<Hash>,
+{
+    let Some(block_number_to_archive) = block_number.checked_sub(&confirmation_depth_k) else {
+        return Ok(BlocksToArchive::Nothing);
+    };
+    if best_archived_block_number >= block_number {
+        // Best archived block is not an ancestor of the block being imported, this can only happen
+        // with chain that is shorter than archived history, which is definitely a fork
+        return Ok(BlocksToArchive::ReorgBelowArchivingPoint);
+    }
+
+    let mut blocks = Vec::new();
+    let mut hash = parent_hash;
+    let mut number = block_number - One::one();
+    while number > best_archived_block_number {
+        if number <= block_number_to_archive {
+            blocks.push((hash, number));
+        }
+        hash = parent_hash_of(hash)?;
+        number -= One::one();
+    }
+
+    if hash != best_archived_block_hash {
+        return Ok(BlocksToArchive::ReorgBelowArchivingPoint);
+    }
+
+    if blocks.is_empty() {
+        return Ok(BlocksToArchive::Nothing);
+    }
+
+    blocks.reverse();
+    Ok(BlocksToArchive::Blocks(blocks))
+}
+
 fn initialize_archiver<Block, Client, AS>(
     segment_headers_store: &SegmentHeadersStore<AS>,
     subspace_link: &SubspaceLink<Block>,
@@ -458,13 +631,24 @@ where
         .runtime_api()
         .chain_constants(best_block_hash)?
         .confirmation_depth_k();
+    // Runtime checks this during genesis build already, but chain spec might have been built with
+    // older runtime or storage might have been changed afterwards
+    if confirmation_depth_k < MIN_CONFIRMATION_DEPTH_K {
+        return Err(sp_blockchain::Error::Application(
+            format!(
+                "Confirmation depth K {confirmation_depth_k} is less than minimum \
+                {MIN_CONFIRMATION_DEPTH_K}, archiving would be vulnerable to short reorgs"
+            )
+            .into(),
+        ));
+    }
 
     let maybe_last_archived_block = find_last_archived_block(
         client,
         segment_headers_store,
         best_block_number.saturating_sub(confirmation_depth_k.into()),
     )?;
-    let have_last_segment_header = maybe_last_archived_block.is_some();
+    let mut is_continuation = maybe_last_archived_block.is_some();
     let mut best_archived_block = None;
 
     let mut archiver =
@@ -526,14 +710,58 @@ where
             Archiver::new(subspace_link.kzg().clone()).expect("Incorrect parameters for archiver")
         };
 
+    let mut blocks_to_archive_from = archiver
+        .last_archived_block_number()
+        .map(|n| n + 1)
+        .unwrap_or_default();
+
+    // Archiver state persisted before shutdown allows to skip blocks that were already added to the
+    // archiver after the last archived segment. It is only used if it is a continuation of the
+    // last archived segment and the block it was persisted at is still part of the canonical
+    // chain at archiving depth, otherwise those blocks are simply archived again.
+    if let Some((state_block_hash, state_block_number, archiver_state)) =
+        load_archiver_state::<Block, _>(client)?
+    {
+        let current_archiver_state = archiver.state();
+        let state_matches_chain = archiver_state.segment_index()
+            == current_archiver_state.segment_index()
+            && archiver_state.last_archived_block() == current_archiver_state.last_archived_block()
+            && state_block_number <= best_block_number.saturating_sub(confirmation_depth_k.into())
+            && client.hash(state_block_number)? == Some(state_block_hash);
+        let maybe_restored_blocks_to_archive_from =
+            TryInto::<BlockNumber>::try_into(state_block_number)
+                .ok()
+                .map(|state_block_number| state_block_number + 1)
+                .filter(|&restored_blocks_to_archive_from| {
+                    state_matches_chain && restored_blocks_to_archive_from > blocks_to_archive_from
+                });
+
+        if let Some(restored_blocks_to_archive_from) = maybe_restored_blocks_to_archive_from {
+            match Archiver::from_state(subspace_link.kzg().clone(), archiver_state) {
+                Ok(restored_archiver) => {
+                    info!(%state_block_number, "Restored persisted archiver state");
+
+                    archiver = restored_archiver;
+                    blocks_to_archive_from = restored_blocks_to_archive_from;
+                    is_continuation = true;
+                    best_archived_block.replace((state_block_hash, state_block_number));
+                }
+                Err(error) => {
+                    warn!(%error, "Failed to restore persisted archiver state, ignoring");
+                }
+            }
+        } else {
+            debug!(
+                %state_block_number,
+                "Persisted archiver state doesn't match the chain, ignoring"
+            );
+        }
+    }
+
     let mut older_archived_segments = Vec::new();
 
-    // Process blocks since last fully archived block (or genesis) up to the current head minus K
+    // Process blocks since last archived block (or genesis) up to the current head minus K
     {
-        let blocks_to_archive_from = archiver
-            .last_archived_block_number()
-            .map(|n| n + 1)
-            .unwrap_or_default();
         let blocks_to_archive_to =
             TryInto::<BlockNumber>::try_into(best_block_number)
                 .unwrap_or_else(|_| {
@@ -544,7 +772,7 @@ where
                 .checked_sub(confirmation_depth_k)
                 .filter(|&blocks_to_archive_to| blocks_to_archive_to >= blocks_to_archive_from)
                 .or({
-                    if have_last_segment_header {
+                    if is_continuation {
                         None
                     } else {
                         // If not continuation, archive genesis block
@@ -558,9 +786,11 @@ where
                 blocks_to_archive_from, blocks_to_archive_to,
             );
 
-            let thread_pool = ThreadPoolBuilder::new()
-                .num_threads(BLOCKS_TO_ARCHIVE_CONCURRENCY)
-                .build()
+            let thread_pool = ThreadPoolManager::global()
+                .build(ThreadPoolConfig::new(
+                    "archiver-init",
+                    NonZeroUsize::new(BLOCKS_TO_ARCHIVE_CONCURRENCY).expect("Not zero; qed"),
+                ))
                 .map_err(|error| {
                     sp_blockchain::Error::Backend(format!(
                         "Failed to create thread pool for archiver initialization: {error}"
@@ -590,6 +820,14 @@ where
                                         calls,
                                     )
                                 })
+                                .and_then(|mut block_object_mappings| {
+                                    apply_object_mapping_limits(
+                                        client,
+                                        block_hash,
+                                        &mut block_object_mappings,
+                                    )?;
+                                    Ok(block_object_mappings)
+                                })
                                 .unwrap_or_default();
 
                             Ok((block, block_object_mappings))
@@ -616,8 +854,14 @@ where
                     encoded_block.len() as f32 / 1024.0
                 );
 
-                let archived_segments =
-                    archiver.add_block(encoded_block, block_object_mappings, false);
+                let archived_segments = archiver
+                    .add_block(encoded_block, block_object_mappings, false)
+                    .map_err(|error| {
+                        sp_blockchain::Error::Application(
+                            format!("Failed to archive block {block_number_to_archive}: {error}")
+                                .into(),
+                        )
+                    })?;
                 let new_segment_headers: Vec<SegmentHeader> = archived_segments
                     .iter()
                     .map(|archived_segment| archived_segment.segment_header)
@@ -641,6 +885,17 @@ where
                     );
                 }
             }
+
+            if let Some((best_archived_block_hash, best_archived_block_number)) =
+                best_archived_block
+            {
+                persist_archiver_state::<Block, _>(
+                    client,
+                    best_archived_block_hash,
+                    best_archived_block_number,
+                    &archiver,
+                )?;
+            }
         }
     }
 
@@ -702,7 +957,11 @@ fn finalize_block<Block, Backend, Client>(
 ///
 /// NOTE: Archiver is doing blocking operations and must run in a dedicated task.
 ///
-/// Archiver is only able to move forward and doesn't support reorgs. Upon restart it will check
+/// Archiver is only able to move forward and doesn't support reorgs below archiving depth, it
+/// follows the best chain and archives blocks of the best chain once they are
+/// [`ChainConstants::confirmation_depth_k`](sp_consensus_subspace::ChainConstants::confirmation_depth_k)
+/// deep. If best chain switches to a fork below archiving depth, archiving is skipped until best
+/// chain extends archived history again. Upon restart it will check
 /// [`SegmentHeadersStore`] and chain history to reconstruct "current" state it was in before last
 /// shutdown and continue incrementally archiving blockchain history from there.
 ///
@@ -749,6 +1008,22 @@ where
         best_archived_block: (mut best_archived_block_hash, mut best_archived_block_number),
     } = initialize_archiver(&segment_headers_store, subspace_link, client.as_ref())?;
 
+    // Archiving happens in bursts whenever a segment is complete, so it gets half of the CPU
+    // budget to leave room for other components
+    let thread_pool_manager = ThreadPoolManager::global();
+    let thread_pool = thread_pool_manager
+        .build(ThreadPoolConfig::new(
+            "archiver",
+            NonZeroUsize::new(thread_pool_manager.cpu_budget().get() / 2)
+                .unwrap_or(NonZeroUsize::MIN),
+        ))
+        .map_err(|error| {
+            sp_blockchain::Error::Backend(format!(
+                "Failed to create thread pool for archiver: {error}"
+            ))
+        })?;
+    archiver.set_thread_pool(Arc::new(thread_pool));
+
     let mut block_importing_notification_stream = subspace_link
         .block_importing_notification_stream
         .subscribe();
@@ -768,96 +1043,153 @@ where
 
         while let Some(BlockImportingNotification {
             block_number,
+            parent_hash,
+            is_new_best,
             // Just to be very explicit that block import shouldn't continue until archiving
             // is over
             acknowledgement_sender: _acknowledgement_sender,
             ..
         }) = block_importing_notification_stream.next().await
         {
-            let block_number_to_archive =
-                match block_number.checked_sub(&confirmation_depth_k.into()) {
-                    Some(block_number_to_archive) => block_number_to_archive,
-                    None => {
-                        continue;
-                    }
-                };
-
-            if best_archived_block_number >= block_number_to_archive {
-                // This block was already archived, skip
+            if !is_new_best {
+                // Archiving follows the best chain, blocks of other forks will be archived if and
+                // when they become part of the best chain
                 continue;
             }
 
-            best_archived_block_number = block_number_to_archive;
-
-            let block = client
-                .block(
+            let blocks_to_archive = find_blocks_to_archive(
+                parent_hash,
+                block_number,
+                confirmation_depth_k.into(),
+                (best_archived_block_hash, best_archived_block_number),
+                |hash| {
                     client
-                        .hash(block_number_to_archive)?
-                        .expect("Older block by number must always exist"),
-                )?
-                .expect("Older block by number must always exist");
+                        .header(hash)?
+                        .map(|header| *header.parent_hash())
+                        .ok_or_else(|| sp_blockchain::Error::MissingHeader(hash.to_string()))
+                },
+            )?;
+
+            let blocks_to_archive = match blocks_to_archive {
+                BlocksToArchive::Nothing => {
+                    continue;
+                }
+                BlocksToArchive::Blocks(blocks_to_archive) => blocks_to_archive,
+                BlocksToArchive::ReorgBelowArchivingPoint => {
+                    // Archived history can't be reverted and segment headers of this fork will not
+                    // match archived history, so archiver is stopped, which in turn stops the node
+                    // and block authoring on top of the inconsistent chain
+                    return Err(sp_blockchain::Error::Application(
+                        format!(
+                            "Best chain switched to a fork below archiving depth at block \
+                            #{block_number} (parent {parent_hash}), best archived block is \
+                            #{best_archived_block_number} ({best_archived_block_hash})"
+                        )
+                        .into(),
+                    ));
+                }
+            };
 
-            let parent_block_hash = *block.block.header().parent_hash();
-            let block_hash_to_archive = block.block.hash();
+            let mut new_segment_headers = Vec::new();
+            for (block_hash_to_archive, block_number_to_archive) in blocks_to_archive {
+                let block = client
+                    .block(block_hash_to_archive)?
+                    .expect("Ancestor of the block being imported must always exist; qed");
 
-            debug!(
-                "Archiving block {:?} ({})",
-                block_number_to_archive, block_hash_to_archive
-            );
+                let parent_block_hash = *block.block.header().parent_hash();
 
-            if parent_block_hash != best_archived_block_hash {
-                let error = format!(
-                    "Attempt to switch to a different fork beyond archiving depth, \
-                    can't do it: parent block hash {}, best archived block hash {}",
-                    parent_block_hash, best_archived_block_hash
+                debug!(
+                    "Archiving block {:?} ({})",
+                    block_number_to_archive, block_hash_to_archive
                 );
-                return Err(sp_blockchain::Error::Consensus(sp_consensus::Error::Other(
-                    error.into(),
-                )));
-            }
 
-            best_archived_block_hash = block_hash_to_archive;
+                let (block_object_mappings, maybe_object_mapping_overflow) = client
+                    .runtime_api()
+                    .validated_object_call_hashes(block_hash_to_archive)
+                    .and_then(|calls| {
+                        client.runtime_api().extract_block_object_mapping(
+                            parent_block_hash,
+                            block.block.clone(),
+                            calls,
+                        )
+                    })
+                    .and_then(|mut block_object_mappings| {
+                        let maybe_object_mapping_overflow = apply_object_mapping_limits(
+                            client.as_ref(),
+                            block_hash_to_archive,
+                            &mut block_object_mappings,
+                        )?;
+                        Ok((block_object_mappings, maybe_object_mapping_overflow))
+                    })
+                    .map_err(|error| {
+                        sp_blockchain::Error::Application(
+                            format!("Failed to retrieve block object mappings: {error}").into(),
+                        )
+                    })?;
+
+                if let Some(object_mapping_overflow) = maybe_object_mapping_overflow {
+                    telemetry!(
+                        telemetry;
+                        CONSENSUS_INFO;
+                        "subspace.object_mapping_overflow";
+                        "number" => ?block_number_to_archive,
+                        "hash" => ?block_hash_to_archive,
+                        "objects" => object_mapping_overflow.objects,
+                        "size" => object_mapping_overflow.size,
+                        "retained_objects" => object_mapping_overflow.retained_objects,
+                    );
+                }
 
-            let block_object_mappings = client
-                .runtime_api()
-                .validated_object_call_hashes(block_hash_to_archive)
-                .and_then(|calls| {
-                    client.runtime_api().extract_block_object_mapping(
-                        parent_block_hash,
-                        block.block.clone(),
-                        calls,
-                    )
-                })
-                .map_err(|error| {
-                    sp_blockchain::Error::Application(
-                        format!("Failed to retrieve block object mappings: {error}").into(),
+                let encoded_block = encode_block(block);
+                debug!(
+                    "Encoded block {} has size of {:.2} kiB",
+                    block_number_to_archive,
+                    encoded_block.len() as f32 / 1024.0
+                );
+
+                let archived_segments = archiver
+                    .add_block(
+                        encoded_block,
+                        block_object_mappings,
+                        !sync_oracle.is_major_syncing(),
                     )
-                })?;
+                    .map_err(|error| {
+                        sp_blockchain::Error::Application(
+                            format!("Failed to archive block {block_number_to_archive}: {error}")
+                                .into(),
+                        )
+                    })?;
 
-            let encoded_block = encode_block(block);
-            debug!(
-                "Encoded block {} has size of {:.2} kiB",
-                block_number_to_archive,
-                encoded_block.len() as f32 / 1024.0
-            );
+                let new_segments_archived = !archived_segments.is_empty();
 
-            let mut new_segment_headers = Vec::new();
-            for archived_segment in archiver.add_block(
-                encoded_block,
-                block_object_mappings,
-                !sync_oracle.is_major_syncing(),
-            ) {
-                let segment_header = archived_segment.segment_header;
+                for archived_segment in archived_segments {
+                    let segment_header = archived_segment.segment_header;
 
-                segment_headers_store.add_segment_headers(slice::from_ref(&segment_header))?;
+                    segment_headers_store.add_segment_headers(slice::from_ref(&segment_header))?;
 
-                send_archived_segment_notification(
-                    &archived_segment_notification_sender,
-                    archived_segment,
-                )
-                .await;
+                    send_archived_segment_notification(
+                        &archived_segment_notification_sender,
+                        archived_segment,
+                    )
+                    .await;
+
+                    new_segment_headers.push(segment_header);
+                }
 
-                new_segment_headers.push(segment_header);
+                best_archived_block_hash = block_hash_to_archive;
+                best_archived_block_number = block_number_to_archive;
+
+                if new_segments_archived
+                    || (block_number_to_archive % ARCHIVER_STATE_PERSISTENCE_INTERVAL.into())
+                        .is_zero()
+                {
+                    persist_archiver_state::<Block, _>(
+                        client.as_ref(),
+                        block_hash_to_archive,
+                        block_number_to_archive,
+                        &archiver,
+                    )?;
+                }
             }
 
             if !new_segment_headers.is_empty() {
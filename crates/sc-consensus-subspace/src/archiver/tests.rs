@@ -0,0 +1,396 @@
+use crate::archiver::{
+    find_blocks_to_archive, load_archiver_state, persist_archiver_state, BlocksToArchive,
+    ARCHIVER_STATE_KEY,
+};
+use parking_lot::Mutex;
+use sc_client_api::AuxStore;
+use sp_consensus_subspace::MIN_CONFIRMATION_DEPTH_K;
+use sp_core::H256;
+use sp_runtime::testing::{Block as TestBlock, ExtrinsicWrapper};
+use std::collections::HashMap;
+use subspace_archiving::archiver::Archiver;
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::objects::BlockObjectMapping;
+use subspace_core_primitives::{RecordedHistorySegment, SegmentHeader};
+
+const CONFIRMATION_DEPTH_K: u32 = 3;
+const GENESIS_HASH: u64 = 0;
+
+type Block = TestBlock<ExtrinsicWrapper<()>>;
+
+#[derive(Default)]
+struct TestAuxStore(Mutex<HashMap<Vec<u8>, Vec<u8>>>);
+
+impl AuxStore for TestAuxStore {
+    fn insert_aux<
+        'a,
+        'b: 'a,
+        'c: 'a,
+        I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+        D: IntoIterator<Item = &'a &'b [u8]>,
+    >(
+        &self,
+        insert: I,
+        delete: D,
+    ) -> sp_blockchain::Result<()> {
+        let mut storage = self.0.lock();
+        for (key, value) in insert {
+            storage.insert(key.to_vec(), value.to_vec());
+        }
+        for key in delete {
+            storage.remove(*key);
+        }
+        Ok(())
+    }
+
+    fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+        Ok(self.0.lock().get(key).cloned())
+    }
+}
+
+/// Tree of blocks, block hashes are unique numbers assigned in order of creation
+#[derive(Default)]
+struct BlockTree {
+    /// Map from block hash to parent hash and block number
+    blocks: HashMap<u64, (u64, u32)>,
+    next_hash: u64,
+    confirmation_depth_k: u32,
+}
+
+impl BlockTree {
+    fn new() -> Self {
+        Self::with_confirmation_depth_k(CONFIRMATION_DEPTH_K)
+    }
+
+    fn with_confirmation_depth_k(confirmation_depth_k: u32) -> Self {
+        let mut block_tree = Self::default();
+        block_tree.blocks.insert(GENESIS_HASH, (GENESIS_HASH, 0));
+        block_tree.next_hash = GENESIS_HASH + 1;
+        block_tree.confirmation_depth_k = confirmation_depth_k;
+        block_tree
+    }
+
+    /// Build a chain of `length` blocks on top of `parent_hash`, returns hashes of new blocks
+    fn extend(&mut self, mut parent_hash: u64, length: u32) -> Vec<u64> {
+        (0..length)
+            .map(|_| {
+                let number = self.blocks[&parent_hash].1 + 1;
+                let hash = self.next_hash;
+                self.next_hash += 1;
+                self.blocks.insert(hash, (parent_hash, number));
+                parent_hash = hash;
+                hash
+            })
+            .collect()
+    }
+
+    fn number(&self, hash: u64) -> u32 {
+        self.blocks[&hash].1
+    }
+
+    /// Simulates import of `block_hash` as the new best block with archiver tracking
+    /// `best_archived_block`
+    fn import(
+        &self,
+        block_hash: u64,
+        best_archived_block: &mut (u64, u32),
+    ) -> BlocksToArchive<u64, u32> {
+        let (parent_hash, block_number) = self.blocks[&block_hash];
+        let blocks_to_archive = find_blocks_to_archive(
+            parent_hash,
+            block_number,
+            self.confirmation_depth_k,
+            *best_archived_block,
+            |hash| Ok(self.blocks[&hash].0),
+        )
+        .unwrap();
+
+        if let BlocksToArchive::Blocks(blocks) = &blocks_to_archive {
+            // Blocks must always extend archived history without gaps
+            let mut expected_parent_hash = best_archived_block.0;
+            for &(hash, number) in blocks {
+                assert_eq!(self.blocks[&hash].0, expected_parent_hash);
+                assert_eq!(self.number(hash), number);
+                expected_parent_hash = hash;
+            }
+            *best_archived_block = *blocks.last().unwrap();
+        }
+
+        blocks_to_archive
+    }
+}
+
+#[test]
+fn linear_chain() {
+    let mut block_tree = BlockTree::new();
+    let chain = block_tree.extend(GENESIS_HASH, 10);
+    let mut best_archived_block = (GENESIS_HASH, 0);
+
+    for (index, &block_hash) in chain.iter().enumerate() {
+        let blocks_to_archive = block_tree.import(block_hash, &mut best_archived_block);
+
+        if (index as u32) < CONFIRMATION_DEPTH_K {
+            assert_eq!(blocks_to_archive, BlocksToArchive::Nothing);
+        } else {
+            // Exactly one block at confirmation depth is archived
+            let expected_block = chain[index - CONFIRMATION_DEPTH_K as usize];
+            assert_eq!(
+                blocks_to_archive,
+                BlocksToArchive::Blocks(vec![(expected_block, block_tree.number(expected_block))])
+            );
+        }
+    }
+
+    assert_eq!(best_archived_block, (chain[6], 7));
+
+    // Re-importing blocks of the same chain (for example sibling at the same height) doesn't
+    // archive anything
+    let sibling = block_tree.extend(chain[8], 1)[0];
+    assert_eq!(
+        block_tree.import(sibling, &mut best_archived_block),
+        BlocksToArchive::Nothing
+    );
+}
+
+#[test]
+fn reorg_above_archiving_depth() {
+    let mut block_tree = BlockTree::new();
+    let chain = block_tree.extend(GENESIS_HASH, 6);
+    let mut best_archived_block = (GENESIS_HASH, 0);
+    for &block_hash in &chain {
+        block_tree.import(block_hash, &mut best_archived_block);
+    }
+    // Blocks 1..=3 are archived
+    assert_eq!(best_archived_block, (chain[2], 3));
+
+    // Fork right on top of the best archived block becomes the best chain at once after being
+    // imported as a longer chain
+    let fork = block_tree.extend(chain[2], 5);
+    // Fork blocks at heights 4 and 5 must be archived instead of blocks of the original chain
+    // at the same heights
+    assert_eq!(
+        block_tree.import(fork[4], &mut best_archived_block),
+        BlocksToArchive::Blocks(vec![(fork[0], 4), (fork[1], 5)])
+    );
+    assert_eq!(best_archived_block, (fork[1], 5));
+
+    // Original chain is now a fork below archiving depth
+    let original_extended = block_tree.extend(chain[5], 3);
+    assert_eq!(
+        block_tree.import(original_extended[2], &mut best_archived_block),
+        BlocksToArchive::ReorgBelowArchivingPoint
+    );
+    // Nothing changes in archived history
+    assert_eq!(best_archived_block, (fork[1], 5));
+}
+
+#[test]
+fn reorg_below_archiving_depth() {
+    let mut block_tree = BlockTree::new();
+    let chain = block_tree.extend(GENESIS_HASH, 8);
+    let mut best_archived_block = (GENESIS_HASH, 0);
+    for &block_hash in &chain {
+        block_tree.import(block_hash, &mut best_archived_block);
+    }
+    assert_eq!(best_archived_block, (chain[4], 5));
+
+    // Fork starting below best archived block
+    let fork = block_tree.extend(chain[2], 7);
+    for &block_hash in &fork {
+        assert_eq!(
+            block_tree.import(block_hash, &mut best_archived_block),
+            BlocksToArchive::ReorgBelowArchivingPoint
+        );
+    }
+    assert_eq!(best_archived_block, (chain[4], 5));
+
+    // Chain that is shorter than archived history
+    let short_fork = block_tree.extend(GENESIS_HASH, 4);
+    assert_eq!(
+        block_tree.import(short_fork[3], &mut best_archived_block),
+        BlocksToArchive::ReorgBelowArchivingPoint
+    );
+}
+
+/// Archives blocks with real archiver, returns segment headers of produced segments
+fn archive_blocks(
+    archiver: &mut Archiver,
+    blocks_to_archive: BlocksToArchive<u64, u32>,
+) -> Vec<SegmentHeader> {
+    let BlocksToArchive::Blocks(blocks) = blocks_to_archive else {
+        return Vec::new();
+    };
+
+    blocks
+        .into_iter()
+        .flat_map(|(hash, _number)| {
+            // Every block is a third of the segment, content depends on the hash such that blocks
+            // of different forks at the same height are different
+            let block = vec![hash as u8; RecordedHistorySegment::SIZE / 3];
+            archiver
+                .add_block(block, BlockObjectMapping::default(), false)
+                .unwrap()
+        })
+        .map(|archived_segment| archived_segment.segment_header)
+        .collect()
+}
+
+#[test]
+fn reorg_around_segment_boundary() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg).unwrap();
+    let mut block_tree = BlockTree::new();
+    let chain = block_tree.extend(GENESIS_HASH, 6);
+    let mut best_archived_block = (GENESIS_HASH, 0);
+
+    let mut segment_headers = Vec::new();
+    for &block_hash in &chain {
+        let blocks_to_archive = block_tree.import(block_hash, &mut best_archived_block);
+        segment_headers.extend(archive_blocks(&mut archiver, blocks_to_archive));
+    }
+    // Blocks 1..=3 are archived and the third block completes the first segment
+    assert_eq!(best_archived_block, (chain[2], 3));
+    assert_eq!(segment_headers.len(), 1);
+    assert_eq!(segment_headers[0].last_archived_block().number, 3);
+
+    // Fork that starts below the last block of the archived segment can't be archived
+    let fork_below = block_tree.extend(chain[1], 6);
+    assert_eq!(
+        block_tree.import(fork_below[5], &mut best_archived_block),
+        BlocksToArchive::ReorgBelowArchivingPoint
+    );
+    assert_eq!(best_archived_block, (chain[2], 3));
+
+    // Fork right on top of the last block of the archived segment continues archived history
+    let fork = block_tree.extend(chain[2], 10);
+    for &block_hash in &fork {
+        let blocks_to_archive = block_tree.import(block_hash, &mut best_archived_block);
+        segment_headers.extend(archive_blocks(&mut archiver, blocks_to_archive));
+        if segment_headers.len() == 2 {
+            break;
+        }
+    }
+    assert_eq!(segment_headers.len(), 2);
+    assert_eq!(
+        segment_headers[1].prev_segment_header_hash(),
+        segment_headers[0].hash()
+    );
+    let last_archived_block_number = segment_headers[1].last_archived_block().number;
+    assert!(last_archived_block_number > 3);
+    // Last block of the second segment belongs to the fork
+    assert_eq!(
+        best_archived_block,
+        (
+            fork[(last_archived_block_number - 4) as usize],
+            last_archived_block_number
+        )
+    );
+
+    // Original chain is now below archiving point right after segment boundary
+    let original_extended = block_tree.extend(chain[5], 10);
+    assert_eq!(
+        block_tree.import(original_extended[9], &mut best_archived_block),
+        BlocksToArchive::ReorgBelowArchivingPoint
+    );
+}
+
+#[test]
+fn linear_chain_at_different_depths() {
+    for confirmation_depth_k in [MIN_CONFIRMATION_DEPTH_K, 5, 10, 100] {
+        let mut block_tree = BlockTree::with_confirmation_depth_k(confirmation_depth_k);
+        let chain = block_tree.extend(GENESIS_HASH, confirmation_depth_k + 10);
+        let mut best_archived_block = (GENESIS_HASH, 0);
+
+        for (index, &block_hash) in chain.iter().enumerate() {
+            let blocks_to_archive = block_tree.import(block_hash, &mut best_archived_block);
+
+            if (index as u32) < confirmation_depth_k {
+                assert_eq!(
+                    blocks_to_archive,
+                    BlocksToArchive::Nothing,
+                    "Nothing is archived before chain reaches confirmation depth \
+                    {confirmation_depth_k}"
+                );
+            } else {
+                let expected_block = chain[index - confirmation_depth_k as usize];
+                assert_eq!(
+                    blocks_to_archive,
+                    BlocksToArchive::Blocks(vec![(
+                        expected_block,
+                        block_tree.number(expected_block)
+                    )]),
+                    "Block at confirmation depth {confirmation_depth_k} is archived"
+                );
+            }
+        }
+
+        // Archived history always lags behind the best block by exactly confirmation depth
+        assert_eq!(best_archived_block, (chain[9], 10));
+
+        // Fork right on top of the best archived block is archived instead of the original chain
+        // once it becomes the best chain
+        let fork = block_tree.extend(chain[9], confirmation_depth_k + 1);
+        assert_eq!(
+            block_tree.import(
+                fork[confirmation_depth_k as usize],
+                &mut best_archived_block
+            ),
+            BlocksToArchive::Blocks(vec![(fork[0], 11)])
+        );
+    }
+}
+
+#[test]
+fn archiver_state_persistence() {
+    let aux_store = TestAuxStore::default();
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+
+    // Nothing persisted yet
+    assert!(load_archiver_state::<Block, _>(&aux_store)
+        .unwrap()
+        .is_none());
+
+    // Archive a bit more than a segment, such that archiver has both archived segment and buffered
+    // blocks
+    for block_number in 0..4_u8 {
+        archiver
+            .add_block(
+                vec![block_number; RecordedHistorySegment::SIZE / 3],
+                BlockObjectMapping::default(),
+                false,
+            )
+            .unwrap();
+    }
+
+    let block_hash = H256::repeat_byte(3);
+    persist_archiver_state::<Block, _>(&aux_store, block_hash, 3, &archiver).unwrap();
+
+    let (loaded_block_hash, loaded_block_number, archiver_state) =
+        load_archiver_state::<Block, _>(&aux_store)
+            .unwrap()
+            .unwrap();
+    assert_eq!(loaded_block_hash, block_hash);
+    assert_eq!(loaded_block_number, 3);
+    assert_eq!(archiver_state, archiver.state());
+
+    // Restored archiver continues exactly where the original one stopped
+    let mut restored_archiver = Archiver::from_state(kzg, archiver_state).unwrap();
+    let next_block = vec![4; RecordedHistorySegment::SIZE / 3];
+    assert_eq!(
+        restored_archiver
+            .add_block(next_block.clone(), BlockObjectMapping::default(), false)
+            .unwrap(),
+        archiver
+            .add_block(next_block, BlockObjectMapping::default(), false)
+            .unwrap()
+    );
+
+    // Corrupted state is ignored rather than failing archiver initialization
+    aux_store
+        .insert_aux(&[(ARCHIVER_STATE_KEY, [1, 2, 3].as_slice())], &[])
+        .unwrap();
+    assert!(load_archiver_state::<Block, _>(&aux_store)
+        .unwrap()
+        .is_none());
+}
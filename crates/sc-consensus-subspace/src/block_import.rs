@@ -55,6 +55,7 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use subspace_core_primitives::{
     BlockNumber, HistorySize, PublicKey, SectorId, SegmentHeader, SegmentIndex, SolutionRange,
+    SolutionShapeError,
 };
 use subspace_proof_of_space::Table;
 use subspace_verification::{calculate_block_weight, PieceCheckParams, VerifySolutionParams};
@@ -69,6 +70,11 @@ where
 {
     /// Block number
     pub block_number: NumberFor<Block>,
+    /// Hash of the parent block
+    pub parent_hash: Block::Hash,
+    /// Whether block is going to become the new best block once imported (according to fork
+    /// choice rule)
+    pub is_new_best: bool,
     /// Sender for pausing the block import when operator is not fast enough to process
     /// the consensus block.
     pub acknowledgement_sender: mpsc::Sender<()>,
@@ -127,6 +133,9 @@ pub enum Error<Header: HeaderT> {
         /// Solution distance
         solution_distance: SolutionRange,
     },
+    /// Solution is structurally invalid
+    #[error("Invalid solution shape: {0}")]
+    InvalidSolutionShape(SolutionShapeError),
     /// Invalid proof of space
     #[error("Invalid proof of space")]
     InvalidProofOfSpace,
@@ -231,6 +240,9 @@ where
             }
             VerificationError::InvalidProofOfTime => Error::InvalidProofOfTime,
             VerificationError::VerificationError(slot, error) => match error {
+                VerificationPrimitiveError::InvalidSolutionShape(error) => {
+                    Error::InvalidSolutionShape(error)
+                }
                 VerificationPrimitiveError::InvalidPieceOffset {
                     piece_offset,
                     max_pieces_in_sector,
@@ -690,7 +702,7 @@ where
 
         // The fork choice rule is that we pick the heaviest chain (i.e. smallest solution range),
         // if there's a tie we go with the longest chain
-        let fork_choice = {
+        let is_new_best = {
             let info = self.client.info();
 
             let last_best_weight = if &info.best_hash == block.header.parent_hash() {
@@ -702,16 +714,19 @@ where
                     .ok_or_else(|| Error::NoBlockWeight(info.best_hash))?
             };
 
-            ForkChoiceStrategy::Custom(total_weight > last_best_weight)
+            total_weight > last_best_weight
         };
-        block.fork_choice = Some(fork_choice);
+        block.fork_choice = Some(ForkChoiceStrategy::Custom(is_new_best));
 
+        let parent_hash = *block.header.parent_hash();
         let (acknowledgement_sender, mut acknowledgement_receiver) = mpsc::channel(0);
 
         self.subspace_link
             .block_importing_notification_sender
             .notify(move || BlockImportingNotification {
                 block_number,
+                parent_hash,
+                is_new_best,
                 acknowledgement_sender,
             });
 
@@ -66,11 +66,11 @@ use std::collections::BTreeMap;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::pin::Pin;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use subspace_core_primitives::{
     BlockNumber, PotCheckpoints, PotOutput, PublicKey, RewardSignature, SectorId, Solution,
-    SolutionRange, REWARD_SIGNING_CONTEXT,
+    SolutionDistance, SolutionRange, REWARD_SIGNING_CONTEXT,
 };
 use subspace_proof_of_space::Table;
 use subspace_verification::{
@@ -86,6 +86,9 @@ const PENDING_SOLUTIONS_CHANNEL_CAPACITY: usize = 10;
 /// connected nodes (none of which will be synced initially). It also accounts for DSN sync, when
 /// normal Substrate sync is paused, which might happen before Substrate's internals decide there is
 /// a sync happening, but DSN sync is already in progress.
+///
+/// Block authoring can additionally be halted (for example when node detected that it is on a
+/// fork), in which case oracle reports major sync while Substrate sync keeps running.
 #[derive(Debug, Clone)]
 pub struct SubspaceSyncOracle<SO>
 where
@@ -93,6 +96,7 @@ where
 {
     force_authoring: bool,
     pause_sync: Arc<AtomicBool>,
+    halt_authoring: Arc<AtomicBool>,
     inner: SO,
 }
 
@@ -106,6 +110,7 @@ where
         // (default state), it also accounts for DSN sync
         (!self.force_authoring && self.inner.is_major_syncing())
             || self.pause_sync.load(Ordering::Acquire)
+            || self.halt_authoring.load(Ordering::Acquire)
     }
 
     fn is_offline(&self) -> bool {
@@ -121,11 +126,13 @@ where
     pub fn new(
         force_authoring: bool,
         pause_sync: Arc<AtomicBool>,
+        halt_authoring: Arc<AtomicBool>,
         substrate_sync_oracle: SO,
     ) -> Self {
         Self {
             force_authoring,
             pause_sync,
+            halt_authoring,
             inner: substrate_sync_oracle,
         }
     }
@@ -142,6 +149,10 @@ pub struct NewSlotInfo {
     pub solution_range: SolutionRange,
     /// Acceptable solution range for voting
     pub voting_solution_range: SolutionRange,
+    /// Number of blocks waiting in import queue when slot arrived
+    pub import_queue_depth: u32,
+    /// Number of slots between the slot of the best block and this slot
+    pub best_block_slot_lag: u64,
 }
 
 /// New slot notification with slot information and sender for solution for the slot.
@@ -206,6 +217,8 @@ where
     pub offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
     /// Proof of time verifier
     pub pot_verifier: PotVerifier,
+    /// Number of blocks waiting in import queue, kept up to date externally
+    pub import_queue_depth: Arc<AtomicU32>,
 }
 
 /// Subspace slot worker responsible for block and vote production
@@ -234,6 +247,7 @@ where
     /// Collection of PoT slots that can be retrieved later if needed by block production
     pot_checkpoints: BTreeMap<Slot, PotCheckpoints>,
     pot_verifier: PotVerifier,
+    import_queue_depth: Arc<AtomicU32>,
     _pos_table: PhantomData<PosTable>,
 }
 
@@ -290,11 +304,26 @@ where
                 }
             };
 
+        // Allows farmers to skip auditing when node falls behind and would build on top of a stale
+        // best block anyway
+        let best_block_slot_lag = self
+            .client
+            .header(best_hash)
+            .ok()
+            .flatten()
+            .and_then(|best_header| extract_pre_digest(&best_header).ok())
+            .map(|best_pre_digest| {
+                u64::from(slot).saturating_sub(u64::from(best_pre_digest.slot()))
+            })
+            .unwrap_or_default();
+
         let new_slot_info = NewSlotInfo {
             slot,
             proof_of_time,
             solution_range,
             voting_solution_range,
+            import_queue_depth: self.import_queue_depth.load(Ordering::Relaxed),
+            best_block_slot_lag,
         };
         let (solution_sender, solution_receiver) =
             mpsc::channel(PENDING_SOLUTIONS_CHANNEL_CAPACITY);
@@ -608,7 +637,7 @@ where
                 Ok(solution_distance) => {
                     // If solution is of high enough quality and block pre-digest wasn't produced yet,
                     // block reward is claimed
-                    if solution_distance <= solution_range / 2 {
+                    if SolutionDistance::from(solution_distance).is_within(solution_range) {
                         if maybe_pre_digest.is_none() {
                             info!(%slot, "🚜 Claimed block at slot");
                             maybe_pre_digest.replace(PreDigest::V0 {
@@ -807,6 +836,7 @@ where
             telemetry,
             offchain_tx_pool_factory,
             pot_verifier,
+            import_queue_depth,
         }: SubspaceSlotWorkerOptions<Block, Client, E, SO, L, BS, AS>,
     ) -> Self {
         Self {
@@ -827,6 +857,7 @@ where
             pending_solutions: Default::default(),
             pot_checkpoints: Default::default(),
             pot_verifier,
+            import_queue_depth,
             _pos_table: PhantomData::<PosTable>,
         }
     }
@@ -1,7 +1,11 @@
+pub mod external_timekeeper;
 pub mod gossip;
 mod state;
 mod timekeeper;
 
+use crate::source::external_timekeeper::{
+    run_external_timekeeper_client, ExternalTimekeeperConfig, ExternalTimekeeperProof,
+};
 use crate::source::gossip::{GossipProof, PotGossipWorker, ToGossipMessage};
 use crate::source::state::{PotState, PotStateUpdateOutcome};
 use crate::source::timekeeper::{run_timekeeper, TimekeeperProof};
@@ -25,12 +29,14 @@ use sp_consensus_subspace::{
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Zero};
 use std::collections::HashSet;
 use std::marker::PhantomData;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::thread;
 use subspace_core_primitives::PotCheckpoints;
 use tracing::{debug, error, trace, warn};
 
 const LOCAL_PROOFS_CHANNEL_CAPACITY: usize = 10;
+const EXTERNAL_PROOFS_CHANNEL_CAPACITY: usize = 10;
 const SLOTS_CHANNEL_CAPACITY: usize = 10;
 const GOSSIP_OUTGOING_CHANNEL_CAPACITY: usize = 10;
 const GOSSIP_INCOMING_CHANNEL_CAPACITY: usize = 10;
@@ -58,6 +64,7 @@ pub struct PotSourceWorker<Block, Client, SO> {
     sync_oracle: SO,
     chain_constants: ChainConstants,
     timekeeper_proofs_receiver: mpsc::Receiver<TimekeeperProof>,
+    external_timekeeper_proofs_receiver: mpsc::Receiver<ExternalTimekeeperProof>,
     to_gossip_sender: mpsc::Sender<ToGossipMessage>,
     from_gossip_receiver: mpsc::Receiver<(PeerId, GossipProof)>,
     last_slot_sent: Slot,
@@ -78,6 +85,7 @@ where
     pub fn new<Network, GossipSync>(
         is_timekeeper: bool,
         timekeeper_cpu_cores: HashSet<usize>,
+        external_timekeeper: Option<ExternalTimekeeperConfig>,
         client: Arc<Client>,
         pot_verifier: PotVerifier,
         network: Network,
@@ -132,8 +140,37 @@ where
 
         let (timekeeper_proofs_sender, timekeeper_proofs_receiver) =
             mpsc::channel(LOCAL_PROOFS_CHANNEL_CAPACITY);
+        let (external_timekeeper_proofs_sender, external_timekeeper_proofs_receiver) =
+            mpsc::channel(EXTERNAL_PROOFS_CHANNEL_CAPACITY);
         let (slot_sender, slot_receiver) = mpsc::channel(SLOTS_CHANNEL_CAPACITY);
-        if is_timekeeper {
+        // Local timekeeper acts as a fallback for external timekeeper and is paused for as long as
+        // external timekeeper works correctly, but explicitly requested timekeeper role always wins
+        let run_local_timekeeper = is_timekeeper || external_timekeeper.is_some();
+        let local_timekeeper_paused = Arc::new(AtomicBool::new(
+            !is_timekeeper && external_timekeeper.is_some(),
+        ));
+        if let Some(external_timekeeper) = external_timekeeper {
+            let pot_verifier = pot_verifier.clone();
+            let local_timekeeper_paused = if is_timekeeper {
+                // Not shared with local timekeeper, such that it never pauses
+                Arc::default()
+            } else {
+                Arc::clone(&local_timekeeper_paused)
+            };
+
+            thread::Builder::new()
+                .name("external-timekeeper".to_string())
+                .spawn(move || {
+                    run_external_timekeeper_client(
+                        external_timekeeper,
+                        pot_verifier,
+                        local_timekeeper_paused,
+                        external_timekeeper_proofs_sender,
+                    );
+                })
+                .expect("Thread creation must not panic");
+        }
+        if run_local_timekeeper {
             let state = Arc::clone(&state);
             let pot_verifier = pot_verifier.clone();
 
@@ -150,9 +187,12 @@ where
                         }
                     }
 
-                    if let Err(error) =
-                        run_timekeeper(state, pot_verifier, timekeeper_proofs_sender)
-                    {
+                    if let Err(error) = run_timekeeper(
+                        state,
+                        pot_verifier,
+                        local_timekeeper_paused,
+                        timekeeper_proofs_sender,
+                    ) {
                         error!(%error, "Timekeeper exited with an error");
                     }
                 })
@@ -179,6 +219,7 @@ where
             sync_oracle,
             chain_constants,
             timekeeper_proofs_receiver,
+            external_timekeeper_proofs_receiver,
             to_gossip_sender,
             from_gossip_receiver,
             last_slot_sent: Slot::from(0),
@@ -202,6 +243,9 @@ where
                 timekeeper_proof = self.timekeeper_proofs_receiver.select_next_some() => {
                     self.handle_timekeeper_proof(timekeeper_proof);
                 }
+                external_timekeeper_proof = self.external_timekeeper_proofs_receiver.select_next_some() => {
+                    self.handle_external_timekeeper_proof(external_timekeeper_proof);
+                }
                 // List of blocks that the client has finalized.
                 maybe_gossip_proof = self.from_gossip_receiver.next() => {
                     if let Some((sender, gossip_proof)) = maybe_gossip_proof {
@@ -283,9 +327,64 @@ where
         }
     }
 
+    fn handle_external_timekeeper_proof(&mut self, proof: ExternalTimekeeperProof) {
+        let ExternalTimekeeperProof {
+            slot,
+            seed,
+            slot_iterations,
+            checkpoints,
+        } = proof;
+
+        if self.sync_oracle.is_major_syncing() {
+            trace!(
+                ?slot,
+                %seed,
+                %slot_iterations,
+                output = %checkpoints.output(),
+                "Ignore external timekeeper proof due to major syncing",
+            );
+
+            return;
+        }
+
+        debug!(
+            ?slot,
+            %seed,
+            %slot_iterations,
+            output = %checkpoints.output(),
+            "Received external timekeeper proof",
+        );
+
+        let gossip_proof = GossipProof {
+            slot,
+            seed,
+            slot_iterations,
+            checkpoints,
+        };
+
+        // Checkpoints were already verified, so they are handled just like verified gossip and
+        // re-gossiped to the rest of the network
+        if self
+            .to_gossip_sender
+            .try_send(ToGossipMessage::Proof(gossip_proof))
+            .is_err()
+        {
+            debug!(
+                %slot,
+                "Gossip is not able to keep-up with slot production (external timekeeper)",
+            );
+        }
+
+        self.handle_verified_proof(gossip_proof);
+    }
+
     // TODO: Follow both verified and unverified checkpoints to start secondary timekeeper ASAP in
     //  case verification succeeds
     fn handle_gossip_proof(&mut self, _sender: PeerId, proof: GossipProof) {
+        self.handle_verified_proof(proof);
+    }
+
+    fn handle_verified_proof(&mut self, proof: GossipProof) {
         let expected_next_slot_input = PotNextSlotInput {
             slot: proof.slot,
             slot_iterations: proof.slot_iterations,
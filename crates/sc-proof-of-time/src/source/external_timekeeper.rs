@@ -0,0 +1,290 @@
+//! External timekeeper protocol and client.
+//!
+//! External timekeepers are dedicated machines (potentially operated by third parties) that
+//! compute proof of time and stream checkpoints to nodes that trust them, such that regular nodes
+//! don't need to dedicate a CPU core to proving.
+//!
+//! Protocol is intentionally simple and runs over plain TCP: every message is SCALE-encoded and
+//! prefixed with its length as little-endian `u32`. Client starts by sending
+//! [`ExternalTimekeeperHandshake`], after which server sends a stream of
+//! [`SignedExternalTimekeeperProof`] messages, one per slot. Proofs are signed with a schnorrkel
+//! key of the timekeeper using [`EXTERNAL_TIMEKEEPER_SIGNING_CONTEXT`], client only accepts proofs
+//! signed by explicitly trusted keys and still verifies checkpoints before using them.
+
+#[cfg(test)]
+mod tests;
+
+use crate::verifier::PotVerifier;
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::SinkExt;
+use parity_scale_codec::{Decode, Encode};
+use schnorrkel::context::SigningContext;
+use schnorrkel::{Keypair, Signature};
+use sp_consensus_slots::Slot;
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::{io, thread};
+use subspace_core_primitives::{PotCheckpoints, PotSeed, PublicKey, RewardSignature};
+use tracing::{debug, info, trace, warn};
+
+/// Signing context used by external timekeepers to sign proofs
+pub const EXTERNAL_TIMEKEEPER_SIGNING_CONTEXT: &[u8] = b"subspace_external_timekeeper";
+/// Version of the external timekeeper protocol
+pub const EXTERNAL_TIMEKEEPER_PROTOCOL_VERSION: u8 = 0;
+/// Max size of a single protocol message, anything larger is treated as protocol violation
+const MAX_MESSAGE_SIZE: u32 = 1024;
+/// Delay before reconnecting to the next external timekeeper after failure
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Handshake sent by client after connection is established
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct ExternalTimekeeperHandshake {
+    /// Protocol version, see [`EXTERNAL_TIMEKEEPER_PROTOCOL_VERSION`]
+    pub version: u8,
+    /// Genesis seed of the chain client is interested in
+    pub genesis_seed: PotSeed,
+}
+
+/// Proof of time for a slot produced by external timekeeper
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct ExternalTimekeeperProof {
+    /// Slot number
+    pub slot: Slot,
+    /// Proof of time seed
+    pub seed: PotSeed,
+    /// Iterations per slot
+    pub slot_iterations: NonZeroU32,
+    /// Proof of time checkpoints
+    pub checkpoints: PotCheckpoints,
+}
+
+/// [`ExternalTimekeeperProof`] signed by external timekeeper
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct SignedExternalTimekeeperProof {
+    /// Proof of time
+    pub proof: ExternalTimekeeperProof,
+    /// Public key of the timekeeper
+    pub public_key: PublicKey,
+    /// Signature of SCALE-encoded proof
+    pub signature: RewardSignature,
+}
+
+impl SignedExternalTimekeeperProof {
+    /// Sign proof with timekeeper's keypair
+    pub fn sign(proof: ExternalTimekeeperProof, keypair: &Keypair) -> Self {
+        let signature =
+            proof.using_encoded(|message| keypair.sign(signing_context().bytes(message)));
+
+        Self {
+            proof,
+            public_key: PublicKey::from(keypair.public.to_bytes()),
+            signature: RewardSignature::from(signature.to_bytes()),
+        }
+    }
+
+    /// Check that signature is valid for contained proof and public key
+    pub fn is_signature_valid(&self) -> bool {
+        let Ok(public_key) = schnorrkel::PublicKey::from_bytes(self.public_key.as_ref()) else {
+            return false;
+        };
+        let Ok(signature) = Signature::from_bytes(self.signature.as_ref()) else {
+            return false;
+        };
+
+        self.proof
+            .using_encoded(|message| {
+                public_key.verify(signing_context().bytes(message), &signature)
+            })
+            .is_ok()
+    }
+}
+
+fn signing_context() -> SigningContext {
+    schnorrkel::signing_context(EXTERNAL_TIMEKEEPER_SIGNING_CONTEXT)
+}
+
+/// Write length-prefixed SCALE-encoded message
+pub fn write_message<W, M>(writer: &mut W, message: &M) -> io::Result<()>
+where
+    W: Write,
+    M: Encode,
+{
+    let encoded = message.encode();
+    let length = u32::try_from(encoded.len())
+        .ok()
+        .filter(|&length| length <= MAX_MESSAGE_SIZE)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Message is too large"))?;
+
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(&encoded)?;
+    writer.flush()
+}
+
+/// Read length-prefixed SCALE-encoded message
+pub fn read_message<R, M>(reader: &mut R) -> io::Result<M>
+where
+    R: Read,
+    M: Decode,
+{
+    let mut length = [0; 4];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_le_bytes(length);
+    if length > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Message size {length} exceeds limit {MAX_MESSAGE_SIZE}"),
+        ));
+    }
+
+    let mut encoded = vec![0; length as usize];
+    reader.read_exact(&mut encoded)?;
+
+    M::decode(&mut encoded.as_slice())
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Configuration of external timekeeper client
+#[derive(Debug, Clone)]
+pub struct ExternalTimekeeperConfig {
+    /// Addresses (`host:port`) of external timekeepers, tried in order
+    pub addresses: Vec<String>,
+    /// Public keys of trusted external timekeepers, proofs signed by other keys are ignored
+    pub trusted_public_keys: HashSet<PublicKey>,
+    /// How long to wait for valid proofs from external timekeepers before falling back to local
+    /// proving
+    pub fallback_timeout: Duration,
+}
+
+/// Runs external timekeeper client, sends verified proofs to `proofs_sender`.
+///
+/// Local timekeeper is unpaused with `local_timekeeper_paused` whenever no valid proofs were
+/// received for [`ExternalTimekeeperConfig::fallback_timeout`] and paused again once external
+/// timekeepers are back.
+pub(super) fn run_external_timekeeper_client(
+    config: ExternalTimekeeperConfig,
+    pot_verifier: PotVerifier,
+    local_timekeeper_paused: Arc<AtomicBool>,
+    mut proofs_sender: mpsc::Sender<ExternalTimekeeperProof>,
+) {
+    let ExternalTimekeeperConfig {
+        addresses,
+        trusted_public_keys,
+        fallback_timeout,
+    } = config;
+
+    let mut last_valid_proof = Instant::now();
+    let mut check_fallback = |received_valid_proof: bool| {
+        if received_valid_proof {
+            last_valid_proof = Instant::now();
+            if !local_timekeeper_paused.swap(true, Ordering::AcqRel) {
+                info!("External timekeeper is available, pausing local timekeeper");
+            }
+        } else if last_valid_proof.elapsed() >= fallback_timeout
+            && local_timekeeper_paused.swap(false, Ordering::AcqRel)
+        {
+            warn!(
+                ?fallback_timeout,
+                "No valid proofs from external timekeepers, falling back to local timekeeper"
+            );
+        }
+    };
+
+    for address in addresses.iter().cycle() {
+        let mut stream = match connect(address, &pot_verifier, fallback_timeout) {
+            Ok(stream) => stream,
+            Err(error) => {
+                debug!(%error, %address, "Failed to connect to external timekeeper");
+                check_fallback(false);
+                thread::sleep(RECONNECT_DELAY);
+                continue;
+            }
+        };
+
+        debug!(%address, "Connected to external timekeeper");
+
+        loop {
+            let signed_proof = match read_message::<_, SignedExternalTimekeeperProof>(&mut stream) {
+                Ok(signed_proof) => signed_proof,
+                Err(error) => {
+                    debug!(%error, %address, "External timekeeper connection failed");
+                    break;
+                }
+            };
+
+            if !trusted_public_keys.contains(&signed_proof.public_key) {
+                debug!(
+                    %address,
+                    public_key = %signed_proof.public_key,
+                    "Proof from untrusted external timekeeper, disconnecting"
+                );
+                break;
+            }
+            if !signed_proof.is_signature_valid() {
+                warn!(%address, "Invalid external timekeeper proof signature, disconnecting");
+                break;
+            }
+
+            let proof = signed_proof.proof;
+            if !pot_verifier.verify_checkpoints(
+                proof.seed,
+                proof.slot_iterations,
+                &proof.checkpoints,
+            ) {
+                warn!(
+                    %address,
+                    slot = %proof.slot,
+                    "Invalid external timekeeper checkpoints, disconnecting"
+                );
+                break;
+            }
+
+            trace!(%address, slot = %proof.slot, "Received external timekeeper proof");
+            check_fallback(true);
+
+            if let Err(error) = proofs_sender.try_send(proof) {
+                if let Err(error) = block_on(proofs_sender.send(error.into_inner())) {
+                    debug!(%error, "Couldn't send checkpoints, channel is closed");
+                    return;
+                }
+            }
+        }
+
+        check_fallback(false);
+        thread::sleep(RECONNECT_DELAY);
+    }
+
+    // No addresses, nothing to do except relying on local timekeeper
+    local_timekeeper_paused.store(false, Ordering::Release);
+}
+
+fn connect(
+    address: &str,
+    pot_verifier: &PotVerifier,
+    read_timeout: Duration,
+) -> io::Result<TcpStream> {
+    let socket_address = address.to_socket_addrs()?.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "Address didn't resolve to anything",
+        )
+    })?;
+    let mut stream = TcpStream::connect_timeout(&socket_address, read_timeout)?;
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(read_timeout))?;
+
+    write_message(
+        &mut stream,
+        &ExternalTimekeeperHandshake {
+            version: EXTERNAL_TIMEKEEPER_PROTOCOL_VERSION,
+            genesis_seed: pot_verifier.genesis_seed(),
+        },
+    )?;
+
+    Ok(stream)
+}
@@ -0,0 +1,136 @@
+use crate::source::external_timekeeper::{
+    read_message, run_external_timekeeper_client, write_message, ExternalTimekeeperConfig,
+    ExternalTimekeeperHandshake, ExternalTimekeeperProof, SignedExternalTimekeeperProof,
+    EXTERNAL_TIMEKEEPER_PROTOCOL_VERSION,
+};
+use crate::verifier::PotVerifier;
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::StreamExt;
+use schnorrkel::{ExpansionMode, Keypair, MiniSecretKey};
+use sp_consensus_slots::Slot;
+use std::net::TcpListener;
+use std::num::{NonZeroU32, NonZeroUsize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use std::{io, thread};
+use subspace_core_primitives::{PotSeed, PublicKey};
+
+const SEED: [u8; 16] = [
+    0xd6, 0x66, 0xcc, 0xd8, 0xd5, 0x93, 0xc2, 0x3d, 0xa8, 0xdb, 0x6b, 0x5b, 0x14, 0x13, 0xb1, 0x3a,
+];
+
+fn keypair(seed: u8) -> Keypair {
+    MiniSecretKey::from_bytes(&[seed; 32])
+        .unwrap()
+        .expand_to_keypair(ExpansionMode::Ed25519)
+}
+
+fn proof(slot: u64) -> ExternalTimekeeperProof {
+    let seed = PotSeed::from(SEED);
+    let slot_iterations = NonZeroU32::new(512).unwrap();
+
+    ExternalTimekeeperProof {
+        slot: Slot::from(slot),
+        seed,
+        slot_iterations,
+        checkpoints: subspace_proof_of_time::prove(seed, slot_iterations).unwrap(),
+    }
+}
+
+#[test]
+fn signature_verification() {
+    let keypair = keypair(1);
+    let signed_proof = SignedExternalTimekeeperProof::sign(proof(1), &keypair);
+    assert!(signed_proof.is_signature_valid());
+
+    // Different proof
+    let mut tampered = signed_proof;
+    tampered.proof.slot = Slot::from(2);
+    assert!(!tampered.is_signature_valid());
+
+    // Different public key
+    let mut tampered = signed_proof;
+    tampered.public_key = PublicKey::from(self::keypair(2).public.to_bytes());
+    assert!(!tampered.is_signature_valid());
+}
+
+#[test]
+fn message_framing() {
+    let signed_proof = SignedExternalTimekeeperProof::sign(proof(1), &keypair(1));
+
+    let mut buffer = Vec::new();
+    write_message(&mut buffer, &signed_proof).unwrap();
+    let decoded = read_message::<_, SignedExternalTimekeeperProof>(&mut buffer.as_slice()).unwrap();
+    assert_eq!(decoded, signed_proof);
+
+    // Truncated message
+    assert!(
+        read_message::<_, SignedExternalTimekeeperProof>(&mut &buffer[..buffer.len() - 1]).is_err()
+    );
+
+    // Oversized message
+    let error =
+        read_message::<_, SignedExternalTimekeeperProof>(&mut u32::MAX.to_le_bytes().as_slice())
+            .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert!(write_message(&mut Vec::new(), &vec![0u8; 2048]).is_err());
+}
+
+#[test]
+fn client_receives_trusted_proofs() {
+    let keypair = keypair(1);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let (next_proof_sender, next_proof_receiver) = std::sync::mpsc::channel::<()>();
+
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let handshake = read_message::<_, ExternalTimekeeperHandshake>(&mut stream).unwrap();
+        assert_eq!(handshake.version, EXTERNAL_TIMEKEEPER_PROTOCOL_VERSION);
+        assert_eq!(handshake.genesis_seed, PotSeed::from(SEED));
+
+        for slot in 1.. {
+            write_message(
+                &mut stream,
+                &SignedExternalTimekeeperProof::sign(proof(slot), &keypair),
+            )
+            .unwrap();
+            if next_proof_receiver.recv().is_err() {
+                break;
+            }
+        }
+    });
+
+    let pot_verifier = PotVerifier::new(PotSeed::from(SEED), NonZeroUsize::new(10).unwrap());
+    let local_timekeeper_paused = Arc::new(AtomicBool::new(false));
+    let (proofs_sender, mut proofs_receiver) = mpsc::channel(1);
+    let client = thread::spawn({
+        let local_timekeeper_paused = Arc::clone(&local_timekeeper_paused);
+
+        move || {
+            run_external_timekeeper_client(
+                ExternalTimekeeperConfig {
+                    addresses: vec![address],
+                    trusted_public_keys: [PublicKey::from(self::keypair(1).public.to_bytes())]
+                        .into_iter()
+                        .collect(),
+                    fallback_timeout: Duration::from_secs(10),
+                },
+                pot_verifier,
+                local_timekeeper_paused,
+                proofs_sender,
+            )
+        }
+    });
+
+    let received_proof = block_on(proofs_receiver.next()).unwrap();
+    assert_eq!(received_proof, proof(1));
+    assert!(local_timekeeper_paused.load(Ordering::Acquire));
+
+    // Client exits once proofs are no longer consumed
+    drop(proofs_receiver);
+    next_proof_sender.send(()).unwrap();
+    client.join().unwrap();
+}
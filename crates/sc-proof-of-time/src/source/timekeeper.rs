@@ -5,12 +5,17 @@ use futures::executor::block_on;
 use futures::SinkExt;
 use sp_consensus_slots::Slot;
 use std::num::NonZeroU32;
-use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 use subspace_core_primitives::{PotCheckpoints, PotSeed};
 use subspace_proof_of_time::PotError;
 use tracing::debug;
 
+/// How often paused timekeeper checks whether it should resume
+const PAUSED_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Proof of time slot information
 pub(super) struct TimekeeperProof {
     /// Slot number
@@ -23,15 +28,29 @@ pub(super) struct TimekeeperProof {
     pub(super) checkpoints: PotCheckpoints,
 }
 
-/// Runs timekeeper, must be running on a fast dedicated CPU core
+/// Runs timekeeper, must be running on a fast dedicated CPU core.
+///
+/// Proving is suspended while `paused` is set (for instance when external timekeeper is used), in
+/// which case timekeeper resumes from the latest known state once unpaused.
 pub(super) fn run_timekeeper(
     state: Arc<PotState>,
     pot_verifier: PotVerifier,
+    paused: Arc<AtomicBool>,
     mut proofs_sender: mpsc::Sender<TimekeeperProof>,
 ) -> Result<(), PotError> {
     let mut next_slot_input = state.next_slot_input(Ordering::Acquire);
 
     loop {
+        if paused.load(Ordering::Acquire) {
+            if proofs_sender.is_closed() {
+                return Ok(());
+            }
+
+            thread::sleep(PAUSED_CHECK_INTERVAL);
+            next_slot_input = state.next_slot_input(Ordering::Acquire);
+            continue;
+        }
+
         let checkpoints =
             subspace_proof_of_time::prove(next_slot_input.seed, next_slot_input.slot_iterations)?;
 
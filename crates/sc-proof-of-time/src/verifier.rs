@@ -22,11 +22,21 @@ struct CacheValue {
     checkpoints: Arc<Mutex<Option<PotCheckpoints>>>,
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+struct InvalidCacheKey {
+    seed: PotSeed,
+    slot_iterations: NonZeroU32,
+    checkpoints: PotCheckpoints,
+}
+
 /// Verifier data structure that verifies and caches results of PoT verification
 #[derive(Debug, Clone)]
 pub struct PotVerifier {
     genesis_seed: PotSeed,
     cache: Arc<Mutex<LruCache<CacheKey, CacheValue>>>,
+    /// Checkpoints that failed verification, such that the same invalid checkpoints received
+    /// repeatedly (from gossip, blocks or external timekeepers) are not verified over and over
+    invalid_cache: Arc<Mutex<LruCache<InvalidCacheKey, ()>>>,
 }
 
 impl PotVerifier {
@@ -34,6 +44,7 @@ impl PotVerifier {
         Self {
             genesis_seed,
             cache: Arc::new(Mutex::new(LruCache::new(cache_size))),
+            invalid_cache: Arc::new(Mutex::new(LruCache::new(cache_size))),
         }
     }
 
@@ -224,7 +235,10 @@ impl PotVerifier {
         }
     }
 
-    /// Verify proof of time checkpoints
+    /// Verify proof of time checkpoints.
+    ///
+    /// Both successful and failed verification results are cached, only checkpoints that were not
+    /// seen before are fully verified.
     pub fn verify_checkpoints(
         &self,
         seed: PotSeed,
@@ -258,6 +272,16 @@ impl PotVerifier {
                 continue;
             }
 
+            let invalid_cache_key = InvalidCacheKey {
+                seed,
+                slot_iterations,
+                checkpoints: *checkpoints,
+            };
+            if self.invalid_cache.lock().get(&invalid_cache_key).is_some() {
+                // These exact checkpoints were already verified and found to be invalid
+                return false;
+            }
+
             let cache_value = CacheValue {
                 checkpoints: Arc::default(),
             };
@@ -289,6 +313,7 @@ impl PotVerifier {
                         self.cache.lock().push(cache_key, removed_cache_value);
                     }
                 }
+                self.invalid_cache.lock().push(invalid_cache_key, ());
                 return false;
             }
 
@@ -124,6 +124,25 @@ fn test_basic() {
     ));
 }
 
+#[test]
+fn invalid_checkpoints_cached() {
+    let genesis_seed = PotSeed::from(SEED);
+    let slot_iterations = NonZeroU32::new(512).unwrap();
+    let checkpoints = subspace_proof_of_time::prove(genesis_seed, slot_iterations).unwrap();
+    let mut invalid_checkpoints = checkpoints;
+    invalid_checkpoints[3] = checkpoints[4];
+
+    let verifier = PotVerifier::new(genesis_seed, NonZeroUsize::new(1000).unwrap());
+
+    // Invalid checkpoints are rejected both when verified for the first time and when cached
+    assert!(!verifier.verify_checkpoints(genesis_seed, slot_iterations, &invalid_checkpoints));
+    assert!(!verifier.verify_checkpoints(genesis_seed, slot_iterations, &invalid_checkpoints));
+    // Cached invalid checkpoints do not prevent correct checkpoints from being verified
+    assert!(verifier.verify_checkpoints(genesis_seed, slot_iterations, &checkpoints));
+    assert!(verifier.verify_checkpoints(genesis_seed, slot_iterations, &checkpoints));
+    assert!(!verifier.verify_checkpoints(genesis_seed, slot_iterations, &invalid_checkpoints));
+}
+
 #[test]
 fn parameters_change() {
     let genesis_seed = PotSeed::from(SEED);
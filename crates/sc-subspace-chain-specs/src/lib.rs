@@ -16,6 +16,8 @@
 
 //! Consensus chain specifications for Subspace.
 
+pub mod test_net;
+
 /// Devnet chain spec
 pub const DEVNET_CHAIN_SPEC: &str = include_str!("../res/chain-spec-raw-devnet.json");
 /// Gemini 3h chain spec
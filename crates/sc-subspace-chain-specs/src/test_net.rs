@@ -0,0 +1,210 @@
+//! Generator of throwaway test networks for CI and local devnets.
+//!
+//! Generated chain spec pre-funds a configurable number of farmers whose accounts are derived
+//! deterministically from their index, uses reduced proof-of-time and confirmation depth
+//! parameters, and comes together with the genesis segment archived ahead of time, so farmers can
+//! start plotting before the node has even started.
+
+use sc_chain_spec::{ChainType, GenericChainSpec, NoExtension, Properties};
+use sc_consensus_subspace::archiver::encode_block;
+use sp_core::crypto::AccountId32;
+use sp_core::{sr25519, Get, Pair};
+use sp_domains::storage::RawGenesis;
+use sp_runtime::generic::SignedBlock;
+use sp_runtime::traits::{BlakeTwo256, Block as BlockT, Hash as HashT, Header as HeaderT};
+use sp_runtime::BuildStorage;
+use std::marker::PhantomData;
+use std::num::NonZeroU32;
+use subspace_archiving::archiver::{Archiver, NewArchivedSegment};
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::objects::BlockObjectMapping;
+use subspace_core_primitives::PotKey;
+use subspace_runtime::{
+    AllowAuthoringBy, BalancesConfig, DomainsConfig, EnableRewardsAt, RuntimeConfigsConfig,
+    RuntimeGenesisConfig, SS58Prefix, SubspaceConfig, SudoConfig, SystemConfig, VestingConfig,
+    VERSION, WASM_BINARY,
+};
+use subspace_runtime_primitives::opaque::{Block, Header};
+use subspace_runtime_primitives::{AccountId, Balance, DECIMAL_PLACES, SSC};
+
+/// Seed of the sudo account of test networks
+const SUDO_SEED: &str = "//TestNetSudo";
+
+/// Test network parameters.
+#[derive(Debug, Clone)]
+pub struct TestNetConfig {
+    /// Number of pre-funded test farmers, see [`test_farmer_account_id`]
+    pub farmers: u32,
+    /// Initial balance of each test farmer
+    pub farmer_balance: Balance,
+    /// Number of proof-of-time iterations per slot, must be a multiple of twice the number of
+    /// checkpoints
+    pub pot_slot_iterations: NonZeroU32,
+    /// Depth after which blocks are archived
+    pub confirmation_depth_k: u32,
+}
+
+impl TestNetConfig {
+    /// Config with `farmers` test farmers and parameters reduced for fast block production and
+    /// archiving
+    pub fn new(farmers: u32) -> Self {
+        Self {
+            farmers,
+            farmer_balance: 1_000 * SSC,
+            // Orders of magnitude faster than on public networks, any CPU can keep up with it
+            pot_slot_iterations: NonZeroU32::new(1_000_000).expect("Not zero; qed"),
+            confirmation_depth_k: 2,
+        }
+    }
+}
+
+/// Generated test network.
+pub struct TestNet {
+    /// Chain spec of the test network
+    pub chain_spec: GenericChainSpec<RuntimeGenesisConfig>,
+    /// First archived segment, produced from the genesis block of the chain spec
+    pub genesis_segment: NewArchivedSegment,
+}
+
+/// Account of the test farmer with specified index, derived from `//TestFarmer//{index}` seed.
+///
+/// Farmers are expected to use it as reward address.
+pub fn test_farmer_account_id(index: u32) -> AccountId {
+    AccountId32::from(
+        sr25519::Pair::from_string(&format!("//TestFarmer//{index}"), None)
+            .expect("Static seed format is valid; qed")
+            .public(),
+    )
+}
+
+/// Generate test network according to provided config.
+pub fn test_net(config: TestNetConfig, kzg: Kzg) -> Result<TestNet, String> {
+    let chain_spec = test_net_chain_spec(config)?;
+    let genesis_segment = archive_genesis_segment(&chain_spec, kzg)?;
+
+    Ok(TestNet {
+        chain_spec,
+        genesis_segment,
+    })
+}
+
+/// Chain spec of the test network, see [`test_net`] for generating it along with genesis segment.
+pub fn test_net_chain_spec(
+    config: TestNetConfig,
+) -> Result<GenericChainSpec<RuntimeGenesisConfig>, String> {
+    let wasm_binary = WASM_BINARY.ok_or_else(|| "Wasm binary not available".to_string())?;
+
+    // TODO: Migrate once https://github.com/paritytech/polkadot-sdk/issues/2963 is un-broken
+    #[allow(deprecated)]
+    Ok(GenericChainSpec::from_genesis(
+        // Name
+        "Subspace test network",
+        // ID
+        "subspace_test_net",
+        ChainType::Local,
+        move || test_net_genesis_config(&config),
+        // Bootnodes
+        vec![],
+        // Telemetry
+        None,
+        // Protocol ID
+        Some("subspace-test-net"),
+        None,
+        // Properties
+        Some(test_net_properties()),
+        // Extensions
+        NoExtension::None,
+        // Code
+        wasm_binary,
+    ))
+}
+
+fn test_net_genesis_config(config: &TestNetConfig) -> RuntimeGenesisConfig {
+    let sudo_account = AccountId32::from(
+        sr25519::Pair::from_string(SUDO_SEED, None)
+            .expect("Static seed format is valid; qed")
+            .public(),
+    );
+
+    let balances = [(sudo_account.clone(), Balance::MAX / 2)]
+        .into_iter()
+        .chain(
+            (0..config.farmers).map(|index| (test_farmer_account_id(index), config.farmer_balance)),
+        )
+        .collect();
+
+    RuntimeGenesisConfig {
+        system: SystemConfig::default(),
+        balances: BalancesConfig { balances },
+        transaction_payment: Default::default(),
+        sudo: SudoConfig {
+            key: Some(sudo_account),
+        },
+        subspace: SubspaceConfig {
+            enable_rewards_at: EnableRewardsAt::Height(None),
+            allow_authoring_by: AllowAuthoringBy::Anyone,
+            pot_slot_iterations: config.pot_slot_iterations,
+            phantom: PhantomData,
+        },
+        vesting: VestingConfig { vesting: vec![] },
+        runtime_configs: RuntimeConfigsConfig {
+            enable_domains: false,
+            enable_dynamic_cost_of_storage: false,
+            enable_balance_transfers: true,
+            enable_non_root_calls: true,
+            confirmation_depth_k: config.confirmation_depth_k,
+        },
+        domains: DomainsConfig {
+            genesis_domain: None,
+        },
+    }
+}
+
+fn test_net_properties() -> Properties {
+    let mut properties = Properties::new();
+
+    properties.insert("ss58Format".to_string(), SS58Prefix::get().into());
+    properties.insert("tokenDecimals".to_string(), DECIMAL_PLACES.into());
+    properties.insert("tokenSymbol".to_string(), "tSSC".into());
+    properties.insert(
+        "potExternalEntropy".to_string(),
+        serde_json::to_value(None::<PotKey>).expect("Serialization is infallible; qed"),
+    );
+
+    properties
+}
+
+/// Archive genesis block of the chain spec the same way archiver of the node would do it.
+pub fn archive_genesis_segment(
+    chain_spec: &dyn BuildStorage,
+    kzg: Kzg,
+) -> Result<NewArchivedSegment, String> {
+    let storage = chain_spec.build_storage()?;
+    let state_version = VERSION.state_version();
+
+    let state_root = RawGenesis::from_storage(storage).state_root::<BlakeTwo256>(state_version);
+    let extrinsics_root = BlakeTwo256::trie_root(Vec::new(), state_version);
+    let genesis_block = Block::new(
+        Header::new(
+            0,
+            extrinsics_root,
+            state_root,
+            Default::default(),
+            Default::default(),
+        ),
+        Vec::new(),
+    );
+
+    let encoded_block = encode_block(SignedBlock {
+        block: genesis_block,
+        justifications: None,
+    });
+
+    Ok(Archiver::new(kzg)
+        .map_err(|error| error.to_string())?
+        .add_block(encoded_block, BlockObjectMapping::default(), false)
+        .map_err(|error| error.to_string())?
+        .into_iter()
+        .next()
+        .expect("Genesis block always results in exactly one archived segment; qed"))
+}
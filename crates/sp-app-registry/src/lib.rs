@@ -0,0 +1,66 @@
+// Copyright (C) 2023 Subspace Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Primitives for registered applications with data upload quotas.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![forbid(unsafe_code)]
+#![warn(rust_2018_idioms, missing_debug_implementations, missing_docs)]
+
+use codec::{Codec, Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::Perbill;
+
+/// Data upload quota and pricing of a registered application.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct AppQuota {
+    /// Maximum number of bytes of data-carrying extrinsics application can include in a single
+    /// block.
+    pub bytes_per_block: u32,
+    /// Maximum number of bytes of data-carrying extrinsics application can include within a day.
+    pub bytes_per_day: u64,
+    /// Portion of the transaction fee (after other rebates) that is discounted for data-carrying
+    /// extrinsics of the application.
+    pub fee_discount: Perbill,
+}
+
+/// Remaining data upload quota of a registered application.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct RemainingQuota {
+    /// Number of bytes that can still be included in the current block.
+    pub block_bytes: u32,
+    /// Number of bytes that can still be included within the current day.
+    pub day_bytes: u64,
+}
+
+impl RemainingQuota {
+    /// Whether data-carrying extrinsic of `size` bytes fits into remaining quota.
+    #[inline]
+    pub fn fits(&self, size: u32) -> bool {
+        size <= self.block_bytes && u64::from(size) <= self.day_bytes
+    }
+}
+
+sp_api::decl_runtime_apis! {
+    /// API for querying data upload quotas of registered applications.
+    pub trait AppRegistryApi<AccountId: Codec> {
+        /// Quota of registered application, `None` if account is not registered.
+        fn app_quota(account: AccountId) -> Option<AppQuota>;
+
+        /// Remaining quota of registered application for extrinsics included in the next block,
+        /// `None` if account is not registered.
+        fn remaining_quota(account: AccountId) -> Option<RemainingQuota>;
+    }
+}
@@ -0,0 +1,164 @@
+//! Proofs of archival finality.
+//!
+//! Blocks in Subspace are final once they are archived, since archived history is what farmers
+//! store and what all future blocks are built on. Proof of archival finality allows external
+//! verifiers (like bridges) that track segment headers to confirm finality of a particular block
+//! without following every header of the chain.
+//!
+//! Proof consists of two parts:
+//! * chain of segment headers starting right after the segment header trusted by verifier and
+//!   ending with the segment header of the segment that archived the block
+//! * chain of block headers starting with the block being proven and ending with the block that
+//!   committed to the last segment header in its digest
+//!
+//! NOTE: block headers in the proof are not checked for consensus validity, verifier relies on
+//! the fact that segment commitment in the digest of the last header is only included into the
+//! canonical chain that was archived.
+
+use crate::digests::CompatibleDigestItem;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::traits::Header as HeaderT;
+use sp_std::vec::Vec;
+use subspace_core_primitives::{Blake3Hash, BlockNumber, SegmentHeader, SegmentIndex};
+
+/// Error happening during verification of [`ArchivalFinalityProof`]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+pub enum ArchivalFinalityProofError {
+    /// Proof doesn't contain any segment headers
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Proof doesn't contain any segment headers")
+    )]
+    NoSegmentHeaders,
+    /// Proof doesn't contain any block headers
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Proof doesn't contain any block headers")
+    )]
+    NoBlockHeaders,
+    /// First segment header doesn't follow the trusted segment header
+    #[cfg_attr(
+        feature = "thiserror",
+        error("First segment header doesn't follow the trusted segment header")
+    )]
+    UntrustedSegmentHeader,
+    /// Segment header doesn't follow the previous segment header
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Segment header {0} doesn't follow the previous segment header")
+    )]
+    SegmentHeadersNotChained(SegmentIndex),
+    /// Block header is not a child of the previous block header
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Block header at position {0} is not a child of the previous block header")
+    )]
+    BlockHeadersNotChained(usize),
+    /// Block was not fully archived by the last segment
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Block {0} was not fully archived by the last segment")
+    )]
+    BlockNotArchived(BlockNumber),
+    /// Last block header doesn't commit to the last segment header
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Last block header doesn't commit to the last segment header")
+    )]
+    SegmentCommitmentNotFound,
+}
+
+/// Proof that block is final due to being archived, see module documentation for details
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct ArchivalFinalityProof<Header> {
+    /// Segment headers, each following the previous one, the last one archived the block
+    pub segment_headers: Vec<SegmentHeader>,
+    /// Block headers, each being a child of the previous one, starting with the block being
+    /// proven and ending with the block that committed to the last segment header
+    pub block_headers: Vec<Header>,
+}
+
+impl<Header> ArchivalFinalityProof<Header>
+where
+    Header: HeaderT,
+    BlockNumber: From<Header::Number>,
+{
+    /// Verify proof against hash of the segment header trusted by verifier (`None` means that
+    /// segment headers must start from the genesis segment), returns header of the finalized block
+    /// and header of the last segment on success.
+    ///
+    /// Returned segment header can be used as trusted segment header for subsequent proofs.
+    pub fn verify(
+        &self,
+        trusted_segment_header_hash: Option<Blake3Hash>,
+    ) -> Result<(&Header, &SegmentHeader), ArchivalFinalityProofError> {
+        let first_segment_header = self
+            .segment_headers
+            .first()
+            .ok_or(ArchivalFinalityProofError::NoSegmentHeaders)?;
+        let follows_trusted = match trusted_segment_header_hash {
+            Some(trusted_segment_header_hash) => {
+                first_segment_header.prev_segment_header_hash() == trusted_segment_header_hash
+            }
+            None => first_segment_header.segment_index() == SegmentIndex::ZERO,
+        };
+        if !follows_trusted {
+            return Err(ArchivalFinalityProofError::UntrustedSegmentHeader);
+        }
+        for [previous, segment_header] in self.segment_headers.array_windows::<2>() {
+            if segment_header.segment_index() != previous.segment_index() + SegmentIndex::ONE
+                || segment_header.prev_segment_header_hash() != previous.hash()
+            {
+                return Err(ArchivalFinalityProofError::SegmentHeadersNotChained(
+                    segment_header.segment_index(),
+                ));
+            }
+        }
+        let last_segment_header = self
+            .segment_headers
+            .last()
+            .expect("Not empty, checked above; qed");
+
+        let block_header = self
+            .block_headers
+            .first()
+            .ok_or(ArchivalFinalityProofError::NoBlockHeaders)?;
+        for (position, [parent, header]) in self.block_headers.array_windows::<2>().enumerate() {
+            if *header.parent_hash() != parent.hash() {
+                return Err(ArchivalFinalityProofError::BlockHeadersNotChained(
+                    position + 1,
+                ));
+            }
+        }
+
+        let block_number = BlockNumber::from(*block_header.number());
+        let last_archived_block = last_segment_header.last_archived_block();
+        let fully_archived = block_number < last_archived_block.number
+            || (block_number == last_archived_block.number
+                && last_archived_block.partial_archived().is_none());
+        if !fully_archived {
+            return Err(ArchivalFinalityProofError::BlockNotArchived(block_number));
+        }
+
+        let expected_segment_commitment = (
+            last_segment_header.segment_index(),
+            last_segment_header.segment_commitment(),
+        );
+        let commits_to_segment = self
+            .block_headers
+            .last()
+            .expect("Not empty, checked above; qed")
+            .digest()
+            .logs()
+            .iter()
+            .filter_map(|log| log.as_segment_commitment())
+            .any(|segment_commitment| segment_commitment == expected_segment_commitment);
+        if !commits_to_segment {
+            return Err(ArchivalFinalityProofError::SegmentCommitmentNotFound);
+        }
+
+        Ok((block_header, last_segment_header))
+    }
+}
@@ -17,7 +17,8 @@
 //! Private implementation details of Subspace consensus digests.
 
 use crate::{
-    ConsensusLog, FarmerPublicKey, FarmerSignature, PotParametersChange, SUBSPACE_ENGINE_ID,
+    ConsensusLog, FarmerPublicKey, FarmerSignature, PotParametersChange, SolutionRangeAdjustment,
+    SUBSPACE_ENGINE_ID, SUBSPACE_REWARDS_ENGINE_ID,
 };
 use codec::{Decode, Encode};
 use log::trace;
@@ -31,6 +32,7 @@ use sp_std::num::NonZeroU32;
 use subspace_core_primitives::{
     PotOutput, SegmentCommitment, SegmentIndex, Solution, SolutionRange,
 };
+use subspace_verification::RewardsStatement;
 
 /// A Subspace pre-runtime digest. This contains all data required to validate a block and for the
 /// Subspace runtime module.
@@ -170,6 +172,22 @@ pub trait CompatibleDigestItem: Sized {
 
     /// If this item is a Subspace update of root plot public key, return it.
     fn as_root_plot_public_key_update(&self) -> Option<Option<FarmerPublicKey>>;
+
+    /// Construct digest item that indicates change of solution range adjustment algorithm.
+    fn solution_range_adjustment(solution_range_adjustment: SolutionRangeAdjustment) -> Self;
+
+    /// If this item is a Subspace solution range adjustment algorithm change, return it.
+    fn as_solution_range_adjustment(&self) -> Option<SolutionRangeAdjustment>;
+
+    /// Construct digest item that contains statement of rewards issued in the block.
+    fn rewards_statement<RewardAddress: Encode, Balance: Encode>(
+        rewards_statement: &RewardsStatement<RewardAddress, Balance>,
+    ) -> Self;
+
+    /// If this item is a Subspace rewards statement, return it.
+    fn as_rewards_statement<RewardAddress: Decode, Balance: Decode>(
+        &self,
+    ) -> Option<RewardsStatement<RewardAddress, Balance>>;
 }
 
 impl CompatibleDigestItem for DigestItem {
@@ -320,6 +338,35 @@ impl CompatibleDigestItem for DigestItem {
             }
         })
     }
+
+    fn solution_range_adjustment(solution_range_adjustment: SolutionRangeAdjustment) -> Self {
+        Self::Consensus(
+            SUBSPACE_ENGINE_ID,
+            ConsensusLog::SolutionRangeAdjustment(solution_range_adjustment).encode(),
+        )
+    }
+
+    fn as_solution_range_adjustment(&self) -> Option<SolutionRangeAdjustment> {
+        self.consensus_try_to(&SUBSPACE_ENGINE_ID).and_then(|c| {
+            if let ConsensusLog::SolutionRangeAdjustment(solution_range_adjustment) = c {
+                Some(solution_range_adjustment)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn rewards_statement<RewardAddress: Encode, Balance: Encode>(
+        rewards_statement: &RewardsStatement<RewardAddress, Balance>,
+    ) -> Self {
+        Self::Consensus(SUBSPACE_REWARDS_ENGINE_ID, rewards_statement.encode())
+    }
+
+    fn as_rewards_statement<RewardAddress: Decode, Balance: Decode>(
+        &self,
+    ) -> Option<RewardsStatement<RewardAddress, Balance>> {
+        self.consensus_try_to(&SUBSPACE_REWARDS_ENGINE_ID)
+    }
 }
 
 /// Various kinds of digest types used in errors
@@ -345,6 +392,10 @@ pub enum ErrorDigestType {
     EnableSolutionRangeAdjustmentAndOverride,
     /// Root plot public key was updated
     RootPlotPublicKeyUpdate,
+    /// Solution range adjustment algorithm was changed
+    SolutionRangeAdjustment,
+    /// Rewards statement
+    RewardsStatement,
 }
 
 impl fmt::Display for ErrorDigestType {
@@ -380,6 +431,12 @@ impl fmt::Display for ErrorDigestType {
             ErrorDigestType::RootPlotPublicKeyUpdate => {
                 write!(f, "RootPlotPublicKeyUpdate")
             }
+            ErrorDigestType::SolutionRangeAdjustment => {
+                write!(f, "SolutionRangeAdjustment")
+            }
+            ErrorDigestType::RewardsStatement => {
+                write!(f, "RewardsStatement")
+            }
         }
     }
 }
@@ -449,6 +506,8 @@ pub struct SubspaceDigestItems<PublicKey, RewardAddress, Signature> {
     pub enable_solution_range_adjustment_and_override: Option<Option<SolutionRange>>,
     /// Root plot public key was updated
     pub root_plot_public_key_update: Option<Option<FarmerPublicKey>>,
+    /// Solution range adjustment algorithm was changed
+    pub solution_range_adjustment: Option<SolutionRangeAdjustment>,
 }
 
 /// Extract the Subspace global randomness from the given header.
@@ -470,6 +529,7 @@ where
     let mut segment_commitments = BTreeMap::new();
     let mut maybe_enable_and_override_solution_range = None;
     let mut maybe_root_plot_public_key_update = None;
+    let mut maybe_solution_range_adjustment = None;
 
     for log in header.digest().logs() {
         match log {
@@ -572,6 +632,18 @@ where
                             }
                         }
                     }
+                    ConsensusLog::SolutionRangeAdjustment(solution_range_adjustment) => {
+                        match maybe_solution_range_adjustment {
+                            Some(_) => {
+                                return Err(Error::Duplicate(
+                                    ErrorDigestType::SolutionRangeAdjustment,
+                                ));
+                            }
+                            None => {
+                                maybe_solution_range_adjustment.replace(solution_range_adjustment);
+                            }
+                        }
+                    }
                 }
             }
             DigestItem::Seal(id, data) => {
@@ -612,6 +684,7 @@ where
         segment_commitments,
         enable_solution_range_adjustment_and_override: maybe_enable_and_override_solution_range,
         root_plot_public_key_update: maybe_root_plot_public_key_update,
+        solution_range_adjustment: maybe_solution_range_adjustment,
     })
 }
 
@@ -651,6 +724,35 @@ where
     pre_digest.ok_or(Error::Missing(ErrorDigestType::PreDigest))
 }
 
+/// Extract the Subspace rewards statement from the given header, returns `Ok(None)` if header
+/// doesn't contain one (for instance, headers produced before rewards statements were introduced).
+pub fn extract_rewards_statement<Header, RewardAddress, Balance>(
+    header: &Header,
+) -> Result<Option<RewardsStatement<RewardAddress, Balance>>, Error>
+where
+    Header: HeaderT,
+    RewardAddress: Decode,
+    Balance: Decode,
+{
+    let mut maybe_rewards_statement = None;
+    for log in header.digest().logs() {
+        let DigestItem::Consensus(id, data) = log else {
+            continue;
+        };
+        if id != &SUBSPACE_REWARDS_ENGINE_ID {
+            continue;
+        }
+
+        let rewards_statement = RewardsStatement::decode(&mut data.as_slice())
+            .map_err(|error| Error::FailedToDecode(ErrorDigestType::RewardsStatement, error))?;
+        if maybe_rewards_statement.replace(rewards_statement).is_some() {
+            return Err(Error::Duplicate(ErrorDigestType::RewardsStatement));
+        }
+    }
+
+    Ok(maybe_rewards_statement)
+}
+
 type NumberOf<T> = <T as HeaderT>::Number;
 
 /// Params used to derive the next solution range.
@@ -671,6 +773,8 @@ pub struct DeriveNextSolutionRangeParams<Header: HeaderT> {
     pub should_adjust_solution_range: bool,
     /// Solution range override that should be used instead of deriving from current.
     pub maybe_next_solution_range_override: Option<SolutionRange>,
+    /// Algorithm used to adjust solution range.
+    pub solution_range_adjustment: SolutionRangeAdjustment,
 }
 
 /// Derives next solution range if era duration interval has met.
@@ -686,6 +790,7 @@ pub fn derive_next_solution_range<Header: HeaderT>(
         era_start_slot,
         should_adjust_solution_range,
         maybe_next_solution_range_override,
+        solution_range_adjustment,
     } = params;
 
     if number.is_zero() || number % era_duration != Zero::zero() {
@@ -699,15 +804,31 @@ pub fn derive_next_solution_range<Header: HeaderT>(
         // era has change so take this override and reset it
         solution_range_override
     } else {
-        subspace_verification::derive_next_solution_range(
-            u64::from(era_start_slot),
-            u64::from(current_slot),
-            slot_probability,
-            current_solution_range,
-            era_duration
-                .try_into()
-                .unwrap_or_else(|_| panic!("Era duration is always within u64; qed")),
-        )
+        let era_duration = era_duration
+            .try_into()
+            .unwrap_or_else(|_| panic!("Era duration is always within u64; qed"));
+
+        match solution_range_adjustment {
+            SolutionRangeAdjustment::V1 => subspace_verification::derive_next_solution_range(
+                u64::from(era_start_slot),
+                u64::from(current_slot),
+                slot_probability,
+                current_solution_range,
+                era_duration,
+            ),
+            SolutionRangeAdjustment::V2 {
+                smoothing_factor,
+                max_adjustment,
+            } => subspace_verification::derive_next_solution_range_v2(
+                u64::from(era_start_slot),
+                u64::from(current_slot),
+                slot_probability,
+                current_solution_range,
+                era_duration,
+                smoothing_factor,
+                max_adjustment,
+            ),
+        }
     };
 
     Ok(Some(next_solution_range))
@@ -731,6 +852,10 @@ pub struct NextDigestsVerificationParams<'a, Header: HeaderT> {
     /// Next Solution range override.
     /// If the digest logs indicate that solution range override is provided, value is updated.
     pub maybe_next_solution_range_override: &'a mut Option<SolutionRange>,
+    /// Algorithm used to adjust solution range.
+    /// If the digest logs indicate that algorithm has changed, value is updated after the next
+    /// solution range is verified, since change only takes effect on the next era change.
+    pub solution_range_adjustment: &'a mut SolutionRangeAdjustment,
     /// Root plot public key.
     /// Value is updated when digest items contain an update.
     pub maybe_root_plot_public_key: &'a mut Option<FarmerPublicKey>,
@@ -748,6 +873,7 @@ pub fn verify_next_digests<Header: HeaderT>(
         era_start_slot,
         should_adjust_solution_range,
         maybe_next_solution_range_override,
+        solution_range_adjustment,
         maybe_root_plot_public_key: root_plot_public_key,
     } = params;
 
@@ -781,6 +907,7 @@ pub fn verify_next_digests<Header: HeaderT>(
             era_start_slot,
             should_adjust_solution_range: *should_adjust_solution_range,
             maybe_next_solution_range_override: *maybe_next_solution_range_override,
+            solution_range_adjustment: *solution_range_adjustment,
         })?;
 
     if expected_next_solution_range.is_some() {
@@ -793,6 +920,10 @@ pub fn verify_next_digests<Header: HeaderT>(
         ));
     }
 
+    if let Some(updated_solution_range_adjustment) = header_digests.solution_range_adjustment {
+        *solution_range_adjustment = updated_solution_range_adjustment;
+    }
+
     if let Some(updated_root_plot_public_key) = &header_digests.root_plot_public_key_update {
         match updated_root_plot_public_key {
             Some(updated_root_plot_public_key) => {
@@ -18,10 +18,11 @@
 
 #![forbid(unsafe_code, missing_docs)]
 #![cfg_attr(not(feature = "std"), no_std)]
-#![feature(let_chains)]
+#![feature(array_windows, let_chains)]
 
 extern crate alloc;
 
+pub mod archival_finality;
 pub mod digests;
 pub mod inherents;
 pub mod offence;
@@ -38,7 +39,7 @@ use sp_core::crypto::KeyTypeId;
 use sp_core::H256;
 use sp_io::hashing;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
-use sp_runtime::{ConsensusEngineId, Justification};
+use sp_runtime::{ConsensusEngineId, Justification, Perbill};
 use sp_runtime_interface::pass_by::PassBy;
 use sp_runtime_interface::{pass_by, runtime_interface};
 use sp_std::num::NonZeroU32;
@@ -100,6 +101,15 @@ impl From<&FarmerPublicKey> for PublicKey {
 
 /// The `ConsensusEngineId` of Subspace.
 const SUBSPACE_ENGINE_ID: ConsensusEngineId = *b"SUB_";
+/// The `ConsensusEngineId` of Subspace rewards statement, separate from [`SUBSPACE_ENGINE_ID`]
+/// since statement is generic over reward address and balance types.
+const SUBSPACE_REWARDS_ENGINE_ID: ConsensusEngineId = *b"SUBR";
+
+/// Minimum allowed confirmation depth `K`.
+///
+/// Archived history can't be reorged, so blocks must be buried deep enough for regular short
+/// forks between farmers that produced blocks at the same time to resolve before archiving.
+pub const MIN_CONFIRMATION_DEPTH_K: BlockNumber = 2;
 
 /// Subspace justification
 #[derive(Debug, Clone, Encode, Decode, TypeInfo)]
@@ -169,7 +179,7 @@ impl PotNextSlotInput {
     ) -> Self {
         let next_slot = parent_slot + Slot::from(1);
         let slot_iterations;
-        let seed;
+        let maybe_entropy;
 
         // The change to number of iterations might have happened before `next_slot`
         if let Some(parameters_change) = pot_parameters_change
@@ -177,20 +187,17 @@ impl PotNextSlotInput {
         {
             slot_iterations = parameters_change.slot_iterations;
             // Only if entropy injection happens exactly on next slot we need to mix it in
-            if parameters_change.slot == next_slot {
-                seed = parent_output.seed_with_entropy(&parameters_change.entropy);
-            } else {
-                seed = parent_output.seed();
-            }
+            maybe_entropy =
+                (parameters_change.slot == next_slot).then_some(&parameters_change.entropy);
         } else {
             slot_iterations = base_slot_iterations;
-            seed = parent_output.seed();
+            maybe_entropy = None;
         }
 
         PotNextSlotInput {
             slot: next_slot,
             slot_iterations,
-            seed,
+            seed: parent_output.next_seed(maybe_entropy),
         }
     }
 }
@@ -232,6 +239,9 @@ enum ConsensusLog {
     /// Root plot public key was updated.
     #[codec(index = 6)]
     RootPlotPublicKeyUpdate(Option<FarmerPublicKey>),
+    /// Algorithm used to adjust solution range was changed, takes effect on the next era change.
+    #[codec(index = 7)]
+    SolutionRangeAdjustment(SolutionRangeAdjustment),
 }
 
 /// Farmer vote.
@@ -403,6 +413,25 @@ impl Default for SolutionRanges {
     }
 }
 
+/// Algorithm used to adjust solution range on era change.
+#[derive(Decode, Encode, MaxEncodedLen, PartialEq, Eq, Clone, Copy, Debug, Default, TypeInfo)]
+pub enum SolutionRangeAdjustment {
+    /// Solution range is scaled by the ratio of actual and expected number of blocks in the era,
+    /// limited to 4x change in either direction.
+    #[default]
+    #[codec(index = 0)]
+    V1,
+    /// Same ratio is used as a target, but solution range is exponentially smoothed towards it and
+    /// clamped, which avoids oscillating win rates after large changes of pledged space.
+    #[codec(index = 1)]
+    V2 {
+        /// Fraction of the distance between current and target solution range covered in one era
+        smoothing_factor: Perbill,
+        /// Max change of solution range in one era relatively to the current solution range
+        max_adjustment: Perbill,
+    },
+}
+
 /// Subspace blockchain constants.
 #[derive(Debug, Encode, Decode, PartialEq, Eq, Clone, Copy, TypeInfo)]
 pub enum ChainConstants {
@@ -691,8 +720,48 @@ impl PotParameters {
     }
 }
 
+/// Entropy collected for injection into proof of time chain, whose target slot is not known yet
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct PendingPotEntropy {
+    /// Number of the block entropy was collected at
+    pub block_number: BlockNumber,
+    /// Collected entropy
+    pub entropy: Blake3Hash,
+}
+
+/// Proof of time schedule: current parameters, changes that are already scheduled and entropy
+/// that will be scheduled for injection in the future
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct PotSchedule {
+    /// Number of iterations for proof of time per slot, corresponds to slot that directly follows
+    /// parent block's slot and can change before slot for which block is produced
+    pub slot_iterations: NonZeroU32,
+    /// Scheduled changes of parameters in order of slots at which they take effect
+    pub scheduled_changes: Vec<PotParametersChange>,
+    /// Collected entropy that doesn't have target slot yet, in order of block numbers
+    pub pending_entropy: Vec<PendingPotEntropy>,
+    /// Interval, in blocks, between blocks that are sources of entropy
+    pub entropy_injection_interval: BlockNumber,
+    /// Interval, in entropy injection intervals, where to take entropy for injection from
+    pub entropy_injection_lookback_depth: u8,
+    /// Delay after block, in slots, when entropy injection takes effect
+    pub entropy_injection_delay: Slot,
+}
+
+/// Information about inclusion of segment header into the consensus chain, which happens shortly
+/// after corresponding segment is archived
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct SegmentArchivalInfo {
+    /// Number of the consensus block that included segment header
+    pub block_number: BlockNumber,
+    /// Timestamp (milliseconds since Unix epoch) of the consensus block that included segment
+    /// header
+    pub timestamp: u64,
+}
+
 sp_api::decl_runtime_apis! {
     /// API necessary for block authorship with Subspace.
+    #[api_version(3)]
     pub trait SubspaceApi<RewardAddress: Encode + Decode> {
         /// Proof of time parameters
         fn pot_parameters() -> PotParameters;
@@ -746,5 +815,16 @@ sp_api::decl_runtime_apis! {
 
         /// Get Subspace blockchain constants
         fn chain_constants() -> ChainConstants;
+
+        /// Get information about inclusion of segment header with specified segment index into
+        /// the consensus chain, `None` for segments that were not included yet or were included
+        /// before this information started being tracked
+        #[api_version(2)]
+        fn segment_archival_info(segment_index: SegmentIndex) -> Option<SegmentArchivalInfo>;
+
+        /// Proof of time schedule with current parameters, scheduled changes of parameters and
+        /// collected entropy that is not scheduled for injection yet
+        #[api_version(3)]
+        fn pot_schedule() -> PotSchedule;
     }
 }
@@ -1,15 +1,24 @@
-use crate::digests::PreDigestPotInfo;
+use crate::archival_finality::{ArchivalFinalityProof, ArchivalFinalityProofError};
+use crate::digests::{
+    derive_next_solution_range, extract_rewards_statement, extract_subspace_digest_items,
+    verify_next_digests, DeriveNextSolutionRangeParams, Error as DigestError, ErrorDigestType,
+    NextDigestsVerificationParams, PreDigestPotInfo,
+};
 use crate::{
     is_equivocation_proof_valid, CompatibleDigestItem, EquivocationProof, FarmerPublicKey,
-    FarmerSignature,
+    FarmerSignature, SolutionRangeAdjustment,
 };
 use schnorrkel::Keypair;
 use sp_consensus_slots::Slot;
 use sp_core::crypto::UncheckedFrom;
 use sp_runtime::traits::BlakeTwo256;
-use sp_runtime::{Digest, DigestItem};
-use std::num::NonZeroU64;
-use subspace_core_primitives::{HistorySize, PieceOffset, Solution, REWARD_SIGNING_CONTEXT};
+use sp_runtime::{Digest, DigestItem, Perbill};
+use std::num::{NonZeroU32, NonZeroU64};
+use subspace_core_primitives::{
+    ArchivedBlockProgress, HistorySize, LastArchivedBlock, PieceOffset, SegmentCommitment,
+    SegmentHeader, SegmentIndex, Solution, SolutionRange, REWARD_SIGNING_CONTEXT,
+};
+use subspace_verification::{verify_rewards_statement, RewardsStatement, RewardsStatementError};
 
 type Header = sp_runtime::generic::Header<u32, BlakeTwo256>;
 type PreDigest = crate::PreDigest<FarmerPublicKey, ()>;
@@ -97,3 +106,297 @@ fn test_is_equivocation_proof_valid() {
 
     assert!(is_equivocation_proof_valid::<_, ()>(&equivocation_proof));
 }
+
+fn archival_finality_proof() -> ArchivalFinalityProof<Header> {
+    let mut segment_headers = Vec::<SegmentHeader>::new();
+    for segment_index in 0..3_u64 {
+        segment_headers.push(SegmentHeader::V0 {
+            segment_index: SegmentIndex::from(segment_index),
+            segment_commitment: SegmentCommitment::from([segment_index as u8; 48]),
+            prev_segment_header_hash: segment_headers
+                .last()
+                .map(SegmentHeader::hash)
+                .unwrap_or_default(),
+            last_archived_block: LastArchivedBlock {
+                number: 10 * (segment_index as u32 + 1),
+                archived_progress: ArchivedBlockProgress::Partial(1),
+            },
+        });
+    }
+    let last_segment_header = *segment_headers.last().unwrap();
+
+    let mut block_headers = Vec::<Header>::new();
+    for number in 25..=35 {
+        let mut header = Header {
+            parent_hash: block_headers
+                .last()
+                .map(|parent| parent.hash())
+                .unwrap_or_default(),
+            number,
+            state_root: Default::default(),
+            extrinsics_root: Default::default(),
+            digest: Digest::default(),
+        };
+        if number == 35 {
+            header.digest.push(DigestItem::segment_commitment(
+                last_segment_header.segment_index(),
+                last_segment_header.segment_commitment(),
+            ));
+        }
+        block_headers.push(header);
+    }
+
+    ArchivalFinalityProof {
+        segment_headers,
+        block_headers,
+    }
+}
+
+#[test]
+fn test_archival_finality_proof() {
+    let proof = archival_finality_proof();
+
+    // From genesis segment
+    let (block_header, segment_header) = proof.verify(None).unwrap();
+    assert_eq!(block_header.number, 25);
+    assert_eq!(segment_header.segment_index(), SegmentIndex::from(2));
+
+    // From trusted segment
+    {
+        let mut proof = proof.clone();
+        let trusted_segment_header = proof.segment_headers.remove(0);
+        assert!(proof.verify(Some(trusted_segment_header.hash())).is_ok());
+        assert_eq!(
+            proof.verify(None),
+            Err(ArchivalFinalityProofError::UntrustedSegmentHeader)
+        );
+        assert_eq!(
+            proof.verify(Some(Default::default())),
+            Err(ArchivalFinalityProofError::UntrustedSegmentHeader)
+        );
+    }
+
+    // Broken segment headers chain
+    {
+        let mut proof = proof.clone();
+        proof.segment_headers.remove(1);
+        assert_eq!(
+            proof.verify(None),
+            Err(ArchivalFinalityProofError::SegmentHeadersNotChained(
+                SegmentIndex::from(2)
+            ))
+        );
+    }
+
+    // Broken block headers chain
+    {
+        let mut proof = proof.clone();
+        proof.block_headers.remove(3);
+        assert_eq!(
+            proof.verify(None),
+            Err(ArchivalFinalityProofError::BlockHeadersNotChained(3))
+        );
+    }
+
+    // Partially archived block
+    {
+        let mut proof = proof.clone();
+        proof.block_headers.drain(..5);
+        assert_eq!(
+            proof.verify(None),
+            Err(ArchivalFinalityProofError::BlockNotArchived(30))
+        );
+    }
+
+    // Segment commitment is missing
+    {
+        let mut proof = proof.clone();
+        proof.block_headers.pop();
+        assert_eq!(
+            proof.verify(None),
+            Err(ArchivalFinalityProofError::SegmentCommitmentNotFound)
+        );
+    }
+
+    // Empty proofs
+    {
+        let mut proof = proof.clone();
+        proof.block_headers.clear();
+        assert_eq!(
+            proof.verify(None),
+            Err(ArchivalFinalityProofError::NoBlockHeaders)
+        );
+        proof.segment_headers.clear();
+        assert_eq!(
+            proof.verify(None),
+            Err(ArchivalFinalityProofError::NoSegmentHeaders)
+        );
+    }
+}
+
+#[test]
+fn test_rewards_statement() {
+    let rewards_statement = RewardsStatement::<u64, u128> {
+        block_reward: Some(100),
+        vote_rewards: vec![(1, 10), (2, 10)],
+    };
+    let mut header = Header {
+        parent_hash: [0u8; 32].into(),
+        number: 1,
+        state_root: Default::default(),
+        extrinsics_root: Default::default(),
+        digest: Digest::default(),
+    };
+
+    assert_eq!(extract_rewards_statement::<_, u64, u128>(&header), Ok(None));
+
+    header
+        .digest
+        .push(DigestItem::rewards_statement(&rewards_statement));
+    assert_eq!(
+        extract_rewards_statement::<_, u64, u128>(&header),
+        Ok(Some(rewards_statement.clone()))
+    );
+    assert_eq!(
+        header.digest.logs()[0].as_rewards_statement::<u64, u128>(),
+        Some(rewards_statement.clone())
+    );
+
+    assert_eq!(
+        verify_rewards_statement(&rewards_statement, Some((100, 10))),
+        Ok(())
+    );
+    assert_eq!(
+        verify_rewards_statement(&rewards_statement, Some((101, 10))),
+        Err(RewardsStatementError::InvalidBlockReward)
+    );
+    assert_eq!(
+        verify_rewards_statement(&rewards_statement, Some((100, 11))),
+        Err(RewardsStatementError::InvalidVoteReward { position: 0 })
+    );
+    assert_eq!(
+        verify_rewards_statement(&rewards_statement, None),
+        Err(RewardsStatementError::InvalidBlockReward)
+    );
+
+    header
+        .digest
+        .push(DigestItem::rewards_statement(&rewards_statement));
+    assert_eq!(
+        extract_rewards_statement::<_, u64, u128>(&header),
+        Err(DigestError::Duplicate(ErrorDigestType::RewardsStatement))
+    );
+}
+
+#[test]
+fn test_next_solution_range_uses_announced_adjustment() {
+    let keypair = Keypair::generate();
+    let public_key = FarmerPublicKey::unchecked_from(keypair.public.to_bytes());
+    let era_duration = 4;
+    let slot_probability = (1, 6);
+    let solution_range = SolutionRange::MAX / 1024;
+    let solution_range_adjustment = SolutionRangeAdjustment::V2 {
+        smoothing_factor: Perbill::from_percent(50),
+        max_adjustment: Perbill::from_percent(10),
+    };
+
+    let header = |number: u32, extra_logs: Vec<DigestItem>| {
+        let mut logs = vec![
+            DigestItem::subspace_pre_digest(&crate::PreDigest::V0 {
+                slot: Slot::from(u64::from(number)),
+                solution: Solution::genesis_solution(public_key.clone(), public_key.clone()),
+                pot_info: PreDigestPotInfo::V0 {
+                    proof_of_time: Default::default(),
+                    future_proof_of_time: Default::default(),
+                },
+            }),
+            DigestItem::pot_slot_iterations(NonZeroU32::new(1).unwrap()),
+            DigestItem::solution_range(solution_range),
+        ];
+        logs.extend(extra_logs);
+
+        Header {
+            parent_hash: Default::default(),
+            number,
+            state_root: Default::default(),
+            extrinsics_root: Default::default(),
+            digest: Digest { logs },
+        }
+    };
+    let verify = |header: &Header, solution_range_adjustment: &mut SolutionRangeAdjustment| {
+        let header_digests =
+            extract_subspace_digest_items::<_, FarmerPublicKey, FarmerPublicKey, FarmerSignature>(
+                header,
+            )
+            .unwrap();
+
+        verify_next_digests::<Header>(NextDigestsVerificationParams {
+            number: header.number,
+            header_digests: &header_digests,
+            era_duration,
+            slot_probability,
+            era_start_slot: Slot::from(0),
+            should_adjust_solution_range: &mut true,
+            maybe_next_solution_range_override: &mut None,
+            solution_range_adjustment,
+            maybe_root_plot_public_key: &mut None,
+        })
+    };
+
+    // Change of the algorithm is picked up from the digest
+    let mut current_solution_range_adjustment = SolutionRangeAdjustment::V1;
+    verify(
+        &header(
+            3,
+            vec![DigestItem::solution_range_adjustment(
+                solution_range_adjustment,
+            )],
+        ),
+        &mut current_solution_range_adjustment,
+    )
+    .unwrap();
+    assert_eq!(current_solution_range_adjustment, solution_range_adjustment);
+
+    let derive = |solution_range_adjustment| {
+        derive_next_solution_range::<Header>(DeriveNextSolutionRangeParams {
+            number: era_duration,
+            era_duration,
+            slot_probability,
+            current_slot: Slot::from(u64::from(era_duration)),
+            current_solution_range: solution_range,
+            era_start_slot: Slot::from(0),
+            should_adjust_solution_range: true,
+            maybe_next_solution_range_override: None,
+            solution_range_adjustment,
+        })
+        .unwrap()
+        .unwrap()
+    };
+    let v1_next_solution_range = derive(SolutionRangeAdjustment::V1);
+    let v2_next_solution_range = derive(solution_range_adjustment);
+    assert_ne!(v1_next_solution_range, v2_next_solution_range);
+
+    // On era change next solution range must be derived with announced algorithm
+    assert_eq!(
+        verify(
+            &header(
+                era_duration,
+                vec![DigestItem::next_solution_range(v1_next_solution_range)]
+            ),
+            &mut current_solution_range_adjustment,
+        ),
+        Err(DigestError::NextDigestVerificationError(
+            ErrorDigestType::NextSolutionRange
+        ))
+    );
+    assert_eq!(
+        verify(
+            &header(
+                era_duration,
+                vec![DigestItem::next_solution_range(v2_next_solution_range)]
+            ),
+            &mut current_solution_range_adjustment,
+        ),
+        Ok(())
+    );
+}
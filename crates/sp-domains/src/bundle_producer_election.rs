@@ -3,12 +3,12 @@ use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use sp_core::crypto::{VrfPublic, Wraps};
 use sp_core::sr25519::vrf::{VrfPreOutput, VrfSignature, VrfTranscript};
-use subspace_core_primitives::Blake3Hash;
+use subspace_core_primitives::GlobalChallenge;
 
 const VRF_TRANSCRIPT_LABEL: &[u8] = b"bundle_producer_election";
 
 /// Generates a domain-specific vrf transcript from given global_challenge.
-pub fn make_transcript(domain_id: DomainId, global_challenge: &Blake3Hash) -> VrfTranscript {
+pub fn make_transcript(domain_id: DomainId, global_challenge: &GlobalChallenge) -> VrfTranscript {
     VrfTranscript::new(
         VRF_TRANSCRIPT_LABEL,
         &[
@@ -37,8 +37,9 @@ pub fn calculate_threshold(
         * operator_stake
 }
 
-pub fn is_below_threshold(vrf_output: &VrfPreOutput, threshold: u128) -> bool {
-    let vrf_pre_output = u128::from_le_bytes(
+/// Returns the value of the vrf output that is compared against the election threshold.
+pub fn vrf_output_value(vrf_output: &VrfPreOutput) -> u128 {
+    u128::from_le_bytes(
         vrf_output
             .0
             .to_bytes()
@@ -46,9 +47,11 @@ pub fn is_below_threshold(vrf_output: &VrfPreOutput, threshold: u128) -> bool {
             .0
             .try_into()
             .expect("Slice splitted from VrfPreOutput must fit into u128; qed"),
-    );
+    )
+}
 
-    vrf_pre_output < threshold
+pub fn is_below_threshold(vrf_output: &VrfPreOutput, threshold: u128) -> bool {
+    vrf_output_value(vrf_output) < threshold
 }
 
 #[derive(Debug, Decode, Encode, TypeInfo, PartialEq, Eq, Clone)]
@@ -70,7 +73,7 @@ pub(crate) fn verify_vrf_signature(
     domain_id: DomainId,
     public_key: &OperatorPublicKey,
     vrf_signature: &VrfSignature,
-    global_challenge: &Blake3Hash,
+    global_challenge: &GlobalChallenge,
 ) -> Result<(), ProofOfElectionError> {
     if !public_key.as_inner_ref().vrf_verify(
         &make_transcript(domain_id, global_challenge).into(),
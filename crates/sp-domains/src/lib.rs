@@ -1248,11 +1248,22 @@ sp_api::decl_runtime_apis! {
         /// Reture the consensus chain byte fee that will used to charge the domain transaction for consensus
         /// chain storage fee
         fn consensus_chain_byte_fee() -> Balance;
+
+        /// Returns the balance of the domain treasury
+        fn domain_treasury_balance(domain_id: DomainId) -> Balance;
+
+        /// Returns the number of the consensus block that confirmed the given domain block, only
+        /// available for recently confirmed domain blocks
+        fn confirmed_domain_block_consensus_number(domain_id: DomainId, domain_number: HeaderNumberFor<DomainHeader>) -> Option<NumberFor<Block>>;
     }
 
     pub trait BundleProducerElectionApi<Balance: Encode + Decode> {
         fn bundle_producer_election_params(domain_id: DomainId) -> Option<BundleProducerElectionParams<Balance>>;
 
         fn operator(operator_id: OperatorId) -> Option<(OperatorPublicKey, Balance)>;
+
+        /// Returns the new signing key of the operator that replaces the current one at the end of
+        /// the current epoch, if rotation was scheduled.
+        fn operator_pending_signing_key(operator_id: OperatorId) -> Option<OperatorPublicKey>;
     }
 }
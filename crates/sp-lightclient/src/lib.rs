@@ -36,7 +36,7 @@ use sp_consensus_subspace::digests::{
     Error as DigestError, ErrorDigestType, NextDigestsVerificationParams, PreDigest,
     SubspaceDigestItems,
 };
-use sp_consensus_subspace::{FarmerPublicKey, FarmerSignature};
+use sp_consensus_subspace::{FarmerPublicKey, FarmerSignature, SolutionRangeAdjustment};
 use sp_runtime::traits::Header as HeaderT;
 use sp_runtime::ArithmeticError;
 use sp_std::cmp::Ordering;
@@ -46,7 +46,7 @@ use sp_std::num::NonZeroU64;
 #[cfg(not(feature = "pot"))]
 use subspace_core_primitives::Randomness;
 use subspace_core_primitives::{
-    ArchivedHistorySegment, BlockWeight, HistorySize, PublicKey, RewardSignature, SectorId,
+    ArchivedHistorySegment, BlockWeight, HistorySize, PublicKey, RewardSignature,
     SegmentCommitment, SegmentIndex, SolutionRange, REWARD_SIGNING_CONTEXT,
 };
 use subspace_verification::{
@@ -109,6 +109,8 @@ pub struct HeaderExt<Header> {
     pub maybe_current_solution_range_override: Option<SolutionRange>,
     /// Solution range override for the next era.
     pub maybe_next_solution_range_override: Option<SolutionRange>,
+    /// Algorithm used to adjust solution range on era change.
+    pub solution_range_adjustment: SolutionRangeAdjustment,
     /// Restrict block authoring to this public key.
     pub maybe_root_plot_public_key: Option<FarmerPublicKey>,
 
@@ -342,6 +344,7 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
         let mut should_adjust_solution_range = parent_header.should_adjust_solution_range;
         let mut maybe_next_solution_range_override =
             parent_header.maybe_next_solution_range_override;
+        let mut solution_range_adjustment = parent_header.solution_range_adjustment;
         verify_next_digests::<Header>(NextDigestsVerificationParams {
             number: *header.number(),
             header_digests: &header_digests,
@@ -352,6 +355,7 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
             era_start_slot: parent_header.era_start_slot,
             should_adjust_solution_range: &mut should_adjust_solution_range,
             maybe_next_solution_range_override: &mut maybe_next_solution_range_override,
+            solution_range_adjustment: &mut solution_range_adjustment,
             maybe_root_plot_public_key: &mut maybe_root_plot_public_key,
         })?;
 
@@ -365,10 +369,7 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
         )?;
 
         // verify solution
-        let sector_id = SectorId::new(
-            PublicKey::from(&header_digests.pre_digest.solution().public_key).hash(),
-            header_digests.pre_digest.solution().sector_index,
-        );
+        let sector_id = header_digests.pre_digest.solution().sector_id();
 
         let max_pieces_in_sector = self.store.max_pieces_in_sector();
 
@@ -465,6 +466,7 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
             should_adjust_solution_range,
             maybe_current_solution_range_override,
             maybe_next_solution_range_override,
+            solution_range_adjustment,
             maybe_root_plot_public_key,
 
             #[cfg(all(test, not(feature = "pot")))]
@@ -95,6 +95,7 @@ fn archived_segment() -> &'static NewArchivedSegment {
 
         archiver
             .add_block(block, Default::default(), true)
+            .unwrap()
             .into_iter()
             .next()
             .unwrap()
@@ -331,6 +332,7 @@ fn initialize_store(
         should_adjust_solution_range,
         maybe_current_solution_range_override: None,
         maybe_next_solution_range_override: None,
+        solution_range_adjustment: Default::default(),
         maybe_root_plot_public_key,
         test_overrides: Default::default(),
     };
@@ -368,6 +370,7 @@ fn add_next_digests(store: &MockStorage, number: NumberOf<Header>, header: &mut
             era_start_slot: parent_header.era_start_slot,
             should_adjust_solution_range: true,
             maybe_next_solution_range_override: None,
+            solution_range_adjustment: parent_header.solution_range_adjustment,
         })
         .unwrap()
     {
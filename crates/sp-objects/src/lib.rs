@@ -17,16 +17,29 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use sp_runtime::traits::Block as BlockT;
 use sp_std::vec::Vec;
-use subspace_core_primitives::objects::BlockObjectMapping;
+use subspace_core_primitives::objects::{BlockObjectMapping, ObjectMappingLimits};
+use subspace_core_primitives::Blake3Hash;
 use subspace_runtime_primitives::Hash;
 
 sp_api::decl_runtime_apis! {
+    #[api_version(3)]
     pub trait ObjectsApi {
         /// Returns all the validated object call hashes for a given block
         fn validated_object_call_hashes() -> Vec<Hash>;
 
         /// Extract block object mapping for a given block
         fn extract_block_object_mapping(block: Block, validated_object_calls: Vec<Hash>) -> BlockObjectMapping;
+
+        /// Limits on object mapping of a single block, applied by the archiver to extracted object
+        /// mappings
+        #[api_version(2)]
+        fn object_mapping_limits() -> ObjectMappingLimits;
+
+        /// Hashes of objects that would be stored by the extrinsic once it is successfully
+        /// included in a block, used to track objects that are not yet included in a block
+        #[api_version(3)]
+        fn extrinsic_object_hashes(extrinsic: <Block as BlockT>::Extrinsic) -> Vec<Blake3Hash>;
     }
 }
@@ -15,11 +15,14 @@ fn criterion_benchmark(c: &mut Criterion) {
 
     c.bench_function("segment-archiving-large-block", |b| {
         b.iter(|| {
-            archiver.clone().add_block(
-                black_box(input.clone()),
-                black_box(Default::default()),
-                black_box(true),
-            );
+            archiver
+                .clone()
+                .add_block(
+                    black_box(input.clone()),
+                    black_box(Default::default()),
+                    black_box(true),
+                )
+                .unwrap();
         })
     });
 
@@ -27,11 +30,13 @@ fn criterion_benchmark(c: &mut Criterion) {
         b.iter(|| {
             let mut archiver = archiver.clone();
             for chunk in input.chunks(SMALL_BLOCK_SIZE) {
-                archiver.add_block(
-                    black_box(chunk.to_vec()),
-                    black_box(Default::default()),
-                    black_box(true),
-                );
+                archiver
+                    .add_block(
+                        black_box(chunk.to_vec()),
+                        black_box(Default::default()),
+                        black_box(true),
+                    )
+                    .unwrap();
             }
         })
     });
@@ -40,11 +45,13 @@ fn criterion_benchmark(c: &mut Criterion) {
         b.iter(|| {
             let mut archiver = archiver.clone();
             for chunk in input.chunks(SMALL_BLOCK_SIZE) {
-                archiver.add_block(
-                    black_box(chunk.to_vec()),
-                    black_box(Default::default()),
-                    black_box(false),
-                );
+                archiver
+                    .add_block(
+                        black_box(chunk.to_vec()),
+                        black_box(Default::default()),
+                        black_box(false),
+                    )
+                    .unwrap();
             }
         })
     });
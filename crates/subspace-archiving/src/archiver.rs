@@ -22,10 +22,13 @@ use crate::archiver::incremental_record_commitments::{
 };
 use alloc::collections::VecDeque;
 use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::cmp::Ordering;
 use core::num::NonZeroUsize;
+use core::time::Duration;
+use core::{fmt, mem};
 use parity_scale_codec::{Compact, CompactLen, Decode, Encode, Input, Output};
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -36,10 +39,12 @@ use subspace_core_primitives::objects::{
 };
 use subspace_core_primitives::{
     ArchivedBlockProgress, ArchivedHistorySegment, Blake3Hash, BlockNumber, LastArchivedBlock,
-    PieceArray, RawRecord, RecordWitness, RecordedHistorySegment, SegmentCommitment, SegmentHeader,
-    SegmentIndex,
+    PieceArray, PiecePosition, RawRecord, RecordWitness, RecordedHistorySegment, SegmentCommitment,
+    SegmentHeader, SegmentIndex,
 };
 use subspace_erasure_coding::ErasureCoding;
+#[cfg(feature = "parallel")]
+use subspace_thread_pool::ManagedThreadPool;
 
 const INITIAL_LAST_ARCHIVED_BLOCK: LastArchivedBlock = LastArchivedBlock {
     number: 0,
@@ -213,6 +218,117 @@ pub enum ArchiverInstantiationError {
     },
 }
 
+/// Error happening when block is streamed into archiver, see [`Archiver::start_block()`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+pub enum StreamingError {
+    /// Previous block was not fully fed into archiver yet
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Previous block was not fully fed yet, {missing_bytes} bytes are missing")
+    )]
+    BlockInProgress {
+        /// Number of bytes of the previous block that were not fed yet
+        missing_bytes: u32,
+    },
+    /// No block is being streamed into archiver
+    #[cfg_attr(feature = "thiserror", error("No block is being streamed"))]
+    NoBlockInProgress,
+    /// More bytes were fed than block size specified when block was started
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Fed {excess_bytes} bytes more than remaining size of the block")
+    )]
+    TooManyBytes {
+        /// Number of bytes over the block size
+        excess_bytes: usize,
+    },
+}
+
+/// Block that is being streamed into archiver, only tracks the part of the block that was not
+/// included into archived segments yet
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+struct StreamedBlock {
+    /// Size of the part of the block that was not included into archived segments yet
+    remaining_size: u32,
+    /// Already received bytes of that part of the block
+    bytes: Vec<u8>,
+    /// Object mapping of that part of the block, offsets are relative to its beginning
+    object_mapping: BlockObjectMapping,
+    /// Whether the beginning of the block was already included into archived segments
+    continuation: bool,
+    /// Whether incremental archiving is used once block is fully received
+    incremental: bool,
+}
+
+impl StreamedBlock {
+    fn missing_bytes(&self) -> u32 {
+        self.remaining_size
+            - u32::try_from(self.bytes.len()).expect("Blocks length is never bigger than u32; qed")
+    }
+}
+
+/// Statistics of archived segment, see [`ArchiverObserver::on_segment_completed()`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SegmentArchivingStats {
+    /// Time it took to archive segment (erasure coding, commitments and witnesses creation), always
+    /// zero in `no_std` environment
+    pub duration: Duration,
+    /// Number of items in the segment
+    pub segment_items: usize,
+    /// Number of bytes of blocks (including partial blocks) in the segment
+    pub block_bytes: usize,
+    /// Number of padding bytes at the end of the segment
+    pub padding_bytes: usize,
+}
+
+/// Observer of archiving progress that can be used to collect metrics, see
+/// [`Archiver::set_observer()`].
+///
+/// Methods are called synchronously during archiving and are expected to return quickly.
+pub trait ArchiverObserver: fmt::Debug + Send + Sync {
+    /// Block of `block_size` bytes was added to the archiver (or started to be streamed into it)
+    fn on_block_buffered(&self, _block_size: u32) {}
+
+    /// Enough data was accumulated and archiving of the segment started
+    fn on_segment_started(&self, _segment_index: SegmentIndex) {}
+
+    /// Segment was archived
+    fn on_segment_completed(&self, _segment_index: SegmentIndex, _stats: SegmentArchivingStats) {}
+}
+
+/// Snapshot of archiver state, see [`Archiver::state()`].
+///
+/// Contains everything that was added to the archiver, but was not archived yet. It can be
+/// persisted in SCALE-encoded form and used to resume archiving with [`Archiver::from_state()`]
+/// after restart without adding all blocks since the last archived segment again.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct ArchiverState {
+    /// Index of the next archived segment
+    segment_index: SegmentIndex,
+    /// Hash of the segment header of the last archived segment
+    prev_segment_header_hash: Blake3Hash,
+    /// Last archived block
+    last_archived_block: LastArchivedBlock,
+    /// Buffered segment items alongside object mappings of blocks, which are not encoded as part
+    /// of segment items
+    buffer: Vec<(SegmentItem, BlockObjectMapping)>,
+    /// Block that is being streamed into archiver
+    streamed_block: Option<StreamedBlock>,
+}
+
+impl ArchiverState {
+    /// Index of the next archived segment
+    pub fn segment_index(&self) -> SegmentIndex {
+        self.segment_index
+    }
+
+    /// Last archived block
+    pub fn last_archived_block(&self) -> LastArchivedBlock {
+        self.last_archived_block
+    }
+}
+
 /// Block archiver for Subspace blockchain.
 ///
 /// It takes new confirmed (at `K` depth) blocks and concatenates them into a buffer, buffer is
@@ -221,6 +337,13 @@ pub enum ArchiverInstantiationError {
 /// commitments with witnesses are appended and records become pieces that are returned alongside
 /// corresponding segment header header.
 ///
+/// Blocks can either be added whole with [`Archiver::add_block()`] or streamed in chunks with
+/// [`Archiver::start_block()`] and [`Archiver::feed()`], both produce identical archived segments.
+///
+/// ## Errors
+/// Adding whole block while another block is being streamed, as well as feeding blocks
+/// inconsistently with their declared size, results in [`StreamingError`].
+///
 /// ## Panics
 /// Panics when operating on blocks, whose length doesn't fit into u32 (should never be the case in
 /// blockchain context anyway).
@@ -241,6 +364,14 @@ pub struct Archiver {
     prev_segment_header_hash: Blake3Hash,
     /// Last archived block
     last_archived_block: LastArchivedBlock,
+    /// Block that is being streamed into archiver
+    streamed_block: Option<StreamedBlock>,
+    /// Thread pool for erasure coding and commitments creation, global thread pool is used if not
+    /// set
+    #[cfg(feature = "parallel")]
+    thread_pool: Option<Arc<ManagedThreadPool>>,
+    /// Observer of archiving progress
+    observer: Option<Arc<dyn ArchiverObserver>>,
 }
 
 impl Archiver {
@@ -269,6 +400,10 @@ impl Archiver {
             segment_index: SegmentIndex::ZERO,
             prev_segment_header_hash: Blake3Hash::default(),
             last_archived_block: INITIAL_LAST_ARCHIVED_BLOCK,
+            streamed_block: None,
+            #[cfg(feature = "parallel")]
+            thread_pool: None,
+            observer: None,
         })
     }
 
@@ -333,6 +468,79 @@ impl Archiver {
         Ok(archiver)
     }
 
+    /// Create a new instance of the archiver from state previously obtained with [`Self::state()`].
+    ///
+    /// Unlike [`Self::with_initial_state()`], blocks that were added after the last archived
+    /// segment don't need to be added again, archiving continues with the block that follows the
+    /// last block added before the state was taken.
+    pub fn from_state(kzg: Kzg, state: ArchiverState) -> Result<Self, ArchiverInstantiationError> {
+        let mut archiver = Self::new(kzg)?;
+
+        let ArchiverState {
+            segment_index,
+            prev_segment_header_hash,
+            last_archived_block,
+            buffer,
+            streamed_block,
+        } = state;
+
+        archiver.segment_index = segment_index;
+        archiver.prev_segment_header_hash = prev_segment_header_hash;
+        archiver.last_archived_block = last_archived_block;
+        archiver.buffer = buffer
+            .into_iter()
+            .map(|(mut segment_item, block_object_mapping)| {
+                match &mut segment_item {
+                    SegmentItem::Block { object_mapping, .. }
+                    | SegmentItem::BlockStart { object_mapping, .. }
+                    | SegmentItem::BlockContinuation { object_mapping, .. } => {
+                        *object_mapping = block_object_mapping;
+                    }
+                    SegmentItem::Padding | SegmentItem::ParentSegmentHeader(_) => {
+                        // No object mapping here
+                    }
+                }
+
+                segment_item
+            })
+            .collect();
+        archiver.streamed_block = streamed_block;
+
+        Ok(archiver)
+    }
+
+    /// Snapshot of the archiver state that can be used to resume archiving with
+    /// [`Self::from_state()`].
+    ///
+    /// State doesn't contain block numbers of added blocks, the caller is responsible for
+    /// persisting which block was added last alongside the state.
+    pub fn state(&self) -> ArchiverState {
+        ArchiverState {
+            segment_index: self.segment_index,
+            prev_segment_header_hash: self.prev_segment_header_hash,
+            last_archived_block: self.last_archived_block,
+            buffer: self
+                .buffer
+                .iter()
+                .map(|segment_item| {
+                    let object_mapping = match segment_item {
+                        SegmentItem::Block { object_mapping, .. }
+                        | SegmentItem::BlockStart { object_mapping, .. }
+                        | SegmentItem::BlockContinuation { object_mapping, .. } => {
+                            object_mapping.clone()
+                        }
+                        SegmentItem::Padding | SegmentItem::ParentSegmentHeader(_) => {
+                            BlockObjectMapping::default()
+                        }
+                    };
+
+                    (segment_item.clone(), object_mapping)
+                })
+                .collect(),
+            streamed_block: self.streamed_block.clone(),
+        }
+    }
+
     /// Get last archived block if there was any
     pub fn last_archived_block_number(&self) -> Option<BlockNumber> {
         if self.last_archived_block != INITIAL_LAST_ARCHIVED_BLOCK {
@@ -342,31 +550,313 @@ impl Archiver {
         }
     }
 
+    /// Use dedicated thread pool for erasure coding and commitments creation instead of the global
+    /// one, this way archiving can be isolated from other work happening in the global thread pool
+    /// and accounted for in the CPU budget of the thread pool manager it was created with
+    #[cfg(feature = "parallel")]
+    pub fn set_thread_pool(&mut self, thread_pool: Arc<ManagedThreadPool>) {
+        self.thread_pool.replace(thread_pool);
+    }
+
+    /// Set observer that will be notified about archiving progress
+    pub fn set_observer(&mut self, observer: Arc<dyn ArchiverObserver>) {
+        self.observer.replace(observer);
+    }
+
+    /// Run `f` in dedicated thread pool if set, such that parallel processing inside uses it
+    fn in_thread_pool<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R + Send,
+        R: Send,
+    {
+        #[cfg(feature = "parallel")]
+        if let Some(thread_pool) = self.thread_pool.clone() {
+            return thread_pool.install(|| f(self));
+        }
+
+        f(self)
+    }
+
     /// Adds new block to internal buffer, potentially producing pieces and segment header headers.
     ///
     /// Incremental archiving can be enabled if amortized block addition cost is preferred over
     /// throughput.
+    ///
+    /// Returns an error if another block is being streamed with [`Self::start_block()`] and wasn't
+    /// fully fed yet.
     pub fn add_block(
         &mut self,
         bytes: Vec<u8>,
         object_mapping: BlockObjectMapping,
         incremental: bool,
-    ) -> Vec<NewArchivedSegment> {
+    ) -> Result<Vec<NewArchivedSegment>, StreamingError> {
+        if let Some(streamed_block) = &self.streamed_block {
+            return Err(StreamingError::BlockInProgress {
+                missing_bytes: streamed_block.missing_bytes(),
+            });
+        }
+
+        if let Some(observer) = &self.observer {
+            observer.on_block_buffered(
+                u32::try_from(bytes.len()).expect("Blocks length is never bigger than u32; qed"),
+            );
+        }
+
         // Append new block to the buffer
         self.buffer.push_back(SegmentItem::Block {
             bytes,
             object_mapping,
         });
 
+        Ok(self.in_thread_pool(|archiver| {
+            let mut archived_segments = Vec::new();
+
+            while let Some(segment) = archiver.produce_segment(incremental) {
+                archived_segments.push(archiver.produce_archived_segment(segment));
+            }
+
+            archived_segments
+        }))
+    }
+
+    /// Start streaming new block of `block_size` bytes into archiver, bytes of the block are then
+    /// provided with [`Self::feed()`] in chunks of arbitrary size.
+    ///
+    /// Produces exactly the same archived segments as [`Self::add_block()`] would, but segments are
+    /// produced as soon as enough bytes were fed and only the part of the block that doesn't fill
+    /// the current segment yet is buffered, so the whole block doesn't need to be in memory.
+    pub fn start_block(
+        &mut self,
+        block_size: u32,
+        object_mapping: BlockObjectMapping,
+        incremental: bool,
+    ) -> Result<Vec<NewArchivedSegment>, StreamingError> {
+        if let Some(streamed_block) = &self.streamed_block {
+            return Err(StreamingError::BlockInProgress {
+                missing_bytes: streamed_block.missing_bytes(),
+            });
+        }
+
+        if let Some(observer) = &self.observer {
+            observer.on_block_buffered(block_size);
+        }
+
+        self.streamed_block.replace(StreamedBlock {
+            remaining_size: block_size,
+            bytes: Vec::new(),
+            object_mapping,
+            continuation: false,
+            incremental,
+        });
+
+        Ok(self.in_thread_pool(Self::process_streamed_block))
+    }
+
+    /// Feed next chunk of bytes of the block started with [`Self::start_block()`], potentially
+    /// producing archived segments.
+    ///
+    /// Once all bytes of the block were fed, next block can be started.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<Vec<NewArchivedSegment>, StreamingError> {
+        let Some(streamed_block) = &mut self.streamed_block else {
+            return Err(StreamingError::NoBlockInProgress);
+        };
+
+        let missing_bytes = streamed_block.missing_bytes() as usize;
+        if bytes.len() > missing_bytes {
+            return Err(StreamingError::TooManyBytes {
+                excess_bytes: bytes.len() - missing_bytes,
+            });
+        }
+
+        streamed_block.bytes.extend_from_slice(bytes);
+
+        Ok(self.in_thread_pool(Self::process_streamed_block))
+    }
+
+    /// Whether there is a block that is being streamed into archiver and wasn't fully fed yet
+    pub fn is_streaming(&self) -> bool {
+        self.streamed_block.is_some()
+    }
+
+    /// Produce archived segments for streamed block if possible.
+    ///
+    /// Buffer alone never has enough data for a segment, so this mirrors what
+    /// [`Self::produce_segment()`] would do with the whole block appended to the buffer, which only
+    /// depends on the size of the block, not its contents.
+    fn process_streamed_block(&mut self) -> Vec<NewArchivedSegment> {
         let mut archived_segments = Vec::new();
 
-        while let Some(segment) = self.produce_segment(incremental) {
+        while let Some(mut streamed_block) = self.streamed_block.take() {
+            if streamed_block.missing_bytes() == 0 {
+                // The rest of the block is received, from here on it is processed like any other
+                // block
+                let StreamedBlock {
+                    bytes,
+                    object_mapping,
+                    continuation,
+                    incremental,
+                    ..
+                } = streamed_block;
+
+                self.buffer.push_back(if continuation {
+                    SegmentItem::BlockContinuation {
+                        bytes,
+                        object_mapping,
+                    }
+                } else {
+                    SegmentItem::Block {
+                        bytes,
+                        object_mapping,
+                    }
+                });
+
+                while let Some(segment) = self.produce_segment(incremental) {
+                    archived_segments.push(self.produce_archived_segment(segment));
+                }
+
+                break;
+            }
+
+            let segment_size = Segment::V0 { items: Vec::new() }.encoded_size()
+                + self
+                    .buffer
+                    .iter()
+                    .map(SegmentItem::encoded_size)
+                    .sum::<usize>();
+            let remaining_size = streamed_block.remaining_size as usize;
+            // `+1` corresponds to `SegmentItem::X {}` enum variant encoding
+            let segment_item_size =
+                1 + Compact::compact_len(&streamed_block.remaining_size) + remaining_size;
+
+            if segment_size + segment_item_size < RecordedHistorySegment::SIZE {
+                // The rest of the block fits into the segment, wait for all of its bytes
+                self.streamed_block.replace(streamed_block);
+                break;
+            }
+
+            let spill_over = segment_size + segment_item_size - RecordedHistorySegment::SIZE;
+
+            let segment = if spill_over > remaining_size {
+                // Not even the beginning of the block fits into the segment, segment is produced
+                // from buffer contents only
+                self.produce_streamed_segment(None)
+            } else {
+                let split_point = remaining_size - spill_over;
+                if streamed_block.bytes.len() < split_point {
+                    // Not enough bytes to fill the segment yet
+                    self.streamed_block.replace(streamed_block);
+                    break;
+                }
+
+                let continuation_bytes = streamed_block.bytes.split_off(split_point);
+                let bytes = mem::replace(&mut streamed_block.bytes, continuation_bytes);
+
+                let continuation_object_mapping = BlockObjectMapping {
+                    objects: streamed_block
+                        .object_mapping
+                        .objects
+                        .extract_if(|block_object: &mut BlockObject| {
+                            let current_offset = block_object.offset();
+                            if current_offset >= split_point as u32 {
+                                block_object.set_offset(current_offset - split_point as u32);
+                                true
+                            } else {
+                                false
+                            }
+                        })
+                        .collect(),
+                };
+                let object_mapping = mem::replace(
+                    &mut streamed_block.object_mapping,
+                    continuation_object_mapping,
+                );
+
+                let segment_item = if streamed_block.continuation {
+                    SegmentItem::BlockContinuation {
+                        bytes,
+                        object_mapping,
+                    }
+                } else {
+                    SegmentItem::BlockStart {
+                        bytes,
+                        object_mapping,
+                    }
+                };
+
+                streamed_block.remaining_size -= split_point as u32;
+                streamed_block.continuation = true;
+
+                self.produce_streamed_segment(Some(segment_item))
+            };
+
             archived_segments.push(self.produce_archived_segment(segment));
+            self.streamed_block.replace(streamed_block);
         }
 
         archived_segments
     }
 
+    /// Produce segment from all buffer contents followed by the beginning of the streamed block
+    /// (if fits into the segment), see [`Self::process_streamed_block()`]
+    fn produce_streamed_segment(&mut self, streamed_segment_item: Option<SegmentItem>) -> Segment {
+        let mut last_archived_block = self.last_archived_block;
+        let streamed_block_included = streamed_segment_item.is_some();
+
+        let mut segment = Segment::V0 {
+            items: Vec::with_capacity(self.buffer.len() + 1),
+        };
+
+        for segment_item in self.buffer.drain(..).chain(streamed_segment_item) {
+            match &segment_item {
+                SegmentItem::Padding => {
+                    unreachable!("Buffer never contains SegmentItem::Padding; qed");
+                }
+                SegmentItem::Block { .. } => {
+                    // Skip block number increase in case of the very first block
+                    if last_archived_block != INITIAL_LAST_ARCHIVED_BLOCK {
+                        last_archived_block.number += 1;
+                    }
+                    last_archived_block.set_complete();
+                }
+                SegmentItem::BlockStart { bytes, .. } => {
+                    // Skip block number increase in case of the very first block
+                    if last_archived_block != INITIAL_LAST_ARCHIVED_BLOCK {
+                        last_archived_block.number += 1;
+                    }
+                    last_archived_block.set_partial_archived(
+                        u32::try_from(bytes.len())
+                            .expect("Blocks length is never bigger than u32; qed"),
+                    );
+                }
+                SegmentItem::BlockContinuation { bytes, .. } => {
+                    let archived_bytes = last_archived_block.partial_archived().expect(
+                        "Block continuation implies that there are some bytes archived \
+                        already; qed",
+                    );
+                    last_archived_block.set_partial_archived(
+                        archived_bytes
+                            + u32::try_from(bytes.len())
+                                .expect("Blocks length is never bigger than u32; qed"),
+                    );
+                }
+                SegmentItem::ParentSegmentHeader(_) => {
+                    // We are not interested in segment header here
+                }
+            }
+
+            segment.push_item(segment_item);
+        }
+
+        if !streamed_block_included {
+            // Last item in the buffer is always complete
+            last_archived_block.set_complete();
+        }
+
+        self.last_archived_block = last_archived_block;
+
+        segment
+    }
+
     /// Try to slice buffer contents into segments if there is enough data, producing one segment at
     /// a time
     fn produce_segment(&mut self, incremental: bool) -> Option<Segment> {
@@ -610,6 +1100,31 @@ impl Archiver {
 
     // Take segment as an input, apply necessary transformations and produce archived segment
     fn produce_archived_segment(&mut self, segment: Segment) -> NewArchivedSegment {
+        if let Some(observer) = &self.observer {
+            observer.on_segment_started(self.segment_index);
+        }
+        #[cfg(feature = "std")]
+        let started_at = std::time::Instant::now();
+
+        let (segment_items, block_bytes, padding_bytes) = {
+            let Segment::V0 { items } = &segment;
+            let block_bytes = items
+                .iter()
+                .map(|segment_item| match segment_item {
+                    SegmentItem::Block { bytes, .. }
+                    | SegmentItem::BlockStart { bytes, .. }
+                    | SegmentItem::BlockContinuation { bytes, .. } => bytes.len(),
+                    SegmentItem::Padding | SegmentItem::ParentSegmentHeader(_) => 0,
+                })
+                .sum::<usize>();
+
+            (
+                items.len(),
+                block_bytes,
+                RecordedHistorySegment::SIZE - segment.encoded_size(),
+            )
+        };
+
         // Create mappings
         let object_mapping = {
             let mut corrected_object_mapping =
@@ -676,49 +1191,100 @@ impl Archiver {
             // Segment is quite big and no longer necessary
             drop(segment);
 
+            let chunks_per_record = RawRecord::SIZE / Scalar::SAFE_BYTES;
+            // Chunk of `Scalar::SAFE_BYTES` bytes at `record_offset` of the record at
+            // `record_index`
+            let source_chunk = |record_index: usize, record_offset: usize| {
+                let offset = record_index * RawRecord::SIZE + record_offset * Scalar::SAFE_BYTES;
+                Scalar::from(
+                    <&[u8; Scalar::SAFE_BYTES]>::try_from(
+                        &raw_record_shards[offset..][..Scalar::SAFE_BYTES],
+                    )
+                    .expect("Statically known to exist in a record; qed"),
+                )
+            };
+
+            // Chunks at the same offset of all records form independent columns that are extended
+            // to obtain corresponding parity shards
+            let column = |record_offset: usize| {
+                (0..RecordedHistorySegment::NUM_RAW_RECORDS)
+                    .map(move |record_index| source_chunk(record_index, record_offset))
+            };
+
             let mut pieces = ArchivedHistorySegment::default();
 
-            // Scratch buffer to avoid re-allocation
-            let mut tmp_source_shards_scalars =
-                Vec::<Scalar>::with_capacity(RecordedHistorySegment::NUM_RAW_RECORDS);
-            // Iterate over the chunks of `Scalar::SAFE_BYTES` bytes of all records
-            for record_offset in 0..RawRecord::SIZE / Scalar::SAFE_BYTES {
-                // Collect chunks of each record at the same offset
-                raw_record_shards
-                    .array_chunks::<{ RawRecord::SIZE }>()
-                    .map(|record_bytes| {
-                        record_bytes
-                            .array_chunks::<{ Scalar::SAFE_BYTES }>()
-                            .nth(record_offset)
-                            .expect("Statically known to exist in a record; qed")
-                    })
-                    .map(Scalar::from)
-                    .collect_into(&mut tmp_source_shards_scalars);
-
-                // Extend to obtain corresponding parity shards
-                let parity_shards = self
-                    .erasure_coding
-                    .extend(&tmp_source_shards_scalars)
-                    .expect(
+            // Columns are extended one at a time and written into pieces right away, such that only
+            // one column is in memory at a time
+            #[cfg(not(feature = "parallel"))]
+            self.erasure_coding
+                .extend_columns((0..chunks_per_record).map(column))
+                .enumerate()
+                .for_each(|(record_offset, shards)| {
+                    let shards = shards.expect(
                         "Erasure coding instance is deliberately configured to support this \
                         input; qed",
                     );
 
-                let interleaved_input_chunks = tmp_source_shards_scalars
-                    .drain(..)
-                    .zip(parity_shards)
-                    .flat_map(|(a, b)| [a, b]);
-                let output_chunks = pieces.iter_mut().map(|piece| {
-                    piece
-                        .record_mut()
-                        .iter_mut()
-                        .nth(record_offset)
-                        .expect("Statically known to exist in a record; qed")
+                    // Source shards are interleaved with parity shards just like pieces are
+                    shards
+                        .into_iter()
+                        .zip(pieces.iter_mut())
+                        .for_each(|(scalar, piece)| {
+                            piece
+                                .record_mut()
+                                .iter_mut()
+                                .nth(record_offset)
+                                .expect("Statically known to exist in a record; qed")
+                                .copy_from_slice(&scalar.to_bytes());
+                        });
                 });
 
-                interleaved_input_chunks
-                    .zip(output_chunks)
-                    .for_each(|(input, output)| output.copy_from_slice(&input.to_bytes()));
+            // Columns are extended concurrently in batches of one column per thread and written
+            // into pieces after each batch, such that only a bounded number of parity columns is in
+            // memory at a time
+            #[cfg(feature = "parallel")]
+            {
+                let batch_size = rayon::current_num_threads().max(1);
+                for batch_start in (0..chunks_per_record).step_by(batch_size) {
+                    let batch = batch_start..(batch_start + batch_size).min(chunks_per_record);
+
+                    let parity_shards = batch
+                        .clone()
+                        .into_par_iter()
+                        .map(|record_offset| {
+                            let source_shards = column(record_offset).collect::<Vec<_>>();
+
+                            self.erasure_coding.extend(&source_shards).expect(
+                                "Erasure coding instance is deliberately configured to support \
+                                this input; qed",
+                            )
+                        })
+                        .collect::<Vec<_>>();
+
+                    // Source pieces are interleaved with parity pieces: source, parity, source, ...
+                    pieces
+                        .par_iter_mut()
+                        .enumerate()
+                        .for_each(|(position, piece)| {
+                            let record_index = position / 2;
+                            let is_source = position % 2 == 0;
+
+                            piece
+                                .record_mut()
+                                .iter_mut()
+                                .enumerate()
+                                .skip(batch.start)
+                                .take(batch.len())
+                                .for_each(|(record_offset, output)| {
+                                    let scalar = if is_source {
+                                        source_chunk(record_index, record_offset)
+                                    } else {
+                                        parity_shards[record_offset - batch.start][record_index]
+                                    };
+                                    output.copy_from_slice(&scalar.to_bytes());
+                                });
+                        });
+                }
             }
 
             pieces
@@ -792,35 +1358,36 @@ impl Archiver {
         );
 
         // Create witness for every record and write it to corresponding piece.
-        pieces
-            .iter_mut()
-            .zip(record_commitments)
-            .enumerate()
-            .for_each(|(position, (piece, commitment))| {
-                let commitment_bytes = commitment.to_bytes();
-                let (_record, commitment, witness) = piece.split_mut();
-                commitment.copy_from_slice(&commitment_bytes);
-                // TODO: Consider batch witness creation for improved performance
-                witness.copy_from_slice(
-                    &self
-                        .kzg
-                        .create_witness(
-                            &polynomial,
-                            ArchivedHistorySegment::NUM_PIECES,
-                            position as u32,
-                        )
-                        .expect("Position is statically known to be valid; qed")
-                        .to_bytes(),
-                );
-            });
+        #[cfg(not(feature = "parallel"))]
+        let iter = pieces.iter_mut().zip(record_commitments).enumerate();
+        #[cfg(feature = "parallel")]
+        let iter = pieces.par_iter_mut().zip(record_commitments).enumerate();
+
+        iter.for_each(|(position, (piece, commitment))| {
+            let commitment_bytes = commitment.to_bytes();
+            let (_record, commitment, witness) = piece.split_mut();
+            commitment.copy_from_slice(&commitment_bytes);
+            // TODO: Consider batch witness creation for improved performance
+            witness.copy_from_slice(
+                &self
+                    .kzg
+                    .create_witness(
+                        &polynomial,
+                        ArchivedHistorySegment::NUM_PIECES,
+                        position as u32,
+                    )
+                    .expect("Position is statically known to be valid; qed")
+                    .to_bytes(),
+            );
+        });
 
         // Now produce segment header
-        let segment_header = SegmentHeader::V0 {
-            segment_index: self.segment_index,
+        let segment_header = SegmentHeader::latest(
+            self.segment_index,
             segment_commitment,
-            prev_segment_header_hash: self.prev_segment_header_hash,
-            last_archived_block: self.last_archived_block,
-        };
+            self.prev_segment_header_hash,
+            self.last_archived_block,
+        );
 
         // Update state
         self.segment_index += SegmentIndex::ONE;
@@ -831,6 +1398,23 @@ impl Archiver {
         self.buffer
             .push_front(SegmentItem::ParentSegmentHeader(segment_header));
 
+        if let Some(observer) = &self.observer {
+            #[cfg(feature = "std")]
+            let duration = started_at.elapsed();
+            #[cfg(not(feature = "std"))]
+            let duration = Duration::ZERO;
+
+            observer.on_segment_completed(
+                segment_header.segment_index(),
+                SegmentArchivingStats {
+                    duration,
+                    segment_items,
+                    block_bytes,
+                    padding_bytes,
+                },
+            );
+        }
+
         NewArchivedSegment {
             segment_header,
             pieces,
@@ -846,58 +1430,15 @@ pub fn is_piece_valid(
     segment_commitment: &SegmentCommitment,
     position: u32,
 ) -> bool {
-    let (record, commitment, witness) = piece.split();
-    let witness = match Witness::try_from_bytes(witness) {
-        Ok(witness) => witness,
-        _ => {
-            return false;
-        }
-    };
-
-    let mut scalars = Vec::with_capacity(record.len().next_power_of_two());
-
-    for record_chunk in record.iter() {
-        match Scalar::try_from(record_chunk) {
-            Ok(scalar) => {
-                scalars.push(scalar);
-            }
-            _ => {
-                return false;
-            }
-        }
-    }
-
-    // Number of scalars for KZG must be a power of two elements
-    scalars.resize(scalars.capacity(), Scalar::default());
-
-    let polynomial = match kzg.poly(&scalars) {
-        Ok(polynomial) => polynomial,
-        _ => {
-            return false;
-        }
-    };
-
-    if kzg
-        .commit(&polynomial)
-        .map(|commitment| commitment.to_bytes())
-        .as_ref()
-        != Ok(commitment)
-    {
-        return false;
-    }
-
-    let Ok(segment_commitment) = Commitment::try_from(segment_commitment) else {
+    let Some(position) = PiecePosition::new(position) else {
         return false;
     };
 
-    let commitment_hash = blake3_254_hash_to_scalar(commitment.as_ref());
-
-    kzg.verify(
-        &segment_commitment,
-        ArchivedHistorySegment::NUM_PIECES,
-        position,
-        &commitment_hash,
-        &witness,
+    // Only position of the piece within a segment matters for verification
+    piece.is_valid(
+        kzg,
+        segment_commitment,
+        SegmentIndex::ZERO.piece_index(position),
     )
 }
 
@@ -18,5 +18,6 @@
 #![feature(array_chunks, extract_if, iter_collect_into, slice_flatten)]
 
 pub mod archiver;
+pub mod object_reconstructor;
 pub mod piece_reconstructor;
 pub mod reconstructor;
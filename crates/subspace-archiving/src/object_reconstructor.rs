@@ -0,0 +1,216 @@
+extern crate alloc;
+
+use crate::archiver::SegmentItem;
+use alloc::vec::Vec;
+use parity_scale_codec::{Compact, CompactLen, Decode, Input};
+use subspace_core_primitives::crypto::Scalar;
+use subspace_core_primitives::objects::GlobalObject;
+use subspace_core_primitives::{Piece, PieceIndex, PiecePosition, RawRecord, SegmentIndex};
+
+/// Encoded `Segment::V0` enum variant
+const SEGMENT_V0_VARIANT: u8 = 0;
+/// Encoded `SegmentItem::BlockContinuation` enum variant
+const BLOCK_CONTINUATION_VARIANT: u8 = 3;
+/// Max size of compact encoding of object length
+const MAX_OBJECT_LENGTH_ENCODED_SIZE: usize = 9;
+
+/// Object reconstruction error
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+pub enum ObjectReconstructionError {
+    /// Objects are only stored in source pieces, but mapping points to parity piece
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Object mapping points to parity piece {piece_index}")
+    )]
+    NotSourcePiece {
+        /// Piece index from object mapping
+        piece_index: PieceIndex,
+    },
+    /// Offset of the object is outside of the raw record
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Object offset {offset} is outside of raw record")
+    )]
+    InvalidOffset {
+        /// Offset from object mapping
+        offset: u32,
+    },
+    /// Piece getter didn't return piece
+    #[cfg_attr(feature = "thiserror", error("Piece {piece_index} was not found"))]
+    PieceNotFound {
+        /// Piece index that was requested
+        piece_index: PieceIndex,
+    },
+    /// Object length can't be decoded
+    #[cfg_attr(feature = "thiserror", error("Object length can't be decoded"))]
+    InvalidObjectLength,
+    /// Object is larger than allowed
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Object size {object_size} is larger than max object size {max_object_size}")
+    )]
+    ObjectTooLarge {
+        /// Size of the object
+        object_size: u64,
+        /// Max allowed object size
+        max_object_size: usize,
+    },
+    /// Object continues in the next segment, but it doesn't start with parent segment header
+    /// followed by block continuation
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Segment {segment_index} doesn't start with block continuation: {error}")
+    )]
+    InvalidSegmentStart {
+        /// Segment index where object was supposed to continue
+        segment_index: SegmentIndex,
+        /// Low-level error
+        error: parity_scale_codec::Error,
+    },
+}
+
+/// Object reconstructor retrieves objects stored in archived history using global object mappings.
+///
+/// Objects are stored in source pieces as SCALE-encoded byte vectors and can span multiple pieces
+/// and even segments, reconstructor takes care of skipping parity pieces and segment header with
+/// block continuation prefix at the beginning of the next segment when stitching object together.
+///
+/// NOTE: Pieces returned by piece getter are assumed to be verified already.
+#[derive(Debug, Copy, Clone)]
+pub struct ObjectReconstructor {
+    max_object_size: usize,
+}
+
+impl ObjectReconstructor {
+    /// Create new instance that will refuse to reconstruct objects larger than `max_object_size`
+    pub fn new(max_object_size: usize) -> Self {
+        Self { max_object_size }
+    }
+
+    /// Reconstruct object contents (without length prefix) using `piece_getter` to retrieve
+    /// pieces it is stored in
+    pub fn reconstruct_object<PG>(
+        &self,
+        global_object: &GlobalObject,
+        mut piece_getter: PG,
+    ) -> Result<Vec<u8>, ObjectReconstructionError>
+    where
+        PG: FnMut(PieceIndex) -> Option<Piece>,
+    {
+        let mut piece_index = global_object.piece_index();
+        if !piece_index.is_source() {
+            return Err(ObjectReconstructionError::NotSourcePiece { piece_index });
+        }
+        let offset = global_object.offset();
+        if offset as usize >= RawRecord::SIZE {
+            return Err(ObjectReconstructionError::InvalidOffset { offset });
+        }
+
+        let piece = piece_getter(piece_index)
+            .ok_or(ObjectReconstructionError::PieceNotFound { piece_index })?;
+        let mut data = raw_record_bytes(&piece)
+            .skip(offset as usize)
+            .collect::<Vec<_>>();
+
+        let object_size = loop {
+            if let Ok(Compact(object_size)) = Compact::<u64>::decode(&mut data.as_slice()) {
+                break object_size;
+            }
+            if data.len() >= MAX_OBJECT_LENGTH_ENCODED_SIZE {
+                return Err(ObjectReconstructionError::InvalidObjectLength);
+            }
+
+            piece_index = next_source_piece_index(piece_index);
+            append_piece_data(&mut data, piece_index, &mut piece_getter)?;
+        };
+
+        if object_size > self.max_object_size as u64 {
+            return Err(ObjectReconstructionError::ObjectTooLarge {
+                object_size,
+                max_object_size: self.max_object_size,
+            });
+        }
+
+        let prefix_size = Compact::compact_len(&object_size);
+        let encoded_object_size = prefix_size + object_size as usize;
+
+        while data.len() < encoded_object_size {
+            piece_index = next_source_piece_index(piece_index);
+            append_piece_data(&mut data, piece_index, &mut piece_getter)?;
+        }
+
+        data.truncate(encoded_object_size);
+        data.drain(..prefix_size);
+
+        Ok(data)
+    }
+}
+
+/// Source pieces are interleaved with parity pieces and the last source piece of a segment is
+/// followed by the first source piece of the next segment, so the next source piece is always two
+/// pieces away
+fn next_source_piece_index(piece_index: PieceIndex) -> PieceIndex {
+    PieceIndex::from(u64::from(piece_index) + 2)
+}
+
+/// Bytes of the raw record stored in the piece
+fn raw_record_bytes(piece: &Piece) -> impl Iterator<Item = u8> + '_ {
+    piece
+        .record()
+        .iter()
+        .flat_map(|chunk| &chunk[..Scalar::SAFE_BYTES])
+        .copied()
+}
+
+/// Append object data stored in the piece to `data`, stripping segment header and block
+/// continuation prefix if piece is the first in the segment
+fn append_piece_data<PG>(
+    data: &mut Vec<u8>,
+    piece_index: PieceIndex,
+    piece_getter: &mut PG,
+) -> Result<(), ObjectReconstructionError>
+where
+    PG: FnMut(PieceIndex) -> Option<Piece>,
+{
+    let piece = piece_getter(piece_index)
+        .ok_or(ObjectReconstructionError::PieceNotFound { piece_index })?;
+    let raw_record = raw_record_bytes(&piece).collect::<Vec<_>>();
+
+    if piece_index.position() != PiecePosition::ZERO {
+        data.extend_from_slice(&raw_record);
+        return Ok(());
+    }
+
+    let segment_index = piece_index.segment_index();
+    let mut input = raw_record.as_slice();
+    skip_segment_prefix(&mut input).map_err(|error| {
+        ObjectReconstructionError::InvalidSegmentStart {
+            segment_index,
+            error,
+        }
+    })?;
+    data.extend_from_slice(input);
+
+    Ok(())
+}
+
+/// Skip segment variant, parent segment header and block continuation variant with its length at
+/// the beginning of the segment
+fn skip_segment_prefix(input: &mut &[u8]) -> Result<(), parity_scale_codec::Error> {
+    if input.read_byte()? != SEGMENT_V0_VARIANT {
+        return Err("Unexpected segment variant".into());
+    }
+    if !matches!(
+        SegmentItem::decode(input)?,
+        SegmentItem::ParentSegmentHeader(_)
+    ) {
+        return Err("Segment doesn't start with parent segment header".into());
+    }
+    if input.read_byte()? != BLOCK_CONTINUATION_VARIANT {
+        return Err("Parent segment header is not followed by block continuation".into());
+    }
+    Compact::<u32>::decode(input)?;
+
+    Ok(())
+}
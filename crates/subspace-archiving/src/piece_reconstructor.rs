@@ -1,14 +1,19 @@
 extern crate alloc;
 
-use alloc::string::String;
+use crate::archiver::is_piece_valid;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use core::iter;
 use core::num::NonZeroUsize;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 use subspace_core_primitives::crypto::kzg::{Commitment, Kzg, Polynomial};
 use subspace_core_primitives::crypto::{blake3_254_hash_to_scalar, Scalar};
-use subspace_core_primitives::{ArchivedHistorySegment, Piece, RawRecord};
-use subspace_erasure_coding::ErasureCoding;
+use subspace_core_primitives::{
+    ArchivedHistorySegment, Piece, PieceArray, PiecePosition, RawRecord, Record, SegmentCommitment,
+    SegmentIndex,
+};
+use subspace_erasure_coding::{ErasureCoding, RecoveryReport};
 
 /// Reconstructor-related instantiation error.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -69,6 +74,65 @@ impl PiecesReconstructor {
         })
     }
 
+    /// Recover chunks at `record_offset` of records of all pieces using given set of pieces,
+    /// `tmp_shards_scalars` is a scratch buffer to avoid re-allocation
+    fn recover_chunks(
+        &self,
+        input_pieces: &[Option<Piece>],
+        record_offset: usize,
+        tmp_shards_scalars: &mut Vec<Option<Scalar>>,
+    ) -> Result<Vec<Scalar>, ReconstructorError> {
+        tmp_shards_scalars.clear();
+
+        // Collect chunks of each record at the same offset
+        for maybe_piece in input_pieces.iter() {
+            let maybe_scalar = maybe_piece
+                .as_ref()
+                .map(|piece| {
+                    piece
+                        .record()
+                        .iter()
+                        .nth(record_offset)
+                        .expect("Statically guaranteed to exist in a piece; qed")
+                })
+                .map(Scalar::try_from)
+                .transpose()
+                .map_err(ReconstructorError::DataShardsReconstruction)?;
+
+            tmp_shards_scalars.push(maybe_scalar);
+        }
+
+        self.erasure_coding
+            .recover(tmp_shards_scalars)
+            .map_err(ReconstructorError::DataShardsReconstruction)
+    }
+
+    /// Create commitment for recovered record
+    fn commit_record(&self, record: &Record) -> Result<Commitment, ReconstructorError> {
+        let scalars = {
+            let mut scalars = Vec::with_capacity(record.len().next_power_of_two());
+
+            Scalar::try_batch_from_bytes_into(record.as_slice(), &mut scalars)
+                .map_err(|error| ReconstructorError::DataShardsReconstruction(error.to_string()))?;
+
+            // Number of scalars for KZG must be a power of two elements
+            scalars.resize(scalars.capacity(), Scalar::default());
+
+            scalars
+        };
+
+        let polynomial = self
+            .kzg
+            .poly(&scalars)
+            .expect("KZG instance must be configured to support this many scalars; qed");
+        let commitment = self
+            .kzg
+            .commit(&polynomial)
+            .expect("KZG instance must be configured to support this many scalars; qed");
+
+        Ok(commitment)
+    }
+
     /// Returns incomplete pieces (witness missing) and polynomial that can be used to generate
     /// necessary witnesses later.
     fn reconstruct_shards(
@@ -82,27 +146,7 @@ impl PiecesReconstructor {
             Vec::<Option<Scalar>>::with_capacity(ArchivedHistorySegment::NUM_PIECES);
         // Iterate over the chunks of `Scalar::SAFE_BYTES` bytes of all records
         for record_offset in 0..RawRecord::SIZE / Scalar::SAFE_BYTES {
-            // Collect chunks of each record at the same offset
-            for maybe_piece in input_pieces.iter() {
-                let maybe_scalar = maybe_piece
-                    .as_ref()
-                    .map(|piece| {
-                        piece
-                            .record()
-                            .iter()
-                            .nth(record_offset)
-                            .expect("Statically guaranteed to exist in a piece; qed")
-                    })
-                    .map(Scalar::try_from)
-                    .transpose()
-                    .map_err(ReconstructorError::DataShardsReconstruction)?;
-
-                tmp_shards_scalars.push(maybe_scalar);
-            }
-
-            self.erasure_coding
-                .recover(&tmp_shards_scalars)
-                .map_err(ReconstructorError::DataShardsReconstruction)?
+            self.recover_chunks(input_pieces, record_offset, &mut tmp_shards_scalars)?
                 .into_iter()
                 .zip(reconstructed_pieces.iter_mut().map(|piece| {
                     piece
@@ -114,8 +158,6 @@ impl PiecesReconstructor {
                 .for_each(|(source_scalar, segment_data)| {
                     segment_data.copy_from_slice(&source_scalar.to_bytes());
                 });
-
-            tmp_shards_scalars.clear();
         }
 
         let source_record_commitments = {
@@ -132,31 +174,7 @@ impl PiecesReconstructor {
                     Commitment::try_from_bytes(input_piece.commitment())
                         .map_err(|_error| ReconstructorError::InvalidInputPieceCommitment)
                 } else {
-                    let scalars = {
-                        let mut scalars =
-                            Vec::with_capacity(piece.record().len().next_power_of_two());
-
-                        for record_chunk in piece.record().iter() {
-                            scalars.push(
-                                Scalar::try_from(record_chunk)
-                                    .map_err(ReconstructorError::DataShardsReconstruction)?,
-                            );
-                        }
-
-                        // Number of scalars for KZG must be a power of two elements
-                        scalars.resize(scalars.capacity(), Scalar::default());
-
-                        scalars
-                    };
-
-                    let polynomial = self.kzg.poly(&scalars).expect(
-                        "KZG instance must be configured to support this many scalars; qed",
-                    );
-                    let commitment = self.kzg.commit(&polynomial).expect(
-                        "KZG instance must be configured to support this many scalars; qed",
-                    );
-
-                    Ok(commitment)
+                    self.commit_record(piece.record())
                 }
             })
             .collect::<Result<Vec<_>, _>>()?
@@ -222,8 +240,78 @@ impl PiecesReconstructor {
         Ok(pieces)
     }
 
+    /// Same as [`Self::reconstruct_segment()`], but additionally verifies input pieces against
+    /// segment commitment and returns report describing which pieces were provided, which were
+    /// recovered and which were corrupted.
+    ///
+    /// Corrupted pieces (those whose record doesn't match commitment or commitment doesn't match
+    /// segment commitment) are discarded and recovered from the rest of the pieces, which allows
+    /// to identify peers that served bad pieces.
+    pub fn reconstruct_segment_with_report(
+        &self,
+        segment_pieces: &[Option<Piece>],
+        segment_commitment: &SegmentCommitment,
+    ) -> Result<(ArchivedHistorySegment, RecoveryReport), ReconstructorError> {
+        // Verify all pieces in a batch first, only fall back to individual verification to find out
+        // which pieces are corrupted if batch verification fails
+        let present_pieces = segment_pieces
+            .iter()
+            .enumerate()
+            .filter_map(|(position, maybe_piece)| {
+                let piece = maybe_piece.as_ref()?;
+                let position = PiecePosition::new(position as u32)?;
+
+                Some((SegmentIndex::ZERO.piece_index(position), &**piece))
+            })
+            .collect::<Vec<_>>();
+
+        let valid_pieces = if present_pieces.len() == segment_pieces.iter().flatten().count()
+            && PieceArray::verify_segment_pieces(&self.kzg, segment_commitment, &present_pieces)
+                .is_ok()
+        {
+            segment_pieces
+                .iter()
+                .map(|maybe_piece| maybe_piece.as_ref().map(|_piece| true))
+                .collect::<Vec<_>>()
+        } else {
+            #[cfg(not(feature = "parallel"))]
+            let iter = segment_pieces.iter().enumerate();
+            #[cfg(feature = "parallel")]
+            let iter = segment_pieces.par_iter().enumerate();
+
+            iter.map(|(position, maybe_piece)| {
+                maybe_piece.as_ref().map(|piece| {
+                    is_piece_valid(&self.kzg, piece, segment_commitment, position as u32)
+                })
+            })
+            .collect::<Vec<_>>()
+        };
+
+        let mut report = RecoveryReport::new(segment_pieces);
+        let segment_pieces = segment_pieces
+            .iter()
+            .zip(valid_pieces)
+            .enumerate()
+            .map(|(position, (maybe_piece, maybe_valid))| {
+                if maybe_valid == Some(false) {
+                    report.mark_corrupted(position);
+                    None
+                } else {
+                    maybe_piece.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let pieces = self.reconstruct_segment(&segment_pieces)?;
+
+        Ok((pieces, report))
+    }
+
     /// Returns the missing piece for a segment using given set of pieces of a segment of the archived
     /// history (any half of all pieces are required to be present).
+    ///
+    /// Unlike [`Self::reconstruct_segment()`], only the record of the requested piece and records of
+    /// missing source pieces (their commitments are necessary to create witness) are recovered.
     pub fn reconstruct_piece(
         &self,
         segment_pieces: &[Option<Piece>],
@@ -233,10 +321,91 @@ impl PiecesReconstructor {
             return Err(ReconstructorError::IncorrectPiecePosition);
         }
 
-        let (reconstructed_records, polynomial) = self.reconstruct_shards(segment_pieces)?;
+        // Requested piece goes first, followed by missing source pieces
+        let recovered_positions = iter::once(piece_position)
+            .chain(
+                segment_pieces
+                    .iter()
+                    .enumerate()
+                    .step_by(2)
+                    .filter(|&(position, maybe_piece)| {
+                        maybe_piece.is_none() && position != piece_position
+                    })
+                    .map(|(position, _maybe_piece)| position),
+            )
+            .collect::<Vec<_>>();
+        let mut recovered_pieces = recovered_positions
+            .iter()
+            .map(|_position| Piece::default())
+            .collect::<Vec<_>>();
+
+        // Scratch buffer to avoid re-allocation
+        let mut tmp_shards_scalars =
+            Vec::<Option<Scalar>>::with_capacity(ArchivedHistorySegment::NUM_PIECES);
+        // Iterate over the chunks of `Scalar::SAFE_BYTES` bytes of all records
+        for record_offset in 0..RawRecord::SIZE / Scalar::SAFE_BYTES {
+            let recovered_chunks =
+                self.recover_chunks(segment_pieces, record_offset, &mut tmp_shards_scalars)?;
+
+            for (&position, recovered_piece) in
+                recovered_positions.iter().zip(&mut recovered_pieces)
+            {
+                recovered_piece
+                    .record_mut()
+                    .iter_mut()
+                    .nth(record_offset)
+                    .expect("Statically guaranteed to exist in a piece; qed")
+                    .copy_from_slice(&recovered_chunks[position].to_bytes());
+            }
+        }
+
+        let source_record_commitments = {
+            #[cfg(not(feature = "parallel"))]
+            let iter = segment_pieces.iter().enumerate().step_by(2);
+            #[cfg(feature = "parallel")]
+            let iter = segment_pieces.par_iter().enumerate().step_by(2);
+
+            iter.map(|(position, maybe_input_piece)| {
+                if let Some(input_piece) = maybe_input_piece {
+                    Commitment::try_from_bytes(input_piece.commitment())
+                        .map_err(|_error| ReconstructorError::InvalidInputPieceCommitment)
+                } else {
+                    let recovered_piece = recovered_positions
+                        .iter()
+                        .zip(&recovered_pieces)
+                        .find_map(|(&recovered_position, recovered_piece)| {
+                            (recovered_position == position).then_some(recovered_piece)
+                        })
+                        .expect("Records of all missing source pieces were recovered; qed");
+
+                    self.commit_record(recovered_piece.record())
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?
+        };
+        let record_commitments = self
+            .erasure_coding
+            .extend_commitments(&source_record_commitments)
+            .expect(
+                "Erasure coding instance is deliberately configured to support this input; qed",
+            );
+        drop(source_record_commitments);
+
+        let record_commitment_hashes = record_commitments
+            .iter()
+            .map(|commitment| blake3_254_hash_to_scalar(&commitment.to_bytes()))
+            .collect::<Vec<_>>();
+
+        let polynomial = self
+            .kzg
+            .poly(&record_commitment_hashes)
+            .expect("Internally produced values must never fail; qed");
 
-        let mut piece = Piece::from(&reconstructed_records[piece_position]);
+        let mut piece = recovered_pieces.swap_remove(0);
 
+        piece
+            .commitment_mut()
+            .copy_from_slice(&record_commitments[piece_position].to_bytes());
         piece.witness_mut().copy_from_slice(
             &self
                 .kzg
@@ -5,15 +5,24 @@ use rayon::prelude::*;
 use std::assert_matches::assert_matches;
 use std::io::Write;
 use std::iter;
+#[cfg(feature = "parallel")]
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
 use subspace_archiving::archiver;
-use subspace_archiving::archiver::{Archiver, ArchiverInstantiationError, SegmentItem};
+use subspace_archiving::archiver::{
+    Archiver, ArchiverInstantiationError, ArchiverObserver, ArchiverState, NewArchivedSegment,
+    SegmentArchivingStats, SegmentItem, StreamingError,
+};
 use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
 use subspace_core_primitives::crypto::Scalar;
 use subspace_core_primitives::objects::{BlockObject, BlockObjectMapping, PieceObject};
 use subspace_core_primitives::{
     ArchivedBlockProgress, ArchivedHistorySegment, Blake3Hash, LastArchivedBlock, PieceArray,
-    Record, RecordedHistorySegment, SegmentCommitment, SegmentHeader, SegmentIndex,
+    PieceIndex, PieceVerificationError, Record, RecordedHistorySegment, SegmentCommitment,
+    SegmentHeader, SegmentIndex,
 };
+#[cfg(feature = "parallel")]
+use subspace_thread_pool::{ThreadPoolConfig, ThreadPoolManager};
 
 fn extract_data<O: Into<u64>>(data: &[u8], offset: O) -> &[u8] {
     let offset: u64 = offset.into();
@@ -96,6 +105,7 @@ fn archiver() {
     // There is not enough data to produce archived segment yet
     assert!(archiver
         .add_block(block_0.clone(), block_0_object_mapping.clone(), true)
+        .unwrap()
         .is_empty());
 
     let (block_1, block_1_object_mapping) = {
@@ -133,8 +143,9 @@ fn archiver() {
         (block, object_mapping)
     };
     // This should produce 1 archived segment
-    let archived_segments =
-        archiver.add_block(block_1.clone(), block_1_object_mapping.clone(), true);
+    let archived_segments = archiver
+        .add_block(block_1.clone(), block_1_object_mapping.clone(), true)
+        .unwrap();
     assert_eq!(archived_segments.len(), 1);
 
     let first_archived_segment = archived_segments.into_iter().next().unwrap();
@@ -205,14 +216,102 @@ fn archiver() {
         assert!(valid, "Piece at position {position} is valid");
     }
 
+    {
+        let segment_commitment = first_archived_segment.segment_header.segment_commitment();
+        let piece_index = first_archived_segment
+            .segment_header
+            .segment_index()
+            .first_piece_index();
+        let piece = &first_archived_segment.pieces[0];
+        assert_eq!(piece.verify(&kzg, &segment_commitment, piece_index), Ok(()));
+
+        // Wrong position within segment
+        assert_eq!(
+            piece.verify(&kzg, &segment_commitment, piece_index + PieceIndex::ONE),
+            Err(PieceVerificationError::SegmentCommitmentMismatch)
+        );
+
+        // Corrupted record
+        let mut corrupted_piece = *piece;
+        corrupted_piece.record_mut()[0][0] ^= 1;
+        assert_eq!(
+            corrupted_piece.verify(&kzg, &segment_commitment, piece_index),
+            Err(PieceVerificationError::RecordCommitmentMismatch)
+        );
+        assert!(!corrupted_piece.is_valid(&kzg, &segment_commitment, piece_index));
+
+        // Record witness can be verified on its own, without the record
+        let record_witness = piece.witness();
+        assert_eq!(
+            record_witness.verify(
+                &kzg,
+                piece.commitment(),
+                &segment_commitment,
+                piece_index.position()
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            record_witness.verify(
+                &kzg,
+                first_archived_segment.pieces[1].commitment(),
+                &segment_commitment,
+                piece_index.position()
+            ),
+            Err(PieceVerificationError::SegmentCommitmentMismatch)
+        );
+    }
+
+    {
+        let segment_commitment = first_archived_segment.segment_header.segment_commitment();
+        let pieces = first_archived_segment
+            .segment_header
+            .segment_index()
+            .segment_piece_indexes()
+            .into_iter()
+            .zip(first_archived_segment.pieces.iter())
+            // Subset of pieces, like the one that is enough for reconstruction
+            .step_by(2)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            PieceArray::verify_segment_pieces(&kzg, &segment_commitment, &pieces),
+            Ok(())
+        );
+
+        // Wrong position within segment
+        let mut invalid_pieces = pieces.clone();
+        invalid_pieces[1].0 += PieceIndex::ONE;
+        assert_eq!(
+            PieceArray::verify_segment_pieces(&kzg, &segment_commitment, &invalid_pieces),
+            Err(PieceVerificationError::SegmentCommitmentMismatch)
+        );
+
+        // Corrupted record of one of the pieces
+        let mut corrupted_piece = *pieces[3].1;
+        corrupted_piece.record_mut()[0][0] ^= 1;
+        let mut invalid_pieces = pieces.clone();
+        invalid_pieces[3].1 = &corrupted_piece;
+        assert_eq!(
+            PieceArray::verify_segment_pieces(&kzg, &segment_commitment, &invalid_pieces),
+            Err(PieceVerificationError::RecordCommitmentMismatch)
+        );
+
+        // Invalid segment commitment
+        assert_eq!(
+            PieceArray::verify_segment_pieces(&kzg, &SegmentCommitment::default(), &pieces),
+            Err(PieceVerificationError::InvalidSegmentCommitment)
+        );
+    }
+
     let block_2 = {
         let mut block = vec![0u8; RecordedHistorySegment::SIZE * 2];
         thread_rng().fill(block.as_mut_slice());
         block
     };
     // This should be big enough to produce two archived segments in one go
-    let archived_segments =
-        archiver.add_block(block_2.clone(), BlockObjectMapping::default(), true);
+    let archived_segments = archiver
+        .add_block(block_2.clone(), BlockObjectMapping::default(), true)
+        .unwrap();
     assert_eq!(archived_segments.len(), 2);
 
     // Check that initializing archiver with initial state before last block results in the same
@@ -227,11 +326,9 @@ fn archiver() {
         .unwrap();
 
         assert_eq!(
-            archiver_with_initial_state.add_block(
-                block_2.clone(),
-                BlockObjectMapping::default(),
-                true
-            ),
+            archiver_with_initial_state
+                .add_block(block_2.clone(), BlockObjectMapping::default(), true)
+                .unwrap(),
             archived_segments,
         );
     }
@@ -337,8 +434,9 @@ fn archiver() {
         thread_rng().fill(block.as_mut_slice());
         block
     };
-    let archived_segments =
-        archiver.add_block(block_3.clone(), BlockObjectMapping::default(), true);
+    let archived_segments = archiver
+        .add_block(block_3.clone(), BlockObjectMapping::default(), true)
+        .unwrap();
     assert_eq!(archived_segments.len(), 1);
 
     // Check that initializing archiver with initial state before last block results in the same
@@ -353,7 +451,9 @@ fn archiver() {
         .unwrap();
 
         assert_eq!(
-            archiver_with_initial_state.add_block(block_3, BlockObjectMapping::default(), true),
+            archiver_with_initial_state
+                .add_block(block_3, BlockObjectMapping::default(), true)
+                .unwrap(),
             archived_segments,
         );
     }
@@ -474,6 +574,7 @@ fn one_byte_smaller_segment() {
         Archiver::new(kzg.clone())
             .unwrap()
             .add_block(vec![0u8; block_size], BlockObjectMapping::default(), true)
+            .unwrap()
             .len(),
         1
     );
@@ -486,6 +587,7 @@ fn one_byte_smaller_segment() {
             BlockObjectMapping::default(),
             true
         )
+        .unwrap()
         .is_empty());
 }
 
@@ -510,22 +612,25 @@ fn spill_over_edge_case() {
         - 3;
     assert!(archiver
         .add_block(vec![0u8; block_size], BlockObjectMapping::default(), true)
+        .unwrap()
         .is_empty());
 
     // Here we add one more block with internal length that takes 4 bytes in compact length
     // encoding + one more for enum variant, this should result in new segment being created, but
     // the very first segment item will not include newly added block because it would result in
     // subtracting with overflow when trying to slice internal bytes of the segment item
-    let archived_segments = archiver.add_block(
-        vec![0u8; RecordedHistorySegment::SIZE],
-        BlockObjectMapping {
-            objects: vec![BlockObject::V0 {
-                hash: Blake3Hash::default(),
-                offset: 0,
-            }],
-        },
-        true,
-    );
+    let archived_segments = archiver
+        .add_block(
+            vec![0u8; RecordedHistorySegment::SIZE],
+            BlockObjectMapping {
+                objects: vec![BlockObject::V0 {
+                    hash: Blake3Hash::default(),
+                    offset: 0,
+                }],
+            },
+            true,
+        )
+        .unwrap();
     assert_eq!(archived_segments.len(), 2);
     // If spill over actually happened, we'll not find object mapping in the first segment
     assert_eq!(
@@ -551,8 +656,9 @@ fn object_on_the_edge_of_segment() {
     let kzg = Kzg::new(embedded_kzg_settings());
     let mut archiver = Archiver::new(kzg).unwrap();
     let first_block = vec![0u8; RecordedHistorySegment::SIZE];
-    let archived_segments =
-        archiver.add_block(first_block.clone(), BlockObjectMapping::default(), true);
+    let archived_segments = archiver
+        .add_block(first_block.clone(), BlockObjectMapping::default(), true)
+        .unwrap();
     assert_eq!(archived_segments.len(), 1);
     let archived_segment = archived_segments.into_iter().next().unwrap();
     let left_unarchived_from_first_block = first_block.len() as u32
@@ -605,16 +711,19 @@ fn object_on_the_edge_of_segment() {
     // First ensure that any smaller offset will get translated into the first archived segment,
     // this is a protection against code regressions
     {
-        let archived_segments = archiver.clone().add_block(
-            second_block.clone(),
-            BlockObjectMapping {
-                objects: vec![BlockObject::V0 {
-                    hash: object_mapping.hash(),
-                    offset: object_mapping.offset() - 1,
-                }],
-            },
-            true,
-        );
+        let archived_segments = archiver
+            .clone()
+            .add_block(
+                second_block.clone(),
+                BlockObjectMapping {
+                    objects: vec![BlockObject::V0 {
+                        hash: object_mapping.hash(),
+                        offset: object_mapping.offset() - 1,
+                    }],
+                },
+                true,
+            )
+            .unwrap();
 
         assert_eq!(archived_segments.len(), 2);
         assert_eq!(
@@ -627,13 +736,15 @@ fn object_on_the_edge_of_segment() {
         );
     }
 
-    let archived_segments = archiver.add_block(
-        second_block,
-        BlockObjectMapping {
-            objects: vec![object_mapping],
-        },
-        true,
-    );
+    let archived_segments = archiver
+        .add_block(
+            second_block,
+            BlockObjectMapping {
+                objects: vec![object_mapping],
+            },
+            true,
+        )
+        .unwrap();
 
     assert_eq!(archived_segments.len(), 2);
     assert_eq!(
@@ -664,3 +775,429 @@ fn object_on_the_edge_of_segment() {
         mapped_bytes
     );
 }
+
+/// Stream block into archiver in chunks of `chunk_size` bytes, returns archived segments produced
+/// along with the number of bytes fed before the first archived segment was produced
+fn stream_block(
+    archiver: &mut Archiver,
+    block: &[u8],
+    object_mapping: BlockObjectMapping,
+    chunk_size: usize,
+) -> (Vec<NewArchivedSegment>, Option<usize>) {
+    let mut archived_segments = archiver
+        .start_block(block.len() as u32, object_mapping, true)
+        .unwrap();
+    let mut fed_before_first_segment = (!archived_segments.is_empty()).then_some(0);
+
+    let mut fed_bytes = 0;
+    for chunk in block.chunks(chunk_size) {
+        archived_segments.extend(archiver.feed(chunk).unwrap());
+        fed_bytes += chunk.len();
+        if fed_before_first_segment.is_none() && !archived_segments.is_empty() {
+            fed_before_first_segment.replace(fed_bytes);
+        }
+    }
+    assert!(!archiver.is_streaming());
+
+    (archived_segments, fed_before_first_segment)
+}
+
+#[test]
+fn streaming_archiver() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+    let mut streaming_archiver = Archiver::new(kzg).unwrap();
+
+    let random_block = |size: usize| {
+        let mut block = vec![0u8; size];
+        thread_rng().fill(block.as_mut_slice());
+        block
+    };
+
+    let block_0 = random_block(RecordedHistorySegment::SIZE / 2);
+    let block_0_object_mapping = BlockObjectMapping {
+        objects: vec![BlockObject::V0 {
+            hash: Blake3Hash::default(),
+            offset: 10,
+        }],
+    };
+    let block_1 = random_block(RecordedHistorySegment::SIZE * 2 + 123);
+    let block_1_object_mapping = BlockObjectMapping {
+        objects: [100, RecordedHistorySegment::SIZE, block_1.len() - 1]
+            .into_iter()
+            .map(|offset| BlockObject::V0 {
+                hash: Blake3Hash::default(),
+                offset: offset as u32,
+            })
+            .collect(),
+    };
+    let block_2 = random_block(1_000);
+
+    assert!(archiver
+        .add_block(block_0.clone(), block_0_object_mapping.clone(), true)
+        .unwrap()
+        .is_empty());
+    let (archived_segments, _) = stream_block(
+        &mut streaming_archiver,
+        &block_0,
+        block_0_object_mapping,
+        1_000_003,
+    );
+    assert!(archived_segments.is_empty());
+
+    let expected_archived_segments = archiver
+        .add_block(block_1.clone(), block_1_object_mapping.clone(), true)
+        .unwrap();
+    assert_eq!(expected_archived_segments.len(), 2);
+    let (archived_segments, fed_before_first_segment) = stream_block(
+        &mut streaming_archiver,
+        &block_1,
+        block_1_object_mapping,
+        1_000_003,
+    );
+    assert_eq!(archived_segments, expected_archived_segments);
+    // First segment is produced as soon as there is enough data for it, long before the whole
+    // block is fed
+    assert!(fed_before_first_segment.unwrap() <= RecordedHistorySegment::SIZE / 2 + 1_000_003);
+
+    assert_eq!(
+        streaming_archiver.last_archived_block_number(),
+        archiver.last_archived_block_number()
+    );
+
+    // Whole blocks and streamed blocks can be mixed
+    assert_eq!(
+        streaming_archiver
+            .add_block(block_2.clone(), BlockObjectMapping::default(), true)
+            .unwrap(),
+        archiver
+            .add_block(block_2, BlockObjectMapping::default(), true)
+            .unwrap()
+    );
+    let block_3 = random_block(RecordedHistorySegment::SIZE);
+    let expected_archived_segments = archiver
+        .add_block(block_3.clone(), BlockObjectMapping::default(), true)
+        .unwrap();
+    let (archived_segments, _) = stream_block(
+        &mut streaming_archiver,
+        &block_3,
+        BlockObjectMapping::default(),
+        RecordedHistorySegment::SIZE,
+    );
+    assert_eq!(archived_segments, expected_archived_segments);
+}
+
+#[test]
+fn streaming_archiver_spill_over_edge_case() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+    let mut streaming_archiver = Archiver::new(kzg).unwrap();
+
+    // Same sizes as in `spill_over_edge_case`, where not even the beginning of the second block
+    // fits into the first segment
+    let block_size = RecordedHistorySegment::SIZE
+        - 1
+        - 1
+        - Compact::compact_len(&(RecordedHistorySegment::SIZE as u32))
+        - 3;
+    assert!(archiver
+        .add_block(vec![0u8; block_size], BlockObjectMapping::default(), true)
+        .unwrap()
+        .is_empty());
+    let (archived_segments, _) = stream_block(
+        &mut streaming_archiver,
+        &vec![0u8; block_size],
+        BlockObjectMapping::default(),
+        block_size / 3,
+    );
+    assert!(archived_segments.is_empty());
+
+    let object_mapping = BlockObjectMapping {
+        objects: vec![BlockObject::V0 {
+            hash: Blake3Hash::default(),
+            offset: 0,
+        }],
+    };
+    let expected_archived_segments = archiver
+        .add_block(
+            vec![0u8; RecordedHistorySegment::SIZE],
+            object_mapping.clone(),
+            true,
+        )
+        .unwrap();
+    assert_eq!(expected_archived_segments.len(), 2);
+    let (archived_segments, fed_before_first_segment) = stream_block(
+        &mut streaming_archiver,
+        &vec![0u8; RecordedHistorySegment::SIZE],
+        object_mapping,
+        RecordedHistorySegment::SIZE / 4,
+    );
+    assert_eq!(archived_segments, expected_archived_segments);
+    // The first segment doesn't depend on the contents of the block
+    assert_eq!(fed_before_first_segment, Some(0));
+}
+
+#[test]
+fn streaming_archiver_invalid_usage() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg).unwrap();
+
+    assert_eq!(
+        archiver.feed(&[0u8; 10]),
+        Err(StreamingError::NoBlockInProgress)
+    );
+
+    assert!(archiver
+        .start_block(10, BlockObjectMapping::default(), true)
+        .unwrap()
+        .is_empty());
+    assert!(archiver.feed(&[0u8; 4]).unwrap().is_empty());
+    assert_eq!(
+        archiver.start_block(10, BlockObjectMapping::default(), true),
+        Err(StreamingError::BlockInProgress { missing_bytes: 6 })
+    );
+    assert_eq!(
+        archiver.add_block(vec![0u8; 10], BlockObjectMapping::default(), true),
+        Err(StreamingError::BlockInProgress { missing_bytes: 6 })
+    );
+    assert_eq!(
+        archiver.feed(&[0u8; 7]),
+        Err(StreamingError::TooManyBytes { excess_bytes: 1 })
+    );
+    assert!(archiver.feed(&[0u8; 6]).unwrap().is_empty());
+    assert!(!archiver.is_streaming());
+    assert_eq!(
+        archiver.feed(&[0u8; 1]),
+        Err(StreamingError::NoBlockInProgress)
+    );
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn dedicated_thread_pool() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+    let mut dedicated_archiver = Archiver::new(kzg.clone()).unwrap();
+    dedicated_archiver.set_thread_pool(Arc::new(
+        ThreadPoolManager::new(NonZeroUsize::new(2).unwrap())
+            .build(ThreadPoolConfig::new(
+                "archiver",
+                NonZeroUsize::new(2).unwrap(),
+            ))
+            .unwrap(),
+    ));
+
+    let mut block = vec![0u8; RecordedHistorySegment::SIZE];
+    thread_rng().fill(block.as_mut_slice());
+
+    let archived_segments = archiver
+        .add_block(block.clone(), BlockObjectMapping::default(), true)
+        .unwrap();
+    assert_eq!(archived_segments.len(), 1);
+    assert_eq!(
+        dedicated_archiver
+            .add_block(block, BlockObjectMapping::default(), true)
+            .unwrap(),
+        archived_segments
+    );
+
+    let archived_segment = archived_segments.into_iter().next().unwrap();
+    assert!(archived_segment
+        .pieces
+        .par_iter()
+        .enumerate()
+        .all(|(position, piece)| {
+            archiver::is_piece_valid(
+                &kzg,
+                piece,
+                &archived_segment.segment_header.segment_commitment(),
+                position as u32,
+            )
+        }));
+}
+
+#[test]
+fn state_snapshot() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+
+    let random_block = |size: usize| {
+        let mut block = vec![0u8; size];
+        thread_rng().fill(block.as_mut_slice());
+        block
+    };
+    let object_mapping = |offset: u32| BlockObjectMapping {
+        objects: vec![BlockObject::V0 {
+            hash: Blake3Hash::default(),
+            offset,
+        }],
+    };
+
+    // State of fresh archiver
+    {
+        let state = archiver.state();
+        assert_eq!(
+            ArchiverState::decode(&mut state.encode().as_slice()).unwrap(),
+            state
+        );
+        let mut restored_archiver = Archiver::from_state(kzg.clone(), state).unwrap();
+        assert_eq!(restored_archiver.last_archived_block_number(), None);
+
+        let block = random_block(RecordedHistorySegment::SIZE / 3);
+        assert_eq!(
+            restored_archiver
+                .add_block(block.clone(), object_mapping(10), true)
+                .unwrap(),
+            archiver
+                .clone()
+                .add_block(block, object_mapping(10), true)
+                .unwrap(),
+        );
+    }
+
+    assert!(archiver
+        .add_block(
+            random_block(RecordedHistorySegment::SIZE / 3),
+            object_mapping(100),
+            true
+        )
+        .unwrap()
+        .is_empty());
+    // Part of this block will be buffered as block continuation after segment header
+    assert_eq!(
+        archiver
+            .add_block(
+                random_block(RecordedHistorySegment::SIZE),
+                object_mapping(RecordedHistorySegment::SIZE as u32 - 100),
+                true
+            )
+            .unwrap()
+            .len(),
+        1
+    );
+    let streamed_block = random_block(RecordedHistorySegment::SIZE / 2);
+    archiver
+        .start_block(streamed_block.len() as u32, object_mapping(1_000), true)
+        .unwrap();
+    archiver.feed(&streamed_block[..1_000]).unwrap();
+
+    let state = archiver.state();
+    assert_eq!(state.last_archived_block().number, 1);
+    let mut restored_archiver = Archiver::from_state(
+        kzg,
+        ArchiverState::decode(&mut state.encode().as_slice()).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(restored_archiver.state(), state);
+
+    // Restored archiver must produce identical segments, including object mappings
+    assert_eq!(
+        restored_archiver.feed(&streamed_block[1_000..]).unwrap(),
+        archiver.feed(&streamed_block[1_000..]).unwrap()
+    );
+    let block = random_block(RecordedHistorySegment::SIZE);
+    let archived_segments = archiver
+        .add_block(block.clone(), BlockObjectMapping::default(), true)
+        .unwrap();
+    assert_eq!(archived_segments.len(), 1);
+    assert_eq!(
+        restored_archiver
+            .add_block(block, BlockObjectMapping::default(), true)
+            .unwrap(),
+        archived_segments
+    );
+    assert!(archived_segments[0]
+        .object_mapping
+        .iter()
+        .any(|piece_object_mapping| !piece_object_mapping.objects.is_empty()));
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum ArchiverEvent {
+    BlockBuffered(u32),
+    SegmentStarted(SegmentIndex),
+    SegmentCompleted(SegmentIndex, SegmentArchivingStats),
+}
+
+#[derive(Debug, Default)]
+struct RecordingObserver {
+    events: Mutex<Vec<ArchiverEvent>>,
+}
+
+impl ArchiverObserver for RecordingObserver {
+    fn on_block_buffered(&self, block_size: u32) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(ArchiverEvent::BlockBuffered(block_size));
+    }
+
+    fn on_segment_started(&self, segment_index: SegmentIndex) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(ArchiverEvent::SegmentStarted(segment_index));
+    }
+
+    fn on_segment_completed(&self, segment_index: SegmentIndex, stats: SegmentArchivingStats) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(ArchiverEvent::SegmentCompleted(segment_index, stats));
+    }
+}
+
+#[test]
+fn archiver_observer() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg).unwrap();
+    let observer = Arc::new(RecordingObserver::default());
+    archiver.set_observer(observer.clone());
+
+    let block_size = RecordedHistorySegment::SIZE / 3 * 2;
+    assert!(archiver
+        .add_block(vec![0u8; block_size], BlockObjectMapping::default(), true)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        observer
+            .events
+            .lock()
+            .unwrap()
+            .drain(..)
+            .collect::<Vec<_>>(),
+        vec![ArchiverEvent::BlockBuffered(block_size as u32)]
+    );
+
+    let archived_segments = archiver
+        .add_block(vec![0u8; block_size], BlockObjectMapping::default(), true)
+        .unwrap();
+    assert_eq!(archived_segments.len(), 1);
+
+    let events = observer
+        .events
+        .lock()
+        .unwrap()
+        .drain(..)
+        .collect::<Vec<_>>();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0], ArchiverEvent::BlockBuffered(block_size as u32));
+    assert_eq!(events[1], ArchiverEvent::SegmentStarted(SegmentIndex::ZERO));
+    let ArchiverEvent::SegmentCompleted(segment_index, stats) = events[2] else {
+        panic!("Expected segment completion, got {:?}", events[2]);
+    };
+    assert_eq!(segment_index, SegmentIndex::ZERO);
+    // Full first block and the beginning of the second block
+    assert_eq!(stats.segment_items, 2);
+    assert_eq!(
+        stats.block_bytes,
+        block_size
+            + archived_segments[0]
+                .segment_header
+                .last_archived_block()
+                .partial_archived()
+                .unwrap() as usize
+    );
+    // Second block spills over into the next segment, so there is no padding
+    assert_eq!(stats.padding_bytes, 0);
+}
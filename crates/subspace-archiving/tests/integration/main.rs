@@ -1,5 +1,6 @@
 #![feature(assert_matches)]
 
 mod archiver;
+mod object_reconstruction;
 mod piece_reconstruction;
 mod reconstructor;
@@ -0,0 +1,169 @@
+use parity_scale_codec::Encode;
+use rand::{thread_rng, Rng};
+use std::collections::HashMap;
+use subspace_archiving::archiver::{Archiver, NewArchivedSegment};
+use subspace_archiving::object_reconstructor::{ObjectReconstructionError, ObjectReconstructor};
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::objects::{BlockObject, BlockObjectMapping, GlobalObject};
+use subspace_core_primitives::{Blake3Hash, Piece, PieceIndex, RawRecord, RecordedHistorySegment};
+
+/// Create random block with objects of given sizes stored at given offsets
+fn block_with_objects(
+    size: usize,
+    objects: &[(usize, usize)],
+) -> (Vec<u8>, BlockObjectMapping, Vec<Vec<u8>>) {
+    let mut block = vec![0u8; size];
+    thread_rng().fill(block.as_mut_slice());
+
+    let mut object_mapping = BlockObjectMapping::default();
+    let mut contents = Vec::with_capacity(objects.len());
+    for &(offset, object_size) in objects {
+        let mut object = vec![0u8; object_size];
+        thread_rng().fill(object.as_mut_slice());
+
+        let encoded_object = object.encode();
+        block[offset..][..encoded_object.len()].copy_from_slice(&encoded_object);
+
+        object_mapping.objects.push(BlockObject::V0 {
+            hash: Blake3Hash::default(),
+            offset: offset as u32,
+        });
+        contents.push(object);
+    }
+
+    (block, object_mapping, contents)
+}
+
+fn global_objects(archived_segment: &NewArchivedSegment) -> Vec<GlobalObject> {
+    let first_piece_index = archived_segment
+        .segment_header
+        .segment_index()
+        .first_piece_index();
+
+    archived_segment
+        .object_mapping
+        .iter()
+        .enumerate()
+        .flat_map(|(source_position, piece_object_mapping)| {
+            let piece_index =
+                PieceIndex::from(u64::from(first_piece_index) + source_position as u64 * 2);
+
+            piece_object_mapping
+                .objects
+                .iter()
+                .map(move |piece_object| GlobalObject::V0 {
+                    piece_index,
+                    offset: piece_object.offset(),
+                })
+        })
+        .collect()
+}
+
+#[test]
+fn object_reconstruction() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg).unwrap();
+
+    // Object that spans multiple pieces within a segment
+    let (block_0, block_0_object_mapping, block_0_objects) = block_with_objects(
+        RecordedHistorySegment::SIZE / 2,
+        &[(RecordedHistorySegment::SIZE / 4 - 10, RawRecord::SIZE * 2)],
+    );
+    // Object that spans segment boundary and object fully inside of the second segment
+    let (block_1, block_1_object_mapping, block_1_objects) = block_with_objects(
+        RecordedHistorySegment::SIZE * 2,
+        &[
+            (RecordedHistorySegment::SIZE / 2 - 1_000, 5_000),
+            (RecordedHistorySegment::SIZE + 100, 100),
+        ],
+    );
+
+    assert!(archiver
+        .add_block(block_0, block_0_object_mapping, true)
+        .unwrap()
+        .is_empty());
+    let archived_segments = archiver
+        .add_block(block_1, block_1_object_mapping, true)
+        .unwrap();
+    assert_eq!(archived_segments.len(), 2);
+
+    let pieces = archived_segments
+        .iter()
+        .flat_map(|archived_segment| {
+            let first_piece_index = archived_segment
+                .segment_header
+                .segment_index()
+                .first_piece_index();
+
+            archived_segment
+                .pieces
+                .iter()
+                .enumerate()
+                .map(move |(position, piece)| {
+                    (
+                        PieceIndex::from(u64::from(first_piece_index) + position as u64),
+                        Piece::from(piece),
+                    )
+                })
+        })
+        .collect::<HashMap<_, _>>();
+    let global_objects = archived_segments
+        .iter()
+        .flat_map(global_objects)
+        .collect::<Vec<_>>();
+    assert_eq!(global_objects.len(), 3);
+
+    let object_reconstructor = ObjectReconstructor::new(RecordedHistorySegment::SIZE);
+    for (global_object, expected_object) in global_objects
+        .iter()
+        .zip(block_0_objects.iter().chain(&block_1_objects))
+    {
+        let object = object_reconstructor
+            .reconstruct_object(global_object, |piece_index| {
+                pieces.get(&piece_index).cloned()
+            })
+            .unwrap();
+        assert_eq!(&object, expected_object);
+    }
+
+    // Object larger than allowed
+    assert_eq!(
+        ObjectReconstructor::new(1_000).reconstruct_object(&global_objects[0], |piece_index| {
+            pieces.get(&piece_index).cloned()
+        }),
+        Err(ObjectReconstructionError::ObjectTooLarge {
+            object_size: RawRecord::SIZE as u64 * 2,
+            max_object_size: 1_000,
+        })
+    );
+
+    // Second piece of the object is not available
+    let missing_piece_index = PieceIndex::from(u64::from(global_objects[0].piece_index()) + 2);
+    assert_eq!(
+        object_reconstructor.reconstruct_object(&global_objects[0], |piece_index| {
+            if piece_index == missing_piece_index {
+                None
+            } else {
+                pieces.get(&piece_index).cloned()
+            }
+        }),
+        Err(ObjectReconstructionError::PieceNotFound {
+            piece_index: missing_piece_index
+        })
+    );
+
+    // Objects are never stored in parity pieces
+    let parity_piece_index = PieceIndex::from(u64::from(global_objects[0].piece_index()) + 1);
+    assert_eq!(
+        object_reconstructor.reconstruct_object(
+            &GlobalObject::V0 {
+                piece_index: parity_piece_index,
+                offset: 0,
+            },
+            |piece_index| pieces.get(&piece_index).cloned()
+        ),
+        Err(ObjectReconstructionError::NotSourcePiece {
+            piece_index: parity_piece_index
+        })
+    );
+}
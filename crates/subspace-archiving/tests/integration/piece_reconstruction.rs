@@ -1,11 +1,13 @@
+use rand::seq::SliceRandom;
 use rand::Rng;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
-use subspace_archiving::archiver::Archiver;
+use subspace_archiving::archiver::{is_piece_valid, Archiver};
 use subspace_archiving::piece_reconstructor::{PiecesReconstructor, ReconstructorError};
 use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
 use subspace_core_primitives::objects::BlockObjectMapping;
 use subspace_core_primitives::{ArchivedHistorySegment, FlatPieces, Piece, RecordedHistorySegment};
+use subspace_erasure_coding::ShardStatus;
 
 fn pieces_to_option_of_pieces(pieces: &FlatPieces) -> Vec<Option<Piece>> {
     pieces.iter().map(Piece::from).map(Some).collect()
@@ -25,7 +27,9 @@ fn segment_reconstruction_works() {
 
     let block = get_random_block();
 
-    let archived_segments = archiver.add_block(block, BlockObjectMapping::default(), true);
+    let archived_segments = archiver
+        .add_block(block, BlockObjectMapping::default(), true)
+        .unwrap();
 
     assert_eq!(archived_segments.len(), 1);
 
@@ -66,7 +70,9 @@ fn piece_reconstruction_works() {
     // Block that fits into the segment fully
     let block = get_random_block();
 
-    let archived_segments = archiver.add_block(block, BlockObjectMapping::default(), true);
+    let archived_segments = archiver
+        .add_block(block, BlockObjectMapping::default(), true)
+        .unwrap();
 
     assert_eq!(archived_segments.len(), 1);
 
@@ -104,6 +110,128 @@ fn piece_reconstruction_works() {
     }
 }
 
+#[test]
+fn piece_reconstruction_from_half_of_pieces_works() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+
+    let archived_segments = archiver
+        .add_block(get_random_block(), BlockObjectMapping::default(), true)
+        .unwrap();
+
+    assert_eq!(archived_segments.len(), 1);
+    let archived_segment = archived_segments.into_iter().next().unwrap();
+
+    // Keep exactly half of the pieces, such that both source and parity pieces are missing
+    let mut maybe_pieces = pieces_to_option_of_pieces(&archived_segment.pieces);
+    let mut positions = (0..ArchivedHistorySegment::NUM_PIECES).collect::<Vec<_>>();
+    positions.shuffle(&mut rand::thread_rng());
+    let missing_positions = &positions[..ArchivedHistorySegment::NUM_PIECES / 2];
+    for &position in missing_positions {
+        maybe_pieces[position].take();
+    }
+
+    let reconstructor = PiecesReconstructor::new(kzg.clone()).unwrap();
+
+    let missing_source_position = *missing_positions
+        .iter()
+        .find(|&position| position % 2 == 0)
+        .unwrap();
+    let missing_parity_position = *missing_positions
+        .iter()
+        .find(|&position| position % 2 == 1)
+        .unwrap();
+
+    for position in [missing_source_position, missing_parity_position] {
+        let reconstructed_piece = reconstructor
+            .reconstruct_piece(&maybe_pieces, position)
+            .unwrap();
+
+        assert_eq!(
+            reconstructed_piece,
+            Piece::from(&archived_segment.pieces[position])
+        );
+        assert!(is_piece_valid(
+            &kzg,
+            &reconstructed_piece,
+            &archived_segment.segment_header.segment_commitment(),
+            position as u32,
+        ));
+    }
+
+    // Not enough pieces once one more is missing
+    maybe_pieces[positions[ArchivedHistorySegment::NUM_PIECES / 2]].take();
+    assert!(matches!(
+        reconstructor.reconstruct_piece(&maybe_pieces, missing_source_position),
+        Err(ReconstructorError::DataShardsReconstruction(..))
+    ));
+}
+
+#[test]
+fn segment_reconstruction_with_report_works() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+
+    let block = get_random_block();
+
+    let archived_segments = archiver
+        .add_block(block, BlockObjectMapping::default(), true)
+        .unwrap();
+
+    assert_eq!(archived_segments.len(), 1);
+
+    let archived_segment = archived_segments.into_iter().next().unwrap();
+    let segment_commitment = archived_segment.segment_header.segment_commitment();
+    let mut maybe_pieces = pieces_to_option_of_pieces(&archived_segment.pieces);
+
+    // Remove some pieces from the array
+    maybe_pieces
+        .iter_mut()
+        .skip(100)
+        .take(30)
+        .for_each(|piece| {
+            piece.take();
+        });
+
+    let reconstructor = PiecesReconstructor::new(kzg).unwrap();
+
+    // No corrupted pieces
+    let (_flat_pieces, report) = reconstructor
+        .reconstruct_segment_with_report(&maybe_pieces, &segment_commitment)
+        .unwrap();
+    assert_eq!(report.corrupted().count(), 0);
+    assert_eq!(
+        report.recovered().collect::<Vec<_>>(),
+        (100..130).collect::<Vec<_>>()
+    );
+
+    // Corrupt record of one piece and commitment of another
+    maybe_pieces[5].as_mut().unwrap().record_mut()[0] = [0; 32];
+    maybe_pieces[6].as_mut().unwrap().commitment_mut()[0] ^= 1;
+
+    let (flat_pieces, report) = reconstructor
+        .reconstruct_segment_with_report(&maybe_pieces, &segment_commitment)
+        .unwrap();
+
+    archived_segment
+        .pieces
+        .iter()
+        .zip(flat_pieces.iter())
+        .for_each(|(original_piece, reconstructed_piece)| {
+            assert_eq!(original_piece, reconstructed_piece);
+        });
+
+    assert_eq!(report.corrupted().collect::<Vec<_>>(), vec![5, 6]);
+    assert_eq!(
+        report.recovered().collect::<Vec<_>>(),
+        [5, 6].into_iter().chain(100..130).collect::<Vec<_>>()
+    );
+    assert_eq!(report.status(0), Some(ShardStatus::Provided));
+    assert_eq!(report.status(100), Some(ShardStatus::Recovered));
+    assert!(!report.is_valid(5));
+    assert!(report.is_valid(100));
+}
+
 #[test]
 fn segment_reconstruction_fails() {
     let kzg = Kzg::new(embedded_kzg_settings());
@@ -126,7 +254,9 @@ fn segment_reconstruction_fails() {
     // Block that fits into the segment fully
     let block = get_random_block();
 
-    let archived_segments = archiver.add_block(block, BlockObjectMapping::default(), true);
+    let archived_segments = archiver
+        .add_block(block, BlockObjectMapping::default(), true)
+        .unwrap();
 
     assert_eq!(archived_segments.len(), 1);
 
@@ -163,7 +293,9 @@ fn piece_reconstruction_fails() {
     // Block that fits into the segment fully
     let block = get_random_block();
 
-    let archived_segments = archiver.add_block(block, BlockObjectMapping::default(), true);
+    let archived_segments = archiver
+        .add_block(block, BlockObjectMapping::default(), true)
+        .unwrap();
 
     assert_eq!(archived_segments.len(), 1);
 
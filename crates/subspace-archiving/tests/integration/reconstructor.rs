@@ -50,11 +50,28 @@ fn basic() {
     };
     let archived_segments = archiver
         .add_block(block_0.clone(), BlockObjectMapping::default(), true)
+        .unwrap()
         .into_iter()
-        .chain(archiver.add_block(block_1.clone(), BlockObjectMapping::default(), true))
-        .chain(archiver.add_block(block_2.clone(), BlockObjectMapping::default(), true))
-        .chain(archiver.add_block(block_3.clone(), BlockObjectMapping::default(), true))
-        .chain(archiver.add_block(block_4, BlockObjectMapping::default(), true))
+        .chain(
+            archiver
+                .add_block(block_1.clone(), BlockObjectMapping::default(), true)
+                .unwrap(),
+        )
+        .chain(
+            archiver
+                .add_block(block_2.clone(), BlockObjectMapping::default(), true)
+                .unwrap(),
+        )
+        .chain(
+            archiver
+                .add_block(block_3.clone(), BlockObjectMapping::default(), true)
+                .unwrap(),
+        )
+        .chain(
+            archiver
+                .add_block(block_4, BlockObjectMapping::default(), true)
+                .unwrap(),
+        )
         .collect::<Vec<_>>();
 
     assert_eq!(archived_segments.len(), 5);
@@ -258,8 +275,13 @@ fn partial_data() {
     };
     let archived_segments = archiver
         .add_block(block_0.clone(), BlockObjectMapping::default(), true)
+        .unwrap()
         .into_iter()
-        .chain(archiver.add_block(block_1, BlockObjectMapping::default(), true))
+        .chain(
+            archiver
+                .add_block(block_1, BlockObjectMapping::default(), true)
+                .unwrap(),
+        )
         .collect::<Vec<_>>();
 
     assert_eq!(archived_segments.len(), 1);
@@ -332,7 +354,9 @@ fn invalid_usage() {
         block
     };
 
-    let archived_segments = archiver.add_block(block_0, BlockObjectMapping::default(), true);
+    let archived_segments = archiver
+        .add_block(block_0, BlockObjectMapping::default(), true)
+        .unwrap();
 
     assert_eq!(archived_segments.len(), 4);
 
@@ -27,11 +27,82 @@ use alloc::vec::Vec;
 use core::cmp::Ordering;
 use core::hash::{Hash, Hasher};
 use core::mem;
-use derive_more::{AsMut, AsRef, Deref, DerefMut, From, Into};
+use derive_more::{AsMut, AsRef, Deref, DerefMut, Display, From, Into};
 use parity_scale_codec::{Decode, Encode, EncodeLike, Input, MaxEncodedLen};
 use rust_kzg_blst::types::fr::FsFr;
 use scale_info::{Type, TypeInfo};
 
+/// Streaming BLAKE3 hasher.
+///
+/// Allows hashing values incrementally, for example large objects that are not fully in memory,
+/// produces the same hash as one-shot functions like [`blake3_hash()`] for the same input.
+#[derive(Debug, Default, Clone)]
+pub struct Blake3Hasher(blake3::Hasher);
+
+impl Blake3Hasher {
+    /// Create new hasher in default mode
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create new hasher in keyed mode, equivalent to [`blake3_hash_with_key()`]
+    #[inline]
+    pub fn new_keyed(key: &[u8; 32]) -> Self {
+        Self(blake3::Hasher::new_keyed(key))
+    }
+
+    /// Add more data to the hasher
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        self.0.update(data);
+        self
+    }
+
+    /// Add more data to the hasher in parallel (only useful for large values well above 128kiB)
+    #[cfg(feature = "parallel")]
+    #[inline]
+    pub fn update_parallel(&mut self, data: &[u8]) -> &mut Self {
+        self.0.update_rayon(data);
+        self
+    }
+
+    /// Hash of all data added so far, more data can be added after this
+    #[inline]
+    pub fn finalize(&self) -> Blake3Hash {
+        *self.0.finalize().as_bytes()
+    }
+
+    /// Reset hasher to the initial state, key (if any) is preserved
+    #[inline]
+    pub fn reset(&mut self) -> &mut Self {
+        self.0.reset();
+        self
+    }
+}
+
+/// Allows to hash SCALE-encoded values without encoding them into intermediate buffer
+impl parity_scale_codec::Output for Blake3Hasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Write for Blake3Hasher {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 /// BLAKE3 hashing of a single value.
 pub fn blake3_hash(data: &[u8]) -> Blake3Hash {
     *blake3::hash(data).as_bytes()
@@ -40,9 +111,7 @@ pub fn blake3_hash(data: &[u8]) -> Blake3Hash {
 /// BLAKE3 hashing of a single value in parallel (only useful for large values well above 128kiB).
 #[cfg(feature = "parallel")]
 pub fn blake3_hash_parallel(data: &[u8]) -> Blake3Hash {
-    let mut state = blake3::Hasher::new();
-    state.update_rayon(data);
-    *state.finalize().as_bytes()
+    Blake3Hasher::new().update_parallel(data).finalize()
 }
 
 /// BLAKE3 keyed hashing of a single value.
@@ -52,11 +121,30 @@ pub fn blake3_hash_with_key(key: &[u8; 32], data: &[u8]) -> Blake3Hash {
 
 /// BLAKE3 hashing of a list of values.
 pub fn blake3_hash_list(data: &[&[u8]]) -> Blake3Hash {
-    let mut state = blake3::Hasher::new();
+    let mut hasher = Blake3Hasher::new();
     for d in data {
-        state.update(d);
+        hasher.update(d);
     }
-    *state.finalize().as_bytes()
+    hasher.finalize()
+}
+
+/// BLAKE3 keyed hashing of a list of values.
+pub fn blake3_hash_list_with_key(key: &[u8; 32], data: &[&[u8]]) -> Blake3Hash {
+    let mut hasher = Blake3Hasher::new_keyed(key);
+    for d in data {
+        hasher.update(d);
+    }
+    hasher.finalize()
+}
+
+/// BLAKE3 hashing of SCALE-encoded value without encoding it into intermediate buffer.
+pub fn blake3_hash_encoded<T>(value: &T) -> Blake3Hash
+where
+    T: Encode + ?Sized,
+{
+    let mut hasher = Blake3Hasher::new();
+    value.encode_to(&mut hasher);
+    hasher.finalize()
 }
 
 /// BLAKE3 hashing of a single value truncated to 254 bits.
@@ -70,6 +158,20 @@ pub fn blake3_254_hash_to_scalar(data: &[u8]) -> Scalar {
         .expect("Last bit erased, thus hash is guaranteed to fit into scalar; qed")
 }
 
+/// Error happening during batch conversion of bytes into scalars, see
+/// [`Scalar::try_batch_from_bytes()`]
+#[derive(Debug, Clone, Eq, PartialEq, Display)]
+#[display(fmt = "Invalid scalar bytes at index {index}: {error}")]
+pub struct BatchError {
+    /// Index of the first element that failed to convert
+    pub index: usize,
+    /// Conversion error
+    pub error: String,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BatchError {}
+
 /// Representation of a single BLS12-381 scalar value.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, From, Into, AsRef, AsMut, Deref, DerefMut)]
 #[repr(transparent)]
@@ -153,7 +255,9 @@ mod scalar_serde {
 
     // Custom wrapper so we don't have to write serialization/deserialization code manually
     #[derive(Serialize, Deserialize)]
-    struct Scalar(#[serde(with = "hex::serde")] pub(super) [u8; super::Scalar::FULL_BYTES]);
+    struct Scalar(
+        #[serde(with = "crate::serde::hex_bytes")] pub(super) [u8; super::Scalar::FULL_BYTES],
+    );
 
     impl Serialize for super::Scalar {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -239,6 +343,52 @@ impl Scalar {
         self.into()
     }
 
+    /// Convert a batch of bytes into scalars, error contains index of the first invalid element
+    pub fn try_batch_from_bytes(bytes: &[[u8; Self::FULL_BYTES]]) -> Result<Vec<Self>, BatchError> {
+        let mut scalars = Vec::with_capacity(bytes.len());
+        Self::try_batch_from_bytes_into(bytes, &mut scalars)?;
+        Ok(scalars)
+    }
+
+    /// Same as [`Self::try_batch_from_bytes()`], but appends scalars to provided vector, which
+    /// allows to reuse allocations and to reserve extra capacity upfront (for instance when the
+    /// number of scalars will need to be padded to a power of two afterwards).
+    ///
+    /// In case of error vector contains successfully converted scalars that precede invalid
+    /// element.
+    pub fn try_batch_from_bytes_into(
+        bytes: &[[u8; Self::FULL_BYTES]],
+        scalars: &mut Vec<Self>,
+    ) -> Result<(), BatchError> {
+        scalars.reserve(bytes.len());
+        for (index, bytes) in bytes.iter().enumerate() {
+            scalars.push(Self::try_from(bytes).map_err(|error| BatchError { index, error })?);
+        }
+
+        Ok(())
+    }
+
+    /// Convert a batch of scalars into bytes, inverse of [`Self::try_batch_from_bytes()`]
+    pub fn batch_to_bytes(scalars: &[Self]) -> Vec<[u8; Self::FULL_BYTES]> {
+        scalars.iter().map(Self::to_bytes).collect()
+    }
+
+    /// Same as [`Self::batch_to_bytes()`], but writes bytes into provided output.
+    ///
+    /// Panics if lengths of `scalars` and `output` don't match.
+    pub fn batch_to_bytes_into(scalars: &[Self], output: &mut [[u8; Self::FULL_BYTES]]) {
+        assert_eq!(
+            scalars.len(),
+            output.len(),
+            "Number of scalars must match size of output"
+        );
+
+        scalars
+            .iter()
+            .zip(output)
+            .for_each(|(scalar, output)| *output = scalar.to_bytes());
+    }
+
     /// Convenient conversion from slice of scalar to underlying representation for efficiency
     /// purposes.
     #[inline]
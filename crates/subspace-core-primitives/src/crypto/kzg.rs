@@ -5,19 +5,25 @@ mod tests;
 
 extern crate alloc;
 
-use crate::crypto::Scalar;
+use crate::crypto::{blake3_hash_with_key, Scalar};
+use crate::Blake3Hash;
 use alloc::collections::btree_map::Entry;
 use alloc::collections::BTreeMap;
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 use alloc::vec::Vec;
+use alloc::{format, vec};
 use core::mem;
+use core::ops::Range;
 use derive_more::{AsMut, AsRef, Deref, DerefMut, From, Into};
 use kzg::eip_4844::{BYTES_PER_G1, BYTES_PER_G2};
-use kzg::{FFTFr, FFTSettings, Fr, KZGSettings, G1, G2};
+use kzg::{FFTFr, FFTSettings, Fr, G1Mul, G2Mul, KZGSettings, G1, G2};
 #[cfg(feature = "std")]
 use parking_lot::Mutex;
+use rust_kzg_blst::consts::SCALE2_ROOT_OF_UNITY;
+use rust_kzg_blst::kzg_proofs::pairings_verify;
 use rust_kzg_blst::types::fft_settings::FsFFTSettings;
+use rust_kzg_blst::types::fr::FsFr;
 use rust_kzg_blst::types::g1::FsG1;
 use rust_kzg_blst::types::g2::FsG2;
 use rust_kzg_blst::types::kzg_settings::FsKZGSettings;
@@ -38,6 +44,95 @@ pub const NUM_G1_POWERS: usize = 32_768;
 /// Number of G2 powers stored in [`EMBEDDED_KZG_SETTINGS_BYTES`]
 pub const NUM_G2_POWERS: usize = 65;
 
+/// Root of unity at `index` in the evaluation domain of `num_values` values (rounded up to the
+/// next power of two), `None` if index is outside of the domain.
+///
+/// Same as using expanded roots of unity of [`FsFFTSettings`], but without heap allocations.
+fn root_of_unity_at(num_values: usize, index: u32) -> Option<FsFr> {
+    let num_values = num_values.next_power_of_two();
+    if index as usize >= num_values {
+        return None;
+    }
+
+    let root_of_unity = FsFr::from_u64_arr(SCALE2_ROOT_OF_UNITY.get(num_values.ilog2() as usize)?);
+    Some(root_of_unity.pow(index as usize))
+}
+
+/// Coefficient for aggregating values number `index` in verification (Fiat-Shamir heuristic),
+/// `seed` must be derived from all of the values that are being aggregated
+pub(crate) fn aggregation_coefficient(seed: &Blake3Hash, index: usize) -> Scalar {
+    let mut hash = blake3_hash_with_key(seed, &(index as u64).to_le_bytes());
+    // Erase last 2 bits to effectively truncate the hash (number is interpreted as little-endian)
+    hash[31] &= 0b00111111;
+    Scalar::try_from(hash)
+        .expect("Last bit erased, thus hash is guaranteed to fit into scalar; qed")
+}
+
+/// Coefficients (from the lowest degree) of the polynomial that is zero at all of the `points`
+fn vanishing_polynomial(points: &[FsFr]) -> Vec<FsFr> {
+    let mut coefficients = Vec::with_capacity(points.len() + 1);
+    coefficients.push(FsFr::one());
+    for point in points {
+        // Multiply by `X - point`
+        coefficients.push(FsFr::zero());
+        for degree in (0..coefficients.len()).rev() {
+            let shifted = match degree.checked_sub(1) {
+                Some(lower_degree) => coefficients[lower_degree],
+                None => FsFr::zero(),
+            };
+            coefficients[degree] = shifted.sub(&coefficients[degree].mul(point));
+        }
+    }
+    coefficients
+}
+
+/// Quotient of the division of `dividend` by monic `divisor` (coefficients from the lowest
+/// degree), remainder is discarded
+fn divide_by_monic(dividend: &[FsFr], divisor: &[FsFr]) -> Vec<FsFr> {
+    let divisor_degree = divisor.len() - 1;
+    let Some(quotient_len) = (dividend.len() + 1).checked_sub(divisor.len()) else {
+        return Vec::new();
+    };
+
+    let mut remainder = dividend.to_vec();
+    let mut quotient = vec![FsFr::zero(); quotient_len];
+    for degree in (0..quotient_len).rev() {
+        let coefficient = remainder[degree + divisor_degree];
+        quotient[degree] = coefficient;
+        for (offset, divisor_coefficient) in divisor.iter().enumerate() {
+            remainder[degree + offset] =
+                remainder[degree + offset].sub(&coefficient.mul(divisor_coefficient));
+        }
+    }
+    quotient
+}
+
+/// Coefficients (from the lowest degree) of the polynomial of the lowest degree that evaluates to
+/// `values` at distinct `points` (Lagrange interpolation)
+fn interpolate(points: &[FsFr], values: &[FsFr]) -> Vec<FsFr> {
+    let vanishing = vanishing_polynomial(points);
+    let mut coefficients = vec![FsFr::zero(); points.len()];
+
+    for (index, (point, value)) in points.iter().zip(values).enumerate() {
+        // `vanishing / (X - point)` is zero at all other points
+        let basis = divide_by_monic(&vanishing, &[point.negate(), FsFr::one()]);
+        let denominator = points
+            .iter()
+            .enumerate()
+            .filter(|&(other_index, _)| other_index != index)
+            .fold(FsFr::one(), |denominator, (_, other_point)| {
+                denominator.mul(&point.sub(other_point))
+            });
+        let scale = value.mul(&denominator.inverse());
+
+        for (coefficient, basis_coefficient) in coefficients.iter_mut().zip(&basis) {
+            *coefficient = coefficient.add(&scale.mul(basis_coefficient));
+        }
+    }
+
+    coefficients
+}
+
 // Symmetric function is present in tests
 /// Function turns bytes into `FsKZGSettings`, it is up to the user to ensure that bytes make sense,
 /// otherwise result can be very wrong (but will not panic).
@@ -113,6 +208,20 @@ impl Commitment {
     /// Commitment size in bytes.
     const SIZE: usize = 48;
 
+    /// Linear combination of commitments with corresponding coefficients.
+    ///
+    /// Commitments are additively homomorphic, so the result is a commitment to the same linear
+    /// combination of polynomials behind `commitments`. Extra commitments or coefficients are
+    /// ignored.
+    pub fn linear_combination(commitments: &[Self], coefficients: &[Scalar]) -> Self {
+        let mut result = FsG1::identity();
+        for (commitment, coefficient) in commitments.iter().zip(coefficients) {
+            result = result.add_or_dbl(&commitment.0.mul(coefficient));
+        }
+
+        Self(result)
+    }
+
     /// Convert commitment to raw bytes
     #[inline]
     pub fn to_bytes(&self) -> [u8; Self::SIZE] {
@@ -346,6 +455,35 @@ impl TryFrom<[u8; Self::SIZE]> for Witness {
     }
 }
 
+/// Proof that a contiguous range of values are evaluations of the polynomial behind a commitment
+/// at consecutive indices.
+///
+/// Allows to verify a small subset of values (for example a few chunks of a record) against a
+/// commitment without having access to all of the values the commitment was created from. This is
+/// a multi-point opening with a single witness (commitment to the quotient of the polynomial by
+/// the vanishing polynomial of the range), so the proof has the same size and verification costs
+/// one pairing check regardless of the number of values in the range.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RangeProof {
+    /// Index of the first value in the range, number of values is implied by the values the proof
+    /// is verified against
+    pub first_index: u32,
+    /// Witness for all values in the range
+    pub witness: Witness,
+}
+
+/// Evaluation of the polynomial at specific index along with its witness, used for aggregated
+/// verification with [`Kzg::verify_aggregated()`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Evaluation {
+    /// Index of the value
+    pub index: u32,
+    /// Value at above index
+    pub value: Scalar,
+    /// Witness for above value
+    pub witness: Witness,
+}
+
 #[derive(Debug)]
 struct Inner {
     kzg_settings: FsKZGSettings,
@@ -411,6 +549,8 @@ impl Kzg {
 
     /// Verifies that `value` is the evaluation at `index` of the polynomial created from
     /// `num_values` values matching the `commitment`.
+    ///
+    /// Doesn't allocate on the heap.
     pub fn verify(
         &self,
         commitment: &Commitment,
@@ -419,14 +559,10 @@ impl Kzg {
         value: &Scalar,
         witness: &Witness,
     ) -> bool {
-        let fft_settings = match self.get_fft_settings(num_values) {
-            Ok(fft_settings) => fft_settings,
-            Err(error) => {
-                debug!(error, "Failed to derive fft settings");
-                return false;
-            }
+        let Some(x) = root_of_unity_at(num_values, index) else {
+            debug!(num_values, index, "Invalid index for number of values");
+            return false;
         };
-        let x = fft_settings.get_expanded_roots_of_unity_at(index as usize);
 
         match self
             .inner
@@ -441,6 +577,221 @@ impl Kzg {
         }
     }
 
+    /// Max number of values a single [`RangeProof`] can cover, limited by the number of G2 powers
+    /// in KZG settings
+    pub fn max_range_proof_values(&self) -> usize {
+        self.inner.kzg_settings.secret_g2.len().saturating_sub(1)
+    }
+
+    /// Points at indices in `range` of the evaluation domain of `num_values` values, `None` if
+    /// range is empty, too long for [`RangeProof`] or outside of the domain
+    fn range_points(&self, num_values: usize, range: Range<u32>) -> Option<Vec<FsFr>> {
+        if range.is_empty()
+            || range.len() > self.max_range_proof_values()
+            || range.end as usize > num_values
+        {
+            return None;
+        }
+
+        range
+            .map(|index| root_of_unity_at(num_values, index))
+            .collect()
+    }
+
+    /// Computes a [`RangeProof`] of evaluations of `polynomial` at indices in `range`
+    pub fn create_range_proof(
+        &self,
+        polynomial: &Polynomial,
+        num_values: usize,
+        range: Range<u32>,
+    ) -> Result<RangeProof, String> {
+        let Some(points) = self.range_points(num_values, range.clone()) else {
+            return Err(format!(
+                "Invalid range {range:?} for polynomial of {num_values} values, at most {} values \
+                are supported",
+                self.max_range_proof_values()
+            ));
+        };
+
+        // Remainder of the division is the interpolation polynomial of the values in the range,
+        // so the quotient is what verifier checks the commitment against
+        let quotient = FsPoly {
+            coeffs: divide_by_monic(&polynomial.0.coeffs, &vanishing_polynomial(&points)),
+        };
+
+        Ok(RangeProof {
+            first_index: range.start,
+            witness: Witness(self.inner.kzg_settings.commit_to_poly(&quotient)?),
+        })
+    }
+
+    /// Verifies that `values` are evaluations at consecutive indices starting with
+    /// `proof.first_index` of the polynomial created from `num_values` values matching the
+    /// `commitment`.
+    pub fn verify_range_proof(
+        &self,
+        commitment: &Commitment,
+        num_values: usize,
+        values: &[Scalar],
+        proof: &RangeProof,
+    ) -> bool {
+        let range = u32::try_from(values.len())
+            .ok()
+            .and_then(|len| Some(proof.first_index..proof.first_index.checked_add(len)?));
+        let Some(points) = range.and_then(|range| self.range_points(num_values, range)) else {
+            debug!(
+                first_index = proof.first_index,
+                num_values,
+                values = values.len(),
+                "Range proof doesn't match values"
+            );
+            return false;
+        };
+
+        // Check is `e(C - [I(s)]G1, G2) == e(W, [Z(s)]G2)`, where `I` is interpolation polynomial
+        // of the values and `Z` is vanishing polynomial of the range
+        let interpolation = FsPoly {
+            coeffs: interpolate(&points, Scalar::slice_to_repr(values)),
+        };
+        let interpolation_commitment = match self.inner.kzg_settings.commit_to_poly(&interpolation)
+        {
+            Ok(interpolation_commitment) => interpolation_commitment,
+            Err(error) => {
+                debug!(error, "Failed to commit to interpolation polynomial");
+                return false;
+            }
+        };
+        let vanishing_commitment = vanishing_polynomial(&points)
+            .iter()
+            .zip(&self.inner.kzg_settings.secret_g2)
+            .map(|(coefficient, secret_g2)| secret_g2.mul(coefficient))
+            .reduce(|sum, term| sum.add_or_dbl(&term))
+            .expect("Range is not empty, hence vanishing polynomial is not empty; qed");
+
+        pairings_verify(
+            &commitment.0.sub(&interpolation_commitment),
+            &FsG2::generator(),
+            &proof.witness.0,
+            &vanishing_commitment,
+        )
+    }
+
+    /// Verifies that all `evaluations` are evaluations of the polynomial created from `num_values`
+    /// values matching the `commitment` using a single aggregated pairing check.
+    ///
+    /// This is much faster than [`Self::verify()`] for every evaluation individually, but doesn't
+    /// tell which evaluation is invalid in case of failure. Empty list of evaluations is valid.
+    pub fn verify_aggregated(
+        &self,
+        commitment: &Commitment,
+        num_values: usize,
+        evaluations: &[Evaluation],
+    ) -> bool {
+        self.verify_evaluations(commitment, num_values, evaluations.iter().copied())
+    }
+
+    /// Verifies a batch of `values` at `indices` with their `witnesses` against the `commitment`
+    /// to polynomial created from `num_values` values, same as [`Self::verify_aggregated()`], but
+    /// for inputs stored in separate slices.
+    ///
+    /// Returns `false` if `indices`, `values` and `witnesses` have different lengths.
+    pub fn verify_batch(
+        &self,
+        commitment: &Commitment,
+        num_values: usize,
+        indices: &[u32],
+        values: &[Scalar],
+        witnesses: &[Witness],
+    ) -> bool {
+        if indices.len() != values.len() || indices.len() != witnesses.len() {
+            debug!(
+                indices = indices.len(),
+                values = values.len(),
+                witnesses = witnesses.len(),
+                "Batch inputs have different lengths"
+            );
+            return false;
+        }
+
+        let evaluations =
+            indices
+                .iter()
+                .zip(values.iter().zip(witnesses))
+                .map(|(&index, (&value, &witness))| Evaluation {
+                    index,
+                    value,
+                    witness,
+                });
+
+        self.verify_evaluations(commitment, num_values, evaluations)
+    }
+
+    /// Implementation of [`Self::verify_aggregated()`] that takes evaluations as an iterator, such
+    /// that evaluations don't need to be collected when stored differently
+    fn verify_evaluations<I>(
+        &self,
+        commitment: &Commitment,
+        num_values: usize,
+        evaluations: I,
+    ) -> bool
+    where
+        I: Iterator<Item = Evaluation> + Clone,
+    {
+        if evaluations.clone().next().is_none() {
+            return true;
+        }
+
+        // Coefficients are derived from all inputs, such that they can't be known in advance
+        let seed = {
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(&commitment.to_bytes());
+            hasher.update(&(num_values as u64).to_le_bytes());
+            for evaluation in evaluations.clone() {
+                hasher.update(&evaluation.index.to_le_bytes());
+                hasher.update(&evaluation.value.to_bytes());
+                hasher.update(&evaluation.witness.to_bytes());
+            }
+            *hasher.finalize().as_bytes()
+        };
+
+        // Individual check is `e(C - [y]G1, G2) == e(W, [s - x]G2)`, random linear combination of
+        // all of them is `e(sum(r)*C - [sum(r*y)]G1 + sum(r*x*W), G2) == e(sum(r*W), [s]G2)`
+        let mut coefficients_sum = FsFr::zero();
+        let mut weighted_values_sum = FsFr::zero();
+        let mut weighted_witnesses_sum = FsG1::identity();
+        let mut weighted_shifted_witnesses_sum = FsG1::identity();
+        for (evaluation_index, evaluation) in evaluations.enumerate() {
+            let Some(x) = root_of_unity_at(num_values, evaluation.index) else {
+                debug!(
+                    num_values,
+                    index = evaluation.index,
+                    "Invalid index for number of values"
+                );
+                return false;
+            };
+            let coefficient = FsFr::from(aggregation_coefficient(&seed, evaluation_index));
+
+            coefficients_sum = coefficients_sum.add(&coefficient);
+            weighted_values_sum = weighted_values_sum.add(&coefficient.mul(&evaluation.value));
+            let weighted_witness = evaluation.witness.0.mul(&coefficient);
+            weighted_shifted_witnesses_sum =
+                weighted_shifted_witnesses_sum.add_or_dbl(&weighted_witness.mul(&x));
+            weighted_witnesses_sum = weighted_witnesses_sum.add_or_dbl(&weighted_witness);
+        }
+
+        let lhs = commitment
+            .0
+            .mul(&coefficients_sum)
+            .sub(&FsG1::generator().mul(&weighted_values_sum))
+            .add_or_dbl(&weighted_shifted_witnesses_sum);
+        let Some(secret_g2) = self.inner.kzg_settings.secret_g2.get(1) else {
+            debug!("KZG settings don't contain enough G2 powers");
+            return false;
+        };
+
+        pairings_verify(&lhs, &FsG2::generator(), &weighted_witnesses_sum, secret_g2)
+    }
+
     /// Get FFT settings for specified number of values, uses internal cache to avoid derivation
     /// every time.
     pub fn get_fft_settings(&self, num_values: usize) -> Result<Arc<FsFFTSettings>, String> {
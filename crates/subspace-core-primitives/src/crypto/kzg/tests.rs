@@ -1,5 +1,9 @@
-use crate::crypto::kzg::{embedded_kzg_settings, Kzg};
+use crate::crypto::kzg::{
+    embedded_kzg_settings, root_of_unity_at, Commitment, Evaluation, Kzg, RangeProof, NUM_G2_POWERS,
+};
 use crate::crypto::Scalar;
+use kzg::{FFTSettings, Fr};
+use rust_kzg_blst::types::fr::FsFr;
 
 #[test]
 fn basic() {
@@ -24,3 +28,246 @@ fn basic() {
         );
     }
 }
+
+#[test]
+fn roots_of_unity() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+
+    for num_values in [1, 2, 7, 256, 1 << 16] {
+        let fft_settings = kzg.get_fft_settings(num_values).unwrap();
+
+        for index in [0, 1, num_values as u32 / 2, num_values as u32 - 1] {
+            assert_eq!(
+                root_of_unity_at(num_values, index),
+                Some(fft_settings.get_expanded_roots_of_unity_at(index as usize)),
+                "failed on index {index} of {num_values} values"
+            );
+        }
+        assert_eq!(
+            root_of_unity_at(num_values, num_values.next_power_of_two() as u32),
+            None
+        );
+    }
+}
+
+#[test]
+fn range_proof() {
+    let values = (0..16)
+        .map(|_| Scalar::from(rand::random::<[u8; Scalar::SAFE_BYTES]>()))
+        .collect::<Vec<_>>();
+
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let polynomial = kzg.poly(&values).unwrap();
+    let commitment = kzg.commit(&polynomial).unwrap();
+
+    let num_values = values.len();
+
+    let proof = kzg
+        .create_range_proof(&polynomial, num_values, 3..7)
+        .unwrap();
+    assert!(kzg.verify_range_proof(&commitment, num_values, &values[3..7], &proof));
+
+    // Values from a different range
+    assert!(!kzg.verify_range_proof(&commitment, num_values, &values[4..8], &proof));
+    // Not enough values
+    assert!(!kzg.verify_range_proof(&commitment, num_values, &values[3..6], &proof));
+    // Modified value
+    let mut modified_values = values[3..7].to_vec();
+    modified_values[2] = Scalar::from(rand::random::<[u8; Scalar::SAFE_BYTES]>());
+    assert!(!kzg.verify_range_proof(&commitment, num_values, &modified_values, &proof));
+    // Range outside of polynomial
+    let shifted_proof = RangeProof {
+        first_index: 14,
+        ..proof
+    };
+    assert!(!kzg.verify_range_proof(&commitment, num_values, &values[3..7], &shifted_proof));
+
+    // Single value and the whole polynomial
+    let proof = kzg
+        .create_range_proof(&polynomial, num_values, 15..16)
+        .unwrap();
+    assert!(kzg.verify_range_proof(&commitment, num_values, &values[15..], &proof));
+    let proof = kzg
+        .create_range_proof(&polynomial, num_values, 0..16)
+        .unwrap();
+    assert!(kzg.verify_range_proof(&commitment, num_values, &values, &proof));
+
+    assert!(kzg
+        .create_range_proof(&polynomial, num_values, 5..5)
+        .is_err());
+    assert!(kzg
+        .create_range_proof(&polynomial, num_values, 10..17)
+        .is_err());
+}
+
+#[test]
+fn range_proof_max_values() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let max_values = kzg.max_range_proof_values();
+    assert_eq!(max_values, NUM_G2_POWERS - 1);
+
+    let values = (0..max_values * 2)
+        .map(|_| Scalar::from(rand::random::<[u8; Scalar::SAFE_BYTES]>()))
+        .collect::<Vec<_>>();
+    let polynomial = kzg.poly(&values).unwrap();
+    let commitment = kzg.commit(&polynomial).unwrap();
+    let num_values = values.len();
+
+    let range = 1..max_values as u32 + 1;
+    let proof = kzg
+        .create_range_proof(&polynomial, num_values, range.clone())
+        .unwrap();
+    assert!(kzg.verify_range_proof(
+        &commitment,
+        num_values,
+        &values[range.start as usize..range.end as usize],
+        &proof
+    ));
+
+    // Range proof can't cover more values than there are G2 powers available
+    assert!(kzg
+        .create_range_proof(&polynomial, num_values, 0..max_values as u32 + 1)
+        .is_err());
+    assert!(!kzg.verify_range_proof(&commitment, num_values, &values[..max_values + 1], &proof));
+}
+
+#[test]
+fn aggregated_verification() {
+    let values = (0..16)
+        .map(|_| Scalar::from(rand::random::<[u8; Scalar::SAFE_BYTES]>()))
+        .collect::<Vec<_>>();
+
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let polynomial = kzg.poly(&values).unwrap();
+    let commitment = kzg.commit(&polynomial).unwrap();
+
+    let num_values = values.len();
+
+    let evaluations = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let index = index as u32;
+            Evaluation {
+                index,
+                value: *value,
+                witness: kzg.create_witness(&polynomial, num_values, index).unwrap(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    assert!(kzg.verify_aggregated(&commitment, num_values, &evaluations));
+    assert!(kzg.verify_aggregated(&commitment, num_values, &evaluations[5..9]));
+    assert!(kzg.verify_aggregated(&commitment, num_values, &[]));
+
+    // Wrong value
+    let mut invalid_evaluations = evaluations.clone();
+    invalid_evaluations[3].value = values[4];
+    assert!(!kzg.verify_aggregated(&commitment, num_values, &invalid_evaluations));
+    // Swapped witnesses that individually don't match, but might cancel out without randomization
+    let mut invalid_evaluations = evaluations.clone();
+    invalid_evaluations.swap(1, 2);
+    invalid_evaluations[1].index = 1;
+    invalid_evaluations[2].index = 2;
+    assert!(!kzg.verify_aggregated(&commitment, num_values, &invalid_evaluations));
+    // Index outside of polynomial
+    let mut invalid_evaluations = evaluations.clone();
+    invalid_evaluations[0].index = 16;
+    assert!(!kzg.verify_aggregated(&commitment, num_values, &invalid_evaluations));
+    // Different commitment
+    let other_commitment = kzg.commit(&kzg.poly(&values[..8]).unwrap()).unwrap();
+    assert!(!kzg.verify_aggregated(&other_commitment, num_values, &evaluations));
+}
+
+#[test]
+fn batch_verification() {
+    let values = (0..16)
+        .map(|_| Scalar::from(rand::random::<[u8; Scalar::SAFE_BYTES]>()))
+        .collect::<Vec<_>>();
+
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let polynomial = kzg.poly(&values).unwrap();
+    let commitment = kzg.commit(&polynomial).unwrap();
+
+    let num_values = values.len();
+
+    let indices = [0, 3, 7, 15];
+    let batch_values = indices
+        .iter()
+        .map(|&index| values[index as usize])
+        .collect::<Vec<_>>();
+    let witnesses = indices
+        .iter()
+        .map(|&index| kzg.create_witness(&polynomial, num_values, index).unwrap())
+        .collect::<Vec<_>>();
+
+    assert!(kzg.verify_batch(&commitment, num_values, &indices, &batch_values, &witnesses));
+    assert!(kzg.verify_batch(&commitment, num_values, &[], &[], &[]));
+
+    // Wrong value
+    let mut invalid_values = batch_values.clone();
+    invalid_values[2] = values[8];
+    assert!(!kzg.verify_batch(
+        &commitment,
+        num_values,
+        &indices,
+        &invalid_values,
+        &witnesses
+    ));
+    // Mismatched lengths
+    assert!(!kzg.verify_batch(
+        &commitment,
+        num_values,
+        &indices,
+        &batch_values[..3],
+        &witnesses
+    ));
+    assert!(!kzg.verify_batch(
+        &commitment,
+        num_values,
+        &indices,
+        &batch_values,
+        &witnesses[..3]
+    ));
+}
+
+#[test]
+fn commitments_linear_combination() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+
+    let records = (0..3)
+        .map(|_| {
+            (0..8)
+                .map(|_| Scalar::from(rand::random::<[u8; Scalar::SAFE_BYTES]>()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let commitments = records
+        .iter()
+        .map(|values| kzg.commit(&kzg.poly(values).unwrap()).unwrap())
+        .collect::<Vec<_>>();
+    let coefficients = (0..3)
+        .map(|_| Scalar::from(rand::random::<[u8; Scalar::SAFE_BYTES]>()))
+        .collect::<Vec<_>>();
+
+    let combined_values = (0..8)
+        .map(|index| {
+            records
+                .iter()
+                .zip(&coefficients)
+                .fold(FsFr::zero(), |sum, (values, coefficient)| {
+                    sum.add(&coefficient.mul(&values[index]))
+                })
+        })
+        .map(Scalar::from)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        Commitment::linear_combination(&commitments, &coefficients),
+        kzg.commit(&kzg.poly(&combined_values).unwrap()).unwrap()
+    );
+    assert_ne!(
+        Commitment::linear_combination(&commitments, &coefficients[..2]),
+        kzg.commit(&kzg.poly(&combined_values).unwrap()).unwrap()
+    );
+}
@@ -0,0 +1,60 @@
+//! [`Arbitrary`] implementations for data structures that can't derive it, either because they
+//! are too large to be created on the stack, wrap foreign types or only allow a subset of values.
+
+use crate::crypto::Scalar;
+use crate::segments::ArchivedHistorySegment;
+use crate::{FlatPieces, Piece, PieceArray, PiecePosition};
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Max number of pieces in arbitrary [`FlatPieces`], pieces are large and bigger numbers would
+/// make fuzzing impractically slow
+const MAX_ARBITRARY_FLAT_PIECES: usize = 4;
+
+impl<'a> Arbitrary<'a> for Scalar {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::from(&<[u8; Self::SAFE_BYTES]>::arbitrary(u)?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <[u8; Self::SAFE_BYTES]>::size_hint(depth)
+    }
+}
+
+/// NOTE: This is a stack-allocated data structure and can cause stack overflow! Use [`Piece`]
+/// instead where possible.
+impl<'a> Arbitrary<'a> for PieceArray {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut piece_array = Self::default();
+        u.fill_buffer(piece_array.as_mut())?;
+        Ok(piece_array)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Piece {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut piece = Self::default();
+        u.fill_buffer(piece.as_mut())?;
+        Ok(piece)
+    }
+}
+
+impl<'a> Arbitrary<'a> for FlatPieces {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut pieces = Self::new(u.int_in_range(0..=MAX_ARBITRARY_FLAT_PIECES)?);
+        for piece in pieces.iter_mut() {
+            u.fill_buffer(piece.as_mut())?;
+        }
+        Ok(pieces)
+    }
+}
+
+impl<'a> Arbitrary<'a> for PiecePosition {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let position = u.int_in_range(0..=ArchivedHistorySegment::NUM_PIECES as u32 - 1)?;
+        Ok(Self::new(position).expect("Position is within archived segment; qed"))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        u32::size_hint(depth)
+    }
+}
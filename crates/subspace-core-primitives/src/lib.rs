@@ -19,6 +19,7 @@
 #![warn(rust_2018_idioms, missing_docs)]
 #![cfg_attr(feature = "std", warn(missing_debug_implementations))]
 #![feature(
+    allocator_api,
     array_chunks,
     const_option,
     const_trait_impl,
@@ -31,39 +32,52 @@
 
 pub mod checksum;
 pub mod crypto;
+#[cfg(feature = "arbitrary")]
+mod fuzzing;
 pub mod objects;
 mod pieces;
+mod pot_checkpoints;
+mod sectors;
 mod segments;
 #[cfg(feature = "serde")]
 mod serde;
+mod solutions;
 #[cfg(test)]
 mod tests;
 
 extern crate alloc;
 
-use crate::crypto::{blake3_hash, blake3_hash_list, blake3_hash_with_key, Scalar};
+use crate::crypto::{blake3_hash, blake3_hash_encoded, blake3_hash_list, Scalar};
 #[cfg(feature = "serde")]
 use ::serde::{Deserialize, Serialize};
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::convert::AsRef;
-use core::fmt;
 use core::iter::Iterator;
-use core::num::{NonZeroU64, NonZeroU8};
-use core::simd::Simd;
+use core::num::{NonZeroU32, NonZeroU8};
 use core::str::FromStr;
+use core::{fmt, iter, mem};
 use derive_more::{Add, AsMut, AsRef, Deref, DerefMut, Display, Div, From, Into, Mul, Rem, Sub};
 use num_traits::{WrappingAdd, WrappingSub};
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+#[cfg(feature = "rkyv")]
+pub use pieces::ArchivedPieceArray;
 pub use pieces::{
-    ChunkWitness, FlatPieces, Piece, PieceArray, PieceIndex, PieceOffset, RawRecord, Record,
-    RecordCommitment, RecordWitness, SBucket,
+    BoundedFlatPieces, ChunkWitness, FlatPieces, FlatPiecesView, FlatPiecesViewMut,
+    PageAlignedAllocator, Piece, PieceArray, PieceCheckError, PieceIndex, PieceOffset,
+    PiecePosition, PieceVerificationError, RawRecord, Record, RecordChunksView, RecordCommitment,
+    RecordWitness, SBucket,
 };
+pub use pot_checkpoints::{CompactPotCheckpoints, CompactPotCheckpointsError, SlotPotCheckpoints};
 use scale_info::TypeInfo;
+pub use sectors::{SectorId, SectorSlotChallenge};
 pub use segments::{
     ArchivedHistorySegment, HistorySize, RecordedHistorySegment, SegmentCommitment, SegmentIndex,
 };
-use static_assertions::{const_assert, const_assert_eq};
+#[cfg(feature = "rkyv")]
+pub use segments::{ArchivedSegmentCommitment, ArchivedSegmentIndex};
+pub use solutions::{SolutionBuilder, SolutionBuilderError, SolutionShapeError};
+use static_assertions::const_assert;
 
 // Refuse to compile on lower than 32-bit platforms
 const_assert!(core::mem::size_of::<usize>() >= core::mem::size_of::<u32>());
@@ -96,9 +110,10 @@ pub type Blake3Hash = [u8; BLAKE3_HASH_SIZE];
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Randomness(
-    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))] [u8; RANDOMNESS_LENGTH],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))] [u8; RANDOMNESS_LENGTH],
 );
 
 impl AsRef<[u8]> for Randomness {
@@ -117,9 +132,72 @@ impl AsMut<[u8]> for Randomness {
 
 impl Randomness {
     /// Derive global slot challenge from global randomness.
-    // TODO: Separate type for global challenge
-    pub fn derive_global_challenge(&self, slot: SlotNumber) -> Blake3Hash {
-        blake3_hash_list(&[&self.0, &slot.to_le_bytes()])
+    pub fn derive_global_challenge(&self, slot: SlotNumber) -> GlobalChallenge {
+        GlobalChallenge(blake3_hash_list(&[&self.0, &slot.to_le_bytes()]))
+    }
+}
+
+/// Global challenge for a particular slot, derived from global randomness.
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    From,
+    Into,
+    Deref,
+    Encode,
+    Decode,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GlobalChallenge(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))] Blake3Hash,
+);
+
+impl AsRef<[u8]> for GlobalChallenge {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Hash of farmer's [`PublicKey`], used for deriving [`SectorId`].
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    From,
+    Into,
+    Deref,
+    Encode,
+    Decode,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PublicKeyHash(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))] Blake3Hash,
+);
+
+impl AsRef<[u8]> for PublicKeyHash {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
     }
 }
 
@@ -173,6 +251,7 @@ impl PosSeed {
 #[derive(
     Debug, Copy, Clone, Eq, PartialEq, Deref, DerefMut, Encode, Decode, TypeInfo, MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PosProof([u8; Self::SIZE]);
 
 impl From<[u8; PosProof::SIZE]> for PosProof {
@@ -224,8 +303,11 @@ impl PosProof {
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct PotKey(#[cfg_attr(feature = "serde", serde(with = "hex::serde"))] [u8; Self::SIZE]);
+pub struct PotKey(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))] [u8; Self::SIZE],
+);
 
 impl fmt::Display for PotKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -269,8 +351,11 @@ impl PotKey {
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct PotSeed(#[cfg_attr(feature = "serde", serde(with = "hex::serde"))] [u8; Self::SIZE]);
+pub struct PotSeed(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))] [u8; Self::SIZE],
+);
 
 impl fmt::Display for PotSeed {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -319,8 +404,11 @@ impl PotSeed {
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct PotOutput(#[cfg_attr(feature = "serde", serde(with = "hex::serde"))] [u8; Self::SIZE]);
+pub struct PotOutput(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))] [u8; Self::SIZE],
+);
 
 impl fmt::Display for PotOutput {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -352,6 +440,16 @@ impl PotOutput {
         seed.copy_from_slice(&hash[..Self::SIZE]);
         seed
     }
+
+    /// Derive seed for the next slot, `maybe_entropy` is present only if entropy is injected
+    /// exactly at the next slot
+    #[inline]
+    pub fn next_seed(&self, maybe_entropy: Option<&Blake3Hash>) -> PotSeed {
+        match maybe_entropy {
+            Some(entropy) => self.seed_with_entropy(entropy),
+            None => self.seed(),
+        }
+    }
 }
 
 /// Proof of time checkpoints, result of proving
@@ -381,6 +479,53 @@ impl PotCheckpoints {
     pub fn output(&self) -> PotOutput {
         self.0[Self::NUM_CHECKPOINTS.get() as usize - 1]
     }
+
+    /// Number of iterations between two consecutive checkpoints of a slot with `slot_iterations`
+    /// iterations
+    #[inline]
+    pub const fn checkpoint_iterations(slot_iterations: NonZeroU32) -> u32 {
+        slot_iterations.get() / Self::NUM_CHECKPOINTS.get() as u32
+    }
+
+    /// Iterate over checkpoints together with the number of iterations from the seed of the slot
+    /// each checkpoint corresponds to
+    pub fn iter_with_iterations(
+        &self,
+        slot_iterations: NonZeroU32,
+    ) -> impl ExactSizeIterator<Item = (u32, PotOutput)> + '_ {
+        let checkpoint_iterations = Self::checkpoint_iterations(slot_iterations);
+
+        self.0.iter().enumerate().map(move |(index, &checkpoint)| {
+            (checkpoint_iterations * (index as u32 + 1), checkpoint)
+        })
+    }
+
+    /// Iterate over pairs of input and output of each checkpoint, where input of the first
+    /// checkpoint is `seed` and input of every next checkpoint is the previous checkpoint
+    pub fn iter_with_inputs(
+        &self,
+        seed: PotSeed,
+    ) -> impl Iterator<Item = (PotOutput, PotOutput)> + '_ {
+        let inputs = iter::once(PotOutput::from(*seed)).chain(self.0.iter().copied());
+
+        inputs.zip(self.0.iter().copied())
+    }
+
+    /// Number of whole slots that fit into `iterations` with `slot_iterations` iterations per slot
+    #[inline]
+    pub const fn slots_in_iterations(iterations: u64, slot_iterations: NonZeroU32) -> SlotNumber {
+        iterations / slot_iterations.get() as u64
+    }
+
+    /// Number of iterations necessary to prove `slots` slots with `slot_iterations` iterations per
+    /// slot, `None` on overflow
+    #[inline]
+    pub const fn iterations_in_slots(
+        slots: SlotNumber,
+        slot_iterations: NonZeroU32,
+    ) -> Option<u64> {
+        slots.checked_mul(slot_iterations.get() as u64)
+    }
 }
 
 /// A Ristretto Schnorr public key as bytes produced by `schnorrkel` crate.
@@ -401,9 +546,10 @@ impl PotCheckpoints {
     From,
     Into,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PublicKey(
-    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))] [u8; PUBLIC_KEY_LENGTH],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))] [u8; PUBLIC_KEY_LENGTH],
 );
 
 impl fmt::Display for PublicKey {
@@ -421,8 +567,8 @@ impl AsRef<[u8]> for PublicKey {
 
 impl PublicKey {
     /// Public key hash.
-    pub fn hash(&self) -> Blake3Hash {
-        blake3_hash(&self.0)
+    pub fn hash(&self) -> PublicKeyHash {
+        PublicKeyHash(blake3_hash(&self.0))
     }
 }
 
@@ -443,9 +589,11 @@ impl PublicKey {
     From,
     Into,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RewardSignature(
-    #[cfg_attr(feature = "serde", serde(with = "serde_arrays"))] [u8; REWARD_SIGNATURE_LENGTH],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))]
+    [u8; REWARD_SIGNATURE_LENGTH],
 );
 
 impl AsRef<[u8]> for RewardSignature {
@@ -457,8 +605,14 @@ impl AsRef<[u8]> for RewardSignature {
 
 /// Progress of an archived block.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(feature = "rkyv", archive(check_bytes, compare(PartialEq)))]
 pub enum ArchivedBlockProgress {
     /// The block has been fully archived.
     Complete,
@@ -493,8 +647,14 @@ impl ArchivedBlockProgress {
 
 /// Last archived block
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(feature = "rkyv", archive(check_bytes, compare(PartialEq)))]
 pub struct LastArchivedBlock {
     /// Block number
     pub number: BlockNumber,
@@ -519,15 +679,58 @@ impl LastArchivedBlock {
     }
 }
 
+/// Version of the [`SegmentHeader`] data structure
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, TypeInfo)]
+pub enum SegmentHeaderVersion {
+    /// [`SegmentHeader::V0`]
+    #[codec(index = 0)]
+    V0,
+}
+
+impl SegmentHeaderVersion {
+    /// Version used for newly created segment headers
+    pub const LATEST: Self = Self::V0;
+}
+
+/// Accessors for fields that are present in all versions of the segment header.
+///
+/// Allows code to work with segment headers without matching on specific versions, such that new
+/// versions can be introduced without changes to such code.
+pub trait SegmentHeaderFields {
+    /// Version of the segment header
+    fn version(&self) -> SegmentHeaderVersion;
+
+    /// Segment index
+    fn segment_index(&self) -> SegmentIndex;
+
+    /// Segment commitment of the records in a segment.
+    fn segment_commitment(&self) -> SegmentCommitment;
+
+    /// Hash of the segment header of the previous segment
+    fn prev_segment_header_hash(&self) -> Blake3Hash;
+
+    /// Last archived block
+    fn last_archived_block(&self) -> LastArchivedBlock;
+}
+
 /// Segment header for a specific segment.
 ///
 /// Each segment will have corresponding [`SegmentHeader`] included as the first item in the next
 /// segment. Each `SegmentHeader` includes hash of the previous one and all together form a chain of
 /// segment headers that is used for quick and efficient verification that some [`Piece`]
 /// corresponds to the actual archival history of the blockchain.
+///
+/// New versions are added as new variants with new codec index, existing variants must never be
+/// changed such that historical segment headers can always be decoded.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Encode, Decode, TypeInfo, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(feature = "rkyv", archive(check_bytes, compare(PartialEq)))]
 pub enum SegmentHeader {
     /// V0 of the segment header data structure
     #[codec(index = 0)]
@@ -538,6 +741,7 @@ pub enum SegmentHeader {
         /// Root of commitments of all records in a segment.
         segment_commitment: SegmentCommitment,
         /// Hash of the segment header of the previous segment
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))]
         prev_segment_header_hash: Blake3Hash,
         /// Last archived block
         last_archived_block: LastArchivedBlock,
@@ -545,9 +749,47 @@ pub enum SegmentHeader {
 }
 
 impl SegmentHeader {
+    /// Create segment header of the [latest](SegmentHeaderVersion::LATEST) version
+    pub fn latest(
+        segment_index: SegmentIndex,
+        segment_commitment: SegmentCommitment,
+        prev_segment_header_hash: Blake3Hash,
+        last_archived_block: LastArchivedBlock,
+    ) -> Self {
+        Self::V0 {
+            segment_index,
+            segment_commitment,
+            prev_segment_header_hash,
+            last_archived_block,
+        }
+    }
+
+    /// Convert segment header to the [latest](SegmentHeaderVersion::LATEST) version, fields that
+    /// older versions don't have are derived from the fields they do have.
+    ///
+    /// NOTE: Upgraded segment header may have a different [hash](Self::hash) than the original,
+    /// the chain of segment headers always links hashes of segment headers as they were created.
+    pub fn upgrade(self) -> Self {
+        match self {
+            Self::V0 { .. } => self,
+        }
+    }
+
+    /// Version of the segment header
+    pub fn version(&self) -> SegmentHeaderVersion {
+        match self {
+            Self::V0 { .. } => SegmentHeaderVersion::V0,
+        }
+    }
+
+    /// Whether segment header is of the [latest](SegmentHeaderVersion::LATEST) version
+    pub fn is_latest(&self) -> bool {
+        self.version() == SegmentHeaderVersion::LATEST
+    }
+
     /// Hash of the whole segment header
     pub fn hash(&self) -> Blake3Hash {
-        blake3_hash(&self.encode())
+        blake3_hash_encoded(self)
     }
 
     /// Segment index
@@ -587,12 +829,40 @@ impl SegmentHeader {
     }
 }
 
+impl SegmentHeaderFields for SegmentHeader {
+    #[inline]
+    fn version(&self) -> SegmentHeaderVersion {
+        SegmentHeader::version(self)
+    }
+
+    #[inline]
+    fn segment_index(&self) -> SegmentIndex {
+        SegmentHeader::segment_index(self)
+    }
+
+    #[inline]
+    fn segment_commitment(&self) -> SegmentCommitment {
+        SegmentHeader::segment_commitment(self)
+    }
+
+    #[inline]
+    fn prev_segment_header_hash(&self) -> Blake3Hash {
+        SegmentHeader::prev_segment_header_hash(self)
+    }
+
+    #[inline]
+    fn last_archived_block(&self) -> LastArchivedBlock {
+        SegmentHeader::last_archived_block(self)
+    }
+}
+
 /// Sector index in consensus
 pub type SectorIndex = u16;
 
 // TODO: Versioned solution enum
 /// Farmer solution for slot challenge.
 #[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct Solution<PublicKey, RewardAddress> {
@@ -909,144 +1179,66 @@ impl Default for U256 {
     }
 }
 
-/// Challenge used for a particular sector for particular slot
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deref)]
-pub struct SectorSlotChallenge(Blake3Hash);
-
-impl SectorSlotChallenge {
-    /// Index of s-bucket within sector to be audited
-    #[inline]
-    pub fn s_bucket_audit_index(&self) -> SBucket {
-        // As long as number of s-buckets is 2^16, we can pick first two bytes instead of actually
-        // calculating `U256::from_le_bytes(self.0) % Record::NUM_S_BUCKETS)`
-        const_assert_eq!(Record::NUM_S_BUCKETS, 1 << u16::BITS as usize);
-        SBucket::from(u16::from_le_bytes([self.0[0], self.0[1]]))
-    }
-}
+/// Wrap-around distance between global challenge and audit chunk, both interpreted as
+/// [`SolutionRange`].
+///
+/// Solution is valid when its distance is within half of the solution range, see
+/// [`SolutionDistance::is_within()`].
+#[derive(
+    Debug,
+    Display,
+    Default,
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    From,
+    Into,
+    Encode,
+    Decode,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+pub struct SolutionDistance(SolutionRange);
 
-/// Data structure representing sector ID in farmer's plot
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Encode, Decode, TypeInfo)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct SectorId(#[cfg_attr(feature = "serde", serde(with = "hex::serde"))] Blake3Hash);
+impl SolutionDistance {
+    /// Largest possible distance, values are at most this far from each other in either direction
+    pub const MAX: Self = Self(SolutionRange::MAX / 2 + 1);
 
-impl AsRef<[u8]> for SectorId {
+    /// Distance between two values interpreted as solution range
     #[inline]
-    fn as_ref(&self) -> &[u8] {
-        &self.0
+    pub fn between(a: SolutionRange, b: SolutionRange) -> Self {
+        Self(bidirectional_distance(&a, &b))
     }
-}
 
-impl SectorId {
-    /// Create new sector ID by deriving it from public key and sector index
-    pub fn new(public_key_hash: Blake3Hash, sector_index: SectorIndex) -> Self {
-        Self(blake3_hash_with_key(
-            &public_key_hash,
-            &sector_index.to_le_bytes(),
-        ))
-    }
-
-    /// Derive piece index that should be stored in sector at `piece_offset` for specified size of
-    /// blockchain history
-    pub fn derive_piece_index(
-        &self,
-        piece_offset: PieceOffset,
-        history_size: HistorySize,
-        max_pieces_in_sector: u16,
-        recent_segments: HistorySize,
-        recent_history_fraction: (HistorySize, HistorySize),
-    ) -> PieceIndex {
-        let recent_segments_in_pieces = recent_segments.in_pieces().get();
-        // Recent history must be at most `recent_history_fraction` of all history to use separate
-        // policy for recent pieces
-        let min_history_size_in_pieces = recent_segments_in_pieces
-            * recent_history_fraction.1.in_pieces().get()
-            / recent_history_fraction.0.in_pieces().get();
-        let input_hash = {
-            let piece_offset_bytes = piece_offset.to_bytes();
-            let mut key = [0; 32];
-            key[..piece_offset_bytes.len()].copy_from_slice(&piece_offset_bytes);
-            U256::from_le_bytes(blake3_hash_with_key(&key, &self.0))
-        };
-        let history_size_in_pieces = history_size.in_pieces().get();
-        let num_interleaved_pieces = 1.max(
-            u64::from(max_pieces_in_sector) * recent_history_fraction.0.in_pieces().get()
-                / recent_history_fraction.1.in_pieces().get()
-                * 2,
+    /// Calculate solution distance for given global challenge and masked chunk audited with sector
+    /// slot challenge
+    #[inline]
+    pub fn calculate(
+        global_challenge: &GlobalChallenge,
+        chunk: &[u8; 32],
+        sector_slot_challenge: &SectorSlotChallenge,
+    ) -> Self {
+        let global_challenge_as_solution_range = SolutionRange::from_le_bytes(
+            *global_challenge
+                .array_chunks::<{ mem::size_of::<SolutionRange>() }>()
+                .next()
+                .expect("Solution range is smaller in size than global challenge; qed"),
         );
 
-        let piece_index = if history_size_in_pieces > min_history_size_in_pieces
-            && u64::from(piece_offset) < num_interleaved_pieces
-            && u16::from(piece_offset) % 2 == 1
-        {
-            // For odd piece offsets at the beginning of the sector pick pieces at random from
-            // recent history only
-            input_hash % U256::from(recent_segments_in_pieces)
-                + U256::from(history_size_in_pieces - recent_segments_in_pieces)
-        } else {
-            input_hash % U256::from(history_size_in_pieces)
-        };
-
-        PieceIndex::from(u64::try_from(piece_index).expect(
-            "Remainder of division by PieceIndex is guaranteed to fit into PieceIndex; qed",
-        ))
-    }
-
-    /// Derive sector slot challenge for this sector from provided global challenge
-    pub fn derive_sector_slot_challenge(
-        &self,
-        global_challenge: &Blake3Hash,
-    ) -> SectorSlotChallenge {
-        let sector_slot_challenge = Simd::from(self.0) ^ Simd::from(*global_challenge);
-        SectorSlotChallenge(sector_slot_challenge.to_array())
-    }
-
-    /// Derive evaluation seed
-    pub fn derive_evaluation_seed(
-        &self,
-        piece_offset: PieceOffset,
-        history_size: HistorySize,
-    ) -> PosSeed {
-        let evaluation_seed = blake3_hash_list(&[
-            &self.0,
-            &piece_offset.to_bytes(),
-            &history_size.get().to_le_bytes(),
-        ]);
-
-        PosSeed::from(evaluation_seed)
+        Self::between(
+            global_challenge_as_solution_range,
+            sector_slot_challenge.audit_chunk(chunk),
+        )
     }
 
-    /// Derive history size when sector created at `history_size` expires.
-    ///
-    /// Returns `None` on overflow.
-    pub fn derive_expiration_history_size(
-        &self,
-        history_size: HistorySize,
-        sector_expiration_check_segment_commitment: &SegmentCommitment,
-        min_sector_lifetime: HistorySize,
-    ) -> Option<HistorySize> {
-        let sector_expiration_check_history_size =
-            history_size.sector_expiration_check(min_sector_lifetime)?;
-
-        let input_hash = U256::from_le_bytes(blake3_hash_list(&[
-            &self.0,
-            sector_expiration_check_segment_commitment.as_ref(),
-        ]));
-
-        let last_possible_expiration =
-            min_sector_lifetime.checked_add(history_size.get().checked_mul(4u64)?)?;
-        let expires_in = input_hash
-            % U256::from(
-                last_possible_expiration
-                    .get()
-                    .checked_sub(sector_expiration_check_history_size.get())?,
-            );
-        let expires_in = u64::try_from(expires_in).expect("Number modulo u64 fits into u64; qed");
-
-        let expiration_history_size = sector_expiration_check_history_size.get() + expires_in;
-        let expiration_history_size = NonZeroU64::try_from(expiration_history_size).expect(
-            "History size is not zero, so result is not zero even if expires immediately; qed",
-        );
-        Some(HistorySize::from(expiration_history_size))
+    /// Whether solution distance is within specified solution range (not larger than half of it)
+    #[inline]
+    pub const fn is_within(self, solution_range: SolutionRange) -> bool {
+        self.0 <= solution_range / 2
     }
 }
 
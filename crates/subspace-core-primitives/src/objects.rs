@@ -25,7 +25,7 @@ extern crate alloc;
 use crate::{Blake3Hash, PieceIndex};
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::{Compact, CompactLen, Decode, Encode, Input, Output};
 use scale_info::TypeInfo;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -40,6 +40,7 @@ pub enum BlockObject {
     #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
     V0 {
         /// Object hash
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))]
         hash: Blake3Hash,
         /// Offset of object in the encoded block.
         offset: u32,
@@ -80,6 +81,89 @@ pub struct BlockObjectMapping {
     pub objects: Vec<BlockObject>,
 }
 
+impl BlockObjectMapping {
+    /// Apply limits to the object mapping according to their overflow policy.
+    ///
+    /// Returns details of the overflow if object mapping exceeded limits.
+    pub fn apply_limits(&mut self, limits: &ObjectMappingLimits) -> Option<ObjectMappingOverflow> {
+        let objects = self.objects.len() as u32;
+        let size = self.encoded_size() as u32;
+
+        if objects <= limits.max_objects && size <= limits.max_size {
+            return None;
+        }
+
+        match limits.overflow_policy {
+            ObjectMappingOverflowPolicy::Truncate => {
+                let mut retained_objects = 0_u32;
+                let mut retained_size = 0_usize;
+                for object in &self.objects {
+                    if retained_objects == limits.max_objects {
+                        break;
+                    }
+                    let new_size = retained_size + object.encoded_size();
+                    // Length prefix of the vector grows with the number of objects
+                    if Compact::<u32>::compact_len(&(retained_objects + 1)) + new_size
+                        > limits.max_size as usize
+                    {
+                        break;
+                    }
+                    retained_objects += 1;
+                    retained_size = new_size;
+                }
+                self.objects.truncate(retained_objects as usize);
+            }
+            ObjectMappingOverflowPolicy::Reject => {
+                self.objects.clear();
+            }
+        }
+
+        Some(ObjectMappingOverflow {
+            objects,
+            size,
+            retained_objects: self.objects.len() as u32,
+        })
+    }
+}
+
+/// What to do with object mapping of a block that exceeds [`ObjectMappingLimits`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum ObjectMappingOverflowPolicy {
+    /// Keep objects from the beginning of the block that fit into limits and drop the rest
+    #[codec(index = 0)]
+    Truncate,
+    /// Drop object mapping of the block entirely
+    #[codec(index = 1)]
+    Reject,
+}
+
+/// Limits on object mapping of a single block, protect consumers of object mappings from blocks
+/// that contain an excessive number of objects
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ObjectMappingLimits {
+    /// Max number of objects in a block
+    pub max_objects: u32,
+    /// Max size of encoded block object mapping in bytes
+    pub max_size: u32,
+    /// What to do with object mapping that exceeds limits
+    pub overflow_policy: ObjectMappingOverflowPolicy,
+}
+
+/// Details of block object mapping that exceeded [`ObjectMappingLimits`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ObjectMappingOverflow {
+    /// Number of objects before limits were applied
+    pub objects: u32,
+    /// Size of encoded object mapping before limits were applied
+    pub size: u32,
+    /// Number of objects retained after limits were applied
+    pub retained_objects: u32,
+}
+
 /// Object stored inside of the block
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -90,6 +174,7 @@ pub enum PieceObject {
     #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
     V0 {
         /// Object hash
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))]
         hash: Blake3Hash,
         // TODO: This is a raw record offset, not a regular one
         /// Offset of the object
@@ -153,3 +238,143 @@ impl GlobalObject {
         }
     }
 }
+
+/// Mapping of objects stored in the history of the blockchain
+#[derive(Debug, Default, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct GlobalObjectMapping {
+    /// Objects stored in the history of the blockchain
+    pub objects: Vec<GlobalObject>,
+}
+
+/// V2 of [`GlobalObjectMapping`] encoding, meant to be used in RPC responses and gossip.
+///
+/// Objects of large blocks tend to be located in the same or adjacent pieces with increasing
+/// offsets, so instead of full piece index and offset of each object, only difference from the
+/// previous object is stored, which is typically 1-2 bytes each in compact encoding. Differences
+/// are zigzag-encoded, so arbitrary order of objects is supported, but sorted objects are encoded
+/// most efficiently.
+///
+/// Converts to/from [`GlobalObjectMapping`] losslessly.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, TypeInfo)]
+pub struct CompactGlobalObjectMapping {
+    objects: Vec<GlobalObject>,
+}
+
+impl From<GlobalObjectMapping> for CompactGlobalObjectMapping {
+    #[inline]
+    fn from(mapping: GlobalObjectMapping) -> Self {
+        Self {
+            objects: mapping.objects,
+        }
+    }
+}
+
+impl From<CompactGlobalObjectMapping> for GlobalObjectMapping {
+    #[inline]
+    fn from(mapping: CompactGlobalObjectMapping) -> Self {
+        Self {
+            objects: mapping.objects,
+        }
+    }
+}
+
+impl Encode for CompactGlobalObjectMapping {
+    fn size_hint(&self) -> usize {
+        Compact::<u32>::compact_len(&(self.objects.len() as u32))
+            + self
+                .deltas()
+                .map(|(piece_index_delta, offset)| {
+                    Compact::<u64>::compact_len(&piece_index_delta)
+                        + Compact::<u32>::compact_len(&offset)
+                })
+                .sum::<usize>()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        Compact(self.objects.len() as u32).encode_to(dest);
+        for (piece_index_delta, offset) in self.deltas() {
+            Compact(piece_index_delta).encode_to(dest);
+            Compact(offset).encode_to(dest);
+        }
+    }
+}
+
+impl Decode for CompactGlobalObjectMapping {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        let len = Compact::<u32>::decode(input)?.0;
+
+        // Don't trust length prefix for pre-allocation, each object takes at least 2 bytes
+        let mut objects =
+            Vec::with_capacity((len as usize).min(input.remaining_len()?.unwrap_or_default() / 2));
+        let mut last_piece_index = 0_u64;
+        let mut last_offset = 0_u32;
+        for _ in 0..len {
+            let piece_index_delta = Compact::<u64>::decode(input)?.0;
+            let offset = Compact::<u32>::decode(input)?.0;
+
+            let piece_index = last_piece_index.wrapping_add(zigzag_decode(piece_index_delta));
+            let offset = if piece_index == last_piece_index {
+                last_offset.wrapping_add(zigzag_decode(u64::from(offset)) as u32)
+            } else {
+                offset
+            };
+
+            objects.push(GlobalObject::V0 {
+                piece_index: PieceIndex::from(piece_index),
+                offset,
+            });
+            last_piece_index = piece_index;
+            last_offset = offset;
+        }
+
+        Ok(Self { objects })
+    }
+}
+
+impl CompactGlobalObjectMapping {
+    /// Objects stored in the history of the blockchain
+    pub fn objects(&self) -> &[GlobalObject] {
+        &self.objects
+    }
+
+    /// Iterator over piece index delta and offset (delta within the same piece or absolute offset
+    /// otherwise) of each object, both zigzag-encoded where necessary
+    fn deltas(&self) -> impl Iterator<Item = (u64, u32)> + '_ {
+        let mut last_piece_index = 0_u64;
+        let mut last_offset = 0_u32;
+
+        self.objects.iter().map(move |object| {
+            let piece_index = u64::from(object.piece_index());
+            let offset = object.offset();
+
+            let piece_index_delta = zigzag_encode(piece_index.wrapping_sub(last_piece_index));
+            let encoded_offset = if piece_index == last_piece_index {
+                // Sign-extended 32-bit delta is zigzag-encoded into value that fits into 32 bits
+                zigzag_encode(offset.wrapping_sub(last_offset) as i32 as u64) as u32
+            } else {
+                offset
+            };
+
+            last_piece_index = piece_index;
+            last_offset = offset;
+
+            (piece_index_delta, encoded_offset)
+        })
+    }
+}
+
+/// Zigzag encoding of two's complement value, maps small negative and positive values to small
+/// positive values
+#[inline]
+fn zigzag_encode(value: u64) -> u64 {
+    let value = value as i64;
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Inverse of [`zigzag_encode()`]
+#[inline]
+fn zigzag_decode(value: u64) -> u64 {
+    (value >> 1) ^ (value & 1).wrapping_neg()
+}
@@ -3,12 +3,14 @@ mod serde;
 #[cfg(test)]
 mod tests;
 
-use crate::crypto::kzg::{Commitment, Witness};
-use crate::crypto::Scalar;
-use crate::segments::{ArchivedHistorySegment, SegmentIndex};
+use crate::crypto::kzg::{aggregation_coefficient, Commitment, Evaluation, Kzg, Witness};
+use crate::crypto::{blake3_254_hash_to_scalar, Scalar};
+use crate::segments::{ArchivedHistorySegment, SegmentCommitment, SegmentIndex};
 use crate::RecordedHistorySegment;
+use ::kzg::Fr;
 #[cfg(feature = "serde")]
 use ::serde::{Deserialize, Serialize};
+use alloc::alloc::{AllocError, Allocator, Global, Layout};
 use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::vec;
@@ -17,15 +19,21 @@ use core::array::TryFromSliceError;
 use core::iter::Step;
 use core::num::TryFromIntError;
 use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+#[cfg(feature = "simd")]
+use core::simd::Simd;
 use core::{mem, slice};
 use derive_more::{
     Add, AddAssign, AsMut, AsRef, Deref, DerefMut, Display, Div, DivAssign, From, Into, Mul,
     MulAssign, Sub, SubAssign,
 };
-use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use parity_scale_codec::{
+    Compact, CompactLen, Decode, Encode, EncodeLike, Input, MaxEncodedLen, Output,
+};
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
-use scale_info::TypeInfo;
+use scale_info::{Type, TypeInfo};
+use static_assertions::const_assert;
 
 /// S-bucket used in consensus
 #[derive(
@@ -52,6 +60,7 @@ use scale_info::TypeInfo;
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(transparent)]
 pub struct SBucket(u16);
@@ -115,6 +124,60 @@ impl SBucket {
     pub const ZERO: SBucket = SBucket(0);
     /// Max s-bucket index
     pub const MAX: SBucket = SBucket((Record::NUM_S_BUCKETS - 1) as u16);
+
+    /// S-bucket that corresponds to source chunk with specified index within record, returns
+    /// `None` if index is outside of record
+    #[inline]
+    pub const fn from_source_chunk_index(source_chunk_index: usize) -> Option<Self> {
+        if source_chunk_index < Record::NUM_CHUNKS {
+            Some(Self((source_chunk_index * 2) as u16))
+        } else {
+            None
+        }
+    }
+
+    /// S-bucket that corresponds to parity chunk with specified index within erasure coded record,
+    /// returns `None` if index is outside of record
+    #[inline]
+    pub const fn from_parity_chunk_index(parity_chunk_index: usize) -> Option<Self> {
+        if parity_chunk_index < Record::NUM_CHUNKS {
+            Some(Self((parity_chunk_index * 2 + 1) as u16))
+        } else {
+            None
+        }
+    }
+
+    /// Whether this s-bucket corresponds to a source chunk of the record
+    #[inline]
+    pub const fn is_source(&self) -> bool {
+        self.0 % 2 == 0
+    }
+
+    /// Whether this s-bucket corresponds to a parity chunk of erasure coded record
+    #[inline]
+    pub const fn is_parity(&self) -> bool {
+        !self.is_source()
+    }
+
+    /// Index of the chunk among source chunks of the record, `None` for parity s-buckets
+    #[inline]
+    pub const fn source_chunk_index(&self) -> Option<usize> {
+        if self.is_source() {
+            Some(self.0 as usize / 2)
+        } else {
+            None
+        }
+    }
+
+    /// Index of the chunk among parity chunks of erasure coded record, `None` for source s-buckets
+    #[inline]
+    pub const fn parity_chunk_index(&self) -> Option<usize> {
+        if self.is_parity() {
+            Some(self.0 as usize / 2)
+        } else {
+            None
+        }
+    }
 }
 
 /// Piece index in consensus
@@ -142,6 +205,7 @@ impl SBucket {
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(transparent)]
 pub struct PieceIndex(u64);
@@ -205,9 +269,147 @@ impl PieceIndex {
 
     /// Position of a piece in a segment
     #[inline]
-    pub const fn position(&self) -> u32 {
+    pub const fn position(&self) -> PiecePosition {
         // Position is statically guaranteed to fit into u32
-        (self.0 % ArchivedHistorySegment::NUM_PIECES as u64) as u32
+        PiecePosition((self.0 % ArchivedHistorySegment::NUM_PIECES as u64) as u32)
+    }
+
+    /// Whether piece index corresponds to a source piece (as opposed to parity piece)
+    #[inline]
+    pub const fn is_source(&self) -> bool {
+        self.position().is_source()
+    }
+}
+
+/// Position of a piece in archived segment.
+///
+/// Source and parity pieces are interleaved in archived segment, such that source pieces are at
+/// even positions and parity pieces are at odd positions.
+///
+/// Decoding, deserialization and arbitrary generation go through [`PiecePosition::new()`], so
+/// position is always within archived segment.
+#[derive(
+    Debug,
+    Display,
+    Default,
+    Copy,
+    Clone,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    Hash,
+    Encode,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[repr(transparent)]
+pub struct PiecePosition(u32);
+
+impl Decode for PiecePosition {
+    #[inline]
+    fn decode<I: Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        Self::new(u32::decode(input)?)
+            .ok_or_else(|| "Piece position is outside of archived segment".into())
+    }
+
+    #[inline]
+    fn encoded_fixed_size() -> Option<usize> {
+        u32::encoded_fixed_size()
+    }
+}
+
+impl From<PiecePosition> for u32 {
+    #[inline]
+    fn from(original: PiecePosition) -> Self {
+        original.0
+    }
+}
+
+impl From<PiecePosition> for usize {
+    #[inline]
+    fn from(original: PiecePosition) -> Self {
+        original.0 as usize
+    }
+}
+
+impl TryFrom<u32> for PiecePosition {
+    type Error = u32;
+
+    /// Returns position back as an error if it is outside of archived segment
+    #[inline]
+    fn try_from(position: u32) -> Result<Self, Self::Error> {
+        Self::new(position).ok_or(position)
+    }
+}
+
+impl PiecePosition {
+    /// Position of the first piece in a segment
+    pub const ZERO: PiecePosition = PiecePosition(0);
+
+    /// Create new instance, returns `None` if position is outside of archived segment
+    #[inline]
+    pub const fn new(position: u32) -> Option<Self> {
+        if position < ArchivedHistorySegment::NUM_PIECES as u32 {
+            Some(Self(position))
+        } else {
+            None
+        }
+    }
+
+    /// Position of source piece with specified index among source pieces of a segment, returns
+    /// `None` if index is outside of archived segment
+    #[inline]
+    pub const fn from_source_index(source_index: u32) -> Option<Self> {
+        if source_index < RecordedHistorySegment::NUM_RAW_RECORDS as u32 {
+            Some(Self(source_index * 2))
+        } else {
+            None
+        }
+    }
+
+    /// Position of parity piece with specified index among parity pieces of a segment, returns
+    /// `None` if index is outside of archived segment
+    #[inline]
+    pub const fn from_parity_index(parity_index: u32) -> Option<Self> {
+        if parity_index < RecordedHistorySegment::NUM_RAW_RECORDS as u32 {
+            Some(Self(parity_index * 2 + 1))
+        } else {
+            None
+        }
+    }
+
+    /// Whether this is a position of a source piece
+    #[inline]
+    pub const fn is_source(&self) -> bool {
+        self.0 % 2 == 0
+    }
+
+    /// Whether this is a position of a parity piece
+    #[inline]
+    pub const fn is_parity(&self) -> bool {
+        !self.is_source()
+    }
+
+    /// Index of the piece among source pieces of a segment, `None` for parity pieces
+    #[inline]
+    pub const fn source_index(&self) -> Option<u32> {
+        if self.is_source() {
+            Some(self.0 / 2)
+        } else {
+            None
+        }
+    }
+
+    /// Index of the piece among parity pieces of a segment, `None` for source pieces
+    #[inline]
+    pub const fn parity_index(&self) -> Option<u32> {
+        if self.is_parity() {
+            Some(self.0 / 2)
+        } else {
+            None
+        }
     }
 }
 
@@ -236,6 +438,7 @@ impl PieceIndex {
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(transparent)]
 pub struct PieceOffset(u16);
@@ -307,7 +510,8 @@ impl PieceOffset {
 
 /// Raw record contained within recorded history segment before archiving is applied.
 ///
-/// NOTE: This is a stack-allocated data structure and can cause stack overflow!
+/// NOTE: This is a stack-allocated data structure and can cause stack overflow! Use
+/// [`RawRecord::new_boxed()`] or [`RawRecord::new_zero_vec()`] to allocate it on the heap instead.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Deref, DerefMut)]
 #[repr(transparent)]
 pub struct RawRecord([[u8; Scalar::SAFE_BYTES]; Self::NUM_CHUNKS]);
@@ -335,7 +539,14 @@ impl AsMut<[u8]> for RawRecord {
 
 impl RawRecord {
     /// Number of chunks (scalars) within one raw record.
+    #[cfg(not(subspace_testing_geometry))]
     pub const NUM_CHUNKS: usize = 2_usize.pow(15);
+    // Reduced geometry for lightweight test networks and simulations, incompatible with production
+    // networks. Enabled with `RUSTFLAGS="--cfg subspace_testing_geometry"` rather than a feature,
+    // such that feature unification can't silently change consensus constants of other crates.
+    /// Number of chunks (scalars) within one raw record.
+    #[cfg(subspace_testing_geometry)]
+    pub const NUM_CHUNKS: usize = 2_usize.pow(10);
     /// Size of raw record in bytes, is guaranteed to be a multiple of [`Scalar::SAFE_BYTES`].
     pub const SIZE: usize = Scalar::SAFE_BYTES * Self::NUM_CHUNKS;
 
@@ -346,6 +557,41 @@ impl RawRecord {
         // SAFETY: Data structure filled with zeroes is a valid invariant
         unsafe { Box::new_zeroed().assume_init() }
     }
+
+    /// Create vector filled with zero raw records without hitting stack overflow
+    #[inline]
+    pub fn new_zero_vec(length: usize) -> Vec<Self> {
+        // TODO: Should have been just `::new()`, but https://github.com/rust-lang/rust/issues/53827
+        let mut raw_records = Vec::with_capacity(length);
+        {
+            let slice = raw_records.spare_capacity_mut();
+            // SAFETY: Same memory layout due to `#[repr(transparent)]` on `RawRecord` and
+            // `MaybeUninit<[[T; M]; N]>` is guaranteed to have the same layout as
+            // `[[MaybeUninit<T>; M]; N]`
+            let slice = unsafe {
+                slice::from_raw_parts_mut(
+                    slice.as_mut_ptr()
+                        as *mut [[mem::MaybeUninit<u8>; Scalar::SAFE_BYTES]; Self::NUM_CHUNKS],
+                    length,
+                )
+            };
+            for byte in slice.flatten_mut().flatten_mut() {
+                byte.write(0);
+            }
+        }
+        // SAFETY: All values are initialized above.
+        unsafe {
+            raw_records.set_len(raw_records.capacity());
+        }
+
+        raw_records
+    }
+
+    /// Fill raw record with zeroes in place, useful for reusing existing allocation
+    #[inline]
+    pub fn fill_zeroes(&mut self) {
+        self.as_mut().fill(0);
+    }
 }
 
 /// Record contained within a piece.
@@ -426,6 +672,56 @@ impl Record {
         records
     }
 
+    /// Source chunk of the record that corresponds to `s_bucket`, `None` for s-buckets that
+    /// correspond to parity chunks (see [`RecordChunksView`] for accessing those).
+    #[inline]
+    pub fn source_chunk_for_s_bucket(
+        &self,
+        s_bucket: SBucket,
+    ) -> Option<&[u8; Scalar::FULL_BYTES]> {
+        self.0.get(s_bucket.source_chunk_index()?)
+    }
+
+    /// Mask (XOR) record chunk with `mask`, this is how chunks are encoded with proof of space
+    /// quality during plotting and decoded back during reading and verification.
+    #[inline]
+    pub fn mask_chunk(
+        chunk: &[u8; Scalar::FULL_BYTES],
+        mask: &[u8; Scalar::FULL_BYTES],
+    ) -> [u8; Scalar::FULL_BYTES] {
+        let mut chunk = *chunk;
+        Self::mask_chunk_in_place(&mut chunk, mask);
+        chunk
+    }
+
+    /// Mask (XOR) record chunk with `mask` in place, see [`Self::mask_chunk()`].
+    #[inline]
+    pub fn mask_chunk_in_place(
+        chunk: &mut [u8; Scalar::FULL_BYTES],
+        mask: &[u8; Scalar::FULL_BYTES],
+    ) {
+        #[cfg(feature = "simd")]
+        {
+            *chunk = (Simd::from(*chunk) ^ Simd::from(*mask)).to_array();
+        }
+        #[cfg(not(feature = "simd"))]
+        {
+            chunk
+                .iter_mut()
+                .zip(mask)
+                .for_each(|(byte, mask_byte)| *byte ^= mask_byte);
+        }
+    }
+
+    /// Mask (XOR) every chunk of the record with corresponding chunk of `mask` in place.
+    #[inline]
+    pub fn xor_in_place(&mut self, mask: &Record) {
+        self.0
+            .iter_mut()
+            .zip(&mask.0)
+            .for_each(|(chunk, mask_chunk)| Self::mask_chunk_in_place(chunk, mask_chunk));
+    }
+
     /// Convenient conversion from slice of record to underlying representation for efficiency
     /// purposes.
     #[inline]
@@ -459,6 +755,53 @@ impl Record {
     }
 }
 
+// Interleaving of source and parity chunks assumes erasure coding rate of 1/2
+const_assert!(Record::NUM_S_BUCKETS == Record::NUM_CHUNKS * 2);
+
+/// S-bucket-oriented view of erasure coded record chunks.
+///
+/// Record is erasure coded such that its source and parity chunks are interleaved across
+/// s-buckets: source chunks correspond to even s-buckets and parity chunks to odd s-buckets.
+#[derive(Debug, Copy, Clone)]
+pub struct RecordChunksView<'a, T> {
+    source_chunks: &'a [T],
+    parity_chunks: &'a [T],
+}
+
+impl<'a, T> RecordChunksView<'a, T> {
+    /// Create new view, returns `None` unless both source and parity chunks contain exactly
+    /// [`Record::NUM_CHUNKS`] elements
+    #[inline]
+    pub fn new(source_chunks: &'a [T], parity_chunks: &'a [T]) -> Option<Self> {
+        if source_chunks.len() == Record::NUM_CHUNKS && parity_chunks.len() == Record::NUM_CHUNKS {
+            Some(Self {
+                source_chunks,
+                parity_chunks,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Chunk that corresponds to `s_bucket`
+    #[inline]
+    pub fn chunk_for_s_bucket(&self, s_bucket: SBucket) -> &'a T {
+        match s_bucket.source_chunk_index() {
+            Some(source_chunk_index) => &self.source_chunks[source_chunk_index],
+            None => &self.parity_chunks[usize::from(s_bucket) / 2],
+        }
+    }
+
+    /// Iterator over chunks in s-bucket order
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        self.source_chunks
+            .iter()
+            .zip(self.parity_chunks)
+            .flat_map(|(source_chunk, parity_chunk)| [source_chunk, parity_chunk])
+    }
+}
+
 /// Record commitment contained within a piece.
 #[derive(
     Debug,
@@ -476,10 +819,12 @@ impl Record {
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(transparent)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RecordCommitment(
-    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))] [u8; RecordCommitment::SIZE],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))]
+    [u8; RecordCommitment::SIZE],
 );
 
 impl Default for RecordCommitment {
@@ -559,10 +904,12 @@ impl TryFrom<RecordCommitment> for Commitment {
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(transparent)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RecordWitness(
-    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))] [u8; RecordWitness::SIZE],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))]
+    [u8; RecordWitness::SIZE],
 );
 
 impl Default for RecordWitness {
@@ -598,6 +945,33 @@ impl AsMut<[u8]> for RecordWitness {
 impl RecordWitness {
     /// Size of record witness in bytes.
     pub const SIZE: usize = 48;
+
+    /// Verify that record commitment is part of the segment with specified segment commitment at
+    /// specified position using this witness
+    pub fn verify(
+        &self,
+        kzg: &Kzg,
+        record_commitment: &RecordCommitment,
+        segment_commitment: &SegmentCommitment,
+        position: PiecePosition,
+    ) -> Result<(), PieceVerificationError> {
+        let witness = Witness::try_from(self)
+            .map_err(|_error| PieceVerificationError::InvalidRecordWitness)?;
+        let segment_commitment = Commitment::try_from(segment_commitment)
+            .map_err(|_error| PieceVerificationError::InvalidSegmentCommitment)?;
+
+        if !kzg.verify(
+            &segment_commitment,
+            ArchivedHistorySegment::NUM_PIECES,
+            u32::from(position),
+            &blake3_254_hash_to_scalar(record_commitment.as_ref()),
+            &witness,
+        ) {
+            return Err(PieceVerificationError::SegmentCommitmentMismatch);
+        }
+
+        Ok(())
+    }
 }
 
 impl From<Witness> for RecordWitness {
@@ -642,10 +1016,12 @@ impl TryFrom<RecordWitness> for Witness {
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(transparent)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ChunkWitness(
-    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))] [u8; ChunkWitness::SIZE],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))]
+    [u8; ChunkWitness::SIZE],
 );
 
 impl Default for ChunkWitness {
@@ -715,10 +1091,26 @@ impl TryFrom<ChunkWitness> for Witness {
 /// Internally piece contains a record and corresponding witness that together with segment
 /// commitment of the segment this piece belongs to can be used to verify that a piece belongs to
 /// the actual archival history of the blockchain.
-#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Encode, Decode, TypeInfo)]
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Encode, TypeInfo)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Piece(Box<PieceArray>);
 
+impl Decode for Piece {
+    #[inline]
+    fn decode<I: Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        // Read directly into heap allocation, decoding `PieceArray` first would need to place it on
+        // the stack and copy afterwards
+        let mut piece = Self::default();
+        input.read(piece.as_mut())?;
+        Ok(piece)
+    }
+
+    #[inline]
+    fn encoded_fixed_size() -> Option<usize> {
+        Some(Self::SIZE)
+    }
+}
+
 impl Default for Piece {
     #[inline]
     fn default() -> Self {
@@ -749,8 +1141,19 @@ impl TryFrom<Vec<u8>> for Piece {
 
     #[inline]
     fn try_from(vec: Vec<u8>) -> Result<Self, Self::Error> {
-        // TODO: Maybe possible to transmute boxed slice into boxed array
-        Self::try_from(vec.as_slice())
+        if vec.len() != Self::SIZE || vec.capacity() != Self::SIZE {
+            // Either length is wrong or allocation doesn't match piece size and would need to be
+            // reallocated anyway, copy in both cases
+            return Self::try_from(vec.as_slice());
+        }
+
+        let piece = <Box<[u8; Self::SIZE]>>::try_from(vec.into_boxed_slice())
+            .expect("Length and capacity checked above; qed");
+        // SAFETY: `PieceArray` is `#[repr(transparent)]` and guaranteed to have the same memory
+        // layout as `[u8; Piece::SIZE]`
+        Ok(Self(unsafe {
+            Box::from_raw(Box::into_raw(piece).cast::<PieceArray>())
+        }))
     }
 }
 
@@ -796,8 +1199,83 @@ impl AsMut<[u8]> for Piece {
 impl Piece {
     /// Size of a piece (in bytes).
     pub const SIZE: usize = Record::SIZE + RecordCommitment::SIZE + RecordWitness::SIZE;
+
+    /// Read piece from reader, reads exactly [`Self::SIZE`] bytes
+    #[cfg(feature = "std")]
+    pub fn from_reader<R>(mut reader: R) -> std::io::Result<Self>
+    where
+        R: std::io::Read,
+    {
+        let mut piece = Self::default();
+        reader.read_exact(piece.as_mut())?;
+        Ok(piece)
+    }
+
+    /// Create piece from bytes, rejecting pieces that are obviously invalid, see
+    /// [`PieceArray::check()`] for details.
+    ///
+    /// This is much cheaper than [`PieceArray::verify()`] and meant for pieces received from
+    /// untrusted sources, such that garbage is rejected early rather than deep in KZG verification.
+    pub fn try_new_checked(bytes: &[u8]) -> Result<Self, PieceCheckError> {
+        let piece = Self::try_from(bytes)
+            .map_err(|_error| PieceCheckError::InvalidSize { size: bytes.len() })?;
+        piece.check()?;
+        Ok(piece)
+    }
 }
 
+/// Error happening during cheap structural check of a piece
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Display)]
+pub enum PieceCheckError {
+    /// Piece has invalid size
+    #[display(fmt = "Invalid piece size {size}, expected {}", Piece::SIZE)]
+    InvalidSize {
+        /// Size of the provided piece bytes
+        size: usize,
+    },
+    /// Record commitment is all zeroes
+    #[display(fmt = "Record commitment is all zeroes")]
+    ZeroRecordCommitment,
+    /// Record witness is all zeroes
+    #[display(fmt = "Record witness is all zeroes")]
+    ZeroRecordWitness,
+    /// Record chunk is not a canonical scalar
+    #[display(fmt = "Record chunk {chunk_index} is not a canonical scalar")]
+    NonCanonicalRecordChunk {
+        /// Index of the first invalid chunk in the record
+        chunk_index: usize,
+    },
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PieceCheckError {}
+
+/// Error happening during verification of a piece
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Display)]
+pub enum PieceVerificationError {
+    /// Record witness bytes are not a valid witness
+    #[display(fmt = "Invalid record witness")]
+    InvalidRecordWitness,
+    /// Record chunk is not a valid scalar
+    #[display(fmt = "Invalid record chunk")]
+    InvalidRecordChunk,
+    /// Failed to create record commitment
+    #[display(fmt = "Failed to create record commitment")]
+    RecordCommitmentCreation,
+    /// Record commitment doesn't match record
+    #[display(fmt = "Record commitment doesn't match record")]
+    RecordCommitmentMismatch,
+    /// Segment commitment bytes are not a valid commitment
+    #[display(fmt = "Invalid segment commitment")]
+    InvalidSegmentCommitment,
+    /// Record commitment is not part of the segment
+    #[display(fmt = "Record commitment is not part of the segment")]
+    SegmentCommitmentMismatch,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PieceVerificationError {}
+
 /// A piece of archival history in Subspace Network.
 ///
 /// This version is allocated on the stack, for heap-allocated piece see [`Piece`].
@@ -823,6 +1301,12 @@ impl Piece {
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(feature = "rkyv", archive(check_bytes, compare(PartialEq)))]
+#[cfg_attr(feature = "rkyv", archive_attr(repr(transparent)))]
 #[repr(transparent)]
 pub struct PieceArray([u8; Piece::SIZE]);
 
@@ -847,6 +1331,18 @@ impl AsMut<[u8]> for PieceArray {
     }
 }
 
+#[cfg(feature = "rkyv")]
+impl Deref for ArchivedPieceArray {
+    type Target = PieceArray;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: Both `ArchivedPieceArray` and `PieceArray` are `#[repr(transparent)]` wrappers
+        // around `[u8; Piece::SIZE]`, any bytes are valid piece
+        unsafe { mem::transmute(self) }
+    }
+}
+
 impl PieceArray {
     /// Create boxed value without hitting stack overflow
     #[inline]
@@ -856,6 +1352,147 @@ impl PieceArray {
         unsafe { Box::<Self>::new_zeroed().assume_init() }
     }
 
+    /// Verify that record commitment matches record and that record witness proves that record
+    /// commitment is a part of the segment with specified commitment at position corresponding to
+    /// piece index.
+    ///
+    /// See [`Self::is_valid()`] if details of verification failure are not important.
+    pub fn verify(
+        &self,
+        kzg: &Kzg,
+        segment_commitment: &SegmentCommitment,
+        piece_index: PieceIndex,
+    ) -> Result<(), PieceVerificationError> {
+        let (record, commitment, witness) = self.split();
+
+        let mut scalars = Vec::with_capacity(record.len().next_power_of_two());
+        Scalar::try_batch_from_bytes_into(record.as_slice(), &mut scalars)
+            .map_err(|_error| PieceVerificationError::InvalidRecordChunk)?;
+        // Number of scalars for KZG must be a power of two elements
+        scalars.resize(scalars.capacity(), Scalar::default());
+
+        let record_commitment = kzg
+            .poly(&scalars)
+            .and_then(|polynomial| kzg.commit(&polynomial))
+            .map_err(|_error| PieceVerificationError::RecordCommitmentCreation)?;
+        if record_commitment.to_bytes() != **commitment {
+            return Err(PieceVerificationError::RecordCommitmentMismatch);
+        }
+
+        witness.verify(kzg, commitment, segment_commitment, piece_index.position())
+    }
+
+    /// Cheap structural check that rejects obviously invalid pieces: record commitment and record
+    /// witness must not be all zeroes and every record chunk must be a canonical scalar.
+    ///
+    /// Passing this check doesn't mean piece is valid, see [`Self::verify()`] for that.
+    pub fn check(&self) -> Result<(), PieceCheckError> {
+        let (record, commitment, witness) = self.split();
+
+        if commitment.iter().all(|&byte| byte == 0) {
+            return Err(PieceCheckError::ZeroRecordCommitment);
+        }
+        if witness.iter().all(|&byte| byte == 0) {
+            return Err(PieceCheckError::ZeroRecordWitness);
+        }
+        if let Some(chunk_index) = record
+            .iter()
+            .position(|record_chunk| Scalar::try_from(record_chunk).is_err())
+        {
+            return Err(PieceCheckError::NonCanonicalRecordChunk { chunk_index });
+        }
+
+        Ok(())
+    }
+
+    /// Check whether piece is valid, see [`Self::verify()`] for details
+    #[inline]
+    pub fn is_valid(
+        &self,
+        kzg: &Kzg,
+        segment_commitment: &SegmentCommitment,
+        piece_index: PieceIndex,
+    ) -> bool {
+        self.verify(kzg, segment_commitment, piece_index).is_ok()
+    }
+
+    /// Verify multiple pieces of the same segment at once, equivalent to calling [`Self::verify()`]
+    /// on each piece, but an order of magnitude faster for large number of pieces.
+    ///
+    /// Record commitments are checked with a single commitment to the random linear combination of
+    /// records and record witnesses are checked with a single aggregated pairing check. In case of
+    /// failure it is not known which piece is invalid, [`Self::verify()`] can be used on individual
+    /// pieces to find out. Empty list of pieces is valid.
+    pub fn verify_segment_pieces(
+        kzg: &Kzg,
+        segment_commitment: &SegmentCommitment,
+        pieces: &[(PieceIndex, &Self)],
+    ) -> Result<(), PieceVerificationError> {
+        if pieces.is_empty() {
+            return Ok(());
+        }
+
+        // Coefficients are derived from all inputs, such that they can't be known in advance
+        let seed = {
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(segment_commitment.as_ref());
+            for (piece_index, piece) in pieces {
+                hasher.update(&piece_index.to_bytes());
+                hasher.update(piece.as_ref());
+            }
+            *hasher.finalize().as_bytes()
+        };
+        let coefficients = (0..pieces.len())
+            .map(|index| aggregation_coefficient(&seed, index))
+            .collect::<Vec<_>>();
+
+        let mut combined_record = vec![Scalar::default(); Record::NUM_CHUNKS.next_power_of_two()];
+        let mut record_commitments = Vec::with_capacity(pieces.len());
+        let mut evaluations = Vec::with_capacity(pieces.len());
+        for ((piece_index, piece), coefficient) in pieces.iter().zip(&coefficients) {
+            let (record, commitment, witness) = piece.split();
+
+            for (combined_chunk, record_chunk) in combined_record.iter_mut().zip(record.iter()) {
+                let record_chunk = Scalar::try_from(record_chunk)
+                    .map_err(|_error| PieceVerificationError::InvalidRecordChunk)?;
+                *combined_chunk = Scalar::from(combined_chunk.add(&coefficient.mul(&record_chunk)));
+            }
+
+            record_commitments.push(
+                Commitment::try_from(commitment)
+                    .map_err(|_error| PieceVerificationError::RecordCommitmentMismatch)?,
+            );
+            evaluations.push(Evaluation {
+                index: u32::from(piece_index.position()),
+                value: blake3_254_hash_to_scalar(commitment.as_ref()),
+                witness: Witness::try_from(witness)
+                    .map_err(|_error| PieceVerificationError::InvalidRecordWitness)?,
+            });
+        }
+
+        let combined_record_commitment = kzg
+            .poly(&combined_record)
+            .and_then(|polynomial| kzg.commit(&polynomial))
+            .map_err(|_error| PieceVerificationError::RecordCommitmentCreation)?;
+        if combined_record_commitment
+            != Commitment::linear_combination(&record_commitments, &coefficients)
+        {
+            return Err(PieceVerificationError::RecordCommitmentMismatch);
+        }
+
+        let segment_commitment = Commitment::try_from(segment_commitment)
+            .map_err(|_error| PieceVerificationError::InvalidSegmentCommitment)?;
+        if !kzg.verify_aggregated(
+            &segment_commitment,
+            ArchivedHistorySegment::NUM_PIECES,
+            &evaluations,
+        ) {
+            return Err(PieceVerificationError::SegmentCommitmentMismatch);
+        }
+
+        Ok(())
+    }
+
     /// Split piece into underlying components.
     #[inline]
     pub fn split(&self) -> (&Record, &RecordCommitment, &RecordWitness) {
@@ -926,6 +1563,42 @@ impl PieceArray {
         self.split_mut().1
     }
 
+    /// Convenient conversion from slice of piece array to underlying representation for efficiency
+    /// purposes.
+    #[inline]
+    pub fn slice_to_repr(value: &[Self]) -> &[[u8; Piece::SIZE]] {
+        // SAFETY: `PieceArray` is `#[repr(transparent)]` and guaranteed to have the same memory
+        // layout
+        unsafe { mem::transmute(value) }
+    }
+
+    /// Convenient conversion from slice of underlying representation to piece array for efficiency
+    /// purposes.
+    #[inline]
+    pub fn slice_from_repr(value: &[[u8; Piece::SIZE]]) -> &[Self] {
+        // SAFETY: `PieceArray` is `#[repr(transparent)]` and guaranteed to have the same memory
+        // layout
+        unsafe { mem::transmute(value) }
+    }
+
+    /// Convenient conversion from mutable slice of piece array to underlying representation for
+    /// efficiency purposes.
+    #[inline]
+    pub fn slice_mut_to_repr(value: &mut [Self]) -> &mut [[u8; Piece::SIZE]] {
+        // SAFETY: `PieceArray` is `#[repr(transparent)]` and guaranteed to have the same memory
+        // layout
+        unsafe { mem::transmute(value) }
+    }
+
+    /// Convenient conversion from mutable slice of underlying representation to piece array for
+    /// efficiency purposes.
+    #[inline]
+    pub fn slice_mut_from_repr(value: &mut [[u8; Piece::SIZE]]) -> &mut [Self] {
+        // SAFETY: `PieceArray` is `#[repr(transparent)]` and guaranteed to have the same memory
+        // layout
+        unsafe { mem::transmute(value) }
+    }
+
     /// Witness contained within a piece.
     #[inline]
     pub fn witness(&self) -> &RecordWitness {
@@ -939,31 +1612,162 @@ impl PieceArray {
     }
 }
 
-/// Flat representation of multiple pieces concatenated for higher efficient for processing.
-#[derive(
-    Debug,
-    Default,
-    Clone,
-    PartialEq,
-    Eq,
-    Ord,
-    PartialOrd,
-    Hash,
-    Encode,
-    Decode,
-    TypeInfo,
-    Deref,
-    DerefMut,
-)]
-pub struct FlatPieces(Vec<PieceArray>);
+/// Allocator that aligns allocations to at least [`PageAlignedAllocator::ALIGNMENT`] bytes.
+///
+/// Memory allocated this way can be written to or read from disk using direct (unbuffered) I/O,
+/// which requires buffers to be aligned to page/logical block size. It can also be used for sector
+/// buffers with [`Vec::with_capacity_in()`].
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct PageAlignedAllocator;
+
+impl PageAlignedAllocator {
+    /// Alignment of allocations, matches page size and logical block size of most disks
+    pub const ALIGNMENT: usize = 4096;
 
-impl FlatPieces {
-    /// Allocate `FlatPieces` that will hold `piece_count` pieces filled with zeroes.
     #[inline]
-    pub fn new(piece_count: usize) -> Self {
-        let mut pieces = Vec::with_capacity(piece_count);
+    fn aligned_layout(layout: Layout) -> Result<Layout, AllocError> {
+        layout.align_to(Self::ALIGNMENT).map_err(|_| AllocError)
+    }
+}
+
+// SAFETY: All operations are delegated to global allocator, layout is adjusted the same way for
+// both allocation and deallocation
+unsafe impl Allocator for PageAlignedAllocator {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Global.allocate(Self::aligned_layout(layout)?)
+    }
+
+    #[inline]
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Global.allocate_zeroed(Self::aligned_layout(layout)?)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        let layout = Self::aligned_layout(layout)
+            .expect("Layout was successfully aligned during allocation; qed");
+        // SAFETY: Memory was allocated by global allocator with the same aligned layout
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+/// Flat representation of multiple pieces concatenated for higher efficient for processing.
+///
+/// Underlying memory is aligned to [`FlatPieces::ALIGNMENT`], such that byte view of pieces can be
+/// written to disk with direct (unbuffered) I/O without copying.
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Deref, DerefMut)]
+pub struct FlatPieces(Vec<PieceArray, PageAlignedAllocator>);
+
+impl Default for FlatPieces {
+    #[inline]
+    fn default() -> Self {
+        Self(Vec::new_in(PageAlignedAllocator))
+    }
+}
+
+impl Encode for FlatPieces {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        Compact::<u32>::compact_len(&(self.0.len() as u32)) + self.as_ref().len()
+    }
+
+    #[inline]
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        // Same encoding as `Vec<PieceArray>`
+        Compact(self.0.len() as u32).encode_to(dest);
+        dest.write(self.as_ref());
+    }
+}
+
+impl EncodeLike for FlatPieces {}
+
+impl Decode for FlatPieces {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        let piece_count = Compact::<u32>::decode(input)?.0 as usize;
+
+        Self::decode_pieces(input, piece_count)
+    }
+}
+
+impl FlatPieces {
+    /// Decode `piece_count` pieces that follow length prefix
+    fn decode_pieces<I: Input>(
+        input: &mut I,
+        piece_count: usize,
+    ) -> Result<Self, parity_scale_codec::Error> {
+        /// When length of the input is unknown, pieces are allocated in batches, such that invalid
+        /// length prefix doesn't result in huge allocation before input is exhausted
+        const DECODING_BATCH_PIECES: usize = 16;
+
+        let mut flat_pieces = Self::default();
+        let batch_pieces = match input.remaining_len()? {
+            Some(remaining_len) => {
+                if remaining_len / Piece::SIZE < piece_count {
+                    return Err("Not enough data to decode pieces".into());
+                }
+                // Length prefix is known to be valid, allocate all pieces at once
+                flat_pieces.0.reserve_exact(piece_count);
+                piece_count
+            }
+            None => DECODING_BATCH_PIECES,
+        };
+        while flat_pieces.0.len() < piece_count {
+            let offset = flat_pieces.0.len();
+            flat_pieces.extend_zeroed((piece_count - offset).min(batch_pieces));
+            // Read directly into heap allocation, decoding `PieceArray` first would need to place
+            // it on the stack and copy afterwards
+            input
+                .read(PieceArray::slice_mut_to_repr(&mut flat_pieces.0[offset..]).flatten_mut())?;
+        }
+
+        Ok(flat_pieces)
+    }
+}
+
+impl TypeInfo for FlatPieces {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type::builder()
+            .path(scale_info::Path::new(
+                stringify!(FlatPieces),
+                module_path!(),
+            ))
+            .docs(&["Flat representation of multiple pieces concatenated"])
+            .composite(
+                scale_info::build::Fields::unnamed()
+                    .field(|f| f.ty::<Vec<PieceArray>>().type_name("Vec<PieceArray>")),
+            )
+    }
+}
+
+impl FlatPieces {
+    /// Alignment of the memory backing pieces and all slices returned by
+    /// [`Self::as_aligned_slices()`]
+    pub const ALIGNMENT: usize = PageAlignedAllocator::ALIGNMENT;
+    /// Size of slices returned by [`Self::as_aligned_slices()`] (except the last one), multiple of
+    /// [`Self::ALIGNMENT`]
+    pub const ALIGNED_SLICE_SIZE: usize = 256 * Self::ALIGNMENT;
+
+    /// Allocate `FlatPieces` that will hold `piece_count` pieces filled with zeroes.
+    ///
+    /// Underlying memory is aligned to [`Self::ALIGNMENT`].
+    #[inline]
+    pub fn new(piece_count: usize) -> Self {
+        let mut flat_pieces = Self(Vec::with_capacity_in(piece_count, PageAlignedAllocator));
+        flat_pieces.extend_zeroed(piece_count);
+        flat_pieces
+    }
+
+    /// Append `piece_count` pieces filled with zeroes without placing them on the stack.
+    ///
+    /// Capacity grows geometrically if needed, such that appending in batches doesn't reallocate
+    /// on every batch.
+    fn extend_zeroed(&mut self, piece_count: usize) {
+        self.0.reserve(piece_count);
         {
-            let slice = pieces.spare_capacity_mut();
+            let slice = &mut self.0.spare_capacity_mut()[..piece_count];
             // SAFETY: Same memory layout due to `#[repr(transparent)]` on `PieceArray` and
             // `MaybeUninit<[T; N]>` is guaranteed to have the same layout as `[MaybeUninit<T>; N]`
             let slice = unsafe {
@@ -976,16 +1780,25 @@ impl FlatPieces {
                 byte.write(0);
             }
         }
-        // SAFETY: All values are initialized above.
+        // SAFETY: Values are initialized above.
         unsafe {
-            pieces.set_len(pieces.capacity());
+            self.0.set_len(self.0.len() + piece_count);
         }
-        Self(pieces)
+    }
+
+    /// Byte view of all pieces split into slices of [`Self::ALIGNED_SLICE_SIZE`] bytes.
+    ///
+    /// Every slice starts at address aligned to [`Self::ALIGNMENT`] and all slices except the last
+    /// one have length that is multiple of [`Self::ALIGNMENT`], which makes them suitable for direct
+    /// (unbuffered) I/O. The last slice might need to be padded by the caller.
+    #[inline]
+    pub fn as_aligned_slices(&self) -> impl ExactSizeIterator<Item = &'_ [u8]> + '_ {
+        self.as_ref().chunks(Self::ALIGNED_SLICE_SIZE)
     }
 
     /// Extract internal representation.
     #[inline]
-    pub fn into_inner(self) -> Vec<PieceArray> {
+    pub fn into_inner(self) -> Vec<PieceArray, PageAlignedAllocator> {
         self.0
     }
 
@@ -1016,6 +1829,18 @@ impl FlatPieces {
 
 #[cfg(feature = "parallel")]
 impl FlatPieces {
+    /// Parallel iterator over all pieces.
+    #[inline]
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = &'_ PieceArray> + '_ {
+        self.0.par_iter()
+    }
+
+    /// Mutable parallel iterator over all pieces.
+    #[inline]
+    pub fn par_iter_mut(&mut self) -> impl IndexedParallelIterator<Item = &'_ mut PieceArray> + '_ {
+        self.0.par_iter_mut()
+    }
+
     /// Parallel iterator over source pieces (even indices).
     #[inline]
     pub fn par_source(&self) -> impl IndexedParallelIterator<Item = &'_ PieceArray> + '_ {
@@ -1048,7 +1873,9 @@ impl FlatPieces {
 impl From<PieceArray> for FlatPieces {
     #[inline]
     fn from(value: PieceArray) -> Self {
-        Self(vec![value])
+        let mut pieces = Vec::with_capacity_in(1, PageAlignedAllocator);
+        pieces.push(value);
+        Self(pieces)
     }
 }
 
@@ -1069,3 +1896,359 @@ impl AsMut<[u8]> for FlatPieces {
         pieces.flatten_mut()
     }
 }
+
+impl FlatPieces {
+    /// Borrow pieces as [`FlatPiecesView`].
+    #[inline]
+    pub fn as_view(&self) -> FlatPiecesView<'_> {
+        FlatPiecesView(&self.0)
+    }
+
+    /// Borrow pieces as [`FlatPiecesViewMut`].
+    #[inline]
+    pub fn as_view_mut(&mut self) -> FlatPiecesViewMut<'_> {
+        FlatPiecesViewMut(&mut self.0)
+    }
+}
+
+impl From<FlatPiecesView<'_>> for FlatPieces {
+    #[inline]
+    fn from(value: FlatPiecesView<'_>) -> Self {
+        Self(value.0.to_vec_in(PageAlignedAllocator))
+    }
+}
+
+/// [`FlatPieces`] that hold at most `N` pieces.
+///
+/// Unlike [`FlatPieces`] it implements [`MaxEncodedLen`] and can be used in bounded runtime
+/// storage, encoding is the same as for [`FlatPieces`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Deref)]
+pub struct BoundedFlatPieces<const N: usize>(FlatPieces);
+
+impl<const N: usize> Encode for BoundedFlatPieces<N> {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+
+    #[inline]
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        self.0.encode_to(dest)
+    }
+}
+
+impl<const N: usize> EncodeLike for BoundedFlatPieces<N> {}
+
+impl<const N: usize> EncodeLike<FlatPieces> for BoundedFlatPieces<N> {}
+
+impl<const N: usize> Decode for BoundedFlatPieces<N> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        let piece_count = Compact::<u32>::decode(input)?.0 as usize;
+        if piece_count > N {
+            return Err("BoundedFlatPieces exceeds its limit".into());
+        }
+
+        FlatPieces::decode_pieces(input, piece_count).map(Self)
+    }
+}
+
+impl<const N: usize> MaxEncodedLen for BoundedFlatPieces<N> {
+    #[inline]
+    fn max_encoded_len() -> usize {
+        Compact::<u32>::compact_len(&(N as u32)) + N * Piece::SIZE
+    }
+}
+
+impl<const N: usize> TypeInfo for BoundedFlatPieces<N> {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type::builder()
+            .path(scale_info::Path::new(
+                stringify!(BoundedFlatPieces),
+                module_path!(),
+            ))
+            .docs(&["Flat representation of bounded number of pieces concatenated"])
+            .composite(
+                scale_info::build::Fields::unnamed()
+                    .field(|f| f.ty::<Vec<PieceArray>>().type_name("Vec<PieceArray>")),
+            )
+    }
+}
+
+impl<const N: usize> TryFrom<FlatPieces> for BoundedFlatPieces<N> {
+    type Error = FlatPieces;
+
+    /// Returns original pieces back if there are more than `N` of them
+    #[inline]
+    fn try_from(value: FlatPieces) -> Result<Self, Self::Error> {
+        if value.len() > N {
+            return Err(value);
+        }
+
+        Ok(Self(value))
+    }
+}
+
+impl<const N: usize> From<BoundedFlatPieces<N>> for FlatPieces {
+    #[inline]
+    fn from(value: BoundedFlatPieces<N>) -> Self {
+        value.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for BoundedFlatPieces<N> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl<const N: usize> AsMut<[u8]> for BoundedFlatPieces<N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.0.as_mut()
+    }
+}
+
+impl<const N: usize> BoundedFlatPieces<N> {
+    /// Maximum number of pieces
+    pub const BOUND: usize = N;
+
+    /// Allocate `BoundedFlatPieces` that will hold `piece_count` pieces filled with zeroes.
+    ///
+    /// Returns `None` if `piece_count` exceeds `N`.
+    #[inline]
+    pub fn new(piece_count: usize) -> Option<Self> {
+        (piece_count <= N).then(|| Self(FlatPieces::new(piece_count)))
+    }
+
+    /// Extract internal representation.
+    #[inline]
+    pub fn into_inner(self) -> FlatPieces {
+        self.0
+    }
+
+    /// Borrow pieces as [`FlatPiecesView`].
+    #[inline]
+    pub fn as_view(&self) -> FlatPiecesView<'_> {
+        self.0.as_view()
+    }
+
+    /// Borrow pieces as [`FlatPiecesViewMut`], number of pieces can't be changed through it.
+    #[inline]
+    pub fn as_view_mut(&mut self) -> FlatPiecesViewMut<'_> {
+        self.0.as_view_mut()
+    }
+}
+
+/// Borrowed flat representation of multiple pieces concatenated, see [`FlatPieces`] for owned
+/// version.
+///
+/// Unlike [`FlatPieces`] it can be created over externally owned memory (like memory-mapped file
+/// with plotted sectors or archived segments) without copying anything.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deref)]
+pub struct FlatPiecesView<'a>(&'a [PieceArray]);
+
+impl<'a> FlatPiecesView<'a> {
+    /// Create view over pieces concatenated in `bytes`.
+    ///
+    /// Returns `None` if length of `bytes` is not a multiple of [`Piece::SIZE`]. There are no
+    /// alignment requirements.
+    #[inline]
+    pub fn from_bytes(bytes: &'a [u8]) -> Option<Self> {
+        if bytes.len() % Piece::SIZE != 0 {
+            return None;
+        }
+
+        // SAFETY: Length was checked above and `[u8; Piece::SIZE]` has alignment of 1
+        let pieces = unsafe {
+            slice::from_raw_parts(
+                bytes.as_ptr() as *const [u8; Piece::SIZE],
+                bytes.len() / Piece::SIZE,
+            )
+        };
+
+        Some(Self(PieceArray::slice_from_repr(pieces)))
+    }
+
+    /// Extract internal representation.
+    #[inline]
+    pub fn into_inner(self) -> &'a [PieceArray] {
+        self.0
+    }
+
+    /// Iterator over source pieces (even indices).
+    #[inline]
+    pub fn source(&self) -> impl ExactSizeIterator<Item = &'a PieceArray> + 'a {
+        self.0.iter().step_by(2)
+    }
+
+    /// Iterator over parity pieces (odd indices).
+    #[inline]
+    pub fn parity(&self) -> impl ExactSizeIterator<Item = &'a PieceArray> + 'a {
+        self.0.iter().skip(1).step_by(2)
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<'a> FlatPiecesView<'a> {
+    /// Parallel iterator over all pieces.
+    #[inline]
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = &'a PieceArray> + 'a {
+        self.0.par_iter()
+    }
+
+    /// Parallel iterator over source pieces (even indices).
+    #[inline]
+    pub fn par_source(&self) -> impl IndexedParallelIterator<Item = &'a PieceArray> + 'a {
+        self.0.par_iter().step_by(2)
+    }
+
+    /// Parallel iterator over parity pieces (odd indices).
+    #[inline]
+    pub fn par_parity(&self) -> impl IndexedParallelIterator<Item = &'a PieceArray> + 'a {
+        self.0.par_iter().skip(1).step_by(2)
+    }
+}
+
+impl<'a> From<&'a [PieceArray]> for FlatPiecesView<'a> {
+    #[inline]
+    fn from(value: &'a [PieceArray]) -> Self {
+        Self(value)
+    }
+}
+
+impl AsRef<[u8]> for FlatPiecesView<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        PieceArray::slice_to_repr(self.0).flatten()
+    }
+}
+
+/// Mutable borrowed flat representation of multiple pieces concatenated, see [`FlatPiecesView`].
+#[derive(Debug, PartialEq, Eq, Deref, DerefMut)]
+pub struct FlatPiecesViewMut<'a>(&'a mut [PieceArray]);
+
+impl<'a> FlatPiecesViewMut<'a> {
+    /// Create mutable view over pieces concatenated in `bytes`.
+    ///
+    /// Returns `None` if length of `bytes` is not a multiple of [`Piece::SIZE`]. There are no
+    /// alignment requirements.
+    #[inline]
+    pub fn from_bytes(bytes: &'a mut [u8]) -> Option<Self> {
+        if bytes.len() % Piece::SIZE != 0 {
+            return None;
+        }
+
+        // SAFETY: Length was checked above and `[u8; Piece::SIZE]` has alignment of 1
+        let pieces = unsafe {
+            slice::from_raw_parts_mut(
+                bytes.as_mut_ptr() as *mut [u8; Piece::SIZE],
+                bytes.len() / Piece::SIZE,
+            )
+        };
+
+        Some(Self(PieceArray::slice_mut_from_repr(pieces)))
+    }
+
+    /// Extract internal representation.
+    #[inline]
+    pub fn into_inner(self) -> &'a mut [PieceArray] {
+        self.0
+    }
+
+    /// Reborrow as immutable view.
+    #[inline]
+    pub fn as_view(&self) -> FlatPiecesView<'_> {
+        FlatPiecesView(self.0)
+    }
+
+    /// Iterator over source pieces (even indices).
+    #[inline]
+    pub fn source(&self) -> impl ExactSizeIterator<Item = &'_ PieceArray> + '_ {
+        self.0.iter().step_by(2)
+    }
+
+    /// Mutable iterator over source pieces (even indices).
+    #[inline]
+    pub fn source_mut(&mut self) -> impl ExactSizeIterator<Item = &'_ mut PieceArray> + '_ {
+        self.0.iter_mut().step_by(2)
+    }
+
+    /// Iterator over parity pieces (odd indices).
+    #[inline]
+    pub fn parity(&self) -> impl ExactSizeIterator<Item = &'_ PieceArray> + '_ {
+        self.0.iter().skip(1).step_by(2)
+    }
+
+    /// Mutable iterator over parity pieces (odd indices).
+    #[inline]
+    pub fn parity_mut(&mut self) -> impl ExactSizeIterator<Item = &'_ mut PieceArray> + '_ {
+        self.0.iter_mut().skip(1).step_by(2)
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl FlatPiecesViewMut<'_> {
+    /// Parallel iterator over all pieces.
+    #[inline]
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = &'_ PieceArray> + '_ {
+        self.0.par_iter()
+    }
+
+    /// Mutable parallel iterator over all pieces.
+    #[inline]
+    pub fn par_iter_mut(&mut self) -> impl IndexedParallelIterator<Item = &'_ mut PieceArray> + '_ {
+        self.0.par_iter_mut()
+    }
+
+    /// Parallel iterator over source pieces (even indices).
+    #[inline]
+    pub fn par_source(&self) -> impl IndexedParallelIterator<Item = &'_ PieceArray> + '_ {
+        self.0.par_iter().step_by(2)
+    }
+
+    /// Mutable parallel iterator over source pieces (even indices).
+    #[inline]
+    pub fn par_source_mut(
+        &mut self,
+    ) -> impl IndexedParallelIterator<Item = &'_ mut PieceArray> + '_ {
+        self.0.par_iter_mut().step_by(2)
+    }
+
+    /// Parallel iterator over parity pieces (odd indices).
+    #[inline]
+    pub fn par_parity(&self) -> impl IndexedParallelIterator<Item = &'_ PieceArray> + '_ {
+        self.0.par_iter().skip(1).step_by(2)
+    }
+
+    /// Mutable parallel iterator over parity pieces (odd indices).
+    #[inline]
+    pub fn par_parity_mut(
+        &mut self,
+    ) -> impl IndexedParallelIterator<Item = &'_ mut PieceArray> + '_ {
+        self.0.par_iter_mut().skip(1).step_by(2)
+    }
+}
+
+impl<'a> From<&'a mut [PieceArray]> for FlatPiecesViewMut<'a> {
+    #[inline]
+    fn from(value: &'a mut [PieceArray]) -> Self {
+        Self(value)
+    }
+}
+
+impl AsRef<[u8]> for FlatPiecesViewMut<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        PieceArray::slice_to_repr(self.0).flatten()
+    }
+}
+
+impl AsMut<[u8]> for FlatPiecesViewMut<'_> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        PieceArray::slice_mut_to_repr(self.0).flatten_mut()
+    }
+}
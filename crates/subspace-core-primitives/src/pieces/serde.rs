@@ -1,7 +1,40 @@
-use crate::{FlatPieces, Piece, PieceArray};
+use crate::serde::hex_bytes;
+use crate::{FlatPieces, Piece, PieceArray, PiecePosition};
+use alloc::vec::Vec;
 use hex::{decode_to_slice, FromHex, FromHexError};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+impl hex_bytes::FromBytes for PieceArray {
+    #[inline]
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Vec<u8>> {
+        if bytes.len() != Piece::SIZE {
+            return Err(bytes);
+        }
+
+        let mut out = Self::default();
+        out.copy_from_slice(&bytes);
+
+        Ok(out)
+    }
+}
+
+impl hex_bytes::FromBytes for FlatPieces {
+    #[inline]
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Vec<u8>> {
+        if bytes.len() % Piece::SIZE != 0 {
+            return Err(bytes);
+        }
+
+        let mut out = FlatPieces::new(bytes.len() / Piece::SIZE);
+        bytes
+            .chunks_exact(Piece::SIZE)
+            .zip(out.iter_mut())
+            .for_each(|(bytes, piece)| piece.copy_from_slice(bytes));
+
+        Ok(out)
+    }
+}
+
 impl FromHex for PieceArray {
     type Error = FromHexError;
 
@@ -36,7 +69,7 @@ impl Serialize for PieceArray {
                 where
                     S: Serializer,
                 {
-                    hex::serde::serialize(self.values, serializer)
+                    hex_bytes::serialize(self.values, serializer)
                 }
             }
             &SerializeWith {
@@ -65,7 +98,7 @@ impl<'de> Deserialize<'de> for PieceArray {
             where
                 D: Deserializer<'de>,
             {
-                hex::serde::deserialize(deserializer)
+                hex_bytes::deserialize(deserializer)
             }
 
             #[inline]
@@ -82,7 +115,7 @@ impl<'de> Deserialize<'de> for PieceArray {
                         D: Deserializer<'de>,
                     {
                         Ok(DeserializeWith {
-                            value: hex::serde::deserialize(deserializer)?,
+                            value: hex_bytes::deserialize(deserializer)?,
                         })
                     }
                 }
@@ -135,7 +168,7 @@ impl Serialize for FlatPieces {
                 where
                     S: Serializer,
                 {
-                    hex::serde::serialize(self.values, serializer)
+                    hex_bytes::serialize(self.values, serializer)
                 }
             }
             &SerializeWith {
@@ -164,7 +197,7 @@ impl<'de> Deserialize<'de> for FlatPieces {
             where
                 D: Deserializer<'de>,
             {
-                hex::serde::deserialize(deserializer)
+                hex_bytes::deserialize(deserializer)
             }
 
             #[inline]
@@ -181,7 +214,7 @@ impl<'de> Deserialize<'de> for FlatPieces {
                         D: Deserializer<'de>,
                     {
                         Ok(DeserializeWith {
-                            value: hex::serde::deserialize(deserializer)?,
+                            value: hex_bytes::deserialize(deserializer)?,
                         })
                     }
                 }
@@ -197,3 +230,23 @@ impl<'de> Deserialize<'de> for FlatPieces {
         Deserializer::deserialize_newtype_struct(deserializer, "FlatPieces", Visitor)
     }
 }
+
+impl<'de> Deserialize<'de> for PiecePosition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        /// Same representation as derived implementation would use, but without validation
+        #[derive(Deserialize)]
+        #[serde(rename = "PiecePosition")]
+        struct UncheckedPiecePosition(u32);
+
+        let UncheckedPiecePosition(position) = UncheckedPiecePosition::deserialize(deserializer)?;
+
+        PiecePosition::new(position).ok_or_else(|| {
+            de::Error::custom(format_args!(
+                "piece position {position} is outside of archived segment"
+            ))
+        })
+    }
+}
@@ -1,8 +1,462 @@
-use crate::pieces::SBucket;
-use crate::Record;
+use crate::pieces::{
+    BoundedFlatPieces, FlatPieces, FlatPiecesView, FlatPiecesViewMut, Piece, PieceCheckError,
+    PieceIndex, PiecePosition, RawRecord, RecordChunksView, SBucket,
+};
+use crate::segments::{ArchivedHistorySegment, SegmentIndex};
+use crate::{Record, RecordedHistorySegment};
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 
 // Statically validate that we can store all possible s-buckets in SBucket data structure
 #[test]
 fn s_buckets_fit_into_data_structure() {
     assert!((SBucket::ZERO..=SBucket(u16::MAX)).count() <= Record::NUM_S_BUCKETS);
 }
+
+#[test]
+fn heap_allocated_raw_records() {
+    let mut raw_records = RawRecord::new_zero_vec(2);
+    assert_eq!(raw_records.len(), 2);
+    assert!(raw_records
+        .iter()
+        .all(|raw_record| raw_record.as_ref().iter().all(|&byte| byte == 0)));
+
+    raw_records[1].as_mut().fill(1);
+    raw_records[1].fill_zeroes();
+    assert_eq!(raw_records[1].as_ref(), RawRecord::new_boxed().as_ref());
+}
+
+#[test]
+fn heap_allocated_recorded_history_segment() {
+    let bytes = [1, 2, 3];
+    let mut segment = RecordedHistorySegment::from_bytes_boxed(&bytes).unwrap();
+    assert_eq!(&segment.as_ref()[..bytes.len()], &bytes);
+    assert!(segment.as_ref()[bytes.len()..]
+        .iter()
+        .all(|&byte| byte == 0));
+
+    segment.fill_zeroes();
+    assert!(segment.as_ref().iter().all(|&byte| byte == 0));
+
+    assert!(
+        RecordedHistorySegment::from_bytes_boxed(&vec![0; RecordedHistorySegment::SIZE + 1])
+            .is_none()
+    );
+}
+
+#[test]
+fn flat_pieces_views() {
+    let mut bytes = vec![0u8; Piece::SIZE * 4];
+    for (index, piece_bytes) in bytes.chunks_exact_mut(Piece::SIZE).enumerate() {
+        piece_bytes.fill(index as u8);
+    }
+
+    {
+        let mut view = FlatPiecesViewMut::from_bytes(&mut bytes).unwrap();
+        assert_eq!(view.len(), 4);
+        for piece in view.parity_mut() {
+            piece.as_mut()[0] = u8::MAX;
+        }
+    }
+
+    let view = FlatPiecesView::from_bytes(&bytes).unwrap();
+    assert_eq!(view.as_ref(), bytes.as_slice());
+    assert_eq!(view.source().count(), 2);
+    assert!(view
+        .source()
+        .enumerate()
+        .all(|(index, piece)| piece.as_ref()[0] == (index * 2) as u8));
+    assert!(view.parity().all(|piece| piece.as_ref()[0] == u8::MAX));
+
+    let flat_pieces = FlatPieces::from(view);
+    assert_eq!(flat_pieces.as_view(), view);
+    assert_eq!(flat_pieces.as_ref(), bytes.as_slice());
+
+    assert!(FlatPiecesView::from_bytes(&bytes[1..]).is_none());
+    assert!(FlatPiecesViewMut::from_bytes(&mut bytes[..Piece::SIZE - 1]).is_none());
+}
+
+#[test]
+fn flat_pieces_alignment() {
+    let mut flat_pieces = FlatPieces::new(3);
+    assert_eq!(
+        flat_pieces.as_ref().as_ptr() as usize % FlatPieces::ALIGNMENT,
+        0
+    );
+    flat_pieces
+        .iter_mut()
+        .enumerate()
+        .for_each(|(index, piece)| piece.as_mut().fill(index as u8));
+
+    let aligned_slices = flat_pieces.as_aligned_slices().collect::<Vec<_>>();
+    assert_eq!(aligned_slices.concat(), flat_pieces.as_ref());
+    for aligned_slice in &aligned_slices {
+        assert_eq!(aligned_slice.as_ptr() as usize % FlatPieces::ALIGNMENT, 0);
+    }
+    for aligned_slice in &aligned_slices[..aligned_slices.len() - 1] {
+        assert_eq!(aligned_slice.len() % FlatPieces::ALIGNMENT, 0);
+    }
+
+    // Alignment is preserved by other ways of creating flat pieces
+    for flat_pieces in [
+        flat_pieces.clone(),
+        FlatPieces::from(flat_pieces.as_view()),
+        FlatPieces::decode(&mut flat_pieces.encode().as_slice()).unwrap(),
+    ] {
+        assert_eq!(
+            flat_pieces.as_ref().as_ptr() as usize % FlatPieces::ALIGNMENT,
+            0
+        );
+    }
+
+    // Encoding is the same as for vector of pieces
+    assert_eq!(flat_pieces.encode(), flat_pieces.to_vec().encode());
+    assert_eq!(
+        FlatPieces::decode(&mut flat_pieces.to_vec().encode().as_slice()).unwrap(),
+        flat_pieces
+    );
+    // Truncated input is rejected
+    let encoding = flat_pieces.encode();
+    assert!(FlatPieces::decode(&mut &encoding[..encoding.len() - 1]).is_err());
+
+    // When input length is known, all pieces are allocated at once
+    let many_pieces = FlatPieces::new(100);
+    let decoded = FlatPieces::decode(&mut many_pieces.encode().as_slice()).unwrap();
+    assert_eq!(decoded.0.capacity(), 100);
+    assert_eq!(decoded, many_pieces);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn flat_pieces_parallel_iterators() {
+    use rayon::prelude::*;
+
+    let mut flat_pieces = FlatPieces::new(5);
+    flat_pieces
+        .par_iter_mut()
+        .enumerate()
+        .for_each(|(index, piece)| piece.as_mut().fill(index as u8));
+
+    assert_eq!(
+        flat_pieces
+            .par_source()
+            .map(|piece| piece.as_ref()[0])
+            .collect::<Vec<_>>(),
+        flat_pieces
+            .source()
+            .map(|piece| piece.as_ref()[0])
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        flat_pieces
+            .par_parity()
+            .map(|piece| piece.as_ref()[0])
+            .collect::<Vec<_>>(),
+        flat_pieces
+            .parity()
+            .map(|piece| piece.as_ref()[0])
+            .collect::<Vec<_>>()
+    );
+
+    let view = flat_pieces.as_view();
+    assert_eq!(view.par_iter().count(), 5);
+    assert_eq!(view.par_source().count(), 3);
+    assert_eq!(view.par_parity().count(), 2);
+}
+
+#[test]
+fn piece_positions() {
+    let segment_index = SegmentIndex::from(2);
+    let first_piece_index = segment_index.first_piece_index();
+
+    for (position, piece_index) in segment_index
+        .segment_piece_indexes()
+        .into_iter()
+        .enumerate()
+    {
+        let position = PiecePosition::new(position as u32).unwrap();
+        assert_eq!(piece_index.segment_index(), segment_index);
+        assert_eq!(piece_index.position(), position);
+        assert_eq!(segment_index.piece_index(position), piece_index);
+        assert_eq!(piece_index.is_source(), position.is_source());
+    }
+
+    let source_position = PiecePosition::from_source_index(3).unwrap();
+    assert!(source_position.is_source());
+    assert!(!source_position.is_parity());
+    assert_eq!(source_position.source_index(), Some(3));
+    assert_eq!(source_position.parity_index(), None);
+    assert_eq!(
+        segment_index.piece_index(source_position),
+        first_piece_index + PieceIndex::from(6)
+    );
+
+    let parity_position = PiecePosition::from_parity_index(3).unwrap();
+    assert!(parity_position.is_parity());
+    assert_eq!(parity_position.parity_index(), Some(3));
+    assert_eq!(parity_position.source_index(), None);
+    assert_eq!(u32::from(parity_position), 7);
+
+    // Positions outside of archived segment
+    assert!(PiecePosition::new(ArchivedHistorySegment::NUM_PIECES as u32).is_none());
+    assert!(
+        PiecePosition::from_source_index(RecordedHistorySegment::NUM_RAW_RECORDS as u32).is_none()
+    );
+    assert!(
+        PiecePosition::from_parity_index(RecordedHistorySegment::NUM_RAW_RECORDS as u32).is_none()
+    );
+    assert!(PiecePosition::try_from(ArchivedHistorySegment::NUM_PIECES as u32).is_err());
+
+    // Decoding goes through the same range check
+    assert_eq!(
+        PiecePosition::decode(&mut parity_position.encode().as_slice()).unwrap(),
+        parity_position
+    );
+    assert!(PiecePosition::decode(
+        &mut (ArchivedHistorySegment::NUM_PIECES as u32)
+            .encode()
+            .as_slice()
+    )
+    .is_err());
+    #[cfg(feature = "serde")]
+    {
+        assert_eq!(
+            serde_json::from_str::<PiecePosition>(
+                &serde_json::to_string(&parity_position).unwrap()
+            )
+            .unwrap(),
+            parity_position
+        );
+        assert!(serde_json::from_str::<PiecePosition>(
+            &ArchivedHistorySegment::NUM_PIECES.to_string()
+        )
+        .is_err());
+    }
+}
+
+#[test]
+fn record_chunks_view() {
+    let source_s_bucket = SBucket::from_source_chunk_index(3).unwrap();
+    assert!(source_s_bucket.is_source());
+    assert_eq!(source_s_bucket.source_chunk_index(), Some(3));
+    assert_eq!(source_s_bucket.parity_chunk_index(), None);
+    assert_eq!(u16::from(source_s_bucket), 6);
+
+    let parity_s_bucket = SBucket::from_parity_chunk_index(3).unwrap();
+    assert!(parity_s_bucket.is_parity());
+    assert_eq!(parity_s_bucket.parity_chunk_index(), Some(3));
+    assert_eq!(parity_s_bucket.source_chunk_index(), None);
+    assert_eq!(u16::from(parity_s_bucket), 7);
+
+    assert!(SBucket::from_source_chunk_index(Record::NUM_CHUNKS).is_none());
+    assert!(SBucket::from_parity_chunk_index(Record::NUM_CHUNKS).is_none());
+
+    let mut record = Record::new_boxed();
+    for (index, chunk) in record.iter_mut().enumerate() {
+        chunk[..4].copy_from_slice(&(index as u32).to_le_bytes());
+    }
+    let source_chunks = record.to_vec();
+    let parity_chunks = source_chunks
+        .iter()
+        .map(|chunk| chunk.map(|byte| !byte))
+        .collect::<Vec<_>>();
+
+    assert!(RecordChunksView::new(&source_chunks, &parity_chunks[1..]).is_none());
+    let record_chunks = RecordChunksView::new(&source_chunks, &parity_chunks).unwrap();
+
+    assert_eq!(record_chunks.iter().count(), Record::NUM_S_BUCKETS);
+    for (s_bucket, chunk) in (SBucket::ZERO..=SBucket::MAX).zip(record_chunks.iter()) {
+        assert_eq!(record_chunks.chunk_for_s_bucket(s_bucket), chunk);
+        assert_eq!(
+            record.source_chunk_for_s_bucket(s_bucket),
+            s_bucket.is_source().then_some(chunk)
+        );
+        match s_bucket.source_chunk_index() {
+            Some(source_chunk_index) => assert_eq!(chunk, &source_chunks[source_chunk_index]),
+            None => assert_eq!(
+                chunk,
+                &parity_chunks[s_bucket.parity_chunk_index().unwrap()]
+            ),
+        }
+    }
+}
+
+#[test]
+fn bounded_flat_pieces() {
+    let mut flat_pieces = FlatPieces::new(2);
+    flat_pieces[1].as_mut().fill(1);
+
+    let bounded = BoundedFlatPieces::<2>::try_from(flat_pieces.clone()).unwrap();
+    assert!(BoundedFlatPieces::<1>::try_from(flat_pieces.clone()).is_err());
+    assert!(BoundedFlatPieces::<1>::new(2).is_none());
+
+    // Encoding is the same as for unbounded version
+    let encoded = bounded.encode();
+    assert_eq!(encoded, flat_pieces.encode());
+    assert_eq!(encoded.len(), BoundedFlatPieces::<2>::max_encoded_len());
+    assert!(encoded.len() < BoundedFlatPieces::<3>::max_encoded_len());
+
+    assert_eq!(
+        BoundedFlatPieces::<2>::decode(&mut encoded.as_slice()).unwrap(),
+        bounded
+    );
+    assert_eq!(
+        BoundedFlatPieces::<3>::decode(&mut encoded.as_slice())
+            .unwrap()
+            .as_view(),
+        flat_pieces.as_view()
+    );
+    // More pieces than the bound
+    assert!(BoundedFlatPieces::<1>::decode(&mut encoded.as_slice()).is_err());
+
+    assert_eq!(FlatPieces::from(bounded), flat_pieces);
+}
+
+#[test]
+fn record_masking() {
+    let mut record = Record::new_boxed();
+    let mut mask = Record::new_boxed();
+    record
+        .as_mut()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(index, byte)| *byte = index as u8);
+    mask.as_mut()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(index, byte)| *byte = (index * 7) as u8);
+    let original_record = record.clone();
+
+    record.xor_in_place(&mask);
+    for ((byte, original_byte), mask_byte) in record
+        .as_ref()
+        .iter()
+        .zip(original_record.as_ref())
+        .zip(mask.as_ref())
+    {
+        assert_eq!(*byte, original_byte ^ mask_byte);
+    }
+    assert_eq!(Record::mask_chunk(&original_record[1], &mask[1]), record[1]);
+
+    // Masking again restores original record
+    Record::mask_chunk_in_place(&mut record[0], &mask[0]);
+    assert_eq!(record[0], original_record[0]);
+    record.xor_in_place(&mask);
+    assert_ne!(record, original_record);
+    Record::mask_chunk_in_place(&mut record[0], &mask[0]);
+    assert_eq!(record, original_record);
+}
+
+#[test]
+fn piece_decoding() {
+    let mut piece = Piece::default();
+    piece
+        .as_mut()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(index, byte)| *byte = index as u8);
+
+    let encoded = piece.encode();
+    assert_eq!(encoded.len(), Piece::SIZE);
+    assert_eq!(Piece::decode(&mut encoded.as_slice()).unwrap(), piece);
+    assert!(Piece::decode(&mut &encoded[..Piece::SIZE - 1]).is_err());
+
+    assert_eq!(Piece::from_reader(encoded.as_slice()).unwrap(), piece);
+    assert!(Piece::from_reader(&encoded[..Piece::SIZE - 1]).is_err());
+}
+
+#[test]
+fn piece_from_vec() {
+    let bytes = (0..Piece::SIZE)
+        .map(|index| index as u8)
+        .collect::<Vec<_>>();
+    let expected_piece = Piece::try_from(bytes.as_slice()).unwrap();
+
+    // Allocation of exact size is reused
+    let vec = bytes.clone().into_boxed_slice().into_vec();
+    assert_eq!(vec.capacity(), Piece::SIZE);
+    let vec_ptr = vec.as_ptr();
+    let piece = Piece::try_from(vec).unwrap();
+    assert_eq!(piece, expected_piece);
+    assert_eq!(piece.as_ref().as_ptr(), vec_ptr);
+
+    // Excess capacity results in a copy
+    let mut vec = Vec::with_capacity(Piece::SIZE * 2);
+    vec.extend_from_slice(&bytes);
+    assert_eq!(Piece::try_from(vec).unwrap(), expected_piece);
+
+    // Wrong length is rejected
+    assert!(Piece::try_from(bytes[..Piece::SIZE - 1].to_vec()).is_err());
+    let mut vec = bytes;
+    vec.push(0);
+    assert!(Piece::try_from(vec).is_err());
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn piece_array_rkyv() {
+    use crate::pieces::PieceArray;
+
+    let bytes = (0..Piece::SIZE)
+        .map(|index| index as u8)
+        .collect::<Vec<_>>();
+    let piece = Piece::try_from(bytes.as_slice()).unwrap();
+
+    let archived_bytes = rkyv::to_bytes::<PieceArray, 0>(&piece).unwrap();
+    assert_eq!(archived_bytes.len(), Piece::SIZE);
+
+    let archived_piece = rkyv::check_archived_root::<PieceArray>(&archived_bytes).unwrap();
+    // Archived piece is accessed in place, without copying
+    assert_eq!(archived_piece.as_ref().as_ptr(), archived_bytes.as_ptr());
+    assert!(*archived_piece == *piece);
+    assert_eq!(archived_piece.split(), piece.split());
+}
+
+#[test]
+fn piece_checked_construction() {
+    let mut piece = Piece::default();
+    {
+        let (record, commitment, witness) = piece.split_mut();
+        // Chunks below the scalar field modulus are canonical
+        for (index, chunk) in record.iter_mut().enumerate() {
+            chunk[16] = index as u8;
+        }
+        commitment.as_mut().fill(1);
+        witness.as_mut().fill(2);
+    }
+    assert_eq!(Piece::try_new_checked(piece.as_ref()), Ok(piece.clone()));
+
+    assert_eq!(
+        Piece::try_new_checked(&piece.as_ref()[1..]),
+        Err(PieceCheckError::InvalidSize {
+            size: Piece::SIZE - 1
+        })
+    );
+
+    {
+        let mut piece = piece.clone();
+        piece.commitment_mut().as_mut().fill(0);
+        assert_eq!(
+            Piece::try_new_checked(piece.as_ref()),
+            Err(PieceCheckError::ZeroRecordCommitment)
+        );
+    }
+
+    {
+        let mut piece = piece.clone();
+        piece.split_mut().2.as_mut().fill(0);
+        assert_eq!(
+            Piece::try_new_checked(piece.as_ref()),
+            Err(PieceCheckError::ZeroRecordWitness)
+        );
+    }
+
+    {
+        let mut piece = piece.clone();
+        // All ones is above the scalar field modulus
+        piece.record_mut()[5] = [u8::MAX; 32];
+        assert_eq!(
+            Piece::try_new_checked(piece.as_ref()),
+            Err(PieceCheckError::NonCanonicalRecordChunk { chunk_index: 5 })
+        );
+    }
+}
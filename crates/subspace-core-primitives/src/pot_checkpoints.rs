@@ -0,0 +1,195 @@
+//! Compact encoding of proof of time checkpoints of consecutive slots.
+//!
+//! Checkpoints themselves are outputs of AES and can't be compressed, but when checkpoints of
+//! consecutive slots are sent together, a lot of information around them is redundant: slot
+//! numbers are implied by position and the seed of the next slot is in most cases derived from the
+//! output of the previous slot (except for slots where entropy is injected). Compact encoding
+//! stores only the first slot number and seeds that can't be derived.
+
+use crate::{PotCheckpoints, PotSeed, SlotNumber};
+use alloc::vec::Vec;
+use derive_more::Display;
+use parity_scale_codec::{Decode, Encode, Input};
+use scale_info::TypeInfo;
+
+/// Proof of time checkpoints of a single slot together with the seed they were proven from
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Encode, Decode, TypeInfo)]
+pub struct SlotPotCheckpoints {
+    /// Slot number
+    pub slot: SlotNumber,
+    /// Proof of time seed
+    pub seed: PotSeed,
+    /// Proof of time checkpoints
+    pub checkpoints: PotCheckpoints,
+}
+
+/// Error happening when building [`CompactPotCheckpoints`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Display)]
+pub enum CompactPotCheckpointsError {
+    /// Slot doesn't immediately follow the last slot in the sequence
+    #[display(fmt = "Expected slot {expected}, but got slot {actual}")]
+    NonConsecutiveSlot {
+        /// Slot that was expected
+        expected: SlotNumber,
+        /// Slot that was provided
+        actual: SlotNumber,
+    },
+    /// Slot number is too large to be included into the sequence
+    #[display(fmt = "Slot number {_0} is too large")]
+    SlotNumberTooLarge(SlotNumber),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CompactPotCheckpointsError {}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
+enum CompactEntry {
+    /// Seed is derived from the output of the previous slot
+    #[codec(index = 0)]
+    DerivedSeed { checkpoints: PotCheckpoints },
+    /// Seed can't be derived and is stored explicitly
+    #[codec(index = 1)]
+    ExplicitSeed {
+        seed: PotSeed,
+        checkpoints: PotCheckpoints,
+    },
+}
+
+/// Compactly encoded sequence of proof of time checkpoints of consecutive slots.
+///
+/// Use [`Self::push()`] to append slots one by one as they are proven and [`Self::iter()`] to
+/// decode them back on the other side.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Encode, TypeInfo)]
+pub struct CompactPotCheckpoints {
+    first_slot: SlotNumber,
+    entries: Vec<CompactEntry>,
+}
+
+impl Decode for CompactPotCheckpoints {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        let first_slot = SlotNumber::decode(input)?;
+        let entries = Vec::<CompactEntry>::decode(input)?;
+
+        if matches!(entries.first(), Some(CompactEntry::DerivedSeed { .. })) {
+            return Err("First slot must have explicit seed".into());
+        }
+        if first_slot
+            .checked_add(entries.len() as SlotNumber)
+            .is_none()
+        {
+            return Err("Slot number overflow".into());
+        }
+
+        Ok(Self {
+            first_slot,
+            entries,
+        })
+    }
+}
+
+impl CompactPotCheckpoints {
+    /// Create compact encoding from an iterator over checkpoints of consecutive slots
+    pub fn from_slots<I>(slots: I) -> Result<Self, CompactPotCheckpointsError>
+    where
+        I: IntoIterator<Item = SlotPotCheckpoints>,
+    {
+        let mut compact_checkpoints = Self::default();
+        for slot_checkpoints in slots {
+            compact_checkpoints.push(slot_checkpoints)?;
+        }
+
+        Ok(compact_checkpoints)
+    }
+
+    /// Append checkpoints of the slot that immediately follows the last slot in the sequence
+    pub fn push(
+        &mut self,
+        slot_checkpoints: SlotPotCheckpoints,
+    ) -> Result<(), CompactPotCheckpointsError> {
+        let SlotPotCheckpoints {
+            slot,
+            seed,
+            checkpoints,
+        } = slot_checkpoints;
+
+        // Slot after the last one in the sequence must be representable
+        if slot == SlotNumber::MAX {
+            return Err(CompactPotCheckpointsError::SlotNumberTooLarge(slot));
+        }
+
+        let Some(last_entry) = self.entries.last() else {
+            self.first_slot = slot;
+            self.entries
+                .push(CompactEntry::ExplicitSeed { seed, checkpoints });
+            return Ok(());
+        };
+
+        let expected = self.first_slot + self.entries.len() as SlotNumber;
+        if slot != expected {
+            return Err(CompactPotCheckpointsError::NonConsecutiveSlot {
+                expected,
+                actual: slot,
+            });
+        }
+
+        let last_checkpoints = match last_entry {
+            CompactEntry::DerivedSeed { checkpoints } => checkpoints,
+            CompactEntry::ExplicitSeed { checkpoints, .. } => checkpoints,
+        };
+
+        if last_checkpoints.output().seed() == seed {
+            self.entries.push(CompactEntry::DerivedSeed { checkpoints });
+        } else {
+            self.entries
+                .push(CompactEntry::ExplicitSeed { seed, checkpoints });
+        }
+
+        Ok(())
+    }
+
+    /// First slot in the sequence, `None` if sequence is empty
+    pub fn first_slot(&self) -> Option<SlotNumber> {
+        (!self.entries.is_empty()).then_some(self.first_slot)
+    }
+
+    /// Number of slots in the sequence
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether sequence is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over checkpoints of slots in the sequence, restoring slot numbers and seeds
+    pub fn iter(&self) -> impl Iterator<Item = SlotPotCheckpoints> + '_ {
+        let mut last_checkpoints = None::<PotCheckpoints>;
+
+        self.entries
+            .iter()
+            .zip(self.first_slot..)
+            .map(move |(entry, slot)| {
+                let (seed, checkpoints) = match *entry {
+                    CompactEntry::DerivedSeed { checkpoints } => (
+                        last_checkpoints
+                            .expect(
+                                "First entry always has explicit seed, guaranteed by `push()` and \
+                                decoding; qed",
+                            )
+                            .output()
+                            .seed(),
+                        checkpoints,
+                    ),
+                    CompactEntry::ExplicitSeed { seed, checkpoints } => (seed, checkpoints),
+                };
+                last_checkpoints.replace(checkpoints);
+
+                SlotPotCheckpoints {
+                    slot,
+                    seed,
+                    checkpoints,
+                }
+            })
+    }
+}
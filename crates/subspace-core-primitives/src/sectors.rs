@@ -0,0 +1,216 @@
+//! Sector geometry: sector IDs, per-slot sector challenges and s-bucket layout within a sector.
+//!
+//! Farmer, consensus verification and the runtime rely on these to agree on which pieces are
+//! stored in a sector and which part of the sector is audited in a particular slot.
+
+#[cfg(test)]
+mod tests;
+
+use crate::crypto::{blake3_hash_list, blake3_hash_with_key};
+use crate::{
+    Blake3Hash, GlobalChallenge, HistorySize, PieceIndex, PieceOffset, PosSeed, PublicKeyHash,
+    Record, SBucket, SectorIndex, SegmentCommitment, SolutionRange, U256,
+};
+#[cfg(feature = "serde")]
+use ::serde::{Deserialize, Serialize};
+use alloc::boxed::Box;
+use core::mem;
+use core::num::NonZeroU64;
+use core::simd::Simd;
+use derive_more::Deref;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use static_assertions::const_assert;
+
+/// Challenge used for a particular sector for particular slot
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deref)]
+pub struct SectorSlotChallenge(Blake3Hash);
+
+impl SectorSlotChallenge {
+    /// Index of s-bucket within sector to be audited
+    #[inline]
+    pub fn s_bucket_audit_index(&self) -> SBucket {
+        // As long as number of s-buckets is a power of two not larger than 2^16, we can pick first
+        // two bytes instead of actually calculating `U256::from_le_bytes(self.0) %
+        // Record::NUM_S_BUCKETS)`, with 2^16 s-buckets modulo is a no-op
+        const_assert!(Record::NUM_S_BUCKETS.is_power_of_two());
+        const_assert!(Record::NUM_S_BUCKETS <= 1 << u16::BITS as usize);
+        let s_bucket = u32::from(u16::from_le_bytes([self.0[0], self.0[1]]));
+        SBucket::from((s_bucket % Record::NUM_S_BUCKETS as u32) as u16)
+    }
+
+    /// Audit chunk derived from masked chunk, interpreted as solution range
+    #[inline]
+    pub fn audit_chunk(&self, chunk: &[u8; 32]) -> SolutionRange {
+        let audit_chunk = blake3_hash_with_key(&self.0, chunk);
+        SolutionRange::from_le_bytes(
+            *audit_chunk
+                .array_chunks::<{ mem::size_of::<SolutionRange>() }>()
+                .next()
+                .expect("Solution range is smaller in size than audit chunk; qed"),
+        )
+    }
+}
+
+/// Data structure representing sector ID in farmer's plot
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SectorId(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))] Blake3Hash,
+);
+
+impl AsRef<[u8]> for SectorId {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl SectorId {
+    /// Create new sector ID by deriving it from public key and sector index
+    pub fn new(public_key_hash: PublicKeyHash, sector_index: SectorIndex) -> Self {
+        Self(blake3_hash_with_key(
+            &public_key_hash,
+            &sector_index.to_le_bytes(),
+        ))
+    }
+
+    /// Derive piece index that should be stored in sector at `piece_offset` for specified size of
+    /// blockchain history
+    pub fn derive_piece_index(
+        &self,
+        piece_offset: PieceOffset,
+        history_size: HistorySize,
+        max_pieces_in_sector: u16,
+        recent_segments: HistorySize,
+        recent_history_fraction: (HistorySize, HistorySize),
+    ) -> PieceIndex {
+        let recent_segments_in_pieces = recent_segments.in_pieces().get();
+        // Recent history must be at most `recent_history_fraction` of all history to use separate
+        // policy for recent pieces
+        let min_history_size_in_pieces = recent_segments_in_pieces
+            * recent_history_fraction.1.in_pieces().get()
+            / recent_history_fraction.0.in_pieces().get();
+        let input_hash = {
+            let piece_offset_bytes = piece_offset.to_bytes();
+            let mut key = [0; 32];
+            key[..piece_offset_bytes.len()].copy_from_slice(&piece_offset_bytes);
+            U256::from_le_bytes(blake3_hash_with_key(&key, &self.0))
+        };
+        let history_size_in_pieces = history_size.in_pieces().get();
+        let num_interleaved_pieces = 1.max(
+            u64::from(max_pieces_in_sector) * recent_history_fraction.0.in_pieces().get()
+                / recent_history_fraction.1.in_pieces().get()
+                * 2,
+        );
+
+        let piece_index = if history_size_in_pieces > min_history_size_in_pieces
+            && u64::from(piece_offset) < num_interleaved_pieces
+            && u16::from(piece_offset) % 2 == 1
+        {
+            // For odd piece offsets at the beginning of the sector pick pieces at random from
+            // recent history only
+            input_hash % U256::from(recent_segments_in_pieces)
+                + U256::from(history_size_in_pieces - recent_segments_in_pieces)
+        } else {
+            input_hash % U256::from(history_size_in_pieces)
+        };
+
+        PieceIndex::from(u64::try_from(piece_index).expect(
+            "Remainder of division by PieceIndex is guaranteed to fit into PieceIndex; qed",
+        ))
+    }
+
+    /// Derive sector slot challenge for this sector from provided global challenge
+    pub fn derive_sector_slot_challenge(
+        &self,
+        global_challenge: &GlobalChallenge,
+    ) -> SectorSlotChallenge {
+        let sector_slot_challenge = Simd::from(self.0) ^ Simd::from(global_challenge.0);
+        SectorSlotChallenge(sector_slot_challenge.to_array())
+    }
+
+    /// Derive evaluation seed
+    pub fn derive_evaluation_seed(
+        &self,
+        piece_offset: PieceOffset,
+        history_size: HistorySize,
+    ) -> PosSeed {
+        let evaluation_seed = blake3_hash_list(&[
+            &self.0,
+            &piece_offset.to_bytes(),
+            &history_size.get().to_le_bytes(),
+        ]);
+
+        PosSeed::from(evaluation_seed)
+    }
+
+    /// Derive history size when sector created at `history_size` expires.
+    ///
+    /// Returns `None` on overflow.
+    pub fn derive_expiration_history_size(
+        &self,
+        history_size: HistorySize,
+        sector_expiration_check_segment_commitment: &SegmentCommitment,
+        min_sector_lifetime: HistorySize,
+    ) -> Option<HistorySize> {
+        let sector_expiration_check_history_size =
+            history_size.sector_expiration_check(min_sector_lifetime)?;
+
+        let input_hash = U256::from_le_bytes(blake3_hash_list(&[
+            &self.0,
+            sector_expiration_check_segment_commitment.as_ref(),
+        ]));
+
+        let last_possible_expiration =
+            min_sector_lifetime.checked_add(history_size.get().checked_mul(4u64)?)?;
+        let expires_in = input_hash
+            % U256::from(
+                last_possible_expiration
+                    .get()
+                    .checked_sub(sector_expiration_check_history_size.get())?,
+            );
+        let expires_in = u64::try_from(expires_in).expect("Number modulo u64 fits into u64; qed");
+
+        let expiration_history_size = sector_expiration_check_history_size.get() + expires_in;
+        let expiration_history_size = NonZeroU64::try_from(expiration_history_size).expect(
+            "History size is not zero, so result is not zero even if expires immediately; qed",
+        );
+        Some(HistorySize::from(expiration_history_size))
+    }
+}
+
+impl SBucket {
+    /// Offset of this s-bucket relatively to the beginning of s-buckets of a sector (in chunks),
+    /// given sizes of all s-buckets in a sector (in chunks)
+    #[inline]
+    pub fn offset_in_sector(&self, s_bucket_sizes: &[u16; Record::NUM_S_BUCKETS]) -> u32 {
+        s_bucket_sizes
+            .iter()
+            .take(usize::from(*self))
+            .copied()
+            .map(u32::from)
+            .sum()
+    }
+
+    /// Offsets of all s-buckets relatively to the beginning of s-buckets of a sector (in chunks),
+    /// given sizes of all s-buckets in a sector (in chunks)
+    pub fn offsets_in_sector(
+        s_bucket_sizes: &[u16; Record::NUM_S_BUCKETS],
+    ) -> Box<[u32; Record::NUM_S_BUCKETS]> {
+        // TODO: Should have been just `::new()`, but https://github.com/rust-lang/rust/issues/53827
+        // SAFETY: Data structure filled with zeroes is a valid invariant
+        let mut s_bucket_offsets =
+            unsafe { Box::<[u32; Record::NUM_S_BUCKETS]>::new_zeroed().assume_init() };
+
+        let mut base_offset = 0;
+        for (s_bucket_size, s_bucket_offset) in
+            s_bucket_sizes.iter().zip(s_bucket_offsets.iter_mut())
+        {
+            *s_bucket_offset = base_offset;
+            base_offset += u32::from(*s_bucket_size);
+        }
+
+        s_bucket_offsets
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{
+    GlobalChallenge, PublicKey, Record, SBucket, SectorId, SectorIndex, Solution, SolutionRange,
+};
+
+fn sector_id(sector_index: SectorIndex) -> SectorId {
+    SectorId::new(PublicKey::from([1; 32]).hash(), sector_index)
+}
+
+#[test]
+fn sector_id_derivation() {
+    let public_key_hash = PublicKey::from([1; 32]).hash();
+
+    assert_eq!(
+        SectorId::new(public_key_hash, 0),
+        SectorId::new(public_key_hash, 0)
+    );
+    assert_ne!(
+        SectorId::new(public_key_hash, 0),
+        SectorId::new(public_key_hash, 1)
+    );
+    assert_ne!(
+        SectorId::new(public_key_hash, 0),
+        SectorId::new(PublicKey::from([2; 32]).hash(), 0)
+    );
+}
+
+#[test]
+fn solution_sector_id() {
+    struct FarmerPublicKey([u8; 32]);
+
+    impl From<&FarmerPublicKey> for PublicKey {
+        fn from(public_key: &FarmerPublicKey) -> Self {
+            PublicKey::from(public_key.0)
+        }
+    }
+
+    let solution = Solution::genesis_solution(FarmerPublicKey([1; 32]), ());
+    assert_eq!(solution.sector_id(), sector_id(solution.sector_index));
+}
+
+#[test]
+fn sector_slot_challenge() {
+    let sector_id = sector_id(0);
+
+    // Zero global challenge doesn't change sector ID
+    let sector_slot_challenge = sector_id.derive_sector_slot_challenge(&GlobalChallenge::default());
+    assert_eq!(sector_slot_challenge.as_slice(), sector_id.as_ref());
+
+    for byte in 0..=u8::MAX {
+        let global_challenge = GlobalChallenge::from([byte; 32]);
+        let sector_slot_challenge = sector_id.derive_sector_slot_challenge(&global_challenge);
+
+        for ((&challenge, &sector_id), &global_challenge) in sector_slot_challenge
+            .iter()
+            .zip(sector_id.as_ref())
+            .zip(global_challenge.iter())
+        {
+            assert_eq!(challenge, sector_id ^ global_challenge);
+        }
+
+        // Audit index is defined by the first two bytes of the challenge and is always valid
+        let s_bucket_audit_index = sector_slot_challenge.s_bucket_audit_index();
+        assert!(s_bucket_audit_index <= SBucket::MAX);
+        assert_eq!(
+            usize::from(s_bucket_audit_index),
+            usize::from(u16::from_le_bytes([
+                sector_slot_challenge[0],
+                sector_slot_challenge[1]
+            ])) % Record::NUM_S_BUCKETS
+        );
+    }
+}
+
+#[test]
+fn audit_chunk() {
+    let sector_slot_challenge =
+        sector_id(0).derive_sector_slot_challenge(&GlobalChallenge::from([1; 32]));
+    let other_sector_slot_challenge =
+        sector_id(1).derive_sector_slot_challenge(&GlobalChallenge::from([1; 32]));
+
+    let chunk = [2; 32];
+    assert_eq!(
+        sector_slot_challenge.audit_chunk(&chunk),
+        sector_slot_challenge.audit_chunk(&chunk)
+    );
+    assert_ne!(
+        sector_slot_challenge.audit_chunk(&chunk),
+        sector_slot_challenge.audit_chunk(&[3; 32])
+    );
+    assert_ne!(
+        sector_slot_challenge.audit_chunk(&chunk),
+        other_sector_slot_challenge.audit_chunk(&chunk)
+    );
+    // Fits into solution range type
+    let _: SolutionRange = sector_slot_challenge.audit_chunk(&chunk);
+}
+
+#[test]
+fn s_bucket_offsets() {
+    let mut s_bucket_sizes = Box::new([0_u16; Record::NUM_S_BUCKETS]);
+    for (index, s_bucket_size) in s_bucket_sizes.iter_mut().enumerate() {
+        *s_bucket_size = (index % 7) as u16;
+    }
+
+    let s_bucket_offsets = SBucket::offsets_in_sector(&s_bucket_sizes);
+
+    let mut expected_offset = 0_u32;
+    for (s_bucket, (&s_bucket_offset, &s_bucket_size)) in
+        (SBucket::ZERO..=SBucket::MAX).zip(s_bucket_offsets.iter().zip(s_bucket_sizes.iter()))
+    {
+        assert_eq!(s_bucket_offset, expected_offset);
+        assert_eq!(s_bucket.offset_in_sector(&s_bucket_sizes), expected_offset);
+        expected_offset += u32::from(s_bucket_size);
+    }
+
+    // Empty sector
+    let s_bucket_sizes = Box::new([0_u16; Record::NUM_S_BUCKETS]);
+    assert!(SBucket::offsets_in_sector(&s_bucket_sizes)
+        .iter()
+        .all(|&offset| offset == 0));
+    assert_eq!(SBucket::MAX.offset_in_sector(&s_bucket_sizes), 0);
+}
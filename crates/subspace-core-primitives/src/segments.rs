@@ -1,5 +1,5 @@
 use crate::crypto::kzg::Commitment;
-use crate::pieces::{FlatPieces, Piece, PieceIndex, RawRecord};
+use crate::pieces::{FlatPieces, Piece, PieceIndex, PiecePosition, RawRecord};
 use alloc::boxed::Box;
 use alloc::string::String;
 use core::array::TryFromSliceError;
@@ -42,7 +42,13 @@ use serde::{Deserialize, Serialize};
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(feature = "rkyv", archive(check_bytes, compare(PartialEq)))]
 #[repr(transparent)]
 pub struct SegmentIndex(u64);
 
@@ -85,6 +91,11 @@ impl SegmentIndex {
         PieceIndex::from((self.0 + 1) * ArchivedHistorySegment::NUM_PIECES as u64 - 1)
     }
 
+    /// Get index of the piece at specified position in this segment.
+    pub fn piece_index(&self, position: PiecePosition) -> PieceIndex {
+        self.first_piece_index() + PieceIndex::from(u64::from(u32::from(position)))
+    }
+
     /// List of piece indexes that belong to this segment.
     pub fn segment_piece_indexes(&self) -> [PieceIndex; ArchivedHistorySegment::NUM_PIECES] {
         let mut piece_indices = [PieceIndex::ZERO; ArchivedHistorySegment::NUM_PIECES];
@@ -140,10 +151,17 @@ impl SegmentIndex {
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(transparent)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(feature = "rkyv", archive(check_bytes, compare(PartialEq)))]
 pub struct SegmentCommitment(
-    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))] [u8; SegmentCommitment::SIZE],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::hex_bytes"))]
+    [u8; SegmentCommitment::SIZE],
 );
 
 impl Default for SegmentCommitment {
@@ -226,6 +244,7 @@ impl TryFrom<SegmentCommitment> for Commitment {
     TypeInfo,
     MaxEncodedLen,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(transparent)]
 pub struct HistorySize(NonZeroU64);
@@ -265,7 +284,8 @@ impl HistorySize {
 
 /// Recorded history segment before archiving is applied.
 ///
-/// NOTE: This is a stack-allocated data structure and can cause stack overflow!
+/// NOTE: This is a stack-allocated data structure and can cause stack overflow! Use
+/// [`RecordedHistorySegment::new_boxed()`] to allocate it on the heap instead.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Deref, DerefMut)]
 #[repr(transparent)]
 pub struct RecordedHistorySegment([RawRecord; Self::NUM_RAW_RECORDS]);
@@ -298,7 +318,12 @@ impl AsMut<[u8]> for RecordedHistorySegment {
 
 impl RecordedHistorySegment {
     /// Number of raw records in one segment of recorded history.
+    #[cfg(not(subspace_testing_geometry))]
     pub const NUM_RAW_RECORDS: usize = 128;
+    // Reduced geometry, see `RawRecord::NUM_CHUNKS`
+    /// Number of raw records in one segment of recorded history.
+    #[cfg(subspace_testing_geometry)]
+    pub const NUM_RAW_RECORDS: usize = 16;
     /// Erasure coding rate for records during archiving process.
     pub const ERASURE_CODING_RATE: (usize, usize) = (1, 2);
     /// Size of recorded history segment in bytes.
@@ -315,6 +340,27 @@ impl RecordedHistorySegment {
         // SAFETY: Data structure filled with zeroes is a valid invariant
         unsafe { Box::<Self>::new_zeroed().assume_init() }
     }
+
+    /// Create boxed value from bytes of recorded history without hitting stack overflow, missing
+    /// bytes at the end are filled with zeroes.
+    ///
+    /// Returns `None` if there are more than [`Self::SIZE`] bytes.
+    #[inline]
+    pub fn from_bytes_boxed(bytes: &[u8]) -> Option<Box<Self>> {
+        if bytes.len() > Self::SIZE {
+            return None;
+        }
+
+        let mut segment = Self::new_boxed();
+        segment.as_mut()[..bytes.len()].copy_from_slice(bytes);
+        Some(segment)
+    }
+
+    /// Fill segment with zeroes in place, useful for reusing existing allocation
+    #[inline]
+    pub fn fill_zeroes(&mut self) {
+        self.as_mut().fill(0);
+    }
 }
 
 /// Archived history segment after archiving is applied.
@@ -1,7 +1,118 @@
 use crate::PosProof;
+use alloc::vec::Vec;
 use hex::{decode_to_slice, FromHex, FromHexError};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+/// Serialization of byte arrays as `0x`-prefixed hex strings in human-readable formats and as raw
+/// bytes otherwise, use with `#[serde(with = "crate::serde::hex_bytes")]`.
+///
+/// Deserialization also accepts hex strings without `0x` prefix for compatibility.
+pub(crate) mod hex_bytes {
+    use alloc::format;
+    use alloc::vec::Vec;
+    use core::fmt;
+    use serde::{de, Deserializer, Serializer};
+
+    /// Maximum number of bytes to preallocate based on size hint of the sequence
+    const MAX_PREALLOCATED_BYTES: usize = 4096;
+
+    /// Types that can be created from deserialized bytes
+    pub(crate) trait FromBytes: Sized {
+        /// Create an instance from bytes, returns bytes back if length is incorrect
+        fn from_bytes(bytes: Vec<u8>) -> Result<Self, Vec<u8>>;
+    }
+
+    impl<const N: usize> FromBytes for [u8; N] {
+        #[inline]
+        fn from_bytes(bytes: Vec<u8>) -> Result<Self, Vec<u8>> {
+            Self::try_from(bytes)
+        }
+    }
+
+    pub(crate) fn serialize<S, T>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: AsRef<[u8]> + ?Sized,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+        } else {
+            serializer.serialize_bytes(bytes.as_ref())
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromBytes,
+    {
+        let bytes = if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BytesVisitor)?
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)?
+        };
+
+        T::from_bytes(bytes).map_err(|bytes| {
+            de::Error::invalid_length(bytes.len(), &"bytes of the expected length")
+        })
+    }
+
+    struct BytesVisitor;
+
+    impl<'de> de::Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("0x-prefixed hex string or bytes")
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            let value = value.strip_prefix("0x").unwrap_or(value);
+            hex::decode(value).map_err(E::custom)
+        }
+
+        fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(value.to_vec())
+        }
+
+        fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(value)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::SeqAccess<'de>,
+        {
+            let mut bytes = Vec::with_capacity(
+                seq.size_hint()
+                    .unwrap_or_default()
+                    .min(MAX_PREALLOCATED_BYTES),
+            );
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+
+            Ok(bytes)
+        }
+    }
+}
+
+impl hex_bytes::FromBytes for PosProof {
+    #[inline]
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Vec<u8>> {
+        <[u8; PosProof::SIZE]>::try_from(bytes).map(Self::from)
+    }
+}
+
 impl FromHex for PosProof {
     type Error = FromHexError;
 
@@ -36,7 +147,7 @@ impl Serialize for PosProof {
                 where
                     S: Serializer,
                 {
-                    hex::serde::serialize(self.values, serializer)
+                    hex_bytes::serialize(self.values, serializer)
                 }
             }
             &SerializeWith {
@@ -65,7 +176,7 @@ impl<'de> Deserialize<'de> for PosProof {
             where
                 D: Deserializer<'de>,
             {
-                hex::serde::deserialize(deserializer)
+                hex_bytes::deserialize(deserializer)
             }
 
             #[inline]
@@ -82,7 +193,7 @@ impl<'de> Deserialize<'de> for PosProof {
                         D: Deserializer<'de>,
                     {
                         Ok(DeserializeWith {
-                            value: hex::serde::deserialize(deserializer)?,
+                            value: hex_bytes::deserialize(deserializer)?,
                         })
                     }
                 }
@@ -0,0 +1,173 @@
+//! Solution assembly and cheap structural checks.
+
+#[cfg(test)]
+mod tests;
+
+use crate::crypto::Scalar;
+use crate::{
+    ChunkWitness, HistorySize, PieceOffset, PosProof, RecordCommitment, RecordWitness, SectorId,
+    SectorIndex, Solution,
+};
+use derive_more::Display;
+
+/// Error happening during cheap structural check of a solution, see [`Solution::verify_shape()`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Display)]
+pub enum SolutionShapeError {
+    /// Record commitment is all zeroes
+    #[display(fmt = "Record commitment is all zeroes")]
+    ZeroRecordCommitment,
+    /// Record witness is all zeroes
+    #[display(fmt = "Record witness is all zeroes")]
+    ZeroRecordWitness,
+    /// Chunk witness is all zeroes
+    #[display(fmt = "Chunk witness is all zeroes")]
+    ZeroChunkWitness,
+    /// Proof of space is all zeroes
+    #[display(fmt = "Proof of space is all zeroes")]
+    ZeroProofOfSpace,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SolutionShapeError {}
+
+impl<PublicKey, RewardAddress> Solution<PublicKey, RewardAddress> {
+    /// Derive ID of the sector solution was found in
+    #[inline]
+    pub fn sector_id<'a>(&'a self) -> SectorId
+    where
+        crate::PublicKey: From<&'a PublicKey>,
+    {
+        SectorId::new(
+            crate::PublicKey::from(&self.public_key).hash(),
+            self.sector_index,
+        )
+    }
+
+    /// Cheap structural check of the solution that rejects obviously invalid solutions before
+    /// expensive KZG and proof of space verification.
+    ///
+    /// Success doesn't mean solution is valid, it still needs to be fully verified.
+    pub fn verify_shape(&self) -> Result<(), SolutionShapeError> {
+        if self.record_commitment.iter().all(|&byte| byte == 0) {
+            return Err(SolutionShapeError::ZeroRecordCommitment);
+        }
+        if self.record_witness.iter().all(|&byte| byte == 0) {
+            return Err(SolutionShapeError::ZeroRecordWitness);
+        }
+        if self.chunk_witness.iter().all(|&byte| byte == 0) {
+            return Err(SolutionShapeError::ZeroChunkWitness);
+        }
+        if self.proof_of_space.iter().all(|&byte| byte == 0) {
+            return Err(SolutionShapeError::ZeroProofOfSpace);
+        }
+
+        Ok(())
+    }
+}
+
+/// Error happening when building solution with [`SolutionBuilder`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Display)]
+pub enum SolutionBuilderError {
+    /// Sector index and history size were not provided
+    #[display(fmt = "Sector is missing")]
+    MissingSector,
+    /// Piece offset, record commitment and witness were not provided
+    #[display(fmt = "Record is missing")]
+    MissingRecord,
+    /// Chunk and its witness were not provided
+    #[display(fmt = "Chunk is missing")]
+    MissingChunk,
+    /// Proof of space was not provided
+    #[display(fmt = "Proof of space is missing")]
+    MissingProofOfSpace,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SolutionBuilderError {}
+
+/// Builder that assembles [`Solution`] from the sector it was found in, record with winning chunk
+/// and corresponding proofs
+#[derive(Debug, Clone)]
+pub struct SolutionBuilder<PublicKey, RewardAddress> {
+    public_key: PublicKey,
+    reward_address: RewardAddress,
+    sector: Option<(SectorIndex, HistorySize)>,
+    record: Option<(PieceOffset, RecordCommitment, RecordWitness)>,
+    chunk: Option<(Scalar, ChunkWitness)>,
+    proof_of_space: Option<PosProof>,
+}
+
+impl<PublicKey, RewardAddress> SolutionBuilder<PublicKey, RewardAddress> {
+    /// Create builder for solution of farmer with `public_key` that sends rewards to
+    /// `reward_address`
+    pub fn new(public_key: PublicKey, reward_address: RewardAddress) -> Self {
+        Self {
+            public_key,
+            reward_address,
+            sector: None,
+            record: None,
+            chunk: None,
+            proof_of_space: None,
+        }
+    }
+
+    /// Sector where solution was found, `history_size` is the size of blockchain history at the
+    /// time of sector creation (from sector metadata)
+    pub fn sector(mut self, sector_index: SectorIndex, history_size: HistorySize) -> Self {
+        self.sector.replace((sector_index, history_size));
+        self
+    }
+
+    /// Record with winning chunk at `piece_offset` within sector with its commitment and witness
+    /// (from record metadata)
+    pub fn record(
+        mut self,
+        piece_offset: PieceOffset,
+        record_commitment: RecordCommitment,
+        record_witness: RecordWitness,
+    ) -> Self {
+        self.record
+            .replace((piece_offset, record_commitment, record_witness));
+        self
+    }
+
+    /// Winning chunk and its witness for the record commitment
+    pub fn chunk(mut self, chunk: Scalar, chunk_witness: ChunkWitness) -> Self {
+        self.chunk.replace((chunk, chunk_witness));
+        self
+    }
+
+    /// Proof of space for the piece offset
+    pub fn proof_of_space(mut self, proof_of_space: PosProof) -> Self {
+        self.proof_of_space.replace(proof_of_space);
+        self
+    }
+
+    /// Assemble solution, all parts must be provided.
+    ///
+    /// Solution is not checked in any way, use [`Solution::verify_shape()`] for cheap structural
+    /// check.
+    pub fn build(self) -> Result<Solution<PublicKey, RewardAddress>, SolutionBuilderError> {
+        let (sector_index, history_size) =
+            self.sector.ok_or(SolutionBuilderError::MissingSector)?;
+        let (piece_offset, record_commitment, record_witness) =
+            self.record.ok_or(SolutionBuilderError::MissingRecord)?;
+        let (chunk, chunk_witness) = self.chunk.ok_or(SolutionBuilderError::MissingChunk)?;
+        let proof_of_space = self
+            .proof_of_space
+            .ok_or(SolutionBuilderError::MissingProofOfSpace)?;
+
+        Ok(Solution {
+            public_key: self.public_key,
+            reward_address: self.reward_address,
+            sector_index,
+            history_size,
+            piece_offset,
+            record_commitment,
+            record_witness,
+            chunk,
+            chunk_witness,
+            proof_of_space,
+        })
+    }
+}
@@ -0,0 +1,83 @@
+use crate::crypto::Scalar;
+use crate::{
+    ChunkWitness, HistorySize, PieceOffset, PosProof, RecordCommitment, RecordWitness,
+    SegmentIndex, Solution, SolutionBuilder, SolutionBuilderError, SolutionShapeError,
+};
+
+fn solution_builder() -> SolutionBuilder<(), ()> {
+    SolutionBuilder::new((), ())
+        .sector(1, HistorySize::from(SegmentIndex::ONE))
+        .record(
+            PieceOffset::ONE,
+            RecordCommitment::from([1; RecordCommitment::SIZE]),
+            RecordWitness::from([2; RecordWitness::SIZE]),
+        )
+        .chunk(
+            Scalar::default(),
+            ChunkWitness::from([3; ChunkWitness::SIZE]),
+        )
+        .proof_of_space(PosProof::from([4; PosProof::SIZE]))
+}
+
+#[test]
+fn solution_builder_assembles_solution() {
+    let solution = solution_builder().build().unwrap();
+
+    assert_eq!(solution.sector_index, 1);
+    assert_eq!(solution.history_size, HistorySize::from(SegmentIndex::ONE));
+    assert_eq!(solution.piece_offset, PieceOffset::ONE);
+    assert_eq!(
+        solution.record_commitment,
+        RecordCommitment::from([1; RecordCommitment::SIZE])
+    );
+    assert_eq!(
+        solution.record_witness,
+        RecordWitness::from([2; RecordWitness::SIZE])
+    );
+    assert_eq!(
+        solution.chunk_witness,
+        ChunkWitness::from([3; ChunkWitness::SIZE])
+    );
+    assert_eq!(solution.proof_of_space, PosProof::from([4; PosProof::SIZE]));
+    assert_eq!(solution.verify_shape(), Ok(()));
+
+    assert_eq!(
+        SolutionBuilder::<(), ()>::new((), ()).build(),
+        Err(SolutionBuilderError::MissingSector)
+    );
+    assert_eq!(
+        SolutionBuilder::<(), ()>::new((), ())
+            .sector(1, HistorySize::from(SegmentIndex::ONE))
+            .build(),
+        Err(SolutionBuilderError::MissingRecord)
+    );
+}
+
+#[test]
+fn solution_shape() {
+    assert_eq!(
+        Solution::genesis_solution((), ()).verify_shape(),
+        Err(SolutionShapeError::ZeroRecordCommitment)
+    );
+
+    let mut solution = solution_builder().build().unwrap();
+    solution.record_witness = RecordWitness::default();
+    assert_eq!(
+        solution.verify_shape(),
+        Err(SolutionShapeError::ZeroRecordWitness)
+    );
+
+    let mut solution = solution_builder().build().unwrap();
+    solution.chunk_witness = ChunkWitness::default();
+    assert_eq!(
+        solution.verify_shape(),
+        Err(SolutionShapeError::ZeroChunkWitness)
+    );
+
+    let mut solution = solution_builder().build().unwrap();
+    solution.proof_of_space = PosProof::default();
+    assert_eq!(
+        solution.verify_shape(),
+        Err(SolutionShapeError::ZeroProofOfSpace)
+    );
+}
@@ -1,5 +1,22 @@
-use crate::crypto::Scalar;
-use crate::U256;
+use crate::crypto::kzg::NUM_G1_POWERS;
+use crate::crypto::{
+    blake3_hash, blake3_hash_encoded, blake3_hash_list, blake3_hash_list_with_key,
+    blake3_hash_with_key, Blake3Hasher, Scalar,
+};
+use crate::objects::{
+    BlockObject, BlockObjectMapping, CompactGlobalObjectMapping, GlobalObject, GlobalObjectMapping,
+    ObjectMappingLimits, ObjectMappingOverflow, ObjectMappingOverflowPolicy,
+};
+use crate::{
+    ArchivedBlockProgress, ArchivedHistorySegment, CompactPotCheckpoints,
+    CompactPotCheckpointsError, GlobalChallenge, LastArchivedBlock, Piece, PieceIndex,
+    PotCheckpoints, PotOutput, PotSeed, PublicKey, Record, RecordCommitment, RecordWitness,
+    RecordedHistorySegment, SBucket, SectorId, SegmentCommitment, SegmentHeader,
+    SegmentHeaderFields, SegmentHeaderVersion, SegmentIndex, SlotPotCheckpoints, SolutionDistance,
+    SolutionRange, U256,
+};
+use core::num::NonZeroU32;
+use parity_scale_codec::{Decode, Encode};
 use rand::thread_rng;
 use rand_core::RngCore;
 
@@ -64,3 +81,636 @@ fn bytes_scalars_conversion() {
         }
     }
 }
+
+#[test]
+fn scalar_batch_conversion() {
+    let scalars = (0..16)
+        .map(|_| Scalar::from(rand::random::<[u8; Scalar::SAFE_BYTES]>()))
+        .collect::<Vec<_>>();
+
+    let bytes = Scalar::batch_to_bytes(&scalars);
+    assert_eq!(bytes.len(), scalars.len());
+    for (bytes, scalar) in bytes.iter().zip(&scalars) {
+        assert_eq!(*bytes, scalar.to_bytes());
+    }
+    assert_eq!(Scalar::try_batch_from_bytes(&bytes).unwrap(), scalars);
+
+    let mut output = vec![[0; Scalar::FULL_BYTES]; scalars.len()];
+    Scalar::batch_to_bytes_into(&scalars, &mut output);
+    assert_eq!(output, bytes);
+
+    // Reuse of existing allocation
+    let mut decoded = Vec::with_capacity(bytes.len() * 2);
+    Scalar::try_batch_from_bytes_into(&bytes, &mut decoded).unwrap();
+    Scalar::try_batch_from_bytes_into(&bytes, &mut decoded).unwrap();
+    assert_eq!(decoded[..scalars.len()], scalars);
+    assert_eq!(decoded[scalars.len()..], scalars);
+
+    // Invalid element is reported with its position
+    let mut invalid_bytes = bytes.clone();
+    invalid_bytes[5] = [u8::MAX; Scalar::FULL_BYTES];
+    invalid_bytes[9] = [u8::MAX; Scalar::FULL_BYTES];
+    let error = Scalar::try_batch_from_bytes(&invalid_bytes).unwrap_err();
+    assert_eq!(error.index, 5);
+
+    let mut decoded = Vec::new();
+    let error = Scalar::try_batch_from_bytes_into(&invalid_bytes, &mut decoded).unwrap_err();
+    assert_eq!(error.index, 5);
+    assert_eq!(decoded, scalars[..5]);
+
+    // Empty batch
+    assert!(Scalar::try_batch_from_bytes(&[]).unwrap().is_empty());
+    assert!(Scalar::batch_to_bytes(&[]).is_empty());
+}
+
+#[test]
+fn block_object_mapping_limits() {
+    let block_object_mapping = BlockObjectMapping {
+        objects: (0..10)
+            .map(|offset| BlockObject::V0 {
+                hash: Default::default(),
+                offset,
+            })
+            .collect(),
+    };
+    // Compact length prefix + 10 objects of 37 bytes each
+    let size = 1 + 10 * 37;
+
+    {
+        let mut block_object_mapping = block_object_mapping.clone();
+        let limits = ObjectMappingLimits {
+            max_objects: 10,
+            max_size: size,
+            overflow_policy: ObjectMappingOverflowPolicy::Reject,
+        };
+        assert_eq!(block_object_mapping.apply_limits(&limits), None);
+        assert_eq!(block_object_mapping.objects.len(), 10);
+    }
+
+    // Truncated by number of objects
+    {
+        let mut block_object_mapping = block_object_mapping.clone();
+        let limits = ObjectMappingLimits {
+            max_objects: 4,
+            max_size: size,
+            overflow_policy: ObjectMappingOverflowPolicy::Truncate,
+        };
+        assert_eq!(
+            block_object_mapping.apply_limits(&limits),
+            Some(ObjectMappingOverflow {
+                objects: 10,
+                size,
+                retained_objects: 4,
+            })
+        );
+        assert_eq!(block_object_mapping.objects.len(), 4);
+    }
+
+    // Truncated by size
+    {
+        let mut block_object_mapping = block_object_mapping.clone();
+        let limits = ObjectMappingLimits {
+            max_objects: 10,
+            max_size: 1 + 3 * 37 + 10,
+            overflow_policy: ObjectMappingOverflowPolicy::Truncate,
+        };
+        assert_eq!(
+            block_object_mapping.apply_limits(&limits),
+            Some(ObjectMappingOverflow {
+                objects: 10,
+                size,
+                retained_objects: 3,
+            })
+        );
+        assert_eq!(block_object_mapping.objects.last().unwrap().offset(), 2);
+    }
+
+    // Rejected entirely
+    {
+        let mut block_object_mapping = block_object_mapping.clone();
+        let limits = ObjectMappingLimits {
+            max_objects: 9,
+            max_size: size,
+            overflow_policy: ObjectMappingOverflowPolicy::Reject,
+        };
+        assert_eq!(
+            block_object_mapping.apply_limits(&limits),
+            Some(ObjectMappingOverflow {
+                objects: 10,
+                size,
+                retained_objects: 0,
+            })
+        );
+        assert!(block_object_mapping.objects.is_empty());
+    }
+}
+
+#[test]
+fn compact_global_object_mapping() {
+    // Typical mapping of a large block: many objects in a few consecutive pieces
+    let mut objects = (0..1_000_u64)
+        .map(|index| GlobalObject::V0 {
+            piece_index: PieceIndex::from(1_000_000 + index / 100),
+            offset: (index % 100) as u32 * 300,
+        })
+        .collect::<Vec<_>>();
+    // Objects out of order must survive round trip too
+    objects.push(GlobalObject::V0 {
+        piece_index: PieceIndex::from(5),
+        offset: u32::MAX,
+    });
+    objects.push(GlobalObject::V0 {
+        piece_index: PieceIndex::from(5),
+        offset: 0,
+    });
+    objects.push(GlobalObject::V0 {
+        piece_index: PieceIndex::from(u64::MAX),
+        offset: 1,
+    });
+    let mapping = GlobalObjectMapping { objects };
+
+    let compact_mapping = CompactGlobalObjectMapping::from(mapping.clone());
+    assert_eq!(compact_mapping.objects(), mapping.objects.as_slice());
+
+    let encoded = compact_mapping.encode();
+    assert_eq!(encoded.len(), compact_mapping.size_hint());
+    // At least 3x smaller than current format
+    assert!(encoded.len() * 3 < mapping.encoded_size());
+
+    let decoded = CompactGlobalObjectMapping::decode(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, compact_mapping);
+    assert_eq!(GlobalObjectMapping::from(decoded), mapping);
+
+    // Empty mapping
+    let encoded = CompactGlobalObjectMapping::default().encode();
+    assert_eq!(encoded, GlobalObjectMapping::default().encode());
+    assert!(CompactGlobalObjectMapping::decode(&mut encoded.as_slice())
+        .unwrap()
+        .objects()
+        .is_empty());
+
+    // Truncated input
+    let encoded = compact_mapping.encode();
+    assert!(CompactGlobalObjectMapping::decode(&mut &encoded[..encoded.len() - 1]).is_err());
+}
+
+fn random_pot_checkpoints() -> PotCheckpoints {
+    let mut checkpoints = PotCheckpoints::default();
+    for checkpoint in checkpoints.iter_mut() {
+        *checkpoint = PotOutput::from(rand::random::<[u8; PotOutput::SIZE]>());
+    }
+    checkpoints
+}
+
+#[test]
+fn pot_checkpoints_helpers() {
+    let slot_iterations = NonZeroU32::new(1_600).unwrap();
+    let checkpoints = random_pot_checkpoints();
+    let seed = PotSeed::from(rand::random::<[u8; PotSeed::SIZE]>());
+
+    assert_eq!(PotCheckpoints::checkpoint_iterations(slot_iterations), 200);
+
+    let with_iterations = checkpoints
+        .iter_with_iterations(slot_iterations)
+        .collect::<Vec<_>>();
+    assert_eq!(with_iterations.len(), checkpoints.len());
+    assert_eq!(with_iterations[0], (200, checkpoints[0]));
+    assert_eq!(
+        with_iterations.last().copied(),
+        Some((slot_iterations.get(), checkpoints.output()))
+    );
+
+    let with_inputs = checkpoints.iter_with_inputs(seed).collect::<Vec<_>>();
+    assert_eq!(with_inputs.len(), checkpoints.len());
+    assert_eq!(with_inputs[0], (PotOutput::from(*seed), checkpoints[0]));
+    for (window, &(input, output)) in checkpoints.windows(2).zip(&with_inputs[1..]) {
+        assert_eq!((input, output), (window[0], window[1]));
+    }
+
+    let output = checkpoints.output();
+    let entropy = rand::random();
+    assert_eq!(output.next_seed(None), output.seed());
+    assert_eq!(
+        output.next_seed(Some(&entropy)),
+        output.seed_with_entropy(&entropy)
+    );
+
+    assert_eq!(
+        PotCheckpoints::slots_in_iterations(16_000, slot_iterations),
+        10
+    );
+    assert_eq!(
+        PotCheckpoints::slots_in_iterations(16_999, slot_iterations),
+        10
+    );
+    assert_eq!(
+        PotCheckpoints::iterations_in_slots(10, slot_iterations),
+        Some(16_000)
+    );
+    assert_eq!(
+        PotCheckpoints::iterations_in_slots(u64::MAX, slot_iterations),
+        None
+    );
+}
+
+#[test]
+fn compact_pot_checkpoints() {
+    let mut slots = Vec::<SlotPotCheckpoints>::new();
+    let mut seed = PotSeed::from(rand::random::<[u8; PotSeed::SIZE]>());
+    for slot in 100..110 {
+        let checkpoints = random_pot_checkpoints();
+        slots.push(SlotPotCheckpoints {
+            slot,
+            seed,
+            checkpoints,
+        });
+        // Inject entropy once in a while
+        seed = if slot % 4 == 0 {
+            checkpoints.output().seed_with_entropy(&rand::random())
+        } else {
+            checkpoints.output().seed()
+        };
+    }
+
+    let compact_checkpoints = CompactPotCheckpoints::from_slots(slots.iter().copied()).unwrap();
+    assert_eq!(compact_checkpoints.len(), slots.len());
+    assert_eq!(compact_checkpoints.first_slot(), Some(100));
+    assert_eq!(compact_checkpoints.iter().collect::<Vec<_>>(), slots);
+
+    let encoded = compact_checkpoints.encode();
+    assert!(encoded.len() < slots.encode().len());
+    let decoded = CompactPotCheckpoints::decode(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, compact_checkpoints);
+    assert_eq!(decoded.iter().collect::<Vec<_>>(), slots);
+
+    // Gaps are not allowed
+    let mut compact_checkpoints = compact_checkpoints;
+    assert_eq!(
+        compact_checkpoints.push(SlotPotCheckpoints {
+            slot: 111,
+            seed,
+            checkpoints: random_pot_checkpoints(),
+        }),
+        Err(CompactPotCheckpointsError::NonConsecutiveSlot {
+            expected: 110,
+            actual: 111,
+        })
+    );
+
+    // Empty sequence
+    let compact_checkpoints = CompactPotCheckpoints::from_slots(core::iter::empty()).unwrap();
+    assert!(compact_checkpoints.is_empty());
+    assert_eq!(compact_checkpoints.first_slot(), None);
+}
+
+#[test]
+fn compact_pot_checkpoints_invalid_encoding() {
+    let checkpoints = random_pot_checkpoints();
+    let compact_checkpoints = CompactPotCheckpoints::from_slots([
+        SlotPotCheckpoints {
+            slot: 1,
+            seed: PotSeed::default(),
+            checkpoints,
+        },
+        SlotPotCheckpoints {
+            slot: 2,
+            seed: checkpoints.output().seed(),
+            checkpoints: random_pot_checkpoints(),
+        },
+    ])
+    .unwrap();
+    let mut encoded = compact_checkpoints.encode();
+
+    // Remove the first entry (variant index, seed and checkpoints) and fix length prefix, such that
+    // the first remaining entry has derived seed
+    let first_entry_size =
+        1 + PotSeed::SIZE + PotOutput::SIZE * PotCheckpoints::NUM_CHECKPOINTS.get() as usize;
+    encoded.drain(9..9 + first_entry_size);
+    encoded[8] = parity_scale_codec::Compact(1_u32).encode()[0];
+    assert!(CompactPotCheckpoints::decode(&mut encoded.as_slice()).is_err());
+}
+
+#[test]
+fn piece_geometry() {
+    // Both production and reduced (`--cfg subspace_testing_geometry`) geometry must be usable with KZG and erasure coding
+    assert!(Record::NUM_CHUNKS.is_power_of_two());
+    assert!(Record::NUM_S_BUCKETS <= NUM_G1_POWERS);
+    assert!(RecordedHistorySegment::NUM_RAW_RECORDS.is_power_of_two());
+    assert!(ArchivedHistorySegment::NUM_PIECES <= NUM_G1_POWERS);
+    assert_eq!(usize::from(SBucket::MAX) + 1, Record::NUM_S_BUCKETS);
+    assert_eq!(
+        Piece::SIZE,
+        Record::SIZE + RecordCommitment::SIZE + RecordWitness::SIZE
+    );
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn arbitrary_encoding_round_trip() {
+    use crate::{FlatPieces, Solution};
+    use arbitrary::{Arbitrary, Unstructured};
+
+    let mut data = vec![0u8; Piece::SIZE * 2];
+    thread_rng().fill_bytes(&mut data);
+    let mut u = Unstructured::new(&data);
+
+    let segment_header = SegmentHeader::arbitrary(&mut u).unwrap();
+    assert_eq!(
+        SegmentHeader::decode(&mut segment_header.encode().as_slice()).unwrap(),
+        segment_header
+    );
+
+    let solution = Solution::<PublicKey, PublicKey>::arbitrary(&mut u).unwrap();
+    assert_eq!(
+        Solution::<PublicKey, PublicKey>::decode(&mut solution.encode().as_slice()).unwrap(),
+        solution
+    );
+
+    let piece = Piece::arbitrary(&mut u).unwrap();
+    assert_eq!(
+        Piece::decode(&mut piece.encode().as_slice()).unwrap(),
+        piece
+    );
+
+    // Pieces are filled with zeroes once data is exhausted
+    let flat_pieces = FlatPieces::arbitrary(&mut u).unwrap();
+    assert_eq!(
+        FlatPieces::decode(&mut flat_pieces.encode().as_slice()).unwrap(),
+        flat_pieces
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn human_readable_serde() {
+    let sector_id = SectorId::new(PublicKey::from([1; 32]).hash(), 0);
+    let json = serde_json::to_string(&sector_id).unwrap();
+    assert_eq!(json, format!("\"0x{}\"", hex::encode(sector_id)));
+    assert_eq!(serde_json::from_str::<SectorId>(&json).unwrap(), sector_id);
+    // Hex without prefix is still accepted
+    assert_eq!(
+        serde_json::from_str::<SectorId>(&format!("\"{}\"", hex::encode(sector_id))).unwrap(),
+        sector_id
+    );
+    // Wrong length is rejected
+    assert!(serde_json::from_str::<SectorId>("\"0x0102\"").is_err());
+
+    let global_challenge = GlobalChallenge::from([2; 32]);
+    let json = serde_json::to_string(&global_challenge).unwrap();
+    assert_eq!(json, format!("\"0x{}\"", hex::encode([2; 32])));
+    assert_eq!(
+        serde_json::from_str::<GlobalChallenge>(&json).unwrap(),
+        global_challenge
+    );
+
+    let mut piece = Piece::default();
+    piece[..4].copy_from_slice(&[1, 2, 3, 4]);
+    let json = serde_json::to_string(&piece).unwrap();
+    assert!(json.starts_with("\"0x01020304"));
+    assert_eq!(serde_json::from_str::<Piece>(&json).unwrap(), piece);
+
+    let record_commitment = RecordCommitment::default();
+    let json = serde_json::to_string(&record_commitment).unwrap();
+    assert!(json.starts_with("\"0x"));
+    assert_eq!(
+        serde_json::from_str::<RecordCommitment>(&json).unwrap(),
+        record_commitment
+    );
+}
+
+#[test]
+fn solution_distance_properties() {
+    // Reference implementation that doesn't rely on wrapping arithmetic
+    fn reference_distance(a: SolutionRange, b: SolutionRange) -> SolutionRange {
+        let forward = (i128::from(a) - i128::from(b)).rem_euclid(1 << 64);
+        forward.min((1 << 64) - forward) as SolutionRange
+    }
+
+    let mut rng = thread_rng();
+    let edge_values = [
+        0,
+        1,
+        SolutionRange::MAX / 2,
+        SolutionRange::MAX - 1,
+        SolutionRange::MAX,
+    ];
+
+    for iteration in 0..10_000 {
+        let (a, b) = if iteration < edge_values.len() * edge_values.len() {
+            (
+                edge_values[iteration / edge_values.len()],
+                edge_values[iteration % edge_values.len()],
+            )
+        } else {
+            (rng.next_u64(), rng.next_u64())
+        };
+        let distance = SolutionDistance::between(a, b);
+
+        assert_eq!(SolutionRange::from(distance), reference_distance(a, b));
+        assert_eq!(distance, SolutionDistance::between(b, a));
+        assert!(distance <= SolutionDistance::MAX);
+        assert_eq!(distance == SolutionDistance::default(), a == b);
+
+        // Distance is within solution range exactly when it is not larger than half of it
+        let solution_range = rng.next_u64();
+        assert!(
+            SolutionDistance::between(a, a.wrapping_add(solution_range / 2))
+                .is_within(solution_range)
+        );
+        assert!(
+            SolutionDistance::between(a, a.wrapping_sub(solution_range / 2))
+                .is_within(solution_range)
+        );
+        assert!(
+            !SolutionDistance::between(a, a.wrapping_add(solution_range / 2 + 1))
+                .is_within(solution_range)
+        );
+        assert!(
+            !SolutionDistance::between(a, a.wrapping_sub(solution_range / 2 + 1))
+                .is_within(solution_range)
+        );
+    }
+
+    // Distance for audited chunk is derived from the first bytes of global challenge and audit
+    // chunk
+    for _ in 0..100 {
+        let mut global_challenge = [0; 32];
+        rng.fill_bytes(&mut global_challenge);
+        let global_challenge = GlobalChallenge::from(global_challenge);
+        let mut chunk = [0; 32];
+        rng.fill_bytes(&mut chunk);
+        let sector_slot_challenge = SectorId::new(PublicKey::from([1; 32]).hash(), 0)
+            .derive_sector_slot_challenge(&global_challenge);
+
+        let global_challenge_as_solution_range =
+            SolutionRange::from_le_bytes(global_challenge[..8].try_into().unwrap());
+        assert_eq!(
+            SolutionDistance::calculate(&global_challenge, &chunk, &sector_slot_challenge),
+            SolutionDistance::between(
+                global_challenge_as_solution_range,
+                sector_slot_challenge.audit_chunk(&chunk)
+            )
+        );
+    }
+}
+
+#[test]
+fn segment_header_versioning() {
+    let historical_segment_header = SegmentHeader::V0 {
+        segment_index: SegmentIndex::from(5),
+        segment_commitment: SegmentCommitment::from([1; SegmentCommitment::SIZE]),
+        prev_segment_header_hash: [2; 32],
+        last_archived_block: LastArchivedBlock {
+            number: 100,
+            archived_progress: ArchivedBlockProgress::Partial(10),
+        },
+    };
+    // Encoding of V0 segment header as stored in historical archives, must never change
+    let historical_encoding = {
+        let mut bytes = vec![0];
+        bytes.extend_from_slice(&5u64.to_le_bytes());
+        bytes.extend_from_slice(&[1; SegmentCommitment::SIZE]);
+        bytes.extend_from_slice(&[2; 32]);
+        bytes.extend_from_slice(&100u32.to_le_bytes());
+        bytes.push(1);
+        bytes.extend_from_slice(&10u32.to_le_bytes());
+        bytes
+    };
+    assert_eq!(historical_segment_header.encode(), historical_encoding);
+
+    let latest_segment_header = SegmentHeader::latest(
+        SegmentIndex::from(6),
+        SegmentCommitment::from([3; SegmentCommitment::SIZE]),
+        historical_segment_header.hash(),
+        LastArchivedBlock {
+            number: 150,
+            archived_progress: ArchivedBlockProgress::Complete,
+        },
+    );
+    assert_eq!(
+        latest_segment_header.version(),
+        SegmentHeaderVersion::LATEST
+    );
+    assert!(latest_segment_header.is_latest());
+
+    // Store with segment headers created at different times, possibly by different versions of
+    // the software
+    let store = vec![historical_encoding, latest_segment_header.encode()];
+    let decoded = store
+        .iter()
+        .map(|bytes| SegmentHeader::decode(&mut bytes.as_slice()).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        decoded,
+        vec![historical_segment_header, latest_segment_header]
+    );
+
+    // Chain of segment headers is verified using hashes of headers as they were stored
+    assert_eq!(
+        SegmentHeaderFields::prev_segment_header_hash(&decoded[1]),
+        decoded[0].hash()
+    );
+
+    for (segment_header, bytes) in decoded.into_iter().zip(&store) {
+        let upgraded = segment_header.upgrade();
+        assert!(upgraded.is_latest());
+        // Fields common to all versions are preserved by upgrade
+        assert_eq!(
+            SegmentHeaderFields::segment_index(&upgraded),
+            segment_header.segment_index()
+        );
+        assert_eq!(
+            SegmentHeaderFields::segment_commitment(&upgraded),
+            segment_header.segment_commitment()
+        );
+        assert_eq!(
+            SegmentHeaderFields::prev_segment_header_hash(&upgraded),
+            segment_header.prev_segment_header_hash()
+        );
+        assert_eq!(
+            SegmentHeaderFields::last_archived_block(&upgraded),
+            segment_header.last_archived_block()
+        );
+        // Upgrade of the latest version is a no-op
+        assert_eq!(upgraded.upgrade(), upgraded);
+        // Original encoding is not affected by decoding
+        assert_eq!(&segment_header.encode(), bytes);
+    }
+
+    // Unknown (future) version is rejected instead of being misinterpreted
+    let mut unknown_version = latest_segment_header.encode();
+    unknown_version[0] = u8::MAX;
+    assert!(SegmentHeader::decode(&mut unknown_version.as_slice()).is_err());
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn segment_header_rkyv() {
+    use rkyv::Deserialize;
+
+    let segment_header = SegmentHeader::V0 {
+        segment_index: SegmentIndex::from(5),
+        segment_commitment: SegmentCommitment::from([1; SegmentCommitment::SIZE]),
+        prev_segment_header_hash: [2; 32],
+        last_archived_block: LastArchivedBlock {
+            number: 100,
+            archived_progress: ArchivedBlockProgress::Partial(10),
+        },
+    };
+
+    let bytes = rkyv::to_bytes::<_, 0>(&segment_header).unwrap();
+    let archived_segment_header = rkyv::check_archived_root::<SegmentHeader>(&bytes).unwrap();
+    assert!(*archived_segment_header == segment_header);
+
+    let deserialized: SegmentHeader = archived_segment_header
+        .deserialize(&mut rkyv::Infallible)
+        .unwrap();
+    assert_eq!(deserialized, segment_header);
+
+    // Corrupted archive (invalid version discriminant) is rejected instead of being interpreted
+    let mut bytes = bytes;
+    bytes[0] = u8::MAX;
+    assert!(rkyv::check_archived_root::<SegmentHeader>(&bytes).is_err());
+}
+
+#[test]
+fn blake3_hasher_streaming() {
+    let mut data = vec![0u8; 1024 * 1024 + 123];
+    thread_rng().fill_bytes(&mut data);
+    let mut key = [0u8; 32];
+    thread_rng().fill_bytes(&mut key);
+
+    let mut hasher = Blake3Hasher::new();
+    for chunk in data.chunks(1000) {
+        hasher.update(chunk);
+    }
+    assert_eq!(hasher.finalize(), blake3_hash(&data));
+    hasher.reset();
+    assert_eq!(hasher.finalize(), blake3_hash(&[]));
+
+    let mut keyed_hasher = Blake3Hasher::new_keyed(&key);
+    for chunk in data.chunks(1000) {
+        keyed_hasher.update(chunk);
+    }
+    assert_eq!(keyed_hasher.finalize(), blake3_hash_with_key(&key, &data));
+
+    let (first, second) = data.split_at(100);
+    assert_eq!(blake3_hash_list(&[first, second]), blake3_hash(&data));
+    assert_eq!(
+        blake3_hash_list_with_key(&key, &[first, second]),
+        blake3_hash_with_key(&key, &data)
+    );
+
+    // Encoding directly into hasher must match hashing of encoded bytes
+    let value = (data.clone(), 42u64);
+    assert_eq!(blake3_hash_encoded(&value), blake3_hash(&value.encode()));
+
+    #[cfg(feature = "std")]
+    {
+        use std::io::Write;
+
+        let mut hasher = Blake3Hasher::new();
+        hasher.write_all(&data).unwrap();
+        assert_eq!(hasher.finalize(), blake3_hash(&data));
+    }
+}
@@ -0,0 +1,34 @@
+//! Erasure coding backends.
+//!
+//! All backends operate on the same evaluation domain and must produce bit-for-bit identical
+//! results, since parity and commitments derived from them are part of the protocol.
+
+pub mod blst_fft;
+pub mod reference;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use subspace_core_primitives::crypto::kzg::{Commitment, Polynomial};
+use subspace_core_primitives::crypto::Scalar;
+
+/// Erasure coding backend.
+///
+/// See [`ErasureCoding`](crate::ErasureCoding) for description of the semantics of each method.
+pub trait ErasureCodingBackend: fmt::Debug + Send + Sync {
+    /// Max number of shards supported (both source and parity together)
+    fn max_shards(&self) -> usize;
+
+    /// Extend sources using erasure coding, returns parity data
+    fn extend(&self, source: &[Scalar]) -> Result<Vec<Scalar>, String>;
+
+    /// Recovery of missing shards from given shards
+    fn recover(&self, shards: &[Option<Scalar>]) -> Result<Vec<Scalar>, String>;
+
+    /// Recovery of missing shards from given shards in form of normalized polynomial
+    fn recover_poly(&self, shards: &[Option<Scalar>]) -> Result<Polynomial, String>;
+
+    /// Extend commitments using erasure coding, returns both source and parity commitments
+    /// interleaved
+    fn extend_commitments(&self, commitments: &[Commitment]) -> Result<Vec<Commitment>, String>;
+}
@@ -0,0 +1,80 @@
+//! FFT-based erasure coding backend using BLST implementation of `rust-kzg`.
+
+use crate::backend::ErasureCodingBackend;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::num::NonZeroUsize;
+use kzg::{FFTSettings, PolyRecover, DAS, FFTG1, G1};
+use rust_kzg_blst::types::fft_settings::FsFFTSettings;
+use rust_kzg_blst::types::g1::FsG1;
+use rust_kzg_blst::types::poly::FsPoly;
+use subspace_core_primitives::crypto::kzg::{Commitment, Polynomial};
+use subspace_core_primitives::crypto::Scalar;
+
+/// FFT-based erasure coding backend using BLST implementation of `rust-kzg`.
+///
+/// This is the default backend.
+#[derive(Debug)]
+pub struct BlstFftBackend {
+    fft_settings: FsFFTSettings,
+}
+
+impl BlstFftBackend {
+    /// Create new instance.
+    ///
+    /// Number of shards supported is `2^scale`.
+    pub fn new(scale: NonZeroUsize) -> Result<Self, String> {
+        let fft_settings = FsFFTSettings::new(scale.get())?;
+
+        Ok(Self { fft_settings })
+    }
+}
+
+impl ErasureCodingBackend for BlstFftBackend {
+    fn max_shards(&self) -> usize {
+        self.fft_settings.max_width
+    }
+
+    fn extend(&self, source: &[Scalar]) -> Result<Vec<Scalar>, String> {
+        // TODO: das_fft_extension modifies buffer internally, it needs to change to use
+        //  pre-allocated buffer instead of allocating a new one
+        self.fft_settings
+            .das_fft_extension(Scalar::slice_to_repr(source))
+            .map(Scalar::vec_from_repr)
+    }
+
+    fn recover(&self, shards: &[Option<Scalar>]) -> Result<Vec<Scalar>, String> {
+        let poly = FsPoly::recover_poly_from_samples(
+            Scalar::slice_option_to_repr(shards),
+            &self.fft_settings,
+        )?;
+
+        Ok(Scalar::vec_from_repr(poly.coeffs))
+    }
+
+    fn recover_poly(&self, shards: &[Option<Scalar>]) -> Result<Polynomial, String> {
+        let mut poly = Polynomial::from(FsPoly::recover_poly_coeffs_from_samples(
+            Scalar::slice_option_to_repr(shards),
+            &self.fft_settings,
+        )?);
+
+        poly.normalize();
+
+        Ok(poly)
+    }
+
+    fn extend_commitments(&self, commitments: &[Commitment]) -> Result<Vec<Commitment>, String> {
+        // Inverse FFT to interpolate polynomial over source commitments
+        let mut coeffs = self
+            .fft_settings
+            .fft_g1(Commitment::slice_to_repr(commitments), true)?;
+
+        // Double the size
+        coeffs.resize(coeffs.len() * 2, FsG1::identity());
+
+        // FFT to get extended commitments
+        self.fft_settings
+            .fft_g1(&coeffs, false)
+            .map(Commitment::vec_from_repr)
+    }
+}
@@ -0,0 +1,249 @@
+//! Reference erasure coding backend.
+//!
+//! Implements the same math as [`BlstFftBackend`](super::blst_fft::BlstFftBackend) using textbook
+//! algorithms and sharing nothing with it except the evaluation domain (roots of unity). Recovery
+//! uses Lagrange interpolation with quadratic complexity, so this backend is much slower for large
+//! number of shards, it exists primarily to cross-validate other backends.
+
+use crate::backend::ErasureCodingBackend;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::num::NonZeroUsize;
+use kzg::{Fr, G1Mul, G1};
+use rust_kzg_blst::types::fft_settings::FsFFTSettings;
+use rust_kzg_blst::types::fr::FsFr;
+use rust_kzg_blst::types::g1::FsG1;
+use rust_kzg_blst::types::poly::FsPoly;
+use subspace_core_primitives::crypto::kzg::{Commitment, Polynomial};
+use subspace_core_primitives::crypto::Scalar;
+
+/// Element FFT can be performed over
+trait FftElement: Copy {
+    fn fft_add(&self, other: &Self) -> Self;
+
+    fn fft_sub(&self, other: &Self) -> Self;
+
+    fn fft_scale(&self, by: &FsFr) -> Self;
+}
+
+impl FftElement for FsFr {
+    fn fft_add(&self, other: &Self) -> Self {
+        Fr::add(self, other)
+    }
+
+    fn fft_sub(&self, other: &Self) -> Self {
+        Fr::sub(self, other)
+    }
+
+    fn fft_scale(&self, by: &FsFr) -> Self {
+        Fr::mul(self, by)
+    }
+}
+
+impl FftElement for FsG1 {
+    fn fft_add(&self, other: &Self) -> Self {
+        // `add_or_dbl` takes `&mut self` even though it doesn't modify it, hence a copy
+        G1::add_or_dbl(&mut { *self }, other)
+    }
+
+    fn fft_sub(&self, other: &Self) -> Self {
+        G1::sub(self, other)
+    }
+
+    fn fft_scale(&self, by: &FsFr) -> Self {
+        G1Mul::mul(self, by)
+    }
+}
+
+/// Reference erasure coding backend, see module-level documentation for details.
+#[derive(Debug)]
+pub struct ReferenceBackend {
+    /// `roots_of_unity[i]` is `ω^i`, where `ω` is primitive root of unity of order `max_shards`
+    roots_of_unity: Vec<FsFr>,
+}
+
+impl ReferenceBackend {
+    /// Create new instance.
+    ///
+    /// Number of shards supported is `2^scale`.
+    pub fn new(scale: NonZeroUsize) -> Result<Self, String> {
+        let fft_settings = FsFFTSettings::new(scale.get())?;
+        let roots_of_unity =
+            fft_settings.expanded_roots_of_unity[..fft_settings.max_width].to_vec();
+
+        Ok(Self { roots_of_unity })
+    }
+
+    /// `ω^exponent` (or `ω^-exponent` if `inverse` is `true`)
+    fn root(&self, exponent: usize, inverse: bool) -> FsFr {
+        let max_shards = self.roots_of_unity.len();
+        let exponent = exponent % max_shards;
+
+        if inverse {
+            self.roots_of_unity[(max_shards - exponent) % max_shards]
+        } else {
+            self.roots_of_unity[exponent]
+        }
+    }
+
+    /// Distance between adjacent points of the domain of size `size` in terms of `ω` exponent
+    fn stride(&self, size: usize) -> Result<usize, String> {
+        if size == 0 || !size.is_power_of_two() || size > self.roots_of_unity.len() {
+            return Err(format!(
+                "Size must be a power of two not larger than {}, {size} given",
+                self.roots_of_unity.len()
+            ));
+        }
+
+        Ok(self.roots_of_unity.len() / size)
+    }
+
+    /// Evaluate polynomial with coefficients `values` over domain of size `values.len()` or
+    /// interpolate polynomial over evaluations if `inverse` is `true`
+    fn fft<T>(&self, values: &[T], inverse: bool) -> Result<Vec<T>, String>
+    where
+        T: FftElement,
+    {
+        let stride = self.stride(values.len())?;
+        let mut result = self.fft_inner(values, stride, inverse);
+
+        if inverse {
+            let size_inverse = FsFr::from_u64(values.len() as u64).inverse();
+            for value in &mut result {
+                *value = value.fft_scale(&size_inverse);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Recursive radix-2 Cooley-Tukey FFT without normalization
+    fn fft_inner<T>(&self, values: &[T], stride: usize, inverse: bool) -> Vec<T>
+    where
+        T: FftElement,
+    {
+        if values.len() == 1 {
+            return values.to_vec();
+        }
+
+        let evens = values.iter().step_by(2).copied().collect::<Vec<_>>();
+        let odds = values
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .copied()
+            .collect::<Vec<_>>();
+        let evens = self.fft_inner(&evens, stride * 2, inverse);
+        let odds = self.fft_inner(&odds, stride * 2, inverse);
+
+        let half = values.len() / 2;
+        let mut result = vec![values[0]; values.len()];
+        for (i, (even, odd)) in evens.iter().zip(&odds).enumerate() {
+            let odd = odd.fft_scale(&self.root(i * stride, inverse));
+            result[i] = even.fft_add(&odd);
+            result[i + half] = even.fft_sub(&odd);
+        }
+
+        result
+    }
+}
+
+impl ErasureCodingBackend for ReferenceBackend {
+    fn max_shards(&self) -> usize {
+        self.roots_of_unity.len()
+    }
+
+    fn extend(&self, source: &[Scalar]) -> Result<Vec<Scalar>, String> {
+        // Source shards are evaluations on even points of the domain twice as large, parity shards
+        // are evaluations of the same polynomial on odd points, which is the same as evaluating
+        // polynomial with coefficients `c_j * ω'^j` on even points, where `ω'` is the generator
+        // of the larger domain
+        let stride = self.stride(source.len() * 2)?;
+        let mut coeffs = self.fft(Scalar::slice_to_repr(source), true)?;
+        for (j, coeff) in coeffs.iter_mut().enumerate() {
+            *coeff = coeff.mul(&self.root(j * stride, false));
+        }
+
+        self.fft(&coeffs, false).map(Scalar::vec_from_repr)
+    }
+
+    fn recover(&self, shards: &[Option<Scalar>]) -> Result<Vec<Scalar>, String> {
+        let stride = self.stride(shards.len())?;
+        let required = shards.len() / 2;
+
+        let known = shards
+            .iter()
+            .enumerate()
+            .filter_map(|(i, shard)| {
+                shard.map(|shard| (self.root(i * stride, false), FsFr::from(shard)))
+            })
+            .take(required.max(1))
+            .collect::<Vec<_>>();
+
+        if known.len() < required.max(1) {
+            return Err(format!(
+                "Impossible to recover, at least {required} shards out of {} are needed, {} \
+                given",
+                shards.len(),
+                known.len()
+            ));
+        }
+
+        // Barycentric weights `w_k = 1 / Π_{j != k} (x_k - x_j)`
+        let weights = known
+            .iter()
+            .enumerate()
+            .map(|(k, (x_k, _))| {
+                known
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != k)
+                    .fold(FsFr::one(), |product, (_, (x_j, _))| {
+                        product.mul(&x_k.sub(x_j))
+                    })
+                    .inverse()
+            })
+            .collect::<Vec<_>>();
+
+        Ok(shards
+            .iter()
+            .enumerate()
+            .map(|(i, shard)| {
+                if let Some(shard) = shard {
+                    return *shard;
+                }
+
+                // `L(x) = Π_k (x - x_k) * Σ_k w_k * y_k / (x - x_k)`
+                let x = self.root(i * stride, false);
+                let mut product = FsFr::one();
+                let mut sum = FsFr::zero();
+                for ((x_k, y_k), w_k) in known.iter().zip(&weights) {
+                    let difference = x.sub(x_k);
+                    product = product.mul(&difference);
+                    sum = sum.add(&w_k.mul(y_k).mul(&difference.inverse()));
+                }
+
+                Scalar::from(product.mul(&sum))
+            })
+            .collect())
+    }
+
+    fn recover_poly(&self, shards: &[Option<Scalar>]) -> Result<Polynomial, String> {
+        let evaluations = self.recover(shards)?;
+        let coeffs = self.fft(Scalar::slice_to_repr(&evaluations), true)?;
+
+        let mut poly = Polynomial::from(FsPoly { coeffs });
+        poly.normalize();
+
+        Ok(poly)
+    }
+
+    fn extend_commitments(&self, commitments: &[Commitment]) -> Result<Vec<Commitment>, String> {
+        let mut coeffs = self.fft(Commitment::slice_to_repr(commitments), true)?;
+
+        coeffs.resize(coeffs.len() * 2, FsG1::identity());
+
+        self.fft(&coeffs, false).map(Commitment::vec_from_repr)
+    }
+}
@@ -5,50 +5,164 @@ mod tests;
 
 extern crate alloc;
 
+pub mod backend;
+
+use crate::backend::blst_fft::BlstFftBackend;
+use crate::backend::reference::ReferenceBackend;
+use crate::backend::ErasureCodingBackend;
 use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
 use core::num::NonZeroUsize;
-use kzg::{FFTSettings, PolyRecover, DAS, FFTG1, G1};
-use rust_kzg_blst::types::fft_settings::FsFFTSettings;
-use rust_kzg_blst::types::g1::FsG1;
-use rust_kzg_blst::types::poly::FsPoly;
 use subspace_core_primitives::crypto::kzg::{Commitment, Polynomial};
 use subspace_core_primitives::crypto::Scalar;
 
+/// Kind of erasure coding backend to use
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum ErasureCodingBackendKind {
+    /// FFT-based backend, see [`BlstFftBackend`]
+    #[default]
+    BlstFft,
+    /// Reference backend, see [`ReferenceBackend`]
+    Reference,
+}
+
+/// Status of a shard position after recovery
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ShardStatus {
+    /// Shard was provided in the input and used as is
+    Provided,
+    /// Shard was missing in the input and was recovered
+    Recovered,
+    /// Shard was provided in the input, but was found to be corrupted (for example, due to
+    /// commitment mismatch), so it was discarded and recovered instead
+    Corrupted,
+}
+
+/// Report describing status of every shard position after recovery.
+///
+/// Positions are in the same order as shards in recovery input and output (source shards
+/// interleaved with parity shards).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RecoveryReport {
+    statuses: Vec<ShardStatus>,
+}
+
+impl RecoveryReport {
+    /// Create report for recovery input, where present shards are provided and missing shards are
+    /// recovered
+    pub fn new<T>(shards: &[Option<T>]) -> Self {
+        Self {
+            statuses: shards
+                .iter()
+                .map(|maybe_shard| {
+                    if maybe_shard.is_some() {
+                        ShardStatus::Provided
+                    } else {
+                        ShardStatus::Recovered
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Mark provided shard as corrupted, caller is responsible for excluding it from recovery
+    /// input
+    pub fn mark_corrupted(&mut self, position: usize) {
+        if let Some(status @ ShardStatus::Provided) = self.statuses.get_mut(position) {
+            *status = ShardStatus::Corrupted;
+        }
+    }
+
+    /// Statuses of all shard positions
+    pub fn statuses(&self) -> &[ShardStatus] {
+        &self.statuses
+    }
+
+    /// Status of shard at specified position, `None` if position is out of range
+    pub fn status(&self, position: usize) -> Option<ShardStatus> {
+        self.statuses.get(position).copied()
+    }
+
+    /// Whether shard at specified position was valid (missing shards are considered valid)
+    pub fn is_valid(&self, position: usize) -> bool {
+        self.status(position) != Some(ShardStatus::Corrupted)
+    }
+
+    /// Positions of shards that were provided and used as is
+    pub fn provided(&self) -> impl Iterator<Item = usize> + '_ {
+        self.positions_with(|status| status == ShardStatus::Provided)
+    }
+
+    /// Positions of shards that were recovered, including corrupted ones
+    pub fn recovered(&self) -> impl Iterator<Item = usize> + '_ {
+        self.positions_with(|status| status != ShardStatus::Provided)
+    }
+
+    /// Positions of shards that were provided, but found to be corrupted
+    pub fn corrupted(&self) -> impl Iterator<Item = usize> + '_ {
+        self.positions_with(|status| status == ShardStatus::Corrupted)
+    }
+
+    fn positions_with<F>(&self, predicate: F) -> impl Iterator<Item = usize> + '_
+    where
+        F: Fn(ShardStatus) -> bool + 'static,
+    {
+        self.statuses
+            .iter()
+            .enumerate()
+            .filter_map(move |(position, &status)| predicate(status).then_some(position))
+    }
+}
+
 /// Erasure coding abstraction.
 ///
 /// Supports creation of parity records and recovery of missing data.
 #[derive(Debug, Clone)]
 pub struct ErasureCoding {
-    fft_settings: Arc<FsFFTSettings>,
+    backend: Arc<dyn ErasureCodingBackend>,
 }
 
 impl ErasureCoding {
-    /// Create new erasure coding instance.
+    /// Create new erasure coding instance with default backend.
     ///
     /// Number of shards supported is `2^scale`, half of shards are source data and the other half
     /// are parity.
     pub fn new(scale: NonZeroUsize) -> Result<Self, String> {
-        let fft_settings = Arc::new(FsFFTSettings::new(scale.get())?);
+        Self::with_backend(scale, ErasureCodingBackendKind::default())
+    }
+
+    /// Create new erasure coding instance with specified backend.
+    ///
+    /// Number of shards supported is `2^scale`, half of shards are source data and the other half
+    /// are parity.
+    pub fn with_backend(
+        scale: NonZeroUsize,
+        backend_kind: ErasureCodingBackendKind,
+    ) -> Result<Self, String> {
+        let backend: Arc<dyn ErasureCodingBackend> = match backend_kind {
+            ErasureCodingBackendKind::BlstFft => Arc::new(BlstFftBackend::new(scale)?),
+            ErasureCodingBackendKind::Reference => Arc::new(ReferenceBackend::new(scale)?),
+        };
 
-        Ok(Self { fft_settings })
+        Ok(Self { backend })
+    }
+
+    /// Create new erasure coding instance from custom backend implementation
+    pub fn from_backend(backend: Arc<dyn ErasureCodingBackend>) -> Self {
+        Self { backend }
     }
 
     /// Max number of shards supported (both source and parity together)
     pub fn max_shards(&self) -> usize {
-        self.fft_settings.max_width
+        self.backend.max_shards()
     }
 
     /// Extend sources using erasure coding.
     ///
     /// Returns parity data.
     pub fn extend(&self, source: &[Scalar]) -> Result<Vec<Scalar>, String> {
-        // TODO: das_fft_extension modifies buffer internally, it needs to change to use
-        //  pre-allocated buffer instead of allocating a new one
-        self.fft_settings
-            .das_fft_extension(Scalar::slice_to_repr(source))
-            .map(Scalar::vec_from_repr)
+        self.backend.extend(source)
     }
 
     /// Recovery of missing shards from given shards (at least 1/2 should be `Some`).
@@ -56,12 +170,21 @@ impl ErasureCoding {
     /// Both in input and output source shards are interleaved with parity shards:
     /// source, parity, source, parity, ...
     pub fn recover(&self, shards: &[Option<Scalar>]) -> Result<Vec<Scalar>, String> {
-        let poly = FsPoly::recover_poly_from_samples(
-            Scalar::slice_option_to_repr(shards),
-            &self.fft_settings,
-        )?;
+        self.backend.recover(shards)
+    }
+
+    /// Recovery of missing shards from given shards (at least 1/2 should be `Some`) together with
+    /// report describing which shards were provided and which were recovered.
+    ///
+    /// Both in input and output source shards are interleaved with parity shards:
+    /// source, parity, source, parity, ...
+    pub fn recover_with_report(
+        &self,
+        shards: &[Option<Scalar>],
+    ) -> Result<(Vec<Scalar>, RecoveryReport), String> {
+        let recovered_shards = self.recover(shards)?;
 
-        Ok(Scalar::vec_from_repr(poly.coeffs))
+        Ok((recovered_shards, RecoveryReport::new(shards)))
     }
 
     /// Recovery of missing shards from given shards (at least 1/2 should be `Some`) in form of
@@ -70,14 +193,7 @@ impl ErasureCoding {
     /// Both in input and output source shards are interleaved with parity shards:
     /// source, parity, source, parity, ...
     pub fn recover_poly(&self, shards: &[Option<Scalar>]) -> Result<Polynomial, String> {
-        let mut poly = Polynomial::from(FsPoly::recover_poly_coeffs_from_samples(
-            Scalar::slice_option_to_repr(shards),
-            &self.fft_settings,
-        )?);
-
-        poly.normalize();
-
-        Ok(poly)
+        self.backend.recover_poly(shards)
     }
 
     /// Recovery of source shards from given shards (at least 1/2 should be `Some`).
@@ -91,6 +207,47 @@ impl ErasureCoding {
         Ok(self.recover(shards)?.into_iter().step_by(2))
     }
 
+    /// Streaming version of [`ErasureCoding::extend()`] for multiple columns of source shards.
+    ///
+    /// Each column is an iterator of source shards, columns are extended lazily one at a time as
+    /// returned iterator is advanced, yielding source shards interleaved with parity shards: source,
+    /// parity, source, parity, ...
+    ///
+    /// This way only one column is in memory at a time, regardless of the number of columns.
+    pub fn extend_columns<Columns>(&self, columns: Columns) -> ExtendColumns<'_, Columns::IntoIter>
+    where
+        Columns: IntoIterator,
+        Columns::Item: IntoIterator<Item = Scalar>,
+    {
+        ExtendColumns {
+            erasure_coding: self,
+            columns: columns.into_iter(),
+            source: Vec::with_capacity(self.max_shards() / 2),
+        }
+    }
+
+    /// Streaming version of [`ErasureCoding::recover()`] for multiple columns of shards.
+    ///
+    /// Each column is an iterator of shards (source shards interleaved with parity shards, at least
+    /// 1/2 should be `Some`), columns are recovered lazily one at a time as returned iterator is
+    /// advanced, yielding all shards of the column.
+    ///
+    /// This way only one column is in memory at a time, regardless of the number of columns.
+    pub fn recover_columns<Columns>(
+        &self,
+        columns: Columns,
+    ) -> RecoverColumns<'_, Columns::IntoIter>
+    where
+        Columns: IntoIterator,
+        Columns::Item: IntoIterator<Item = Option<Scalar>>,
+    {
+        RecoverColumns {
+            erasure_coding: self,
+            columns: columns.into_iter(),
+            shards: Vec::with_capacity(self.max_shards()),
+        }
+    }
+
     /// Extend commitments using erasure coding.
     ///
     /// Returns both source and parity commitments interleaved.
@@ -98,17 +255,72 @@ impl ErasureCoding {
         &self,
         commitments: &[Commitment],
     ) -> Result<Vec<Commitment>, String> {
-        // Inverse FFT to interpolate polynomial over source commitments
-        let mut coeffs = self
-            .fft_settings
-            .fft_g1(Commitment::slice_to_repr(commitments), true)?;
-
-        // Double the size
-        coeffs.resize(coeffs.len() * 2, FsG1::identity());
-
-        // FFT to get extended commitments
-        self.fft_settings
-            .fft_g1(&coeffs, false)
-            .map(Commitment::vec_from_repr)
+        self.backend.extend_commitments(commitments)
+    }
+}
+
+/// Iterator over extended columns, see [`ErasureCoding::extend_columns()`]
+#[derive(Debug)]
+pub struct ExtendColumns<'a, Columns> {
+    erasure_coding: &'a ErasureCoding,
+    columns: Columns,
+    /// Scratch buffer for source shards of a column to avoid re-allocation
+    source: Vec<Scalar>,
+}
+
+impl<Columns> Iterator for ExtendColumns<'_, Columns>
+where
+    Columns: Iterator,
+    Columns::Item: IntoIterator<Item = Scalar>,
+{
+    type Item = Result<Vec<Scalar>, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let column = self.columns.next()?;
+
+        self.source.clear();
+        self.source.extend(column);
+
+        Some(self.erasure_coding.extend(&self.source).map(|parity| {
+            self.source
+                .iter()
+                .zip(parity)
+                .flat_map(|(&source, parity)| [source, parity])
+                .collect()
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.columns.size_hint()
+    }
+}
+
+/// Iterator over recovered columns, see [`ErasureCoding::recover_columns()`]
+#[derive(Debug)]
+pub struct RecoverColumns<'a, Columns> {
+    erasure_coding: &'a ErasureCoding,
+    columns: Columns,
+    /// Scratch buffer for shards of a column to avoid re-allocation
+    shards: Vec<Option<Scalar>>,
+}
+
+impl<Columns> Iterator for RecoverColumns<'_, Columns>
+where
+    Columns: Iterator,
+    Columns::Item: IntoIterator<Item = Option<Scalar>>,
+{
+    type Item = Result<Vec<Scalar>, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let column = self.columns.next()?;
+
+        self.shards.clear();
+        self.shards.extend(column);
+
+        Some(self.erasure_coding.recover(&self.shards))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.columns.size_hint()
     }
 }
@@ -1,4 +1,4 @@
-use crate::ErasureCoding;
+use crate::{ErasureCoding, ErasureCodingBackendKind, RecoveryReport, ShardStatus};
 use kzg::G1;
 use rust_kzg_blst::types::g1::FsG1;
 use std::iter;
@@ -135,3 +135,194 @@ fn not_enough_partial() {
         .replace(Scalar::default());
     assert!(ec.recover(&partial_shards).is_ok());
 }
+
+#[test]
+fn backends_cross_validation() {
+    let scale = NonZeroUsize::new(6).unwrap();
+    let num_shards = 2usize.pow(scale.get() as u32);
+    let blst_fft = ErasureCoding::with_backend(scale, ErasureCodingBackendKind::BlstFft).unwrap();
+    let reference =
+        ErasureCoding::with_backend(scale, ErasureCodingBackendKind::Reference).unwrap();
+
+    for _ in 0..4 {
+        let source_shards = (0..num_shards / 2)
+            .map(|_| rand::random::<[u8; Scalar::SAFE_BYTES]>())
+            .map(Scalar::from)
+            .collect::<Vec<_>>();
+
+        let parity_shards = blst_fft.extend(&source_shards).unwrap();
+        assert_eq!(parity_shards, reference.extend(&source_shards).unwrap());
+
+        let all_shards = concatenated_to_interleaved(
+            source_shards
+                .iter()
+                .chain(&parity_shards)
+                .copied()
+                .collect::<Vec<_>>(),
+        );
+
+        // Erase random half of the shards
+        let mut partial_shards = all_shards.iter().copied().map(Some).collect::<Vec<_>>();
+        let mut erased = 0;
+        while erased < num_shards / 2 {
+            let index = rand::random::<usize>() % num_shards;
+            if partial_shards[index].take().is_some() {
+                erased += 1;
+            }
+        }
+
+        assert_eq!(blst_fft.recover(&partial_shards).unwrap(), all_shards);
+        assert_eq!(reference.recover(&partial_shards).unwrap(), all_shards);
+
+        let blst_fft_poly = blst_fft.recover_poly(&partial_shards).unwrap();
+        let reference_poly = reference.recover_poly(&partial_shards).unwrap();
+        // Polynomial doesn't expose coefficients, compare debug representation instead
+        assert_eq!(format!("{blst_fft_poly:?}"), format!("{reference_poly:?}"));
+    }
+}
+
+#[test]
+fn backends_cross_validation_commitments() {
+    let scale = NonZeroUsize::new(4).unwrap();
+    let num_shards = 2usize.pow(scale.get() as u32);
+    let blst_fft = ErasureCoding::with_backend(scale, ErasureCodingBackendKind::BlstFft).unwrap();
+    let reference =
+        ErasureCoding::with_backend(scale, ErasureCodingBackendKind::Reference).unwrap();
+
+    let source_commitments = (0..num_shards / 2)
+        .map(|_| Commitment::from(FsG1::rand()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        blst_fft.extend_commitments(&source_commitments).unwrap(),
+        reference.extend_commitments(&source_commitments).unwrap()
+    );
+}
+
+#[test]
+fn reference_backend_errors() {
+    let scale = NonZeroUsize::new(6).unwrap();
+    let num_shards = 2usize.pow(scale.get() as u32);
+    let ec = ErasureCoding::with_backend(scale, ErasureCodingBackendKind::Reference).unwrap();
+
+    assert!(ec.extend(&vec![Scalar::default(); num_shards - 1]).is_err());
+    assert!(ec.extend(&vec![Scalar::default(); num_shards]).is_err());
+
+    let mut partial_shards = vec![None; num_shards];
+    partial_shards
+        .iter_mut()
+        .take(num_shards / 2 - 1)
+        .for_each(|maybe_scalar| {
+            maybe_scalar.replace(Scalar::default());
+        });
+    assert!(ec.recover(&partial_shards).is_err());
+}
+
+#[test]
+fn recovery_report() {
+    let scale = NonZeroUsize::new(4).unwrap();
+    let num_shards = 2usize.pow(scale.get() as u32);
+    let ec = ErasureCoding::new(scale).unwrap();
+
+    let source_shards = (0..num_shards / 2)
+        .map(|_| rand::random::<[u8; Scalar::SAFE_BYTES]>())
+        .map(Scalar::from)
+        .collect::<Vec<_>>();
+    let parity_shards = ec.extend(&source_shards).unwrap();
+    let all_shards = concatenated_to_interleaved(
+        source_shards
+            .into_iter()
+            .chain(parity_shards)
+            .collect::<Vec<_>>(),
+    );
+
+    let mut partial_shards = all_shards.iter().copied().map(Some).collect::<Vec<_>>();
+    // Drop every other shard
+    partial_shards
+        .iter_mut()
+        .step_by(2)
+        .for_each(|maybe_shard| {
+            maybe_shard.take();
+        });
+
+    let (recovered_shards, report) = ec.recover_with_report(&partial_shards).unwrap();
+    assert_eq!(recovered_shards, all_shards);
+    assert_eq!(
+        report.provided().collect::<Vec<_>>(),
+        (1..num_shards).step_by(2).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        report.recovered().collect::<Vec<_>>(),
+        (0..num_shards).step_by(2).collect::<Vec<_>>()
+    );
+    assert_eq!(report.corrupted().count(), 0);
+    assert!((0..num_shards).all(|position| report.is_valid(position)));
+    assert_eq!(report.status(num_shards), None);
+
+    let mut report = RecoveryReport::new(&partial_shards);
+    report.mark_corrupted(1);
+    // Missing shard can't be corrupted
+    report.mark_corrupted(2);
+    assert_eq!(report.status(1), Some(ShardStatus::Corrupted));
+    assert_eq!(report.status(2), Some(ShardStatus::Recovered));
+    assert!(!report.is_valid(1));
+    assert_eq!(report.corrupted().collect::<Vec<_>>(), vec![1]);
+    assert!(report.recovered().any(|position| position == 1));
+    assert!(!report.provided().any(|position| position == 1));
+}
+
+#[test]
+fn columns_streaming() {
+    let scale = NonZeroUsize::new(4).unwrap();
+    let num_shards = 2usize.pow(scale.get() as u32);
+    let ec = ErasureCoding::new(scale).unwrap();
+
+    let columns = (0..5)
+        .map(|_| {
+            (0..num_shards / 2)
+                .map(|_| rand::random::<[u8; Scalar::SAFE_BYTES]>())
+                .map(Scalar::from)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let extended_columns = ec
+        .extend_columns(columns.iter().map(|column| column.iter().copied()))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(extended_columns.len(), columns.len());
+    for (column, extended_column) in columns.iter().zip(&extended_columns) {
+        assert_eq!(
+            extended_column,
+            &concatenated_to_interleaved(
+                column
+                    .iter()
+                    .copied()
+                    .chain(ec.extend(column).unwrap())
+                    .collect()
+            )
+        );
+    }
+
+    // Erase different shards in each column
+    let recovered_columns = ec
+        .recover_columns(extended_columns.iter().enumerate().map(
+            |(column_index, extended_column)| {
+                extended_column
+                    .iter()
+                    .enumerate()
+                    .map(move |(position, &shard)| {
+                        ((position + column_index) % 2 == 0).then_some(shard)
+                    })
+            },
+        ))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(recovered_columns, extended_columns);
+
+    // Errors are reported per column
+    let mut results = ec.recover_columns([vec![None; num_shards], vec![Some(Scalar::default())]]);
+    assert!(results.next().unwrap().is_err());
+    assert!(results.next().unwrap().is_err());
+    assert!(results.next().is_none());
+}
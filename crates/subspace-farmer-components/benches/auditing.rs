@@ -9,7 +9,8 @@ use subspace_archiving::archiver::Archiver;
 use subspace_core_primitives::crypto::kzg;
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::{
-    Blake3Hash, HistorySize, PublicKey, Record, RecordedHistorySegment, SectorId, SolutionRange,
+    GlobalChallenge, HistorySize, PublicKey, Record, RecordedHistorySegment, SectorId,
+    SolutionRange,
 };
 use subspace_erasure_coding::ErasureCoding;
 use subspace_farmer_components::auditing::audit_plot_sync;
@@ -59,6 +60,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             Default::default(),
             true,
         )
+        .unwrap()
         .into_iter()
         .next()
         .unwrap()
@@ -74,7 +76,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         ),
         min_sector_lifetime: HistorySize::from(NonZeroU64::new(4).unwrap()),
     };
-    let global_challenge = &Blake3Hash::default();
+    let global_challenge = &GlobalChallenge::default();
     let solution_range = SolutionRange::MAX;
 
     let sector_size = sector_size(pieces_in_sector);
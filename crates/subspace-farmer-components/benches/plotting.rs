@@ -51,6 +51,7 @@ fn criterion_benchmark(c: &mut Criterion) {
             Default::default(),
             true,
         )
+        .unwrap()
         .into_iter()
         .next()
         .unwrap()
@@ -13,8 +13,8 @@ use subspace_archiving::archiver::Archiver;
 use subspace_core_primitives::crypto::kzg;
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::{
-    Blake3Hash, HistorySize, PosSeed, PublicKey, Record, RecordedHistorySegment, SectorId,
-    SolutionRange,
+    Blake3Hash, GlobalChallenge, HistorySize, PosSeed, PublicKey, Record, RecordedHistorySegment,
+    SectorId, SolutionRange,
 };
 use subspace_erasure_coding::ErasureCoding;
 use subspace_farmer_components::auditing::audit_plot_sync;
@@ -66,6 +66,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             Default::default(),
             true,
         )
+        .unwrap()
         .into_iter()
         .next()
         .unwrap()
@@ -157,6 +158,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     let (global_challenge, solution_candidates) = &loop {
         let mut global_challenge = Blake3Hash::default();
         rng.fill_bytes(&mut global_challenge);
+        let global_challenge = GlobalChallenge::from(global_challenge);
 
         let audit_results = audit_plot_sync(
             public_key,
@@ -61,6 +61,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             Default::default(),
             true,
         )
+        .unwrap()
         .into_iter()
         .next()
         .unwrap()
@@ -5,7 +5,8 @@ use rayon::prelude::*;
 use std::io;
 use subspace_core_primitives::crypto::Scalar;
 use subspace_core_primitives::{
-    Blake3Hash, PublicKey, SBucket, SectorId, SectorIndex, SectorSlotChallenge, SolutionRange,
+    GlobalChallenge, PublicKey, PublicKeyHash, SBucket, SectorId, SectorIndex, SectorSlotChallenge,
+    SolutionRange,
 };
 use subspace_verification::is_within_solution_range;
 use thiserror::Error;
@@ -54,7 +55,7 @@ pub(crate) struct ChunkCandidate {
 /// This is primarily helpful in test environment, prefer [`audit_plot_sync`] for auditing real plots.
 pub fn audit_sector_sync<'a, Sector>(
     public_key: &'a PublicKey,
-    global_challenge: &Blake3Hash,
+    global_challenge: &GlobalChallenge,
     solution_range: SolutionRange,
     sector: Sector,
     sector_metadata: &'a SectorMetadataChecksummed,
@@ -105,7 +106,7 @@ where
 /// Audit the whole plot and generate streams of solutions
 pub fn audit_plot_sync<'a, Plot>(
     public_key: &'a PublicKey,
-    global_challenge: &Blake3Hash,
+    global_challenge: &GlobalChallenge,
     solution_range: SolutionRange,
     plot: &'a Plot,
     sectors_metadata: &'a [SectorMetadataChecksummed],
@@ -179,6 +180,51 @@ where
         .collect()
 }
 
+/// Issue read-ahead for s-buckets that will be read by [`audit_plot_sync`] for the same
+/// `global_challenge`.
+///
+/// Doesn't wait for data to be read, but allows storage to fetch it in the background (for example
+/// while farmer is busy proving previous slot), which reduces audit latency on high-latency disks.
+pub fn prefetch_plot_audit_sync<Plot>(
+    public_key: &PublicKey,
+    global_challenge: &GlobalChallenge,
+    plot: &Plot,
+    sectors_metadata: &[SectorMetadataChecksummed],
+    maybe_sector_being_modified: Option<SectorIndex>,
+) -> Result<(), AuditingError>
+where
+    Plot: ReadAtSync,
+{
+    let public_key_hash = public_key.hash();
+
+    sectors_metadata
+        .iter()
+        .filter(|sector_metadata| maybe_sector_being_modified != Some(sector_metadata.sector_index))
+        .try_for_each(|sector_metadata| {
+            let sector_auditing_info =
+                collect_sector_auditing_details(public_key_hash, global_challenge, sector_metadata);
+
+            if sector_auditing_info.s_bucket_audit_size == 0 {
+                // S-bucket is empty
+                return Ok(());
+            }
+
+            plot.offset(
+                u64::from(sector_metadata.sector_index)
+                    * sector_size(sector_metadata.pieces_in_sector) as u64,
+            )
+            .read_ahead(
+                sector_auditing_info.s_bucket_audit_offset_in_sector,
+                sector_auditing_info.s_bucket_audit_size,
+            )
+            .map_err(|error| AuditingError::SBucketReading {
+                sector_index: sector_metadata.sector_index,
+                s_bucket_audit_index: sector_auditing_info.s_bucket_audit_index,
+                error,
+            })
+        })
+}
+
 struct SectorAuditingDetails {
     sector_id: SectorId,
     sector_slot_challenge: SectorSlotChallenge,
@@ -190,8 +236,8 @@ struct SectorAuditingDetails {
 }
 
 fn collect_sector_auditing_details(
-    public_key_hash: Blake3Hash,
-    global_challenge: &Blake3Hash,
+    public_key_hash: PublicKeyHash,
+    global_challenge: &GlobalChallenge,
     sector_metadata: &SectorMetadataChecksummed,
 ) -> SectorAuditingDetails {
     let sector_id = SectorId::new(public_key_hash, sector_metadata.sector_index);
@@ -201,13 +247,7 @@ fn collect_sector_auditing_details(
     let s_bucket_audit_size = Scalar::FULL_BYTES
         * usize::from(sector_metadata.s_bucket_sizes[usize::from(s_bucket_audit_index)]);
     let s_bucket_audit_offset = Scalar::FULL_BYTES as u64
-        * sector_metadata
-            .s_bucket_sizes
-            .iter()
-            .take(s_bucket_audit_index.into())
-            .copied()
-            .map(u64::from)
-            .sum::<u64>();
+        * u64::from(s_bucket_audit_index.offset_in_sector(&sector_metadata.s_bucket_sizes));
 
     let sector_contents_map_size =
         SectorContentsMap::encoded_size(sector_metadata.pieces_in_sector);
@@ -226,7 +266,7 @@ fn collect_sector_auditing_details(
 /// Map all winning chunks
 fn map_winning_chunks(
     s_bucket: &[u8],
-    global_challenge: &Blake3Hash,
+    global_challenge: &GlobalChallenge,
     sector_slot_challenge: &SectorSlotChallenge,
     solution_range: SolutionRange,
 ) -> Option<(Vec<ChunkCandidate>, SolutionRange)> {
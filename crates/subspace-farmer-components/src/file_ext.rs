@@ -66,6 +66,10 @@ pub trait FileExt {
     /// desirable, on Windows this can only be set when file is opened, see [`OpenOptionsExt`]
     fn advise_sequential_access(&self) -> Result<()>;
 
+    /// Advise OS/file system that specified range of the file will be read soon, such that it can
+    /// be read ahead of time in the background
+    fn advise_will_need(&self, offset: u64, len: u64) -> Result<()>;
+
     /// Read exact number of bytes at a specific offset
     fn read_exact_at(&self, buf: &mut [u8], offset: u64) -> Result<()>;
 
@@ -133,6 +137,44 @@ impl FileExt for File {
         Ok(())
     }
 
+    #[cfg(target_os = "linux")]
+    fn advise_will_need(&self, offset: u64, len: u64) -> Result<()> {
+        use std::os::unix::io::AsRawFd;
+        let err = unsafe {
+            libc::posix_fadvise(
+                self.as_raw_fd(),
+                offset as libc::off_t,
+                len as libc::off_t,
+                libc::POSIX_FADV_WILLNEED,
+            )
+        };
+        if err != 0 {
+            Err(std::io::Error::from_raw_os_error(err))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn advise_will_need(&self, offset: u64, len: u64) -> Result<()> {
+        use std::os::unix::io::AsRawFd;
+        let mut advisory = libc::radvisory {
+            ra_offset: offset as libc::off_t,
+            ra_count: libc::c_int::try_from(len).unwrap_or(libc::c_int::MAX),
+        };
+        if unsafe { libc::fcntl(self.as_raw_fd(), libc::F_RDADVISE, &mut advisory) } == -1 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    #[cfg(windows)]
+    fn advise_will_need(&self, _offset: u64, _len: u64) -> Result<()> {
+        // Not supported
+        Ok(())
+    }
+
     #[cfg(unix)]
     fn read_exact_at(&self, buf: &mut [u8], offset: u64) -> Result<()> {
         std::os::unix::fs::FileExt::read_exact_at(self, buf, offset)
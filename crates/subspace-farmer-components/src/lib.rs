@@ -6,7 +6,6 @@
     iter_collect_into,
     never_type,
     new_uninit,
-    portable_simd,
     slice_flatten,
     try_blocks
 )]
@@ -137,6 +136,12 @@ pub trait ReadAtSync: Send + Sync {
 
     /// Fill the buffer by reading bytes at a specific offset
     fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()>;
+
+    /// Hint that `len` bytes at a specific offset will likely be read soon, implementations backed
+    /// by slow storage can use this to issue read-ahead, default implementation does nothing
+    fn read_ahead(&self, _offset: u64, _len: usize) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 impl ReadAtSync for ! {
@@ -259,12 +264,20 @@ impl ReadAtSync for File {
     fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()> {
         self.read_exact_at(buf, offset)
     }
+
+    fn read_ahead(&self, offset: u64, len: usize) -> io::Result<()> {
+        self.advise_will_need(offset, len as u64)
+    }
 }
 
 impl ReadAtSync for &File {
     fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()> {
         self.read_exact_at(buf, offset)
     }
+
+    fn read_ahead(&self, offset: u64, len: usize) -> io::Result<()> {
+        self.advise_will_need(offset, len as u64)
+    }
 }
 
 /// Reader with fixed offset added to all attempted reads
@@ -281,6 +294,10 @@ where
     fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()> {
         self.inner.read_at(buf, offset + self.offset)
     }
+
+    fn read_ahead(&self, offset: u64, len: usize) -> io::Result<()> {
+        self.inner.read_ahead(offset + self.offset, len)
+    }
 }
 
 impl<T> ReadAtSync for &ReadAtOffset<'_, T>
@@ -290,6 +307,10 @@ where
     fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()> {
         self.inner.read_at(buf, offset + self.offset)
     }
+
+    fn read_ahead(&self, offset: u64, len: usize) -> io::Result<()> {
+        self.inner.read_ahead(offset + self.offset, len)
+    }
 }
 
 impl<T> ReadAtAsync for ReadAtOffset<'_, T>
@@ -13,14 +13,14 @@ use parity_scale_codec::{Decode, Encode};
 use parking_lot::Mutex;
 use rayon::prelude::*;
 use std::mem;
-use std::simd::Simd;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::crypto::{blake3_hash, blake3_hash_parallel, Scalar};
 use subspace_core_primitives::{
-    Blake3Hash, PieceIndex, PieceOffset, PosSeed, PublicKey, Record, SBucket, SectorId, SectorIndex,
+    Blake3Hash, PieceIndex, PieceOffset, PosSeed, PublicKey, Record, RecordChunksView, SBucket,
+    SectorId, SectorIndex,
 };
 use subspace_erasure_coding::ErasureCoding;
 use subspace_proof_of_space::{Table, TableGenerator};
@@ -540,7 +540,7 @@ fn record_encoding<PosTable>(
     mut encoded_chunks_used: EncodedChunksUsed<'_>,
     table_generator: &mut PosTable::Generator,
     erasure_coding: &ErasureCoding,
-    chunks_scratch: &mut Vec<Option<Simd<u8, 32>>>,
+    chunks_scratch: &mut Vec<Option<[u8; Scalar::FULL_BYTES]>>,
 ) where
     PosTable: Table,
 {
@@ -560,6 +560,10 @@ fn record_encoding<PosTable>(
     let parity_record_chunks = erasure_coding
         .extend(&source_record_chunks)
         .expect("Instance was verified to be able to work with this many values earlier; qed");
+    let record_chunks = RecordChunksView::new(&source_record_chunks, &parity_record_chunks).expect(
+        "Record has correct number of chunks and erasure coding produces the same number of \
+            parity chunks; qed",
+    );
 
     chunks_scratch.clear();
     // For every erasure coded chunk check if there is quality present, if so then encode
@@ -567,15 +571,11 @@ fn record_encoding<PosTable>(
     (u16::from(SBucket::ZERO)..=u16::from(SBucket::MAX))
         .into_par_iter()
         .map(SBucket::from)
-        .zip(
-            source_record_chunks
-                .par_iter()
-                .interleave(&parity_record_chunks),
-        )
-        .map(|(s_bucket, record_chunk)| {
+        .map(|s_bucket| {
+            let record_chunk = record_chunks.chunk_for_s_bucket(s_bucket);
             let proof = pos_table.find_proof(s_bucket.into())?;
 
-            Some(Simd::from(record_chunk.to_bytes()) ^ Simd::from(proof.hash()))
+            Some(Record::mask_chunk(&record_chunk.to_bytes(), &proof.hash()))
         })
         .collect_into_vec(chunks_scratch);
     let num_successfully_encoded_chunks = chunks_scratch
@@ -596,16 +596,14 @@ fn record_encoding<PosTable>(
         .zip(record.iter_mut())
         // Write encoded chunk back so we can reuse original allocation
         .map(|(input_chunk, output_chunk)| {
-            *output_chunk = input_chunk.to_array();
+            *output_chunk = input_chunk;
         })
         .count();
 
     // In some cases there is not enough PoSpace qualities available, in which case we add
     // remaining number of unencoded erasure coded record chunks to the end
-    source_record_chunks
+    record_chunks
         .iter()
-        .zip(&parity_record_chunks)
-        .flat_map(|(a, b)| [a, b])
         .zip(encoded_chunks_used.iter())
         // Skip chunks that were used previously
         .filter_map(|(record_chunk, encoded_chunk_used)| {
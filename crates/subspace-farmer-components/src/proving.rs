@@ -10,7 +10,7 @@ use std::io;
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::{
     ChunkWitness, PieceOffset, PosSeed, PublicKey, Record, SBucket, SectorId, Solution,
-    SolutionRange,
+    SolutionBuilder, SolutionRange,
 };
 use subspace_erasure_coding::ErasureCoding;
 use subspace_proof_of_space::Table;
@@ -289,18 +289,20 @@ where
                     error,
                 })?;
 
-            Solution {
-                public_key: *self.public_key,
-                reward_address: *self.reward_address,
-                sector_index: self.sector_metadata.sector_index,
-                history_size: self.sector_metadata.history_size,
-                piece_offset,
-                record_commitment: record_metadata.commitment,
-                record_witness: record_metadata.witness,
-                chunk,
-                chunk_witness: ChunkWitness::from(chunk_witness),
-                proof_of_space,
-            }
+            SolutionBuilder::new(*self.public_key, *self.reward_address)
+                .sector(
+                    self.sector_metadata.sector_index,
+                    self.sector_metadata.history_size,
+                )
+                .record(
+                    piece_offset,
+                    record_metadata.commitment,
+                    record_metadata.witness,
+                )
+                .chunk(chunk, ChunkWitness::from(chunk_witness))
+                .proof_of_space(proof_of_space)
+                .build()
+                .expect("All parts of the solution are provided above; qed")
         };
 
         match maybe_solution {
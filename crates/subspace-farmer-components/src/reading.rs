@@ -9,7 +9,6 @@ use parity_scale_codec::Decode;
 use rayon::prelude::*;
 use std::io;
 use std::mem::ManuallyDrop;
-use std::simd::Simd;
 use subspace_core_primitives::crypto::{blake3_hash, Scalar};
 use subspace_core_primitives::{
     Piece, PieceOffset, Record, RecordCommitment, RecordWitness, SBucket, SectorId,
@@ -167,8 +166,7 @@ where
                             .find_proof(s_bucket.into())
                             .expect("encoded_chunk_used implies proof exists for this chunk; qed");
 
-                        record_chunk =
-                            Simd::to_array(Simd::from(record_chunk) ^ Simd::from(proof.hash()));
+                        Record::mask_chunk_in_place(&mut record_chunk, &proof.hash());
                     }
 
                     maybe_record_chunk.replace(Scalar::try_from(record_chunk).map_err(
@@ -212,9 +210,7 @@ where
                                 "encoded_chunk_used implies proof exists for this chunk; qed",
                             );
 
-                            record_chunk = Simd::to_array(
-                                Simd::from(record_chunk) ^ Simd::from(proof.hash()),
-                            );
+                            Record::mask_chunk_in_place(&mut record_chunk, &proof.hash());
                         }
 
                         maybe_record_chunk.replace(Scalar::try_from(record_chunk).map_err(
@@ -2,7 +2,7 @@ use bitvec::prelude::*;
 use parity_scale_codec::{Decode, Encode};
 use rayon::prelude::*;
 use std::ops::{Deref, DerefMut};
-use std::{mem, slice};
+use std::{fmt, mem, slice};
 use subspace_core_primitives::checksum::Blake3Checksummed;
 use subspace_core_primitives::crypto::blake3_hash;
 use subspace_core_primitives::{
@@ -59,24 +59,7 @@ pub struct SectorMetadata {
 impl SectorMetadata {
     /// Returns offsets of each s-bucket relatively to the beginning of the sector (in chunks)
     pub fn s_bucket_offsets(&self) -> Box<[u32; Record::NUM_S_BUCKETS]> {
-        // TODO: Should have been just `::new()`, but https://github.com/rust-lang/rust/issues/53827
-        // SAFETY: Data structure filled with zeroes is a valid invariant
-        let mut s_bucket_offsets =
-            unsafe { Box::<[u32; Record::NUM_S_BUCKETS]>::new_zeroed().assume_init() };
-
-        self.s_bucket_sizes
-            .iter()
-            .zip(s_bucket_offsets.iter_mut())
-            .for_each({
-                let mut base_offset = 0;
-
-                move |(s_bucket_size, s_bucket_offset)| {
-                    *s_bucket_offset = base_offset;
-                    base_offset += u32::from(*s_bucket_size);
-                }
-            });
-
-        s_bucket_offsets
+        SBucket::offsets_in_sector(&self.s_bucket_sizes)
     }
 }
 
@@ -124,6 +107,130 @@ impl SectorMetadataChecksummed {
 
         default.encoded_size()
     }
+
+    /// Size of sector metadata encoded with [`Self::encode_with_version()`]
+    #[inline]
+    pub fn encoded_size_with_version() -> usize {
+        SectorFormatVersion::CURRENT.encoded_size() + Self::encoded_size()
+    }
+
+    /// Encode sector metadata prefixed with format version of the sector
+    pub fn encode_with_version(&self, version: SectorFormatVersion) -> Vec<u8> {
+        (version, self).encode()
+    }
+
+    /// Decode sector metadata encoded with [`Self::encode_with_version()`], returns format version
+    /// of the sector alongside metadata
+    pub fn decode_with_version(
+        input: &mut &[u8],
+    ) -> Result<(SectorFormatVersion, Self), SectorFormatError> {
+        let version = SectorFormatVersion::decode(input)?;
+        if !version.is_supported() {
+            return Err(SectorFormatError::UnsupportedVersion(version));
+        }
+
+        Ok((version, Self::decode(input)?))
+    }
+}
+
+/// Version of the plotted sector format.
+///
+/// Version is stored alongside sector metadata, such that sectors plotted by older releases can
+/// still be read and upgraded in place with [`upgrade_sector()`] instead of being plotted again.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Encode, Decode)]
+pub struct SectorFormatVersion(u8);
+
+impl fmt::Display for SectorFormatVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+impl From<SectorFormatVersion> for u8 {
+    #[inline]
+    fn from(value: SectorFormatVersion) -> Self {
+        value.0
+    }
+}
+
+impl SectorFormatVersion {
+    /// Sectors plotted before format versioning was introduced
+    pub const V0: Self = Self(0);
+    /// Same as [`Self::V0`], but format version is stored alongside sector metadata
+    pub const V1: Self = Self(1);
+    /// Format used for newly plotted sectors
+    pub const CURRENT: Self = Self::V1;
+
+    /// Whether sectors in this format can be read and upgraded
+    #[inline]
+    pub fn is_supported(self) -> bool {
+        self <= Self::CURRENT
+    }
+
+    /// Whether upgrade from this version to [`Self::CURRENT`] requires rewriting sector contents,
+    /// otherwise only sector metadata needs to be rewritten
+    #[inline]
+    pub fn requires_contents_upgrade(self) -> bool {
+        // Layout of sector contents didn't change so far
+        false
+    }
+}
+
+/// Errors happening when dealing with sectors in different formats
+#[derive(Debug, Error)]
+pub enum SectorFormatError {
+    /// Sector format version is not supported
+    #[error("Sector format version {0} is not supported")]
+    UnsupportedVersion(SectorFormatVersion),
+    /// Failed to decode sector metadata
+    #[error("Failed to decode sector metadata: {0}")]
+    Decode(#[from] parity_scale_codec::Error),
+    /// Invalid sector size
+    #[error("Invalid sector size, expected {expected}, actual {actual}")]
+    InvalidSectorSize {
+        /// Expected size
+        expected: usize,
+        /// Actual size
+        actual: usize,
+    },
+}
+
+/// Upgrade sector plotted in older format to [`SectorFormatVersion::CURRENT`] in place.
+///
+/// `sector` is only accessed when [`SectorFormatVersion::requires_contents_upgrade()`] returns
+/// `true` for `version`, otherwise it can be empty, which allows to skip reading of sector contents
+/// when only metadata needs to be upgraded.
+pub fn upgrade_sector(
+    version: SectorFormatVersion,
+    sector_metadata: &mut SectorMetadata,
+    sector: &mut [u8],
+) -> Result<(), SectorFormatError> {
+    if !version.is_supported() {
+        return Err(SectorFormatError::UnsupportedVersion(version));
+    }
+
+    if version.requires_contents_upgrade() {
+        let expected = sector_size(sector_metadata.pieces_in_sector);
+        if sector.len() != expected {
+            return Err(SectorFormatError::InvalidSectorSize {
+                expected,
+                actual: sector.len(),
+            });
+        }
+    }
+
+    let mut version = version;
+    while version < SectorFormatVersion::CURRENT {
+        version = match version {
+            // Only format version tag was added to metadata, nothing to do
+            SectorFormatVersion::V0 => SectorFormatVersion::V1,
+            version => {
+                return Err(SectorFormatError::UnsupportedVersion(version));
+            }
+        };
+    }
+
+    Ok(())
 }
 
 /// Commitment and witness corresponding to the same record
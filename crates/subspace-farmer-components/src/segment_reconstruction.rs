@@ -84,7 +84,7 @@ pub(crate) async fn recover_missing_piece<PG: PieceGetter>(
     while let Some(maybe_received_piece) = received_segment_pieces.next().await {
         if let Some((piece_index, received_piece)) = maybe_received_piece {
             segment_pieces
-                .get_mut(piece_index.position() as usize)
+                .get_mut(usize::from(piece_index.position()))
                 .expect("Piece position is by definition within segment; qed")
                 .replace(received_piece);
         }
@@ -104,7 +104,7 @@ pub(crate) async fn recover_missing_piece<PG: PieceGetter>(
 
     let archiver = PiecesReconstructor::new(kzg).expect("Internal constructor call must succeed.");
 
-    let result = archiver.reconstruct_piece(&segment_pieces, position as usize)?;
+    let result = archiver.reconstruct_piece(&segment_pieces, usize::from(position))?;
 
     info!(%missing_piece_index, "Recovering missing piece succeeded.");
 
@@ -1,8 +1,14 @@
 pub(crate) mod benchmark;
+pub(crate) mod cache;
+pub(crate) mod estimate;
 pub(crate) mod farm;
+pub(crate) mod identity;
 mod info;
+pub(crate) mod monitoring_server;
+pub(crate) mod plotter;
 mod scrub;
 mod shared;
+pub(crate) mod wipe;
 
 pub(crate) use info::info;
 pub(crate) use scrub::scrub;
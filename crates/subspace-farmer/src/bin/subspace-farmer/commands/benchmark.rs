@@ -7,7 +7,7 @@ use std::fs::OpenOptions;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
-use subspace_core_primitives::{Record, SolutionRange};
+use subspace_core_primitives::{Blake3Hash, GlobalChallenge, Record, SolutionRange};
 use subspace_erasure_coding::ErasureCoding;
 use subspace_farmer::single_disk_farm::farming::rayon_files::RayonFiles;
 use subspace_farmer::single_disk_farm::farming::{PlotAudit, PlotAuditOptions};
@@ -135,7 +135,7 @@ fn audit(
 
             group.bench_function("plot/single", |b| {
                 b.iter_batched(
-                    rand::random,
+                    || GlobalChallenge::from(rand::random::<Blake3Hash>()),
                     |global_challenge| {
                         let options = PlotAuditOptions::<PosTable> {
                             public_key: single_disk_farm_info.public_key(),
@@ -147,6 +147,8 @@ fn audit(
                                 solution_range: SolutionRange::MIN,
                                 // No solution will be found, pure audit
                                 voting_solution_range: SolutionRange::MIN,
+                                import_queue_depth: 0,
+                                best_block_slot_lag: 0,
                             },
                             sectors_metadata: &sectors_metadata,
                             kzg: &kzg,
@@ -168,7 +170,7 @@ fn audit(
 
             group.bench_function("plot/rayon", |b| {
                 b.iter_batched(
-                    rand::random,
+                    || GlobalChallenge::from(rand::random::<Blake3Hash>()),
                     |global_challenge| {
                         let options = PlotAuditOptions::<PosTable> {
                             public_key: single_disk_farm_info.public_key(),
@@ -180,6 +182,8 @@ fn audit(
                                 solution_range: SolutionRange::MIN,
                                 // No solution will be found, pure audit
                                 voting_solution_range: SolutionRange::MIN,
+                                import_queue_depth: 0,
+                                best_block_slot_lag: 0,
                             },
                             sectors_metadata: &sectors_metadata,
                             kzg: &kzg,
@@ -257,11 +261,13 @@ fn prove(
                 reward_address: single_disk_farm_info.public_key(),
                 slot_info: SlotInfo {
                     slot_number: 0,
-                    global_challenge: rand::random(),
+                    global_challenge: GlobalChallenge::from(rand::random::<Blake3Hash>()),
                     // Solution is guaranteed to be found
                     solution_range: SolutionRange::MAX,
                     // Solution is guaranteed to be found
                     voting_solution_range: SolutionRange::MAX,
+                    import_queue_depth: 0,
+                    best_block_slot_lag: 0,
                 },
                 sectors_metadata: &sectors_metadata,
                 kzg: &kzg,
@@ -301,11 +307,13 @@ fn prove(
                 reward_address: single_disk_farm_info.public_key(),
                 slot_info: SlotInfo {
                     slot_number: 0,
-                    global_challenge: rand::random(),
+                    global_challenge: GlobalChallenge::from(rand::random::<Blake3Hash>()),
                     // Solution is guaranteed to be found
                     solution_range: SolutionRange::MAX,
                     // Solution is guaranteed to be found
                     voting_solution_range: SolutionRange::MAX,
+                    import_queue_depth: 0,
+                    best_block_slot_lag: 0,
                 },
                 sectors_metadata: &sectors_metadata,
                 kzg: &kzg,
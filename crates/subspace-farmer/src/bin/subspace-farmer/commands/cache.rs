@@ -0,0 +1,72 @@
+use clap::Subcommand;
+use rayon::prelude::*;
+use std::path::PathBuf;
+use subspace_farmer::error_code::ErrorCode;
+use subspace_farmer::single_disk_farm::SingleDiskFarm;
+use tracing::{error, info, info_span};
+
+/// Arguments for piece cache management
+#[derive(Debug, Subcommand)]
+pub(crate) enum CacheArgs {
+    /// Salvage valid pieces of the piece cache after abrupt shutdown (like power loss) or disk
+    /// errors, corrupted cache elements are erased while the rest of the cache is preserved
+    Repair {
+        /// One or more farm located at specified path.
+        ///
+        /// Example:
+        ///   /path/to/directory
+        disk_farms: Vec<PathBuf>,
+        /// Disable farm locking, for example if file system doesn't support it
+        #[arg(long)]
+        disable_farm_locking: bool,
+    },
+}
+
+pub(crate) fn cache(cache_args: CacheArgs) {
+    match cache_args {
+        CacheArgs::Repair {
+            disk_farms,
+            disable_farm_locking,
+        } => {
+            if disk_farms.is_empty() {
+                info!("No farm was specified, so there is nothing to do");
+            } else {
+                repair(&disk_farms, disable_farm_locking);
+            }
+        }
+    }
+}
+
+fn repair(disk_farms: &[PathBuf], disable_farm_locking: bool) {
+    disk_farms
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(disk_farm_index, directory)| {
+            let span = info_span!("", %disk_farm_index);
+            let _span_guard = span.enter();
+            info!(
+                path = %directory.display(),
+                "Start repairing piece cache"
+            );
+
+            match SingleDiskFarm::repair_cache(directory, disable_farm_locking) {
+                Ok(report) => {
+                    info!(
+                        path = %directory.display(),
+                        valid_elements = %report.valid_elements,
+                        corrupted_elements = %report.corrupted_elements,
+                        empty_elements = %report.empty_elements,
+                        "Piece cache repaired successfully"
+                    );
+                }
+                Err(error) => {
+                    error!(
+                        code = %ErrorCode::CacheReadFailed,
+                        path = %directory.display(),
+                        %error,
+                        "Failed to repair piece cache"
+                    );
+                }
+            }
+        });
+}
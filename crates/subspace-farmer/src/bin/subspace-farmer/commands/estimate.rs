@@ -0,0 +1,177 @@
+use anyhow::anyhow;
+use bytesize::ByteSize;
+use clap::{Parser, ValueHint};
+use std::time::Duration;
+use subspace_core_primitives::{BlockNumber, Record};
+use subspace_farmer::NodeRpcClient;
+use subspace_farmer_components::sector::sector_size;
+use subspace_rpc_primitives::SolutionRangeHistory;
+use tracing::info;
+
+/// Duration of a day
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+/// Give up projecting time to first reward after this many days
+const MAX_PROJECTION_DAYS: u32 = 10 * 365;
+
+/// Arguments for rewards estimation
+#[derive(Debug, Parser)]
+pub(crate) struct EstimateArgs {
+    /// WebSocket RPC URL of the Subspace node to retrieve historical solution ranges from
+    #[arg(long, value_hint = ValueHint::Url, default_value = "ws://127.0.0.1:9944")]
+    node_rpc_url: String,
+    /// Size of the plot to estimate rewards for, for example 2T or 500GiB.
+    ///
+    /// Only space used by sectors is considered, farm metadata and piece cache are not included.
+    #[arg(long)]
+    plot_size: ByteSize,
+    /// Assumed network growth in percents per day.
+    ///
+    /// By default derived from historical solution ranges, note that negative values mean network
+    /// shrinks.
+    #[arg(long, allow_negative_numbers = true)]
+    network_growth: Option<f64>,
+    /// Number of historical solution range samples to retrieve from the node
+    #[arg(long, default_value_t = 100)]
+    history_samples: u32,
+    /// Interval between historical solution range samples in blocks
+    #[arg(long, default_value_t = 600)]
+    history_interval: BlockNumber,
+    /// Number of days to project daily rewards for
+    #[arg(long, default_value_t = 30)]
+    days: u32,
+}
+
+/// Estimate expected rewards for a plot of specified size given historical solution ranges and
+/// network growth
+pub(crate) async fn estimate(estimate_args: EstimateArgs) -> anyhow::Result<()> {
+    let EstimateArgs {
+        node_rpc_url,
+        plot_size,
+        network_growth,
+        history_samples,
+        history_interval,
+        days,
+    } = estimate_args;
+
+    info!(url = %node_rpc_url, "Connecting to node RPC");
+    let node_client = NodeRpcClient::new(&node_rpc_url).await?;
+
+    let history = node_client
+        .solution_range_history(history_samples, history_interval)
+        .await
+        .map_err(|error| anyhow!("Failed to retrieve solution range history: {error}"))?;
+
+    let Some(last_sample) = history.samples.last() else {
+        return Err(anyhow!(
+            "Node returned no solution range history, is it synced past genesis?"
+        ));
+    };
+
+    let sectors = plot_size.as_u64() / sector_size(history.max_pieces_in_sector) as u64;
+    if sectors == 0 {
+        return Err(anyhow!(
+            "Plot size {plot_size} is smaller than a single sector of {}",
+            ByteSize::b(sector_size(history.max_pieces_in_sector) as u64)
+        ));
+    }
+    let slots_per_day = DAY.as_secs_f64() / history.slot_duration.as_secs_f64();
+
+    let daily_growth = match network_growth {
+        Some(network_growth) => network_growth / 100.0,
+        None => observed_daily_growth(&history, slots_per_day).unwrap_or_default(),
+    };
+    // Solution range is inversely proportional to total space pledged to the network
+    let solution_range_at = |day: f64| {
+        last_sample.solution_range as f64 / (1.0 + daily_growth).powf(day).max(f64::MIN_POSITIVE)
+    };
+
+    // Every slot each sector audits one s-bucket, which contains on average this many chunks
+    let chunks_per_audit = f64::from(history.max_pieces_in_sector) * Record::NUM_CHUNKS as f64
+        / Record::NUM_S_BUCKETS as f64;
+    let voting_multiplier =
+        history.voting_solution_range as f64 / last_sample.solution_range.max(1) as f64;
+    // Expected number of blocks and votes (combined) per day
+    let daily_rewards = |day: f64| {
+        let win_probability = solution_range_at(day) / u64::MAX as f64;
+        let blocks = sectors as f64 * chunks_per_audit * win_probability * slots_per_day;
+        (blocks, blocks * (voting_multiplier - 1.0).max(0.0))
+    };
+
+    info!(
+        %plot_size,
+        sectors,
+        samples = history.samples.len(),
+        last_block = last_sample.block_number,
+        solution_range = last_sample.solution_range,
+        "Retrieved solution range history"
+    );
+    info!(
+        "Assumed network growth: {:.3}% per day{}",
+        daily_growth * 100.0,
+        if network_growth.is_some() {
+            ""
+        } else {
+            " (derived from history)"
+        }
+    );
+
+    println!("Day\tBlocks\tVotes");
+    for day in 0..=days {
+        let (blocks, votes) = daily_rewards(f64::from(day));
+        println!("{day}\t{blocks:.3}\t{votes:.3}");
+    }
+
+    // Rewards follow Poisson distribution, so probability of no rewards by the time `T` is
+    // `exp(-expected_rewards(T))`
+    for (label, probability) in [("Median", 0.5_f64), ("90% probability", 0.9)] {
+        let target = -(1.0 - probability).ln();
+        match time_to_expected_rewards(target, |day| {
+            let (blocks, votes) = daily_rewards(day);
+            blocks + votes
+        }) {
+            Some(time) => {
+                println!(
+                    "{label} time to first reward: {:.1} days",
+                    time.as_secs_f64() / DAY.as_secs_f64()
+                );
+            }
+            None => {
+                println!("{label} time to first reward: more than {MAX_PROJECTION_DAYS} days");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Daily growth derived from the change of solution range between first and last samples
+fn observed_daily_growth(history: &SolutionRangeHistory, slots_per_day: f64) -> Option<f64> {
+    let first = history.samples.first()?;
+    let last = history.samples.last()?;
+    let elapsed_slots = last.slot_number.checked_sub(first.slot_number)?;
+    if elapsed_slots == 0 || last.solution_range == 0 {
+        return None;
+    }
+
+    let space_ratio = first.solution_range as f64 / last.solution_range as f64;
+    Some(space_ratio.powf(slots_per_day / elapsed_slots as f64) - 1.0)
+}
+
+/// Time it takes for cumulative expected rewards to reach `target`, `None` if it doesn't happen
+/// within [`MAX_PROJECTION_DAYS`]
+fn time_to_expected_rewards<F>(target: f64, daily_rewards: F) -> Option<Duration>
+where
+    F: Fn(f64) -> f64,
+{
+    let mut cumulative = 0.0;
+    for day in 0..MAX_PROJECTION_DAYS {
+        let rewards = daily_rewards(f64::from(day));
+        if cumulative + rewards >= target {
+            let fraction = (target - cumulative) / rewards;
+            return Some(DAY.mul_f64(f64::from(day) + fraction));
+        }
+        cumulative += rewards;
+    }
+
+    None
+}
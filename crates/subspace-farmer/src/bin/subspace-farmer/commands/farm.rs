@@ -1,61 +1,42 @@
-mod dsn;
 mod metrics;
 
-use crate::commands::farm::dsn::configure_dsn;
 use crate::commands::farm::metrics::{FarmerMetrics, SectorState};
 use crate::utils::shutdown_signal;
 use anyhow::anyhow;
 use bytesize::ByteSize;
 use clap::{Parser, ValueHint};
-use futures::channel::oneshot;
-use futures::stream::{FuturesOrdered, FuturesUnordered};
 use futures::{FutureExt, StreamExt};
-use parking_lot::Mutex;
 use prometheus_client::registry::Registry;
 use std::fs;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::num::{NonZeroU8, NonZeroUsize};
 use std::path::PathBuf;
-use std::pin::pin;
 use std::str::FromStr;
-use std::sync::Arc;
-use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
-use subspace_core_primitives::{PublicKey, Record, SectorIndex};
-use subspace_erasure_coding::ErasureCoding;
-use subspace_farmer::farmer_cache::FarmerCache;
+use std::time::Duration;
+use subspace_core_primitives::PublicKey;
+use subspace_farmer::farmer::{
+    should_farm_during_initial_plotting, DsnOptions, FarmOptions, Farmer, FarmerError, FarmerEvent,
+};
+use subspace_farmer::monitoring::FarmMonitor;
 use subspace_farmer::single_disk_farm::farming::FarmingNotification;
+use subspace_farmer::single_disk_farm::plot_encryption::PlotEncryption;
 use subspace_farmer::single_disk_farm::{
-    SectorExpirationDetails, SectorPlottingDetails, SectorUpdate, SingleDiskFarm,
-    SingleDiskFarmError, SingleDiskFarmOptions,
+    SectorExpirationDetails, SectorPlottingDetails, SectorUpdate, SingleDiskFarmError,
 };
-use subspace_farmer::utils::farmer_piece_getter::FarmerPieceGetter;
-use subspace_farmer::utils::piece_validator::SegmentCommitmentPieceValidator;
-use subspace_farmer::utils::plotted_pieces::PlottedPieces;
 use subspace_farmer::utils::ss58::parse_ss58_reward_address;
-use subspace_farmer::utils::{
-    all_cpu_cores, create_plotting_thread_pool_manager, parse_cpu_cores_sets,
-    recommended_number_of_farming_threads, run_future_in_dedicated_thread,
-    thread_pool_core_indices, AsyncJoinOnDrop, CpuCoreSet,
-};
-use subspace_farmer::{Identity, NodeClient, NodeRpcClient};
-use subspace_farmer_components::plotting::PlottedSector;
+use subspace_farmer::utils::AsyncJoinOnDrop;
 use subspace_metrics::{start_prometheus_metrics_server, RegistryAdapter};
-use subspace_networking::libp2p::identity::{ed25519, Keypair};
 use subspace_networking::libp2p::multiaddr::Protocol;
-use subspace_networking::libp2p::Multiaddr;
-use subspace_networking::utils::piece_provider::PieceProvider;
+use subspace_networking::libp2p::{Multiaddr, PeerId};
+use subspace_networking::utils::piece_request_tickets::{
+    DEFAULT_PIECE_REQUEST_TICKET_DIFFICULTY, MAX_PIECE_REQUEST_TICKET_DIFFICULTY,
+};
 use subspace_proof_of_space::Table;
-use tokio::sync::Semaphore;
-use tracing::{debug, error, info, info_span, warn};
+use tracing::info;
 use zeroize::Zeroizing;
 
-fn should_farm_during_initial_plotting() -> bool {
-    let total_cpu_cores = all_cpu_cores()
-        .iter()
-        .flat_map(|set| set.cpu_cores())
-        .count();
-    total_cpu_cores > 8
-}
+/// Interval with which farm summaries are published to monitoring server
+const MONITORING_REPORT_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Arguments for farmer
 #[derive(Debug, Parser)]
@@ -73,10 +54,29 @@ pub(crate) struct FarmingArgs {
     /// `size` is max allocated size in human readable format (e.g. 10GB, 2TiB) or just bytes that
     /// farmer will make sure not not exceed (and will pre-allocated all the space on startup to
     /// ensure it will not run out of space in runtime).
+    ///
+    /// Optionally `prefetch=true` can be added to issue read-ahead for audit of the next slot while
+    /// previous slot is being proven, which improves audit latency on high-latency disks:
+    ///
+    ///   path=/path/to/directory,size=5T,prefetch=true
+    ///
+    /// Optionally `encryption=identity` or `encryption=file:/path/to/key` can be added to encrypt
+    /// plot at rest with a key derived from farmer identity or from key material stored in a file
+    /// (for example provided by OS keystore), encryption can only be enabled when farm is created:
+    ///
+    ///   path=/path/to/directory,size=5T,encryption=identity
     disk_farms: Vec<DiskFarm>,
     /// WebSocket RPC URL of the Subspace node to connect to
     #[arg(long, value_hint = ValueHint::Url, default_value = "ws://127.0.0.1:9944")]
     node_rpc_url: String,
+    /// WebSocket RPC URL of a trusted node (for example public RPC node) to relay slot info and
+    /// archived history from while the local node is syncing, allows to start plotting and farming
+    /// without waiting for local node to sync.
+    ///
+    /// Farmer relies on this node to provide correct information until local node catches up,
+    /// solutions are only accepted by the trusted node if it exposes unsafe RPC methods.
+    #[arg(long, value_hint = ValueHint::Url)]
+    trusted_node_rpc_url: Option<String>,
     /// Address for farming rewards
     #[arg(long, value_parser = parse_ss58_reward_address)]
     reward_address: PublicKey,
@@ -103,6 +103,9 @@ pub(crate) struct FarmingArgs {
     /// DSN parameters
     #[clap(flatten)]
     dsn: DsnArgs,
+    /// Remote monitoring parameters
+    #[clap(flatten)]
+    monitoring: MonitoringArgs,
     /// Do not print info about configured farms on startup
     #[arg(long)]
     no_info: bool,
@@ -184,6 +187,10 @@ pub(crate) struct FarmingArgs {
     /// each with a pair of CPU cores.
     #[arg(long, conflicts_with_all = &["sector_encoding_concurrency", "replotting_thread_pool_size"])]
     replotting_cpu_cores: Option<String>,
+    /// URL of remote plotter (see `plotter` command) to request plotted sectors from instead of
+    /// plotting them locally, for instance `http://192.168.1.10:9966`
+    #[arg(long, value_hint = ValueHint::Url)]
+    remote_plotter_url: Option<String>,
     /// Disable farm locking, for example if file system doesn't support it
     #[arg(long)]
     disable_farm_locking: bool,
@@ -199,6 +206,22 @@ fn cache_percentage_parser(s: &str) -> anyhow::Result<NonZeroU8> {
     Ok(cache_percentage)
 }
 
+/// Arguments for remote monitoring
+#[derive(Debug, Parser)]
+struct MonitoringArgs {
+    /// URL of monitoring server (see `monitoring-server` command) to periodically publish farm
+    /// summaries to, for instance `http://192.168.1.10:9955`
+    #[arg(long, requires_all = ["monitoring_token", "monitoring_farmer_name"])]
+    monitoring_url: Option<String>,
+    /// Token shared with monitoring server, used to authenticate published reports
+    #[arg(long)]
+    monitoring_token: Option<String>,
+    /// Name of this farmer in monitoring server, must be unique among farmers reporting to the
+    /// same monitoring server
+    #[arg(long)]
+    monitoring_farmer_name: Option<String>,
+}
+
 /// Arguments for DSN
 #[derive(Debug, Parser)]
 struct DsnArgs {
@@ -245,6 +268,65 @@ struct DsnArgs {
     /// Defines whether we should run blocking Kademlia bootstrap() operation before other requests.
     #[arg(long, default_value_t = false)]
     disable_bootstrap_on_start: bool,
+    /// Client-only DSN operation for resource-constrained machines: farmer doesn't listen for
+    /// incoming connections (`--listen-on` is ignored) and doesn't serve pieces to other peers,
+    /// while piece retrieval for plotting works as usual.
+    #[arg(long, default_value_t = false)]
+    client_only: bool,
+    /// Difficulty of tickets attached to outgoing piece requests and required from incoming piece
+    /// requests to be served with priority when anonymous requests are limited, at most 24.
+    #[arg(
+        long,
+        default_value_t = DEFAULT_PIECE_REQUEST_TICKET_DIFFICULTY,
+        value_parser = clap::value_parser!(u8).range(..=i64::from(MAX_PIECE_REQUEST_TICKET_DIFFICULTY)),
+    )]
+    piece_request_ticket_difficulty: u8,
+    /// Max number of piece requests per minute without valid ticket that will be served (shared by
+    /// all peers), protects against bulk scrapers, not limited by default.
+    #[arg(long)]
+    anonymous_piece_requests_limit: Option<u32>,
+    /// Peers whose piece requests are always served regardless of tickets (for instance own
+    /// plotting nodes or gateways), reserved peers are always trusted, multiple are supported.
+    #[arg(long)]
+    trusted_piece_requesters: Vec<PeerId>,
+}
+
+impl From<DsnArgs> for DsnOptions {
+    fn from(dsn_args: DsnArgs) -> Self {
+        let DsnArgs {
+            bootstrap_nodes,
+            listen_on,
+            allow_private_ips,
+            reserved_peers,
+            in_connections,
+            out_connections,
+            pending_in_connections,
+            pending_out_connections,
+            external_addresses,
+            disable_bootstrap_on_start,
+            client_only,
+            piece_request_ticket_difficulty,
+            anonymous_piece_requests_limit,
+            trusted_piece_requesters,
+        } = dsn_args;
+
+        Self {
+            bootstrap_nodes,
+            listen_on,
+            allow_private_ips,
+            reserved_peers,
+            in_connections,
+            out_connections,
+            pending_in_connections,
+            pending_out_connections,
+            external_addresses,
+            disable_bootstrap_on_start,
+            client_only,
+            piece_request_ticket_difficulty,
+            anonymous_piece_requests_limit,
+            trusted_piece_requesters,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -253,6 +335,42 @@ pub(crate) struct DiskFarm {
     directory: PathBuf,
     /// How much space in bytes can farm use for plots (metadata space is not included)
     allocated_plotting_space: u64,
+    /// Whether to issue read-ahead for audit of the next slot
+    audit_prefetch: bool,
+    /// Plot encryption, if enabled
+    encryption: Option<DiskFarmEncryption>,
+}
+
+/// Source of plot encryption key
+#[derive(Debug, Clone)]
+enum DiskFarmEncryption {
+    /// Key derived from farmer identity
+    Identity,
+    /// Key material stored in a file
+    KeyFile(PathBuf),
+}
+
+impl DiskFarmEncryption {
+    fn plot_encryption(&self) -> anyhow::Result<PlotEncryption> {
+        Ok(match self {
+            Self::Identity => PlotEncryption::Identity,
+            Self::KeyFile(path) => {
+                let key_material = Zeroizing::new(fs::read(path).map_err(|error| {
+                    anyhow!(
+                        "Failed to read plot encryption key from {}: {error}",
+                        path.display()
+                    )
+                })?);
+                if key_material.is_empty() {
+                    return Err(anyhow!(
+                        "Plot encryption key file {} is empty",
+                        path.display()
+                    ));
+                }
+                PlotEncryption::External(key_material)
+            }
+        })
+    }
 }
 
 impl FromStr for DiskFarm {
@@ -260,12 +378,14 @@ impl FromStr for DiskFarm {
 
     fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
         let parts = s.split(',').collect::<Vec<_>>();
-        if parts.len() != 2 {
-            return Err("Must contain 2 coma-separated components".to_string());
+        if !(2..=4).contains(&parts.len()) {
+            return Err("Must contain 2 to 4 coma-separated components".to_string());
         }
 
         let mut plot_directory = None;
         let mut allocated_plotting_space = None;
+        let mut audit_prefetch = false;
+        let mut encryption = None;
 
         for part in parts {
             let part = part.splitn(2, '=').collect::<Vec<_>>();
@@ -290,9 +410,31 @@ impl FromStr for DiskFarm {
                             .as_u64(),
                     );
                 }
+                "prefetch" => {
+                    audit_prefetch = value.parse::<bool>().map_err(|error| {
+                        format!("Failed to parse `prefetch` \"{value}\": {error}")
+                    })?;
+                }
+                "encryption" => {
+                    encryption.replace(match value {
+                        "identity" => DiskFarmEncryption::Identity,
+                        value => match value.strip_prefix("file:") {
+                            Some(path) if !path.is_empty() => {
+                                DiskFarmEncryption::KeyFile(PathBuf::from(path))
+                            }
+                            _ => {
+                                return Err(format!(
+                                    "Failed to parse `encryption` \"{value}\": must be \
+                                    `identity` or `file:/path/to/key`"
+                                ));
+                            }
+                        },
+                    });
+                }
                 key => {
                     return Err(format!(
-                        "Key \"{key}\" is not supported, only `path` or `size`"
+                        "Key \"{key}\" is not supported, only `path`, `size`, `prefetch` or \
+                        `encryption`"
                     ));
                 }
             }
@@ -305,6 +447,8 @@ impl FromStr for DiskFarm {
             allocated_plotting_space: allocated_plotting_space.ok_or({
                 "`size` key is required with path to directory where plots will be stored"
             })?,
+            audit_prefetch,
+            encryption,
         })
     }
 }
@@ -319,9 +463,11 @@ where
 
     let FarmingArgs {
         node_rpc_url,
+        trusted_node_rpc_url,
         reward_address,
         max_pieces_in_sector,
         mut dsn,
+        monitoring,
         cache_percentage,
         no_info,
         dev,
@@ -337,6 +483,7 @@ where
         plotting_cpu_cores,
         replotting_thread_pool_size,
         replotting_cpu_cores,
+        remote_plotter_url,
         disable_farm_locking,
     } = farming_args;
 
@@ -352,70 +499,111 @@ where
         disk_farms = vec![DiskFarm {
             directory: tmp_directory.as_ref().to_path_buf(),
             allocated_plotting_space: plot_size.as_u64(),
+            audit_prefetch: false,
+            encryption: None,
         }];
 
         Some(tmp_directory)
     } else {
-        if disk_farms.is_empty() {
-            return Err(anyhow!("There must be at least one disk farm provided"));
-        }
-
-        for farm in &disk_farms {
-            if !farm.directory.exists() {
-                if let Err(error) = fs::create_dir(&farm.directory) {
-                    return Err(anyhow!(
-                        "Directory {} doesn't exist and can't be created: {}",
-                        farm.directory.display(),
-                        error
-                    ));
-                }
-            }
-        }
         None
     };
 
-    let plotted_pieces = Arc::new(Mutex::new(None));
-
-    info!(url = %node_rpc_url, "Connecting to node RPC");
-    let node_client = NodeRpcClient::new(&node_rpc_url).await?;
-
-    let farmer_app_info = node_client
-        .farmer_app_info()
-        .await
-        .map_err(|error| anyhow::anyhow!(error))?;
-
-    let first_farm_directory = &disk_farms
-        .first()
-        .expect("Disk farm collection is not be empty as checked above; qed")
-        .directory;
-
-    let identity = Identity::open_or_create(first_farm_directory)
-        .map_err(|error| anyhow!("Failed to open or create identity: {error}"))?;
-    let keypair = derive_libp2p_keypair(identity.secret_key());
-    let peer_id = keypair.public().to_peer_id();
-
-    let (farmer_cache, farmer_cache_worker) = FarmerCache::new(node_client.clone(), peer_id);
+    let disk_farm_directories = disk_farms
+        .iter()
+        .map(|disk_farm| disk_farm.directory.clone())
+        .collect::<Vec<_>>();
+    let mut farmer_builder = Farmer::builder(node_rpc_url, reward_address)
+        .with_cache_percentage(cache_percentage)
+        .with_dsn(dsn.into())
+        .with_farm_during_initial_plotting(farm_during_initial_plotting);
+    for disk_farm in disk_farms {
+        let plot_encryption = disk_farm
+            .encryption
+            .as_ref()
+            .map(DiskFarmEncryption::plot_encryption)
+            .transpose()?;
+
+        farmer_builder = farmer_builder.with_farm(FarmOptions {
+            directory: disk_farm.directory,
+            allocated_space: disk_farm.allocated_plotting_space,
+            audit_prefetch: disk_farm.audit_prefetch,
+            plot_encryption,
+        });
+    }
+    if let Some(trusted_node_rpc_url) = trusted_node_rpc_url {
+        farmer_builder = farmer_builder.with_trusted_node_rpc_url(trusted_node_rpc_url);
+    }
+    if let Some(max_pieces_in_sector) = max_pieces_in_sector {
+        farmer_builder = farmer_builder.with_max_pieces_in_sector(max_pieces_in_sector);
+    }
+    if let Some(sector_downloading_concurrency) = sector_downloading_concurrency {
+        farmer_builder =
+            farmer_builder.with_sector_downloading_concurrency(sector_downloading_concurrency);
+    }
+    if let Some(sector_encoding_concurrency) = sector_encoding_concurrency {
+        farmer_builder =
+            farmer_builder.with_sector_encoding_concurrency(sector_encoding_concurrency);
+    }
+    if let Some(record_encoding_concurrency) = record_encoding_concurrency {
+        farmer_builder =
+            farmer_builder.with_record_encoding_concurrency(record_encoding_concurrency);
+    }
+    if let Some(farming_thread_pool_size) = farming_thread_pool_size {
+        farmer_builder = farmer_builder.with_farming_thread_pool_size(farming_thread_pool_size);
+    }
+    if let Some(plotting_thread_pool_size) = plotting_thread_pool_size {
+        farmer_builder = farmer_builder.with_plotting_thread_pool_size(plotting_thread_pool_size);
+    }
+    if let Some(plotting_cpu_cores) = plotting_cpu_cores {
+        farmer_builder = farmer_builder.with_plotting_cpu_cores(plotting_cpu_cores);
+    }
+    if let Some(replotting_thread_pool_size) = replotting_thread_pool_size {
+        farmer_builder =
+            farmer_builder.with_replotting_thread_pool_size(replotting_thread_pool_size);
+    }
+    if let Some(replotting_cpu_cores) = replotting_cpu_cores {
+        farmer_builder = farmer_builder.with_replotting_cpu_cores(replotting_cpu_cores);
+    }
+    if let Some(remote_plotter_url) = remote_plotter_url {
+        farmer_builder = farmer_builder.with_remote_plotter_url(remote_plotter_url);
+    }
+    if disable_farm_locking {
+        farmer_builder = farmer_builder.with_farm_locking_disabled();
+    }
 
     // Metrics
     let mut prometheus_metrics_registry = Registry::default();
     let farmer_metrics = FarmerMetrics::new(&mut prometheus_metrics_registry);
     let should_start_prometheus_server = !prometheus_listen_on.is_empty();
 
-    let (node, mut node_runner) = {
-        if dsn.bootstrap_nodes.is_empty() {
-            dsn.bootstrap_nodes = farmer_app_info.dsn_bootstrap_nodes.clone();
-        }
-
-        configure_dsn(
-            hex::encode(farmer_app_info.genesis_hash),
-            first_farm_directory,
-            keypair,
-            dsn,
-            Arc::downgrade(&plotted_pieces),
-            node_client.clone(),
-            farmer_cache.clone(),
+    let farmer = match farmer_builder
+        .build::<PosTable>(
             should_start_prometheus_server.then_some(&mut prometheus_metrics_registry),
-        )?
+        )
+        .await
+    {
+        Ok(farmer) => farmer,
+        Err(FarmerError::SingleDiskFarm {
+            error:
+                SingleDiskFarmError::InsufficientAllocatedSpace {
+                    min_space,
+                    allocated_space,
+                },
+            ..
+        }) => {
+            return Err(anyhow!(
+                "Allocated space {} ({}) is not enough, minimum is ~{} (~{}, {} bytes to be \
+                exact)",
+                bytesize::to_string(allocated_space, true),
+                bytesize::to_string(allocated_space, false),
+                bytesize::to_string(min_space, true),
+                bytesize::to_string(min_space, false),
+                min_space
+            ));
+        }
+        Err(error) => {
+            return Err(error.into());
+        }
     };
 
     let _prometheus_worker = if should_start_prometheus_server {
@@ -430,185 +618,18 @@ where
         None
     };
 
-    let kzg = Kzg::new(embedded_kzg_settings());
-    let erasure_coding = ErasureCoding::new(
-        NonZeroUsize::new(Record::NUM_S_BUCKETS.next_power_of_two().ilog2() as usize)
-            .expect("Not zero; qed"),
-    )
-    .map_err(|error| anyhow::anyhow!(error))?;
-    let validator = Some(SegmentCommitmentPieceValidator::new(
-        node.clone(),
-        node_client.clone(),
-        kzg.clone(),
-    ));
-    let piece_provider = PieceProvider::new(node.clone(), validator.clone());
-
-    let piece_getter = FarmerPieceGetter::new(
-        piece_provider,
-        farmer_cache.clone(),
-        node_client.clone(),
-        Arc::clone(&plotted_pieces),
-    );
-
-    let farmer_cache_worker_fut = run_future_in_dedicated_thread(
-        {
-            let future = farmer_cache_worker.run(piece_getter.downgrade());
-
-            move || future
-        },
-        "farmer-cache-worker".to_string(),
-    )?;
-
-    let mut single_disk_farms = Vec::with_capacity(disk_farms.len());
-    let max_pieces_in_sector = match max_pieces_in_sector {
-        Some(max_pieces_in_sector) => {
-            if max_pieces_in_sector > farmer_app_info.protocol_info.max_pieces_in_sector {
-                warn!(
-                    protocol_value = farmer_app_info.protocol_info.max_pieces_in_sector,
-                    desired_value = max_pieces_in_sector,
-                    "Can't set max pieces in sector higher than protocol value, using protocol \
-                    value"
-                );
-
-                farmer_app_info.protocol_info.max_pieces_in_sector
-            } else {
-                max_pieces_in_sector
-            }
-        }
-        None => farmer_app_info.protocol_info.max_pieces_in_sector,
-    };
-
-    let mut plotting_thread_pool_core_indices;
-    let mut replotting_thread_pool_core_indices;
-    if let Some(plotting_cpu_cores) = plotting_cpu_cores {
-        plotting_thread_pool_core_indices = parse_cpu_cores_sets(&plotting_cpu_cores)
-            .map_err(|error| anyhow::anyhow!("Failed to parse `--plotting-cpu-cores`: {error}"))?;
-        replotting_thread_pool_core_indices = match replotting_cpu_cores {
-            Some(replotting_cpu_cores) => {
-                parse_cpu_cores_sets(&replotting_cpu_cores).map_err(|error| {
-                    anyhow::anyhow!("Failed to parse `--replotting-cpu-cores`: {error}")
-                })?
-            }
-            None => plotting_thread_pool_core_indices.clone(),
-        };
-        if plotting_thread_pool_core_indices.len() != replotting_thread_pool_core_indices.len() {
-            return Err(anyhow::anyhow!(
-                "Number of plotting thread pools ({}) is not the same as for replotting ({})",
-                plotting_thread_pool_core_indices.len(),
-                replotting_thread_pool_core_indices.len()
-            ));
-        }
-    } else {
-        plotting_thread_pool_core_indices =
-            thread_pool_core_indices(plotting_thread_pool_size, sector_encoding_concurrency);
-        replotting_thread_pool_core_indices = {
-            let mut replotting_thread_pool_core_indices =
-                thread_pool_core_indices(replotting_thread_pool_size, sector_encoding_concurrency);
-            if replotting_thread_pool_size.is_none() {
-                // The default behavior is to use all CPU cores, but for replotting we just want half
-                replotting_thread_pool_core_indices
-                    .iter_mut()
-                    .for_each(|set| set.truncate(set.cpu_cores().len() / 2));
-            }
-            replotting_thread_pool_core_indices
-        };
-
-        if plotting_thread_pool_core_indices.len() > 1 {
-            info!(
-                l3_cache_groups = %plotting_thread_pool_core_indices.len(),
-                "Multiple L3 cache groups detected"
-            );
-
-            if plotting_thread_pool_core_indices.len() > disk_farms.len() {
-                plotting_thread_pool_core_indices =
-                    CpuCoreSet::regroup(&plotting_thread_pool_core_indices, disk_farms.len());
-                replotting_thread_pool_core_indices =
-                    CpuCoreSet::regroup(&replotting_thread_pool_core_indices, disk_farms.len());
-
-                info!(
-                    farms_count = %disk_farms.len(),
-                    "Regrouped CPU cores to match number of farms, more farms may leverage CPU more efficiently"
-                );
-            }
-        }
-    }
-
-    let downloading_semaphore = Arc::new(Semaphore::new(
-        sector_downloading_concurrency
-            .map(|sector_downloading_concurrency| sector_downloading_concurrency.get())
-            .unwrap_or(plotting_thread_pool_core_indices.len() + 1),
-    ));
-
-    let record_encoding_concurrency = record_encoding_concurrency.unwrap_or_else(|| {
-        let cpu_cores = plotting_thread_pool_core_indices
-            .first()
-            .expect("Guaranteed to have some CPU cores; qed");
-
-        NonZeroUsize::new((cpu_cores.cpu_cores().len() / 2).min(8))
-            .expect("Guaranteed to have some CPU cores; qed")
-    });
-
-    let plotting_thread_pool_manager = create_plotting_thread_pool_manager(
-        plotting_thread_pool_core_indices
-            .into_iter()
-            .zip(replotting_thread_pool_core_indices),
-    )?;
-    let farming_thread_pool_size = farming_thread_pool_size
-        .map(|farming_thread_pool_size| farming_thread_pool_size.get())
-        .unwrap_or_else(recommended_number_of_farming_threads);
-
-    let mut plotting_delay_senders = Vec::with_capacity(disk_farms.len());
-
-    for (disk_farm_index, disk_farm) in disk_farms.into_iter().enumerate() {
-        debug!(url = %node_rpc_url, %disk_farm_index, "Connecting to node RPC");
-        let node_client = NodeRpcClient::new(&node_rpc_url).await?;
-        let (plotting_delay_sender, plotting_delay_receiver) = oneshot::channel();
-        plotting_delay_senders.push(plotting_delay_sender);
-
-        let single_disk_farm_fut = SingleDiskFarm::new::<_, _, PosTable>(
-            SingleDiskFarmOptions {
-                directory: disk_farm.directory.clone(),
-                farmer_app_info: farmer_app_info.clone(),
-                allocated_space: disk_farm.allocated_plotting_space,
-                max_pieces_in_sector,
-                node_client,
-                reward_address,
-                kzg: kzg.clone(),
-                erasure_coding: erasure_coding.clone(),
-                piece_getter: piece_getter.clone(),
-                cache_percentage,
-                downloading_semaphore: Arc::clone(&downloading_semaphore),
-                record_encoding_concurrency,
-                farm_during_initial_plotting,
-                farming_thread_pool_size,
-                plotting_thread_pool_manager: plotting_thread_pool_manager.clone(),
-                plotting_delay: Some(plotting_delay_receiver),
-                disable_farm_locking,
-            },
-            disk_farm_index,
-        );
-
-        let single_disk_farm = match single_disk_farm_fut.await {
-            Ok(single_disk_farm) => single_disk_farm,
-            Err(SingleDiskFarmError::InsufficientAllocatedSpace {
-                min_space,
-                allocated_space,
-            }) => {
-                return Err(anyhow::anyhow!(
-                    "Allocated space {} ({}) is not enough, minimum is ~{} (~{}, {} bytes to be \
-                    exact)",
-                    bytesize::to_string(allocated_space, true),
-                    bytesize::to_string(allocated_space, false),
-                    bytesize::to_string(min_space, true),
-                    bytesize::to_string(min_space, false),
-                    min_space
-                ));
-            }
-            Err(error) => {
-                return Err(error.into());
-            }
-        };
+    let farm_monitor = monitoring
+        .monitoring_farmer_name
+        .clone()
+        .filter(|_| monitoring.monitoring_url.is_some())
+        .map(FarmMonitor::new);
 
+    for (disk_farm_index, (single_disk_farm, directory)) in farmer
+        .single_disk_farms()
+        .iter()
+        .zip(&disk_farm_directories)
+        .enumerate()
+    {
         if !no_info {
             let info = single_disk_farm.info();
             println!("Single disk farm {disk_farm_index}:");
@@ -620,280 +641,141 @@ where
                 bytesize::to_string(info.allocated_space(), true),
                 bytesize::to_string(info.allocated_space(), false)
             );
-            println!("  Directory: {}", disk_farm.directory.display());
-        }
-
-        single_disk_farms.push(single_disk_farm);
-    }
-
-    let cache_acknowledgement_receiver = farmer_cache
-        .replace_backing_caches(
-            single_disk_farms
-                .iter()
-                .map(|single_disk_farm| single_disk_farm.piece_cache())
-                .collect(),
-        )
-        .await;
-    drop(farmer_cache);
-
-    // Wait for cache initialization before starting plotting
-    tokio::spawn(async move {
-        if cache_acknowledgement_receiver.await.is_ok() {
-            for plotting_delay_sender in plotting_delay_senders {
-                // Doesn't matter if receiver is gone
-                let _ = plotting_delay_sender.send(());
-            }
+            println!("  Directory: {}", directory.display());
         }
-    });
-
-    // Store piece readers so we can reference them later
-    let piece_readers = single_disk_farms
-        .iter()
-        .map(|single_disk_farm| single_disk_farm.piece_reader())
-        .collect::<Vec<_>>();
 
-    info!("Collecting already plotted pieces (this will take some time)...");
+        let total_sector_count = single_disk_farm.total_sectors_count();
+        let plotted_sectors_count = single_disk_farm.plotted_sectors_count().await;
 
-    // Collect already plotted pieces
-    {
-        let mut future_plotted_pieces = PlottedPieces::new(piece_readers);
-
-        for (disk_farm_index, single_disk_farm) in single_disk_farms.iter().enumerate() {
-            let disk_farm_index = disk_farm_index.try_into().map_err(|_error| {
-                anyhow!(
-                    "More than 256 plots are not supported, consider running multiple farmer \
-                    instances"
-                )
-            })?;
-
-            (0 as SectorIndex..)
-                .zip(single_disk_farm.plotted_sectors().await)
-                .for_each(
-                    |(sector_index, plotted_sector_result)| match plotted_sector_result {
-                        Ok(plotted_sector) => {
-                            future_plotted_pieces.add_sector(disk_farm_index, &plotted_sector);
-                        }
-                        Err(error) => {
-                            error!(
-                                %error,
-                                %disk_farm_index,
-                                %sector_index,
-                                "Failed reading plotted sector on startup, skipping"
-                            );
-                        }
-                    },
-                );
+        if let Some(farm_monitor) = &farm_monitor {
+            farm_monitor.add_farm(single_disk_farm, plotted_sectors_count);
         }
-
-        plotted_pieces.lock().replace(future_plotted_pieces);
+        farmer_metrics.update_sectors_total(
+            single_disk_farm.id(),
+            total_sector_count - plotted_sectors_count,
+            SectorState::NotPlotted,
+        );
+        farmer_metrics.update_sectors_total(
+            single_disk_farm.id(),
+            plotted_sectors_count,
+            SectorState::Plotted,
+        );
     }
 
-    info!("Finished collecting already plotted pieces successfully");
-
-    let total_and_plotted_sectors = single_disk_farms
-        .iter()
-        .map(|single_disk_farm| async {
-            let total_sector_count = single_disk_farm.total_sectors_count();
-            let plotted_sectors_count = single_disk_farm.plotted_sectors_count().await;
-
-            (total_sector_count, plotted_sectors_count)
-        })
-        .collect::<FuturesOrdered<_>>()
-        .collect::<Vec<_>>()
-        .await;
-
-    let mut single_disk_farms_stream = single_disk_farms
-        .into_iter()
-        .enumerate()
-        .zip(total_and_plotted_sectors)
-        .map(|((disk_farm_index, single_disk_farm), sector_counts)| {
-            let disk_farm_index = disk_farm_index.try_into().expect(
-                "More than 256 plots are not supported, this is checked above already; qed",
-            );
-            let plotted_pieces = Arc::clone(&plotted_pieces);
-            let span = info_span!("", %disk_farm_index);
-
-            // Collect newly plotted pieces
-            let on_plotted_sector_callback =
-                move |plotted_sector: &PlottedSector,
-                      maybe_old_plotted_sector: &Option<PlottedSector>| {
-                    let _span_guard = span.enter();
-
-                    {
-                        let mut plotted_pieces = plotted_pieces.lock();
-                        let plotted_pieces = plotted_pieces
-                            .as_mut()
-                            .expect("Initial value was populated above; qed");
-
-                        if let Some(old_plotted_sector) = &maybe_old_plotted_sector {
-                            plotted_pieces.delete_sector(disk_farm_index, old_plotted_sector);
-                        }
-                        plotted_pieces.add_sector(disk_farm_index, plotted_sector);
-                    }
-                };
-
-            let (total_sector_count, plotted_sectors_count) = sector_counts;
-            farmer_metrics.update_sectors_total(
-                single_disk_farm.id(),
-                total_sector_count - plotted_sectors_count,
-                SectorState::NotPlotted,
-            );
-            farmer_metrics.update_sectors_total(
-                single_disk_farm.id(),
-                plotted_sectors_count,
-                SectorState::Plotted,
-            );
-            single_disk_farm
-                .on_sector_update(Arc::new({
-                    let single_disk_farm_id = *single_disk_farm.id();
-                    let farmer_metrics = farmer_metrics.clone();
-
-                    move |(_sector_index, sector_state)| match sector_state {
-                        SectorUpdate::Plotting(SectorPlottingDetails::Starting { .. }) => {
-                            farmer_metrics.sector_plotting.inc();
-                        }
-                        SectorUpdate::Plotting(SectorPlottingDetails::Downloading) => {
-                            farmer_metrics.sector_downloading.inc();
-                        }
-                        SectorUpdate::Plotting(SectorPlottingDetails::Downloaded(time)) => {
-                            farmer_metrics
-                                .observe_sector_downloading_time(&single_disk_farm_id, time);
-                            farmer_metrics.sector_downloaded.inc();
-                        }
-                        SectorUpdate::Plotting(SectorPlottingDetails::Encoding) => {
-                            farmer_metrics.sector_encoding.inc();
-                        }
-                        SectorUpdate::Plotting(SectorPlottingDetails::Encoded(time)) => {
-                            farmer_metrics.observe_sector_encoding_time(&single_disk_farm_id, time);
-                            farmer_metrics.sector_encoded.inc();
-                        }
-                        SectorUpdate::Plotting(SectorPlottingDetails::Writing) => {
-                            farmer_metrics.sector_writing.inc();
-                        }
-                        SectorUpdate::Plotting(SectorPlottingDetails::Written(time)) => {
-                            farmer_metrics.observe_sector_writing_time(&single_disk_farm_id, time);
-                            farmer_metrics.sector_written.inc();
-                        }
-                        SectorUpdate::Plotting(SectorPlottingDetails::Finished {
-                            plotted_sector,
-                            old_plotted_sector,
-                            time,
-                        }) => {
-                            on_plotted_sector_callback(plotted_sector, old_plotted_sector);
-                            farmer_metrics.observe_sector_plotting_time(&single_disk_farm_id, time);
-                            farmer_metrics.sector_plotted.inc();
-                            farmer_metrics
-                                .update_sector_state(&single_disk_farm_id, SectorState::Plotted);
-                        }
-                        SectorUpdate::Expiration(SectorExpirationDetails::AboutToExpire) => {
-                            farmer_metrics.update_sector_state(
-                                &single_disk_farm_id,
-                                SectorState::AboutToExpire,
-                            );
-                        }
-                        SectorUpdate::Expiration(SectorExpirationDetails::Expired) => {
-                            farmer_metrics
-                                .update_sector_state(&single_disk_farm_id, SectorState::Expired);
-                        }
-                        SectorUpdate::Expiration(SectorExpirationDetails::Determined {
-                            ..
-                        }) => {
-                            // Not interested in here
-                        }
-                    }
-                }))
-                .detach();
-
-            single_disk_farm
-                .on_farming_notification(Arc::new({
-                    let single_disk_farm_id = *single_disk_farm.id();
-                    let farmer_metrics = farmer_metrics.clone();
-
-                    move |farming_notification| match farming_notification {
-                        FarmingNotification::Auditing(auditing_details) => {
-                            farmer_metrics.observe_auditing_time(
-                                &single_disk_farm_id,
-                                &auditing_details.time,
-                            );
-                        }
-                        FarmingNotification::Proving(proving_details) => {
-                            farmer_metrics.observe_proving_time(
-                                &single_disk_farm_id,
-                                &proving_details.time,
-                                proving_details.result,
-                            );
-                        }
-                        FarmingNotification::NonFatalError(error) => {
-                            farmer_metrics.note_farming_error(&single_disk_farm_id, error);
-                        }
-                    }
-                }))
-                .detach();
-
-            single_disk_farm.run()
-        })
-        .collect::<FuturesUnordered<_>>();
-
-    // Drop original instance such that the only remaining instances are in `SingleDiskFarm`
-    // event handlers
-    drop(plotted_pieces);
+    let _metrics_worker = {
+        let mut farmer_events = farmer.events();
 
-    let farm_fut = run_future_in_dedicated_thread(
-        move || async move {
-            while let Some(result) = single_disk_farms_stream.next().await {
-                let id = result?;
-
-                info!(%id, "Farm exited successfully");
+        let join_handle = tokio::spawn(async move {
+            while let Some(farmer_event) = farmer_events.next().await {
+                update_metrics(&farmer_metrics, &farmer_event);
             }
-            anyhow::Ok(())
-        },
-        "farmer-farm".to_string(),
-    )?;
+        });
+        AsyncJoinOnDrop::new(join_handle, true)
+    };
 
-    let networking_fut = run_future_in_dedicated_thread(
-        move || async move { node_runner.run().await },
-        "farmer-networking".to_string(),
-    )?;
+    let _monitoring_worker = farm_monitor
+        .zip(monitoring.monitoring_url)
+        .zip(monitoring.monitoring_token)
+        .map(|((farm_monitor, monitoring_url), monitoring_token)| {
+            info!(url = %monitoring_url, "Publishing farm summaries to monitoring server");
 
-    // This defines order in which things are dropped
-    let networking_fut = networking_fut;
-    let farm_fut = farm_fut;
-    let farmer_cache_worker_fut = farmer_cache_worker_fut;
+            let join_handle = tokio::spawn(farm_monitor.run_publisher(
+                monitoring_url,
+                monitoring_token,
+                MONITORING_REPORT_INTERVAL,
+            ));
+            AsyncJoinOnDrop::new(join_handle, true)
+        });
 
-    let networking_fut = pin!(networking_fut);
-    let farm_fut = pin!(farm_fut);
-    let farmer_cache_worker_fut = pin!(farmer_cache_worker_fut);
+    let mut farmer_handle = farmer.start()?;
 
-    futures::select!(
+    let maybe_result = futures::select!(
         // Signal future
-        _ = signal.fuse() => {},
+        _ = signal.fuse() => None,
 
-        // Networking future
-        _ = networking_fut.fuse() => {
-            info!("Node runner exited.")
-        },
-
-        // Farm future
-        result = farm_fut.fuse() => {
-            result??;
-        },
-
-        // Piece cache worker future
-        _ = farmer_cache_worker_fut.fuse() => {
-            info!("Farmer cache worker exited.")
-        },
+        // Farmer future
+        result = farmer_handle.wait().fuse() => Some(result),
     );
 
+    match maybe_result {
+        Some(result) => result?,
+        None => farmer_handle.stop().await?,
+    }
+
     anyhow::Ok(())
 }
 
-fn derive_libp2p_keypair(schnorrkel_sk: &schnorrkel::SecretKey) -> Keypair {
-    let mut secret_bytes = Zeroizing::new(schnorrkel_sk.to_ed25519_bytes());
-
-    let keypair = ed25519::Keypair::from(
-        ed25519::SecretKey::try_from_bytes(&mut secret_bytes.as_mut()[..32])
-            .expect("Secret key is exactly 32 bytes in size; qed"),
-    );
-
-    Keypair::from(keypair)
+fn update_metrics(farmer_metrics: &FarmerMetrics, farmer_event: &FarmerEvent) {
+    match farmer_event {
+        FarmerEvent::SectorUpdate {
+            farm_id, update, ..
+        } => match update {
+            SectorUpdate::Plotting(SectorPlottingDetails::Starting { .. }) => {
+                farmer_metrics.sector_plotting.inc();
+            }
+            SectorUpdate::Plotting(SectorPlottingDetails::Downloading) => {
+                farmer_metrics.sector_downloading.inc();
+            }
+            SectorUpdate::Plotting(SectorPlottingDetails::Downloaded(time)) => {
+                farmer_metrics.observe_sector_downloading_time(farm_id, time);
+                farmer_metrics.sector_downloaded.inc();
+            }
+            SectorUpdate::Plotting(SectorPlottingDetails::Encoding) => {
+                farmer_metrics.sector_encoding.inc();
+            }
+            SectorUpdate::Plotting(SectorPlottingDetails::Encoded(time)) => {
+                farmer_metrics.observe_sector_encoding_time(farm_id, time);
+                farmer_metrics.sector_encoded.inc();
+            }
+            SectorUpdate::Plotting(SectorPlottingDetails::Writing) => {
+                farmer_metrics.sector_writing.inc();
+            }
+            SectorUpdate::Plotting(SectorPlottingDetails::Written(time)) => {
+                farmer_metrics.observe_sector_writing_time(farm_id, time);
+                farmer_metrics.sector_written.inc();
+            }
+            SectorUpdate::Plotting(SectorPlottingDetails::Finished { time, .. }) => {
+                farmer_metrics.observe_sector_plotting_time(farm_id, time);
+                farmer_metrics.sector_plotted.inc();
+                farmer_metrics.update_sector_state(farm_id, SectorState::Plotted);
+            }
+            SectorUpdate::Expiration(SectorExpirationDetails::AboutToExpire) => {
+                farmer_metrics.update_sector_state(farm_id, SectorState::AboutToExpire);
+            }
+            SectorUpdate::Expiration(SectorExpirationDetails::Expired) => {
+                farmer_metrics.update_sector_state(farm_id, SectorState::Expired);
+            }
+            SectorUpdate::Quarantined => {
+                farmer_metrics.update_sector_state(farm_id, SectorState::Quarantined);
+            }
+            SectorUpdate::Expiration(SectorExpirationDetails::Determined { .. }) => {
+                // Not interested in here
+            }
+        },
+        FarmerEvent::FarmingNotification {
+            farm_id,
+            notification,
+            ..
+        } => match notification {
+            FarmingNotification::Auditing(auditing_details) => {
+                farmer_metrics.observe_auditing_time(farm_id, &auditing_details.time);
+            }
+            FarmingNotification::Proving(proving_details) => {
+                farmer_metrics.observe_proving_time(
+                    farm_id,
+                    &proving_details.time,
+                    proving_details.result,
+                );
+            }
+            FarmingNotification::ClockJump(_clock_jump_details) => {
+                farmer_metrics.note_clock_jump(farm_id);
+            }
+            FarmingNotification::NonFatalError(error) => {
+                farmer_metrics.note_farming_error(farm_id, error);
+            }
+        },
+        FarmerEvent::Solution { .. } | FarmerEvent::FarmExited { .. } => {
+            // Not interested in here
+        }
+    }
 }
@@ -9,6 +9,7 @@ use std::time::Duration;
 use subspace_core_primitives::SectorIndex;
 use subspace_farmer::single_disk_farm::farming::ProvingResult;
 use subspace_farmer::single_disk_farm::{FarmingError, SingleDiskFarmId};
+use subspace_metrics::{MetricsComponent, TraceExemplar, TracedHistogram};
 
 #[derive(Debug, Copy, Clone)]
 pub(super) enum SectorState {
@@ -16,6 +17,7 @@ pub(super) enum SectorState {
     Plotted,
     AboutToExpire,
     Expired,
+    Quarantined,
 }
 
 impl fmt::Display for SectorState {
@@ -25,15 +27,17 @@ impl fmt::Display for SectorState {
             Self::Plotted => "Plotted",
             Self::AboutToExpire => "AboutToExpire",
             Self::Expired => "Expired",
+            Self::Quarantined => "Quarantined",
         })
     }
 }
 
 #[derive(Debug, Clone)]
 pub(super) struct FarmerMetrics {
-    auditing_time: Family<Vec<(String, String)>, Histogram>,
-    proving_time: Family<Vec<(String, String)>, Histogram>,
+    auditing_time: Family<Vec<(String, String)>, TracedHistogram>,
+    proving_time: Family<Vec<(String, String)>, TracedHistogram>,
     farming_errors: Family<Vec<(String, String)>, Counter<u64, AtomicU64>>,
+    clock_jumps: Family<Vec<(String, String)>, Counter<u64, AtomicU64>>,
     sector_downloading_time: Family<Vec<(String, String)>, Histogram>,
     sector_encoding_time: Family<Vec<(String, String)>, Histogram>,
     sector_writing_time: Family<Vec<(String, String)>, Histogram>,
@@ -51,10 +55,10 @@ pub(super) struct FarmerMetrics {
 
 impl FarmerMetrics {
     pub(super) fn new(registry: &mut Registry) -> Self {
-        let sub_registry = registry.sub_registry_with_prefix("subspace_farmer");
+        let sub_registry = MetricsComponent::Farmer.sub_registry(registry);
 
         let auditing_time = Family::<_, _>::new_with_constructor(|| {
-            Histogram::new(exponential_buckets(0.0002, 2.0, 15))
+            TracedHistogram::new(exponential_buckets(0.0002, 2.0, 15))
         });
 
         sub_registry.register_with_unit(
@@ -65,7 +69,7 @@ impl FarmerMetrics {
         );
 
         let proving_time = Family::<_, _>::new_with_constructor(|| {
-            Histogram::new(exponential_buckets(0.0002, 2.0, 15))
+            TracedHistogram::new(exponential_buckets(0.0002, 2.0, 15))
         });
 
         sub_registry.register_with_unit(
@@ -83,6 +87,14 @@ impl FarmerMetrics {
             farming_errors.clone(),
         );
 
+        let clock_jumps = Family::<_, _>::new_with_constructor(Counter::<_, _>::default);
+
+        sub_registry.register(
+            "clock_jumps",
+            "System suspend/resume or clock jumps detected during farming",
+            clock_jumps.clone(),
+        );
+
         let sector_downloading_time = Family::<_, _>::new_with_constructor(|| {
             Histogram::new(exponential_buckets(0.1, 2.0, 15))
         });
@@ -212,6 +224,7 @@ impl FarmerMetrics {
             auditing_time,
             proving_time,
             farming_errors,
+            clock_jumps,
             sector_downloading_time,
             sector_encoding_time,
             sector_writing_time,
@@ -238,7 +251,7 @@ impl FarmerMetrics {
                 "farm_id".to_string(),
                 single_disk_farm_id.to_string(),
             )])
-            .observe(time.as_secs_f64());
+            .observe(time.as_secs_f64(), TraceExemplar::current());
     }
 
     pub(super) fn observe_proving_time(
@@ -252,7 +265,7 @@ impl FarmerMetrics {
                 ("farm_id".to_string(), single_disk_farm_id.to_string()),
                 ("result".to_string(), result.to_string()),
             ])
-            .observe(time.as_secs_f64());
+            .observe(time.as_secs_f64(), TraceExemplar::current());
     }
 
     pub(super) fn note_farming_error(
@@ -268,6 +281,15 @@ impl FarmerMetrics {
             .inc();
     }
 
+    pub(super) fn note_clock_jump(&self, single_disk_farm_id: &SingleDiskFarmId) {
+        self.clock_jumps
+            .get_or_create(&vec![(
+                "farm_id".to_string(),
+                single_disk_farm_id.to_string(),
+            )])
+            .inc();
+    }
+
     pub(super) fn update_sectors_total(
         &self,
         single_disk_farm_id: &SingleDiskFarmId,
@@ -322,6 +344,17 @@ impl FarmerMetrics {
                         return;
                     }
                 }
+                {
+                    let quarantined_sectors = self.sectors_total.get_or_create(&vec![
+                        ("farm_id".to_string(), single_disk_farm_id.to_string()),
+                        ("state".to_string(), SectorState::Quarantined.to_string()),
+                    ]);
+                    if quarantined_sectors.get() > 0 {
+                        // Replaced quarantined sector
+                        quarantined_sectors.dec();
+                        return;
+                    }
+                }
                 // Replaced about to expire sector
                 self.sectors_total
                     .get_or_create(&vec![
@@ -330,7 +363,7 @@ impl FarmerMetrics {
                     ])
                     .dec();
             }
-            SectorState::AboutToExpire | SectorState::Expired => {
+            SectorState::AboutToExpire | SectorState::Expired | SectorState::Quarantined => {
                 self.sectors_total
                     .get_or_create(&vec![
                         ("farm_id".to_string(), single_disk_farm_id.to_string()),
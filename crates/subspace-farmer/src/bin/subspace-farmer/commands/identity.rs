@@ -0,0 +1,94 @@
+use anyhow::anyhow;
+use clap::Subcommand;
+use std::io;
+use std::io::Write;
+use std::path::PathBuf;
+use subspace_farmer::Identity;
+use tracing::{info, warn};
+use zeroize::Zeroizing;
+
+/// Arguments for identity management
+#[derive(Debug, Subcommand)]
+pub(crate) enum IdentityArgs {
+    /// Restore farm identity from BIP39 mnemonic, mnemonic is read from standard input
+    ImportMnemonic {
+        /// Farm to restore identity for, must not have identity yet
+        ///
+        /// Example:
+        ///   /path/to/directory
+        disk_farm: PathBuf,
+        /// Optional derivation path consisting of hard junctions, allows to use different
+        /// identities derived from the same mnemonic for different farms
+        ///
+        /// Example:
+        ///   //farm//1
+        #[arg(long)]
+        derivation_path: Option<String>,
+    },
+    /// Print BIP39 mnemonic of the farm identity, so it can be backed up
+    ExportMnemonic {
+        /// Farm to export identity of
+        ///
+        /// Example:
+        ///   /path/to/directory
+        disk_farm: PathBuf,
+        /// Do not ask for confirmation before printing mnemonic
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+pub(crate) fn identity(identity_args: IdentityArgs) -> anyhow::Result<()> {
+    match identity_args {
+        IdentityArgs::ImportMnemonic {
+            disk_farm,
+            derivation_path,
+        } => import_mnemonic(disk_farm, derivation_path),
+        IdentityArgs::ExportMnemonic { disk_farm, yes } => export_mnemonic(disk_farm, yes),
+    }
+}
+
+fn import_mnemonic(disk_farm: PathBuf, derivation_path: Option<String>) -> anyhow::Result<()> {
+    if !disk_farm.exists() {
+        return Err(anyhow!("Directory {} doesn't exist", disk_farm.display()));
+    }
+
+    eprintln!("Enter mnemonic:");
+    let mut mnemonic = Zeroizing::new(String::new());
+    io::stdin().read_line(&mut mnemonic)?;
+
+    let identity = Identity::from_mnemonic(&disk_farm, mnemonic.trim(), derivation_path)?;
+
+    info!(
+        path = %disk_farm.display(),
+        public_key = %hex::encode(identity.public_key().to_bytes()),
+        "Identity restored from mnemonic"
+    );
+
+    Ok(())
+}
+
+fn export_mnemonic(disk_farm: PathBuf, yes: bool) -> anyhow::Result<()> {
+    let identity = Identity::open(&disk_farm)?
+        .ok_or_else(|| anyhow!("Identity not found in {}", disk_farm.display()))?;
+
+    if !yes {
+        warn!("⚠ Anyone with access to the mnemonic can take over farm identity and its rewards ⚠");
+        eprint!("Type `yes` to print mnemonic: ");
+        io::stderr().flush()?;
+
+        let mut confirmation = String::new();
+        io::stdin().read_line(&mut confirmation)?;
+        if confirmation.trim() != "yes" {
+            info!("Not confirmed, exiting");
+            return Ok(());
+        }
+    }
+
+    println!("{}", identity.mnemonic().as_str());
+    if let Some(derivation_path) = identity.derivation_path() {
+        println!("Derivation path: {derivation_path}");
+    }
+
+    Ok(())
+}
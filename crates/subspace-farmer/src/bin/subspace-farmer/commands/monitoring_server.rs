@@ -0,0 +1,41 @@
+use crate::utils::shutdown_signal;
+use anyhow::anyhow;
+use clap::Parser;
+use std::net::SocketAddr;
+use subspace_farmer::monitoring::{start_aggregation_server, MonitoringAggregator};
+use tracing::info;
+
+/// Arguments for monitoring server
+#[derive(Debug, Parser)]
+pub(crate) struct MonitoringServerArgs {
+    /// Address to listen on for reports from farmers and requests from dashboards
+    #[arg(long, default_value = "0.0.0.0:9955")]
+    listen_on: SocketAddr,
+    /// Token shared with farmers, reports that are not authenticated with it are rejected
+    #[arg(long)]
+    token: String,
+}
+
+/// Start server that aggregates farm summaries published by farmers (see `--monitoring-url` option
+/// of `farm` command), latest reports are available through `monitoring_farmerReports` JSON-RPC
+/// method
+pub(crate) async fn monitoring_server(
+    monitoring_server_args: MonitoringServerArgs,
+) -> anyhow::Result<()> {
+    let MonitoringServerArgs { listen_on, token } = monitoring_server_args;
+
+    let (address, server_handle) =
+        start_aggregation_server(listen_on, MonitoringAggregator::new(&token))
+            .await
+            .map_err(|error| anyhow!("Failed to start monitoring server: {error}"))?;
+
+    info!(%address, "Monitoring server started");
+
+    shutdown_signal().await;
+
+    server_handle
+        .stop()
+        .map_err(|error| anyhow!("Failed to stop monitoring server: {error}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use crate::utils::shutdown_signal;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::{Parser, ValueHint};
+use std::error::Error;
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::{Piece, PieceIndex, Record};
+use subspace_erasure_coding::ErasureCoding;
+use subspace_farmer::remote_plotter::{start_remote_plotter_server, RemotePlotter};
+use subspace_farmer::{NodeClient, NodeRpcClient};
+use subspace_farmer_components::{PieceGetter, PieceGetterRetryPolicy};
+use subspace_proof_of_space::Table;
+use tracing::info;
+
+/// Arguments for remote plotter
+#[derive(Debug, Parser)]
+pub(crate) struct PlotterArgs {
+    /// Address to listen on for requests from farmers
+    #[arg(long, default_value = "0.0.0.0:9966")]
+    listen_on: SocketAddr,
+    /// WebSocket RPC URL of the Subspace node to retrieve pieces from
+    #[arg(long, value_hint = ValueHint::Url, default_value = "ws://127.0.0.1:9944")]
+    node_rpc_url: String,
+    /// Defines how many sectors plotter will encode concurrently
+    #[arg(long, default_value = "1")]
+    sector_encoding_concurrency: NonZeroUsize,
+    /// Defines how many plotted sectors plotter will keep in memory while waiting for farmers to
+    /// download them, including sectors that are being plotted, further requests are rejected
+    /// until farmers download already plotted sectors.
+    ///
+    /// Increase will result in higher memory usage.
+    #[arg(long, default_value = "4")]
+    max_plotting_jobs: NonZeroUsize,
+    /// Defines how many record plotter will encode in a single sector concurrently
+    #[arg(long, default_value = "8")]
+    record_encoding_concurrency: NonZeroUsize,
+}
+
+/// Retrieves pieces for plotting from the node
+#[derive(Debug, Clone)]
+struct NodePieceGetter {
+    node_client: NodeRpcClient,
+}
+
+#[async_trait]
+impl PieceGetter for NodePieceGetter {
+    async fn get_piece(
+        &self,
+        piece_index: PieceIndex,
+        _retry_policy: PieceGetterRetryPolicy,
+    ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>> {
+        self.node_client.piece(piece_index).await
+    }
+}
+
+/// Start remote plotter that plots sectors for farmers (see `--remote-plotter-url` option of
+/// `farm` command)
+pub(crate) async fn plotter<PosTable>(plotter_args: PlotterArgs) -> anyhow::Result<()>
+where
+    PosTable: Table,
+{
+    let PlotterArgs {
+        listen_on,
+        node_rpc_url,
+        sector_encoding_concurrency,
+        max_plotting_jobs,
+        record_encoding_concurrency,
+    } = plotter_args;
+
+    info!(url = %node_rpc_url, "Connecting to node RPC");
+    let node_client = NodeRpcClient::new(&node_rpc_url).await?;
+
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let erasure_coding = ErasureCoding::new(
+        NonZeroUsize::new(Record::NUM_S_BUCKETS.next_power_of_two().ilog2() as usize)
+            .expect("Not zero; qed"),
+    )
+    .map_err(|error| anyhow!(error))?;
+
+    let remote_plotter = RemotePlotter::<_, PosTable>::new(
+        NodePieceGetter { node_client },
+        kzg,
+        erasure_coding,
+        sector_encoding_concurrency,
+        max_plotting_jobs,
+        record_encoding_concurrency,
+    );
+
+    let (address, server_handle) = start_remote_plotter_server(listen_on, remote_plotter)
+        .await
+        .map_err(|error| anyhow!("Failed to start remote plotter: {error}"))?;
+
+    info!(%address, "Remote plotter started");
+
+    shutdown_signal().await;
+
+    server_handle
+        .stop()
+        .map_err(|error| anyhow!("Failed to stop remote plotter: {error}"))?;
+
+    Ok(())
+}
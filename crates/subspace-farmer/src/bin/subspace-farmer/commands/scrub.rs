@@ -1,5 +1,6 @@
 use rayon::prelude::*;
 use std::path::PathBuf;
+use subspace_farmer::error_code::ErrorCode;
 use subspace_farmer::single_disk_farm::SingleDiskFarm;
 use tracing::{error, info, info_span};
 
@@ -24,6 +25,7 @@ pub(crate) fn scrub(disk_farms: &[PathBuf], disable_farm_locking: bool) {
                 }
                 Err(error) => {
                     error!(
+                        code = %ErrorCode::FarmCorrupted,
                         path = %directory.display(),
                         %error,
                         "Irrecoverable farm error occurred, your file system might need to be \
@@ -13,6 +13,14 @@ pub(crate) fn print_disk_farm_info(directory: PathBuf, disk_farm_index: usize) {
                 bytesize::to_string(info.allocated_space(), true),
                 bytesize::to_string(info.allocated_space(), false)
             );
+            match info.plot_encryption() {
+                Some(plot_encryption) => {
+                    println!("  Plot encryption: {:?}", plot_encryption.key_source);
+                }
+                None => {
+                    println!("  Plot encryption: none");
+                }
+            }
             println!("  Directory: {}", directory.display());
         }
         SingleDiskFarmSummary::NotFound { directory } => {
@@ -0,0 +1,124 @@
+use anyhow::anyhow;
+use clap::Parser;
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::{fs, io};
+use subspace_farmer::single_disk_farm::{SingleDiskFarm, SingleDiskFarmWipeTargets};
+use tracing::info;
+
+/// File with known addresses of DSN peers, stored alongside the farm
+const KNOWN_ADDRESSES_FILE: &str = "known_addresses.bin";
+
+/// Arguments for farm wiping.
+///
+/// Without any of the granular flags the whole farm is wiped, otherwise only selected parts are.
+#[derive(Debug, Parser)]
+pub(crate) struct WipeArgs {
+    /// One or more farm located at specified path.
+    ///
+    /// Example:
+    ///   /path/to/directory
+    disk_farms: Vec<PathBuf>,
+    /// Wipe only piece cache
+    #[arg(long)]
+    piece_cache: bool,
+    /// Wipe only metadata of plotted sectors, sectors will be plotted again from scratch
+    #[arg(long)]
+    plots_metadata: bool,
+    /// Wipe only farm identity, farm info, plot and its metadata are tied to identity and are
+    /// wiped too
+    #[arg(long)]
+    identity: bool,
+    /// Do not ask for confirmation before deleting files
+    #[arg(long)]
+    yes: bool,
+}
+
+pub(crate) fn wipe(wipe_args: WipeArgs) -> anyhow::Result<()> {
+    let WipeArgs {
+        disk_farms,
+        piece_cache,
+        plots_metadata,
+        identity,
+        yes,
+    } = wipe_args;
+
+    if disk_farms.is_empty() {
+        info!("No farm was specified, so there is nothing to do");
+        return Ok(());
+    }
+
+    for disk_farm in &disk_farms {
+        if !disk_farm.exists() {
+            return Err(anyhow!("Directory {} doesn't exist", disk_farm.display()));
+        }
+    }
+
+    let wipe_everything = !(piece_cache || plots_metadata || identity);
+    let targets = if wipe_everything {
+        SingleDiskFarmWipeTargets::ALL
+    } else {
+        SingleDiskFarmWipeTargets {
+            plot: false,
+            plots_metadata,
+            piece_cache,
+            identity,
+            info: false,
+        }
+    };
+
+    let files = disk_farms
+        .iter()
+        .flat_map(|disk_farm| {
+            let known_addresses =
+                Some(disk_farm.join(KNOWN_ADDRESSES_FILE)).filter(|_| wipe_everything);
+
+            known_addresses
+                .into_iter()
+                .chain(SingleDiskFarm::wipe_files(disk_farm, targets))
+        })
+        .filter(|file| file.exists())
+        .collect::<Vec<_>>();
+
+    if files.is_empty() {
+        info!("Nothing to wipe");
+        return Ok(());
+    }
+
+    if !yes {
+        if !io::stdin().is_terminal() {
+            return Err(anyhow!(
+                "Confirmation can't be requested without terminal, use `--yes` to wipe anyway"
+            ));
+        }
+
+        eprintln!("Following files will be deleted:");
+        for file in &files {
+            eprintln!("  {}", file.display());
+        }
+        eprint!("Type `yes` to continue: ");
+        io::stderr().flush()?;
+
+        let mut confirmation = String::new();
+        io::stdin().read_line(&mut confirmation)?;
+        if confirmation.trim() != "yes" {
+            return Err(anyhow!("Wiping was not confirmed"));
+        }
+    }
+
+    for disk_farm in &disk_farms {
+        if wipe_everything {
+            let known_addresses = disk_farm.join(KNOWN_ADDRESSES_FILE);
+            if known_addresses.exists() {
+                info!("Wiping known addresses");
+                fs::remove_file(known_addresses)?;
+            }
+        }
+
+        SingleDiskFarm::wipe_targets(disk_farm, targets)?;
+    }
+
+    info!("Done");
+
+    Ok(())
+}
@@ -3,10 +3,9 @@
 mod commands;
 mod utils;
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::env;
 use std::path::PathBuf;
-use std::{env, fs};
-use subspace_farmer::single_disk_farm::SingleDiskFarm;
 use subspace_proof_of_space::chia::ChiaTable;
 use tracing::info;
 use tracing_subscriber::filter::LevelFilter;
@@ -18,15 +17,47 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
 type PosTable = ChiaTable;
 
-#[allow(clippy::large_enum_variant)]
+/// Format of log output
+#[derive(Debug, Copy, Clone, Default, ValueEnum)]
+enum LogFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// Structured JSON, one event per line
+    Json,
+}
+
 #[derive(Debug, Parser)]
 #[clap(about, version)]
+struct Cli {
+    /// Format of log output.
+    ///
+    /// `json` emits one structured event per line, events that correspond to known failures
+    /// contain stable `code` field that can be used for alerting.
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Subcommand)]
 enum Command {
     /// Start a farmer, does plotting and farming
     Farm(commands::farm::FarmingArgs),
     /// Run various benchmarks
     #[clap(subcommand)]
     Benchmark(commands::benchmark::BenchmarkArgs),
+    /// Manage farm identity
+    #[clap(subcommand)]
+    Identity(commands::identity::IdentityArgs),
+    /// Manage farm piece cache
+    #[clap(subcommand)]
+    Cache(commands::cache::CacheArgs),
+    /// Run monitoring server that aggregates farm summaries published by farmers
+    MonitoringServer(commands::monitoring_server::MonitoringServerArgs),
+    /// Run remote plotter that plots sectors for farmers on other machines
+    Plotter(commands::plotter::PlotterArgs),
     /// Print information about farm and its content
     Info {
         /// One or more farm located at specified path.
@@ -46,14 +77,11 @@ enum Command {
         #[arg(long)]
         disable_farm_locking: bool,
     },
-    /// Wipes the farm
-    Wipe {
-        /// One or more farm located at specified path.
-        ///
-        /// Example:
-        ///   /path/to/directory
-        disk_farms: Vec<PathBuf>,
-    },
+    /// Wipes the farm or selected parts of it
+    Wipe(commands::wipe::WipeArgs),
+    /// Estimate expected rewards for a plot of given size using solution range history from the
+    /// node
+    Estimate(commands::estimate::EstimateArgs),
 }
 
 #[tokio::main]
@@ -63,26 +91,33 @@ async fn main() -> anyhow::Result<()> {
     if env::var("RUST_LOG").is_err() {
         env::set_var("RUST_LOG", "info,quinn_udp=error");
     }
-    tracing_subscriber::registry()
-        .with(
-            fmt::layer()
-                // TODO: Workaround for https://github.com/tokio-rs/tracing/issues/2214, also on
-                //  Windows terminal doesn't support the same colors as bash does
-                .with_ansi(if cfg!(windows) {
-                    false
-                } else {
-                    supports_color::on(supports_color::Stream::Stderr).is_some()
-                })
-                .with_filter(
-                    EnvFilter::builder()
-                        .with_default_directive(LevelFilter::INFO.into())
-                        .from_env_lossy(),
-                ),
-        )
-        .init();
-    utils::raise_fd_limit();
+    let Cli {
+        log_format,
+        command,
+    } = Cli::parse();
 
-    let command = Command::parse();
+    let env_filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .from_env_lossy();
+    let fmt_layer = match log_format {
+        LogFormat::Text => fmt::layer()
+            // TODO: Workaround for https://github.com/tokio-rs/tracing/issues/2214, also on
+            //  Windows terminal doesn't support the same colors as bash does
+            .with_ansi(if cfg!(windows) {
+                false
+            } else {
+                supports_color::on(supports_color::Stream::Stderr).is_some()
+            })
+            .with_filter(env_filter)
+            .boxed(),
+        LogFormat::Json => fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_filter(env_filter)
+            .boxed(),
+    };
+    tracing_subscriber::registry().with(fmt_layer).init();
+    utils::raise_fd_limit();
 
     match command {
         Command::Farm(farming_args) => {
@@ -91,6 +126,18 @@ async fn main() -> anyhow::Result<()> {
         Command::Benchmark(benchmark_args) => {
             commands::benchmark::benchmark(benchmark_args)?;
         }
+        Command::Identity(identity_args) => {
+            commands::identity::identity(identity_args)?;
+        }
+        Command::Cache(cache_args) => {
+            commands::cache::cache(cache_args);
+        }
+        Command::MonitoringServer(monitoring_server_args) => {
+            commands::monitoring_server::monitoring_server(monitoring_server_args).await?;
+        }
+        Command::Plotter(plotter_args) => {
+            commands::plotter::plotter::<PosTable>(plotter_args).await?;
+        }
         Command::Info { disk_farms } => {
             if disk_farms.is_empty() {
                 info!("No farm was specified, so there is nothing to do");
@@ -108,27 +155,11 @@ async fn main() -> anyhow::Result<()> {
                 commands::scrub(&disk_farms, disable_farm_locking);
             }
         }
-        Command::Wipe { disk_farms } => {
-            for disk_farm in &disk_farms {
-                if !disk_farm.exists() {
-                    panic!("Directory {} doesn't exist", disk_farm.display());
-                }
-            }
-
-            for disk_farm in &disk_farms {
-                if disk_farm.join("known_addresses.bin").exists() {
-                    info!("Wiping known addresses");
-                    let _ = fs::remove_file(disk_farm.join("known_addresses.bin"));
-                }
-
-                SingleDiskFarm::wipe(disk_farm)?;
-            }
-
-            if disk_farms.is_empty() {
-                info!("No farm was specified, so there is nothing to do");
-            } else {
-                info!("Done");
-            }
+        Command::Wipe(wipe_args) => {
+            commands::wipe::wipe(wipe_args)?;
+        }
+        Command::Estimate(estimate_args) => {
+            commands::estimate::estimate(estimate_args).await?;
         }
     }
     Ok(())
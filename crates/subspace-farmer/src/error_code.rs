@@ -0,0 +1,95 @@
+//! Machine-readable codes of farmer errors and notable events.
+//!
+//! Codes are attached to log events as `code` field. Unlike human-readable log messages, which
+//! change from release to release, codes are stable and can be used for alerting on specific
+//! failures by log aggregation systems (especially in combination with JSON log format).
+
+use std::fmt;
+
+/// Stable code of farmer error or notable event
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ErrorCode {
+    /// Request to the node failed or subscription to node notifications ended
+    NodeRequestFailed,
+    /// Plotted sector expired and will be replotted
+    SectorExpired,
+    /// Plotted sector is about to expire and will be replotted
+    SectorAboutToExpire,
+    /// Plotted sector can't be read
+    SectorReadFailed,
+    /// Sector metadata is corrupted or doesn't match the farm
+    SectorMetadataCorrupted,
+    /// Sector failed auditing or proving repeatedly and was quarantined until replotted
+    SectorQuarantined,
+    /// Plotting of a sector failed
+    PlottingFailed,
+    /// Remote plotter failed to plot a sector or sector received from it was invalid
+    RemotePlottingFailed,
+    /// Auditing of plotted sectors failed
+    AuditingFailed,
+    /// Proving of the solution failed
+    ProvingFailed,
+    /// Proving didn't finish within farming time limit
+    ProvingTimeout,
+    /// Solution was found, but couldn't be submitted to the node
+    SolutionSubmissionFailed,
+    /// Farming failed with an error that doesn't make farm unusable
+    FarmingFailed,
+    /// Reward signing failed
+    RewardSigningFailed,
+    /// Background task of the farm panicked
+    BackgroundTaskPanicked,
+    /// Piece cache has no space left for a piece it was supposed to store
+    CacheFull,
+    /// Piece can't be read from piece cache
+    CacheReadFailed,
+    /// Piece can't be written into piece cache
+    CacheWriteFailed,
+    /// Piece stored in piece cache doesn't match its checksum
+    CacheChecksumMismatch,
+    /// Piece can't be read from the plot
+    PieceReadFailed,
+    /// Piece received from the network is invalid
+    PieceValidationFailed,
+    /// Farm's disk is corrupted in a way that can't be fixed automatically
+    FarmCorrupted,
+    /// Generic I/O error
+    Io,
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ErrorCode {
+    /// String representation of the code, never changes once code is introduced
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::NodeRequestFailed => "node_request_failed",
+            Self::SectorExpired => "sector_expired",
+            Self::SectorAboutToExpire => "sector_about_to_expire",
+            Self::SectorReadFailed => "sector_read_failed",
+            Self::SectorMetadataCorrupted => "sector_metadata_corrupted",
+            Self::SectorQuarantined => "sector_quarantined",
+            Self::PlottingFailed => "plotting_failed",
+            Self::RemotePlottingFailed => "remote_plotting_failed",
+            Self::AuditingFailed => "auditing_failed",
+            Self::ProvingFailed => "proving_failed",
+            Self::ProvingTimeout => "proving_timeout",
+            Self::SolutionSubmissionFailed => "solution_submission_failed",
+            Self::FarmingFailed => "farming_failed",
+            Self::RewardSigningFailed => "reward_signing_failed",
+            Self::BackgroundTaskPanicked => "background_task_panicked",
+            Self::CacheFull => "cache_full",
+            Self::CacheReadFailed => "cache_read_failed",
+            Self::CacheWriteFailed => "cache_write_failed",
+            Self::CacheChecksumMismatch => "cache_checksum_mismatch",
+            Self::PieceReadFailed => "piece_read_failed",
+            Self::PieceValidationFailed => "piece_validation_failed",
+            Self::FarmCorrupted => "farm_corrupted",
+            Self::Io => "io",
+        }
+    }
+}
@@ -0,0 +1,1115 @@
+//! Farmer library facade.
+//!
+//! [`Farmer`] wires together everything the `farm` command of the CLI does: node connection,
+//! networking, farmer cache, plotting thread pools and [`SingleDiskFarm`]s, so that farming can be
+//! embedded into other applications (GUI wrappers, custom orchestrators) without shelling out to
+//! the CLI binary.
+//!
+//! Farmer is configured with [`FarmerBuilder`], [`Farmer::events()`] allows to observe what is
+//! happening with farms and [`Farmer::start()`] returns [`FarmerHandle`] that can be used to stop
+//! farmer or to wait for it to exit. Errors are returned as [`FarmerError`], farmer never
+//! terminates the process.
+
+mod dsn;
+#[cfg(test)]
+mod tests;
+
+use crate::error_code::ErrorCode;
+use crate::farmer::dsn::configure_dsn;
+pub use crate::farmer::dsn::DsnOptions;
+use crate::farmer_cache::FarmerCache;
+use crate::identity::IdentityError;
+use crate::node_client::relay_node_client::RelayNodeClient;
+use crate::remote_plotter::RemotePlotterClient;
+use crate::single_disk_farm::farming::FarmingNotification;
+use crate::single_disk_farm::plot_encryption::PlotEncryption;
+use crate::single_disk_farm::{
+    SectorPlottingDetails, SectorUpdate, SingleDiskFarm, SingleDiskFarmError, SingleDiskFarmId,
+    SingleDiskFarmOptions,
+};
+use crate::utils::farmer_piece_getter::FarmerPieceGetter;
+use crate::utils::piece_validator::SegmentCommitmentPieceValidator;
+use crate::utils::plotted_pieces::PlottedPieces;
+use crate::utils::{
+    all_cpu_cores, create_plotting_thread_pool_manager, parse_cpu_cores_sets,
+    recommended_number_of_farming_threads, run_future_in_dedicated_thread,
+    thread_pool_core_indices, AsyncJoinOnDrop, CpuCoreSet,
+};
+use crate::{Identity, NodeClient, NodeRpcClient, RpcClientError};
+use futures::channel::oneshot;
+use futures::channel::oneshot::Canceled;
+use futures::stream::FuturesUnordered;
+use futures::{select, stream, FutureExt, Stream, StreamExt};
+use parking_lot::Mutex;
+use prometheus_client::registry::Registry;
+use rayon::ThreadPoolBuildError;
+use std::future::Future;
+use std::num::{NonZeroU8, NonZeroUsize, ParseIntError};
+use std::path::PathBuf;
+use std::pin::{pin, Pin};
+use std::sync::Arc;
+use std::{fmt, fs, io};
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::{PublicKey, Record, SectorIndex};
+use subspace_erasure_coding::ErasureCoding;
+use subspace_metrics::MetricsComponent;
+use subspace_networking::libp2p::identity::{ed25519, Keypair};
+use subspace_networking::utils::piece_provider::PieceProvider;
+use subspace_networking::{CreationError, KnownPeersManagerPersistenceError, Node, NodeRunner};
+use subspace_proof_of_space::Table;
+use subspace_rpc_primitives::SolutionResponse;
+use subspace_thread_pool::ThreadPoolManager;
+use thiserror::Error;
+use tokio::sync::{broadcast, Semaphore};
+use tokio::task::JoinError;
+use tracing::{debug, error, info, info_span, warn};
+use zeroize::Zeroizing;
+
+/// Number of next sectors of each farm whose pieces are prioritized in farmer cache
+const PRIORITIZED_SECTORS_PER_FARM: SectorIndex = 10;
+/// Number of events buffered for each subscriber of [`Farmer::events()`], slow subscribers miss
+/// oldest events
+const EVENTS_CHANNEL_CAPACITY: usize = 1024;
+
+/// Whether farming during initial plotting is enabled by default, it is not on machines with 8 or
+/// less logical cores because plotting is so intense on CPU and memory that farming will likely not
+/// work properly
+pub fn should_farm_during_initial_plotting() -> bool {
+    let total_cpu_cores = all_cpu_cores()
+        .iter()
+        .flat_map(|set| set.cpu_cores())
+        .count();
+    total_cpu_cores > 8
+}
+
+/// Errors that happen when creating or running [`Farmer`]
+#[derive(Debug, Error)]
+pub enum FarmerError {
+    /// No farms were configured
+    #[error("There must be at least one farm provided")]
+    NoFarms,
+    /// Too many farms were configured
+    #[error("More than 256 farms are not supported, consider running multiple farmer instances")]
+    TooManyFarms,
+    /// Failed to create farm directory
+    #[error("Directory {} doesn't exist and can't be created: {error}", directory.display())]
+    FarmDirectory {
+        /// Farm directory
+        directory: PathBuf,
+        /// Low-level error
+        error: io::Error,
+    },
+    /// Failed to connect to node
+    #[error("Failed to connect to node at {url}: {error}")]
+    NodeConnection {
+        /// Node RPC URL
+        url: String,
+        /// Low-level error
+        error: RpcClientError,
+    },
+    /// Node RPC request failed
+    #[error("Node RPC request failed: {0}")]
+    NodeRpc(RpcClientError),
+    /// Failed to open or create identity
+    #[error("Failed to open or create identity: {0}")]
+    Identity(#[from] IdentityError),
+    /// Failed to open known peers of networking stack
+    #[error("Failed to open known peers: {0}")]
+    KnownPeers(#[from] KnownPeersManagerPersistenceError),
+    /// Failed to create networking stack
+    #[error("Failed to create networking stack: {0}")]
+    Networking(#[from] CreationError),
+    /// Failed to instantiate erasure coding
+    #[error("Failed to instantiate erasure coding: {0}")]
+    ErasureCoding(String),
+    /// Invalid CPU cores specification
+    #[error("Failed to parse {kind} CPU cores: {error}")]
+    InvalidCpuCores {
+        /// Whether CPU cores are for plotting or replotting
+        kind: &'static str,
+        /// Low-level error
+        error: ParseIntError,
+    },
+    /// Number of plotting and replotting thread pools doesn't match
+    #[error(
+        "Number of plotting thread pools ({plotting}) is not the same as for replotting \
+        ({replotting})"
+    )]
+    ThreadPoolsMismatch {
+        /// Number of plotting thread pools
+        plotting: usize,
+        /// Number of replotting thread pools
+        replotting: usize,
+    },
+    /// Failed to create thread pool
+    #[error("Failed to create thread pool: {0}")]
+    ThreadPool(#[from] ThreadPoolBuildError),
+    /// Failed to spawn thread
+    #[error("Failed to spawn thread: {0}")]
+    Thread(#[from] io::Error),
+    /// Failed to create remote plotter client
+    #[error("Failed to create remote plotter client: {0}")]
+    RemotePlotter(jsonrpsee::core::Error),
+    /// Failed to open single disk farm
+    #[error("Failed to open farm {farm_index}: {error}")]
+    SingleDiskFarm {
+        /// Index of the farm
+        farm_index: usize,
+        /// Low-level error
+        error: SingleDiskFarmError,
+    },
+    /// Farm failed
+    #[error("Farm failed: {0}")]
+    Farm(anyhow::Error),
+    /// Background thread exited unexpectedly
+    #[error("Farmer background thread exited unexpectedly")]
+    BackgroundThreadCanceled(#[from] Canceled),
+    /// Farmer task panicked
+    #[error("Farmer task panicked: {0}")]
+    TaskPanicked(#[from] JoinError),
+}
+
+/// Options of a single farm of the [`Farmer`]
+#[derive(Clone)]
+pub struct FarmOptions {
+    /// Path to directory where data is stored
+    pub directory: PathBuf,
+    /// How much space in bytes can farm use for plots (metadata space is not included)
+    pub allocated_space: u64,
+    /// Whether to issue read-ahead for audit of the next slot
+    pub audit_prefetch: bool,
+    /// Plot encryption, can only be enabled when farm is created
+    pub plot_encryption: Option<PlotEncryption>,
+}
+
+impl fmt::Debug for FarmOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FarmOptions")
+            .field("directory", &self.directory)
+            .field("allocated_space", &self.allocated_space)
+            .field("audit_prefetch", &self.audit_prefetch)
+            .field(
+                "plot_encryption",
+                &self
+                    .plot_encryption
+                    .as_ref()
+                    .map(|plot_encryption| plot_encryption.key_source()),
+            )
+            .finish()
+    }
+}
+
+/// Events emitted by [`Farmer`], see [`Farmer::events()`]
+#[derive(Debug, Clone)]
+pub enum FarmerEvent {
+    /// Sector of a farm was updated
+    SectorUpdate {
+        /// Index of the farm in the order farms were added to [`FarmerBuilder`]
+        farm_index: u8,
+        /// ID of the farm
+        farm_id: SingleDiskFarmId,
+        /// Index of the sector
+        sector_index: SectorIndex,
+        /// Update details
+        update: SectorUpdate,
+    },
+    /// Farming notification of a farm
+    FarmingNotification {
+        /// Index of the farm in the order farms were added to [`FarmerBuilder`]
+        farm_index: u8,
+        /// ID of the farm
+        farm_id: SingleDiskFarmId,
+        /// Notification details
+        notification: FarmingNotification,
+    },
+    /// Solution was found by a farm
+    Solution {
+        /// Index of the farm in the order farms were added to [`FarmerBuilder`]
+        farm_index: u8,
+        /// ID of the farm
+        farm_id: SingleDiskFarmId,
+        /// Solution that was submitted to the node
+        solution_response: SolutionResponse,
+    },
+    /// Farm exited successfully
+    FarmExited {
+        /// ID of the farm
+        farm_id: SingleDiskFarmId,
+    },
+}
+
+/// Builder for [`Farmer`]
+#[derive(Debug)]
+pub struct FarmerBuilder {
+    node_rpc_url: String,
+    trusted_node_rpc_url: Option<String>,
+    reward_address: PublicKey,
+    farms: Vec<FarmOptions>,
+    cache_percentage: NonZeroU8,
+    max_pieces_in_sector: Option<u16>,
+    dsn: DsnOptions,
+    sector_downloading_concurrency: Option<NonZeroUsize>,
+    sector_encoding_concurrency: Option<NonZeroUsize>,
+    record_encoding_concurrency: Option<NonZeroUsize>,
+    farm_during_initial_plotting: bool,
+    farming_thread_pool_size: Option<NonZeroUsize>,
+    plotting_thread_pool_size: Option<NonZeroUsize>,
+    plotting_cpu_cores: Option<String>,
+    replotting_thread_pool_size: Option<NonZeroUsize>,
+    replotting_cpu_cores: Option<String>,
+    remote_plotter_url: Option<String>,
+    disable_farm_locking: bool,
+}
+
+impl FarmerBuilder {
+    /// Create builder for farmer that connects to node at `node_rpc_url` (WebSocket RPC URL) and
+    /// sends rewards to `reward_address`
+    pub fn new(node_rpc_url: String, reward_address: PublicKey) -> Self {
+        Self {
+            node_rpc_url,
+            trusted_node_rpc_url: None,
+            reward_address,
+            farms: Vec::new(),
+            cache_percentage: NonZeroU8::MIN,
+            max_pieces_in_sector: None,
+            dsn: DsnOptions::default(),
+            sector_downloading_concurrency: None,
+            sector_encoding_concurrency: None,
+            record_encoding_concurrency: None,
+            farm_during_initial_plotting: should_farm_during_initial_plotting(),
+            farming_thread_pool_size: None,
+            plotting_thread_pool_size: None,
+            plotting_cpu_cores: None,
+            replotting_thread_pool_size: None,
+            replotting_cpu_cores: None,
+            remote_plotter_url: None,
+            disable_farm_locking: false,
+        }
+    }
+
+    /// Add farm, farms are indexed in the order they were added
+    pub fn with_farm(mut self, farm: FarmOptions) -> Self {
+        self.farms.push(farm);
+        self
+    }
+
+    /// WebSocket RPC URL of a trusted node to relay slot info and archived history from while the
+    /// local node is syncing, see [`RelayNodeClient`]
+    pub fn with_trusted_node_rpc_url(mut self, trusted_node_rpc_url: String) -> Self {
+        self.trusted_node_rpc_url.replace(trusted_node_rpc_url);
+        self
+    }
+
+    /// Percentage of allocated space dedicated for caching purposes, 1% by default
+    pub fn with_cache_percentage(mut self, cache_percentage: NonZeroU8) -> Self {
+        self.cache_percentage = cache_percentage;
+        self
+    }
+
+    /// Maximum number of pieces in sector (can override protocol value to something lower)
+    pub fn with_max_pieces_in_sector(mut self, max_pieces_in_sector: u16) -> Self {
+        self.max_pieces_in_sector.replace(max_pieces_in_sector);
+        self
+    }
+
+    /// DSN options, [`DsnOptions::default()`] is used by default
+    pub fn with_dsn(mut self, dsn: DsnOptions) -> Self {
+        self.dsn = dsn;
+        self
+    }
+
+    /// How many sectors farmer will download concurrently, defaults to number of plotting thread
+    /// pools + 1
+    pub fn with_sector_downloading_concurrency(
+        mut self,
+        sector_downloading_concurrency: NonZeroUsize,
+    ) -> Self {
+        self.sector_downloading_concurrency
+            .replace(sector_downloading_concurrency);
+        self
+    }
+
+    /// How many sectors farmer will encode concurrently (number of plotting thread pools)
+    pub fn with_sector_encoding_concurrency(
+        mut self,
+        sector_encoding_concurrency: NonZeroUsize,
+    ) -> Self {
+        self.sector_encoding_concurrency
+            .replace(sector_encoding_concurrency);
+        self
+    }
+
+    /// How many records farmer will encode in a single sector concurrently
+    pub fn with_record_encoding_concurrency(
+        mut self,
+        record_encoding_concurrency: NonZeroUsize,
+    ) -> Self {
+        self.record_encoding_concurrency
+            .replace(record_encoding_concurrency);
+        self
+    }
+
+    /// Whether to farm during initial plotting, see [`should_farm_during_initial_plotting()`] for
+    /// default
+    pub fn with_farm_during_initial_plotting(mut self, farm_during_initial_plotting: bool) -> Self {
+        self.farm_during_initial_plotting = farm_during_initial_plotting;
+        self
+    }
+
+    /// Size of per farm thread pool used for farming
+    pub fn with_farming_thread_pool_size(mut self, farming_thread_pool_size: NonZeroUsize) -> Self {
+        self.farming_thread_pool_size
+            .replace(farming_thread_pool_size);
+        self
+    }
+
+    /// Size of one thread pool used for plotting
+    pub fn with_plotting_thread_pool_size(
+        mut self,
+        plotting_thread_pool_size: NonZeroUsize,
+    ) -> Self {
+        self.plotting_thread_pool_size
+            .replace(plotting_thread_pool_size);
+        self
+    }
+
+    /// Exact CPU cores to be used for plotting, coma-separated with whitespace separating different
+    /// thread pools, replaces sector encoding concurrency and plotting thread pool size
+    pub fn with_plotting_cpu_cores(mut self, plotting_cpu_cores: String) -> Self {
+        self.plotting_cpu_cores.replace(plotting_cpu_cores);
+        self
+    }
+
+    /// Size of one thread pool used for replotting
+    pub fn with_replotting_thread_pool_size(
+        mut self,
+        replotting_thread_pool_size: NonZeroUsize,
+    ) -> Self {
+        self.replotting_thread_pool_size
+            .replace(replotting_thread_pool_size);
+        self
+    }
+
+    /// Exact CPU cores to be used for replotting, requires plotting CPU cores to be specified with
+    /// the same number of CPU cores groups
+    pub fn with_replotting_cpu_cores(mut self, replotting_cpu_cores: String) -> Self {
+        self.replotting_cpu_cores.replace(replotting_cpu_cores);
+        self
+    }
+
+    /// URL of remote plotter to request plotted sectors from instead of plotting them locally
+    pub fn with_remote_plotter_url(mut self, remote_plotter_url: String) -> Self {
+        self.remote_plotter_url.replace(remote_plotter_url);
+        self
+    }
+
+    /// Disable farm locking, for example if file system doesn't support it
+    pub fn with_farm_locking_disabled(mut self) -> Self {
+        self.disable_farm_locking = true;
+        self
+    }
+
+    /// Create farmer: connect to the node, configure networking and open all farms.
+    ///
+    /// Farmer doesn't plot or farm until [`Farmer::start()`] is called. Metrics of networking and
+    /// thread pools are registered in `prometheus_metrics_registry` if provided.
+    ///
+    /// NOTE: Though this function is async, it will do some blocking I/O.
+    pub async fn build<PosTable>(
+        self,
+        prometheus_metrics_registry: Option<&mut Registry>,
+    ) -> Result<Farmer, FarmerError>
+    where
+        PosTable: Table,
+    {
+        let FarmerBuilder {
+            node_rpc_url,
+            trusted_node_rpc_url,
+            reward_address,
+            farms,
+            cache_percentage,
+            max_pieces_in_sector,
+            mut dsn,
+            sector_downloading_concurrency,
+            sector_encoding_concurrency,
+            record_encoding_concurrency,
+            farm_during_initial_plotting,
+            farming_thread_pool_size,
+            plotting_thread_pool_size,
+            plotting_cpu_cores,
+            replotting_thread_pool_size,
+            replotting_cpu_cores,
+            remote_plotter_url,
+            disable_farm_locking,
+        } = self;
+        let mut prometheus_metrics_registry = prometheus_metrics_registry;
+
+        if farms.is_empty() {
+            return Err(FarmerError::NoFarms);
+        }
+        if farms.len() > usize::from(u8::MAX) + 1 {
+            return Err(FarmerError::TooManyFarms);
+        }
+
+        for farm in &farms {
+            if !farm.directory.exists() {
+                fs::create_dir(&farm.directory).map_err(|error| FarmerError::FarmDirectory {
+                    directory: farm.directory.clone(),
+                    error,
+                })?;
+            }
+        }
+
+        let plotted_pieces = Arc::new(Mutex::new(None));
+
+        info!(url = %node_rpc_url, "Connecting to node RPC");
+        let node_client =
+            connect_node_client(&node_rpc_url, trusted_node_rpc_url.as_deref()).await?;
+
+        let farmer_app_info = node_client
+            .farmer_app_info()
+            .await
+            .map_err(FarmerError::NodeRpc)?;
+
+        let first_farm_directory = &farms
+            .first()
+            .expect("Farm collection is not be empty as checked above; qed")
+            .directory;
+
+        let identity = Identity::open_or_create(first_farm_directory)?;
+        let keypair = derive_libp2p_keypair(identity.secret_key());
+        let peer_id = keypair.public().to_peer_id();
+
+        let (farmer_cache, farmer_cache_worker) = FarmerCache::new(node_client.clone(), peer_id);
+
+        let farming_thread_pool_size = farming_thread_pool_size
+            .map(|farming_thread_pool_size| farming_thread_pool_size.get())
+            .unwrap_or_else(recommended_number_of_farming_threads);
+        // Plotting thread pools share all CPU cores, farming thread pools are mostly idle waiting
+        // for I/O and are latency-sensitive, so they are budgeted on top of that
+        let thread_pool_manager = ThreadPoolManager::new(
+            NonZeroUsize::new(
+                all_cpu_cores()
+                    .iter()
+                    .map(|cpu_core_set| cpu_core_set.cpu_cores().len())
+                    .sum::<usize>()
+                    + farming_thread_pool_size,
+            )
+            .expect("Guaranteed to have some CPU cores; qed"),
+        );
+        if let Some(prometheus_metrics_registry) = &mut prometheus_metrics_registry {
+            thread_pool_manager.register_metrics(
+                MetricsComponent::Farmer.sub_registry(prometheus_metrics_registry),
+            );
+        }
+
+        let piece_request_ticket_difficulty = dsn.piece_request_ticket_difficulty;
+        let (node, node_runner) = {
+            if dsn.bootstrap_nodes.is_empty() {
+                dsn.bootstrap_nodes = farmer_app_info.dsn_bootstrap_nodes.clone();
+            }
+
+            configure_dsn(
+                hex::encode(farmer_app_info.genesis_hash),
+                first_farm_directory,
+                keypair,
+                dsn,
+                Arc::downgrade(&plotted_pieces),
+                node_client.clone(),
+                farmer_cache.clone(),
+                prometheus_metrics_registry,
+            )?
+        };
+
+        node.on_reachability_change(Arc::new({
+            let farmer_cache = farmer_cache.clone();
+
+            move |reachability| {
+                if reachability.is_private() {
+                    info!("Farmer is not publicly reachable, cached pieces will not be announced");
+                }
+                farmer_cache.set_announce_pieces(!reachability.is_private());
+            }
+        }))
+        .detach();
+
+        let kzg = Kzg::new(embedded_kzg_settings());
+        let erasure_coding = ErasureCoding::new(
+            NonZeroUsize::new(Record::NUM_S_BUCKETS.next_power_of_two().ilog2() as usize)
+                .expect("Not zero; qed"),
+        )
+        .map_err(FarmerError::ErasureCoding)?;
+        let validator = Some(SegmentCommitmentPieceValidator::new(
+            node.clone(),
+            node_client.clone(),
+            kzg.clone(),
+        ));
+        let piece_provider = PieceProvider::new(node.clone(), validator.clone())
+            .with_request_tickets(piece_request_ticket_difficulty);
+
+        let piece_getter = FarmerPieceGetter::new(
+            piece_provider,
+            farmer_cache.clone(),
+            node_client.clone(),
+            Arc::clone(&plotted_pieces),
+        );
+
+        let farmer_cache_worker_fut = run_future_in_dedicated_thread(
+            {
+                let future = farmer_cache_worker.run(piece_getter.downgrade());
+
+                move || future
+            },
+            "farmer-cache-worker".to_string(),
+        )?;
+
+        let max_pieces_in_sector = match max_pieces_in_sector {
+            Some(max_pieces_in_sector) => {
+                if max_pieces_in_sector > farmer_app_info.protocol_info.max_pieces_in_sector {
+                    warn!(
+                        protocol_value = farmer_app_info.protocol_info.max_pieces_in_sector,
+                        desired_value = max_pieces_in_sector,
+                        "Can't set max pieces in sector higher than protocol value, using \
+                        protocol value"
+                    );
+
+                    farmer_app_info.protocol_info.max_pieces_in_sector
+                } else {
+                    max_pieces_in_sector
+                }
+            }
+            None => farmer_app_info.protocol_info.max_pieces_in_sector,
+        };
+
+        let mut plotting_thread_pool_core_indices;
+        let mut replotting_thread_pool_core_indices;
+        if let Some(plotting_cpu_cores) = plotting_cpu_cores {
+            plotting_thread_pool_core_indices =
+                parse_cpu_cores_sets(&plotting_cpu_cores).map_err(|error| {
+                    FarmerError::InvalidCpuCores {
+                        kind: "plotting",
+                        error,
+                    }
+                })?;
+            replotting_thread_pool_core_indices =
+                match replotting_cpu_cores {
+                    Some(replotting_cpu_cores) => parse_cpu_cores_sets(&replotting_cpu_cores)
+                        .map_err(|error| FarmerError::InvalidCpuCores {
+                            kind: "replotting",
+                            error,
+                        })?,
+                    None => plotting_thread_pool_core_indices.clone(),
+                };
+            if plotting_thread_pool_core_indices.len() != replotting_thread_pool_core_indices.len()
+            {
+                return Err(FarmerError::ThreadPoolsMismatch {
+                    plotting: plotting_thread_pool_core_indices.len(),
+                    replotting: replotting_thread_pool_core_indices.len(),
+                });
+            }
+        } else {
+            plotting_thread_pool_core_indices =
+                thread_pool_core_indices(plotting_thread_pool_size, sector_encoding_concurrency);
+            replotting_thread_pool_core_indices = {
+                let mut replotting_thread_pool_core_indices = thread_pool_core_indices(
+                    replotting_thread_pool_size,
+                    sector_encoding_concurrency,
+                );
+                if replotting_thread_pool_size.is_none() {
+                    // The default behavior is to use all CPU cores, but for replotting we just want
+                    // half
+                    replotting_thread_pool_core_indices
+                        .iter_mut()
+                        .for_each(|set| set.truncate(set.cpu_cores().len() / 2));
+                }
+                replotting_thread_pool_core_indices
+            };
+
+            if plotting_thread_pool_core_indices.len() > 1 {
+                info!(
+                    l3_cache_groups = %plotting_thread_pool_core_indices.len(),
+                    "Multiple L3 cache groups detected"
+                );
+
+                if plotting_thread_pool_core_indices.len() > farms.len() {
+                    plotting_thread_pool_core_indices =
+                        CpuCoreSet::regroup(&plotting_thread_pool_core_indices, farms.len());
+                    replotting_thread_pool_core_indices =
+                        CpuCoreSet::regroup(&replotting_thread_pool_core_indices, farms.len());
+
+                    info!(
+                        farms_count = %farms.len(),
+                        "Regrouped CPU cores to match number of farms, more farms may leverage CPU \
+                        more efficiently"
+                    );
+                }
+            }
+        }
+
+        let downloading_semaphore = Arc::new(Semaphore::new(
+            sector_downloading_concurrency
+                .map(|sector_downloading_concurrency| sector_downloading_concurrency.get())
+                .unwrap_or(plotting_thread_pool_core_indices.len() + 1),
+        ));
+
+        let record_encoding_concurrency = record_encoding_concurrency.unwrap_or_else(|| {
+            let cpu_cores = plotting_thread_pool_core_indices
+                .first()
+                .expect("Guaranteed to have some CPU cores; qed");
+
+            NonZeroUsize::new((cpu_cores.cpu_cores().len() / 2).min(8))
+                .expect("Guaranteed to have some CPU cores; qed")
+        });
+
+        let plotting_thread_pool_manager = create_plotting_thread_pool_manager(
+            &thread_pool_manager,
+            plotting_thread_pool_core_indices
+                .into_iter()
+                .zip(replotting_thread_pool_core_indices),
+        )?;
+
+        let remote_plotter = remote_plotter_url
+            .map(|url| {
+                info!(%url, "Using remote plotter");
+
+                RemotePlotterClient::new(&url)
+            })
+            .transpose()
+            .map_err(FarmerError::RemotePlotter)?;
+
+        let mut single_disk_farms = Vec::with_capacity(farms.len());
+        let mut plotting_delay_senders = Vec::with_capacity(farms.len());
+
+        for (farm_index, farm) in farms.into_iter().enumerate() {
+            debug!(url = %node_rpc_url, %farm_index, "Connecting to node RPC");
+            let node_client =
+                connect_node_client(&node_rpc_url, trusted_node_rpc_url.as_deref()).await?;
+            let (plotting_delay_sender, plotting_delay_receiver) = oneshot::channel();
+            plotting_delay_senders.push(plotting_delay_sender);
+
+            let single_disk_farm = SingleDiskFarm::new::<_, _, PosTable>(
+                SingleDiskFarmOptions {
+                    directory: farm.directory,
+                    farmer_app_info: farmer_app_info.clone(),
+                    allocated_space: farm.allocated_space,
+                    max_pieces_in_sector,
+                    node_client,
+                    reward_address,
+                    kzg: kzg.clone(),
+                    erasure_coding: erasure_coding.clone(),
+                    piece_getter: piece_getter.clone(),
+                    cache_percentage,
+                    downloading_semaphore: Arc::clone(&downloading_semaphore),
+                    record_encoding_concurrency,
+                    farm_during_initial_plotting,
+                    farming_thread_pool_size,
+                    thread_pool_manager: thread_pool_manager.clone(),
+                    plotting_thread_pool_manager: plotting_thread_pool_manager.clone(),
+                    plotting_delay: Some(plotting_delay_receiver),
+                    disable_farm_locking,
+                    audit_prefetch: farm.audit_prefetch,
+                    plot_encryption: farm.plot_encryption,
+                    remote_plotter: remote_plotter.clone(),
+                },
+                farm_index,
+            )
+            .await
+            .map_err(|error| FarmerError::SingleDiskFarm { farm_index, error })?;
+
+            single_disk_farms.push(single_disk_farm);
+        }
+
+        {
+            let mut prioritized_piece_indices = Vec::new();
+            for single_disk_farm in &single_disk_farms {
+                prioritized_piece_indices.extend(
+                    single_disk_farm
+                        .upcoming_piece_indices(PRIORITIZED_SECTORS_PER_FARM)
+                        .await,
+                );
+            }
+            farmer_cache.set_prioritized_pieces(prioritized_piece_indices);
+        }
+
+        let cache_acknowledgement_receiver = farmer_cache
+            .replace_backing_caches(
+                single_disk_farms
+                    .iter()
+                    .map(|single_disk_farm| single_disk_farm.piece_cache())
+                    .collect(),
+            )
+            .await;
+        drop(farmer_cache);
+
+        // Wait for cache initialization before starting plotting
+        tokio::spawn(async move {
+            if cache_acknowledgement_receiver.await.is_ok() {
+                for plotting_delay_sender in plotting_delay_senders {
+                    // Doesn't matter if receiver is gone
+                    let _ = plotting_delay_sender.send(());
+                }
+            }
+        });
+
+        // Store piece readers so we can reference them later
+        let piece_readers = single_disk_farms
+            .iter()
+            .map(|single_disk_farm| single_disk_farm.piece_reader())
+            .collect::<Vec<_>>();
+
+        info!("Collecting already plotted pieces (this will take some time)...");
+
+        // Collect already plotted pieces
+        {
+            let mut future_plotted_pieces = PlottedPieces::new(piece_readers);
+
+            for (farm_index, single_disk_farm) in (0_u8..).zip(&single_disk_farms) {
+                (0 as SectorIndex..)
+                    .zip(single_disk_farm.plotted_sectors().await)
+                    .for_each(
+                        |(sector_index, plotted_sector_result)| match plotted_sector_result {
+                            Ok(plotted_sector) => {
+                                future_plotted_pieces.add_sector(farm_index, &plotted_sector);
+                            }
+                            Err(error) => {
+                                error!(
+                                    code = %ErrorCode::SectorReadFailed,
+                                    %error,
+                                    %farm_index,
+                                    %sector_index,
+                                    "Failed reading plotted sector on startup, skipping"
+                                );
+                            }
+                        },
+                    );
+            }
+
+            plotted_pieces.lock().replace(future_plotted_pieces);
+        }
+
+        info!("Finished collecting already plotted pieces successfully");
+
+        let (event_sender, _) = broadcast::channel(EVENTS_CHANNEL_CAPACITY);
+
+        Ok(Farmer {
+            node,
+            node_runner,
+            single_disk_farms,
+            plotted_pieces,
+            farmer_cache_worker_fut: Box::pin(farmer_cache_worker_fut),
+            event_sender,
+        })
+    }
+}
+
+/// Farmer that plots and farms using multiple [`SingleDiskFarm`]s, created with [`FarmerBuilder`].
+///
+/// Farmer doesn't plot or farm until [`Farmer::start()`] is called.
+#[must_use = "Farmer does not function unless start() method is called"]
+pub struct Farmer {
+    node: Node,
+    node_runner: NodeRunner<FarmerCache>,
+    single_disk_farms: Vec<SingleDiskFarm>,
+    plotted_pieces: Arc<Mutex<Option<PlottedPieces>>>,
+    farmer_cache_worker_fut: Pin<Box<dyn Future<Output = Result<(), Canceled>> + Send>>,
+    event_sender: broadcast::Sender<FarmerEvent>,
+}
+
+impl fmt::Debug for Farmer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Farmer")
+            .field("peer_id", &self.node.id())
+            .field(
+                "farms",
+                &self
+                    .single_disk_farms
+                    .iter()
+                    .map(|single_disk_farm| single_disk_farm.id())
+                    .collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl Farmer {
+    /// Create builder for farmer that connects to node at `node_rpc_url` (WebSocket RPC URL) and
+    /// sends rewards to `reward_address`
+    pub fn builder(node_rpc_url: String, reward_address: PublicKey) -> FarmerBuilder {
+        FarmerBuilder::new(node_rpc_url, reward_address)
+    }
+
+    /// Networking node of the farmer
+    pub fn node(&self) -> &Node {
+        &self.node
+    }
+
+    /// Farms in the order they were added to [`FarmerBuilder`]
+    pub fn single_disk_farms(&self) -> &[SingleDiskFarm] {
+        &self.single_disk_farms
+    }
+
+    /// Subscribe to farmer events, only events emitted after subscription are received.
+    ///
+    /// Subscribe before [`Farmer::start()`] to not miss any events. Subscribers that can't keep up
+    /// miss oldest events.
+    pub fn events(&self) -> impl Stream<Item = FarmerEvent> + Send + Unpin + 'static {
+        Box::pin(stream::unfold(
+            self.event_sender.subscribe(),
+            |mut event_receiver| async move {
+                loop {
+                    match event_receiver.recv().await {
+                        Ok(event) => {
+                            return Some((event, event_receiver));
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            debug!(%skipped, "Farmer event subscriber lagged behind");
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            return None;
+                        }
+                    }
+                }
+            },
+        ))
+    }
+
+    /// Start plotting, farming and networking in background threads.
+    ///
+    /// Returned handle can be used to stop farmer or wait for it to exit, dropping it stops farmer
+    /// as well.
+    pub fn start(self) -> Result<FarmerHandle, FarmerError> {
+        let Farmer {
+            node,
+            mut node_runner,
+            single_disk_farms,
+            plotted_pieces,
+            farmer_cache_worker_fut,
+            event_sender,
+        } = self;
+
+        let mut single_disk_farms_stream = (0_u8..)
+            .zip(single_disk_farms)
+            .map(|(farm_index, single_disk_farm)| {
+                let farm_id = *single_disk_farm.id();
+
+                single_disk_farm
+                    .on_sector_update(Arc::new({
+                        let plotted_pieces = Arc::clone(&plotted_pieces);
+                        let event_sender = event_sender.clone();
+                        let span = info_span!("", %farm_index);
+
+                        move |(sector_index, update)| {
+                            // Collect newly plotted pieces
+                            if let SectorUpdate::Plotting(SectorPlottingDetails::Finished {
+                                plotted_sector,
+                                old_plotted_sector,
+                                ..
+                            }) = update
+                            {
+                                let _span_guard = span.enter();
+
+                                let mut plotted_pieces = plotted_pieces.lock();
+                                let plotted_pieces = plotted_pieces
+                                    .as_mut()
+                                    .expect("Initial value was populated during build; qed");
+
+                                if let Some(old_plotted_sector) = old_plotted_sector {
+                                    plotted_pieces.delete_sector(farm_index, old_plotted_sector);
+                                }
+                                plotted_pieces.add_sector(farm_index, plotted_sector);
+                            }
+
+                            // Doesn't matter if there are no subscribers
+                            let _ = event_sender.send(FarmerEvent::SectorUpdate {
+                                farm_index,
+                                farm_id,
+                                sector_index: *sector_index,
+                                update: update.clone(),
+                            });
+                        }
+                    }))
+                    .detach();
+
+                single_disk_farm
+                    .on_farming_notification(Arc::new({
+                        let event_sender = event_sender.clone();
+
+                        move |notification| {
+                            // Doesn't matter if there are no subscribers
+                            let _ = event_sender.send(FarmerEvent::FarmingNotification {
+                                farm_index,
+                                farm_id,
+                                notification: notification.clone(),
+                            });
+                        }
+                    }))
+                    .detach();
+
+                single_disk_farm
+                    .on_solution(Arc::new({
+                        let event_sender = event_sender.clone();
+
+                        move |solution_response| {
+                            // Doesn't matter if there are no subscribers
+                            let _ = event_sender.send(FarmerEvent::Solution {
+                                farm_index,
+                                farm_id,
+                                solution_response: solution_response.clone(),
+                            });
+                        }
+                    }))
+                    .detach();
+
+                single_disk_farm.run()
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        // Drop original instance such that the only remaining instances are in `SingleDiskFarm`
+        // event handlers
+        drop(plotted_pieces);
+
+        let farm_fut = run_future_in_dedicated_thread(
+            move || async move {
+                while let Some(result) = single_disk_farms_stream.next().await {
+                    let farm_id = result.map_err(FarmerError::Farm)?;
+
+                    info!(%farm_id, "Farm exited successfully");
+                    // Doesn't matter if there are no subscribers
+                    let _ = event_sender.send(FarmerEvent::FarmExited { farm_id });
+                }
+                Ok::<_, FarmerError>(())
+            },
+            "farmer-farm".to_string(),
+        )?;
+
+        let networking_fut = run_future_in_dedicated_thread(
+            move || async move { node_runner.run().await },
+            "farmer-networking".to_string(),
+        )?;
+
+        let (stop_sender, stop_receiver) = oneshot::channel::<()>();
+
+        let join_handle = tokio::spawn(async move {
+            // Keep node alive for as long as farmer is running
+            let _node = node;
+
+            // This defines order in which things are dropped
+            let networking_fut = networking_fut;
+            let farm_fut = farm_fut;
+            let farmer_cache_worker_fut = farmer_cache_worker_fut;
+
+            let networking_fut = pin!(networking_fut);
+            let farm_fut = pin!(farm_fut);
+            let farmer_cache_worker_fut = pin!(farmer_cache_worker_fut);
+
+            select!(
+                // Stop signal future, stop handle being dropped also counts
+                _ = stop_receiver.fuse() => {},
+
+                // Networking future
+                _ = networking_fut.fuse() => {
+                    info!("Node runner exited.")
+                },
+
+                // Farm future
+                result = farm_fut.fuse() => {
+                    result??;
+                },
+
+                // Piece cache worker future
+                _ = farmer_cache_worker_fut.fuse() => {
+                    info!("Farmer cache worker exited.")
+                },
+            );
+
+            Ok::<_, FarmerError>(())
+        });
+
+        Ok(FarmerHandle {
+            stop_sender: Some(stop_sender),
+            join_handle: Some(AsyncJoinOnDrop::new(join_handle, true)),
+        })
+    }
+}
+
+/// Handle of the running [`Farmer`], farmer is stopped when handle is dropped
+#[derive(Debug)]
+#[must_use = "Farmer is stopped when handle is dropped"]
+pub struct FarmerHandle {
+    stop_sender: Option<oneshot::Sender<()>>,
+    join_handle: Option<AsyncJoinOnDrop<Result<(), FarmerError>>>,
+}
+
+impl FarmerHandle {
+    /// Wait for farmer to exit, either because of an error or because it was stopped, returns
+    /// immediately if farmer has already exited.
+    ///
+    /// This future is cancellation-safe.
+    pub async fn wait(&mut self) -> Result<(), FarmerError> {
+        let Some(join_handle) = &mut self.join_handle else {
+            return Ok(());
+        };
+
+        let result = join_handle.await;
+        self.join_handle.take();
+
+        result?
+    }
+
+    /// Stop farmer and wait for it to exit
+    pub async fn stop(mut self) -> Result<(), FarmerError> {
+        if let Some(stop_sender) = self.stop_sender.take() {
+            // Doesn't matter if farmer has already exited
+            let _ = stop_sender.send(());
+        }
+
+        self.wait().await
+    }
+}
+
+/// Connect to local node, relaying information from trusted node (if specified) while local node is
+/// syncing
+async fn connect_node_client(
+    node_rpc_url: &str,
+    trusted_node_rpc_url: Option<&str>,
+) -> Result<RelayNodeClient<NodeRpcClient>, FarmerError> {
+    let node_client =
+        NodeRpcClient::new(node_rpc_url)
+            .await
+            .map_err(|error| FarmerError::NodeConnection {
+                url: node_rpc_url.to_string(),
+                error: error.into(),
+            })?;
+    let trusted_node_client = match trusted_node_rpc_url {
+        Some(trusted_node_rpc_url) => {
+            debug!(url = %trusted_node_rpc_url, "Connecting to trusted node RPC");
+            Some(
+                NodeRpcClient::new(trusted_node_rpc_url)
+                    .await
+                    .map_err(|error| FarmerError::NodeConnection {
+                        url: trusted_node_rpc_url.to_string(),
+                        error: error.into(),
+                    })?,
+            )
+        }
+        None => None,
+    };
+
+    RelayNodeClient::new(node_client, trusted_node_client)
+        .await
+        .map_err(|error| FarmerError::NodeConnection {
+            url: node_rpc_url.to_string(),
+            error,
+        })
+}
+
+fn derive_libp2p_keypair(schnorrkel_sk: &schnorrkel::SecretKey) -> Keypair {
+    let mut secret_bytes = Zeroizing::new(schnorrkel_sk.to_ed25519_bytes());
+
+    let keypair = ed25519::Keypair::from(
+        ed25519::SecretKey::try_from_bytes(&mut secret_bytes.as_mut()[..32])
+            .expect("Secret key is exactly 32 bytes in size; qed"),
+    );
+
+    Keypair::from(keypair)
+}
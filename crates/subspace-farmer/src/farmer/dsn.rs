@@ -1,22 +1,28 @@
-use crate::commands::farm::DsnArgs;
+use crate::farmer::FarmerError;
+use crate::farmer_cache::FarmerCache;
+use crate::node_client::relay_node_client::RelayNodeClient;
+use crate::node_client::NodeClientExt;
+use crate::utils::plotted_pieces::PlottedPieces;
+use crate::{NodeClient, NodeRpcClient, KNOWN_PEERS_CACHE_SIZE};
 use parking_lot::Mutex;
 use prometheus_client::registry::Registry;
 use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::path::Path;
 use std::sync::{Arc, Weak};
-use subspace_farmer::farmer_cache::FarmerCache;
-use subspace_farmer::node_client::NodeClientExt;
-use subspace_farmer::utils::plotted_pieces::PlottedPieces;
-use subspace_farmer::{NodeClient, NodeRpcClient, KNOWN_PEERS_CACHE_SIZE};
 use subspace_networking::libp2p::identity::Keypair;
 use subspace_networking::libp2p::kad::RecordKey;
 use subspace_networking::libp2p::multiaddr::Protocol;
+use subspace_networking::libp2p::{Multiaddr, PeerId};
 use subspace_networking::utils::multihash::ToMultihash;
+use subspace_networking::utils::piece_request_tickets::{
+    PieceRequestPolicy, PieceRequestThrottle, DEFAULT_PIECE_REQUEST_TICKET_DIFFICULTY,
+};
 use subspace_networking::utils::strip_peer_id;
 use subspace_networking::{
     construct, Config, KademliaMode, KnownPeersManager, KnownPeersManagerConfig, Node, NodeRunner,
-    PieceByIndexRequest, PieceByIndexRequestHandler, PieceByIndexResponse,
-    SegmentHeaderBySegmentIndexesRequestHandler, SegmentHeaderRequest, SegmentHeaderResponse,
+    PieceByIndexRequestHandler, PieceByIndexResponse, SegmentHeaderBySegmentIndexesRequestHandler,
+    SegmentHeaderRequest, SegmentHeaderResponse,
 };
 use subspace_rpc_primitives::MAX_SEGMENT_HEADERS_PER_REQUEST;
 use tracing::{debug, error, info, Instrument};
@@ -25,13 +31,84 @@ use tracing::{debug, error, info, Instrument};
 ///
 /// Must be the same as RPC limit since all requests go to the node anyway.
 const SEGMENT_HEADER_NUMBER_LIMIT: u64 = MAX_SEGMENT_HEADERS_PER_REQUEST as u64;
+/// Default port for subspace networking of the farmer
+const DEFAULT_DSN_PORT: u16 = 30533;
+
+/// Options for DSN (distributed storage network) the farmer participates in
+#[derive(Debug, Clone)]
+pub struct DsnOptions {
+    /// Multiaddrs of bootstrap nodes to connect to on startup, bootstrap nodes of the node farmer
+    /// is connected to are used if empty
+    pub bootstrap_nodes: Vec<Multiaddr>,
+    /// Multiaddrs to listen on for subspace networking
+    pub listen_on: Vec<Multiaddr>,
+    /// Whether to allow keeping non-global (private, shared, loopback..) addresses in Kademlia DHT
+    pub allow_private_ips: bool,
+    /// Multiaddrs of reserved nodes to maintain a connection to
+    pub reserved_peers: Vec<Multiaddr>,
+    /// Max established incoming connection limit
+    pub in_connections: u32,
+    /// Max established outgoing swarm connection limit
+    pub out_connections: u32,
+    /// Max pending incoming connection limit
+    pub pending_in_connections: u32,
+    /// Max pending outgoing swarm connection limit
+    pub pending_out_connections: u32,
+    /// Known external addresses
+    pub external_addresses: Vec<Multiaddr>,
+    /// Whether to skip blocking Kademlia bootstrap operation before other requests
+    pub disable_bootstrap_on_start: bool,
+    /// Client-only operation: don't listen for incoming connections and don't serve requests, only
+    /// make outgoing requests
+    pub client_only: bool,
+    /// Difficulty of tickets attached to outgoing piece requests and required from incoming piece
+    /// requests to be served with priority when anonymous requests are limited
+    pub piece_request_ticket_difficulty: u8,
+    /// Max number of piece requests per minute without valid ticket that will be served (shared by
+    /// all peers), not limited if `None`
+    pub anonymous_piece_requests_limit: Option<u32>,
+    /// Peers whose piece requests are always served regardless of tickets
+    pub trusted_piece_requesters: Vec<PeerId>,
+}
+
+impl Default for DsnOptions {
+    fn default() -> Self {
+        Self {
+            bootstrap_nodes: Vec::new(),
+            listen_on: vec![
+                Multiaddr::from(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+                    .with(Protocol::Udp(DEFAULT_DSN_PORT))
+                    .with(Protocol::QuicV1),
+                Multiaddr::from(IpAddr::V6(Ipv6Addr::UNSPECIFIED))
+                    .with(Protocol::Udp(DEFAULT_DSN_PORT))
+                    .with(Protocol::QuicV1),
+                Multiaddr::from(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+                    .with(Protocol::Tcp(DEFAULT_DSN_PORT)),
+                Multiaddr::from(IpAddr::V6(Ipv6Addr::UNSPECIFIED))
+                    .with(Protocol::Tcp(DEFAULT_DSN_PORT)),
+            ],
+            allow_private_ips: false,
+            reserved_peers: Vec::new(),
+            in_connections: 300,
+            out_connections: 100,
+            pending_in_connections: 100,
+            pending_out_connections: 100,
+            external_addresses: Vec::new(),
+            disable_bootstrap_on_start: false,
+            client_only: false,
+            piece_request_ticket_difficulty: DEFAULT_PIECE_REQUEST_TICKET_DIFFICULTY,
+            anonymous_piece_requests_limit: None,
+            trusted_piece_requesters: Vec::new(),
+        }
+    }
+}
 
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub(super) fn configure_dsn(
     protocol_prefix: String,
     base_path: &Path,
     keypair: Keypair,
-    DsnArgs {
+    DsnOptions {
         listen_on,
         bootstrap_nodes,
         allow_private_ips,
@@ -42,12 +119,16 @@ pub(super) fn configure_dsn(
         pending_out_connections,
         external_addresses,
         disable_bootstrap_on_start,
-    }: DsnArgs,
+        client_only,
+        piece_request_ticket_difficulty,
+        anonymous_piece_requests_limit,
+        trusted_piece_requesters,
+    }: DsnOptions,
     weak_plotted_pieces: Weak<Mutex<Option<PlottedPieces>>>,
-    node_client: NodeRpcClient,
+    node_client: RelayNodeClient<NodeRpcClient>,
     farmer_cache: FarmerCache,
     prometheus_metrics_registry: Option<&mut Registry>,
-) -> Result<(Node, NodeRunner<FarmerCache>), anyhow::Error> {
+) -> Result<(Node, NodeRunner<FarmerCache>), FarmerError> {
     let networking_parameters_registry = KnownPeersManager::new(KnownPeersManagerConfig {
         path: Some(base_path.join("known_addresses.bin").into_boxed_path()),
         ignore_peer_list: strip_peer_id(bootstrap_nodes.clone())
@@ -59,6 +140,17 @@ pub(super) fn configure_dsn(
     })
     .map(Box::new)?;
 
+    let piece_request_throttle = Arc::new(PieceRequestThrottle::new(PieceRequestPolicy {
+        ticket_difficulty: piece_request_ticket_difficulty,
+        trusted_peers: strip_peer_id(reserved_peers.clone())
+            .into_iter()
+            .map(|(peer_id, _)| peer_id)
+            .chain(trusted_piece_requesters)
+            .collect(),
+        anonymous_requests_limit: anonymous_piece_requests_limit,
+        ..PieceRequestPolicy::default()
+    }));
+
     let default_config = Config::new(
         protocol_prefix,
         keypair,
@@ -71,13 +163,19 @@ pub(super) fn configure_dsn(
         allow_non_global_addresses_in_dht: allow_private_ips,
         networking_parameters_registry,
         request_response_protocols: vec![
-            PieceByIndexRequestHandler::create(move |_, &PieceByIndexRequest { piece_index }| {
+            PieceByIndexRequestHandler::create(move |peer_id, request| {
+                let piece_index = request.piece_index;
+                let admitted = piece_request_throttle.admit(&peer_id, request);
                 debug!(?piece_index, "Piece request received. Trying cache...");
 
                 let weak_plotted_pieces = weak_plotted_pieces.clone();
                 let farmer_cache = farmer_cache.clone();
 
                 async move {
+                    if !admitted {
+                        return None;
+                    }
+
                     let key = RecordKey::from(piece_index.to_multihash());
                     let piece_from_cache = farmer_cache.get_piece(key).await;
 
@@ -181,6 +279,7 @@ pub(super) fn configure_dsn(
         kademlia_mode: KademliaMode::Dynamic,
         external_addresses,
         disable_bootstrap_on_start,
+        client_only,
         ..default_config
     };
 
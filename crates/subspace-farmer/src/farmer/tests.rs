@@ -0,0 +1,39 @@
+use crate::farmer::{FarmOptions, Farmer, FarmerError};
+use std::assert_matches::assert_matches;
+use subspace_core_primitives::PublicKey;
+use subspace_proof_of_space::chia::ChiaTable;
+
+/// Port nothing is listening on
+const UNREACHABLE_NODE_RPC_URL: &str = "ws://127.0.0.1:1";
+
+#[tokio::test]
+async fn build_without_farms() {
+    let result = Farmer::builder(UNREACHABLE_NODE_RPC_URL.to_string(), PublicKey::default())
+        .build::<ChiaTable>(None)
+        .await;
+
+    assert_matches!(result, Err(FarmerError::NoFarms));
+}
+
+#[tokio::test]
+async fn build_with_unreachable_node() {
+    let base_directory = tempfile::tempdir().unwrap();
+    let directory = base_directory.path().join("farm");
+
+    let result = Farmer::builder(UNREACHABLE_NODE_RPC_URL.to_string(), PublicKey::default())
+        .with_farm(FarmOptions {
+            directory: directory.clone(),
+            allocated_space: 1024 * 1024 * 1024,
+            audit_prefetch: false,
+            plot_encryption: None,
+        })
+        .build::<ChiaTable>(None)
+        .await;
+
+    assert_matches!(
+        result,
+        Err(FarmerError::NodeConnection { url, .. }) if url == UNREACHABLE_NODE_RPC_URL
+    );
+    // Farm directory is created before connecting to the node
+    assert!(directory.is_dir());
+}
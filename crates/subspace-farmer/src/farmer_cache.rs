@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests;
 
+use crate::error_code::ErrorCode;
 use crate::node_client::NodeClient;
 use crate::single_disk_farm::piece_cache::{DiskPieceCache, Offset};
 use crate::utils::{run_future_in_dedicated_thread, AsyncJoinOnDrop};
@@ -11,6 +12,7 @@ use futures::{select, FutureExt, StreamExt};
 use parking_lot::RwLock;
 use std::collections::{HashMap, VecDeque};
 use std::num::NonZeroU16;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::{fmt, mem};
@@ -32,6 +34,8 @@ const INTERMEDIATE_CACHE_UPDATE_INTERVAL: usize = 100;
 /// Get piece retry attempts number.
 const PIECE_GETTER_RETRY_NUMBER: NonZeroU16 = NonZeroU16::new(4).expect("Not zero; qed");
 const INITIAL_SYNC_FARM_INFO_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// At most `1 / N` of cache capacity is used for pieces prioritized for local plotting
+const PRIORITIZED_PIECES_MAX_CACHE_SHARE: usize = 2;
 
 type HandlerFn<A> = Arc<dyn Fn(&A) + Send + Sync + 'static>;
 type Handler<A> = Bag<HandlerFn<A>, A>;
@@ -75,6 +79,7 @@ where
     peer_id: PeerId,
     node_client: NC,
     caches: Arc<RwLock<Vec<DiskPieceCacheState>>>,
+    prioritized_piece_indices: Arc<RwLock<Vec<PieceIndex>>>,
     handlers: Arc<Handlers>,
     worker_receiver: Option<mpsc::Receiver<WorkerCommand>>,
 }
@@ -119,7 +124,11 @@ where
             match self.node_client.subscribe_archived_segment_headers().await {
                 Ok(segment_headers_notifications) => segment_headers_notifications,
                 Err(error) => {
-                    error!(%error, "Failed to subscribe to archived segments notifications");
+                    error!(
+                        code = %ErrorCode::NodeRequestFailed,
+                        %error,
+                        "Failed to subscribe to archived segments notifications"
+                    );
                     return;
                 }
             };
@@ -197,6 +206,7 @@ where
                         }
                         Err(error) => {
                             error!(
+                                code = %ErrorCode::CacheReadFailed,
                                 %error,
                                 %disk_farm_index,
                                 ?key,
@@ -325,6 +335,7 @@ where
                 }
                 Err(error) => {
                     error!(
+                        code = %ErrorCode::NodeRequestFailed,
                         %error,
                         "Failed to get farmer app info from node, keeping old cache state without \
                         updates"
@@ -341,14 +352,27 @@ where
 
         debug!(%last_segment_index, "Identified last segment index");
 
-        worker_state.heap.clear();
-        // Change limit to number of pieces
-        worker_state.heap.set_limit(
-            caches
+        let capacity = caches
+            .iter()
+            .map(|state| state.stored_pieces.len() + state.free_offsets.len())
+            .sum::<usize>();
+        let prioritized_piece_indices = {
+            let last_piece_index = last_segment_index.last_piece_index();
+
+            self.prioritized_piece_indices
+                .read()
                 .iter()
-                .map(|state| state.stored_pieces.len() + state.free_offsets.len())
-                .sum::<usize>(),
-        );
+                .copied()
+                .filter(|&piece_index| piece_index <= last_piece_index)
+                .take(capacity / PRIORITIZED_PIECES_MAX_CACHE_SHARE)
+                .collect::<Vec<_>>()
+        };
+
+        worker_state.heap.clear();
+        // Change limit to number of pieces, leaving space for prioritized pieces
+        worker_state
+            .heap
+            .set_limit(capacity - prioritized_piece_indices.len());
 
         for segment_index in SegmentIndex::ZERO..=last_segment_index {
             for piece_index in segment_index.segment_piece_indexes() {
@@ -366,6 +390,13 @@ where
                 (RecordKey::from(piece_index.to_multihash()), *piece_index)
             })
             .collect::<HashMap<_, _>>();
+        // Prioritized pieces are not tracked by the heap, they occupy space the heap leaves for
+        // them until the next initialization
+        piece_indices_to_store.extend(
+            prioritized_piece_indices
+                .iter()
+                .map(|&piece_index| (RecordKey::from(piece_index.to_multihash()), piece_index)),
+        );
 
         caches.iter_mut().for_each(|state| {
             // Filter-out piece indices that are stored, but should not be as well as clean
@@ -387,7 +418,16 @@ where
             "Identified piece indices that should be cached",
         );
 
-        let mut piece_indices_to_store = piece_indices_to_store.into_values();
+        // Download prioritized pieces first, such that they are available for plotting sooner
+        let mut ordered_piece_indices_to_store = prioritized_piece_indices
+            .into_iter()
+            .filter_map(|piece_index| {
+                piece_indices_to_store.remove(&RecordKey::from(piece_index.to_multihash()))
+            })
+            .collect::<Vec<_>>();
+        ordered_piece_indices_to_store.extend(piece_indices_to_store.into_values());
+
+        let mut piece_indices_to_store = ordered_piece_indices_to_store.into_iter();
 
         let download_piece = |piece_index| async move {
             trace!(%piece_index, "Downloading piece");
@@ -447,6 +487,7 @@ where
 
                 if let Err(error) = cache.backend.write_piece(offset, piece_index, &piece) {
                     error!(
+                        code = %ErrorCode::CacheWriteFailed,
                         %error,
                         %disk_farm_index,
                         %piece_index,
@@ -461,6 +502,7 @@ where
                 true
             }) {
                 error!(
+                    code = %ErrorCode::CacheFull,
                     %piece_index,
                     "Failed to store piece in cache, there was no space"
                 );
@@ -585,7 +627,12 @@ where
                 debug!(%segment_index, "Acknowledged archived segment");
             }
             Err(error) => {
-                error!(%segment_index, ?error, "Failed to acknowledge archived segment");
+                error!(
+                    code = %ErrorCode::NodeRequestFailed,
+                    %segment_index,
+                    ?error,
+                    "Failed to acknowledge archived segment"
+                );
             }
         };
     }
@@ -601,6 +648,7 @@ where
             Ok(farmer_app_info) => farmer_app_info.protocol_info.history_size.segment_index(),
             Err(error) => {
                 error!(
+                    code = %ErrorCode::NodeRequestFailed,
                     %error,
                     "Failed to get farmer app info from node, keeping old cache state without \
                     updates"
@@ -688,6 +736,7 @@ where
 
                     if let Err(error) = cache.backend.write_piece(offset, piece_index, &piece) {
                         error!(
+                            code = %ErrorCode::CacheWriteFailed,
                             %error,
                             %disk_farm_index,
                             %piece_index,
@@ -728,6 +777,7 @@ where
 
                     if let Err(error) = cache.backend.write_piece(offset, piece_index, &piece) {
                         error!(
+                            code = %ErrorCode::CacheWriteFailed,
                             %error,
                             %disk_farm_index,
                             %piece_index,
@@ -763,6 +813,10 @@ pub struct FarmerCache {
     /// Individual disk caches where pieces are stored
     caches: Arc<RwLock<Vec<DiskPieceCacheState>>>,
     handlers: Arc<Handlers>,
+    /// Whether cached pieces are announced to other peers as provider records
+    announce_pieces: Arc<AtomicBool>,
+    /// Pieces needed for plotting of local sectors soon, ordered by priority
+    prioritized_piece_indices: Arc<RwLock<Vec<PieceIndex>>>,
     // We do not want to increase capacity unnecessarily on clone
     worker_sender: Arc<mpsc::Sender<WorkerCommand>>,
 }
@@ -777,6 +831,7 @@ impl FarmerCache {
         NC: NodeClient,
     {
         let caches = Arc::default();
+        let prioritized_piece_indices = Arc::default();
         let (worker_sender, worker_receiver) = mpsc::channel(WORKER_CHANNEL_CAPACITY);
         let handlers = Arc::new(Handlers::default());
 
@@ -784,12 +839,15 @@ impl FarmerCache {
             peer_id,
             caches: Arc::clone(&caches),
             handlers: Arc::clone(&handlers),
+            announce_pieces: Arc::new(AtomicBool::new(true)),
+            prioritized_piece_indices: Arc::clone(&prioritized_piece_indices),
             worker_sender: Arc::new(worker_sender),
         };
         let worker = FarmerCacheWorker {
             peer_id,
             node_client,
             caches,
+            prioritized_piece_indices,
             handlers,
             worker_receiver: Some(worker_receiver),
         };
@@ -815,6 +873,7 @@ impl FarmerCache {
                         }
                         Err(error) => {
                             error!(
+                                code = %ErrorCode::CacheReadFailed,
                                 %error,
                                 %disk_farm_index,
                                 ?key,
@@ -871,10 +930,32 @@ impl FarmerCache {
     pub fn on_sync_progress(&self, callback: HandlerFn<f32>) -> HandlerId {
         self.handlers.progress.add(callback)
     }
+
+    /// Set pieces that will be needed for plotting of local sectors soon, ordered by priority.
+    ///
+    /// Such pieces are cached in addition to pieces the cache would normally store (using up to
+    /// half of its capacity), such that plotting doesn't need to download them from the network.
+    /// Takes effect on the next initialization of the cache, see [`Self::replace_backing_caches()`].
+    pub fn set_prioritized_pieces(&self, piece_indices: Vec<PieceIndex>) {
+        *self.prioritized_piece_indices.write() = piece_indices;
+    }
+
+    /// Whether cached pieces should be announced to other peers as provider records.
+    ///
+    /// Announcing makes sense only when farmer is publicly reachable, otherwise peers will be
+    /// directed to a farmer they can't connect to. Pieces are announced by default.
+    pub fn set_announce_pieces(&self, announce_pieces: bool) {
+        self.announce_pieces
+            .store(announce_pieces, Ordering::Relaxed);
+    }
 }
 
 impl LocalRecordProvider for FarmerCache {
     fn record(&self, key: &RecordKey) -> Option<ProviderRecord> {
+        if !self.announce_pieces.load(Ordering::Relaxed) {
+            return None;
+        }
+
         // It is okay to take read lock here, writes locks are very infrequent and very short
         for cache in self.caches.read().iter() {
             if cache.stored_pieces.contains_key(key) {
@@ -22,6 +22,7 @@ use subspace_networking::utils::multihash::ToMultihash;
 use subspace_rpc_primitives::{
     FarmerAppInfo, RewardSignatureResponse, RewardSigningInfo, SlotInfo, SolutionResponse,
 };
+use subspace_temp_files::TempFileManager;
 use tempfile::tempdir;
 
 #[derive(Debug, Clone)]
@@ -193,8 +194,18 @@ async fn basic() {
 
         let initialized_fut = farmer_cache
             .replace_backing_caches(vec![
-                DiskPieceCache::open(path1.as_ref(), 1).unwrap(),
-                DiskPieceCache::open(path2.as_ref(), 1).unwrap(),
+                DiskPieceCache::open(
+                    path1.as_ref(),
+                    1,
+                    TempFileManager::open(path1.as_ref()).unwrap(),
+                )
+                .unwrap(),
+                DiskPieceCache::open(
+                    path2.as_ref(),
+                    1,
+                    TempFileManager::open(path2.as_ref()).unwrap(),
+                )
+                .unwrap(),
             ])
             .await;
 
@@ -376,8 +387,18 @@ async fn basic() {
         // Reopen with the same backing caches
         let initialized_fut = farmer_cache
             .replace_backing_caches(vec![
-                DiskPieceCache::open(path1.as_ref(), 1).unwrap(),
-                DiskPieceCache::open(path2.as_ref(), 1).unwrap(),
+                DiskPieceCache::open(
+                    path1.as_ref(),
+                    1,
+                    TempFileManager::open(path1.as_ref()).unwrap(),
+                )
+                .unwrap(),
+                DiskPieceCache::open(
+                    path2.as_ref(),
+                    1,
+                    TempFileManager::open(path2.as_ref()).unwrap(),
+                )
+                .unwrap(),
             ])
             .await;
         drop(farmer_cache);
@@ -402,3 +423,98 @@ async fn basic() {
         farmer_cache_worker_exited.await.unwrap();
     }
 }
+
+#[tokio::test]
+async fn prioritized_pieces() {
+    let current_segment_index = Arc::new(AtomicU64::new(0));
+    let pieces = Arc::default();
+    let (
+        archived_segment_headers_stream_request_sender,
+        mut archived_segment_headers_stream_request_receiver,
+    ) = mpsc::channel(0);
+    let (acknowledge_archived_segment_header_sender, _acknowledge_archived_segment_header_receiver) =
+        mpsc::channel(0);
+
+    let node_client = MockNodeClient {
+        current_segment_index: Arc::clone(&current_segment_index),
+        pieces: Arc::clone(&pieces),
+        archived_segment_headers_stream_request_sender,
+        acknowledge_archived_segment_header_sender,
+    };
+    let piece_getter = MockPieceGetter {
+        pieces: Arc::clone(&pieces),
+    };
+    let public_key =
+        identity::PublicKey::from(identity::ed25519::PublicKey::try_from_bytes(&[42; 32]).unwrap());
+    let path1 = tempdir().unwrap();
+    let path2 = tempdir().unwrap();
+
+    let (farmer_cache, farmer_cache_worker) =
+        FarmerCache::new(node_client.clone(), public_key.to_peer_id());
+
+    let farmer_cache_worker_exited = tokio::spawn(farmer_cache_worker.run(piece_getter));
+
+    // Piece that is not archived yet is ignored, the rest is limited by half of cache capacity
+    farmer_cache.set_prioritized_pieces(vec![
+        PieceIndex::from(1000),
+        PieceIndex::from(10),
+        PieceIndex::from(11),
+    ]);
+
+    let initialized_fut = farmer_cache
+        .replace_backing_caches(vec![
+            DiskPieceCache::open(
+                path1.as_ref(),
+                1,
+                TempFileManager::open(path1.as_ref()).unwrap(),
+            )
+            .unwrap(),
+            DiskPieceCache::open(
+                path2.as_ref(),
+                1,
+                TempFileManager::open(path2.as_ref()).unwrap(),
+            )
+            .unwrap(),
+        ])
+        .await;
+
+    // Wait for piece cache to be initialized
+    initialized_fut.await.unwrap();
+
+    {
+        let requested_pieces = pieces.lock().keys().copied().collect::<Vec<_>>();
+        assert_eq!(requested_pieces.len(), 2);
+        // Prioritized piece is cached in addition to the closest piece cache would normally store
+        assert!(requested_pieces.contains(&PieceIndex::from(10)));
+        assert!(
+            requested_pieces.contains(&PieceIndex::from(26))
+                || requested_pieces.contains(&PieceIndex::from(196))
+        );
+
+        for piece_index in requested_pieces {
+            farmer_cache
+                .get_piece(RecordKey::from(piece_index.to_multihash()))
+                .await
+                .unwrap();
+        }
+
+        assert!(farmer_cache
+            .get_piece(RecordKey::from(PieceIndex::from(11).to_multihash()))
+            .await
+            .is_none());
+    }
+
+    drop(farmer_cache);
+
+    let (mut archived_segment_headers_sender, archived_segment_headers_receiver) = mpsc::channel(0);
+    archived_segment_headers_stream_request_receiver
+        .next()
+        .await
+        .unwrap()
+        .send(archived_segment_headers_receiver)
+        .unwrap();
+    // Make worker exit
+    archived_segment_headers_sender.close().await.unwrap();
+
+    farmer_cache_worker_exited.await.unwrap();
+}
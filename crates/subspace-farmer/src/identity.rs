@@ -1,5 +1,13 @@
-use parity_scale_codec::{Decode, Encode};
+#[cfg(test)]
+mod tests;
+
+use bip39::Mnemonic;
+use blake2::digest::typenum::U32;
+use blake2::digest::FixedOutput;
+use blake2::{Blake2b, Digest};
+use parity_scale_codec::{Decode, Encode, Input, Output};
 use schnorrkel::context::SigningContext;
+use schnorrkel::derive::ChainCode;
 use schnorrkel::{ExpansionMode, Keypair, PublicKey, SecretKey, Signature};
 use std::ops::Deref;
 use std::path::Path;
@@ -12,16 +20,116 @@ use zeroize::Zeroizing;
 
 /// Entropy used for identity generation.
 const ENTROPY_LENGTH: usize = 32;
+/// Maximum length of the derivation path in bytes.
+pub const MAX_DERIVATION_PATH_LENGTH: usize = 256;
+/// Length of the chain code of a single derivation path junction.
+const JUNCTION_LENGTH: usize = 32;
 
-#[derive(Debug, Encode, Decode)]
+#[derive(Debug)]
 struct IdentityFileContents {
     entropy: Vec<u8>,
+    derivation_path: Option<String>,
+}
+
+// Identity files without derivation path only contain entropy, the same way as files created
+// before derivation paths were introduced, hence derivation path is encoded only if present
+impl Encode for IdentityFileContents {
+    fn size_hint(&self) -> usize {
+        self.entropy.size_hint()
+            + self
+                .derivation_path
+                .as_ref()
+                .map_or(0, |derivation_path| Some(derivation_path).size_hint())
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        self.entropy.encode_to(dest);
+        if let Some(derivation_path) = &self.derivation_path {
+            Some(derivation_path).encode_to(dest);
+        }
+    }
+}
+
+// Derivation path is decoded only if there are bytes left, see `Encode` implementation above
+impl Decode for IdentityFileContents {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        let entropy = Vec::<u8>::decode(input)?;
+        let derivation_path = if input.remaining_len()? == Some(0) {
+            None
+        } else {
+            Option::<String>::decode(input)?
+        };
+
+        Ok(Self {
+            entropy,
+            derivation_path,
+        })
+    }
+}
+
+/// Parses derivation path that consists of hard junctions only (like `//farm//1`) into chain
+/// codes, the same way Substrate does for sr25519 keys.
+fn parse_derivation_path(
+    derivation_path: &str,
+) -> Result<Vec<[u8; JUNCTION_LENGTH]>, IdentityError> {
+    let invalid_derivation_path = || IdentityError::InvalidDerivationPath {
+        derivation_path: derivation_path.to_string(),
+    };
+
+    if derivation_path.len() > MAX_DERIVATION_PATH_LENGTH {
+        return Err(invalid_derivation_path());
+    }
+
+    derivation_path
+        .strip_prefix("//")
+        .ok_or_else(invalid_derivation_path)?
+        .split("//")
+        .map(|junction| {
+            if junction.is_empty() || junction.contains('/') {
+                return Err(invalid_derivation_path());
+            }
+
+            let encoded_junction = match junction.parse::<u64>() {
+                Ok(index) => index.encode(),
+                Err(_) => junction.encode(),
+            };
+
+            let mut chain_code = [0; JUNCTION_LENGTH];
+            if encoded_junction.len() > JUNCTION_LENGTH {
+                let mut hasher = Blake2b::<U32>::new();
+                hasher.update(&encoded_junction);
+                chain_code.copy_from_slice(&hasher.finalize_fixed());
+            } else {
+                chain_code[..encoded_junction.len()].copy_from_slice(&encoded_junction);
+            }
+
+            Ok(chain_code)
+        })
+        .collect()
 }
 
-fn keypair_from_entropy(entropy: &[u8]) -> Keypair {
-    mini_secret_from_entropy(entropy, "")
-        .expect("32 bytes can always build a key; qed")
-        .expand_to_keypair(ExpansionMode::Ed25519)
+fn keypair_from_entropy(
+    entropy: &[u8],
+    derivation_path: Option<&str>,
+) -> Result<Keypair, IdentityError> {
+    let mini_secret_key =
+        mini_secret_from_entropy(entropy, "").expect("32 bytes can always build a key; qed");
+
+    let Some(derivation_path) = derivation_path else {
+        return Ok(mini_secret_key.expand_to_keypair(ExpansionMode::Ed25519));
+    };
+
+    let secret_key = parse_derivation_path(derivation_path)?.into_iter().fold(
+        mini_secret_key.expand(ExpansionMode::Ed25519),
+        |secret_key, chain_code| {
+            secret_key
+                .hard_derive_mini_secret_key(Some(ChainCode(chain_code)), b"")
+                .0
+                .expand(ExpansionMode::Ed25519)
+        },
+    );
+
+    Ok(secret_key.to_keypair())
 }
 
 /// Errors happening when trying to create/open single disk farm
@@ -33,6 +141,21 @@ pub enum IdentityError {
     /// Decoding error
     #[error("Decoding error: {0}")]
     Decoding(#[from] parity_scale_codec::Error),
+    /// Invalid mnemonic
+    #[error("Invalid mnemonic: {0}")]
+    Mnemonic(#[from] bip39::Error),
+    /// Invalid derivation path
+    #[error(
+        "Invalid derivation path {derivation_path}, only hard junctions like `//farm//1` up to \
+        {MAX_DERIVATION_PATH_LENGTH} bytes are supported"
+    )]
+    InvalidDerivationPath {
+        /// Derivation path
+        derivation_path: String,
+    },
+    /// Identity already exists
+    #[error("Identity already exists, refusing to override it")]
+    AlreadyExists,
 }
 
 /// `Identity` struct is an abstraction of public & secret key related operations.
@@ -43,6 +166,7 @@ pub enum IdentityError {
 pub struct Identity {
     keypair: Zeroizing<Keypair>,
     entropy: Zeroizing<Vec<u8>>,
+    derivation_path: Option<String>,
     substrate_ctx: SigningContext,
 }
 
@@ -58,10 +182,15 @@ impl Deref for Identity {
 impl Identity {
     pub(crate) const FILE_NAME: &'static str = "identity.bin";
 
-    /// Size of the identity file on disk
+    /// Size of the identity file on disk.
+    ///
+    /// Identity with derivation path takes slightly more space, this is not accounted for since
+    /// the size is a part of fixed space usage of the farm, changing it would change the number
+    /// of sectors and cache size of existing farms.
     pub fn file_size() -> usize {
         IdentityFileContents {
             entropy: vec![0; ENTROPY_LENGTH],
+            derivation_path: None,
         }
         .encoded_size()
     }
@@ -81,14 +210,9 @@ impl Identity {
         if identity_file.exists() {
             debug!("Opening existing keypair");
             let bytes = Zeroizing::new(fs::read(identity_file)?);
-            let IdentityFileContents { entropy } =
-                IdentityFileContents::decode(&mut bytes.as_ref())?;
-
-            Ok(Some(Self {
-                keypair: Zeroizing::new(keypair_from_entropy(&entropy)),
-                entropy: Zeroizing::new(entropy),
-                substrate_ctx: schnorrkel::context::signing_context(REWARD_SIGNING_CONTEXT),
-            }))
+            let identity_file_contents = IdentityFileContents::decode(&mut bytes.as_ref())?;
+
+            Self::from_file_contents(identity_file_contents).map(Some)
         } else {
             debug!("Existing keypair not found");
             Ok(None)
@@ -101,16 +225,38 @@ impl Identity {
         debug!("Generating new keypair");
         let entropy = rand::random::<[u8; ENTROPY_LENGTH]>().to_vec();
 
-        let identity_file_contents = IdentityFileContents { entropy };
+        let identity_file_contents = IdentityFileContents {
+            entropy,
+            derivation_path: None,
+        };
         fs::write(identity_file, identity_file_contents.encode())?;
 
-        let IdentityFileContents { entropy } = identity_file_contents;
+        Self::from_file_contents(identity_file_contents)
+    }
 
-        Ok(Self {
-            keypair: Zeroizing::new(keypair_from_entropy(&entropy)),
-            entropy: Zeroizing::new(entropy),
-            substrate_ctx: schnorrkel::context::signing_context(REWARD_SIGNING_CONTEXT),
-        })
+    /// Restores identity from BIP39 mnemonic with optional derivation path (like `//farm//1`),
+    /// refuses to override identity that already exists.
+    pub fn from_mnemonic<B: AsRef<Path>>(
+        base_directory: B,
+        mnemonic: &str,
+        derivation_path: Option<String>,
+    ) -> Result<Self, IdentityError> {
+        let identity_file = base_directory.as_ref().join(Self::FILE_NAME);
+        if identity_file.exists() {
+            return Err(IdentityError::AlreadyExists);
+        }
+        debug!("Restoring keypair from mnemonic");
+
+        let identity_file_contents = IdentityFileContents {
+            entropy: Mnemonic::parse(mnemonic)?.to_entropy(),
+            derivation_path,
+        };
+        let encoded_identity_file_contents = Zeroizing::new(identity_file_contents.encode());
+        // Check derivation path before writing anything to disk
+        let identity = Self::from_file_contents(identity_file_contents)?;
+        fs::write(identity_file, encoded_identity_file_contents.as_slice())?;
+
+        Ok(identity)
     }
 
     /// Create identity from given entropy, overrides identity that might already exist.
@@ -124,14 +270,27 @@ impl Identity {
         let identity_file = base_directory.as_ref().join(Self::FILE_NAME);
         debug!("Creating identity from provided entropy");
 
-        let identity_file_contents = IdentityFileContents { entropy };
+        let identity_file_contents = IdentityFileContents {
+            entropy,
+            derivation_path: None,
+        };
         fs::write(identity_file, identity_file_contents.encode())?;
 
-        let IdentityFileContents { entropy } = identity_file_contents;
+        Self::from_file_contents(identity_file_contents)
+    }
+
+    fn from_file_contents(
+        identity_file_contents: IdentityFileContents,
+    ) -> Result<Self, IdentityError> {
+        let IdentityFileContents {
+            entropy,
+            derivation_path,
+        } = identity_file_contents;
 
         Ok(Self {
-            keypair: Zeroizing::new(keypair_from_entropy(&entropy)),
+            keypair: Zeroizing::new(keypair_from_entropy(&entropy, derivation_path.as_deref())?),
             entropy: Zeroizing::new(entropy),
+            derivation_path,
             substrate_ctx: schnorrkel::context::signing_context(REWARD_SIGNING_CONTEXT),
         })
     }
@@ -151,6 +310,25 @@ impl Identity {
         &self.entropy
     }
 
+    /// Returns derivation path applied to the entropy, if any.
+    pub fn derivation_path(&self) -> Option<&str> {
+        self.derivation_path.as_deref()
+    }
+
+    /// Returns BIP39 mnemonic corresponding to the entropy of this identity.
+    ///
+    /// NOTE: Mnemonic is not enough to restore identity if derivation path is used.
+    pub fn mnemonic(&self) -> Zeroizing<String> {
+        Zeroizing::new(
+            Mnemonic::from_entropy(&self.entropy)
+                .expect(
+                    "Entropy is either generated with valid mnemonic length or restored from a \
+                    valid mnemonic; qed",
+                )
+                .to_string(),
+        )
+    }
+
     /// Sign reward hash.
     pub fn sign_reward_hash(&self, header_hash: &[u8]) -> Signature {
         self.keypair.sign(self.substrate_ctx.bytes(header_hash))
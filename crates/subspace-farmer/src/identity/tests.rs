@@ -0,0 +1,93 @@
+use crate::identity::{Identity, IdentityError, IdentityFileContents};
+use parity_scale_codec::{Decode, Encode};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn mnemonic_round_trip() {
+    let directory = tempdir().unwrap();
+    let identity = Identity::create(directory.path()).unwrap();
+    let mnemonic = identity.mnemonic();
+
+    let restored_directory = tempdir().unwrap();
+    let restored_identity =
+        Identity::from_mnemonic(restored_directory.path(), &mnemonic, None).unwrap();
+    assert_eq!(identity.public_key(), restored_identity.public_key());
+
+    let opened_identity = Identity::open(restored_directory.path()).unwrap().unwrap();
+    assert_eq!(identity.public_key(), opened_identity.public_key());
+
+    // Existing identity must not be overridden
+    assert!(matches!(
+        Identity::from_mnemonic(restored_directory.path(), &mnemonic, None),
+        Err(IdentityError::AlreadyExists)
+    ));
+}
+
+#[test]
+fn derivation_paths() {
+    let directory = tempdir().unwrap();
+    let identity = Identity::create(directory.path()).unwrap();
+    let mnemonic = identity.mnemonic();
+
+    let farm_1_directory = tempdir().unwrap();
+    let farm_1_identity = Identity::from_mnemonic(
+        farm_1_directory.path(),
+        &mnemonic,
+        Some("//farm//1".to_string()),
+    )
+    .unwrap();
+    let farm_2_directory = tempdir().unwrap();
+    let farm_2_identity = Identity::from_mnemonic(
+        farm_2_directory.path(),
+        &mnemonic,
+        Some("//farm//2".to_string()),
+    )
+    .unwrap();
+    assert_ne!(identity.public_key(), farm_1_identity.public_key());
+    assert_ne!(farm_1_identity.public_key(), farm_2_identity.public_key());
+    // Derived identity exports the same mnemonic
+    assert_eq!(farm_1_identity.mnemonic(), mnemonic);
+
+    let opened_identity = Identity::open(farm_1_directory.path()).unwrap().unwrap();
+    assert_eq!(farm_1_identity.public_key(), opened_identity.public_key());
+    assert_eq!(opened_identity.derivation_path(), Some("//farm//1"));
+
+    for invalid_derivation_path in ["", "/farm", "//", "//farm/1", "//farm//"] {
+        let directory = tempdir().unwrap();
+        assert!(matches!(
+            Identity::from_mnemonic(
+                directory.path(),
+                &mnemonic,
+                Some(invalid_derivation_path.to_string())
+            ),
+            Err(IdentityError::InvalidDerivationPath { .. })
+        ));
+        // Nothing is written on failure
+        assert!(!directory.path().join(Identity::FILE_NAME).exists());
+    }
+}
+
+#[test]
+fn legacy_identity_file() {
+    let entropy = vec![1; 32];
+    // Identity files used to contain just entropy
+    let legacy_contents = entropy.encode();
+
+    let contents = IdentityFileContents::decode(&mut legacy_contents.as_slice()).unwrap();
+    assert_eq!(contents.entropy, entropy);
+    assert_eq!(contents.derivation_path, None);
+
+    let directory = tempdir().unwrap();
+    let identity = Identity::from_entropy(directory.path(), entropy).unwrap();
+    // Identity without derivation path is stored in legacy format and its size, which is a part
+    // of farm layout, is unchanged
+    assert_eq!(
+        fs::read(directory.path().join(Identity::FILE_NAME)).unwrap(),
+        legacy_contents
+    );
+    assert_eq!(Identity::file_size(), legacy_contents.len());
+    fs::write(directory.path().join(Identity::FILE_NAME), legacy_contents).unwrap();
+    let opened_identity = Identity::open(directory.path()).unwrap().unwrap();
+    assert_eq!(identity.public_key(), opened_identity.public_key());
+}
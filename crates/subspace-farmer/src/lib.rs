@@ -36,9 +36,13 @@
 //! are `target ± ½ * solution range` (while also handing overflow/underflow) when interpreted as
 //! 64-bit unsigned integers.
 
+pub mod error_code;
+pub mod farmer;
 pub mod farmer_cache;
 pub(crate) mod identity;
+pub mod monitoring;
 pub mod node_client;
+pub mod remote_plotter;
 pub mod reward_signing;
 pub mod single_disk_farm;
 pub mod thread_pool_manager;
@@ -47,7 +51,7 @@ pub mod utils;
 /// Size of the LRU cache for peers.
 pub const KNOWN_PEERS_CACHE_SIZE: NonZeroUsize = NonZeroUsize::new(100).expect("Not zero; qed");
 
-pub use identity::Identity;
+pub use identity::{Identity, IdentityError};
 pub use jsonrpsee;
 pub use node_client::node_rpc_client::NodeRpcClient;
 pub use node_client::{Error as RpcClientError, NodeClient};
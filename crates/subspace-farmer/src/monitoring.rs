@@ -0,0 +1,288 @@
+//! Remote monitoring of farms.
+//!
+//! Farmers periodically publish summaries of their farms to an aggregation server using JSON-RPC
+//! over HTTP, which allows operators running farmers on multiple machines to observe all of their
+//! farms in one place. Reports are authenticated with a token shared between farmers and
+//! aggregation server, token itself is never sent over the network, instead reports are MACed
+//! with a key derived from it.
+
+#[cfg(test)]
+mod tests;
+
+use crate::single_disk_farm::farming::FarmingNotification;
+use crate::single_disk_farm::{
+    SectorPlottingDetails, SectorUpdate, SingleDiskFarm, SingleDiskFarmId,
+};
+use jsonrpsee::core::{async_trait, Error as JsonRpseeError, RpcResult};
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::server::{ServerBuilder, ServerHandle};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use subspace_core_primitives::{SectorIndex, SlotNumber};
+use tracing::{debug, warn};
+
+/// Size of MAC attached to reports
+const REPORT_MAC_SIZE: usize = 32;
+
+/// Summary of a single farm
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmSummary {
+    /// ID of the farm
+    pub farm_id: SingleDiskFarmId,
+    /// Space allocated to the farm in bytes
+    pub allocated_space: u64,
+    /// Total number of sectors in the farm
+    pub total_sectors: SectorIndex,
+    /// Number of sectors plotted so far
+    pub plotted_sectors: SectorIndex,
+    /// Last slot for which farm found a solution
+    pub last_winning_slot: Option<SlotNumber>,
+    /// Number of non-fatal farming errors since farmer start
+    pub errors: u64,
+    /// Last non-fatal farming error
+    pub last_error: Option<String>,
+}
+
+/// Report published by a farmer
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmerReport {
+    /// Name of the farmer, must be unique among farmers reporting to the same aggregation server
+    pub farmer_name: String,
+    /// Unix timestamp (in seconds) at which report was created
+    pub timestamp: u64,
+    /// Summaries of individual farms
+    pub farms: Vec<FarmSummary>,
+}
+
+/// [`FarmerReport`] authenticated with monitoring token
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatedFarmerReport {
+    /// Report itself
+    pub report: FarmerReport,
+    /// MAC of the report
+    #[serde(with = "hex")]
+    pub mac: [u8; REPORT_MAC_SIZE],
+}
+
+impl AuthenticatedFarmerReport {
+    /// Authenticate report with monitoring token
+    pub fn new(report: FarmerReport, token: &str) -> Self {
+        let mac = report_mac(&report, token);
+
+        Self { report, mac }
+    }
+
+    /// Check that report was authenticated with the same monitoring token, returns report if it
+    /// was
+    pub fn verify(self, token: &str) -> Option<FarmerReport> {
+        (report_mac(&self.report, token) == self.mac).then_some(self.report)
+    }
+}
+
+fn report_mac(report: &FarmerReport, token: &str) -> [u8; REPORT_MAC_SIZE] {
+    let key = blake3::derive_key("subspace-farmer monitoring report", token.as_bytes());
+    let report = serde_json::to_vec(report).expect("Report serialization never fails; qed");
+
+    *blake3::keyed_hash(&key, &report).as_bytes()
+}
+
+/// Monitoring RPC API exposed by aggregation server
+#[rpc(client, server)]
+pub trait MonitoringRpcApi {
+    /// Submit farmer report
+    #[method(name = "monitoring_submitFarmerReport")]
+    fn submit_farmer_report(&self, report: AuthenticatedFarmerReport) -> RpcResult<()>;
+
+    /// Latest reports of all farmers known to aggregation server
+    #[method(name = "monitoring_farmerReports")]
+    fn farmer_reports(&self) -> RpcResult<Vec<FarmerReport>>;
+}
+
+/// Aggregation server that collects reports from farmers
+#[derive(Debug, Clone)]
+pub struct MonitoringAggregator {
+    token: Arc<str>,
+    reports: Arc<Mutex<BTreeMap<String, FarmerReport>>>,
+}
+
+#[async_trait]
+impl MonitoringRpcApiServer for MonitoringAggregator {
+    fn submit_farmer_report(&self, report: AuthenticatedFarmerReport) -> RpcResult<()> {
+        let Some(report) = report.verify(&self.token) else {
+            return Err(JsonRpseeError::Custom(
+                "Report is not authenticated with correct token".to_string(),
+            ));
+        };
+
+        let mut reports = self.reports.lock();
+        if let Some(existing_report) = reports.get(&report.farmer_name) {
+            // Prevents replaying of old reports
+            if existing_report.timestamp >= report.timestamp {
+                return Err(JsonRpseeError::Custom(
+                    "Report is not newer than already known report".to_string(),
+                ));
+            }
+        }
+        debug!(farmer_name = %report.farmer_name, "Received farmer report");
+        reports.insert(report.farmer_name.clone(), report);
+
+        Ok(())
+    }
+
+    fn farmer_reports(&self) -> RpcResult<Vec<FarmerReport>> {
+        Ok(self.reports())
+    }
+}
+
+impl MonitoringAggregator {
+    /// Create new aggregator accepting reports authenticated with `token`
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: Arc::from(token),
+            reports: Arc::default(),
+        }
+    }
+
+    /// Latest reports of all farmers
+    pub fn reports(&self) -> Vec<FarmerReport> {
+        self.reports.lock().values().cloned().collect()
+    }
+}
+
+/// Start aggregation server on specified address, returns address server is listening on
+/// (useful when port `0` was used) and handle to stop it.
+pub async fn start_aggregation_server(
+    listen_on: SocketAddr,
+    aggregator: MonitoringAggregator,
+) -> Result<(SocketAddr, ServerHandle), JsonRpseeError> {
+    let server = ServerBuilder::default().build(listen_on).await?;
+    let address = server.local_addr()?;
+    let handle = server.start(aggregator.into_rpc())?;
+
+    Ok((address, handle))
+}
+
+/// Collects information about farms and publishes reports to aggregation server
+#[derive(Debug, Clone)]
+pub struct FarmMonitor {
+    farmer_name: String,
+    farms: Arc<Mutex<HashMap<SingleDiskFarmId, FarmSummary>>>,
+}
+
+impl FarmMonitor {
+    /// Create new monitor for farmer with specified name
+    pub fn new(farmer_name: String) -> Self {
+        Self {
+            farmer_name,
+            farms: Arc::default(),
+        }
+    }
+
+    /// Start tracking of farm, must be called before farm starts running
+    pub fn add_farm(&self, single_disk_farm: &SingleDiskFarm, plotted_sectors: SectorIndex) {
+        let farm_id = *single_disk_farm.id();
+
+        self.farms.lock().insert(
+            farm_id,
+            FarmSummary {
+                farm_id,
+                allocated_space: single_disk_farm.info().allocated_space(),
+                total_sectors: single_disk_farm.total_sectors_count(),
+                plotted_sectors,
+                last_winning_slot: None,
+                errors: 0,
+                last_error: None,
+            },
+        );
+
+        single_disk_farm
+            .on_sector_update(Arc::new({
+                let farms = Arc::clone(&self.farms);
+
+                move |(_sector_index, sector_update)| {
+                    if let SectorUpdate::Plotting(SectorPlottingDetails::Finished {
+                        old_plotted_sector: None,
+                        ..
+                    }) = sector_update
+                    {
+                        if let Some(farm_summary) = farms.lock().get_mut(&farm_id) {
+                            farm_summary.plotted_sectors += 1;
+                        }
+                    }
+                }
+            }))
+            .detach();
+
+        single_disk_farm
+            .on_solution(Arc::new({
+                let farms = Arc::clone(&self.farms);
+
+                move |solution_response| {
+                    if let Some(farm_summary) = farms.lock().get_mut(&farm_id) {
+                        farm_summary
+                            .last_winning_slot
+                            .replace(solution_response.slot_number);
+                    }
+                }
+            }))
+            .detach();
+
+        single_disk_farm
+            .on_farming_notification(Arc::new({
+                let farms = Arc::clone(&self.farms);
+
+                move |farming_notification| {
+                    if let FarmingNotification::NonFatalError(error) = farming_notification {
+                        if let Some(farm_summary) = farms.lock().get_mut(&farm_id) {
+                            farm_summary.errors += 1;
+                            farm_summary.last_error.replace(error.to_string());
+                        }
+                    }
+                }
+            }))
+            .detach();
+    }
+
+    /// Create report with current state of all farms
+    pub fn report(&self) -> FarmerReport {
+        let mut farms = self.farms.lock().values().cloned().collect::<Vec<_>>();
+        farms.sort_by_key(|farm_summary| farm_summary.farm_id);
+
+        FarmerReport {
+            farmer_name: self.farmer_name.clone(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            farms,
+        }
+    }
+
+    /// Publish reports to aggregation server at `url` with specified interval, never returns
+    pub async fn run_publisher(self, url: String, token: String, interval: Duration) {
+        let client = match HttpClientBuilder::default().build(&url) {
+            Ok(client) => client,
+            Err(error) => {
+                warn!(%url, %error, "Failed to create monitoring client, reports will not be sent");
+                return;
+            }
+        };
+
+        loop {
+            let report = AuthenticatedFarmerReport::new(self.report(), &token);
+            if let Err(error) = client.submit_farmer_report(report).await {
+                warn!(%url, %error, "Failed to submit report to monitoring server");
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::monitoring::{
+    start_aggregation_server, AuthenticatedFarmerReport, FarmSummary, FarmerReport,
+    MonitoringAggregator, MonitoringRpcApiClient,
+};
+use crate::single_disk_farm::SingleDiskFarmId;
+use jsonrpsee::http_client::HttpClientBuilder;
+
+fn farmer_report(farmer_name: &str, timestamp: u64) -> FarmerReport {
+    FarmerReport {
+        farmer_name: farmer_name.to_string(),
+        timestamp,
+        farms: vec![FarmSummary {
+            farm_id: SingleDiskFarmId::new(),
+            allocated_space: 1024 * 1024 * 1024,
+            total_sectors: 10,
+            plotted_sectors: 3,
+            last_winning_slot: Some(42),
+            errors: 1,
+            last_error: Some("Something went wrong".to_string()),
+        }],
+    }
+}
+
+#[test]
+fn report_authentication() {
+    let report = farmer_report("farmer-1", 1);
+
+    let authenticated_report = AuthenticatedFarmerReport::new(report.clone(), "token");
+    assert_eq!(
+        authenticated_report.clone().verify("token"),
+        Some(report.clone())
+    );
+    assert_eq!(authenticated_report.clone().verify("other-token"), None);
+
+    // Survives serialization round-trip
+    let authenticated_report = serde_json::from_str::<AuthenticatedFarmerReport>(
+        &serde_json::to_string(&authenticated_report).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(authenticated_report.verify("token"), Some(report.clone()));
+
+    // Modified report is rejected
+    let mut authenticated_report = AuthenticatedFarmerReport::new(report, "token");
+    authenticated_report.report.farms[0].plotted_sectors += 1;
+    assert_eq!(authenticated_report.verify("token"), None);
+}
+
+#[tokio::test]
+async fn aggregation_server() {
+    let aggregator = MonitoringAggregator::new("token");
+    let (address, server_handle) =
+        start_aggregation_server("127.0.0.1:0".parse().unwrap(), aggregator.clone())
+            .await
+            .unwrap();
+    let client = HttpClientBuilder::default()
+        .build(format!("http://{address}"))
+        .unwrap();
+
+    let report_1 = farmer_report("farmer-1", 10);
+    let report_2 = farmer_report("farmer-2", 10);
+    client
+        .submit_farmer_report(AuthenticatedFarmerReport::new(report_1.clone(), "token"))
+        .await
+        .unwrap();
+    client
+        .submit_farmer_report(AuthenticatedFarmerReport::new(report_2.clone(), "token"))
+        .await
+        .unwrap();
+
+    // Wrong token
+    assert!(client
+        .submit_farmer_report(AuthenticatedFarmerReport::new(
+            farmer_report("farmer-3", 10),
+            "other-token"
+        ))
+        .await
+        .is_err());
+    // Replayed report
+    assert!(client
+        .submit_farmer_report(AuthenticatedFarmerReport::new(report_1.clone(), "token"))
+        .await
+        .is_err());
+
+    assert_eq!(
+        client.farmer_reports().await.unwrap(),
+        vec![report_1, report_2]
+    );
+    assert_eq!(aggregator.reports().len(), 2);
+
+    server_handle.stop().unwrap();
+}
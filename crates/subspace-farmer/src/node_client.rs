@@ -1,4 +1,5 @@
 pub(crate) mod node_rpc_client;
+pub mod relay_node_client;
 
 use async_trait::async_trait;
 use futures::Stream;
@@ -4,12 +4,14 @@ use futures::{Stream, StreamExt};
 use jsonrpsee::core::client::{ClientT, SubscriptionClientT};
 use jsonrpsee::core::Error as JsonError;
 use jsonrpsee::rpc_params;
+use jsonrpsee::types::error::CallError;
 use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
 use std::pin::Pin;
 use std::sync::Arc;
-use subspace_core_primitives::{Piece, PieceIndex, SegmentHeader, SegmentIndex};
+use subspace_core_primitives::{BlockNumber, Piece, PieceIndex, SegmentHeader, SegmentIndex};
 use subspace_rpc_primitives::{
-    FarmerAppInfo, RewardSignatureResponse, RewardSigningInfo, SlotInfo, SolutionResponse,
+    FarmerAppInfo, FutureHistorySize, RewardSignatureResponse, RewardSigningInfo, SlotInfo,
+    SolutionRangeHistory, SolutionResponse, FUTURE_HISTORY_SIZE_ERROR_CODE,
 };
 use tokio::sync::Semaphore;
 
@@ -42,6 +44,22 @@ impl NodeRpcClient {
             piece_request_semaphore,
         })
     }
+
+    /// Solution ranges of canonical blocks sampled every `interval` blocks going back from the
+    /// best block, at most `samples` samples
+    pub async fn solution_range_history(
+        &self,
+        samples: u32,
+        interval: BlockNumber,
+    ) -> Result<SolutionRangeHistory, Error> {
+        Ok(self
+            .client
+            .request(
+                "subspace_solutionRangeHistory",
+                rpc_params![&samples, &interval],
+            )
+            .await?)
+    }
 }
 
 #[async_trait]
@@ -74,13 +92,25 @@ impl NodeClient for NodeRpcClient {
         &self,
         solution_response: SolutionResponse,
     ) -> Result<(), RpcError> {
-        Ok(self
-            .client
+        self.client
             .request(
                 "subspace_submitSolutionResponse",
                 rpc_params![&solution_response],
             )
-            .await?)
+            .await
+            .map_err(|error| {
+                // Turn future history size error into typed error, such that farmer can handle it
+                if let JsonError::Call(CallError::Custom(error_object)) = &error
+                    && error_object.code() == FUTURE_HISTORY_SIZE_ERROR_CODE
+                    && let Some(future_history_size) = error_object
+                        .data()
+                        .and_then(|data| serde_json::from_str::<FutureHistorySize>(data.get()).ok())
+                {
+                    return Box::new(future_history_size) as RpcError;
+                }
+
+                error.into()
+            })
     }
 
     async fn subscribe_reward_signing(
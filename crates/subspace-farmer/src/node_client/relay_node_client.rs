@@ -0,0 +1,379 @@
+#[cfg(test)]
+mod tests;
+
+use crate::node_client::{Error, NodeClient, NodeClientExt};
+use async_trait::async_trait;
+use futures::future::ready;
+use futures::{future, stream, Stream, StreamExt};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use subspace_core_primitives::{Piece, PieceIndex, SegmentHeader, SegmentIndex};
+use subspace_rpc_primitives::{
+    FarmerAppInfo, RewardSignatureResponse, RewardSigningInfo, SlotInfo, SolutionResponse,
+};
+use tracing::{debug, info, warn};
+
+/// How often to check whether local node has caught up with trusted node
+const SYNC_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Source of consensus information (slot info, archived history) farmer relies on
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TrustMode {
+    /// Information comes from local node that verified the chain itself
+    Local,
+    /// Information is relayed from trusted node while local node is syncing, farmer relies on
+    /// trusted node to provide correct slot info and segment headers
+    TrustedNode,
+}
+
+impl fmt::Display for TrustMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => write!(f, "local node"),
+            Self::TrustedNode => write!(f, "trusted node (local node is syncing)"),
+        }
+    }
+}
+
+/// Node client that allows farmer to plot and farm while local node is still syncing.
+///
+/// Until local node catches up with archived history of trusted node, slot info, reward signing
+/// requests, segment headers and farmer app info are relayed from trusted node and solutions with
+/// reward signatures are submitted to trusted node (which will only accept them if it exposes
+/// unsafe RPC methods). Once local node catches up, client switches to local node permanently.
+///
+/// Without trusted node this is a thin wrapper around local node client.
+#[derive(Debug, Clone)]
+pub struct RelayNodeClient<NC> {
+    local: NC,
+    trusted: Option<NC>,
+    local_synced: Arc<AtomicBool>,
+}
+
+impl<NC> RelayNodeClient<NC>
+where
+    NC: NodeClient,
+{
+    /// Create new instance, checks that both nodes are on the same chain and spawns background
+    /// task that will switch to local node once it is synced
+    pub async fn new(local: NC, trusted: Option<NC>) -> Result<Self, Error> {
+        Self::with_sync_check_interval(local, trusted, SYNC_CHECK_INTERVAL).await
+    }
+
+    async fn with_sync_check_interval(
+        local: NC,
+        trusted: Option<NC>,
+        sync_check_interval: Duration,
+    ) -> Result<Self, Error> {
+        let Some(trusted) = trusted else {
+            return Ok(Self {
+                local,
+                trusted: None,
+                local_synced: Arc::new(AtomicBool::new(true)),
+            });
+        };
+
+        let local_info = local.farmer_app_info().await?;
+        let trusted_info = trusted.farmer_app_info().await?;
+
+        if local_info.genesis_hash != trusted_info.genesis_hash {
+            return Err(format!(
+                "Trusted node is on a different chain: genesis hash {} vs {} of local node",
+                hex::encode(trusted_info.genesis_hash),
+                hex::encode(local_info.genesis_hash),
+            )
+            .into());
+        }
+
+        let local_synced = Arc::new(AtomicBool::new(is_caught_up(&local_info, &trusted_info)));
+
+        if local_synced.load(Ordering::Acquire) {
+            info!(trust_mode = %TrustMode::Local, "Local node is synced, trusted node is not used");
+        } else {
+            warn!(
+                trust_mode = %TrustMode::TrustedNode,
+                local_history_size = %local_info.protocol_info.history_size,
+                trusted_history_size = %trusted_info.protocol_info.history_size,
+                "Local node is syncing, slot info and archived history are relayed from trusted \
+                node until local node catches up"
+            );
+
+            tokio::spawn(monitor_sync(
+                local.clone(),
+                trusted.clone(),
+                Arc::downgrade(&local_synced),
+                sync_check_interval,
+            ));
+        }
+
+        Ok(Self {
+            local,
+            trusted: Some(trusted),
+            local_synced,
+        })
+    }
+
+    /// Current source of consensus information
+    pub fn trust_mode(&self) -> TrustMode {
+        if self.trusted_while_syncing().is_some() {
+            TrustMode::TrustedNode
+        } else {
+            TrustMode::Local
+        }
+    }
+
+    /// Trusted node client if local node is not synced yet
+    fn trusted_while_syncing(&self) -> Option<&NC> {
+        self.trusted
+            .as_ref()
+            .filter(|_| !self.local_synced.load(Ordering::Acquire))
+    }
+
+    /// Relay items from trusted stream until local node is synced and from local stream
+    /// afterwards
+    fn relay<T>(
+        &self,
+        local: Pin<Box<dyn Stream<Item = T> + Send + 'static>>,
+        trusted: Pin<Box<dyn Stream<Item = T> + Send + 'static>>,
+    ) -> Pin<Box<dyn Stream<Item = T> + Send + 'static>>
+    where
+        T: Send + 'static,
+    {
+        let trusted = trusted.take_while({
+            let local_synced = Arc::clone(&self.local_synced);
+
+            move |_| ready(!local_synced.load(Ordering::Acquire))
+        });
+        let local = local.filter({
+            let local_synced = Arc::clone(&self.local_synced);
+
+            move |_| ready(local_synced.load(Ordering::Acquire))
+        });
+
+        Box::pin(stream::select(trusted, local))
+    }
+}
+
+/// Whether local node has caught up with archived history of trusted node
+fn is_caught_up(local_info: &FarmerAppInfo, trusted_info: &FarmerAppInfo) -> bool {
+    !local_info.syncing
+        && local_info.protocol_info.history_size >= trusted_info.protocol_info.history_size
+}
+
+async fn monitor_sync<NC>(
+    local: NC,
+    trusted: NC,
+    local_synced: Weak<AtomicBool>,
+    sync_check_interval: Duration,
+) where
+    NC: NodeClient,
+{
+    loop {
+        tokio::time::sleep(sync_check_interval).await;
+
+        let Some(local_synced) = local_synced.upgrade() else {
+            // All clients were dropped
+            return;
+        };
+
+        let (local_info, trusted_info) =
+            match future::join(local.farmer_app_info(), trusted.farmer_app_info()).await {
+                (Ok(local_info), Ok(trusted_info)) => (local_info, trusted_info),
+                (Err(error), _) | (_, Err(error)) => {
+                    debug!(%error, "Failed to check whether local node is synced");
+                    continue;
+                }
+            };
+
+        if is_caught_up(&local_info, &trusted_info) {
+            local_synced.store(true, Ordering::Release);
+            info!(
+                trust_mode = %TrustMode::Local,
+                "Local node caught up with trusted node, switched to local node"
+            );
+            return;
+        }
+
+        debug!(
+            local_syncing = %local_info.syncing,
+            local_history_size = %local_info.protocol_info.history_size,
+            trusted_history_size = %trusted_info.protocol_info.history_size,
+            "Local node is still syncing"
+        );
+    }
+}
+
+#[async_trait]
+impl<NC> NodeClient for RelayNodeClient<NC>
+where
+    NC: NodeClient,
+{
+    async fn farmer_app_info(&self) -> Result<FarmerAppInfo, Error> {
+        match self.trusted_while_syncing() {
+            Some(trusted) => trusted.farmer_app_info().await,
+            None => self.local.farmer_app_info().await,
+        }
+    }
+
+    async fn subscribe_slot_info(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = SlotInfo> + Send + 'static>>, Error> {
+        let local = self.local.subscribe_slot_info().await?;
+        match self.trusted_while_syncing() {
+            Some(trusted) => Ok(self.relay(local, trusted.subscribe_slot_info().await?)),
+            None => Ok(local),
+        }
+    }
+
+    async fn submit_solution_response(
+        &self,
+        solution_response: SolutionResponse,
+    ) -> Result<(), Error> {
+        match self.trusted_while_syncing() {
+            Some(trusted) => trusted.submit_solution_response(solution_response).await,
+            None => self.local.submit_solution_response(solution_response).await,
+        }
+    }
+
+    async fn subscribe_reward_signing(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = RewardSigningInfo> + Send + 'static>>, Error> {
+        let local = self.local.subscribe_reward_signing().await?;
+        match self.trusted_while_syncing() {
+            Some(trusted) => Ok(self.relay(local, trusted.subscribe_reward_signing().await?)),
+            None => Ok(local),
+        }
+    }
+
+    async fn submit_reward_signature(
+        &self,
+        reward_signature: RewardSignatureResponse,
+    ) -> Result<(), Error> {
+        match self.trusted_while_syncing() {
+            Some(trusted) => trusted.submit_reward_signature(reward_signature).await,
+            None => self.local.submit_reward_signature(reward_signature).await,
+        }
+    }
+
+    async fn subscribe_archived_segment_headers(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = SegmentHeader> + Send + 'static>>, Error> {
+        let local = self.local.subscribe_archived_segment_headers().await?;
+        let Some(trusted) = self.trusted_while_syncing() else {
+            return Ok(local);
+        };
+
+        // Segment headers archived by local node during sync were already received from trusted
+        // node, acknowledge them right away to not hold local node's archiving back
+        let local = local.filter_map({
+            let local_client = self.local.clone();
+            let local_synced = Arc::clone(&self.local_synced);
+
+            move |segment_header| {
+                let local_client = local_client.clone();
+                let local_synced = Arc::clone(&local_synced);
+
+                async move {
+                    if local_synced.load(Ordering::Acquire) {
+                        return Some(segment_header);
+                    }
+
+                    let segment_index = segment_header.segment_index();
+                    if let Err(error) = local_client
+                        .acknowledge_archived_segment_header(segment_index)
+                        .await
+                    {
+                        debug!(
+                            %segment_index,
+                            %error,
+                            "Failed to acknowledge segment header archived by syncing local node"
+                        );
+                    }
+
+                    None
+                }
+            }
+        });
+
+        Ok(self.relay(
+            Box::pin(local),
+            trusted.subscribe_archived_segment_headers().await?,
+        ))
+    }
+
+    async fn segment_headers(
+        &self,
+        segment_indexes: Vec<SegmentIndex>,
+    ) -> Result<Vec<Option<SegmentHeader>>, Error> {
+        let mut segment_headers = self.local.segment_headers(segment_indexes.clone()).await?;
+
+        if let Some(trusted) = self.trusted_while_syncing()
+            && segment_headers.iter().any(Option::is_none)
+        {
+            let trusted_segment_headers = trusted.segment_headers(segment_indexes).await?;
+            segment_headers
+                .iter_mut()
+                .zip(trusted_segment_headers)
+                .filter(|(segment_header, _)| segment_header.is_none())
+                .for_each(|(segment_header, trusted_segment_header)| {
+                    *segment_header = trusted_segment_header;
+                });
+        }
+
+        Ok(segment_headers)
+    }
+
+    async fn piece(&self, piece_index: PieceIndex) -> Result<Option<Piece>, Error> {
+        let maybe_piece = self.local.piece(piece_index).await?;
+
+        match self.trusted_while_syncing() {
+            Some(trusted) if maybe_piece.is_none() => trusted.piece(piece_index).await,
+            _ => Ok(maybe_piece),
+        }
+    }
+
+    async fn acknowledge_archived_segment_header(
+        &self,
+        segment_index: SegmentIndex,
+    ) -> Result<(), Error> {
+        match self.trusted_while_syncing() {
+            Some(trusted) => {
+                // Trusted node is typically a public node that doesn't wait for acknowledgements
+                // and doesn't allow them, so failure is not critical
+                if let Err(error) = trusted
+                    .acknowledge_archived_segment_header(segment_index)
+                    .await
+                {
+                    debug!(
+                        %segment_index,
+                        %error,
+                        "Failed to acknowledge segment header to trusted node"
+                    );
+                }
+
+                Ok(())
+            }
+            None => {
+                self.local
+                    .acknowledge_archived_segment_header(segment_index)
+                    .await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<NC> NodeClientExt for RelayNodeClient<NC>
+where
+    NC: NodeClientExt,
+{
+    async fn last_segment_headers(&self, limit: u64) -> Result<Vec<Option<SegmentHeader>>, Error> {
+        match self.trusted_while_syncing() {
+            Some(trusted) => trusted.last_segment_headers(limit).await,
+            None => self.local.last_segment_headers(limit).await,
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use crate::node_client::relay_node_client::{RelayNodeClient, TrustMode};
+use crate::node_client::Error;
+use crate::NodeClient;
+use futures::channel::mpsc;
+use futures::{SinkExt, Stream, StreamExt};
+use parking_lot::Mutex;
+use std::num::NonZeroU64;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use subspace_core_primitives::{
+    HistorySize, LastArchivedBlock, Piece, PieceIndex, SegmentHeader, SegmentIndex,
+};
+use subspace_farmer_components::FarmerProtocolInfo;
+use subspace_rpc_primitives::{
+    FarmerAppInfo, RewardSignatureResponse, RewardSigningInfo, SlotInfo, SolutionResponse,
+};
+use tokio::time::timeout;
+
+const SYNC_CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone)]
+struct MockNodeClient {
+    genesis_hash: [u8; 32],
+    syncing: Arc<AtomicBool>,
+    history_size: Arc<AtomicU64>,
+    slot_info_receiver: Arc<Mutex<Option<mpsc::UnboundedReceiver<SlotInfo>>>>,
+    segment_headers_receiver: Arc<Mutex<Option<mpsc::UnboundedReceiver<SegmentHeader>>>>,
+    acknowledged_segment_indexes: Arc<Mutex<Vec<SegmentIndex>>>,
+}
+
+impl MockNodeClient {
+    fn new(
+        genesis_hash: [u8; 32],
+        syncing: bool,
+        history_size: u64,
+    ) -> (
+        Self,
+        mpsc::UnboundedSender<SlotInfo>,
+        mpsc::UnboundedSender<SegmentHeader>,
+    ) {
+        let (slot_info_sender, slot_info_receiver) = mpsc::unbounded();
+        let (segment_headers_sender, segment_headers_receiver) = mpsc::unbounded();
+        let client = Self {
+            genesis_hash,
+            syncing: Arc::new(AtomicBool::new(syncing)),
+            history_size: Arc::new(AtomicU64::new(history_size)),
+            slot_info_receiver: Arc::new(Mutex::new(Some(slot_info_receiver))),
+            segment_headers_receiver: Arc::new(Mutex::new(Some(segment_headers_receiver))),
+            acknowledged_segment_indexes: Arc::default(),
+        };
+
+        (client, slot_info_sender, segment_headers_sender)
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeClient for MockNodeClient {
+    async fn farmer_app_info(&self) -> Result<FarmerAppInfo, Error> {
+        // Most of these values make no sense, but they are not used by relay client anyway
+        Ok(FarmerAppInfo {
+            genesis_hash: self.genesis_hash,
+            dsn_bootstrap_nodes: Vec::new(),
+            syncing: self.syncing.load(Ordering::Acquire),
+            farming_timeout: Duration::default(),
+            protocol_info: FarmerProtocolInfo {
+                history_size: HistorySize::new(
+                    NonZeroU64::new(self.history_size.load(Ordering::Acquire)).unwrap(),
+                ),
+                max_pieces_in_sector: 0,
+                recent_segments: HistorySize::from(SegmentIndex::ZERO),
+                recent_history_fraction: (
+                    HistorySize::from(NonZeroU64::new(1).unwrap()),
+                    HistorySize::from(NonZeroU64::new(10).unwrap()),
+                ),
+                min_sector_lifetime: HistorySize::from(NonZeroU64::new(4).unwrap()),
+            },
+        })
+    }
+
+    async fn subscribe_slot_info(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = SlotInfo> + Send + 'static>>, Error> {
+        Ok(Box::pin(self.slot_info_receiver.lock().take().unwrap()))
+    }
+
+    async fn submit_solution_response(
+        &self,
+        _solution_response: SolutionResponse,
+    ) -> Result<(), Error> {
+        unimplemented!()
+    }
+
+    async fn subscribe_reward_signing(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = RewardSigningInfo> + Send + 'static>>, Error> {
+        unimplemented!()
+    }
+
+    async fn submit_reward_signature(
+        &self,
+        _reward_signature: RewardSignatureResponse,
+    ) -> Result<(), Error> {
+        unimplemented!()
+    }
+
+    async fn subscribe_archived_segment_headers(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = SegmentHeader> + Send + 'static>>, Error> {
+        Ok(Box::pin(
+            self.segment_headers_receiver.lock().take().unwrap(),
+        ))
+    }
+
+    async fn segment_headers(
+        &self,
+        _segment_indexes: Vec<SegmentIndex>,
+    ) -> Result<Vec<Option<SegmentHeader>>, Error> {
+        unimplemented!()
+    }
+
+    async fn piece(&self, _piece_index: PieceIndex) -> Result<Option<Piece>, Error> {
+        unimplemented!()
+    }
+
+    async fn acknowledge_archived_segment_header(
+        &self,
+        segment_index: SegmentIndex,
+    ) -> Result<(), Error> {
+        self.acknowledged_segment_indexes.lock().push(segment_index);
+        Ok(())
+    }
+}
+
+fn slot_info(slot_number: u64) -> SlotInfo {
+    SlotInfo {
+        slot_number,
+        global_challenge: [0; 32],
+        solution_range: 0,
+        voting_solution_range: 0,
+        import_queue_depth: 0,
+        best_block_slot_lag: 0,
+    }
+}
+
+fn segment_header(segment_index: u64) -> SegmentHeader {
+    SegmentHeader::V0 {
+        segment_index: SegmentIndex::from(segment_index),
+        segment_commitment: Default::default(),
+        prev_segment_header_hash: [0; 32],
+        last_archived_block: LastArchivedBlock {
+            number: 0,
+            archived_progress: Default::default(),
+        },
+    }
+}
+
+#[tokio::test]
+async fn rejects_different_chain() {
+    let (local, _, _) = MockNodeClient::new([1; 32], true, 1);
+    let (trusted, _, _) = MockNodeClient::new([2; 32], false, 10);
+
+    assert!(RelayNodeClient::new(local, Some(trusted)).await.is_err());
+}
+
+#[tokio::test]
+async fn without_trusted_node() {
+    let (local, local_slot_info_sender, _) = MockNodeClient::new([1; 32], true, 1);
+
+    let client = RelayNodeClient::new(local, None).await.unwrap();
+    assert_eq!(client.trust_mode(), TrustMode::Local);
+    assert!(client.farmer_app_info().await.unwrap().syncing);
+
+    let mut slot_info_stream = client.subscribe_slot_info().await.unwrap();
+    local_slot_info_sender.unbounded_send(slot_info(1)).unwrap();
+    assert_eq!(slot_info_stream.next().await.unwrap().slot_number, 1);
+}
+
+#[tokio::test]
+async fn relays_until_local_node_is_synced() {
+    let (local, mut local_slot_info_sender, local_segment_headers_sender) =
+        MockNodeClient::new([1; 32], true, 1);
+    let (trusted, mut trusted_slot_info_sender, trusted_segment_headers_sender) =
+        MockNodeClient::new([1; 32], false, 10);
+
+    let client = RelayNodeClient::with_sync_check_interval(
+        local.clone(),
+        Some(trusted.clone()),
+        SYNC_CHECK_INTERVAL,
+    )
+    .await
+    .unwrap();
+    assert_eq!(client.trust_mode(), TrustMode::TrustedNode);
+    // Farmer app info is coming from trusted node
+    assert!(!client.farmer_app_info().await.unwrap().syncing);
+
+    let mut slot_info_stream = client.subscribe_slot_info().await.unwrap();
+    let mut segment_headers_stream = client.subscribe_archived_segment_headers().await.unwrap();
+
+    // Local node doesn't have slot info while syncing, but even if it did, it is ignored
+    local_slot_info_sender.send(slot_info(1)).await.unwrap();
+    trusted_slot_info_sender.send(slot_info(2)).await.unwrap();
+    assert_eq!(slot_info_stream.next().await.unwrap().slot_number, 2);
+    // Make sure local slot info was processed too
+    assert!(timeout(SYNC_CHECK_INTERVAL, slot_info_stream.next())
+        .await
+        .is_err());
+
+    // Segment headers archived by local node during sync are acknowledged, but not relayed
+    local_segment_headers_sender
+        .unbounded_send(segment_header(3))
+        .unwrap();
+    trusted_segment_headers_sender
+        .unbounded_send(segment_header(9))
+        .unwrap();
+    assert_eq!(
+        segment_headers_stream.next().await.unwrap().segment_index(),
+        SegmentIndex::from(9)
+    );
+    // Make sure local segment header was processed too
+    assert!(timeout(SYNC_CHECK_INTERVAL, segment_headers_stream.next())
+        .await
+        .is_err());
+    client
+        .acknowledge_archived_segment_header(SegmentIndex::from(9))
+        .await
+        .unwrap();
+    assert_eq!(
+        *local.acknowledged_segment_indexes.lock(),
+        vec![SegmentIndex::from(3)]
+    );
+    assert_eq!(
+        *trusted.acknowledged_segment_indexes.lock(),
+        vec![SegmentIndex::from(9)]
+    );
+
+    // Not synced until history is caught up
+    local.syncing.store(false, Ordering::Release);
+    tokio::time::sleep(SYNC_CHECK_INTERVAL * 5).await;
+    assert_eq!(client.trust_mode(), TrustMode::TrustedNode);
+
+    local.history_size.store(10, Ordering::Release);
+    tokio::time::sleep(SYNC_CHECK_INTERVAL * 5).await;
+    assert_eq!(client.trust_mode(), TrustMode::Local);
+
+    // Trusted node is no longer used
+    trusted_slot_info_sender.send(slot_info(3)).await.unwrap();
+    local_slot_info_sender.send(slot_info(4)).await.unwrap();
+    assert_eq!(slot_info_stream.next().await.unwrap().slot_number, 4);
+
+    client
+        .acknowledge_archived_segment_header(SegmentIndex::from(10))
+        .await
+        .unwrap();
+    assert_eq!(
+        *local.acknowledged_segment_indexes.lock(),
+        vec![SegmentIndex::from(3), SegmentIndex::from(10)]
+    );
+}
@@ -0,0 +1,662 @@
+//! Plotting of sectors by remote plotter.
+//!
+//! Plotting is compute-intensive, while farming mostly needs disk space. Remote plotter allows
+//! machines with a lot of CPU/GPU resources to plot sectors for many farmers that only store and
+//! farm them. Farmer requests sector to be plotted using JSON-RPC over HTTP, plotter downloads
+//! pieces and encodes sector, after which farmer downloads plotted sector in chunks. Failed chunk
+//! requests are retried from the last received byte, so connection issues don't require sector to
+//! be plotted again. Received sector is verified against checksum calculated by plotter before
+//! being written to disk.
+//!
+//! Plotter only plots limited number of sectors concurrently and only keeps limited number of
+//! plotted sectors that were not yet downloaded, further requests are rejected as busy, in which
+//! case farmer waits and tries again later.
+
+#[cfg(test)]
+mod tests;
+
+use jsonrpsee::core::{async_trait, Error as JsonRpseeError, RpcResult};
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::server::{ServerBuilder, ServerHandle};
+use jsonrpsee::types::error::{CallError, ErrorObject};
+use parity_scale_codec::{Decode, Encode};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use subspace_core_primitives::crypto::blake3_hash;
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::{Blake3Hash, PublicKey, SectorId, SectorIndex};
+use subspace_erasure_coding::ErasureCoding;
+use subspace_farmer_components::plotting::{
+    download_sector, encode_sector, DownloadSectorOptions, EncodeSectorOptions, PlottedSector,
+};
+use subspace_farmer_components::sector::sector_size;
+use subspace_farmer_components::{FarmerProtocolInfo, PieceGetter, PieceGetterRetryPolicy};
+use subspace_proof_of_space::Table;
+use thiserror::Error;
+use tokio::sync::Semaphore;
+use tracing::{debug, warn, Instrument};
+use ulid::Ulid;
+
+/// Size of sector chunk transferred in a single request, hex encoding doubles it on the wire,
+/// which still fits into default JSON-RPC response size limit
+pub const SECTOR_CHUNK_SIZE: usize = 2 * 1024 * 1024;
+/// Error code returned when plotter has no capacity for new plotting jobs
+pub const PLOTTER_BUSY_ERROR_CODE: i32 = 1100;
+/// Error code returned when plotter doesn't know requested plotting job (plotter was restarted or
+/// plotted sector expired)
+pub const UNKNOWN_PLOTTING_JOB_ERROR_CODE: i32 = 1101;
+/// How long plotted sector is kept by plotter without farmer requesting it before it is discarded
+const PLOTTED_SECTOR_EXPIRATION: Duration = Duration::from_secs(10 * 60);
+/// Delay before retrying failed request or request rejected because plotter is busy
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+/// How many times in a row request can fail before plotting is considered failed
+const MAX_FAILED_REQUESTS: usize = 10;
+/// Interval at which farmer checks status of plotting job
+const JOB_STATUS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Get piece retry attempts number.
+const PIECE_GETTER_RETRY_NUMBER: u16 = 7;
+
+/// ID of the remote plotting job
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PlottingJobId(Ulid);
+
+impl PlottingJobId {
+    fn new() -> Self {
+        Self(Ulid::new())
+    }
+}
+
+/// Request to plot a sector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlotSectorRequest {
+    /// Public key of the farm sector belongs to
+    pub public_key: PublicKey,
+    /// Sector index
+    pub sector_index: SectorIndex,
+    /// Farmer protocol info
+    pub farmer_protocol_info: FarmerProtocolInfo,
+    /// How many pieces should sector contain
+    pub pieces_in_sector: u16,
+}
+
+/// Information about sector plotted by remote plotter
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemotePlottedSectorInfo {
+    /// Size of plotted sector in bytes
+    pub sector_size: u64,
+    /// BLAKE3 checksum of plotted sector
+    #[serde(with = "hex")]
+    pub sector_checksum: Blake3Hash,
+    /// SCALE-encoded [`PlottedSector`]
+    #[serde(with = "hex")]
+    pub plotted_sector: Vec<u8>,
+}
+
+/// Status of the remote plotting job
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum PlottingJobStatus {
+    /// Waiting for plotting capacity
+    Queued,
+    /// Sector is being plotted
+    Plotting,
+    /// Sector is plotted and can be downloaded
+    Ready(RemotePlottedSectorInfo),
+    /// Plotting failed
+    Failed {
+        /// Error that happened during plotting
+        error: String,
+    },
+}
+
+/// Chunk of plotted sector
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SectorChunk {
+    /// Bytes of the sector
+    #[serde(with = "hex")]
+    pub bytes: Vec<u8>,
+}
+
+/// Remote plotter RPC API exposed by plotting server
+#[rpc(client, server)]
+pub trait RemotePlotterRpcApi {
+    /// Request sector to be plotted, returns ID of the plotting job
+    #[method(name = "plotter_plotSector")]
+    fn plot_sector(&self, request: PlotSectorRequest) -> RpcResult<PlottingJobId>;
+
+    /// Status of the plotting job
+    #[method(name = "plotter_jobStatus")]
+    fn job_status(&self, job_id: PlottingJobId) -> RpcResult<PlottingJobStatus>;
+
+    /// Chunk of plotted sector starting at specified offset, at most [`SECTOR_CHUNK_SIZE`] bytes
+    #[method(name = "plotter_sectorChunk")]
+    fn sector_chunk(&self, job_id: PlottingJobId, offset: u64) -> RpcResult<SectorChunk>;
+
+    /// Finish plotting job, plotter will free resources associated with it
+    #[method(name = "plotter_finishJob")]
+    fn finish_job(&self, job_id: PlottingJobId) -> RpcResult<()>;
+}
+
+#[derive(Debug)]
+enum JobState {
+    Queued,
+    Plotting,
+    Ready {
+        info: RemotePlottedSectorInfo,
+        sector: Arc<Vec<u8>>,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Debug)]
+struct Job {
+    state: JobState,
+    last_accessed: Instant,
+}
+
+impl Job {
+    fn expired(&self) -> bool {
+        matches!(self.state, JobState::Ready { .. } | JobState::Failed { .. })
+            && self.last_accessed.elapsed() >= PLOTTED_SECTOR_EXPIRATION
+    }
+}
+
+struct Inner<PG> {
+    piece_getter: PG,
+    kzg: Kzg,
+    erasure_coding: ErasureCoding,
+    plotting_semaphore: Arc<Semaphore>,
+    max_jobs: usize,
+    record_encoding_concurrency: NonZeroUsize,
+    jobs: Mutex<HashMap<PlottingJobId, Job>>,
+}
+
+/// Plotter that plots sectors on behalf of remote farmers
+pub struct RemotePlotter<PG, PosTable> {
+    inner: Arc<Inner<PG>>,
+    _phantom: PhantomData<fn() -> PosTable>,
+}
+
+impl<PG, PosTable> Clone for RemotePlotter<PG, PosTable> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<PG, PosTable> RemotePlotterRpcApiServer for RemotePlotter<PG, PosTable>
+where
+    PG: PieceGetter + Send + Sync + 'static,
+    PosTable: Table,
+{
+    fn plot_sector(&self, request: PlotSectorRequest) -> RpcResult<PlottingJobId> {
+        let job_id = PlottingJobId::new();
+
+        {
+            let mut jobs = self.inner.jobs.lock();
+            jobs.retain(|_job_id, job| !job.expired());
+
+            // Plotted sectors that were not yet downloaded are counted too, such that memory usage
+            // is bounded
+            if jobs.len() >= self.inner.max_jobs {
+                return Err(error(PLOTTER_BUSY_ERROR_CODE, "Plotter is busy"));
+            }
+
+            jobs.insert(
+                job_id,
+                Job {
+                    state: JobState::Queued,
+                    last_accessed: Instant::now(),
+                },
+            );
+        }
+
+        debug!(
+            ?job_id,
+            public_key = %request.public_key,
+            sector_index = %request.sector_index,
+            "Plotting job queued"
+        );
+
+        let inner = Arc::clone(&self.inner);
+        tokio::spawn(
+            async move {
+                let _plotting_permit =
+                    match Arc::clone(&inner.plotting_semaphore).acquire_owned().await {
+                        Ok(plotting_permit) => plotting_permit,
+                        Err(_error) => {
+                            return;
+                        }
+                    };
+
+                if let Some(job) = inner.jobs.lock().get_mut(&job_id) {
+                    job.state = JobState::Plotting;
+                } else {
+                    // Job was finished by farmer before plotting started
+                    return;
+                }
+
+                let state = match plot_sector::<_, PosTable>(&inner, request).await {
+                    Ok((sector, plotted_sector)) => {
+                        debug!(?job_id, "Sector plotted");
+
+                        JobState::Ready {
+                            info: RemotePlottedSectorInfo {
+                                sector_size: sector.len() as u64,
+                                sector_checksum: blake3_hash(&sector),
+                                plotted_sector: plotted_sector.encode(),
+                            },
+                            sector: Arc::new(sector),
+                        }
+                    }
+                    Err(error) => {
+                        warn!(?job_id, %error, "Failed to plot sector");
+
+                        JobState::Failed { error }
+                    }
+                };
+
+                if let Some(job) = inner.jobs.lock().get_mut(&job_id) {
+                    job.state = state;
+                    job.last_accessed = Instant::now();
+                }
+            }
+            .in_current_span(),
+        );
+
+        Ok(job_id)
+    }
+
+    fn job_status(&self, job_id: PlottingJobId) -> RpcResult<PlottingJobStatus> {
+        let mut jobs = self.inner.jobs.lock();
+        let job = jobs.get_mut(&job_id).ok_or_else(unknown_job_error)?;
+        job.last_accessed = Instant::now();
+
+        Ok(match &job.state {
+            JobState::Queued => PlottingJobStatus::Queued,
+            JobState::Plotting => PlottingJobStatus::Plotting,
+            JobState::Ready { info, .. } => PlottingJobStatus::Ready(info.clone()),
+            JobState::Failed { error } => PlottingJobStatus::Failed {
+                error: error.clone(),
+            },
+        })
+    }
+
+    fn sector_chunk(&self, job_id: PlottingJobId, offset: u64) -> RpcResult<SectorChunk> {
+        let sector = {
+            let mut jobs = self.inner.jobs.lock();
+            let job = jobs.get_mut(&job_id).ok_or_else(unknown_job_error)?;
+            job.last_accessed = Instant::now();
+
+            let JobState::Ready { sector, .. } = &job.state else {
+                return Err(JsonRpseeError::Custom("Sector is not plotted".to_string()));
+            };
+
+            Arc::clone(sector)
+        };
+
+        let offset = usize::try_from(offset)
+            .ok()
+            .filter(|&offset| offset <= sector.len())
+            .ok_or_else(|| JsonRpseeError::Custom("Offset is out of bounds".to_string()))?;
+        let end = sector.len().min(offset + SECTOR_CHUNK_SIZE);
+
+        Ok(SectorChunk {
+            bytes: sector[offset..end].to_vec(),
+        })
+    }
+
+    fn finish_job(&self, job_id: PlottingJobId) -> RpcResult<()> {
+        self.inner.jobs.lock().remove(&job_id);
+
+        Ok(())
+    }
+}
+
+impl<PG, PosTable> RemotePlotter<PG, PosTable>
+where
+    PG: PieceGetter + Send + Sync + 'static,
+    PosTable: Table,
+{
+    /// Create new remote plotter.
+    ///
+    /// Up to `plotting_concurrency` sectors are plotted concurrently, up to `max_jobs` plotting
+    /// jobs (including plotted sectors that were not downloaded yet) are accepted, after which
+    /// plotter reports being busy.
+    pub fn new(
+        piece_getter: PG,
+        kzg: Kzg,
+        erasure_coding: ErasureCoding,
+        plotting_concurrency: NonZeroUsize,
+        max_jobs: NonZeroUsize,
+        record_encoding_concurrency: NonZeroUsize,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                piece_getter,
+                kzg,
+                erasure_coding,
+                plotting_semaphore: Arc::new(Semaphore::new(plotting_concurrency.get())),
+                max_jobs: max_jobs.get(),
+                record_encoding_concurrency,
+                jobs: Mutex::default(),
+            }),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+async fn plot_sector<PG, PosTable>(
+    inner: &Arc<Inner<PG>>,
+    request: PlotSectorRequest,
+) -> Result<(Vec<u8>, PlottedSector), String>
+where
+    PG: PieceGetter + Send + Sync + 'static,
+    PosTable: Table,
+{
+    let PlotSectorRequest {
+        public_key,
+        sector_index,
+        farmer_protocol_info,
+        pieces_in_sector,
+    } = request;
+
+    let downloaded_sector = download_sector(DownloadSectorOptions {
+        public_key: &public_key,
+        sector_index,
+        piece_getter: &inner.piece_getter,
+        piece_getter_retry_policy: PieceGetterRetryPolicy::Limited(PIECE_GETTER_RETRY_NUMBER),
+        farmer_protocol_info,
+        kzg: &inner.kzg,
+        pieces_in_sector,
+    })
+    .await
+    .map_err(|error| error.to_string())?;
+
+    let inner = Arc::clone(inner);
+    tokio::task::spawn_blocking(move || {
+        let mut sector = Vec::new();
+        let mut sector_metadata = Vec::new();
+        let mut table_generators = (0..inner.record_encoding_concurrency.get())
+            .map(|_| PosTable::generator())
+            .collect::<Vec<_>>();
+
+        let plotted_sector = encode_sector::<PosTable>(
+            downloaded_sector,
+            EncodeSectorOptions {
+                sector_index,
+                erasure_coding: &inner.erasure_coding,
+                pieces_in_sector,
+                sector_output: &mut sector,
+                sector_metadata_output: &mut sector_metadata,
+                table_generators: &mut table_generators,
+                abort_early: &AtomicBool::new(false),
+            },
+        )
+        .map_err(|error| error.to_string())?;
+
+        Ok((sector, plotted_sector))
+    })
+    .await
+    .map_err(|_error| "Sector encoding panicked".to_string())?
+}
+
+fn error(code: i32, message: &str) -> JsonRpseeError {
+    JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        code, message, None::<()>,
+    )))
+}
+
+fn unknown_job_error() -> JsonRpseeError {
+    error(UNKNOWN_PLOTTING_JOB_ERROR_CODE, "Unknown plotting job")
+}
+
+fn is_error(error: &JsonRpseeError, code: i32) -> bool {
+    matches!(
+        error,
+        JsonRpseeError::Call(CallError::Custom(error_object)) if error_object.code() == code
+    )
+}
+
+/// Start remote plotter server on specified address, returns address server is listening on
+/// (useful when port `0` was used) and handle to stop it.
+pub async fn start_remote_plotter_server<PG, PosTable>(
+    listen_on: SocketAddr,
+    remote_plotter: RemotePlotter<PG, PosTable>,
+) -> Result<(SocketAddr, ServerHandle), JsonRpseeError>
+where
+    PG: PieceGetter + Send + Sync + 'static,
+    PosTable: Table,
+{
+    let server = ServerBuilder::default().build(listen_on).await?;
+    let address = server.local_addr()?;
+    let handle = server.start(remote_plotter.into_rpc())?;
+
+    Ok((address, handle))
+}
+
+/// Errors happening when plotting using remote plotter
+#[derive(Debug, Error)]
+pub enum RemotePlottingError {
+    /// Request to remote plotter failed
+    #[error("Request to remote plotter failed: {0}")]
+    Request(#[from] JsonRpseeError),
+    /// Remote plotter failed to plot sector
+    #[error("Remote plotter failed to plot sector: {0}")]
+    PlottingFailed(String),
+    /// Sector received from remote plotter is invalid
+    #[error("Sector received from remote plotter is invalid: {0}")]
+    InvalidSector(String),
+}
+
+/// Sector plotted by remote plotter and verified by farmer
+#[derive(Debug)]
+pub struct RemotelyPlottedSector {
+    /// Plotted sector bytes
+    pub sector: Vec<u8>,
+    /// SCALE-encoded sector metadata
+    pub sector_metadata: Vec<u8>,
+    /// Information about plotted sector
+    pub plotted_sector: PlottedSector,
+}
+
+/// Client that plots sectors using remote plotter
+#[derive(Debug, Clone)]
+pub struct RemotePlotterClient {
+    client: Arc<HttpClient>,
+}
+
+impl RemotePlotterClient {
+    /// Create new client for remote plotter at `url`
+    pub fn new(url: &str) -> Result<Self, JsonRpseeError> {
+        let client = HttpClientBuilder::default().build(url)?;
+
+        Ok(Self {
+            client: Arc::new(client),
+        })
+    }
+
+    /// Plot sector using remote plotter.
+    ///
+    /// Waits while plotter is busy. If plotter lost plotting job (for example, because it was
+    /// restarted), sector is requested to be plotted again.
+    pub async fn plot_sector(
+        &self,
+        request: PlotSectorRequest,
+    ) -> Result<RemotelyPlottedSector, RemotePlottingError> {
+        let mut failed_requests = 0;
+
+        loop {
+            let job_id = match self.client.plot_sector(request.clone()).await {
+                Ok(job_id) => job_id,
+                Err(error) => {
+                    if is_error(&error, PLOTTER_BUSY_ERROR_CODE) {
+                        debug!(
+                            sector_index = %request.sector_index,
+                            "Remote plotter is busy, waiting"
+                        );
+                        tokio::time::sleep(RETRY_DELAY).await;
+                    } else {
+                        retry_delay(&mut failed_requests, error).await?;
+                    }
+                    continue;
+                }
+            };
+
+            let result = self
+                .receive_sector(job_id, &request, &mut failed_requests)
+                .await;
+
+            if let Err(error) = self.client.finish_job(job_id).await {
+                debug!(?job_id, %error, "Failed to finish remote plotting job");
+            }
+
+            match result? {
+                Some(remotely_plotted_sector) => {
+                    return Ok(remotely_plotted_sector);
+                }
+                None => {
+                    warn!(
+                        ?job_id,
+                        sector_index = %request.sector_index,
+                        "Remote plotter lost plotting job, requesting sector again"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Wait for sector to be plotted and download it, returns `None` if plotter doesn't know
+    /// about the job anymore
+    async fn receive_sector(
+        &self,
+        job_id: PlottingJobId,
+        request: &PlotSectorRequest,
+        failed_requests: &mut usize,
+    ) -> Result<Option<RemotelyPlottedSector>, RemotePlottingError> {
+        let info = loop {
+            match self.client.job_status(job_id).await {
+                Ok(PlottingJobStatus::Queued | PlottingJobStatus::Plotting) => {
+                    *failed_requests = 0;
+                    tokio::time::sleep(JOB_STATUS_POLL_INTERVAL).await;
+                }
+                Ok(PlottingJobStatus::Ready(info)) => {
+                    *failed_requests = 0;
+                    break info;
+                }
+                Ok(PlottingJobStatus::Failed { error }) => {
+                    return Err(RemotePlottingError::PlottingFailed(error));
+                }
+                Err(error) => {
+                    if is_error(&error, UNKNOWN_PLOTTING_JOB_ERROR_CODE) {
+                        return Ok(None);
+                    }
+                    retry_delay(failed_requests, error).await?;
+                }
+            }
+        };
+
+        let expected_sector_size = sector_size(request.pieces_in_sector);
+        if info.sector_size != expected_sector_size as u64 {
+            return Err(RemotePlottingError::InvalidSector(format!(
+                "Expected sector size {expected_sector_size}, plotter reported {}",
+                info.sector_size
+            )));
+        }
+
+        let mut sector = Vec::with_capacity(expected_sector_size);
+        while sector.len() < expected_sector_size {
+            match self.client.sector_chunk(job_id, sector.len() as u64).await {
+                Ok(chunk) => {
+                    *failed_requests = 0;
+
+                    if chunk.bytes.is_empty()
+                        || sector.len() + chunk.bytes.len() > expected_sector_size
+                    {
+                        return Err(RemotePlottingError::InvalidSector(format!(
+                            "Unexpected chunk of {} bytes at offset {}",
+                            chunk.bytes.len(),
+                            sector.len()
+                        )));
+                    }
+                    sector.extend_from_slice(&chunk.bytes);
+                }
+                Err(error) => {
+                    if is_error(&error, UNKNOWN_PLOTTING_JOB_ERROR_CODE) {
+                        return Ok(None);
+                    }
+                    // Transfer resumes from the last received byte
+                    retry_delay(failed_requests, error).await?;
+                }
+            }
+        }
+
+        verify_remotely_plotted_sector(sector, &info, request).map(Some)
+    }
+}
+
+async fn retry_delay(
+    failed_requests: &mut usize,
+    error: JsonRpseeError,
+) -> Result<(), RemotePlottingError> {
+    *failed_requests += 1;
+    if *failed_requests >= MAX_FAILED_REQUESTS {
+        return Err(error.into());
+    }
+
+    debug!(%error, "Request to remote plotter failed, retrying");
+    tokio::time::sleep(RETRY_DELAY).await;
+
+    Ok(())
+}
+
+fn verify_remotely_plotted_sector(
+    sector: Vec<u8>,
+    info: &RemotePlottedSectorInfo,
+    request: &PlotSectorRequest,
+) -> Result<RemotelyPlottedSector, RemotePlottingError> {
+    if blake3_hash(&sector) != info.sector_checksum {
+        return Err(RemotePlottingError::InvalidSector(
+            "Sector checksum mismatch".to_string(),
+        ));
+    }
+
+    // Sector metadata checksum is verified during decoding
+    let plotted_sector = PlottedSector::decode(&mut info.plotted_sector.as_slice())
+        .map_err(|error| RemotePlottingError::InvalidSector(error.to_string()))?;
+
+    let expected_sector_id = SectorId::new(request.public_key.hash(), request.sector_index);
+    if plotted_sector.sector_index != request.sector_index
+        || plotted_sector.sector_id != expected_sector_id
+        || plotted_sector.sector_metadata.sector_index != request.sector_index
+        || plotted_sector.sector_metadata.pieces_in_sector != request.pieces_in_sector
+        || plotted_sector.piece_indexes.len() != usize::from(request.pieces_in_sector)
+    {
+        return Err(RemotePlottingError::InvalidSector(
+            "Plotted sector doesn't match request".to_string(),
+        ));
+    }
+
+    Ok(RemotelyPlottedSector {
+        sector,
+        sector_metadata: plotted_sector.sector_metadata.encode(),
+        plotted_sector,
+    })
+}
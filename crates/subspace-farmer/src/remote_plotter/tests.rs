@@ -0,0 +1,194 @@
+use crate::remote_plotter::{
+    start_remote_plotter_server, Job, JobState, PlotSectorRequest, PlottingJobId,
+    RemotePlottedSectorInfo, RemotePlotter, RemotePlotterClient, RemotePlotterRpcApiClient,
+    RemotePlottingError, PLOTTER_BUSY_ERROR_CODE, SECTOR_CHUNK_SIZE,
+};
+use async_trait::async_trait;
+use jsonrpsee::core::Error as JsonRpseeError;
+use jsonrpsee::types::error::CallError;
+use parity_scale_codec::Encode;
+use std::error::Error;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::Instant;
+use subspace_core_primitives::crypto::blake3_hash;
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::{
+    HistorySize, Piece, PieceIndex, PublicKey, Record, SectorId, SegmentIndex,
+};
+use subspace_erasure_coding::ErasureCoding;
+use subspace_farmer_components::plotting::PlottedSector;
+use subspace_farmer_components::sector::{sector_size, SectorMetadata};
+use subspace_farmer_components::{FarmerProtocolInfo, PieceGetter, PieceGetterRetryPolicy};
+use subspace_proof_of_space::chia::ChiaTable;
+
+struct NoPieces;
+
+#[async_trait]
+impl PieceGetter for NoPieces {
+    async fn get_piece(
+        &self,
+        _piece_index: PieceIndex,
+        _retry_policy: PieceGetterRetryPolicy,
+    ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(None)
+    }
+}
+
+fn remote_plotter(max_jobs: usize) -> RemotePlotter<NoPieces, ChiaTable> {
+    RemotePlotter::new(
+        NoPieces,
+        Kzg::new(embedded_kzg_settings()),
+        ErasureCoding::new(
+            NonZeroUsize::new(Record::NUM_S_BUCKETS.next_power_of_two().ilog2() as usize).unwrap(),
+        )
+        .unwrap(),
+        NonZeroUsize::new(1).unwrap(),
+        NonZeroUsize::new(max_jobs).unwrap(),
+        NonZeroUsize::new(1).unwrap(),
+    )
+}
+
+fn plot_sector_request(pieces_in_sector: u16) -> PlotSectorRequest {
+    let history_size = HistorySize::from(SegmentIndex::ZERO);
+
+    PlotSectorRequest {
+        public_key: PublicKey::from([1; 32]),
+        sector_index: 5,
+        farmer_protocol_info: FarmerProtocolInfo {
+            history_size,
+            max_pieces_in_sector: pieces_in_sector,
+            recent_segments: history_size,
+            recent_history_fraction: (history_size, history_size),
+            min_sector_lifetime: history_size,
+        },
+        pieces_in_sector,
+    }
+}
+
+/// Insert plotted sector into plotter as if it was plotted in response to `request`
+fn insert_plotted_sector(
+    remote_plotter: &RemotePlotter<NoPieces, ChiaTable>,
+    request: &PlotSectorRequest,
+) -> (PlottingJobId, Vec<u8>) {
+    let sector = (0..sector_size(request.pieces_in_sector))
+        .map(|i| i as u8)
+        .collect::<Vec<_>>();
+    let plotted_sector = PlottedSector {
+        sector_id: SectorId::new(request.public_key.hash(), request.sector_index),
+        sector_index: request.sector_index,
+        sector_metadata: SectorMetadata {
+            sector_index: request.sector_index,
+            pieces_in_sector: request.pieces_in_sector,
+            s_bucket_sizes: Box::new([0; Record::NUM_S_BUCKETS]),
+            history_size: request.farmer_protocol_info.history_size,
+        }
+        .into(),
+        piece_indexes: vec![PieceIndex::ZERO; usize::from(request.pieces_in_sector)],
+    };
+
+    let job_id = PlottingJobId::new();
+    remote_plotter.inner.jobs.lock().insert(
+        job_id,
+        Job {
+            state: JobState::Ready {
+                info: RemotePlottedSectorInfo {
+                    sector_size: sector.len() as u64,
+                    sector_checksum: blake3_hash(&sector),
+                    plotted_sector: plotted_sector.encode(),
+                },
+                sector: Arc::new(sector.clone()),
+            },
+            last_accessed: Instant::now(),
+        },
+    );
+
+    (job_id, sector)
+}
+
+#[tokio::test]
+async fn sector_transfer() {
+    let remote_plotter = remote_plotter(10);
+    let (address, server_handle) =
+        start_remote_plotter_server("127.0.0.1:0".parse().unwrap(), remote_plotter.clone())
+            .await
+            .unwrap();
+    let client = RemotePlotterClient::new(&format!("http://{address}")).unwrap();
+
+    // Sector spans multiple chunks
+    let request = plot_sector_request(3);
+    assert!(sector_size(request.pieces_in_sector) > SECTOR_CHUNK_SIZE);
+    let (job_id, sector) = insert_plotted_sector(&remote_plotter, &request);
+
+    let remotely_plotted_sector = client
+        .receive_sector(job_id, &request, &mut 0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(remotely_plotted_sector.sector, sector);
+    assert_eq!(
+        remotely_plotted_sector.sector_metadata,
+        remotely_plotted_sector
+            .plotted_sector
+            .sector_metadata
+            .encode()
+    );
+
+    // Sector that doesn't match request is rejected
+    let mut other_request = request.clone();
+    other_request.sector_index += 1;
+    assert!(matches!(
+        client.receive_sector(job_id, &other_request, &mut 0).await,
+        Err(RemotePlottingError::InvalidSector(_))
+    ));
+
+    // Corrupted sector is rejected
+    if let Some(Job {
+        state: JobState::Ready { sector, .. },
+        ..
+    }) = remote_plotter.inner.jobs.lock().get_mut(&job_id)
+    {
+        Arc::make_mut(sector)[SECTOR_CHUNK_SIZE] ^= 1;
+    }
+    assert!(matches!(
+        client.receive_sector(job_id, &request, &mut 0).await,
+        Err(RemotePlottingError::InvalidSector(_))
+    ));
+
+    // Finished job is no longer known to plotter
+    client.client.finish_job(job_id).await.unwrap();
+    assert!(client
+        .receive_sector(job_id, &request, &mut 0)
+        .await
+        .unwrap()
+        .is_none());
+
+    server_handle.stop().unwrap();
+}
+
+#[tokio::test]
+async fn busy_plotter() {
+    let remote_plotter = remote_plotter(1);
+    let (address, server_handle) =
+        start_remote_plotter_server("127.0.0.1:0".parse().unwrap(), remote_plotter.clone())
+            .await
+            .unwrap();
+    let client = RemotePlotterClient::new(&format!("http://{address}")).unwrap();
+
+    let request = plot_sector_request(1);
+    let (job_id, _sector) = insert_plotted_sector(&remote_plotter, &request);
+
+    // Plotted sector that was not downloaded yet occupies the only slot
+    let result = client.client.plot_sector(request.clone()).await;
+    assert!(matches!(
+        result,
+        Err(JsonRpseeError::Call(CallError::Custom(error_object)))
+            if error_object.code() == PLOTTER_BUSY_ERROR_CODE
+    ));
+
+    // Slot is freed once farmer finishes the job
+    client.client.finish_job(job_id).await.unwrap();
+    client.client.plot_sector(request).await.unwrap();
+
+    server_handle.stop().unwrap();
+}
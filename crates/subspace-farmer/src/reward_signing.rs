@@ -1,3 +1,4 @@
+use crate::error_code::ErrorCode;
 use crate::identity::Identity;
 use crate::node_client::NodeClient;
 use futures::StreamExt;
@@ -39,6 +40,7 @@ where
                 }
                 Err(error) => {
                     warn!(
+                        code = %ErrorCode::RewardSigningFailed,
                         %error,
                         "Failed to send signature for reward hash 0x{}",
                         hex::encode(hash),
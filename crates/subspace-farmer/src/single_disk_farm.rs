@@ -1,18 +1,28 @@
 pub mod farming;
 pub mod piece_cache;
 pub mod piece_reader;
+pub mod plot_encryption;
 mod plotting;
+#[cfg(test)]
+mod tests;
 
+use crate::error_code::ErrorCode;
 use crate::identity::{Identity, IdentityError};
 use crate::node_client::NodeClient;
+use crate::remote_plotter::RemotePlotterClient;
 use crate::reward_signing::reward_signing;
 use crate::single_disk_farm::farming::rayon_files::RayonFiles;
 pub use crate::single_disk_farm::farming::FarmingError;
 use crate::single_disk_farm::farming::{
     farming, slot_notification_forwarder, FarmingNotification, FarmingOptions, PlotAudit,
 };
-use crate::single_disk_farm::piece_cache::{DiskPieceCache, DiskPieceCacheError};
+use crate::single_disk_farm::piece_cache::{
+    DiskPieceCache, DiskPieceCacheError, PieceCacheRepairReport,
+};
 use crate::single_disk_farm::piece_reader::PieceReader;
+use crate::single_disk_farm::plot_encryption::{
+    PlotCipher, PlotEncryption, PlotEncryptionInfo, PlotEncryptionKeySource, PlotFile,
+};
 use crate::single_disk_farm::plotting::{
     plotting, plotting_scheduler, PlottingOptions, PlottingSchedulerOptions,
 };
@@ -20,7 +30,7 @@ pub use crate::single_disk_farm::plotting::{
     PlottingError, SectorExpirationDetails, SectorPlottingDetails,
 };
 use crate::thread_pool_manager::PlottingThreadPoolManager;
-use crate::utils::{tokio_rayon_spawn_handler, AsyncJoinOnDrop};
+use crate::utils::{tokio_thread_wrapper, AsyncJoinOnDrop};
 use crate::KNOWN_PEERS_CACHE_SIZE;
 use async_lock::RwLock;
 use derive_more::{Display, From};
@@ -31,7 +41,6 @@ use futures::{select, FutureExt, StreamExt};
 use parity_scale_codec::{Decode, Encode};
 use parking_lot::Mutex;
 use rayon::prelude::*;
-use rayon::ThreadPoolBuilder;
 use serde::{Deserialize, Serialize};
 use static_assertions::const_assert;
 use std::error::Error;
@@ -48,17 +57,21 @@ use std::{fs, io, mem};
 use subspace_core_primitives::crypto::blake3_hash;
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::{
-    Blake3Hash, HistorySize, Piece, PieceOffset, PublicKey, Record, SectorId, SectorIndex,
-    SegmentIndex,
+    Blake3Hash, HistorySize, Piece, PieceIndex, PieceOffset, PublicKey, Record, SectorId,
+    SectorIndex, SegmentIndex,
 };
 use subspace_erasure_coding::ErasureCoding;
 use subspace_farmer_components::file_ext::{FileExt, OpenOptionsExt};
 use subspace_farmer_components::plotting::PlottedSector;
-use subspace_farmer_components::sector::{sector_size, SectorMetadata, SectorMetadataChecksummed};
-use subspace_farmer_components::{FarmerProtocolInfo, PieceGetter};
+use subspace_farmer_components::sector::{
+    sector_size, SectorFormatError, SectorFormatVersion, SectorMetadata, SectorMetadataChecksummed,
+};
+use subspace_farmer_components::{FarmerProtocolInfo, PieceGetter, ReadAtSync};
 use subspace_networking::KnownPeersManager;
 use subspace_proof_of_space::Table;
 use subspace_rpc_primitives::{FarmerAppInfo, SolutionResponse};
+use subspace_temp_files::TempFileManager;
+use subspace_thread_pool::{ThreadPoolConfig, ThreadPoolManager};
 use thiserror::Error;
 use tokio::runtime::Handle;
 use tokio::sync::{broadcast, Semaphore};
@@ -117,6 +130,9 @@ pub enum SingleDiskFarmInfo {
         pieces_in_sector: u16,
         /// How much space in bytes is allocated for this farm
         allocated_space: u64,
+        /// Plot encryption information, `None` if plot is not encrypted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        plot_encryption: Option<PlotEncryptionInfo>,
     },
 }
 
@@ -129,6 +145,7 @@ impl SingleDiskFarmInfo {
         public_key: PublicKey,
         pieces_in_sector: u16,
         allocated_space: u64,
+        plot_encryption: Option<PlotEncryptionInfo>,
     ) -> Self {
         Self::V0 {
             id,
@@ -136,6 +153,7 @@ impl SingleDiskFarmInfo {
             public_key,
             pieces_in_sector,
             allocated_space,
+            plot_encryption,
         }
     }
 
@@ -208,6 +226,14 @@ impl SingleDiskFarmInfo {
         } = self;
         *allocated_space
     }
+
+    /// Plot encryption information, `None` if plot is not encrypted
+    pub fn plot_encryption(&self) -> Option<&PlotEncryptionInfo> {
+        let Self::V0 {
+            plot_encryption, ..
+        } = self;
+        plot_encryption.as_ref()
+    }
 }
 
 /// Summary of single disk farm for presentational purposes
@@ -234,6 +260,34 @@ pub enum SingleDiskFarmSummary {
     },
 }
 
+/// Parts of single disk farm to wipe
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SingleDiskFarmWipeTargets {
+    /// Plot itself
+    pub plot: bool,
+    /// Metadata of plotted sectors, sectors will be plotted from scratch afterwards
+    pub plots_metadata: bool,
+    /// Piece cache
+    pub piece_cache: bool,
+    /// Farm identity.
+    ///
+    /// Farm info, plot and its metadata are tied to identity and are always wiped together with it.
+    pub identity: bool,
+    /// Farm info, farm will be created from scratch afterwards
+    pub info: bool,
+}
+
+impl SingleDiskFarmWipeTargets {
+    /// Wipe everything, the whole farm
+    pub const ALL: Self = Self {
+        plot: true,
+        plots_metadata: true,
+        piece_cache: true,
+        identity: true,
+        info: true,
+    };
+}
+
 #[derive(Debug, Encode, Decode)]
 struct PlotMetadataHeader {
     version: u8,
@@ -284,6 +338,9 @@ pub struct SingleDiskFarmOptions<NC, PG> {
     /// Thread pool size used for farming (mostly for blocking I/O, but also for some
     /// compute-intensive operations during proving)
     pub farming_thread_pool_size: usize,
+    /// Thread pool manager that farming thread pool is created with, accounts it in shared CPU
+    /// budget
+    pub thread_pool_manager: ThreadPoolManager,
     /// Thread pool manager used for plotting
     pub plotting_thread_pool_manager: PlottingThreadPoolManager,
     /// Notification for plotter to start, can be used to delay plotting until some initialization
@@ -291,6 +348,13 @@ pub struct SingleDiskFarmOptions<NC, PG> {
     pub plotting_delay: Option<oneshot::Receiver<()>>,
     /// Disable farm locking, for example if file system doesn't support it
     pub disable_farm_locking: bool,
+    /// Issue read-ahead for audit of the next slot while previous slot is being proven, improves
+    /// audit latency on high-latency disks
+    pub audit_prefetch: bool,
+    /// Encrypt plot at rest, can only be enabled when farm is created
+    pub plot_encryption: Option<PlotEncryption>,
+    /// Remote plotter to request plotted sectors from instead of plotting them locally
+    pub remote_plotter: Option<RemotePlotterClient>,
 }
 
 /// Errors happening when trying to create/open single disk farm
@@ -342,6 +406,25 @@ pub enum SingleDiskFarmError {
         /// Current public key
         wrong_public_key: PublicKey,
     },
+    /// Plot encryption option doesn't match farm
+    #[error(
+        "Plot encryption of farm {id} is {expected:?}, but {provided:?} was requested, encryption \
+        can't be changed after farm creation"
+    )]
+    PlotEncryptionMismatch {
+        /// Farm ID
+        id: SingleDiskFarmId,
+        /// Key source of the encryption farm was created with, `None` if plot is not encrypted
+        expected: Option<PlotEncryptionKeySource>,
+        /// Requested key source, `None` if no encryption was requested
+        provided: Option<PlotEncryptionKeySource>,
+    },
+    /// Wrong plot encryption key
+    #[error("Plot encryption key of farm {id} doesn't match the key farm was created with")]
+    WrongPlotEncryptionKey {
+        /// Farm ID
+        id: SingleDiskFarmId,
+    },
     /// Invalid number pieces in sector
     #[error(
         "Invalid number pieces in sector: max supported {max_supported}, farm initialized with \
@@ -463,6 +546,11 @@ pub enum SingleDiskFarmScrubError {
         /// Disk farm info public key
         info: PublicKey,
     },
+    /// Plot encryption key derived from identity doesn't match the key farm was created with
+    #[error(
+        "Plot encryption key derived from identity doesn't match the key farm was created with"
+    )]
+    WrongPlotEncryptionKey,
     /// Metadata file does not exist
     #[error("Metadata file does not exist at {file}")]
     MetadataFileDoesNotExist {
@@ -496,6 +584,14 @@ pub enum SingleDiskFarmScrubError {
     /// Unexpected metadata version
     #[error("Unexpected metadata version {0}")]
     UnexpectedMetadataVersion(u8),
+    /// Failed to upgrade legacy metadata file
+    #[error("Failed to upgrade legacy metadata file {file}: {error}")]
+    FailedToUpgradeMetadataFile {
+        /// Affected file
+        file: PathBuf,
+        /// Low-level error
+        error: io::Error,
+    },
     /// Cache file does not exist
     #[error("Cache file does not exist at {file}")]
     CacheFileDoesNotExist {
@@ -529,6 +625,18 @@ pub enum BackgroundTaskError {
     BackgroundTaskPanicked { task: String },
 }
 
+impl BackgroundTaskError {
+    /// Stable machine-readable code of the error, see [`ErrorCode`]
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            BackgroundTaskError::Plotting(error) => error.error_code(),
+            BackgroundTaskError::Farming(error) => error.error_code(),
+            BackgroundTaskError::RewardSigning(_) => ErrorCode::RewardSigningFailed,
+            BackgroundTaskError::BackgroundTaskPanicked { .. } => ErrorCode::BackgroundTaskPanicked,
+        }
+    }
+}
+
 type BackgroundTask = Pin<Box<dyn Future<Output = Result<(), BackgroundTaskError>> + Send>>;
 
 type HandlerFn<A> = Arc<dyn Fn(&A) + Send + Sync + 'static>;
@@ -541,6 +649,8 @@ pub enum SectorUpdate {
     Plotting(SectorPlottingDetails),
     /// Sector expiration information updated
     Expiration(SectorExpirationDetails),
+    /// Sector failed auditing or proving repeatedly, it is no longer audited and will be replotted
+    Quarantined,
 }
 
 #[derive(Default, Debug)]
@@ -587,7 +697,11 @@ impl Drop for SingleDiskFarm {
 impl SingleDiskFarm {
     pub const PLOT_FILE: &'static str = "plot.bin";
     pub const METADATA_FILE: &'static str = "metadata.bin";
-    const SUPPORTED_PLOT_VERSION: u8 = 0;
+    /// Version of metadata file layout
+    const SUPPORTED_PLOT_VERSION: u8 = 1;
+    /// Version of metadata file layout before sector format versioning was introduced, sector
+    /// metadata was stored without format version, such files are upgraded on farm start
+    const LEGACY_PLOT_VERSION: u8 = 0;
 
     /// Create new single disk farm instance
     ///
@@ -615,15 +729,22 @@ impl SingleDiskFarm {
             downloading_semaphore,
             record_encoding_concurrency,
             farming_thread_pool_size,
+            thread_pool_manager,
             plotting_thread_pool_manager,
             plotting_delay,
             farm_during_initial_plotting,
             disable_farm_locking,
+            audit_prefetch,
+            plot_encryption,
+            remote_plotter,
         } = options;
         fs::create_dir_all(&directory)?;
 
         let identity = Identity::open_or_create(&directory)?;
         let public_key = identity.public_key().to_bytes().into();
+        let plot_cipher = plot_encryption
+            .as_ref()
+            .map(|plot_encryption| plot_encryption.cipher(&identity));
 
         let single_disk_farm_info = match SingleDiskFarmInfo::load_from(&directory)? {
             Some(mut single_disk_farm_info) => {
@@ -653,6 +774,30 @@ impl SingleDiskFarm {
                     });
                 }
 
+                match (single_disk_farm_info.plot_encryption(), &plot_encryption) {
+                    (None, None) => {}
+                    (Some(plot_encryption_info), Some(plot_encryption))
+                        if plot_encryption_info.key_source == plot_encryption.key_source() =>
+                    {
+                        let plot_cipher = plot_cipher
+                            .as_ref()
+                            .expect("Created above when encryption is requested; qed");
+                        if &plot_encryption_info.key_fingerprint != plot_cipher.key_fingerprint() {
+                            return Err(SingleDiskFarmError::WrongPlotEncryptionKey {
+                                id: *single_disk_farm_info.id(),
+                            });
+                        }
+                    }
+                    (expected, provided) => {
+                        return Err(SingleDiskFarmError::PlotEncryptionMismatch {
+                            id: *single_disk_farm_info.id(),
+                            expected: expected
+                                .map(|plot_encryption_info| plot_encryption_info.key_source),
+                            provided: provided.as_ref().map(PlotEncryption::key_source),
+                        });
+                    }
+                }
+
                 if max_pieces_in_sector > pieces_in_sector {
                     info!(
                         pieces_in_sector,
@@ -689,6 +834,15 @@ impl SingleDiskFarm {
                     public_key,
                     max_pieces_in_sector,
                     allocated_space,
+                    plot_encryption
+                        .as_ref()
+                        .map(|plot_encryption| PlotEncryptionInfo {
+                            key_source: plot_encryption.key_source(),
+                            key_fingerprint: *plot_cipher
+                                .as_ref()
+                                .expect("Created above when encryption is requested; qed")
+                                .key_fingerprint(),
+                        }),
                 );
 
                 single_disk_farm_info.store_to(&directory)?;
@@ -705,11 +859,15 @@ impl SingleDiskFarm {
                     .map_err(SingleDiskFarmError::LikelyAlreadyInUse)?,
             )
         };
+        // Removes temporary files left behind if previous run has crashed
+        let temp_files = TempFileManager::open(&directory)?;
 
         let pieces_in_sector = single_disk_farm_info.pieces_in_sector();
         let sector_size = sector_size(pieces_in_sector);
-        let sector_metadata_size = SectorMetadataChecksummed::encoded_size();
-        let single_sector_overhead = (sector_size + sector_metadata_size) as u64;
+        // Version prefix of sector metadata is not accounted for here to make sure number of sectors
+        // of existing farms doesn't change, it is tiny and fits into reserved space anyway
+        let single_sector_overhead =
+            (sector_size + SectorMetadataChecksummed::encoded_size()) as u64;
         // Fixed space usage regardless of plot size
         let fixed_space_usage = RESERVED_PLOT_METADATA
             + RESERVED_FARM_INFO
@@ -767,6 +925,7 @@ impl SingleDiskFarm {
             }
         };
 
+        let sector_metadata_size = SectorMetadataChecksummed::encoded_size_with_version();
         let metadata_file_path = directory.join(Self::METADATA_FILE);
         let mut metadata_file = OpenOptions::new()
             .read(true)
@@ -782,7 +941,7 @@ impl SingleDiskFarm {
             RESERVED_PLOT_METADATA + sector_metadata_size as u64 * u64::from(target_sector_count);
         let metadata_header = if metadata_size == 0 {
             let metadata_header = PlotMetadataHeader {
-                version: 0,
+                version: Self::SUPPORTED_PLOT_VERSION,
                 plotted_sector_count: 0,
             };
 
@@ -810,20 +969,47 @@ impl SingleDiskFarm {
                 PlotMetadataHeader::decode(&mut metadata_header_bytes.as_ref())
                     .map_err(SingleDiskFarmError::FailedToDecodeMetadataHeader)?;
 
+            if metadata_header.plotted_sector_count > target_sector_count {
+                metadata_header.plotted_sector_count = target_sector_count;
+                metadata_file.write_all_at(&metadata_header.encode(), 0)?;
+            }
+
+            if metadata_header.version == Self::LEGACY_PLOT_VERSION {
+                info!(
+                    path = %metadata_file_path.display(),
+                    "Upgrading metadata file to versioned sector metadata"
+                );
+                upgrade_legacy_metadata_file(
+                    &temp_files,
+                    &metadata_file_path,
+                    &metadata_file,
+                    &mut metadata_header,
+                )?;
+
+                // Upgraded metadata file has replaced the original one
+                metadata_file = OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .advise_random_access()
+                    .open(&metadata_file_path)?;
+                metadata_file.advise_random_access()?;
+                metadata_file
+                    .preallocate(expected_metadata_size)
+                    .map_err(SingleDiskFarmError::CantPreallocateMetadataFile)?;
+                metadata_file.set_len(expected_metadata_size)?;
+            }
+
             if metadata_header.version != Self::SUPPORTED_PLOT_VERSION {
                 return Err(SingleDiskFarmError::UnexpectedMetadataVersion(
                     metadata_header.version,
                 ));
             }
 
-            if metadata_header.plotted_sector_count > target_sector_count {
-                metadata_header.plotted_sector_count = target_sector_count;
-                metadata_file.write_all_at(&metadata_header.encode(), 0)?;
-            }
-
             metadata_header
         };
 
+        // Sectors plotted in older format that need to be upgraded
+        let mut outdated_sectors = Vec::new();
         let sectors_metadata = {
             let mut sectors_metadata =
                 Vec::<SectorMetadataChecksummed>::with_capacity(usize::from(target_sector_count));
@@ -834,43 +1020,52 @@ impl SingleDiskFarm {
                     RESERVED_PLOT_METADATA + sector_metadata_size as u64 * u64::from(sector_index);
                 metadata_file.read_exact_at(&mut sector_metadata_bytes, sector_offset)?;
 
-                let sector_metadata =
-                    match SectorMetadataChecksummed::decode(&mut sector_metadata_bytes.as_ref()) {
-                        Ok(sector_metadata) => sector_metadata,
-                        Err(error) => {
-                            warn!(
-                                path = %metadata_file_path.display(),
-                                %error,
-                                %sector_index,
-                                "Failed to decode sector metadata, replacing with dummy expired \
-                                sector metadata"
-                            );
+                let sector_metadata = match SectorMetadataChecksummed::decode_with_version(
+                    &mut sector_metadata_bytes.as_ref(),
+                ) {
+                    Ok((version, sector_metadata)) => {
+                        if version < SectorFormatVersion::CURRENT {
+                            outdated_sectors.push((sector_index, version));
+                        }
 
-                            let dummy_sector = SectorMetadataChecksummed::from(SectorMetadata {
-                                sector_index,
-                                pieces_in_sector,
-                                s_bucket_sizes: Box::new([0; Record::NUM_S_BUCKETS]),
-                                history_size: HistorySize::from(SegmentIndex::ZERO),
-                            });
-                            metadata_file.write_all_at(&dummy_sector.encode(), sector_offset)?;
+                        sector_metadata
+                    }
+                    Err(error) => {
+                        warn!(
+                            code = %ErrorCode::SectorMetadataCorrupted,
+                            path = %metadata_file_path.display(),
+                            %error,
+                            %sector_index,
+                            "Failed to decode sector metadata, replacing with dummy expired \
+                            sector metadata"
+                        );
 
-                            dummy_sector
-                        }
-                    };
+                        let dummy_sector = SectorMetadataChecksummed::from(SectorMetadata {
+                            sector_index,
+                            pieces_in_sector,
+                            s_bucket_sizes: Box::new([0; Record::NUM_S_BUCKETS]),
+                            history_size: HistorySize::from(SegmentIndex::ZERO),
+                        });
+                        metadata_file.write_all_at(
+                            &dummy_sector.encode_with_version(SectorFormatVersion::CURRENT),
+                            sector_offset,
+                        )?;
+
+                        dummy_sector
+                    }
+                };
                 sectors_metadata.push(sector_metadata);
             }
 
             Arc::new(RwLock::new(sectors_metadata))
         };
 
-        let plot_file = Arc::new(
-            OpenOptions::new()
-                .read(true)
-                .write(true)
-                .create(true)
-                .advise_random_access()
-                .open(directory.join(Self::PLOT_FILE))?,
-        );
+        let plot_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .advise_random_access()
+            .open(directory.join(Self::PLOT_FILE))?;
 
         plot_file.advise_random_access()?;
 
@@ -882,7 +1077,9 @@ impl SingleDiskFarm {
         // Truncating file (if necessary)
         plot_file.set_len(sector_size as u64 * u64::from(target_sector_count))?;
 
-        let piece_cache = DiskPieceCache::open(&directory, cache_capacity)?;
+        let plot_file = Arc::new(PlotFile::new(plot_file, plot_cipher.clone()));
+
+        let piece_cache = DiskPieceCache::open(&directory, cache_capacity, temp_files)?;
 
         let (error_sender, error_receiver) = oneshot::channel();
         let error_sender = Arc::new(Mutex::new(Some(error_sender)));
@@ -902,6 +1099,7 @@ impl SingleDiskFarm {
         let (stop_sender, mut stop_receiver) = broadcast::channel::<()>(1);
         let modifying_sector_index = Arc::<RwLock<Option<SectorIndex>>>::default();
         let (sectors_to_plot_sender, sectors_to_plot_receiver) = mpsc::channel(1);
+        let (sectors_to_replot_sender, sectors_to_replot_receiver) = mpsc::channel(10);
         // Some sectors may already be plotted, skip them
         let sectors_indices_left_to_plot =
             metadata_header.plotted_sector_count..target_sector_count;
@@ -939,6 +1137,7 @@ impl SingleDiskFarm {
                     plot_file,
                     metadata_file,
                     sectors_metadata,
+                    outdated_sectors,
                     piece_getter: &piece_getter,
                     kzg: &kzg,
                     erasure_coding: &erasure_coding,
@@ -948,6 +1147,7 @@ impl SingleDiskFarm {
                     downloading_semaphore,
                     record_encoding_concurrency,
                     plotting_thread_pool_manager,
+                    remote_plotter,
                     stop_receiver: stop_receiver.resubscribe(),
                 };
 
@@ -1008,6 +1208,7 @@ impl SingleDiskFarm {
             handlers: Arc::clone(&handlers),
             sectors_metadata: Arc::clone(&sectors_metadata),
             sectors_to_plot_sender,
+            sectors_to_replot_receiver,
             initial_plotting_finished: farming_delay_sender,
             new_segment_processing_delay: NEW_SEGMENT_PROCESSING_DELAY,
         };
@@ -1017,9 +1218,10 @@ impl SingleDiskFarm {
 
         tasks.push(Box::pin({
             let node_client = node_client.clone();
+            let handlers = Arc::clone(&handlers);
 
             async move {
-                slot_notification_forwarder(&node_client, slot_info_forwarder_sender)
+                slot_notification_forwarder(&node_client, slot_info_forwarder_sender, handlers)
                     .await
                     .map_err(BackgroundTaskError::Farming)
             }
@@ -1037,13 +1239,18 @@ impl SingleDiskFarm {
 
             move || {
                 let _span_guard = span.enter();
-                let thread_pool = match ThreadPoolBuilder::new()
-                    .thread_name(move |thread_index| {
-                        format!("farming-{disk_farm_index}.{thread_index}")
-                    })
-                    .num_threads(farming_thread_pool_size)
-                    .spawn_handler(tokio_rayon_spawn_handler())
-                    .build()
+                let thread_pool = match thread_pool_manager
+                    .build(
+                        ThreadPoolConfig::new(
+                            format!("farming-{disk_farm_index}"),
+                            NonZeroUsize::new(farming_thread_pool_size)
+                                .unwrap_or(NonZeroUsize::MIN),
+                        )
+                        // Farming is mostly blocking I/O, so farming thread pools of different
+                        // farms share the same reservation
+                        .group("farming")
+                        .thread_wrapper(tokio_thread_wrapper()),
+                    )
                     .map_err(FarmingError::FailedToCreateThreadPool)
                 {
                     Ok(thread_pool) => thread_pool,
@@ -1078,7 +1285,10 @@ impl SingleDiskFarm {
                             }
                         }
 
-                        let plot = RayonFiles::open(&directory.join(Self::PLOT_FILE))?;
+                        let plot = PlotFile::new(
+                            RayonFiles::open(&directory.join(Self::PLOT_FILE))?,
+                            plot_cipher,
+                        );
                         let plot_audit = PlotAudit::new(&plot);
 
                         let farming_options = FarmingOptions {
@@ -1092,6 +1302,8 @@ impl SingleDiskFarm {
                             handlers,
                             modifying_sector_index,
                             slot_info_notifications: slot_info_forwarder_receiver,
+                            audit_prefetch,
+                            sectors_to_replot_sender,
                         };
                         farming::<PosTable, _, _>(farming_options).await
                     };
@@ -1227,7 +1439,6 @@ impl SingleDiskFarm {
             .open(directory.join(Self::METADATA_FILE))?;
 
         let metadata_size = metadata_file.seek(SeekFrom::End(0))?;
-        let sector_metadata_size = SectorMetadataChecksummed::encoded_size();
 
         let mut metadata_header_bytes = vec![0; PlotMetadataHeader::encoded_size()];
         metadata_file.read_exact_at(&mut metadata_header_bytes, 0)?;
@@ -1240,12 +1451,22 @@ impl SingleDiskFarm {
                 )
             })?;
 
-        if metadata_header.version != SingleDiskFarm::SUPPORTED_PLOT_VERSION {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Unsupported metadata version {}", metadata_header.version),
-            ));
-        }
+        // Legacy metadata file is readable as is, it will be upgraded on next farm start
+        let legacy = match metadata_header.version {
+            Self::LEGACY_PLOT_VERSION => true,
+            Self::SUPPORTED_PLOT_VERSION => false,
+            version => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("Unsupported metadata version {version}"),
+                ));
+            }
+        };
+        let sector_metadata_size = if legacy {
+            SectorMetadataChecksummed::encoded_size()
+        } else {
+            SectorMetadataChecksummed::encoded_size_with_version()
+        };
 
         let mut sectors_metadata = Vec::<SectorMetadataChecksummed>::with_capacity(
             ((metadata_size - RESERVED_PLOT_METADATA) / sector_metadata_size as u64) as usize,
@@ -1257,16 +1478,19 @@ impl SingleDiskFarm {
                 &mut sector_metadata_bytes,
                 RESERVED_PLOT_METADATA + sector_metadata_size as u64 * u64::from(sector_index),
             )?;
-            sectors_metadata.push(
-                SectorMetadataChecksummed::decode(&mut sector_metadata_bytes.as_ref()).map_err(
-                    |error| {
-                        io::Error::new(
-                            io::ErrorKind::Other,
-                            format!("Failed to decode sector metadata: {}", error),
-                        )
-                    },
-                )?,
-            );
+            let sector_metadata = if legacy {
+                SectorMetadataChecksummed::decode(&mut sector_metadata_bytes.as_ref())
+                    .map_err(SectorFormatError::from)
+            } else {
+                SectorMetadataChecksummed::decode_with_version(&mut sector_metadata_bytes.as_ref())
+                    .map(|(_version, sector_metadata)| sector_metadata)
+            };
+            sectors_metadata.push(sector_metadata.map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("Failed to decode sector metadata: {}", error),
+                )
+            })?);
         }
 
         Ok(sectors_metadata)
@@ -1332,6 +1556,38 @@ impl SingleDiskFarm {
             })
     }
 
+    /// Piece indices that will be needed for plotting of up to `max_sectors` next sectors that are
+    /// not plotted yet, in order of plotting.
+    ///
+    /// This is a prediction based on history size known at farm start, actual plotting will use
+    /// history size at the time sector is plotted, which may result in different piece indices.
+    pub async fn upcoming_piece_indices(&self, max_sectors: SectorIndex) -> Vec<PieceIndex> {
+        let public_key = self.single_disk_farm_info.public_key();
+        let plotted_sectors_count = self.plotted_sectors_count().await;
+        let upcoming_sectors = plotted_sectors_count
+            ..self
+                .total_sectors_count
+                .min(plotted_sectors_count.saturating_add(max_sectors));
+
+        upcoming_sectors
+            .flat_map(|sector_index| {
+                let sector_id = SectorId::new(public_key.hash(), sector_index);
+
+                (PieceOffset::ZERO..)
+                    .take(usize::from(self.pieces_in_sector))
+                    .map(move |piece_offset| {
+                        sector_id.derive_piece_index(
+                            piece_offset,
+                            self.farmer_protocol_info.history_size,
+                            self.farmer_protocol_info.max_pieces_in_sector,
+                            self.farmer_protocol_info.recent_segments,
+                            self.farmer_protocol_info.recent_history_fraction,
+                        )
+                    })
+            })
+            .collect()
+    }
+
     /// Get piece cache instance
     pub fn piece_cache(&self) -> DiskPieceCache {
         self.piece_cache.clone()
@@ -1365,7 +1621,15 @@ impl SingleDiskFarm {
         }
 
         while let Some(result) = self.tasks.next().instrument(self.span.clone()).await {
-            result?;
+            if let Err(error) = result {
+                error!(
+                    parent: &self.span,
+                    code = %error.error_code(),
+                    %error,
+                    "Farm background task failed"
+                );
+                return Err(error.into());
+            }
         }
 
         Ok(*self.id())
@@ -1373,6 +1637,50 @@ impl SingleDiskFarm {
 
     /// Wipe everything that belongs to this single disk farm
     pub fn wipe(directory: &Path) -> io::Result<()> {
+        Self::wipe_targets(directory, SingleDiskFarmWipeTargets::ALL)
+    }
+
+    /// Files that will be deleted by [`Self::wipe_targets()`] with the same arguments, only files
+    /// that actually exist are returned
+    pub fn wipe_files(directory: &Path, targets: SingleDiskFarmWipeTargets) -> Vec<PathBuf> {
+        let SingleDiskFarmWipeTargets {
+            plot,
+            plots_metadata,
+            piece_cache,
+            identity,
+            info,
+        } = targets;
+        // Farm info stores public key of the identity and sectors are plotted for it, farm would
+        // not open with a new identity otherwise
+        let plot = plot || identity;
+        let plots_metadata = plots_metadata || identity;
+        let info = info || identity;
+
+        let mut files = Vec::new();
+        if plot {
+            files.push(directory.join(Self::PLOT_FILE));
+        }
+        if plots_metadata {
+            files.push(directory.join(Self::METADATA_FILE));
+        }
+        if piece_cache {
+            files.extend(DiskPieceCache::files(directory));
+        }
+        if identity {
+            files.push(directory.join(Identity::FILE_NAME));
+        }
+        // Info file goes last, such that partially wiped farm can still be recognized as one
+        if info {
+            files.push(directory.join(TempFileManager::MANIFEST_FILE_NAME));
+            files.push(directory.join(SingleDiskFarmInfo::FILE_NAME));
+        }
+
+        files.retain(|file| file.exists());
+        files
+    }
+
+    /// Wipe only specified parts of the farm
+    pub fn wipe_targets(directory: &Path, targets: SingleDiskFarmWipeTargets) -> io::Result<()> {
         let single_disk_info_info_path = directory.join(SingleDiskFarmInfo::FILE_NAME);
         match SingleDiskFarmInfo::load_from(directory) {
             Ok(Some(single_disk_farm_info)) => {
@@ -1392,37 +1700,30 @@ impl SingleDiskFarm {
             }
         }
 
-        {
-            let plot = directory.join(Self::PLOT_FILE);
-            if plot.exists() {
-                info!("Deleting plot file at {}", plot.display());
-                fs::remove_file(plot)?;
-            }
-        }
-        {
-            let metadata = directory.join(Self::METADATA_FILE);
-            if metadata.exists() {
-                info!("Deleting metadata file at {}", metadata.display());
-                fs::remove_file(metadata)?;
-            }
-        }
-        // TODO: Identity should be able to wipe itself instead of assuming a specific file name
-        //  here
-        {
-            let identity = directory.join("identity.bin");
-            if identity.exists() {
-                info!("Deleting identity file at {}", identity.display());
-                fs::remove_file(identity)?;
-            }
+        for file in Self::wipe_files(directory, targets) {
+            info!("Deleting {}", file.display());
+            fs::remove_file(file)?;
         }
 
-        DiskPieceCache::wipe(directory)?;
+        Ok(())
+    }
 
-        info!(
-            "Deleting info file at {}",
-            single_disk_info_info_path.display()
-        );
-        fs::remove_file(single_disk_info_info_path)
+    /// Salvage valid pieces of the farm's piece cache after abrupt shutdown (like power loss) or
+    /// disk errors, corrupted cache elements are erased.
+    pub fn repair_cache(
+        directory: &Path,
+        disable_farm_locking: bool,
+    ) -> Result<PieceCacheRepairReport, SingleDiskFarmError> {
+        let _single_disk_farm_info_lock = if disable_farm_locking {
+            None
+        } else {
+            Some(
+                SingleDiskFarmInfo::try_lock(directory)
+                    .map_err(SingleDiskFarmError::LikelyAlreadyInUse)?,
+            )
+        };
+
+        Ok(DiskPieceCache::repair(directory)?)
     }
 
     /// Check the farm for corruption and repair errors (caused by disk errors or something else),
@@ -1479,7 +1780,28 @@ impl SingleDiskFarm {
             });
         }
 
-        let sector_metadata_size = SectorMetadataChecksummed::encoded_size();
+        // Plot contents can only be checked when encryption key is available
+        let (plot_cipher, check_plot_contents) = match info.plot_encryption() {
+            None => (None, true),
+            Some(plot_encryption_info) => match plot_encryption_info.key_source {
+                PlotEncryptionKeySource::Identity => {
+                    let plot_cipher = PlotCipher::from_identity(&identity);
+                    if plot_cipher.key_fingerprint() != &plot_encryption_info.key_fingerprint {
+                        return Err(SingleDiskFarmScrubError::WrongPlotEncryptionKey);
+                    }
+                    (Some(plot_cipher), true)
+                }
+                PlotEncryptionKeySource::External => {
+                    warn!(
+                        "Plot is encrypted with external key, plotted sectors contents will not \
+                        be checked"
+                    );
+                    (None, false)
+                }
+            },
+        };
+
+        let sector_metadata_size = SectorMetadataChecksummed::encoded_size_with_version();
 
         let metadata_file_path = directory.join(Self::METADATA_FILE);
         let (metadata_file, mut metadata_header) = {
@@ -1508,7 +1830,7 @@ impl SingleDiskFarm {
             // Error doesn't matter here
             let _ = metadata_file.advise_sequential_access();
 
-            let metadata_size = match metadata_file.seek(SeekFrom::End(0)) {
+            let mut metadata_size = match metadata_file.seek(SeekFrom::End(0)) {
                 Ok(metadata_size) => metadata_size,
                 Err(error) => {
                     return Err(SingleDiskFarmScrubError::FailedToDetermineFileSize {
@@ -1542,6 +1864,54 @@ impl SingleDiskFarm {
                     .map_err(SingleDiskFarmScrubError::FailedToDecodeMetadataHeader)?
             };
 
+            if metadata_header.version == Self::LEGACY_PLOT_VERSION {
+                info!(
+                    path = %metadata_file_path.display(),
+                    "Upgrading metadata file to versioned sector metadata"
+                );
+
+                let upgrade_result = TempFileManager::open(directory).and_then(|temp_files| {
+                    upgrade_legacy_metadata_file(
+                        &temp_files,
+                        &metadata_file_path,
+                        &metadata_file,
+                        &mut metadata_header,
+                    )
+                });
+                if let Err(error) = upgrade_result {
+                    return Err(SingleDiskFarmScrubError::FailedToUpgradeMetadataFile {
+                        file: metadata_file_path,
+                        error,
+                    });
+                }
+
+                // Upgraded metadata file has replaced the original one
+                metadata_file = match OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .open(&metadata_file_path)
+                {
+                    Ok(metadata_file) => metadata_file,
+                    Err(error) => {
+                        return Err(SingleDiskFarmScrubError::MetadataCantBeOpened {
+                            file: metadata_file_path,
+                            error,
+                        });
+                    }
+                };
+                // Error doesn't matter here
+                let _ = metadata_file.advise_sequential_access();
+                metadata_size = match metadata_file.seek(SeekFrom::End(0)) {
+                    Ok(metadata_size) => metadata_size,
+                    Err(error) => {
+                        return Err(SingleDiskFarmScrubError::FailedToDetermineFileSize {
+                            file: metadata_file_path,
+                            error,
+                        });
+                    }
+                };
+            }
+
             if metadata_header.version != Self::SUPPORTED_PLOT_VERSION {
                 return Err(SingleDiskFarmScrubError::UnexpectedMetadataVersion(
                     metadata_header.version,
@@ -1641,7 +2011,7 @@ impl SingleDiskFarm {
                 }
             }
 
-            plot_file
+            PlotFile::new(plot_file, plot_cipher)
         };
 
         info!("Checking sectors and corresponding metadata");
@@ -1661,6 +2031,7 @@ impl SingleDiskFarm {
                         + u64::from(sector_index) * sector_metadata_size as u64;
                     if let Err(error) = metadata_file.read_exact_at(sector_metadata_bytes, offset) {
                         warn!(
+                            code = %ErrorCode::SectorMetadataCorrupted,
                             path = %metadata_file_path.display(),
                             %error,
                             %sector_index,
@@ -1678,12 +2049,13 @@ impl SingleDiskFarm {
                         return Ok(());
                     }
 
-                    let sector_metadata = match SectorMetadataChecksummed::decode(
+                    let sector_metadata = match SectorMetadataChecksummed::decode_with_version(
                         &mut sector_metadata_bytes.as_slice(),
                     ) {
-                        Ok(sector_metadata) => sector_metadata,
+                        Ok((_version, sector_metadata)) => sector_metadata,
                         Err(error) => {
                             warn!(
+                                code = %ErrorCode::SectorMetadataCorrupted,
                                 path = %metadata_file_path.display(),
                                 %error,
                                 %sector_index,
@@ -1703,6 +2075,7 @@ impl SingleDiskFarm {
 
                     if sector_metadata.sector_index != sector_index {
                         warn!(
+                            code = %ErrorCode::SectorMetadataCorrupted,
                             path = %metadata_file_path.display(),
                             %sector_index,
                             found_sector_index = sector_metadata.sector_index,
@@ -1720,6 +2093,7 @@ impl SingleDiskFarm {
 
                     if sector_metadata.pieces_in_sector != pieces_in_sector {
                         warn!(
+                            code = %ErrorCode::SectorMetadataCorrupted,
                             path = %metadata_file_path.display(),
                             %sector_index,
                             %pieces_in_sector,
@@ -1737,13 +2111,18 @@ impl SingleDiskFarm {
                         return Ok(());
                     }
 
+                    if !check_plot_contents {
+                        return Ok(());
+                    }
+
                     let mut hasher = blake3::Hasher::new();
                     for piece_offset in 0..pieces_in_sector {
                         let offset = u64::from(sector_index) * sector_size
                             + u64::from(piece_offset) * Piece::SIZE as u64;
 
-                        if let Err(error) = plot_file.read_exact_at(piece.as_mut(), offset) {
+                        if let Err(error) = plot_file.read_at(piece.as_mut(), offset) {
                             warn!(
+                                code = %ErrorCode::SectorReadFailed,
                                 path = %plot_file_path.display(),
                                 %error,
                                 %sector_index,
@@ -1768,8 +2147,7 @@ impl SingleDiskFarm {
                     {
                         let offset = u64::from(sector_index) * sector_size
                             + u64::from(pieces_in_sector) * Piece::SIZE as u64;
-                        if let Err(error) = plot_file.read_exact_at(&mut expected_checksum, offset)
-                        {
+                        if let Err(error) = plot_file.read_at(&mut expected_checksum, offset) {
                             return Err(SingleDiskFarmScrubError::FailedToReadBytes {
                                 file: plot_file_path.clone(),
                                 size: expected_checksum.len() as u64,
@@ -1897,6 +2275,7 @@ impl SingleDiskFarm {
                     let offset = cache_offset * element_size as u64;
                     if let Err(error) = cache_file.read_exact_at(element, offset) {
                         warn!(
+                            code = %ErrorCode::CacheReadFailed,
                             path = %file.display(),
                             %cache_offset,
                             size = %element.len() as u64,
@@ -1922,6 +2301,7 @@ impl SingleDiskFarm {
                     let actual_checksum = blake3_hash(index_and_piece_bytes);
                     if actual_checksum != expected_checksum && element != &dummy_element {
                         warn!(
+                            code = %ErrorCode::CacheChecksumMismatch,
                             %cache_offset,
                             actual_checksum = %hex::encode(actual_checksum),
                             expected_checksum = %hex::encode(expected_checksum),
@@ -1980,9 +2360,9 @@ fn write_dummy_sector_metadata(
         s_bucket_sizes: Box::new([0; Record::NUM_S_BUCKETS]),
         history_size: HistorySize::from(SegmentIndex::ZERO),
     })
-    .encode();
+    .encode_with_version(SectorFormatVersion::CURRENT);
     let sector_offset = RESERVED_PLOT_METADATA
-        + u64::from(sector_index) * SectorMetadataChecksummed::encoded_size() as u64;
+        + u64::from(sector_index) * SectorMetadataChecksummed::encoded_size_with_version() as u64;
     metadata_file
         .write_all_at(&dummy_sector_bytes, sector_offset)
         .map_err(|error| SingleDiskFarmScrubError::FailedToWriteBytes {
@@ -1992,3 +2372,46 @@ fn write_dummy_sector_metadata(
             error,
         })
 }
+
+/// Upgrade metadata file from [`SingleDiskFarm::LEGACY_PLOT_VERSION`] layout, where sector metadata
+/// was stored without format version.
+///
+/// Sector metadata entries become slightly larger and are tagged with [`SectorFormatVersion::V0`],
+/// such that sectors are upgraded further in the background. Upgraded contents are written into a
+/// temporary file that atomically replaces metadata file, such that interruption at any point
+/// leaves either legacy or upgraded metadata file intact. `metadata_file` must be opened again
+/// afterwards.
+fn upgrade_legacy_metadata_file(
+    temp_files: &TempFileManager,
+    metadata_file_path: &Path,
+    metadata_file: &File,
+    metadata_header: &mut PlotMetadataHeader,
+) -> io::Result<()> {
+    let legacy_sector_metadata_size = SectorMetadataChecksummed::encoded_size();
+    let sector_metadata_size = SectorMetadataChecksummed::encoded_size_with_version();
+    let plotted_sector_count = usize::from(metadata_header.plotted_sector_count);
+
+    let mut legacy_sectors_metadata = vec![0; legacy_sector_metadata_size * plotted_sector_count];
+    metadata_file.read_exact_at(&mut legacy_sectors_metadata, RESERVED_PLOT_METADATA)?;
+
+    let mut upgraded_metadata = vec![0; RESERVED_PLOT_METADATA as usize];
+    upgraded_metadata.reserve(sector_metadata_size * plotted_sector_count);
+    for legacy_sector_metadata in legacy_sectors_metadata.chunks_exact(legacy_sector_metadata_size)
+    {
+        upgraded_metadata.extend_from_slice(&SectorFormatVersion::V0.encode());
+        upgraded_metadata.extend_from_slice(legacy_sector_metadata);
+    }
+
+    let upgraded_metadata_header = PlotMetadataHeader {
+        version: SingleDiskFarm::SUPPORTED_PLOT_VERSION,
+        plotted_sector_count: metadata_header.plotted_sector_count,
+    };
+    upgraded_metadata[..PlotMetadataHeader::encoded_size()]
+        .copy_from_slice(&upgraded_metadata_header.encode());
+
+    temp_files.write_atomically(metadata_file_path, &upgraded_metadata)?;
+
+    *metadata_header = upgraded_metadata_header;
+
+    Ok(())
+}
@@ -1,29 +1,55 @@
 pub mod rayon_files;
+#[cfg(test)]
+mod tests;
 
+use crate::error_code::ErrorCode;
 use crate::node_client;
 use crate::node_client::NodeClient;
-use crate::single_disk_farm::Handlers;
+use crate::single_disk_farm::{Handlers, SectorUpdate};
 use async_lock::RwLock;
 use futures::channel::mpsc;
-use futures::StreamExt;
+use futures::{FutureExt, StreamExt};
 use parity_scale_codec::{Decode, Encode, Error, Input, Output};
 use parking_lot::Mutex;
 use rayon::ThreadPoolBuildError;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use std::{fmt, io};
 use subspace_core_primitives::crypto::kzg::Kzg;
-use subspace_core_primitives::{PosSeed, PublicKey, SectorIndex, Solution, SolutionRange};
+use subspace_core_primitives::{
+    HistorySize, PosSeed, PublicKey, SectorIndex, SlotNumber, Solution, SolutionRange,
+};
 use subspace_erasure_coding::ErasureCoding;
-use subspace_farmer_components::auditing::{audit_plot_sync, AuditingError};
+use subspace_farmer_components::auditing::{
+    audit_plot_sync, prefetch_plot_audit_sync, AuditingError,
+};
 use subspace_farmer_components::proving::{ProvableSolutions, ProvingError};
 use subspace_farmer_components::sector::SectorMetadataChecksummed;
 use subspace_farmer_components::ReadAtSync;
 use subspace_proof_of_space::{Table, TableGenerator};
-use subspace_rpc_primitives::{SlotInfo, SolutionResponse};
+use subspace_rpc_primitives::{FutureHistorySize, SlotInfo, SolutionResponse};
 use thiserror::Error;
 use tracing::{debug, error, info, trace, warn};
 
+/// How often to check whether node caught up with history size of sectors, solutions for which
+/// were rejected by node due to node not knowing about such history yet
+const LAGGING_NODE_HISTORY_SIZE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// Slots are skipped when node has blocks waiting for import and its best block is older than
+/// this number of slots
+const MAX_BEST_BLOCK_SLOT_LAG: SlotNumber = 60;
+/// Slot notifications arrive every second, gap larger than this means farmer (or the whole
+/// machine) was not running in between, for instance due to system suspend or VM pause
+const MAX_SLOT_NOTIFICATION_GAP: Duration = Duration::from_secs(30);
+/// Max difference between elapsed wall clock and monotonic clock time before it is considered to
+/// be a clock jump (monotonic clock doesn't advance during system suspend on some platforms)
+const MAX_CLOCK_DRIFT: Duration = Duration::from_secs(10);
+/// Number of times in a row sector can fail auditing or proving before it is quarantined
+const MAX_CONSECUTIVE_SECTOR_FAILURES: u32 = 10;
+/// Weight of the latest interval between slots in the average used to predict next slot arrival
+const SLOT_INTERVAL_AVERAGE_WEIGHT: u32 = 8;
+
 /// Auditing details
 #[derive(Debug, Copy, Clone, Encode, Decode)]
 pub struct AuditingDetails {
@@ -64,6 +90,174 @@ pub struct ProvingDetails {
     pub time: Duration,
 }
 
+/// Clock jump details
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct ClockJumpDetails {
+    /// Time elapsed since previous slot notification according to monotonic clock
+    pub monotonic_gap: Duration,
+    /// Time elapsed since previous slot notification according to wall clock, zero if wall clock
+    /// went backwards
+    pub wall_clock_gap: Duration,
+}
+
+/// Detects system suspend/resume and large clock jumps between slot notifications
+#[derive(Debug)]
+pub(super) struct ClockJumpDetector {
+    last_instant: Instant,
+    last_system_time: SystemTime,
+}
+
+impl ClockJumpDetector {
+    pub(super) fn new() -> Self {
+        Self::new_at(Instant::now(), SystemTime::now())
+    }
+
+    fn new_at(instant: Instant, system_time: SystemTime) -> Self {
+        Self {
+            last_instant: instant,
+            last_system_time: system_time,
+        }
+    }
+
+    /// Check for clock jump since previous check, returns details if there was one
+    pub(super) fn check(&mut self) -> Option<ClockJumpDetails> {
+        self.check_at(Instant::now(), SystemTime::now())
+    }
+
+    fn check_at(&mut self, instant: Instant, system_time: SystemTime) -> Option<ClockJumpDetails> {
+        let monotonic_gap = instant.saturating_duration_since(self.last_instant);
+        let wall_clock_gap = system_time
+            .duration_since(self.last_system_time)
+            .unwrap_or_default();
+        let wall_clock_went_backwards = system_time < self.last_system_time;
+
+        self.last_instant = instant;
+        self.last_system_time = system_time;
+
+        let clock_drift = if monotonic_gap > wall_clock_gap {
+            monotonic_gap - wall_clock_gap
+        } else {
+            wall_clock_gap - monotonic_gap
+        };
+
+        (monotonic_gap > MAX_SLOT_NOTIFICATION_GAP
+            || wall_clock_went_backwards
+            || clock_drift > MAX_CLOCK_DRIFT)
+            .then_some(ClockJumpDetails {
+                monotonic_gap,
+                wall_clock_gap,
+            })
+    }
+}
+
+/// Predicts arrival of the next slot notification from intervals between previous ones.
+///
+/// Global challenge of the next slot is derived from proof of time that doesn't exist before the
+/// slot arrives, but its arrival time is predictable, which allows to check for it throughout
+/// proving of the current slot and issue audit read-ahead right when it arrives.
+#[derive(Debug, Default)]
+pub(super) struct SlotArrivalPredictor {
+    last_arrival: Option<Instant>,
+    average_interval: Option<Duration>,
+}
+
+impl SlotArrivalPredictor {
+    /// Note arrival of the slot notification
+    pub(super) fn note_arrival(&mut self) {
+        self.note_arrival_at(Instant::now());
+    }
+
+    fn note_arrival_at(&mut self, instant: Instant) {
+        if let Some(last_arrival) = self.last_arrival.replace(instant) {
+            let interval = instant.saturating_duration_since(last_arrival);
+            // Gaps due to suspend or farmer falling behind are not representative of slot duration
+            if interval <= MAX_SLOT_NOTIFICATION_GAP {
+                self.average_interval = Some(match self.average_interval {
+                    Some(average_interval) => {
+                        (average_interval * (SLOT_INTERVAL_AVERAGE_WEIGHT - 1) + interval)
+                            / SLOT_INTERVAL_AVERAGE_WEIGHT
+                    }
+                    None => interval,
+                });
+            }
+        }
+    }
+
+    /// Predicted arrival of the next slot notification, `None` if there is not enough data yet
+    pub(super) fn predicted_next_arrival(&self) -> Option<Instant> {
+        Some(self.last_arrival? + self.average_interval?)
+    }
+
+    /// Whether next slot notification is expected to have arrived already (with some margin for
+    /// jitter), always `true` if there is not enough data for prediction
+    pub(super) fn is_next_slot_due(&self) -> bool {
+        self.is_next_slot_due_at(Instant::now())
+    }
+
+    fn is_next_slot_due_at(&self, instant: Instant) -> bool {
+        match (self.predicted_next_arrival(), self.average_interval) {
+            (Some(predicted_next_arrival), Some(average_interval)) => {
+                instant + average_interval / 4 >= predicted_next_arrival
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Tracks sectors that fail auditing or proving (usually due to data corruption on disk) and
+/// quarantines those that fail repeatedly, such that they are not audited until replotted
+#[derive(Debug, Default)]
+pub(super) struct SectorQuarantine {
+    consecutive_failures: HashMap<SectorIndex, u32>,
+    /// Quarantined sectors with history size they were plotted at, replotted sector will have a
+    /// different history size
+    quarantined: HashMap<SectorIndex, HistorySize>,
+}
+
+impl SectorQuarantine {
+    /// Note failure of the sector, returns `true` if sector was quarantined as the result
+    pub(super) fn note_failure(&mut self, sector_metadata: &SectorMetadataChecksummed) -> bool {
+        let sector_index = sector_metadata.sector_index;
+        if self.is_quarantined(sector_metadata) {
+            return false;
+        }
+
+        let failures = self.consecutive_failures.entry(sector_index).or_default();
+        *failures += 1;
+        if *failures < MAX_CONSECUTIVE_SECTOR_FAILURES {
+            return false;
+        }
+
+        self.consecutive_failures.remove(&sector_index);
+        self.quarantined
+            .insert(sector_index, sector_metadata.history_size);
+        true
+    }
+
+    /// Note successful auditing and proving of the sector
+    pub(super) fn note_success(&mut self, sector_index: SectorIndex) {
+        self.consecutive_failures.remove(&sector_index);
+    }
+
+    pub(super) fn is_quarantined(&self, sector_metadata: &SectorMetadataChecksummed) -> bool {
+        self.quarantined.get(&sector_metadata.sector_index) == Some(&sector_metadata.history_size)
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.quarantined.is_empty()
+    }
+
+    /// Release sectors that were replotted since they were quarantined
+    pub(super) fn release_replotted(&mut self, sectors_metadata: &[SectorMetadataChecksummed]) {
+        self.quarantined.retain(|sector_index, history_size| {
+            sectors_metadata
+                .get(usize::from(*sector_index))
+                .map(|sector_metadata| sector_metadata.history_size == *history_size)
+                .unwrap_or_default()
+        });
+    }
+}
+
 /// Various farming notifications
 #[derive(Debug, Clone, Encode, Decode)]
 pub enum FarmingNotification {
@@ -71,6 +265,9 @@ pub enum FarmingNotification {
     Auditing(AuditingDetails),
     /// Proving
     Proving(ProvingDetails),
+    /// System suspend/resume or clock jump was detected, slots that were queued over the gap were
+    /// skipped
+    ClockJump(ClockJumpDetails),
     /// Non-fatal farming error
     NonFatalError(Arc<FarmingError>),
 }
@@ -157,12 +354,27 @@ impl FarmingError {
         }
     }
 
+    /// Stable machine-readable code of the error, see [`ErrorCode`]
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            FarmingError::FailedToSubscribeSlotInfo { .. } => ErrorCode::NodeRequestFailed,
+            FarmingError::FailedToGetFarmerInfo { .. } => ErrorCode::NodeRequestFailed,
+            FarmingError::LowLevelAuditing(_) => ErrorCode::AuditingFailed,
+            FarmingError::LowLevelProving(_) => ErrorCode::ProvingFailed,
+            FarmingError::Io(_) => ErrorCode::Io,
+            FarmingError::FailedToCreateThreadPool(_) => ErrorCode::FarmingFailed,
+            FarmingError::Decoded(_) => ErrorCode::FarmingFailed,
+            FarmingError::SlotNotificationStreamEnded => ErrorCode::NodeRequestFailed,
+        }
+    }
+
     /// Whether this error is fatal and makes farm unusable
     pub fn is_fatal(&self) -> bool {
         match self {
             FarmingError::FailedToSubscribeSlotInfo { .. } => true,
             FarmingError::FailedToGetFarmerInfo { .. } => true,
-            FarmingError::LowLevelAuditing(_) => true,
+            // Sectors that can't be read are quarantined and replotted eventually
+            FarmingError::LowLevelAuditing(AuditingError::SBucketReading { .. }) => false,
             FarmingError::LowLevelProving(error) => error.is_fatal(),
             FarmingError::Io(_) => true,
             FarmingError::FailedToCreateThreadPool(_) => true,
@@ -175,6 +387,7 @@ impl FarmingError {
 pub(super) async fn slot_notification_forwarder<NC>(
     node_client: &NC,
     mut slot_info_forwarder_sender: mpsc::Sender<SlotInfo>,
+    handlers: Arc<Handlers>,
 ) -> Result<(), FarmingError>
 where
     NC: NodeClient,
@@ -186,11 +399,51 @@ where
         .await
         .map_err(|error| FarmingError::FailedToSubscribeSlotInfo { error })?;
 
+    let mut clock_jump_detector = ClockJumpDetector::new();
+
     while let Some(slot_info) = slot_info_notifications.next().await {
         debug!(?slot_info, "New slot");
 
         let slot = slot_info.slot_number;
 
+        // Solutions for slots that were queued while the system was suspended (or clock jumped)
+        // are stale, skip everything that is already queued and resume farming with the next slot
+        // info that node sends after resynchronization
+        if let Some(clock_jump_details) = clock_jump_detector.check() {
+            let mut skipped_slots = 1_usize;
+            while let Some(Some(_slot_info)) = slot_info_notifications.next().now_or_never() {
+                skipped_slots += 1;
+            }
+            // Draining may take a bit of time, don't count it towards the next gap
+            clock_jump_detector = ClockJumpDetector::new();
+
+            warn!(
+                %slot,
+                monotonic_gap = ?clock_jump_details.monotonic_gap,
+                wall_clock_gap = ?clock_jump_details.wall_clock_gap,
+                %skipped_slots,
+                "System suspend/resume or clock jump detected, skipping stale slots"
+            );
+            handlers
+                .farming_notification
+                .call_simple(&FarmingNotification::ClockJump(clock_jump_details));
+            continue;
+        }
+
+        // Node is importing blocks, but its best block is too old, block produced with solution
+        // for this slot will most likely be orphaned anyway
+        if slot_info.import_queue_depth > 0
+            && slot_info.best_block_slot_lag > MAX_BEST_BLOCK_SLOT_LAG
+        {
+            debug!(
+                %slot,
+                import_queue_depth = %slot_info.import_queue_depth,
+                best_block_slot_lag = %slot_info.best_block_slot_lag,
+                "Node is behind, skipping slot"
+            );
+            continue;
+        }
+
         // Error means farmer is still solving for previous slot, which is too late and
         // we need to skip this slot
         if slot_info_forwarder_sender.try_send(slot_info).is_err() {
@@ -201,6 +454,37 @@ where
     Err(FarmingError::SlotNotificationStreamEnded)
 }
 
+/// Note failure of the sector and quarantine it if necessary, returns `true` if sector was
+/// quarantined
+fn note_sector_failure(
+    sector_quarantine: &mut SectorQuarantine,
+    sector_metadata: &SectorMetadataChecksummed,
+    handlers: &Handlers,
+    sectors_to_replot_sender: &mut mpsc::Sender<SectorIndex>,
+) -> bool {
+    if !sector_quarantine.note_failure(sector_metadata) {
+        return false;
+    }
+
+    let sector_index = sector_metadata.sector_index;
+    warn!(
+        code = %ErrorCode::SectorQuarantined,
+        %sector_index,
+        "Sector failed auditing or proving {MAX_CONSECUTIVE_SECTOR_FAILURES} times in a row, \
+        quarantining it until replotted"
+    );
+    handlers
+        .sector_update
+        .call_simple(&(sector_index, SectorUpdate::Quarantined));
+
+    if let Err(error) = sectors_to_replot_sender.try_send(sector_index) {
+        // Expiration of the sector will trigger replotting eventually anyway
+        debug!(%error, %sector_index, "Failed to schedule replotting of quarantined sector");
+    }
+
+    true
+}
+
 /// Plot audit options
 #[derive(Debug)]
 pub struct PlotAuditOptions<'a, PosTable>
@@ -251,6 +535,23 @@ where
         Self(plot)
     }
 
+    /// Issue read-ahead for data that will be read by [`Self::audit()`] with the same options
+    pub fn prefetch<PosTable>(
+        &'a self,
+        options: PlotAuditOptions<'a, PosTable>,
+    ) -> Result<(), AuditingError>
+    where
+        PosTable: Table,
+    {
+        prefetch_plot_audit_sync(
+            options.public_key,
+            &options.slot_info.global_challenge,
+            &self.0,
+            options.sectors_metadata,
+            options.maybe_sector_being_modified,
+        )
+    }
+
     pub fn audit<PosTable>(
         &'a self,
         options: PlotAuditOptions<'a, PosTable>,
@@ -300,6 +601,7 @@ where
                     Ok(solutions) => solutions,
                     Err(error) => {
                         warn!(
+                            code = %ErrorCode::ProvingFailed,
                             %error,
                             %sector_index,
                             "Failed to turn solution candidates into solutions",
@@ -330,6 +632,8 @@ pub(super) struct FarmingOptions<NC, PlotAudit> {
     pub(super) handlers: Arc<Handlers>,
     pub(super) modifying_sector_index: Arc<RwLock<Option<SectorIndex>>>,
     pub(super) slot_info_notifications: mpsc::Receiver<SlotInfo>,
+    pub(super) audit_prefetch: bool,
+    pub(super) sectors_to_replot_sender: mpsc::Sender<SectorIndex>,
 }
 
 /// Starts farming process.
@@ -355,6 +659,8 @@ where
         handlers,
         modifying_sector_index,
         mut slot_info_notifications,
+        audit_prefetch,
+        mut sectors_to_replot_sender,
     } = farming_options;
 
     let farmer_app_info = node_client
@@ -367,7 +673,26 @@ where
 
     let table_generator = Arc::new(Mutex::new(PosTable::generator()));
 
-    while let Some(slot_info) = slot_info_notifications.next().await {
+    // Slot that arrived while previous slot was processed and for which read-ahead was issued
+    let mut maybe_prefetched_slot_info = None;
+    let mut slot_arrival_predictor = SlotArrivalPredictor::default();
+    // History size of the node in case it is behind history size of some of the plotted sectors,
+    // solutions for such sectors are not submitted until node catches up
+    let mut maybe_lagging_node_history_size = None::<(HistorySize, Instant)>;
+    let mut sector_quarantine = SectorQuarantine::default();
+
+    loop {
+        let slot_info = match maybe_prefetched_slot_info.take() {
+            Some(slot_info) => slot_info,
+            None => match slot_info_notifications.next().await {
+                Some(slot_info) => {
+                    slot_arrival_predictor.note_arrival();
+                    slot_info
+                }
+                None => break,
+            },
+        };
+
         let result: Result<(), FarmingError> = try {
             let start = Instant::now();
             let slot = slot_info.slot_number;
@@ -375,20 +700,82 @@ where
 
             debug!(%slot, sector_count = %sectors_metadata.len(), "Reading sectors");
 
-            let mut sectors_solutions = {
+            if let Some((node_history_size, last_check)) = &mut maybe_lagging_node_history_size
+                && last_check.elapsed() >= LAGGING_NODE_HISTORY_SIZE_CHECK_INTERVAL
+            {
+                *last_check = Instant::now();
+                match node_client.farmer_app_info().await {
+                    Ok(farmer_app_info) => {
+                        *node_history_size =
+                            (*node_history_size).max(farmer_app_info.protocol_info.history_size);
+                    }
+                    Err(error) => {
+                        debug!(%error, "Failed to check node's history size");
+                    }
+                }
+
+                let max_sector_history_size = sectors_metadata
+                    .iter()
+                    .map(|sector_metadata| sector_metadata.history_size)
+                    .max();
+                if max_sector_history_size <= Some(*node_history_size) {
+                    info!(
+                        %node_history_size,
+                        "Node caught up with history size of plotted sectors, resuming solution \
+                        submission for all sectors"
+                    );
+                    maybe_lagging_node_history_size.take();
+                }
+            }
+
+            sector_quarantine.release_replotted(&sectors_metadata);
+            let audited_sectors_metadata = if sector_quarantine.is_empty() {
+                Cow::Borrowed(sectors_metadata.as_slice())
+            } else {
+                Cow::Owned(
+                    sectors_metadata
+                        .iter()
+                        .filter(|sector_metadata| {
+                            !sector_quarantine.is_quarantined(sector_metadata)
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                )
+            };
+
+            let (maybe_sector_being_modified, audit_result) = {
                 let modifying_sector_guard = modifying_sector_index.read().await;
                 let maybe_sector_being_modified = modifying_sector_guard.as_ref().copied();
 
-                plot_audit.audit(PlotAuditOptions::<PosTable> {
+                let audit_result = plot_audit.audit(PlotAuditOptions::<PosTable> {
                     public_key: &public_key,
                     reward_address: &reward_address,
                     slot_info,
-                    sectors_metadata: &sectors_metadata,
+                    sectors_metadata: &audited_sectors_metadata,
                     kzg: &kzg,
                     erasure_coding: &erasure_coding,
                     maybe_sector_being_modified,
                     table_generator: &table_generator,
-                })?
+                });
+
+                (maybe_sector_being_modified, audit_result)
+            };
+            let mut sectors_solutions = match audit_result {
+                Ok(sectors_solutions) => sectors_solutions,
+                Err(error) => {
+                    if let AuditingError::SBucketReading { sector_index, .. } = &error
+                        && let Some(sector_metadata) =
+                            sectors_metadata.get(usize::from(*sector_index))
+                    {
+                        note_sector_failure(
+                            &mut sector_quarantine,
+                            sector_metadata,
+                            &handlers,
+                            &mut sectors_to_replot_sender,
+                        );
+                    }
+                    Err(error)?
+                }
             };
 
             sectors_solutions.sort_by(|a, b| {
@@ -407,22 +794,91 @@ where
                     time: start.elapsed(),
                 }));
 
+            // Next slot is checked for when it is predicted to arrive both after audit and between
+            // solutions, read-ahead for it is issued right away such that disk can fetch data
+            // while this slot is being proven
+            let mut prefetch_next_slot = || {
+                if !audit_prefetch
+                    || maybe_prefetched_slot_info.is_some()
+                    || !slot_arrival_predictor.is_next_slot_due()
+                {
+                    return;
+                }
+                let Ok(Some(next_slot_info)) = slot_info_notifications.try_next() else {
+                    return;
+                };
+                slot_arrival_predictor.note_arrival();
+
+                if let Err(error) = plot_audit.prefetch(PlotAuditOptions::<PosTable> {
+                    public_key: &public_key,
+                    reward_address: &reward_address,
+                    slot_info: next_slot_info,
+                    sectors_metadata: &audited_sectors_metadata,
+                    kzg: &kzg,
+                    erasure_coding: &erasure_coding,
+                    maybe_sector_being_modified,
+                    table_generator: &table_generator,
+                }) {
+                    debug!(
+                        slot = %next_slot_info.slot_number,
+                        %error,
+                        "Failed to issue audit read-ahead"
+                    );
+                }
+
+                maybe_prefetched_slot_info.replace(next_slot_info);
+            };
+            prefetch_next_slot();
+
             'solutions_processing: for (sector_index, sector_solutions) in sectors_solutions {
                 if sector_solutions.is_empty() {
                     continue;
                 }
+                if let Some((node_history_size, _last_check)) = &maybe_lagging_node_history_size
+                    && let Some(sector_metadata) = sectors_metadata.get(usize::from(sector_index))
+                    && sector_metadata.history_size > *node_history_size
+                {
+                    debug!(
+                        %slot,
+                        %sector_index,
+                        sector_history_size = %sector_metadata.history_size,
+                        %node_history_size,
+                        "Node is behind sector's history size, skipping solutions"
+                    );
+                    continue;
+                }
                 let mut start = Instant::now();
                 for maybe_solution in sector_solutions {
+                    prefetch_next_slot();
+
                     let solution = match maybe_solution {
                         Ok(solution) => solution,
                         Err(error) => {
-                            error!(%slot, %sector_index, %error, "Failed to prove");
+                            error!(
+                                code = %ErrorCode::ProvingFailed,
+                                %slot,
+                                %sector_index,
+                                %error,
+                                "Failed to prove"
+                            );
+                            if let Some(sector_metadata) =
+                                sectors_metadata.get(usize::from(sector_index))
+                                && note_sector_failure(
+                                    &mut sector_quarantine,
+                                    sector_metadata,
+                                    &handlers,
+                                    &mut sectors_to_replot_sender,
+                                )
+                            {
+                                continue 'solutions_processing;
+                            }
                             // Do not error completely as disk corruption or other reasons why
                             // proving might fail
                             start = Instant::now();
                             continue;
                         }
                     };
+                    sector_quarantine.note_success(sector_index);
 
                     debug!(%slot, %sector_index, "Solution found");
                     trace!(?solution, "Solution found");
@@ -435,6 +891,7 @@ where
                                 time: start.elapsed(),
                             }));
                         warn!(
+                            code = %ErrorCode::ProvingTimeout,
                             %slot,
                             %sector_index,
                             "Proving for solution skipped due to farming time limit",
@@ -457,7 +914,27 @@ where
                                 result: ProvingResult::Rejected,
                                 time: start.elapsed(),
                             }));
+
+                        if let Some(future_history_size) = error.downcast_ref::<FutureHistorySize>()
+                        {
+                            info!(
+                                %slot,
+                                %sector_index,
+                                solution_history_size = %future_history_size.solution_history_size,
+                                node_history_size = %future_history_size.current_history_size,
+                                "Node is behind history size of the sector, pausing solution \
+                                submission for affected sectors until node catches up"
+                            );
+                            maybe_lagging_node_history_size.replace((
+                                future_history_size.current_history_size,
+                                Instant::now(),
+                            ));
+                            // Other sectors might still be fine
+                            continue 'solutions_processing;
+                        }
+
                         warn!(
+                            code = %ErrorCode::SolutionSubmissionFailed,
                             %slot,
                             %sector_index,
                             %error,
@@ -482,6 +959,7 @@ where
                 return Err(error);
             } else {
                 warn!(
+                    code = %error.error_code(),
                     %error,
                     "Non-fatal farming error"
                 );
@@ -19,12 +19,24 @@ impl ReadAtSync for RayonFiles {
 
         file.read_at(buf, offset)
     }
+
+    fn read_ahead(&self, offset: u64, len: usize) -> io::Result<()> {
+        // Read-ahead is issued for the underlying file, so any of the handles will do
+        match self.files.first() {
+            Some(file) => file.read_ahead(offset, len),
+            None => Ok(()),
+        }
+    }
 }
 
 impl ReadAtSync for &RayonFiles {
     fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()> {
         (*self).read_at(buf, offset)
     }
+
+    fn read_ahead(&self, offset: u64, len: usize) -> io::Result<()> {
+        (*self).read_ahead(offset, len)
+    }
 }
 
 impl RayonFiles {
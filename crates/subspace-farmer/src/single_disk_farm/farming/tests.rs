@@ -0,0 +1,141 @@
+use crate::single_disk_farm::farming::{
+    ClockJumpDetector, SectorQuarantine, SlotArrivalPredictor, MAX_CLOCK_DRIFT,
+    MAX_CONSECUTIVE_SECTOR_FAILURES, MAX_SLOT_NOTIFICATION_GAP,
+};
+use std::num::NonZeroU64;
+use std::time::{Duration, Instant, SystemTime};
+use subspace_core_primitives::{HistorySize, Record, SectorIndex};
+use subspace_farmer_components::sector::{SectorMetadata, SectorMetadataChecksummed};
+
+#[test]
+fn clock_jump_detection() {
+    let instant = Instant::now();
+    let system_time = SystemTime::now();
+    let mut detector = ClockJumpDetector::new_at(instant, system_time);
+
+    // Regular slot
+    let second = Duration::from_secs(1);
+    assert!(detector
+        .check_at(instant + second, system_time + second)
+        .is_none());
+
+    // Suspend during which monotonic clock didn't advance
+    let details = detector
+        .check_at(
+            instant + second * 2,
+            system_time + second * 2 + MAX_CLOCK_DRIFT * 2,
+        )
+        .unwrap();
+    assert_eq!(details.monotonic_gap, second);
+    assert_eq!(details.wall_clock_gap, second + MAX_CLOCK_DRIFT * 2);
+
+    // Back to normal
+    assert!(detector
+        .check_at(
+            instant + second * 3,
+            system_time + second * 3 + MAX_CLOCK_DRIFT * 2
+        )
+        .is_none());
+
+    // Pause during which both clocks advanced
+    let gap = MAX_SLOT_NOTIFICATION_GAP + second;
+    let details = detector
+        .check_at(
+            instant + second * 3 + gap,
+            system_time + second * 3 + MAX_CLOCK_DRIFT * 2 + gap,
+        )
+        .unwrap();
+    assert_eq!(details.monotonic_gap, gap);
+    assert_eq!(details.wall_clock_gap, gap);
+
+    // Wall clock went backwards
+    let details = detector
+        .check_at(instant + second * 4 + gap, system_time)
+        .unwrap();
+    assert_eq!(details.monotonic_gap, second);
+    assert_eq!(details.wall_clock_gap, Duration::ZERO);
+}
+
+fn sector_metadata(sector_index: SectorIndex, history_size: u64) -> SectorMetadataChecksummed {
+    SectorMetadataChecksummed::from(SectorMetadata {
+        sector_index,
+        pieces_in_sector: 1,
+        s_bucket_sizes: Box::new([0; Record::NUM_S_BUCKETS]),
+        history_size: HistorySize::from(NonZeroU64::new(history_size).unwrap()),
+    })
+}
+
+#[test]
+fn sector_quarantine() {
+    let mut sectors_metadata = vec![sector_metadata(0, 1), sector_metadata(1, 1)];
+    let mut quarantine = SectorQuarantine::default();
+
+    // Occasional failures do not result in quarantine
+    for _ in 0..MAX_CONSECUTIVE_SECTOR_FAILURES - 1 {
+        assert!(!quarantine.note_failure(&sectors_metadata[0]));
+    }
+    quarantine.note_success(0);
+    for _ in 0..MAX_CONSECUTIVE_SECTOR_FAILURES - 1 {
+        assert!(!quarantine.note_failure(&sectors_metadata[0]));
+    }
+    assert!(quarantine.is_empty());
+
+    // Failing too many times in a row results in quarantine, but only once
+    assert!(quarantine.note_failure(&sectors_metadata[0]));
+    assert!(!quarantine.note_failure(&sectors_metadata[0]));
+    assert!(quarantine.is_quarantined(&sectors_metadata[0]));
+    assert!(!quarantine.is_quarantined(&sectors_metadata[1]));
+
+    // Quarantine remains until sector is replotted
+    quarantine.release_replotted(&sectors_metadata);
+    assert!(quarantine.is_quarantined(&sectors_metadata[0]));
+
+    sectors_metadata[0] = sector_metadata(0, 2);
+    quarantine.release_replotted(&sectors_metadata);
+    assert!(!quarantine.is_quarantined(&sectors_metadata[0]));
+    assert!(quarantine.is_empty());
+}
+
+#[test]
+fn slot_arrival_prediction() {
+    let instant = Instant::now();
+    let second = Duration::from_secs(1);
+    let mut predictor = SlotArrivalPredictor::default();
+
+    // Not enough data, next slot is always checked for
+    assert!(predictor.predicted_next_arrival().is_none());
+    assert!(predictor.is_next_slot_due_at(instant));
+    predictor.note_arrival_at(instant);
+    assert!(predictor.predicted_next_arrival().is_none());
+    assert!(predictor.is_next_slot_due_at(instant));
+
+    // Regular slots
+    for slot in 1..=3 {
+        predictor.note_arrival_at(instant + second * slot);
+    }
+    let last_arrival = instant + second * 3;
+    assert_eq!(
+        predictor.predicted_next_arrival(),
+        Some(last_arrival + second)
+    );
+    assert!(!predictor.is_next_slot_due_at(last_arrival));
+    assert!(!predictor.is_next_slot_due_at(last_arrival + second / 2));
+    assert!(predictor.is_next_slot_due_at(last_arrival + second * 3 / 4));
+    assert!(predictor.is_next_slot_due_at(last_arrival + second * 2));
+
+    // Slower slot only moves prediction partially
+    let last_arrival = last_arrival + second * 3;
+    predictor.note_arrival_at(last_arrival);
+    assert_eq!(
+        predictor.predicted_next_arrival(),
+        Some(last_arrival + second * 5 / 4)
+    );
+
+    // Gaps (for example due to suspend) do not affect prediction
+    let last_arrival = last_arrival + MAX_SLOT_NOTIFICATION_GAP + second;
+    predictor.note_arrival_at(last_arrival);
+    assert_eq!(
+        predictor.predicted_next_arrival(),
+        Some(last_arrival + second * 5 / 4)
+    );
+}
@@ -1,14 +1,17 @@
 #[cfg(test)]
 mod tests;
 
+use crate::error_code::ErrorCode;
 use derive_more::Display;
+use parking_lot::Mutex;
 use std::fs::{File, OpenOptions};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::{fs, io, mem};
-use subspace_core_primitives::crypto::blake3_hash_list;
+use subspace_core_primitives::crypto::{blake3_hash, blake3_hash_list};
 use subspace_core_primitives::{Blake3Hash, Piece, PieceIndex};
 use subspace_farmer_components::file_ext::{FileExt, OpenOptionsExt};
+use subspace_temp_files::TempFileManager;
 use thiserror::Error;
 use tracing::{debug, info, warn};
 
@@ -37,15 +40,35 @@ pub enum DiskPieceCacheError {
     ChecksumMismatch,
 }
 
+/// Number of elements by which persisted number of used elements is advanced at once, such that
+/// cache metadata doesn't need to be updated on every write while cache is being filled
+const USED_ELEMENTS_STEP: usize = 1024;
+
 /// Offset wrapper for pieces in [`DiskPieceCache`]
 #[derive(Debug, Display, Copy, Clone)]
 #[repr(transparent)]
 pub struct Offset(usize);
 
+/// Result of [`DiskPieceCache`] repair
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct PieceCacheRepairReport {
+    /// Number of elements with valid pieces that were kept
+    pub valid_elements: usize,
+    /// Number of corrupted elements that were erased
+    pub corrupted_elements: usize,
+    /// Number of empty elements
+    pub empty_elements: usize,
+}
+
 #[derive(Debug)]
 struct Inner {
+    directory: PathBuf,
+    temp_files: TempFileManager,
     file: File,
     num_elements: usize,
+    /// Number of elements from the beginning of the cache that may contain pieces, elements after
+    /// this are known to be empty
+    used_elements: Mutex<usize>,
 }
 
 /// Dedicated piece cache stored on one disk, is used both to accelerate DSN queries and to plot
@@ -57,10 +80,12 @@ pub struct DiskPieceCache {
 
 impl DiskPieceCache {
     pub(super) const FILE_NAME: &'static str = "piece_cache.bin";
+    const METADATA_FILE_NAME: &'static str = "piece_cache_metadata.bin";
 
     pub(in super::super) fn open(
         directory: &Path,
         capacity: usize,
+        temp_files: TempFileManager,
     ) -> Result<Self, DiskPieceCacheError> {
         if capacity == 0 {
             return Err(DiskPieceCacheError::ZeroCapacity);
@@ -83,10 +108,25 @@ impl DiskPieceCache {
         // Truncating file (if necessary)
         file.set_len(expected_size as u64)?;
 
+        let num_elements = expected_size / Self::element_size();
+        let used_elements = match Self::read_used_elements(directory) {
+            Some(used_elements) => used_elements.min(num_elements),
+            None => {
+                // Cache created by older version of the farmer or metadata was lost, elements were
+                // always written sequentially before, so the first empty element marks the end
+                let used_elements = Self::find_first_empty_element(&file, num_elements)?;
+                Self::write_used_elements(&temp_files, directory, used_elements)?;
+                used_elements
+            }
+        };
+
         Ok(Self {
             inner: Arc::new(Inner {
+                directory: directory.to_path_buf(),
+                temp_files,
                 file,
-                num_elements: expected_size / Self::element_size(),
+                num_elements,
+                used_elements: Mutex::new(used_elements),
             }),
         })
     }
@@ -104,24 +144,25 @@ impl DiskPieceCache {
     ) -> impl ExactSizeIterator<Item = (Offset, Option<PieceIndex>)> + '_ {
         let file = &self.inner.file;
         let mut element = vec![0; Self::element_size()];
-        let mut early_exit = false;
+        let used_elements = *self.inner.used_elements.lock();
 
         (0..self.inner.num_elements).map(move |offset| {
-            if early_exit {
+            if offset >= used_elements {
+                // End of stored pieces, no need to read further
                 return (Offset(offset), None);
             }
 
+            // Empty or corrupted elements (after abrupt shutdown, for example) may occur anywhere,
+            // they are simply reused for new pieces
             match Self::read_piece_internal(file, offset, &mut element) {
-                Ok(maybe_piece_index) => {
-                    if maybe_piece_index.is_none() {
-                        // End of stored pieces, no need to read further
-                        early_exit = true;
-                    }
-
-                    (Offset(offset), maybe_piece_index)
-                }
+                Ok(maybe_piece_index) => (Offset(offset), maybe_piece_index),
                 Err(error) => {
-                    warn!(%error, %offset, "Failed to read cache element");
+                    warn!(
+                        code = %ErrorCode::CacheReadFailed,
+                        %error,
+                        %offset,
+                        "Failed to read cache element"
+                    );
                     (Offset(offset), None)
                 }
             }
@@ -160,6 +201,21 @@ impl DiskPieceCache {
             element_offset + PieceIndex::SIZE as u64 + Piece::SIZE as u64,
         )?;
 
+        // Element is written before metadata is updated, so if metadata update doesn't make it to
+        // the disk, element is ignored after restart, otherwise it is read and verified as usual
+        let mut used_elements = self.inner.used_elements.lock();
+        if offset >= *used_elements {
+            let new_used_elements = (offset + 1)
+                .next_multiple_of(USED_ELEMENTS_STEP)
+                .min(self.inner.num_elements);
+            Self::write_used_elements(
+                &self.inner.temp_files,
+                &self.inner.directory,
+                new_used_elements,
+            )?;
+            *used_elements = new_used_elements;
+        }
+
         Ok(())
     }
 
@@ -245,12 +301,148 @@ impl DiskPieceCache {
         Ok(Some(piece_index))
     }
 
+    /// Salvage valid pieces of the cache in specified directory after abrupt shutdown or disk
+    /// errors.
+    ///
+    /// Corrupted elements are erased and cache metadata is rebuilt from elements that remain.
+    pub(in super::super) fn repair(
+        directory: &Path,
+    ) -> Result<PieceCacheRepairReport, DiskPieceCacheError> {
+        let temp_files = TempFileManager::open(directory)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .advise_sequential_access()
+            .open(directory.join(Self::FILE_NAME))?;
+
+        let num_elements = file.metadata()?.len() as usize / Self::element_size();
+        let mut element = vec![0; Self::element_size()];
+        let mut report = PieceCacheRepairReport::default();
+        let mut used_elements = 0;
+
+        for offset in 0..num_elements {
+            match Self::read_piece_internal(&file, offset, &mut element) {
+                Ok(Some(_piece_index)) => {
+                    report.valid_elements += 1;
+                    used_elements = offset + 1;
+                }
+                Ok(None) => {
+                    report.empty_elements += 1;
+                }
+                Err(error) => {
+                    warn!(
+                        code = %ErrorCode::CacheChecksumMismatch,
+                        %error,
+                        %offset,
+                        "Corrupted or unreadable cache element, erasing"
+                    );
+
+                    element.fill(0);
+                    file.write_all_at(&element, (offset * Self::element_size()) as u64)?;
+                    report.corrupted_elements += 1;
+                }
+            }
+
+            if offset > 0 && offset % 1000 == 0 {
+                info!("Checked {offset}/{num_elements} cache elements");
+            }
+        }
+
+        file.sync_all()?;
+        Self::write_used_elements(&temp_files, directory, used_elements)?;
+
+        Ok(report)
+    }
+
+    /// Reads number of used elements from cache metadata, returns `None` if metadata doesn't
+    /// exist or is corrupted
+    fn read_used_elements(directory: &Path) -> Option<usize> {
+        let metadata_file = directory.join(Self::METADATA_FILE_NAME);
+        let bytes = match fs::read(&metadata_file) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                if error.kind() != io::ErrorKind::NotFound {
+                    warn!(
+                        code = %ErrorCode::CacheReadFailed,
+                        %error,
+                        path = %metadata_file.display(),
+                        "Failed to read cache metadata"
+                    );
+                }
+                return None;
+            }
+        };
+
+        if bytes.len() != mem::size_of::<u64>() + mem::size_of::<Blake3Hash>() {
+            warn!(
+                code = %ErrorCode::CacheChecksumMismatch,
+                path = %metadata_file.display(),
+                "Cache metadata has unexpected size, ignoring"
+            );
+            return None;
+        }
+
+        let (used_elements_bytes, checksum) = bytes.split_at(mem::size_of::<u64>());
+        if blake3_hash(used_elements_bytes) != checksum {
+            warn!(
+                code = %ErrorCode::CacheChecksumMismatch,
+                path = %metadata_file.display(),
+                "Cache metadata checksum mismatch, ignoring"
+            );
+            return None;
+        }
+
+        let used_elements_bytes = used_elements_bytes
+            .try_into()
+            .expect("Size checked above; qed");
+        Some(u64::from_le_bytes(used_elements_bytes) as usize)
+    }
+
+    /// Atomically replaces cache metadata with new number of used elements
+    fn write_used_elements(
+        temp_files: &TempFileManager,
+        directory: &Path,
+        used_elements: usize,
+    ) -> io::Result<()> {
+        let used_elements_bytes = (used_elements as u64).to_le_bytes();
+        let mut contents = Vec::with_capacity(mem::size_of::<u64>() + mem::size_of::<Blake3Hash>());
+        contents.extend_from_slice(&used_elements_bytes);
+        contents.extend_from_slice(&blake3_hash(&used_elements_bytes));
+
+        temp_files.write_atomically(&directory.join(Self::METADATA_FILE_NAME), &contents)
+    }
+
+    /// Finds the first element with zero checksum, only checksums are read to make it fast
+    fn find_first_empty_element(file: &File, num_elements: usize) -> io::Result<usize> {
+        let mut checksum = [0; mem::size_of::<Blake3Hash>()];
+        for offset in 0..num_elements {
+            let checksum_offset = offset * Self::element_size() + PieceIndex::SIZE + Piece::SIZE;
+            file.read_exact_at(&mut checksum, checksum_offset as u64)?;
+
+            if checksum == [0; mem::size_of::<Blake3Hash>()] {
+                return Ok(offset);
+            }
+        }
+
+        Ok(num_elements)
+    }
+
     pub(crate) fn wipe(directory: &Path) -> io::Result<()> {
-        let piece_cache = directory.join(Self::FILE_NAME);
-        if !piece_cache.exists() {
-            return Ok(());
+        for file in Self::files(directory) {
+            if file.exists() {
+                info!("Deleting piece cache file at {}", file.display());
+                fs::remove_file(file)?;
+            }
         }
-        info!("Deleting piece cache file at {}", piece_cache.display());
-        fs::remove_file(piece_cache)
+
+        Ok(())
+    }
+
+    /// Files that constitute piece cache in specified directory
+    pub(crate) fn files(directory: &Path) -> [PathBuf; 2] {
+        [
+            directory.join(Self::METADATA_FILE_NAME),
+            directory.join(Self::FILE_NAME),
+        ]
     }
 }
@@ -1,15 +1,55 @@
-use crate::single_disk_farm::piece_cache::{DiskPieceCache, Offset};
+use crate::single_disk_farm::piece_cache::{DiskPieceCache, Offset, PieceCacheRepairReport};
 use crate::single_disk_farm::DiskPieceCacheError;
 use rand::prelude::*;
 use std::assert_matches::assert_matches;
+use std::fs;
+use std::fs::OpenOptions;
+use std::path::Path;
 use subspace_core_primitives::{Piece, PieceIndex};
+use subspace_farmer_components::file_ext::FileExt;
+use subspace_temp_files::TempFileManager;
 use tempfile::tempdir;
 
+fn write_random_pieces(disk_piece_cache: &DiskPieceCache, count: usize) {
+    for offset in 0..count {
+        let mut piece = Piece::default();
+        thread_rng().fill(piece.as_mut());
+
+        disk_piece_cache
+            .write_piece(Offset(offset), PieceIndex::from(offset as u64), &piece)
+            .unwrap();
+    }
+}
+
+fn overwrite_element(directory: &Path, offset: usize, bytes: &[u8]) {
+    OpenOptions::new()
+        .write(true)
+        .open(directory.join(DiskPieceCache::FILE_NAME))
+        .unwrap()
+        .write_all_at(
+            bytes,
+            (offset * DiskPieceCache::element_size() + PieceIndex::SIZE) as u64,
+        )
+        .unwrap();
+}
+
+fn stored_pieces(disk_piece_cache: &DiskPieceCache) -> Vec<usize> {
+    disk_piece_cache
+        .contents()
+        .filter_map(|(Offset(offset), maybe_piece_index)| maybe_piece_index.map(|_| offset))
+        .collect()
+}
+
 #[test]
 fn basic() {
     let path = tempdir().unwrap();
     {
-        let disk_piece_cache = DiskPieceCache::open(path.as_ref(), 2).unwrap();
+        let disk_piece_cache = DiskPieceCache::open(
+            path.as_ref(),
+            2,
+            TempFileManager::open(path.as_ref()).unwrap(),
+        )
+        .unwrap();
 
         // Initially empty
         assert_eq!(
@@ -116,7 +156,12 @@ fn basic() {
 
     // Reopening works
     {
-        let disk_piece_cache = DiskPieceCache::open(path.as_ref(), 2).unwrap();
+        let disk_piece_cache = DiskPieceCache::open(
+            path.as_ref(),
+            2,
+            TempFileManager::open(path.as_ref()).unwrap(),
+        )
+        .unwrap();
         // Two pieces stored
         assert_eq!(
             disk_piece_cache
@@ -131,7 +176,12 @@ fn basic() {
     {
         DiskPieceCache::wipe(path.as_ref()).unwrap();
 
-        let disk_piece_cache = DiskPieceCache::open(path.as_ref(), 2).unwrap();
+        let disk_piece_cache = DiskPieceCache::open(
+            path.as_ref(),
+            2,
+            TempFileManager::open(path.as_ref()).unwrap(),
+        )
+        .unwrap();
         // Wiped successfully
         assert_eq!(
             disk_piece_cache
@@ -142,3 +192,126 @@ fn basic() {
         );
     }
 }
+
+#[test]
+fn empty_element_in_the_middle() {
+    let path = tempdir().unwrap();
+    {
+        let disk_piece_cache = DiskPieceCache::open(
+            path.as_ref(),
+            3,
+            TempFileManager::open(path.as_ref()).unwrap(),
+        )
+        .unwrap();
+        write_random_pieces(&disk_piece_cache, 3);
+    }
+
+    // Erase the whole element in the middle, like scrubbing does with corrupted elements
+    {
+        let file = OpenOptions::new()
+            .write(true)
+            .open(path.as_ref().join(DiskPieceCache::FILE_NAME))
+            .unwrap();
+        file.write_all_at(
+            &vec![0; DiskPieceCache::element_size()],
+            DiskPieceCache::element_size() as u64,
+        )
+        .unwrap();
+    }
+
+    // Pieces after empty element are still found
+    let disk_piece_cache = DiskPieceCache::open(
+        path.as_ref(),
+        3,
+        TempFileManager::open(path.as_ref()).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(stored_pieces(&disk_piece_cache), vec![0, 2]);
+}
+
+#[test]
+fn metadata_lost() {
+    let path = tempdir().unwrap();
+    {
+        let disk_piece_cache = DiskPieceCache::open(
+            path.as_ref(),
+            3,
+            TempFileManager::open(path.as_ref()).unwrap(),
+        )
+        .unwrap();
+        write_random_pieces(&disk_piece_cache, 2);
+    }
+
+    fs::remove_file(path.as_ref().join(DiskPieceCache::METADATA_FILE_NAME)).unwrap();
+
+    // Falls back to the first empty element
+    let disk_piece_cache = DiskPieceCache::open(
+        path.as_ref(),
+        3,
+        TempFileManager::open(path.as_ref()).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(stored_pieces(&disk_piece_cache), vec![0, 1]);
+
+    // Corrupted metadata is treated the same way as missing
+    drop(disk_piece_cache);
+    fs::write(
+        path.as_ref().join(DiskPieceCache::METADATA_FILE_NAME),
+        [1, 2, 3],
+    )
+    .unwrap();
+    let disk_piece_cache = DiskPieceCache::open(
+        path.as_ref(),
+        3,
+        TempFileManager::open(path.as_ref()).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(stored_pieces(&disk_piece_cache), vec![0, 1]);
+}
+
+#[test]
+fn repair() {
+    let path = tempdir().unwrap();
+    {
+        let disk_piece_cache = DiskPieceCache::open(
+            path.as_ref(),
+            4,
+            TempFileManager::open(path.as_ref()).unwrap(),
+        )
+        .unwrap();
+        write_random_pieces(&disk_piece_cache, 3);
+    }
+
+    // Simulate torn write in the middle and lost metadata
+    overwrite_element(path.as_ref(), 1, &[1; 100]);
+    fs::remove_file(path.as_ref().join(DiskPieceCache::METADATA_FILE_NAME)).unwrap();
+
+    assert_eq!(
+        DiskPieceCache::repair(path.as_ref()).unwrap(),
+        PieceCacheRepairReport {
+            valid_elements: 2,
+            corrupted_elements: 1,
+            empty_elements: 1,
+        }
+    );
+
+    let disk_piece_cache = DiskPieceCache::open(
+        path.as_ref(),
+        4,
+        TempFileManager::open(path.as_ref()).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(stored_pieces(&disk_piece_cache), vec![0, 2]);
+    assert_eq!(disk_piece_cache.read_piece_index(Offset(1)).unwrap(), None);
+
+    // Repairing healthy cache changes nothing
+    drop(disk_piece_cache);
+    assert_eq!(
+        DiskPieceCache::repair(path.as_ref()).unwrap(),
+        PieceCacheRepairReport {
+            valid_elements: 2,
+            corrupted_elements: 0,
+            empty_elements: 2,
+        }
+    );
+}
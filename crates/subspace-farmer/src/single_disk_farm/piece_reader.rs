@@ -1,3 +1,5 @@
+use crate::error_code::ErrorCode;
+use crate::single_disk_farm::plot_encryption::PlotFile;
 use async_lock::RwLock;
 use futures::channel::{mpsc, oneshot};
 use futures::{SinkExt, StreamExt};
@@ -32,7 +34,7 @@ impl PieceReader {
     pub(super) fn new<PosTable>(
         public_key: PublicKey,
         pieces_in_sector: u16,
-        plot_file: Arc<File>,
+        plot_file: Arc<PlotFile<File>>,
         sectors_metadata: Arc<RwLock<Vec<SectorMetadataChecksummed>>>,
         erasure_coding: ErasureCoding,
         modifying_sector_index: Arc<RwLock<Option<SectorIndex>>>,
@@ -83,7 +85,7 @@ impl PieceReader {
 async fn read_pieces<PosTable>(
     public_key: PublicKey,
     pieces_in_sector: u16,
-    plot_file: Arc<File>,
+    plot_file: Arc<PlotFile<File>>,
     sectors_metadata: Arc<RwLock<Vec<SectorMetadataChecksummed>>>,
     erasure_coding: ErasureCoding,
     modifying_sector_index: Arc<RwLock<Option<SectorIndex>>>,
@@ -120,6 +122,7 @@ async fn read_pieces<PosTable>(
                 Some(sector_metadata) => sector_metadata.clone(),
                 None => {
                     error!(
+                        code = %ErrorCode::PieceReadFailed,
                         %sector_index,
                         %sector_count,
                         "Tried to read piece from sector that is not yet plotted"
@@ -205,6 +208,7 @@ where
         Ok(piece) => piece,
         Err(error) => {
             error!(
+                code = %ErrorCode::PieceReadFailed,
                 %sector_index,
                 %piece_offset,
                 %error,
@@ -0,0 +1,300 @@
+//! Optional encryption of plot contents at rest.
+//!
+//! Plot is encrypted with AES-256 in XTS-style tweaked mode where every 16 bytes block is a
+//! separate data unit tweaked by its offset in the plot file. This allows to decrypt arbitrary
+//! ranges of the plot on the fly (auditing reads small chunks at random offsets) at the cost of
+//! one extra AES operation per block, which is negligible on hosts with AES-NI.
+//!
+//! Encryption only protects data at rest from someone with access to the disk, plotted data is
+//! public anyway, but some operators need it to satisfy compliance requirements on leased
+//! hardware.
+
+#[cfg(test)]
+mod tests;
+
+use crate::identity::Identity;
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use aes::{Aes256, Block};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io;
+use std::ops::Range;
+use subspace_core_primitives::Blake3Hash;
+use subspace_farmer_components::file_ext::FileExt;
+use subspace_farmer_components::ReadAtSync;
+use zeroize::Zeroizing;
+
+/// Context used for deriving encryption keys from key material
+const KEY_DERIVATION_CONTEXT: &str = "subspace-farmer 2024-01 plot encryption key";
+/// Context used for deriving fingerprint of the encryption key
+const KEY_FINGERPRINT_CONTEXT: &str = "subspace-farmer 2024-01 plot encryption key fingerprint";
+/// Size of the encryption unit in bytes
+const BLOCK_SIZE: u64 = 16;
+/// Number of blocks processed at once, allows AES implementation to process multiple blocks in
+/// parallel
+const BLOCKS_PER_BATCH: usize = 64;
+
+/// Where encryption key comes from.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlotEncryptionKeySource {
+    /// Key is derived from farmer identity stored next to the plot
+    Identity,
+    /// Key material is provided externally (for example by OS keystore)
+    External,
+}
+
+/// Plot encryption information stored in farm info.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlotEncryptionInfo {
+    /// Where encryption key comes from
+    pub key_source: PlotEncryptionKeySource,
+    /// Fingerprint of the encryption key, allows to detect wrong key before reading garbage
+    #[serde(with = "hex::serde")]
+    pub key_fingerprint: Blake3Hash,
+}
+
+/// Plot encryption option for a farm.
+#[derive(Clone)]
+pub enum PlotEncryption {
+    /// Derive encryption key from farmer identity
+    Identity,
+    /// Derive encryption key from externally provided key material
+    External(Zeroizing<Vec<u8>>),
+}
+
+impl PlotEncryption {
+    /// Create cipher for this encryption option
+    pub fn cipher(&self, identity: &Identity) -> PlotCipher {
+        match self {
+            Self::Identity => PlotCipher::from_identity(identity),
+            Self::External(key_material) => PlotCipher::new(key_material),
+        }
+    }
+
+    /// Where encryption key comes from
+    pub fn key_source(&self) -> PlotEncryptionKeySource {
+        match self {
+            Self::Identity => PlotEncryptionKeySource::Identity,
+            Self::External(_) => PlotEncryptionKeySource::External,
+        }
+    }
+}
+
+/// Cipher used for plot encryption.
+#[derive(Clone)]
+pub struct PlotCipher {
+    data_cipher: Aes256,
+    tweak_cipher: Aes256,
+    key_fingerprint: Blake3Hash,
+}
+
+impl PlotCipher {
+    /// Create new cipher from arbitrary key material, actual keys are derived from it
+    pub fn new(key_material: &[u8]) -> Self {
+        let mut keys = Zeroizing::new([0u8; 64]);
+        blake3::Hasher::new_derive_key(KEY_DERIVATION_CONTEXT)
+            .update(key_material)
+            .finalize_xof()
+            .fill(keys.as_mut());
+
+        let (data_key, tweak_key) = keys.split_at(32);
+
+        Self {
+            data_cipher: Aes256::new(GenericArray::from_slice(data_key)),
+            tweak_cipher: Aes256::new(GenericArray::from_slice(tweak_key)),
+            key_fingerprint: blake3::derive_key(KEY_FINGERPRINT_CONTEXT, keys.as_ref()),
+        }
+    }
+
+    /// Create new cipher with key derived from farmer identity
+    pub fn from_identity(identity: &Identity) -> Self {
+        Self::new(Zeroizing::new(identity.secret_key().to_bytes()).as_ref())
+    }
+
+    /// Fingerprint of the encryption key
+    pub fn key_fingerprint(&self) -> &Blake3Hash {
+        &self.key_fingerprint
+    }
+
+    /// Encrypt bytes in place, `offset` is the offset of the bytes in the plot file.
+    ///
+    /// Both `offset` and length of the buffer must be multiples of 16 bytes.
+    pub fn encrypt(&self, buf: &mut [u8], offset: u64) {
+        self.apply(buf, offset, |blocks| {
+            self.data_cipher.encrypt_blocks(blocks)
+        });
+    }
+
+    /// Decrypt bytes in place, `offset` is the offset of the bytes in the plot file.
+    ///
+    /// Both `offset` and length of the buffer must be multiples of 16 bytes.
+    pub fn decrypt(&self, buf: &mut [u8], offset: u64) {
+        self.apply(buf, offset, |blocks| {
+            self.data_cipher.decrypt_blocks(blocks)
+        });
+    }
+
+    fn apply<F>(&self, buf: &mut [u8], offset: u64, process: F)
+    where
+        F: Fn(&mut [Block]),
+    {
+        assert_eq!(
+            offset % BLOCK_SIZE,
+            0,
+            "Offset must be aligned to block size"
+        );
+        assert_eq!(
+            buf.len() as u64 % BLOCK_SIZE,
+            0,
+            "Buffer length must be multiple of block size"
+        );
+
+        let mut tweaks = [Block::default(); BLOCKS_PER_BATCH];
+        let mut blocks = [Block::default(); BLOCKS_PER_BATCH];
+        let mut block_index = offset / BLOCK_SIZE;
+
+        for batch in buf.chunks_mut(BLOCK_SIZE as usize * BLOCKS_PER_BATCH) {
+            let batch_blocks = batch.len() / BLOCK_SIZE as usize;
+            let tweaks = &mut tweaks[..batch_blocks];
+            let blocks = &mut blocks[..batch_blocks];
+
+            for tweak in tweaks.iter_mut() {
+                tweak.copy_from_slice(&u128::from(block_index).to_le_bytes());
+                block_index += 1;
+            }
+            self.tweak_cipher.encrypt_blocks(tweaks);
+
+            for ((block, bytes), tweak) in blocks
+                .iter_mut()
+                .zip(batch.chunks_exact(BLOCK_SIZE as usize))
+                .zip(tweaks.iter())
+            {
+                block.iter_mut().zip(bytes.iter().zip(tweak)).for_each(
+                    |(block_byte, (byte, tweak_byte))| {
+                        *block_byte = byte ^ tweak_byte;
+                    },
+                );
+            }
+
+            process(blocks);
+
+            for ((bytes, block), tweak) in batch
+                .chunks_exact_mut(BLOCK_SIZE as usize)
+                .zip(blocks.iter())
+                .zip(tweaks.iter())
+            {
+                bytes.iter_mut().zip(block.iter().zip(tweak)).for_each(
+                    |(byte, (block_byte, tweak_byte))| {
+                        *byte = block_byte ^ tweak_byte;
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Range of bytes aligned to encryption block size that covers specified range
+fn aligned_range(offset: u64, len: usize) -> Range<u64> {
+    let start = offset - offset % BLOCK_SIZE;
+    let end = (offset + len as u64).div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+    start..end
+}
+
+/// Plot file with optional transparent encryption.
+///
+/// When cipher is present, all reads are decrypted and all writes are encrypted on the fly,
+/// otherwise calls are forwarded to the inner file as is.
+pub struct PlotFile<F> {
+    file: F,
+    cipher: Option<PlotCipher>,
+}
+
+impl<F> ReadAtSync for PlotFile<F>
+where
+    F: ReadAtSync,
+{
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()> {
+        let Some(cipher) = &self.cipher else {
+            return self.file.read_at(buf, offset);
+        };
+
+        let aligned_range = aligned_range(offset, buf.len());
+        if aligned_range.start == offset && aligned_range.end - offset == buf.len() as u64 {
+            self.file.read_at(buf, offset)?;
+            cipher.decrypt(buf, offset);
+        } else {
+            let mut aligned_buf = vec![0; (aligned_range.end - aligned_range.start) as usize];
+            self.file.read_at(&mut aligned_buf, aligned_range.start)?;
+            cipher.decrypt(&mut aligned_buf, aligned_range.start);
+            let skip = (offset - aligned_range.start) as usize;
+            buf.copy_from_slice(&aligned_buf[skip..][..buf.len()]);
+        }
+
+        Ok(())
+    }
+
+    fn read_ahead(&self, offset: u64, len: usize) -> io::Result<()> {
+        self.file.read_ahead(offset, len)
+    }
+}
+
+impl<F> ReadAtSync for &PlotFile<F>
+where
+    F: ReadAtSync,
+{
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()> {
+        (*self).read_at(buf, offset)
+    }
+
+    fn read_ahead(&self, offset: u64, len: usize) -> io::Result<()> {
+        (*self).read_ahead(offset, len)
+    }
+}
+
+impl<F> PlotFile<F> {
+    /// Wrap a file, plot contents will be encrypted if cipher is provided
+    pub fn new(file: F, cipher: Option<PlotCipher>) -> Self {
+        Self { file, cipher }
+    }
+
+    /// Access inner file, for operations that don't touch plot contents
+    pub fn inner(&self) -> &F {
+        &self.file
+    }
+}
+
+impl PlotFile<File> {
+    /// Write all provided bytes at a specific offset
+    pub fn write_all_at(&self, buf: &[u8], offset: u64) -> io::Result<()> {
+        let Some(cipher) = &self.cipher else {
+            return self.file.write_all_at(buf, offset);
+        };
+
+        let aligned_range = aligned_range(offset, buf.len());
+        let mut aligned_buf = vec![0; (aligned_range.end - aligned_range.start) as usize];
+        let skip = (offset - aligned_range.start) as usize;
+
+        // Partially overwritten blocks at the edges need to be decrypted first to preserve
+        // neighbouring bytes
+        if skip != 0 {
+            let block = &mut aligned_buf[..BLOCK_SIZE as usize];
+            self.file.read_exact_at(block, aligned_range.start)?;
+            cipher.decrypt(block, aligned_range.start);
+        }
+        let tail = (aligned_range.end - (offset + buf.len() as u64)) as usize;
+        if tail != 0 && (aligned_buf.len() > BLOCK_SIZE as usize || skip == 0) {
+            let last_block_offset = aligned_range.end - BLOCK_SIZE;
+            let block = &mut aligned_buf[aligned_buf.len() - BLOCK_SIZE as usize..];
+            self.file.read_exact_at(block, last_block_offset)?;
+            cipher.decrypt(block, last_block_offset);
+        }
+
+        aligned_buf[skip..][..buf.len()].copy_from_slice(buf);
+        cipher.encrypt(&mut aligned_buf, aligned_range.start);
+
+        self.file.write_all_at(&aligned_buf, aligned_range.start)
+    }
+}
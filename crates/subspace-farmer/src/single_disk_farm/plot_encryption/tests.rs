@@ -0,0 +1,107 @@
+use crate::single_disk_farm::plot_encryption::{PlotCipher, PlotFile};
+use rand::prelude::*;
+use std::fs::OpenOptions;
+use subspace_farmer_components::file_ext::FileExt;
+use subspace_farmer_components::ReadAtSync;
+use tempfile::tempdir;
+
+#[test]
+fn cipher_roundtrip() {
+    let cipher = PlotCipher::new(b"key material");
+
+    let mut plaintext = vec![0u8; 16 * 1000];
+    thread_rng().fill(plaintext.as_mut_slice());
+
+    let mut buf = plaintext.clone();
+    cipher.encrypt(&mut buf, 16 * 7);
+    assert_ne!(buf, plaintext);
+
+    // Every block is encrypted independently, so any aligned sub-range can be decrypted separately
+    let mut sub_range = buf[16 * 100..16 * 300].to_vec();
+    cipher.decrypt(&mut sub_range, 16 * (7 + 100));
+    assert_eq!(sub_range, plaintext[16 * 100..16 * 300]);
+
+    // Same bytes at different offset are encrypted differently
+    let mut shifted = plaintext.clone();
+    cipher.encrypt(&mut shifted, 16 * 8);
+    assert_ne!(shifted, buf);
+
+    cipher.decrypt(&mut buf, 16 * 7);
+    assert_eq!(buf, plaintext);
+
+    // Different key material results in different key
+    assert_ne!(
+        PlotCipher::new(b"other key material").key_fingerprint(),
+        cipher.key_fingerprint()
+    );
+}
+
+#[test]
+fn plot_file_unaligned_access() {
+    let directory = tempdir().unwrap();
+    let path = directory.path().join("plot.bin");
+    let file_size = 4096;
+
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .open(&path)
+        .unwrap();
+    file.set_len(file_size).unwrap();
+
+    let plot_file = PlotFile::new(file, Some(PlotCipher::new(b"key material")));
+    let mut expected = vec![0u8; file_size as usize];
+
+    // Writes that cover partial blocks at either end, fit within a single block or are aligned
+    for (offset, len) in [
+        (0, 4096),
+        (3, 10),
+        (5, 100),
+        (16, 32),
+        (1000, 1500),
+        (4090, 6),
+    ] {
+        let mut bytes = vec![0u8; len];
+        thread_rng().fill(bytes.as_mut_slice());
+
+        plot_file.write_all_at(&bytes, offset as u64).unwrap();
+        expected[offset..][..len].copy_from_slice(&bytes);
+
+        let mut read_back = vec![0u8; file_size as usize];
+        plot_file.read_at(&mut read_back, 0).unwrap();
+        assert_eq!(read_back, expected);
+    }
+
+    for (offset, len) in [(1, 1), (7, 33), (32, 64), (2047, 2049)] {
+        let mut read_back = vec![0u8; len];
+        plot_file.read_at(&mut read_back, offset as u64).unwrap();
+        assert_eq!(read_back, expected[offset..][..len]);
+    }
+
+    // Data is not stored in plaintext on disk
+    let mut raw = vec![0u8; file_size as usize];
+    plot_file.inner().read_exact_at(&mut raw, 0).unwrap();
+    assert_ne!(raw, expected);
+}
+
+#[test]
+fn plot_file_without_cipher() {
+    let directory = tempdir().unwrap();
+    let path = directory.path().join("plot.bin");
+
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .open(&path)
+        .unwrap();
+    file.set_len(64).unwrap();
+
+    let plot_file = PlotFile::new(file, None);
+    plot_file.write_all_at(&[1, 2, 3], 5).unwrap();
+
+    let mut raw = [0u8; 3];
+    plot_file.inner().read_exact_at(&mut raw, 5).unwrap();
+    assert_eq!(raw, [1, 2, 3]);
+}
@@ -1,3 +1,8 @@
+use crate::error_code::ErrorCode;
+use crate::remote_plotter::{
+    PlotSectorRequest, RemotePlotterClient, RemotePlottingError, RemotelyPlottedSector,
+};
+use crate::single_disk_farm::plot_encryption::PlotFile;
 use crate::single_disk_farm::{
     BackgroundTaskError, Handlers, PlotMetadataHeader, SectorUpdate, RESERVED_PLOT_METADATA,
 };
@@ -7,7 +12,7 @@ use crate::{node_client, NodeClient};
 use async_lock::RwLock;
 use atomic::Atomic;
 use futures::channel::{mpsc, oneshot};
-use futures::{select, FutureExt, SinkExt, StreamExt};
+use futures::{select, stream, FutureExt, SinkExt, StreamExt};
 use lru::LruCache;
 use parity_scale_codec::{Decode, Encode};
 use std::collections::HashMap;
@@ -20,7 +25,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::{
-    Blake3Hash, HistorySize, PieceOffset, PublicKey, SectorId, SectorIndex, SegmentHeader,
+    HistorySize, PieceOffset, PublicKey, PublicKeyHash, SectorId, SectorIndex, SegmentHeader,
     SegmentIndex,
 };
 use subspace_erasure_coding::ErasureCoding;
@@ -29,8 +34,10 @@ use subspace_farmer_components::plotting::{
     download_sector, encode_sector, DownloadSectorOptions, DownloadedSector, EncodeSectorOptions,
     PlottedSector,
 };
-use subspace_farmer_components::sector::SectorMetadataChecksummed;
-use subspace_farmer_components::{plotting, PieceGetter, PieceGetterRetryPolicy};
+use subspace_farmer_components::sector::{
+    upgrade_sector, SectorFormatVersion, SectorMetadataChecksummed,
+};
+use subspace_farmer_components::{plotting, PieceGetter, PieceGetterRetryPolicy, ReadAtSync};
 use subspace_proof_of_space::Table;
 use thiserror::Error;
 use tokio::sync::{broadcast, OwnedSemaphorePermit, Semaphore};
@@ -138,6 +145,25 @@ pub enum PlottingError {
     /// Background downloading panicked
     #[error("Background downloading panicked")]
     BackgroundDownloadingPanicked,
+    /// Remote plotting error
+    #[error("Remote plotting error: {0}")]
+    RemotePlotting(#[from] RemotePlottingError),
+}
+
+impl PlottingError {
+    /// Stable machine-readable code of the error, see [`ErrorCode`]
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            PlottingError::FailedToGetFarmerInfo { .. } => ErrorCode::NodeRequestFailed,
+            PlottingError::FailedToGetSegmentHeader { .. } => ErrorCode::NodeRequestFailed,
+            PlottingError::MissingArchivedSegmentHeader { .. } => ErrorCode::NodeRequestFailed,
+            PlottingError::FailedToSubscribeArchivedSegments { .. } => ErrorCode::NodeRequestFailed,
+            PlottingError::LowLevel(_) => ErrorCode::PlottingFailed,
+            PlottingError::Io(_) => ErrorCode::Io,
+            PlottingError::BackgroundDownloadingPanicked => ErrorCode::BackgroundTaskPanicked,
+            PlottingError::RemotePlotting(_) => ErrorCode::RemotePlottingFailed,
+        }
+    }
 }
 
 pub(super) struct PlottingOptions<'a, NC, PG> {
@@ -147,9 +173,11 @@ pub(super) struct PlottingOptions<'a, NC, PG> {
     pub(super) sector_size: usize,
     pub(super) sector_metadata_size: usize,
     pub(super) metadata_header: PlotMetadataHeader,
-    pub(super) plot_file: Arc<File>,
+    pub(super) plot_file: Arc<PlotFile<File>>,
     pub(super) metadata_file: File,
     pub(super) sectors_metadata: Arc<RwLock<Vec<SectorMetadataChecksummed>>>,
+    /// Sectors plotted in older format, they are upgraded in place before plotting starts
+    pub(super) outdated_sectors: Vec<(SectorIndex, SectorFormatVersion)>,
     pub(super) piece_getter: &'a PG,
     pub(super) kzg: &'a Kzg,
     pub(super) erasure_coding: &'a ErasureCoding,
@@ -161,9 +189,96 @@ pub(super) struct PlottingOptions<'a, NC, PG> {
     pub(crate) downloading_semaphore: Arc<Semaphore>,
     pub(crate) record_encoding_concurrency: NonZeroUsize,
     pub(super) plotting_thread_pool_manager: PlottingThreadPoolManager,
+    /// Remote plotter that plots sectors instead of this farm, sectors are only written locally
+    pub(super) remote_plotter: Option<RemotePlotterClient>,
     pub(super) stop_receiver: broadcast::Receiver<()>,
 }
 
+/// Upgrade sectors plotted in older format to the current one in place.
+///
+/// Sector contents are only read and rewritten if format change requires it, otherwise only sector
+/// metadata is rewritten.
+#[allow(clippy::too_many_arguments)]
+async fn upgrade_sectors(
+    outdated_sectors: Vec<(SectorIndex, SectorFormatVersion)>,
+    sector_size: usize,
+    sector_metadata_size: usize,
+    plot_file: &PlotFile<File>,
+    metadata_file: &File,
+    sectors_metadata: &RwLock<Vec<SectorMetadataChecksummed>>,
+    modifying_sector_index: &RwLock<Option<SectorIndex>>,
+    abort_early: &AtomicBool,
+) -> Result<(), PlottingError> {
+    if outdated_sectors.is_empty() {
+        return Ok(());
+    }
+
+    info!(
+        sectors = %outdated_sectors.len(),
+        "Upgrading sectors plotted in older format"
+    );
+
+    for (sector_index, version) in outdated_sectors {
+        if abort_early.load(Ordering::Acquire) {
+            return Ok(());
+        }
+
+        let Some(mut sector_metadata) = sectors_metadata
+            .read()
+            .await
+            .get(sector_index as usize)
+            .cloned()
+        else {
+            continue;
+        };
+
+        // Inform others that this sector is being modified
+        modifying_sector_index.write().await.replace(sector_index);
+
+        let sector_offset = sector_index as usize * sector_size;
+        let result = tokio::task::block_in_place(|| {
+            let mut sector = if version.requires_contents_upgrade() {
+                let mut sector = vec![0; sector_size];
+                plot_file.read_at(&mut sector, sector_offset as u64)?;
+                sector
+            } else {
+                Vec::new()
+            };
+
+            upgrade_sector(version, &mut sector_metadata, &mut sector)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+            if !sector.is_empty() {
+                plot_file.write_all_at(&sector, sector_offset as u64)?;
+            }
+            metadata_file.write_all_at(
+                &sector_metadata.encode_with_version(SectorFormatVersion::CURRENT),
+                RESERVED_PLOT_METADATA + (u64::from(sector_index) * sector_metadata_size as u64),
+            )
+        });
+
+        if result.is_ok() {
+            if let Some(existing_sector_metadata) = sectors_metadata
+                .write()
+                .await
+                .get_mut(sector_index as usize)
+            {
+                *existing_sector_metadata = sector_metadata;
+            }
+        }
+
+        // Inform others that this sector is no longer being modified
+        modifying_sector_index.write().await.take();
+
+        result?;
+        debug!(%sector_index, %version, "Sector upgraded successfully");
+    }
+
+    info!("Sectors upgrade complete");
+
+    Ok(())
+}
+
 /// Starts plotting process.
 ///
 /// NOTE: Returned future is async, but does blocking operations and should be running in dedicated
@@ -186,6 +301,7 @@ where
         plot_file,
         metadata_file,
         sectors_metadata,
+        outdated_sectors,
         piece_getter,
         kzg,
         erasure_coding,
@@ -195,6 +311,7 @@ where
         downloading_semaphore,
         record_encoding_concurrency,
         plotting_thread_pool_manager,
+        remote_plotter,
         mut stop_receiver,
     } = plotting_options;
 
@@ -214,9 +331,26 @@ where
         true,
     );
 
-    let mut table_generators = (0..record_encoding_concurrency.get())
-        .map(|_| PosTable::generator())
-        .collect::<Vec<_>>();
+    upgrade_sectors(
+        outdated_sectors,
+        sector_size,
+        sector_metadata_size,
+        &plot_file,
+        &metadata_file,
+        &sectors_metadata,
+        &modifying_sector_index,
+        &abort_early,
+    )
+    .await?;
+
+    // Table generators are not needed when sectors are plotted by remote plotter
+    let mut table_generators = if remote_plotter.is_some() {
+        Vec::new()
+    } else {
+        (0..record_encoding_concurrency.get())
+            .map(|_| PosTable::generator())
+            .collect::<Vec<_>>()
+    };
 
     let mut maybe_next_downloaded_sector_fut = None::<
         AsyncJoinOnDrop<Result<(OwnedSemaphorePermit, DownloadedSector), plotting::PlottingError>>,
@@ -285,156 +419,192 @@ where
             break farmer_app_info;
         };
 
-        let (_downloading_permit, downloaded_sector) =
-            if let Some(downloaded_sector_fut) = maybe_next_downloaded_sector_fut.take() {
-                downloaded_sector_fut
-                    .await
-                    .map_err(|_error| PlottingError::BackgroundDownloadingPanicked)??
-            } else {
-                let downloading_permit = Arc::clone(&downloading_semaphore)
-                    .acquire_owned()
-                    .await
-                    .map_err(plotting::PlottingError::from)?;
+        let sector;
+        let sector_metadata;
+        let plotted_sector;
 
-                handlers.sector_update.call_simple(&(
-                    sector_index,
-                    SectorUpdate::Plotting(SectorPlottingDetails::Downloading),
-                ));
+        let _downloading_permit = if let Some(remote_plotter) = &remote_plotter {
+            let downloading_permit = Arc::clone(&downloading_semaphore)
+                .acquire_owned()
+                .await
+                .map_err(plotting::PlottingError::from)?;
 
-                let start = Instant::now();
+            handlers.sector_update.call_simple(&(
+                sector_index,
+                SectorUpdate::Plotting(SectorPlottingDetails::Encoding),
+            ));
+
+            let start = Instant::now();
 
-                let downloaded_sector_fut = download_sector(DownloadSectorOptions {
-                    public_key: &public_key,
+            RemotelyPlottedSector {
+                sector,
+                sector_metadata,
+                plotted_sector,
+            } = remote_plotter
+                .plot_sector(PlotSectorRequest {
+                    public_key,
                     sector_index,
-                    piece_getter,
-                    piece_getter_retry_policy: PieceGetterRetryPolicy::Limited(
-                        PIECE_GETTER_RETRY_NUMBER.get(),
-                    ),
                     farmer_protocol_info: farmer_app_info.protocol_info,
-                    kzg,
                     pieces_in_sector,
-                });
+                })
+                .await?;
 
-                let downloaded_sector = downloaded_sector_fut.await?;
+            handlers.sector_update.call_simple(&(
+                sector_index,
+                SectorUpdate::Plotting(SectorPlottingDetails::Encoded(start.elapsed())),
+            ));
 
-                handlers.sector_update.call_simple(&(
-                    sector_index,
-                    SectorUpdate::Plotting(SectorPlottingDetails::Downloaded(start.elapsed())),
-                ));
+            downloading_permit
+        } else {
+            let (downloading_permit, downloaded_sector) =
+                if let Some(downloaded_sector_fut) = maybe_next_downloaded_sector_fut.take() {
+                    downloaded_sector_fut
+                        .await
+                        .map_err(|_error| PlottingError::BackgroundDownloadingPanicked)??
+                } else {
+                    let downloading_permit = Arc::clone(&downloading_semaphore)
+                        .acquire_owned()
+                        .await
+                        .map_err(plotting::PlottingError::from)?;
 
-                (downloading_permit, downloaded_sector)
-            };
+                    handlers.sector_update.call_simple(&(
+                        sector_index,
+                        SectorUpdate::Plotting(SectorPlottingDetails::Downloading),
+                    ));
 
-        // Initiate downloading of pieces for the next segment index if already known
-        if let Some(sector_index) = next_segment_index_hint {
-            let piece_getter = piece_getter.clone();
-            let downloading_semaphore = Arc::clone(&downloading_semaphore);
-            let handlers = Arc::clone(&handlers);
-            let kzg = kzg.clone();
-
-            maybe_next_downloaded_sector_fut.replace(AsyncJoinOnDrop::new(
-                tokio::spawn(
-                    async move {
-                        let downloading_permit = downloading_semaphore
-                            .acquire_owned()
-                            .await
-                            .map_err(plotting::PlottingError::from)?;
+                    let start = Instant::now();
 
-                        handlers.sector_update.call_simple(&(
-                            sector_index,
-                            SectorUpdate::Plotting(SectorPlottingDetails::Downloading),
-                        ));
+                    let downloaded_sector_fut = download_sector(DownloadSectorOptions {
+                        public_key: &public_key,
+                        sector_index,
+                        piece_getter,
+                        piece_getter_retry_policy: PieceGetterRetryPolicy::Limited(
+                            PIECE_GETTER_RETRY_NUMBER.get(),
+                        ),
+                        farmer_protocol_info: farmer_app_info.protocol_info,
+                        kzg,
+                        pieces_in_sector,
+                    });
 
-                        let start = Instant::now();
+                    let downloaded_sector = downloaded_sector_fut.await?;
 
-                        let downloaded_sector_fut = download_sector(DownloadSectorOptions {
-                            public_key: &public_key,
-                            sector_index,
-                            piece_getter: &piece_getter,
-                            piece_getter_retry_policy: PieceGetterRetryPolicy::Limited(
-                                PIECE_GETTER_RETRY_NUMBER.get(),
-                            ),
-                            farmer_protocol_info: farmer_app_info.protocol_info,
-                            kzg: &kzg,
-                            pieces_in_sector,
-                        });
+                    handlers.sector_update.call_simple(&(
+                        sector_index,
+                        SectorUpdate::Plotting(SectorPlottingDetails::Downloaded(start.elapsed())),
+                    ));
 
-                        let downloaded_sector = downloaded_sector_fut.await?;
+                    (downloading_permit, downloaded_sector)
+                };
+
+            // Initiate downloading of pieces for the next segment index if already known
+            if let Some(sector_index) = next_segment_index_hint {
+                let piece_getter = piece_getter.clone();
+                let downloading_semaphore = Arc::clone(&downloading_semaphore);
+                let handlers = Arc::clone(&handlers);
+                let kzg = kzg.clone();
+
+                maybe_next_downloaded_sector_fut.replace(AsyncJoinOnDrop::new(
+                    tokio::spawn(
+                        async move {
+                            let downloading_permit = downloading_semaphore
+                                .acquire_owned()
+                                .await
+                                .map_err(plotting::PlottingError::from)?;
+
+                            handlers.sector_update.call_simple(&(
+                                sector_index,
+                                SectorUpdate::Plotting(SectorPlottingDetails::Downloading),
+                            ));
+
+                            let start = Instant::now();
+
+                            let downloaded_sector_fut = download_sector(DownloadSectorOptions {
+                                public_key: &public_key,
+                                sector_index,
+                                piece_getter: &piece_getter,
+                                piece_getter_retry_policy: PieceGetterRetryPolicy::Limited(
+                                    PIECE_GETTER_RETRY_NUMBER.get(),
+                                ),
+                                farmer_protocol_info: farmer_app_info.protocol_info,
+                                kzg: &kzg,
+                                pieces_in_sector,
+                            });
+
+                            let downloaded_sector = downloaded_sector_fut.await?;
+
+                            handlers.sector_update.call_simple(&(
+                                sector_index,
+                                SectorUpdate::Plotting(SectorPlottingDetails::Downloaded(
+                                    start.elapsed(),
+                                )),
+                            ));
+
+                            Ok((downloading_permit, downloaded_sector))
+                        }
+                        .in_current_span(),
+                    ),
+                    true,
+                ));
+            }
+
+            (sector, sector_metadata, table_generators, plotted_sector) = {
+                let plotting_fn = || {
+                    tokio::task::block_in_place(|| {
+                        let mut sector = Vec::new();
+                        let mut sector_metadata = Vec::new();
 
                         handlers.sector_update.call_simple(&(
                             sector_index,
-                            SectorUpdate::Plotting(SectorPlottingDetails::Downloaded(
-                                start.elapsed(),
-                            )),
+                            SectorUpdate::Plotting(SectorPlottingDetails::Encoding),
                         ));
 
-                        Ok((downloading_permit, downloaded_sector))
-                    }
-                    .in_current_span(),
-                ),
-                true,
-            ));
-        }
-
-        let sector;
-        let sector_metadata;
-        let plotted_sector;
-
-        (sector, sector_metadata, table_generators, plotted_sector) = {
-            let plotting_fn = || {
-                tokio::task::block_in_place(|| {
-                    let mut sector = Vec::new();
-                    let mut sector_metadata = Vec::new();
-
-                    handlers.sector_update.call_simple(&(
-                        sector_index,
-                        SectorUpdate::Plotting(SectorPlottingDetails::Encoding),
-                    ));
+                        let start = Instant::now();
 
-                    let start = Instant::now();
+                        let plotted_sector = encode_sector::<PosTable>(
+                            downloaded_sector,
+                            EncodeSectorOptions {
+                                sector_index,
+                                erasure_coding,
+                                pieces_in_sector,
+                                sector_output: &mut sector,
+                                sector_metadata_output: &mut sector_metadata,
+                                table_generators: &mut table_generators,
+                                abort_early: &abort_early,
+                            },
+                        )?;
 
-                    let plotted_sector = encode_sector::<PosTable>(
-                        downloaded_sector,
-                        EncodeSectorOptions {
+                        handlers.sector_update.call_simple(&(
                             sector_index,
-                            erasure_coding,
-                            pieces_in_sector,
-                            sector_output: &mut sector,
-                            sector_metadata_output: &mut sector_metadata,
-                            table_generators: &mut table_generators,
-                            abort_early: &abort_early,
-                        },
-                    )?;
+                            SectorUpdate::Plotting(SectorPlottingDetails::Encoded(start.elapsed())),
+                        ));
 
-                    handlers.sector_update.call_simple(&(
-                        sector_index,
-                        SectorUpdate::Plotting(SectorPlottingDetails::Encoded(start.elapsed())),
-                    ));
+                        Ok((sector, sector_metadata, table_generators, plotted_sector))
+                    })
+                };
 
-                    Ok((sector, sector_metadata, table_generators, plotted_sector))
-                })
-            };
+                let thread_pools = plotting_thread_pool_manager.get_thread_pools();
+                let thread_pool = if replotting {
+                    &thread_pools.replotting
+                } else {
+                    &thread_pools.plotting
+                };
 
-            let thread_pools = plotting_thread_pool_manager.get_thread_pools();
-            let thread_pool = if replotting {
-                &thread_pools.replotting
-            } else {
-                &thread_pools.plotting
-            };
+                // Give a chance to interrupt plotting if necessary
+                yield_now().await;
 
-            // Give a chance to interrupt plotting if necessary
-            yield_now().await;
+                let plotting_result = thread_pool.install(plotting_fn);
 
-            let plotting_result = thread_pool.install(plotting_fn);
+                if matches!(
+                    plotting_result,
+                    Err(PlottingError::LowLevel(plotting::PlottingError::AbortEarly))
+                ) {
+                    return Ok(());
+                }
 
-            if matches!(
-                plotting_result,
-                Err(PlottingError::LowLevel(plotting::PlottingError::AbortEarly))
-            ) {
-                return Ok(());
-            }
+                plotting_result?
+            };
 
-            plotting_result?
+            downloading_permit
         };
 
         // Inform others that this sector is being modified
@@ -449,8 +619,13 @@ where
             let start = Instant::now();
 
             plot_file.write_all_at(&sector, (sector_index as usize * sector_size) as u64)?;
+            let versioned_sector_metadata = {
+                let mut versioned_sector_metadata = SectorFormatVersion::CURRENT.encode();
+                versioned_sector_metadata.extend_from_slice(&sector_metadata);
+                versioned_sector_metadata
+            };
             metadata_file.write_all_at(
-                &sector_metadata,
+                &versioned_sector_metadata,
                 RESERVED_PLOT_METADATA + (u64::from(sector_index) * sector_metadata_size as u64),
             )?;
 
@@ -530,7 +705,7 @@ where
 }
 
 pub(super) struct PlottingSchedulerOptions<NC> {
-    pub(super) public_key_hash: Blake3Hash,
+    pub(super) public_key_hash: PublicKeyHash,
     pub(super) sectors_indices_left_to_plot: Range<SectorIndex>,
     pub(super) target_sector_count: SectorIndex,
     pub(super) last_archived_segment_index: SegmentIndex,
@@ -539,6 +714,8 @@ pub(super) struct PlottingSchedulerOptions<NC> {
     pub(super) handlers: Arc<Handlers>,
     pub(super) sectors_metadata: Arc<RwLock<Vec<SectorMetadataChecksummed>>>,
     pub(super) sectors_to_plot_sender: mpsc::Sender<SectorToPlot>,
+    /// Sectors that were quarantined by farming and need to be replotted regardless of expiration
+    pub(super) sectors_to_replot_receiver: mpsc::Receiver<SectorIndex>,
     pub(super) initial_plotting_finished: Option<oneshot::Sender<()>>,
     // Delay between segment header being acknowledged by farmer and potentially triggering
     // replotting
@@ -561,6 +738,7 @@ where
         handlers,
         sectors_metadata,
         sectors_to_plot_sender,
+        sectors_to_replot_receiver,
         initial_plotting_finished,
         new_segment_processing_delay,
     } = plotting_scheduler_options;
@@ -603,6 +781,7 @@ where
         &last_archived_segment,
         archived_segments_receiver,
         sectors_to_plot_sender,
+        sectors_to_replot_receiver,
         initial_plotting_finished,
     );
 
@@ -663,9 +842,14 @@ struct SectorToReplot {
     expires_at: SegmentIndex,
 }
 
+enum PlottingSchedulerEvent {
+    ArchivedSegment,
+    SectorQuarantined(SectorIndex),
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn send_plotting_notifications<NC>(
-    public_key_hash: Blake3Hash,
+    public_key_hash: PublicKeyHash,
     sectors_indices_left_to_plot: Range<SectorIndex>,
     target_sector_count: SectorIndex,
     min_sector_lifetime: HistorySize,
@@ -673,8 +857,9 @@ async fn send_plotting_notifications<NC>(
     handlers: &Handlers,
     sectors_metadata: Arc<RwLock<Vec<SectorMetadataChecksummed>>>,
     last_archived_segment: &Atomic<SegmentHeader>,
-    mut archived_segments_receiver: mpsc::Receiver<()>,
+    archived_segments_receiver: mpsc::Receiver<()>,
     mut sectors_to_plot_sender: mpsc::Sender<SectorToPlot>,
+    sectors_to_replot_receiver: mpsc::Receiver<SectorIndex>,
     initial_plotting_finished: Option<oneshot::Sender<()>>,
 ) -> Result<(), BackgroundTaskError>
 where
@@ -714,12 +899,31 @@ where
     let mut sectors_to_check = Vec::with_capacity(usize::from(target_sector_count));
     let mut archived_segment_commitments_cache = LruCache::new(ARCHIVED_SEGMENTS_CACHE_SIZE);
 
-    while let Some(()) = archived_segments_receiver.next().await {
+    let mut events = stream::select(
+        archived_segments_receiver.map(|()| PlottingSchedulerEvent::ArchivedSegment),
+        sectors_to_replot_receiver.map(PlottingSchedulerEvent::SectorQuarantined),
+    );
+
+    while let Some(event) = events.next().await {
         let archived_segment_header = last_archived_segment.load(Ordering::SeqCst);
-        trace!(
-            segment_index = %archived_segment_header.segment_index(),
-            "New archived segment received",
-        );
+        match event {
+            PlottingSchedulerEvent::ArchivedSegment => {
+                trace!(
+                    segment_index = %archived_segment_header.segment_index(),
+                    "New archived segment received",
+                );
+            }
+            PlottingSchedulerEvent::SectorQuarantined(sector_index) => {
+                debug!(%sector_index, "Sector quarantined, scheduling replotting");
+
+                sectors_expire_at.remove(&sector_index);
+                // Quarantined sectors are replotted before any expired sectors
+                sectors_to_replot.push(SectorToReplot {
+                    sector_index,
+                    expires_at: SegmentIndex::ZERO,
+                });
+            }
+        }
 
         // It is fine to take a synchronous read lock here because the only time
         // write lock is taken is during plotting, which we know doesn't happen
@@ -731,6 +935,14 @@ where
             .map(|sector_metadata| (sector_metadata.sector_index, sector_metadata.history_size))
             .collect_into(&mut sectors_to_check);
         for (sector_index, history_size) in sectors_to_check.drain(..) {
+            if sectors_to_replot
+                .iter()
+                .any(|sector_to_replot| sector_to_replot.sector_index == sector_index)
+            {
+                // Already scheduled for replotting
+                continue;
+            }
+
             if let Some(expires_at) = sectors_expire_at.get(&sector_index).copied() {
                 trace!(
                     %sector_index,
@@ -741,23 +953,26 @@ where
                 // +1 means we will start replotting a bit before it actually expires to avoid
                 // storing expired sectors
                 if expires_at <= (archived_segment_header.segment_index() + SegmentIndex::ONE) {
+                    let (expiration_details, code) =
+                        if expires_at <= archived_segment_header.segment_index() {
+                            (SectorExpirationDetails::Expired, ErrorCode::SectorExpired)
+                        } else {
+                            (
+                                SectorExpirationDetails::AboutToExpire,
+                                ErrorCode::SectorAboutToExpire,
+                            )
+                        };
                     debug!(
+                        %code,
                         %sector_index,
                         %history_size,
                         %expires_at,
                         "Sector expires soon #1, scheduling replotting"
                     );
 
-                    handlers.sector_update.call_simple(&(
-                        sector_index,
-                        SectorUpdate::Expiration(
-                            if expires_at <= archived_segment_header.segment_index() {
-                                SectorExpirationDetails::Expired
-                            } else {
-                                SectorExpirationDetails::AboutToExpire
-                            },
-                        ),
-                    ));
+                    handlers
+                        .sector_update
+                        .call_simple(&(sector_index, SectorUpdate::Expiration(expiration_details)));
 
                     // Time to replot
                     sectors_to_replot.push(SectorToReplot {
@@ -826,7 +1041,17 @@ where
                     // +1 means we will start replotting a bit before it actually expires to avoid
                     // storing expired sectors
                     if expires_at <= (archived_segment_header.segment_index() + SegmentIndex::ONE) {
+                        let (expiration_details, code) =
+                            if expires_at <= archived_segment_header.segment_index() {
+                                (SectorExpirationDetails::Expired, ErrorCode::SectorExpired)
+                            } else {
+                                (
+                                    SectorExpirationDetails::AboutToExpire,
+                                    ErrorCode::SectorAboutToExpire,
+                                )
+                            };
                         debug!(
+                            %code,
                             %sector_index,
                             %history_size,
                             %expires_at,
@@ -835,13 +1060,7 @@ where
 
                         handlers.sector_update.call_simple(&(
                             sector_index,
-                            SectorUpdate::Expiration(
-                                if expires_at <= archived_segment_header.segment_index() {
-                                    SectorExpirationDetails::Expired
-                                } else {
-                                    SectorExpirationDetails::AboutToExpire
-                                },
-                            ),
+                            SectorUpdate::Expiration(expiration_details),
                         ));
 
                         // Time to replot
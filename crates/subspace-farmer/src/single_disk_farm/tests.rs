@@ -0,0 +1,166 @@
+use crate::single_disk_farm::{
+    upgrade_legacy_metadata_file, PlotMetadataHeader, SingleDiskFarm, RESERVED_PLOT_METADATA,
+};
+use parity_scale_codec::{Decode, Encode};
+use std::fs;
+use std::fs::OpenOptions;
+use subspace_core_primitives::{HistorySize, Record, SegmentIndex};
+use subspace_farmer_components::file_ext::FileExt;
+use subspace_farmer_components::sector::{
+    SectorFormatVersion, SectorMetadata, SectorMetadataChecksummed,
+};
+use subspace_temp_files::TempFileManager;
+use tempfile::tempdir;
+
+fn sector_metadata(sector_index: u16) -> SectorMetadataChecksummed {
+    SectorMetadataChecksummed::from(SectorMetadata {
+        sector_index,
+        pieces_in_sector: 1000,
+        s_bucket_sizes: Box::new([u16::from(sector_index); Record::NUM_S_BUCKETS]),
+        history_size: HistorySize::from(SegmentIndex::from(u64::from(sector_index) + 1)),
+    })
+}
+
+#[test]
+fn legacy_metadata_file_upgrade() {
+    let directory = tempdir().unwrap();
+    let metadata_file_path = directory.path().join(SingleDiskFarm::METADATA_FILE);
+    let sectors_metadata = (0..3).map(sector_metadata).collect::<Vec<_>>();
+
+    // Metadata file in legacy layout: sector metadata without format version
+    {
+        let mut legacy_metadata = vec![0; RESERVED_PLOT_METADATA as usize];
+        let legacy_metadata_header = PlotMetadataHeader {
+            version: SingleDiskFarm::LEGACY_PLOT_VERSION,
+            plotted_sector_count: sectors_metadata.len() as u16,
+        }
+        .encode();
+        legacy_metadata[..legacy_metadata_header.len()].copy_from_slice(&legacy_metadata_header);
+        for sector_metadata in &sectors_metadata {
+            legacy_metadata.extend_from_slice(&sector_metadata.encode());
+        }
+        fs::write(&metadata_file_path, legacy_metadata).unwrap();
+    }
+
+    let legacy_sectors_metadata =
+        SingleDiskFarm::read_all_sectors_metadata(directory.path()).unwrap();
+    assert_eq!(
+        legacy_sectors_metadata
+            .iter()
+            .map(Encode::encode)
+            .collect::<Vec<_>>(),
+        sectors_metadata
+            .iter()
+            .map(Encode::encode)
+            .collect::<Vec<_>>()
+    );
+
+    let temp_files = TempFileManager::open(directory.path()).unwrap();
+    let metadata_file = OpenOptions::new()
+        .read(true)
+        .open(&metadata_file_path)
+        .unwrap();
+    let mut metadata_header = PlotMetadataHeader {
+        version: SingleDiskFarm::LEGACY_PLOT_VERSION,
+        plotted_sector_count: sectors_metadata.len() as u16,
+    };
+    upgrade_legacy_metadata_file(
+        &temp_files,
+        &metadata_file_path,
+        &metadata_file,
+        &mut metadata_header,
+    )
+    .unwrap();
+    drop(metadata_file);
+
+    assert_eq!(
+        metadata_header.version,
+        SingleDiskFarm::SUPPORTED_PLOT_VERSION
+    );
+    assert_eq!(
+        metadata_header.plotted_sector_count,
+        sectors_metadata.len() as u16
+    );
+    // No temporary files are left behind
+    assert!(temp_files.files().is_empty());
+    assert!(!TempFileManager::temp_path(&metadata_file_path).exists());
+
+    let metadata_file = OpenOptions::new()
+        .read(true)
+        .open(&metadata_file_path)
+        .unwrap();
+    let mut metadata_header_bytes = vec![0; PlotMetadataHeader::encoded_size()];
+    metadata_file
+        .read_exact_at(&mut metadata_header_bytes, 0)
+        .unwrap();
+    let stored_metadata_header =
+        PlotMetadataHeader::decode(&mut metadata_header_bytes.as_slice()).unwrap();
+    assert_eq!(
+        stored_metadata_header.version,
+        SingleDiskFarm::SUPPORTED_PLOT_VERSION
+    );
+    assert_eq!(
+        stored_metadata_header.plotted_sector_count,
+        sectors_metadata.len() as u16
+    );
+
+    let sector_metadata_size = SectorMetadataChecksummed::encoded_size_with_version();
+    for (sector_index, sector_metadata) in sectors_metadata.iter().enumerate() {
+        let mut sector_metadata_bytes = vec![0; sector_metadata_size];
+        metadata_file
+            .read_exact_at(
+                &mut sector_metadata_bytes,
+                RESERVED_PLOT_METADATA + (sector_metadata_size * sector_index) as u64,
+            )
+            .unwrap();
+        let (version, upgraded_sector_metadata) =
+            SectorMetadataChecksummed::decode_with_version(&mut sector_metadata_bytes.as_slice())
+                .unwrap();
+        assert_eq!(version, SectorFormatVersion::V0);
+        assert_eq!(upgraded_sector_metadata.encode(), sector_metadata.encode());
+    }
+
+    let upgraded_sectors_metadata =
+        SingleDiskFarm::read_all_sectors_metadata(directory.path()).unwrap();
+    assert_eq!(
+        upgraded_sectors_metadata
+            .iter()
+            .map(Encode::encode)
+            .collect::<Vec<_>>(),
+        sectors_metadata
+            .iter()
+            .map(Encode::encode)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn interrupted_legacy_metadata_file_upgrade() {
+    let directory = tempdir().unwrap();
+    let metadata_file_path = directory.path().join(SingleDiskFarm::METADATA_FILE);
+
+    let mut legacy_metadata = vec![0; RESERVED_PLOT_METADATA as usize];
+    let legacy_metadata_header = PlotMetadataHeader {
+        version: SingleDiskFarm::LEGACY_PLOT_VERSION,
+        plotted_sector_count: 1,
+    }
+    .encode();
+    legacy_metadata[..legacy_metadata_header.len()].copy_from_slice(&legacy_metadata_header);
+    legacy_metadata.extend_from_slice(&sector_metadata(0).encode());
+    fs::write(&metadata_file_path, &legacy_metadata).unwrap();
+
+    // Crash in the middle of writing upgraded metadata leaves partially written temporary file
+    // registered in the manifest
+    {
+        let temp_files = TempFileManager::open(directory.path()).unwrap();
+        let temp_file = temp_files.create(&metadata_file_path).unwrap();
+        temp_file.write_all_at(&[1; 10], 0).unwrap();
+        std::mem::forget(temp_file);
+    }
+
+    // Legacy metadata file is untouched and temporary file is cleaned up on next start
+    assert_eq!(fs::read(&metadata_file_path).unwrap(), legacy_metadata);
+    let temp_files = TempFileManager::open(directory.path()).unwrap();
+    assert!(!TempFileManager::temp_path(&metadata_file_path).exists());
+    assert!(temp_files.files().is_empty());
+}
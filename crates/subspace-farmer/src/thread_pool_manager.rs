@@ -1,14 +1,15 @@
 use parking_lot::{Condvar, Mutex};
-use rayon::{ThreadPool, ThreadPoolBuildError};
+use rayon::ThreadPoolBuildError;
 use std::num::NonZeroUsize;
 use std::ops::Deref;
 use std::sync::Arc;
+use subspace_thread_pool::ManagedThreadPool;
 
 /// A wrapper around thread pool pair for plotting purposes
 #[derive(Debug)]
 pub struct PlottingThreadPoolPair {
-    pub plotting: ThreadPool,
-    pub replotting: ThreadPool,
+    pub plotting: ManagedThreadPool,
+    pub replotting: ManagedThreadPool,
 }
 
 #[derive(Debug)]
@@ -9,14 +9,16 @@ use crate::thread_pool_manager::{PlottingThreadPoolManager, PlottingThreadPoolPa
 use futures::channel::oneshot;
 use futures::channel::oneshot::Canceled;
 use futures::future::Either;
-use rayon::{ThreadBuilder, ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+use rayon::{ThreadBuilder, ThreadPoolBuildError};
 use std::future::Future;
 use std::num::{NonZeroUsize, ParseIntError};
 use std::ops::Deref;
 use std::pin::{pin, Pin};
 use std::str::FromStr;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::{io, thread};
+use subspace_thread_pool::{ManagedThreadPool, ThreadPoolConfig, ThreadPoolManager, ThreadWrapper};
 use tokio::runtime::Handle;
 use tokio::task;
 use tracing::debug;
@@ -27,6 +29,7 @@ use tracing::warn;
 const MAX_DEFAULT_FARMING_THREADS: usize = 32;
 
 /// Joins async join handle on drop
+#[derive(Debug)]
 pub struct AsyncJoinOnDrop<T> {
     handle: Option<task::JoinHandle<T>>,
     abort_on_drop: bool,
@@ -368,42 +371,40 @@ pub fn thread_pool_core_indices(
 }
 
 fn create_plotting_thread_pool_manager_thread_pool_pair(
+    thread_pool_manager: &ThreadPoolManager,
     thread_prefix: &'static str,
     thread_pool_index: usize,
     cpu_core_set: CpuCoreSet,
-) -> Result<ThreadPool, ThreadPoolBuildError> {
-    ThreadPoolBuilder::new()
-        .thread_name(move |thread_index| {
-            format!("{thread_prefix}-{thread_pool_index}.{thread_index}")
-        })
-        .num_threads(cpu_core_set.cpu_cores().len())
-        .spawn_handler({
-            let handle = Handle::current();
-
-            rayon_custom_spawn_handler(move |thread| {
-                let cpu_core_set = cpu_core_set.clone();
-                let handle = handle.clone();
+) -> Result<ManagedThreadPool, ThreadPoolBuildError> {
+    let threads = NonZeroUsize::new(cpu_core_set.cpu_cores().len())
+        .expect("CPU core set is guaranteed to be non-empty; qed");
+    let handle = Handle::current();
 
-                move || {
-                    cpu_core_set.pin_current_thread();
-                    drop(cpu_core_set);
+    thread_pool_manager.build(
+        ThreadPoolConfig::new(format!("{thread_prefix}-{thread_pool_index}"), threads)
+            // Plotting and replotting thread pools of the pair are used one at a time
+            .group(format!("plotting-{thread_pool_index}"))
+            .thread_wrapper(Arc::new(move |run_thread| {
+                cpu_core_set.pin_current_thread();
 
-                    let _guard = handle.enter();
+                let _guard = handle.enter();
 
-                    task::block_in_place(|| thread.run())
-                }
-            })
-        })
-        .build()
+                task::block_in_place(run_thread)
+            })),
+    )
 }
 
 /// Creates thread pool pairs for each of CPU core set pair with number of plotting and replotting threads corresponding
 /// to number of cores in each set and pins threads to all of those CPU cores (each thread to all cors in a set, not
 /// thread per core). Each thread will also have Tokio context available.
 ///
+/// Thread pools are created using `thread_pool_manager`, so they are accounted for in its CPU budget, with plotting and
+/// replotting thread pools of each pair sharing the same reservation.
+///
 /// The easiest way to obtain CPUs is using [`all_cpu_cores`], but [`thread_pool_core_indices`] in case
 /// support for user customizations is desired. They will then have to be composed into pairs for this function.
 pub fn create_plotting_thread_pool_manager<I>(
+    thread_pool_manager: &ThreadPoolManager,
     mut cpu_core_sets: I,
 ) -> Result<PlottingThreadPoolManager, ThreadPoolBuildError>
 where
@@ -419,11 +420,13 @@ where
 
             Ok(PlottingThreadPoolPair {
                 plotting: create_plotting_thread_pool_manager_thread_pool_pair(
+                    thread_pool_manager,
                     "plotting",
                     thread_pool_index,
                     plotting_cpu_core_set,
                 )?,
                 replotting: create_plotting_thread_pool_manager_thread_pool_pair(
+                    thread_pool_manager,
                     "replotting",
                     thread_pool_index,
                     replotting_cpu_core_set,
@@ -477,3 +480,15 @@ pub fn tokio_rayon_spawn_handler() -> impl FnMut(ThreadBuilder) -> io::Result<()
         }
     })
 }
+
+/// This function is supposed to be used with [`ThreadPoolConfig::thread_wrapper()`] to inherit
+/// current tokio runtime.
+pub fn tokio_thread_wrapper() -> ThreadWrapper {
+    let handle = Handle::current();
+
+    Arc::new(move |run_thread| {
+        let _guard = handle.enter();
+
+        task::block_in_place(run_thread)
+    })
+}
@@ -1,3 +1,4 @@
+use crate::error_code::ErrorCode;
 use crate::farmer_cache::FarmerCache;
 use crate::utils::plotted_pieces::PlottedPieces;
 use crate::NodeClient;
@@ -111,6 +112,7 @@ where
             }
             Err(error) => {
                 error!(
+                    code = %ErrorCode::NodeRequestFailed,
                     %error,
                     %piece_index,
                     "Failed to retrieve first segment piece from node"
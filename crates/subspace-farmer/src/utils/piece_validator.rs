@@ -1,6 +1,6 @@
+use crate::error_code::ErrorCode;
 use crate::NodeClient;
 use async_trait::async_trait;
-use subspace_archiving::archiver::is_piece_valid;
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::{Piece, PieceIndex};
 use subspace_networking::libp2p::PeerId;
@@ -46,6 +46,7 @@ where
             Ok(segment_headers) => segment_headers,
             Err(error) => {
                 error!(
+                    code = %ErrorCode::NodeRequestFailed,
                     %piece_index,
                     ?error,
                     "Failed tor retrieve segment headers from node"
@@ -58,6 +59,7 @@ where
             Some(segment_header) => segment_header.segment_commitment(),
             None => {
                 error!(
+                    code = %ErrorCode::NodeRequestFailed,
                     %piece_index,
                     %segment_index,
                     "Segment commitment for segment index wasn't found on node"
@@ -70,17 +72,20 @@ where
             let kzg = self.kzg.clone();
 
             move || {
-                is_piece_valid(&kzg, &piece, &segment_commitment, piece_index.position())
-                    .then_some(piece)
+                piece
+                    .verify(&kzg, &segment_commitment, piece_index)
+                    .map(|()| piece)
             }
         });
 
-        match is_valid_fut.await.unwrap_or_default() {
-            Some(piece) => Some(piece),
-            None => {
+        match is_valid_fut.await {
+            Ok(Ok(piece)) => Some(piece),
+            Ok(Err(error)) => {
                 warn!(
+                    code = %ErrorCode::PieceValidationFailed,
                     %piece_index,
                     %source_peer_id,
+                    %error,
                     "Received invalid piece from peer"
                 );
 
@@ -88,6 +93,10 @@ where
                 let _ = self.dsn_node.ban_peer(source_peer_id).await;
                 None
             }
+            Err(error) => {
+                error!(%piece_index, %error, "Piece validation task failed");
+                None
+            }
         }
     }
 }
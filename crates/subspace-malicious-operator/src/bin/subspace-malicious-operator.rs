@@ -200,6 +200,7 @@ fn main() -> Result<(), Error> {
                     max_pending_out_connections: 150,
                     external_addresses: vec![],
                     disable_bootstrap_on_start: false,
+                    client_only: false,
                 }
             };
 
@@ -212,6 +213,8 @@ fn main() -> Result<(), Error> {
                 sync_from_dsn: true,
                 is_timekeeper: false,
                 timekeeper_cpu_cores: Default::default(),
+                external_timekeeper: None,
+                halt_authoring_on_fork: false,
             };
 
             let partial_components = subspace_service::new_partial::<PosTable, RuntimeApi>(
@@ -30,7 +30,8 @@ use std::error::Error;
 use std::sync::Arc;
 use subspace_core_primitives::PotOutput;
 use subspace_runtime::{
-    CheckStorageAccess, DisablePallets, Runtime, RuntimeCall, SignedExtra, UncheckedExtrinsic,
+    CheckDataInclusion, CheckStorageAccess, DisablePallets, Runtime, RuntimeCall, SignedExtra,
+    UncheckedExtrinsic,
 };
 use subspace_runtime_primitives::opaque::Block as CBlock;
 use subspace_runtime_primitives::{AccountId, Balance, Nonce};
@@ -140,6 +141,7 @@ where
             operator_keystore.clone(),
             // The malicious operator doesn't skip empty bundle
             false,
+            Default::default(),
         );
 
         let malicious_bundle_tamper =
@@ -414,6 +416,7 @@ pub fn construct_signed_extrinsic(
         pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0u128),
         CheckStorageAccess,
         DisablePallets,
+        CheckDataInclusion,
     );
     let raw_payload = generic::SignedPayload::<RuntimeCall, SignedExtra>::from_raw(
         call.clone(),
@@ -429,6 +432,7 @@ pub fn construct_signed_extrinsic(
             (),
             (),
             (),
+            (),
         ),
     );
 
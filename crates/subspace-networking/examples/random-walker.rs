@@ -321,12 +321,7 @@ async fn request_sample_piece(
     let sample_piece_index = PieceIndex::from(0);
 
     let request_result = node
-        .send_generic_request(
-            peer_id,
-            PieceByIndexRequest {
-                piece_index: sample_piece_index,
-            },
-        )
+        .send_generic_request(peer_id, PieceByIndexRequest::new(sample_piece_index))
         .await;
 
     match request_result {
@@ -42,6 +42,8 @@ pub(crate) struct BehaviorConfig<RecordStore> {
     pub(crate) record_store: RecordStore,
     /// The configuration for the [`RequestResponsesBehaviour`] protocol.
     pub(crate) request_response_protocols: Vec<Box<dyn RequestHandler>>,
+    /// Whether to serve inbound requests of request-response protocols.
+    pub(crate) serve_requests: bool,
     /// Connection limits for the swarm.
     pub(crate) connection_limits: ConnectionLimits,
     /// The configuration for the [`ReservedPeersBehaviour`].
@@ -112,9 +114,13 @@ where
             kademlia,
             gossipsub,
             ping: Ping::default(),
-            request_response: RequestResponseFactoryBehaviour::new(
-                config.request_response_protocols,
-            )
+            request_response: if config.serve_requests {
+                RequestResponseFactoryBehaviour::new(config.request_response_protocols)
+            } else {
+                RequestResponseFactoryBehaviour::new_outbound_only(
+                    config.request_response_protocols,
+                )
+            }
             //TODO: Convert to an error.
             .expect("RequestResponse protocols registration failed."),
             block_list: BlockListBehaviour::default(),
@@ -1,38 +1,25 @@
 //! Simple bootstrap node implementation
 
-#![feature(const_option, type_changing_struct_update)]
-
 use clap::Parser;
 use futures::{select, FutureExt};
 use libp2p::identity::ed25519::Keypair;
-use libp2p::kad::Mode;
 use libp2p::{identity, Multiaddr, PeerId};
 use prometheus_client::registry::Registry;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
-use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
 use subspace_metrics::{start_prometheus_metrics_server, RegistryAdapter};
+use subspace_networking::bootstrap_node::{BootstrapNode, BootstrapNodeConfig};
 use subspace_networking::libp2p::multiaddr::Protocol;
-use subspace_networking::utils::strip_peer_id;
-use subspace_networking::{
-    peer_id, Config, KademliaMode, KnownPeersManager, KnownPeersManagerConfig,
-};
+use subspace_networking::peer_id;
 use tracing::{debug, info, Level};
 use tracing_subscriber::fmt::Subscriber;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
 
-/// Defines an expiration period for the peer marked for the removal for Kademlia DHT.
-const REMOVE_KNOWN_PEERS_GRACE_PERIOD_FOR_KADEMLIA_SECS: Duration = Duration::from_secs(3600);
-
-/// Size of the LRU cache for peers.
-pub const KNOWN_PEERS_CACHE_SIZE: NonZeroUsize = NonZeroUsize::new(10000).expect("Not zero; qed");
-
 #[derive(Debug, Parser)]
 #[clap(about, version)]
 enum Command {
@@ -42,8 +29,12 @@ enum Command {
         #[arg(long, alias = "bootstrap-node")]
         bootstrap_nodes: Vec<Multiaddr>,
         /// Keypair for node identity, can be obtained with `generate-keypair` command
-        #[clap(long)]
-        keypair: String,
+        #[clap(long, required_unless_present = "base_path")]
+        keypair: Option<String>,
+        /// Base path for persistent node identity and known peers, identity is generated on first
+        /// start if `--keypair` is not specified
+        #[arg(long)]
+        base_path: Option<PathBuf>,
         /// Multiaddr to listen on for subspace networking, multiple are supported
         #[arg(long, default_values_t = [
             Multiaddr::from(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
@@ -61,6 +52,10 @@ enum Command {
         /// Multiaddresses of reserved peers to maintain connections to, multiple are supported
         #[arg(long, alias = "reserved-peer")]
         reserved_peers: Vec<Multiaddr>,
+        /// Multiaddresses (with `/p2p/` suffix) of peers that are always allowed to connect
+        /// regardless of connection limits, multiple are supported
+        #[arg(long)]
+        allowlist: Vec<Multiaddr>,
         /// Defines max established incoming connections limit for the peer.
         #[arg(long, default_value_t = 300)]
         in_peers: u32,
@@ -140,8 +135,10 @@ async fn main() -> Result<(), Box<dyn Error>> {
         Command::Start {
             bootstrap_nodes,
             keypair,
+            base_path,
             listen_on,
             reserved_peers,
+            allowlist,
             in_peers,
             out_peers,
             pending_in_peers,
@@ -156,8 +153,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 protocol_version
             );
 
-            let decoded_keypair = Keypair::try_from_bytes(hex::decode(keypair)?.as_mut_slice())?;
-            let keypair = identity::Keypair::from(decoded_keypair);
+            let keypair = keypair
+                .map(|keypair| {
+                    Keypair::try_from_bytes(hex::decode(keypair)?.as_mut_slice())
+                        .map(identity::Keypair::from)
+                        .map_err(Box::<dyn Error>::from)
+                })
+                .transpose()?;
 
             // Metrics
             let should_start_prometheus_server = !prometheus_listen_on.is_empty();
@@ -165,43 +167,25 @@ async fn main() -> Result<(), Box<dyn Error>> {
             let dsn_metrics_registry =
                 should_start_prometheus_server.then_some(&mut metrics_registry);
 
-            let known_peers_registry_config = KnownPeersManagerConfig {
-                enable_known_peers_source: false,
-                cache_size: KNOWN_PEERS_CACHE_SIZE,
-                ignore_peer_list: strip_peer_id(bootstrap_nodes.clone())
-                    .into_iter()
-                    .map(|(peer_id, _)| peer_id)
-                    .collect::<HashSet<_>>(),
-                path: None,
-                failed_address_kademlia_removal_interval:
-                    REMOVE_KNOWN_PEERS_GRACE_PERIOD_FOR_KADEMLIA_SECS,
-                failed_address_cache_removal_interval:
-                    REMOVE_KNOWN_PEERS_GRACE_PERIOD_FOR_KADEMLIA_SECS,
-            };
-            let known_peers_registry = KnownPeersManager::new(known_peers_registry_config)?;
-
-            let config = Config {
-                listen_on,
-                allow_non_global_addresses_in_dht: allow_private_ips,
-                reserved_peers,
-                max_established_incoming_connections: in_peers,
-                max_established_outgoing_connections: out_peers,
-                max_pending_incoming_connections: pending_in_peers,
-                max_pending_outgoing_connections: pending_out_peers,
-                bootstrap_addresses: bootstrap_nodes,
-                kademlia_mode: KademliaMode::Static(Mode::Server),
-                external_addresses,
-                networking_parameters_registry: known_peers_registry.boxed(),
-
-                ..Config::new(
-                    protocol_version.to_string(),
+            let bootstrap_node = BootstrapNode::new(
+                BootstrapNodeConfig {
+                    protocol_version,
                     keypair,
-                    (),
-                    dsn_metrics_registry,
-                )
-            };
-            let (node, mut node_runner) =
-                subspace_networking::construct(config).expect("Networking stack creation failed.");
+                    base_path,
+                    listen_on,
+                    bootstrap_nodes,
+                    reserved_peers,
+                    allowlist,
+                    external_addresses,
+                    allow_non_global_addresses_in_dht: allow_private_ips,
+                    max_in_connections: in_peers,
+                    max_out_connections: out_peers,
+                    max_pending_in_connections: pending_in_peers,
+                    max_pending_out_connections: pending_out_peers,
+                },
+                dsn_metrics_registry,
+            )?;
+            let node = bootstrap_node.node();
 
             node.on_new_listener(Arc::new({
                 let node_id = node.id();
@@ -227,11 +211,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 .transpose()?;
             if let Some(prometheus_task) = prometheus_task {
                 select! {
-                   _ = node_runner.run().fuse() => {},
+                   _ = bootstrap_node.run().fuse() => {},
                    _ = prometheus_task.fuse() => {},
                 }
             } else {
-                node_runner.run().await
+                bootstrap_node.run().await
             }
         }
         Command::GenerateKeypair { json } => {
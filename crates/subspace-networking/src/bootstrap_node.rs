@@ -0,0 +1,257 @@
+//! Dedicated DSN bootstrap node.
+//!
+//! Bootstrap node runs Kademlia in server mode and only helps other peers to discover each other,
+//! it doesn't store or serve pieces and doesn't need consensus client, which makes it suitable
+//! for lightweight bootstrap/relay infrastructure.
+
+#[cfg(test)]
+mod tests;
+
+use crate::utils::strip_peer_id;
+use crate::{
+    construct, Config, CreationError, KademliaMode, KnownPeersManager, KnownPeersManagerConfig,
+    KnownPeersManagerPersistenceError, Node, NodeRunner,
+};
+use futures::future::join;
+use libp2p::identity::{ed25519, DecodingError};
+use libp2p::kad::Mode;
+use libp2p::multiaddr::Protocol;
+use libp2p::{identity, Multiaddr, PeerId};
+use prometheus_client::registry::Registry;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{fs, io};
+use subspace_temp_files::TempFileManager;
+use thiserror::Error;
+use tracing::{debug, warn};
+
+/// Defines an expiration period for the peer marked for the removal for Kademlia DHT.
+const REMOVE_KNOWN_PEERS_GRACE_PERIOD_FOR_KADEMLIA: Duration = Duration::from_secs(3600);
+/// Size of the LRU cache for peers.
+const KNOWN_PEERS_CACHE_SIZE: NonZeroUsize = NonZeroUsize::new(10000).expect("Not zero; qed");
+/// File under base path where node identity is stored
+const KEYPAIR_FILE: &str = "keypair.bin";
+/// File under base path where known peers are stored
+const KNOWN_PEERS_FILE: &str = "known_addresses.bin";
+
+/// Errors that might happen during bootstrap node creation.
+#[derive(Debug, Error)]
+pub enum BootstrapNodeError {
+    /// I/O error.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// Stored keypair is invalid.
+    #[error("Invalid keypair: {0}")]
+    InvalidKeypair(#[from] DecodingError),
+    /// Known peers manager error.
+    #[error("Known peers manager error: {0}")]
+    KnownPeersManager(#[from] KnownPeersManagerPersistenceError),
+    /// Can't instantiate the networking stack.
+    #[error("Can't instantiate the networking stack: {0}")]
+    Creation(#[from] CreationError),
+    /// Allowlist entry doesn't contain peer ID.
+    #[error("Allowlist address {0} doesn't contain peer ID")]
+    AllowlistAddressWithoutPeerId(Multiaddr),
+}
+
+/// Bootstrap node configuration.
+#[derive(Clone, Debug)]
+pub struct BootstrapNodeConfig {
+    /// Protocol version for libp2p stack, should be set as genesis hash of the blockchain for
+    /// production use.
+    pub protocol_version: String,
+    /// Identity keypair of the node, when not specified, keypair is loaded from base path or
+    /// generated and stored there on first start.
+    pub keypair: Option<identity::Keypair>,
+    /// Directory for persistent identity and known peers, nothing is persisted when not specified.
+    pub base_path: Option<PathBuf>,
+    /// Where bootstrap node will listen for incoming connections.
+    pub listen_on: Vec<Multiaddr>,
+    /// Other bootstrap nodes to connect to on startup.
+    pub bootstrap_nodes: Vec<Multiaddr>,
+    /// Reserved peers to maintain connections to.
+    pub reserved_peers: Vec<Multiaddr>,
+    /// Peers (multiaddresses with `/p2p/` suffix) that are always allowed to connect regardless of
+    /// connection limits.
+    pub allowlist: Vec<Multiaddr>,
+    /// Known external addresses.
+    pub external_addresses: Vec<Multiaddr>,
+    /// Determines whether we allow keeping non-global (private, shared, loopback..) addresses in
+    /// Kademlia DHT.
+    pub allow_non_global_addresses_in_dht: bool,
+    /// Defines max established incoming swarm connection limit.
+    pub max_in_connections: u32,
+    /// Defines max established outgoing swarm connection limit.
+    pub max_out_connections: u32,
+    /// Defines max pending incoming swarm connection limit.
+    pub max_pending_in_connections: u32,
+    /// Defines max pending outgoing swarm connection limit.
+    pub max_pending_out_connections: u32,
+}
+
+/// Bootstrap node instance, created with [`BootstrapNode::new()`] and driven by
+/// [`BootstrapNode::run()`].
+#[derive(Debug)]
+pub struct BootstrapNode {
+    node: Node,
+    node_runner: NodeRunner<()>,
+    allowlist: HashMap<PeerId, Vec<IpAddr>>,
+}
+
+impl BootstrapNode {
+    /// Create new bootstrap node, metrics are registered in `prometheus_registry` if provided.
+    pub fn new(
+        config: BootstrapNodeConfig,
+        prometheus_registry: Option<&mut Registry>,
+    ) -> Result<Self, BootstrapNodeError> {
+        let BootstrapNodeConfig {
+            protocol_version,
+            keypair,
+            base_path,
+            listen_on,
+            bootstrap_nodes,
+            reserved_peers,
+            allowlist,
+            external_addresses,
+            allow_non_global_addresses_in_dht,
+            max_in_connections,
+            max_out_connections,
+            max_pending_in_connections,
+            max_pending_out_connections,
+        } = config;
+
+        let allowlist = parse_allowlist(allowlist)?;
+
+        if let Some(base_path) = &base_path {
+            fs::create_dir_all(base_path)?;
+        }
+
+        let keypair = match (keypair, &base_path) {
+            (Some(keypair), _) => keypair,
+            (None, Some(base_path)) => load_or_create_keypair(
+                &base_path.join(KEYPAIR_FILE),
+                &TempFileManager::open(base_path)?,
+            )?,
+            (None, None) => ed25519::Keypair::generate().into(),
+        };
+
+        let known_peers_manager = KnownPeersManager::new(KnownPeersManagerConfig {
+            // Known peers are only useful as a source after restart when they are persisted
+            enable_known_peers_source: base_path.is_some(),
+            cache_size: KNOWN_PEERS_CACHE_SIZE,
+            ignore_peer_list: strip_peer_id(bootstrap_nodes.clone())
+                .into_iter()
+                .map(|(peer_id, _)| peer_id)
+                .collect::<HashSet<_>>(),
+            path: base_path.map(|base_path| base_path.join(KNOWN_PEERS_FILE).into_boxed_path()),
+            failed_address_kademlia_removal_interval: REMOVE_KNOWN_PEERS_GRACE_PERIOD_FOR_KADEMLIA,
+            failed_address_cache_removal_interval: REMOVE_KNOWN_PEERS_GRACE_PERIOD_FOR_KADEMLIA,
+        })?;
+
+        debug!(%protocol_version, "Creating bootstrap node");
+
+        let config = Config {
+            listen_on,
+            allow_non_global_addresses_in_dht,
+            reserved_peers,
+            max_established_incoming_connections: max_in_connections,
+            max_established_outgoing_connections: max_out_connections,
+            max_pending_incoming_connections: max_pending_in_connections,
+            max_pending_outgoing_connections: max_pending_out_connections,
+            bootstrap_addresses: bootstrap_nodes,
+            kademlia_mode: KademliaMode::Static(Mode::Server),
+            external_addresses,
+            networking_parameters_registry: known_peers_manager.boxed(),
+            ..Config::new(protocol_version, keypair, (), prometheus_registry)
+        };
+        let (node, node_runner) = construct(config)?;
+
+        Ok(Self {
+            node,
+            node_runner,
+            allowlist,
+        })
+    }
+
+    /// Node handle, can be used to subscribe to events or query node state.
+    pub fn node(&self) -> &Node {
+        &self.node
+    }
+
+    /// Run bootstrap node until networking stack stops.
+    pub async fn run(self) {
+        let Self {
+            node,
+            mut node_runner,
+            allowlist,
+        } = self;
+
+        // Allowlist is applied through the node handle, which requires node runner to be running
+        let apply_allowlist = async move {
+            for (peer_id, ip_addresses) in allowlist {
+                if let Err(error) = node.add_preferred_peer(peer_id, ip_addresses).await {
+                    warn!(%peer_id, %error, "Failed to add allowlisted peer");
+                }
+            }
+        };
+
+        join(node_runner.run(), apply_allowlist).await;
+    }
+}
+
+/// Load ed25519 keypair from `path` or generate a new one and store it there if file doesn't
+/// exist yet, new keypair is written through `temp_files` so that crash never leaves a truncated
+/// keypair behind.
+pub fn load_or_create_keypair(
+    path: &Path,
+    temp_files: &TempFileManager,
+) -> Result<identity::Keypair, BootstrapNodeError> {
+    match fs::read(path) {
+        Ok(mut bytes) => Ok(ed25519::Keypair::try_from_bytes(&mut bytes)?.into()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            let keypair = ed25519::Keypair::generate();
+            temp_files.write_atomically(path, &keypair.to_bytes())?;
+
+            Ok(keypair.into())
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Collect IP addresses allowed for each peer in the allowlist.
+fn parse_allowlist(
+    allowlist: Vec<Multiaddr>,
+) -> Result<HashMap<PeerId, Vec<IpAddr>>, BootstrapNodeError> {
+    let mut peers = HashMap::<PeerId, Vec<IpAddr>>::new();
+
+    for address in allowlist {
+        let mut peer_id = None;
+        let mut ip_addresses = Vec::new();
+
+        for protocol in address.iter() {
+            match protocol {
+                Protocol::P2p(id) => {
+                    peer_id.replace(id);
+                }
+                Protocol::Ip4(ip) => {
+                    ip_addresses.push(IpAddr::V4(ip));
+                }
+                Protocol::Ip6(ip) => {
+                    ip_addresses.push(IpAddr::V6(ip));
+                }
+                _ => {}
+            }
+        }
+
+        let Some(peer_id) = peer_id else {
+            return Err(BootstrapNodeError::AllowlistAddressWithoutPeerId(address));
+        };
+
+        peers.entry(peer_id).or_default().extend(ip_addresses);
+    }
+
+    Ok(peers)
+}
@@ -0,0 +1,34 @@
+use crate::bootstrap_node::{parse_allowlist, BootstrapNodeError};
+use libp2p::identity::ed25519;
+use libp2p::multiaddr::Protocol;
+use libp2p::{identity, Multiaddr, PeerId};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[test]
+fn allowlist_parsing() {
+    let peer_id = PeerId::from(identity::Keypair::from(ed25519::Keypair::generate()).public());
+    let ipv4 = Ipv4Addr::new(1, 2, 3, 4);
+    let ipv6 = Ipv6Addr::new(1, 2, 3, 4, 5, 6, 7, 8);
+
+    let allowlist = parse_allowlist(vec![
+        Multiaddr::from(ipv4)
+            .with(Protocol::Tcp(30433))
+            .with(Protocol::P2p(peer_id)),
+        Multiaddr::from(ipv6)
+            .with(Protocol::Udp(30433))
+            .with(Protocol::QuicV1)
+            .with(Protocol::P2p(peer_id)),
+    ])
+    .unwrap();
+    assert_eq!(allowlist.len(), 1);
+    assert_eq!(
+        allowlist.get(&peer_id).unwrap(),
+        &vec![IpAddr::V4(ipv4), IpAddr::V6(ipv6)]
+    );
+
+    // Peer ID is required
+    assert!(matches!(
+        parse_allowlist(vec![Multiaddr::from(ipv4).with(Protocol::Tcp(30433))]),
+        Err(BootstrapNodeError::AllowlistAddressWithoutPeerId(_))
+    ));
+}
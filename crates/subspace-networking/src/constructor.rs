@@ -11,6 +11,7 @@ use crate::protocols::autonat_wrapper::Config as AutonatWrapperConfig;
 use crate::protocols::request_response::request_response_factory::RequestHandler;
 use crate::protocols::reserved_peers::Config as ReservedPeersConfig;
 use crate::shared::Shared;
+use crate::utils::bandwidth::BandwidthAccounting;
 use crate::utils::rate_limiter::RateLimiter;
 use crate::utils::{strip_peer_id, SubspaceMetrics};
 use backoff::{ExponentialBackoff, SystemClock};
@@ -42,7 +43,7 @@ use std::time::{Duration, Instant};
 use std::{fmt, io, iter};
 use subspace_core_primitives::{crypto, Piece};
 use thiserror::Error;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 const DEFAULT_NETWORK_PROTOCOL_VERSION: &str = "dev";
 const KADEMLIA_PROTOCOL: &str = "/subspace/kad/0.1.0";
@@ -92,6 +93,8 @@ const YAMUX_BUFFER_SIZE: usize = Piece::SIZE + 1024 * 1024;
 pub(crate) const AUTONAT_MAX_CONFIDENCE: usize = 3;
 /// We set a very long pause before autonat initialization (Duration::Max panics).
 const AUTONAT_SERVER_PROBE_DELAY: Duration = Duration::from_secs(3600 * 24 * 365);
+/// Number of distinct peers that need to observe the same IP address for it to become external
+const EXTERNAL_ADDRESS_VOTES_THRESHOLD: NonZeroUsize = NonZeroUsize::new(3).expect("Not zero; qed");
 
 /// Defines Kademlia mode
 #[derive(Clone, Debug)]
@@ -245,8 +248,16 @@ pub struct Config<LocalRecordProvider> {
     /// Known external addresses to the local peer. The addresses will be added on the swarm start
     /// and enable peer to notify others about its reachable address.
     pub external_addresses: Vec<Multiaddr>,
+    /// How many distinct peers need to observe the same IP address before it is considered public
+    /// and advertised as external address (derived from listen addresses), `None` disables
+    /// discovery of external addresses from observed addresses.
+    pub external_address_votes_threshold: Option<NonZeroUsize>,
     /// Defines whether we should run blocking Kademlia bootstrap() operation before other requests.
     pub disable_bootstrap_on_start: bool,
+    /// Client-only operation for resource-constrained consumers (light gateways, mobile): Kademlia
+    /// is forced into client mode, node doesn't listen for incoming connections and doesn't serve
+    /// request-response protocols, while outgoing requests work as usual.
+    pub client_only: bool,
 }
 
 impl<LocalRecordProvider> fmt::Debug for Config<LocalRecordProvider> {
@@ -368,7 +379,9 @@ where
             bootstrap_addresses: Vec::new(),
             kademlia_mode: KademliaMode::Static(Mode::Client),
             external_addresses: Vec::new(),
+            external_address_votes_threshold: Some(EXTERNAL_ADDRESS_VOTES_THRESHOLD),
             disable_bootstrap_on_start: false,
+            client_only: false,
         }
     }
 }
@@ -432,7 +445,9 @@ where
         bootstrap_addresses,
         kademlia_mode,
         external_addresses,
+        external_address_votes_threshold,
         disable_bootstrap_on_start,
+        client_only,
     } = config;
     let local_peer_id = peer_id(&keypair);
 
@@ -440,9 +455,24 @@ where
         %allow_non_global_addresses_in_dht,
         peer_id = %local_peer_id,
         %protocol_version,
+        %client_only,
         "DSN instance configured."
     );
 
+    let (kademlia_mode, listen_on) = if client_only {
+        if !listen_on.is_empty() || !matches!(kademlia_mode, KademliaMode::Static(Mode::Client)) {
+            warn!(
+                ?kademlia_mode,
+                ?listen_on,
+                "Kademlia mode and listen addresses are ignored in client-only mode"
+            );
+        }
+
+        (KademliaMode::Static(Mode::Client), Vec::new())
+    } else {
+        (kademlia_mode, listen_on)
+    };
+
     let connection_limits = ConnectionLimits::default()
         .with_max_established_per_peer(SWARM_MAX_ESTABLISHED_CONNECTIONS_PER_PEER)
         .with_max_pending_incoming(Some(max_pending_incoming_connections))
@@ -472,6 +502,7 @@ where
         gossipsub,
         record_store: LocalOnlyRecordStore::new(local_records_provider),
         request_response_protocols,
+        serve_requests: !client_only,
         connection_limits,
         reserved_peers: ReservedPeersConfig {
             reserved_peers: reserved_peers.clone(),
@@ -507,6 +538,8 @@ where
         temporary_ban_backoff,
     )));
 
+    let bandwidth = BandwidthAccounting::new(metrics.as_ref().map(SubspaceMetrics::bandwidth));
+
     let mut swarm = SwarmBuilder::with_existing_identity(keypair)
         .with_tokio()
         .with_other_transport(|keypair| {
@@ -516,6 +549,7 @@ where
                 Arc::clone(&temporary_bans),
                 timeout,
                 yamux_config,
+                bandwidth.clone(),
             )?)
         })
         .map_err(|error| CreationError::TransportCreationError(error.into()))?
@@ -575,7 +609,13 @@ where
         max_pending_outgoing_connections,
     );
 
-    let shared = Arc::new(Shared::new(local_peer_id, command_sender, rate_limiter));
+    let shared = Arc::new(Shared::new(
+        local_peer_id,
+        swarm.behaviour().kademlia.mode(),
+        command_sender,
+        rate_limiter,
+        bandwidth,
+    ));
     let shared_weak = Arc::downgrade(&shared);
 
     let node = Node::new(shared);
@@ -594,6 +634,7 @@ where
         protocol_version,
         bootstrap_addresses,
         disable_bootstrap_on_start,
+        external_address_votes_threshold,
     });
 
     Ok((node, node_runner))
@@ -1,4 +1,5 @@
 use crate::constructor::temporary_bans::TemporaryBans;
+use crate::utils::bandwidth::{BandwidthAccounting, BandwidthMuxer};
 use futures::future::Either;
 use libp2p::core::multiaddr::{Multiaddr, Protocol};
 use libp2p::core::muxing::StreamMuxerBox;
@@ -26,6 +27,7 @@ pub(super) fn build_transport(
     temporary_bans: Arc<Mutex<TemporaryBans>>,
     timeout: Duration,
     yamux_config: YamuxConfig,
+    bandwidth: BandwidthAccounting,
 ) -> io::Result<Boxed<(PeerId, StreamMuxerBox)>> {
     let wrapped_tcp = {
         let tcp_config = GenTcpConfig::default().nodelay(true);
@@ -65,6 +67,10 @@ pub(super) fn build_transport(
         .map(|either, _| match either {
             Either::Left((peer_id, muxer)) => (peer_id, muxer),
             Either::Right((peer_id, muxer)) => (peer_id, muxer),
+        })
+        .map(move |(peer_id, muxer), _| {
+            let muxer = BandwidthMuxer::new(muxer, peer_id, bandwidth.clone());
+            (peer_id, StreamMuxerBox::new(muxer))
         });
 
     Ok(TokioTransport::system(quic_tcp)?.boxed())
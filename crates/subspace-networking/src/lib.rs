@@ -19,6 +19,7 @@
 #![warn(missing_docs)]
 
 mod behavior;
+pub mod bootstrap_node;
 mod constructor;
 mod node;
 mod node_runner;
@@ -48,7 +49,8 @@ pub use protocols::request_response::handlers::piece_by_index::{
 pub use protocols::request_response::handlers::segment_header::{
     SegmentHeaderBySegmentIndexesRequestHandler, SegmentHeaderRequest, SegmentHeaderResponse,
 };
-pub use shared::PeerDiscovered;
+pub use shared::{PeerDiscovered, Reachability};
+pub use utils::bandwidth::{BandwidthStats, BandwidthUsage, BANDWIDTH_WINDOW};
 pub use utils::multihash::Multihash;
 pub use utils::unique_record_binary_heap::{KeyWrapper, UniqueRecordBinaryHeap};
 pub use utils::PeerAddress;
@@ -1,6 +1,7 @@
 use crate::protocols::request_response::handlers::generic_request_handler::GenericRequest;
 use crate::protocols::request_response::request_response_factory;
-use crate::shared::{Command, CreatedSubscription, PeerDiscovered, Shared};
+use crate::shared::{Command, CreatedSubscription, PeerDiscovered, Reachability, Shared};
+use crate::utils::bandwidth::BandwidthStats;
 use crate::utils::multihash::Multihash;
 use crate::utils::HandlerFn;
 use bytes::Bytes;
@@ -9,9 +10,10 @@ use futures::channel::mpsc::SendError;
 use futures::channel::{mpsc, oneshot};
 use futures::{SinkExt, Stream, StreamExt};
 use libp2p::gossipsub::{Sha256Topic, SubscriptionError};
-use libp2p::kad::PeerRecord;
+use libp2p::kad::{Mode, PeerRecord};
 use libp2p::{Multiaddr, PeerId};
 use parity_scale_codec::Decode;
+use std::net::IpAddr;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -491,6 +493,38 @@ impl Node {
             .await
     }
 
+    /// Mark peer as preferred.
+    ///
+    /// Preferred peers are those this node is already connected to by other means (for instance,
+    /// through consensus networking stack using the same identity). Connections to and from
+    /// preferred peers bypass connection limits and peer will be dialed if its addresses are
+    /// known, which allows to reuse existing relationships instead of discovering new peers.
+    ///
+    /// Calling this again for the same peer replaces previously provided IP addresses.
+    pub async fn add_preferred_peer(
+        &self,
+        peer_id: PeerId,
+        ip_addresses: Vec<IpAddr>,
+    ) -> Result<(), SendError> {
+        self.shared
+            .command_sender
+            .clone()
+            .send(Command::AddPreferredPeer {
+                peer_id,
+                ip_addresses,
+            })
+            .await
+    }
+
+    /// Remove peer previously marked as preferred with [`Self::add_preferred_peer`].
+    pub async fn remove_preferred_peer(&self, peer_id: PeerId) -> Result<(), SendError> {
+        self.shared
+            .command_sender
+            .clone()
+            .send(Command::RemovePreferredPeer { peer_id })
+            .await
+    }
+
     /// Node's own addresses where it listens for incoming requests.
     pub fn listeners(&self) -> Vec<Multiaddr> {
         self.shared.listeners.lock().clone()
@@ -501,6 +535,32 @@ impl Node {
         self.shared.external_addresses.lock().clone()
     }
 
+    /// Current Kademlia mode, only serving nodes run Kademlia in server mode.
+    pub fn kademlia_mode(&self) -> Mode {
+        *self.shared.kademlia_mode.lock()
+    }
+
+    /// Callback is called when Kademlia mode changes (for instance, as the result of reachability
+    /// detection in case of dynamic Kademlia mode).
+    pub fn on_kademlia_mode_change(&self, callback: HandlerFn<Mode>) -> HandlerId {
+        self.shared.handlers.kademlia_mode_change.add(callback)
+    }
+
+    /// Bandwidth used by the node per libp2p protocol and per peer.
+    pub fn bandwidth_stats(&self) -> BandwidthStats {
+        self.shared.bandwidth.stats()
+    }
+
+    /// Current reachability of the node from the public network as detected by AutoNAT.
+    pub fn reachability(&self) -> Reachability {
+        self.shared.reachability.lock().clone()
+    }
+
+    /// Callback is called when reachability of the node changes.
+    pub fn on_reachability_change(&self, callback: HandlerFn<Reachability>) -> HandlerId {
+        self.shared.handlers.reachability_change.add(callback)
+    }
+
     /// Callback is called when node starts listening on new address.
     pub fn on_new_listener(&self, callback: HandlerFn<Multiaddr>) -> HandlerId {
         self.shared.handlers.new_listener.add(callback)
@@ -8,7 +8,10 @@ use crate::constructor::LocalOnlyRecordStore;
 use crate::protocols::request_response::request_response_factory::{
     Event as RequestResponseEvent, IfDisconnected,
 };
-use crate::shared::{Command, CreatedSubscription, PeerDiscovered, Shared};
+use crate::shared::{Command, CreatedSubscription, PeerDiscovered, Reachability, Shared};
+use crate::utils::observed_addresses::{
+    translate_listen_address, ObservedAddresses, PublicIpChange, VOTE_TTL,
+};
 use crate::utils::{is_global_address_or_dns, strip_peer_id, SubspaceMetrics};
 use async_mutex::Mutex as AsyncMutex;
 use bytes::Bytes;
@@ -27,6 +30,7 @@ use libp2p::kad::{
 };
 use libp2p::metrics::{Metrics, Recorder};
 use libp2p::multiaddr::Protocol;
+use libp2p::swarm::dial_opts::{DialOpts, PeerCondition};
 use libp2p::swarm::{DialError, SwarmEvent};
 use libp2p::{futures, Multiaddr, PeerId, Swarm, TransportError};
 use nohash_hasher::IntMap;
@@ -36,6 +40,7 @@ use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::fmt::Debug;
 use std::net::IpAddr;
+use std::num::NonZeroUsize;
 use std::pin::Pin;
 use std::sync::atomic::Ordering;
 use std::sync::{Arc, Weak};
@@ -43,7 +48,7 @@ use std::time::Duration;
 use tokio::sync::OwnedSemaphorePermit;
 use tokio::task::yield_now;
 use tokio::time::Sleep;
-use tracing::{debug, error, trace, warn};
+use tracing::{debug, error, info, trace, warn};
 
 enum QueryResultSender {
     Value {
@@ -129,6 +134,8 @@ where
     _address_removal_task_handler_id: Option<HandlerId>,
     /// Defines whether we should run blocking Kademlia bootstrap() operation before other requests.
     disable_bootstrap_on_start: bool,
+    /// Votes of peers for our public IP addresses, `None` if disabled
+    observed_addresses: Option<ObservedAddresses>,
 }
 
 impl<LocalRecordProvider> fmt::Debug for NodeRunner<LocalRecordProvider>
@@ -160,6 +167,7 @@ where
     pub(crate) protocol_version: String,
     pub(crate) bootstrap_addresses: Vec<Multiaddr>,
     pub(crate) disable_bootstrap_on_start: bool,
+    pub(crate) external_address_votes_threshold: Option<NonZeroUsize>,
 }
 
 impl<LocalRecordProvider> NodeRunner<LocalRecordProvider>
@@ -182,6 +190,7 @@ where
             protocol_version,
             bootstrap_addresses,
             disable_bootstrap_on_start,
+            external_address_votes_threshold,
         }: NodeRunnerConfig<LocalRecordProvider>,
     ) -> Self {
         // Setup the address removal events exchange between persistent params storage and Kademlia.
@@ -223,6 +232,8 @@ where
             removed_addresses_rx,
             _address_removal_task_handler_id: address_removal_task_handler_id,
             disable_bootstrap_on_start,
+            observed_addresses: external_address_votes_threshold
+                .map(|threshold| ObservedAddresses::new(threshold, VOTE_TTL)),
         }
     }
 
@@ -530,6 +541,10 @@ where
 
                 if num_established == 0 {
                     self.peer_ip_addresses.remove(&peer_id);
+
+                    if let Some(observed_addresses) = &mut self.observed_addresses {
+                        observed_addresses.remove_votes(&peer_id);
+                    }
                 }
                 let num_established_peer_connections = shared
                     .num_established_peer_connections
@@ -614,6 +629,54 @@ where
         }
     }
 
+    /// Count address we were observed at by the peer towards voting for our public IP address and
+    /// update external addresses if public IP changed as the result
+    fn handle_observed_address(&mut self, peer_id: PeerId, observed_address: &Multiaddr) {
+        let Some(observed_addresses) = &mut self.observed_addresses else {
+            return;
+        };
+
+        if !(self.allow_non_global_addresses_in_dht || is_global_address_or_dns(observed_address)) {
+            trace!(%peer_id, %observed_address, "Ignoring non-global observed address");
+            return;
+        }
+
+        let Some(PublicIpChange { old, new }) =
+            observed_addresses.add_vote(peer_id, observed_address)
+        else {
+            return;
+        };
+
+        info!(?old, %new, "Public IP address changed according to peers");
+
+        let listen_addresses = self.swarm.listeners().cloned().collect::<Vec<_>>();
+        if let Some(old) = old {
+            for address in listen_addresses
+                .iter()
+                .filter_map(|address| translate_listen_address(address, old))
+            {
+                debug!(%address, "Removing outdated external address");
+                self.swarm.remove_external_address(&address);
+            }
+        }
+        for address in listen_addresses
+            .iter()
+            .filter_map(|address| translate_listen_address(address, new))
+        {
+            debug!(%address, "Adding external address observed by peers");
+            self.swarm.add_external_address(address);
+        }
+
+        // Re-announce ourselves to the DHT so that peers learn the new address sooner than
+        // routing table refresh would do
+        if let Err(error) = self.swarm.behaviour_mut().kademlia.bootstrap() {
+            debug!(
+                ?error,
+                "Failed to re-publish external addresses to Kademlia"
+            );
+        }
+    }
+
     fn should_temporary_ban_on_dial_error(&self, peer_id: &PeerId, error: &DialError) -> bool {
         // TODO: Replace with banning of addresses rather peer IDs if this helps
         if true {
@@ -701,6 +764,8 @@ where
             // Remove temporary ban if there was any
             self.temporary_bans.lock().remove(&peer_id);
 
+            self.handle_observed_address(peer_id, &info.observed_addr);
+
             if info.listen_addrs.len() > 30 {
                 debug!(
                     %local_peer_id,
@@ -812,6 +877,14 @@ where
             } => {
                 debug!("Unexpected AddProvider request received: {:?}", record);
             }
+            KademliaEvent::ModeChanged { new_mode } => {
+                info!(?new_mode, "Kademlia mode changed");
+
+                if let Some(shared) = self.shared_weak.upgrade() {
+                    *shared.kademlia_mode.lock() = new_mode;
+                    shared.handlers.kademlia_mode_change.call_simple(&new_mode);
+                }
+            }
             KademliaEvent::UnroutablePeer { peer } => {
                 debug!(%peer, "Unroutable peer detected");
 
@@ -1189,6 +1262,18 @@ where
                     self.swarm.behaviour_mut().kademlia.set_mode(None);
                 }
 
+                let reachability = Reachability::from(new);
+                if let Some(metrics) = self.metrics.as_mut() {
+                    metrics.set_publicly_reachable(reachability.is_public());
+                }
+                if let Some(shared) = self.shared_weak.upgrade() {
+                    *shared.reachability.lock() = reachability.clone();
+                    shared
+                        .handlers
+                        .reachability_change
+                        .call_simple(&reachability);
+                }
+
                 let connected_peers = self.swarm.connected_peers().copied().collect::<Vec<_>>();
                 self.swarm.behaviour_mut().identify.push(connected_peers);
             }
@@ -1399,6 +1484,38 @@ where
             Command::Dial { address } => {
                 let _ = self.swarm.dial(address);
             }
+            Command::AddPreferredPeer {
+                peer_id,
+                ip_addresses,
+            } => {
+                let connection_limits = &mut self.swarm.behaviour_mut().connection_limits;
+                // Replace previous entry (if any), preferred peers bypass limits for as long as
+                // they stay preferred
+                connection_limits.remove_from_incoming_allow_list(&peer_id, None);
+                connection_limits.remove_from_outgoing_allow_list(&peer_id);
+                connection_limits.add_to_incoming_allow_list(
+                    peer_id,
+                    ip_addresses.into_iter(),
+                    usize::MAX,
+                );
+                connection_limits.add_to_outgoing_allow_list(peer_id, usize::MAX);
+
+                if !self.swarm.is_connected(&peer_id) {
+                    // Addresses are provided by behaviours (Kademlia) if known, otherwise dialing
+                    // fails and we'll wait for preferred peer to connect to us instead
+                    let dial_opts = DialOpts::peer_id(peer_id)
+                        .condition(PeerCondition::DisconnectedAndNotDialing)
+                        .build();
+                    if let Err(error) = self.swarm.dial(dial_opts) {
+                        debug!(%peer_id, %error, "Failed to dial preferred peer");
+                    }
+                }
+            }
+            Command::RemovePreferredPeer { peer_id } => {
+                let connection_limits = &mut self.swarm.behaviour_mut().connection_limits;
+                connection_limits.remove_from_incoming_allow_list(&peer_id, None);
+                connection_limits.remove_from_outgoing_allow_list(&peer_id);
+            }
             Command::ConnectedPeers { result_sender } => {
                 let connected_peers = self.swarm.connected_peers().cloned().collect();
 
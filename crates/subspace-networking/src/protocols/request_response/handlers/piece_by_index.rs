@@ -4,14 +4,46 @@
 //! `RequestResponsesBehaviour` with generic [`GenericRequestHandler`].
 
 use super::generic_request_handler::{GenericRequest, GenericRequestHandler};
-use parity_scale_codec::{Decode, Encode};
+use crate::utils::piece_request_tickets::PieceRequestTicket;
+use parity_scale_codec::{Decode, Encode, Input};
 use subspace_core_primitives::{Piece, PieceIndex};
 
 /// Piece-by-hash protocol request.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Encode, Decode)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Encode)]
 pub struct PieceByIndexRequest {
     /// Request key - piece index
     pub piece_index: PieceIndex,
+    /// Optional ticket that allows request to be served with priority, see
+    /// [`crate::utils::piece_request_tickets`] for details
+    pub ticket: Option<PieceRequestTicket>,
+}
+
+// Manual implementation such that requests of peers that don't know about tickets yet (and thus
+// don't send the last field at all) can still be decoded
+impl Decode for PieceByIndexRequest {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        let piece_index = PieceIndex::decode(input)?;
+        let ticket = if input.remaining_len()? == Some(0) {
+            None
+        } else {
+            Option::<PieceRequestTicket>::decode(input)?
+        };
+
+        Ok(Self {
+            piece_index,
+            ticket,
+        })
+    }
+}
+
+impl PieceByIndexRequest {
+    /// Create request without ticket
+    pub fn new(piece_index: PieceIndex) -> Self {
+        Self {
+            piece_index,
+            ticket: None,
+        }
+    }
 }
 
 impl GenericRequest for PieceByIndexRequest {
@@ -319,11 +319,29 @@ impl RequestResponseFactoryBehaviour {
     /// the same protocol is passed twice.
     pub fn new(
         list: impl IntoIterator<Item = Box<dyn RequestHandler>>,
+    ) -> Result<Self, RegisterError> {
+        Self::new_inner(list, true)
+    }
+
+    /// Creates a new behaviour that only supports outbound requests, inbound queues of the
+    /// protocols are ignored and request handlers are not run.
+    pub fn new_outbound_only(
+        list: impl IntoIterator<Item = Box<dyn RequestHandler>>,
+    ) -> Result<Self, RegisterError> {
+        Self::new_inner(list, false)
+    }
+
+    fn new_inner(
+        list: impl IntoIterator<Item = Box<dyn RequestHandler>>,
+        serve_inbound: bool,
     ) -> Result<Self, RegisterError> {
         let mut protocols = HashMap::new();
         let mut request_handlers = Vec::new();
         for mut handler in list {
-            let config = handler.protocol_config();
+            let mut config = handler.protocol_config();
+            if !serve_inbound {
+                config.inbound_queue.take();
+            }
 
             let protocol_support = if config.inbound_queue.is_some() {
                 ProtocolSupport::Full
@@ -347,10 +365,12 @@ impl RequestResponseFactoryBehaviour {
                 }
             };
 
-            let request_handler_run: Pin<Box<dyn Future<Output = ()> + Send>> =
-                Box::pin(async move { handler.run().await }.fuse());
+            if serve_inbound {
+                let request_handler_run: Pin<Box<dyn Future<Output = ()> + Send>> =
+                    Box::pin(async move { handler.run().await }.fuse());
 
-            request_handlers.push(request_handler_run);
+                request_handlers.push(request_handler_run);
+            }
         }
 
         Ok(Self {
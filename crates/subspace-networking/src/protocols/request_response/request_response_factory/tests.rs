@@ -36,12 +36,23 @@ impl RequestHandler for MockRunner {
 
 async fn build_swarm(
     list: impl Iterator<Item = ProtocolConfig>,
+) -> Swarm<RequestResponseFactoryBehaviour> {
+    build_swarm_inner(list, true).await
+}
+
+async fn build_swarm_inner(
+    list: impl Iterator<Item = ProtocolConfig>,
+    serve_inbound: bool,
 ) -> Swarm<RequestResponseFactoryBehaviour> {
     let configs = list
         .into_iter()
         .map(|config| Box::new(MockRunner(config)) as Box<dyn RequestHandler>)
         .collect::<Vec<_>>();
-    let behaviour = RequestResponseFactoryBehaviour::new(configs).unwrap();
+    let behaviour = if serve_inbound {
+        RequestResponseFactoryBehaviour::new(configs).unwrap()
+    } else {
+        RequestResponseFactoryBehaviour::new_outbound_only(configs).unwrap()
+    };
 
     let mut swarm = SwarmBuilder::with_new_identity()
         .with_tokio()
@@ -226,6 +237,68 @@ async fn max_response_size_exceeded() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn outbound_only_does_not_serve_requests() {
+    let protocol_name = "/test/req-resp/1";
+
+    let (tx, mut rx) = mpsc::channel::<IncomingRequest>(64);
+    let mut swarm_0 = build_swarm_inner(
+        iter::once(ProtocolConfig {
+            name: protocol_name,
+            max_request_size: 1024,
+            max_response_size: 1024 * 1024,
+            request_timeout: Duration::from_secs(30),
+            inbound_queue: Some(tx),
+        }),
+        false,
+    )
+    .await;
+    let mut swarm_1 = build_swarm(iter::once(ProtocolConfig {
+        name: protocol_name,
+        max_request_size: 1024,
+        max_response_size: 1024 * 1024,
+        request_timeout: Duration::from_secs(30),
+        inbound_queue: None,
+    }))
+    .await;
+
+    swarm_1.connect(&mut swarm_0).await;
+
+    let peer_id_0 = *swarm_0.local_peer_id();
+
+    tokio::spawn(async move {
+        loop {
+            swarm_0.select_next_some().await;
+        }
+    });
+
+    // Outbound-only swarm doesn't advertise the protocol, so the request must be rejected
+    let (sender, receiver) = oneshot::channel();
+    swarm_1.behaviour_mut().send_request(
+        &peer_id_0,
+        protocol_name,
+        b"this is a request".to_vec(),
+        sender,
+        IfDisconnected::ImmediateError,
+    );
+    loop {
+        if let SwarmEvent::Behaviour(Event::RequestFinished { result, .. }) =
+            swarm_1.select_next_some().await
+        {
+            assert!(result.is_err());
+            break;
+        }
+    }
+    assert!(matches!(
+        receiver.await.unwrap(),
+        Err(RequestFailure::Network(
+            OutboundFailure::UnsupportedProtocols
+        ))
+    ));
+    // Inbound queue was dropped, nothing can ever arrive
+    assert!(rx.next().await.is_none());
+}
+
 /// A [`RequestId`] is a unique identifier among either all inbound or all outbound requests for
 /// a single [`RequestResponse`] behaviour. It is not guaranteed to be unique across multiple
 /// [`RequestResponse`] behaviours. Thus when handling [`RequestId`] in the context of multiple
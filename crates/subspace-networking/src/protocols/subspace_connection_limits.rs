@@ -70,6 +70,17 @@ impl Behaviour {
             self.incoming_allow_list.remove(peer);
         }
     }
+
+    /// Add to allow list some attempts of outgoing connections to specified peer ID that will bypass global limits
+    pub(crate) fn add_to_outgoing_allow_list(&mut self, peer: PeerId, add_attempts: usize) {
+        let attempts = self.outgoing_allow_list.entry(peer).or_default();
+        *attempts = attempts.saturating_add(add_attempts);
+    }
+
+    /// Remove all attempts of outgoing connections to specified peer ID
+    pub(crate) fn remove_from_outgoing_allow_list(&mut self, peer: &PeerId) {
+        self.outgoing_allow_list.remove(peer);
+    }
 }
 
 impl NetworkBehaviour for Behaviour {
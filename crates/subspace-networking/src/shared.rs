@@ -2,15 +2,18 @@
 //! queries, subscriptions, various events and shared information.
 
 use crate::protocols::request_response::request_response_factory::RequestFailure;
+use crate::utils::bandwidth::BandwidthAccounting;
 use crate::utils::multihash::Multihash;
 use crate::utils::rate_limiter::RateLimiter;
 use crate::utils::Handler;
 use bytes::Bytes;
 use futures::channel::{mpsc, oneshot};
+use libp2p::autonat::NatStatus;
 use libp2p::gossipsub::{PublishError, Sha256Topic, SubscriptionError};
-use libp2p::kad::PeerRecord;
+use libp2p::kad::{Mode, PeerRecord};
 use libp2p::{Multiaddr, PeerId};
 use parking_lot::Mutex;
+use std::net::IpAddr;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 use tokio::sync::OwnedSemaphorePermit;
@@ -43,6 +46,45 @@ impl PeerDiscovered {
     }
 }
 
+/// Reachability of the node from the public network, as detected by AutoNAT probes performed by
+/// other peers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Reachability {
+    /// Not enough probes were done yet to determine reachability
+    #[default]
+    Unknown,
+    /// Node is publicly reachable
+    Public {
+        /// Address at which node was confirmed to be reachable, also determines transport that
+        /// works for inbound connections
+        address: Multiaddr,
+    },
+    /// Node is not publicly reachable (for instance, it is behind NAT or firewall)
+    Private,
+}
+
+impl From<NatStatus> for Reachability {
+    fn from(nat_status: NatStatus) -> Self {
+        match nat_status {
+            NatStatus::Public(address) => Self::Public { address },
+            NatStatus::Private => Self::Private,
+            NatStatus::Unknown => Self::Unknown,
+        }
+    }
+}
+
+impl Reachability {
+    /// Whether node was confirmed to be publicly reachable
+    pub fn is_public(&self) -> bool {
+        matches!(self, Self::Public { .. })
+    }
+
+    /// Whether node was confirmed to be not publicly reachable
+    pub fn is_private(&self) -> bool {
+        matches!(self, Self::Private)
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct CreatedSubscription {
     /// Subscription ID to be used for unsubscribing.
@@ -99,6 +141,13 @@ pub(crate) enum Command {
     Dial {
         address: Multiaddr,
     },
+    AddPreferredPeer {
+        peer_id: PeerId,
+        ip_addresses: Vec<IpAddr>,
+    },
+    RemovePreferredPeer {
+        peer_id: PeerId,
+    },
     ConnectedPeers {
         result_sender: oneshot::Sender<Vec<PeerId>>,
     },
@@ -114,6 +163,8 @@ pub(crate) struct Handlers {
     pub(crate) disconnected_peer: Handler<PeerId>,
     pub(crate) connected_peer: Handler<PeerId>,
     pub(crate) peer_discovered: Handler<PeerDiscovered>,
+    pub(crate) kademlia_mode_change: Handler<Mode>,
+    pub(crate) reachability_change: Handler<Reachability>,
 }
 
 #[derive(Debug)]
@@ -123,26 +174,37 @@ pub(crate) struct Shared {
     /// Addresses on which node is listening for incoming requests.
     pub(crate) listeners: Mutex<Vec<Multiaddr>>,
     pub(crate) external_addresses: Mutex<Vec<Multiaddr>>,
+    /// Current Kademlia mode, changes over time in case of dynamic Kademlia mode.
+    pub(crate) kademlia_mode: Mutex<Mode>,
+    /// Current reachability of the node as detected by AutoNAT.
+    pub(crate) reachability: Mutex<Reachability>,
     pub(crate) num_established_peer_connections: Arc<AtomicUsize>,
     /// Sender end of the channel for sending commands to the swarm.
     pub(crate) command_sender: mpsc::Sender<Command>,
     pub(crate) rate_limiter: RateLimiter,
+    /// Bandwidth used by the node per protocol and per peer.
+    pub(crate) bandwidth: BandwidthAccounting,
 }
 
 impl Shared {
     pub(crate) fn new(
         id: PeerId,
+        kademlia_mode: Mode,
         command_sender: mpsc::Sender<Command>,
         rate_limiter: RateLimiter,
+        bandwidth: BandwidthAccounting,
     ) -> Self {
         Self {
             handlers: Handlers::default(),
             id,
             listeners: Mutex::default(),
             external_addresses: Mutex::default(),
+            kademlia_mode: Mutex::new(kademlia_mode),
+            reachability: Mutex::default(),
             num_established_peer_connections: Arc::new(AtomicUsize::new(0)),
             command_sender,
             rate_limiter,
+            bandwidth,
         }
     }
 }
@@ -1,16 +1,22 @@
 //! Miscellaneous utilities for networking.
 
+pub mod bandwidth;
 pub mod multihash;
+pub(crate) mod observed_addresses;
 pub mod piece_provider;
+pub mod piece_request_tickets;
 pub(crate) mod rate_limiter;
 #[cfg(test)]
 mod tests;
 pub(crate) mod unique_record_binary_heap;
 
+use crate::utils::bandwidth::BandwidthLabels;
 use event_listener_primitives::Bag;
 use futures::future::{Fuse, FusedFuture, FutureExt};
 use libp2p::multiaddr::Protocol;
 use libp2p::{Multiaddr, PeerId};
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
 use prometheus_client::metrics::gauge::Gauge;
 use prometheus_client::registry::Registry;
 use std::future::Future;
@@ -19,21 +25,22 @@ use std::num::NonZeroUsize;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use subspace_metrics::MetricsComponent;
 use tokio::runtime::Handle;
 use tokio::task;
 use tracing::warn;
 
-const NETWORKING_REGISTRY_PREFIX: &str = "subspace";
-
 /// Metrics for Subspace networking
 pub struct SubspaceMetrics {
     established_connections: Gauge,
+    publicly_reachable: Gauge,
+    bandwidth: Family<BandwidthLabels, Counter>,
 }
 
 impl SubspaceMetrics {
     /// Constructor
     pub fn new(registry: &mut Registry) -> Self {
-        let sub_registry = registry.sub_registry_with_prefix(NETWORKING_REGISTRY_PREFIX);
+        let sub_registry = MetricsComponent::Dsn.sub_registry(registry);
 
         let gauge = Gauge::default();
         sub_registry.register(
@@ -42,8 +49,24 @@ impl SubspaceMetrics {
             gauge.clone(),
         );
 
+        let publicly_reachable = Gauge::default();
+        sub_registry.register(
+            "publicly_reachable",
+            "Whether node is publicly reachable according to AutoNAT (1) or not (0)",
+            publicly_reachable.clone(),
+        );
+
+        let bandwidth = Family::default();
+        sub_registry.register(
+            "bandwidth_bytes",
+            "Bytes transferred by libp2p protocol and direction",
+            bandwidth.clone(),
+        );
+
         Self {
             established_connections: gauge,
+            publicly_reachable,
+            bandwidth,
         }
     }
 
@@ -54,6 +77,14 @@ impl SubspaceMetrics {
     pub(crate) fn dec_established_connections(&mut self) {
         self.established_connections.dec();
     }
+
+    pub(crate) fn set_publicly_reachable(&mut self, publicly_reachable: bool) {
+        self.publicly_reachable.set(i64::from(publicly_reachable));
+    }
+
+    pub(crate) fn bandwidth(&self) -> Family<BandwidthLabels, Counter> {
+        self.bandwidth.clone()
+    }
 }
 
 /// Joins async join handle on drop
@@ -0,0 +1,624 @@
+//! Accounting of bandwidth used by DSN, per libp2p protocol and per peer.
+//!
+//! Every substream is wrapped by [`BandwidthMuxer`], which counts bytes that go through it.
+//! Protocol of the substream is determined by sniffing multistream-select negotiation at the
+//! beginning of the substream: protocol is confirmed once the same protocol name was seen in both
+//! directions (proposed by dialer and echoed by listener). Bytes transferred before confirmation
+//! are attributed to the protocol retroactively, substreams that never complete negotiation are
+//! attributed to [`UNKNOWN_PROTOCOL`].
+//!
+//! Only substream payload is counted, transport overhead (noise, yamux, QUIC framing) is not.
+
+#[cfg(test)]
+mod tests;
+
+use futures::{AsyncRead, AsyncWrite};
+use libp2p::core::muxing::{StreamMuxer, StreamMuxerBox, StreamMuxerEvent, SubstreamBox};
+use libp2p::PeerId;
+use parking_lot::Mutex;
+use prometheus_client::encoding::{EncodeLabelSet, EncodeLabelValue};
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io;
+use std::io::{IoSlice, IoSliceMut};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Protocol name substreams that didn't complete protocol negotiation are attributed to.
+pub const UNKNOWN_PROTOCOL: &str = "unknown";
+/// Duration of the rolling window bandwidth usage is reported for in addition to totals.
+pub const BANDWIDTH_WINDOW: Duration = Duration::from_secs(5 * 60);
+/// Granularity of the rolling window.
+const BUCKET_DURATION: Duration = Duration::from_secs(10);
+const BUCKETS_IN_WINDOW: u64 = BANDWIDTH_WINDOW.as_secs() / BUCKET_DURATION.as_secs();
+/// Multistream-select header, exchanged by both sides before protocol names.
+const MULTISTREAM_HEADER: &[u8] = b"/multistream/1.0.0";
+/// Negotiation is expected to complete within this many bytes in each direction, sniffing stops
+/// afterwards.
+const MAX_SNIFFED_BYTES: usize = 1024;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, EncodeLabelValue)]
+pub(crate) enum Direction {
+    Inbound,
+    Outbound,
+}
+
+/// Labels of bandwidth metric.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, EncodeLabelSet)]
+pub(crate) struct BandwidthLabels {
+    protocol: String,
+    direction: Direction,
+}
+
+/// Bandwidth usage of a protocol, a peer or the node as a whole.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct BandwidthUsage {
+    /// Bytes sent since node start
+    pub sent_total: u64,
+    /// Bytes received since node start
+    pub received_total: u64,
+    /// Bytes sent during last [`BANDWIDTH_WINDOW`]
+    pub sent_in_window: u64,
+    /// Bytes received during last [`BANDWIDTH_WINDOW`]
+    pub received_in_window: u64,
+}
+
+/// Snapshot of bandwidth usage.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct BandwidthStats {
+    /// Bandwidth usage of all protocols combined
+    pub total: BandwidthUsage,
+    /// Bandwidth usage per libp2p protocol
+    pub per_protocol: BTreeMap<String, BandwidthUsage>,
+    /// Bandwidth usage per peer, only includes peers that were active during last
+    /// [`BANDWIDTH_WINDOW`] (totals are since peer became active)
+    pub per_peer: HashMap<PeerId, BandwidthUsage>,
+}
+
+/// Total counter along with counts in buckets of the rolling window.
+#[derive(Debug, Default)]
+struct RollingCounter {
+    total: u64,
+    /// Bucket index and bytes in the bucket, oldest first
+    buckets: VecDeque<(u64, u64)>,
+}
+
+impl RollingCounter {
+    fn add(&mut self, bucket: u64, bytes: u64) {
+        self.total += bytes;
+
+        match self.buckets.back_mut() {
+            Some((last_bucket, last_bytes)) if *last_bucket == bucket => {
+                *last_bytes += bytes;
+            }
+            _ => {
+                self.buckets.push_back((bucket, bytes));
+            }
+        }
+
+        self.prune(bucket);
+    }
+
+    fn prune(&mut self, bucket: u64) {
+        while let Some((oldest_bucket, _bytes)) = self.buckets.front() {
+            if oldest_bucket + BUCKETS_IN_WINDOW > bucket {
+                break;
+            }
+            self.buckets.pop_front();
+        }
+    }
+
+    fn in_window(&self, bucket: u64) -> u64 {
+        self.buckets
+            .iter()
+            .filter(|(counter_bucket, _bytes)| counter_bucket + BUCKETS_IN_WINDOW > bucket)
+            .map(|(_bucket, bytes)| bytes)
+            .sum()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    sent: RollingCounter,
+    received: RollingCounter,
+}
+
+impl Counters {
+    fn add(&mut self, direction: Direction, bucket: u64, bytes: u64) {
+        match direction {
+            Direction::Inbound => self.received.add(bucket, bytes),
+            Direction::Outbound => self.sent.add(bucket, bytes),
+        }
+    }
+
+    fn usage(&self, bucket: u64) -> BandwidthUsage {
+        BandwidthUsage {
+            sent_total: self.sent.total,
+            received_total: self.received.total,
+            sent_in_window: self.sent.in_window(bucket),
+            received_in_window: self.received.in_window(bucket),
+        }
+    }
+
+    fn is_idle(&mut self, bucket: u64) -> bool {
+        self.sent.prune(bucket);
+        self.received.prune(bucket);
+        self.sent.buckets.is_empty() && self.received.buckets.is_empty()
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    started: Instant,
+    total: Counters,
+    per_protocol: BTreeMap<String, Counters>,
+    per_peer: HashMap<PeerId, Counters>,
+    last_pruned_bucket: u64,
+}
+
+impl Inner {
+    fn bucket(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.started).as_secs() / BUCKET_DURATION.as_secs()
+    }
+
+    fn prune_peers(&mut self, bucket: u64) {
+        self.per_peer
+            .retain(|_peer_id, counters| !counters.is_idle(bucket));
+        self.last_pruned_bucket = bucket;
+    }
+}
+
+/// Bandwidth accounting shared between all connections of the node.
+#[derive(Debug, Clone)]
+pub struct BandwidthAccounting {
+    inner: Arc<Mutex<Inner>>,
+    metric: Option<Family<BandwidthLabels, Counter>>,
+}
+
+impl Default for BandwidthAccounting {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl BandwidthAccounting {
+    pub(crate) fn new(metric: Option<Family<BandwidthLabels, Counter>>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                started: Instant::now(),
+                total: Counters::default(),
+                per_protocol: BTreeMap::new(),
+                per_peer: HashMap::new(),
+                last_pruned_bucket: 0,
+            })),
+            metric,
+        }
+    }
+
+    /// Snapshot of current bandwidth usage.
+    pub fn stats(&self) -> BandwidthStats {
+        self.stats_at(Instant::now())
+    }
+
+    fn stats_at(&self, now: Instant) -> BandwidthStats {
+        let mut inner = self.inner.lock();
+        let bucket = inner.bucket(now);
+        inner.prune_peers(bucket);
+
+        BandwidthStats {
+            total: inner.total.usage(bucket),
+            per_protocol: inner
+                .per_protocol
+                .iter()
+                .map(|(protocol, counters)| (protocol.clone(), counters.usage(bucket)))
+                .collect(),
+            per_peer: inner
+                .per_peer
+                .iter()
+                .map(|(peer_id, counters)| (*peer_id, counters.usage(bucket)))
+                .collect(),
+        }
+    }
+
+    /// Record bytes transferred with a peer, protocol is `None` if not known yet, in which case
+    /// bytes must be attributed to protocol later with [`Self::record_protocol()`].
+    fn record(
+        &self,
+        peer_id: &PeerId,
+        protocol: Option<&str>,
+        direction: Direction,
+        bytes: u64,
+        now: Instant,
+    ) {
+        if bytes == 0 {
+            return;
+        }
+
+        {
+            let mut inner = self.inner.lock();
+            let bucket = inner.bucket(now);
+
+            if bucket != inner.last_pruned_bucket {
+                inner.prune_peers(bucket);
+            }
+
+            inner.total.add(direction, bucket, bytes);
+            inner
+                .per_peer
+                .entry(*peer_id)
+                .or_default()
+                .add(direction, bucket, bytes);
+            if let Some(protocol) = protocol {
+                Self::add_protocol(&mut inner, protocol, direction, bucket, bytes);
+            }
+        }
+
+        if let Some(protocol) = protocol {
+            self.inc_metric(protocol, direction, bytes);
+        }
+    }
+
+    /// Attribute previously recorded bytes to a protocol.
+    fn record_protocol(&self, protocol: &str, direction: Direction, bytes: u64, now: Instant) {
+        if bytes == 0 {
+            return;
+        }
+
+        {
+            let mut inner = self.inner.lock();
+            let bucket = inner.bucket(now);
+            Self::add_protocol(&mut inner, protocol, direction, bucket, bytes);
+        }
+
+        self.inc_metric(protocol, direction, bytes);
+    }
+
+    fn add_protocol(
+        inner: &mut Inner,
+        protocol: &str,
+        direction: Direction,
+        bucket: u64,
+        bytes: u64,
+    ) {
+        // Avoid allocation for every record when protocol is already known
+        match inner.per_protocol.get_mut(protocol) {
+            Some(counters) => counters.add(direction, bucket, bytes),
+            None => inner
+                .per_protocol
+                .entry(protocol.to_string())
+                .or_default()
+                .add(direction, bucket, bytes),
+        }
+    }
+
+    fn inc_metric(&self, protocol: &str, direction: Direction, bytes: u64) {
+        if let Some(metric) = &self.metric {
+            metric
+                .get_or_create(&BandwidthLabels {
+                    protocol: protocol.to_string(),
+                    direction,
+                })
+                .inc_by(bytes);
+        }
+    }
+}
+
+/// Extracts protocol names from multistream-select messages in one direction of a substream.
+#[derive(Debug, Default)]
+struct ProtocolSniffer {
+    buffer: Vec<u8>,
+    /// Protocols seen so far, dialer may propose several protocols one after another
+    protocols: Vec<String>,
+    done: bool,
+}
+
+impl ProtocolSniffer {
+    /// Feed bytes transferred in this direction, returns `true` if new protocols were found.
+    fn feed(&mut self, bytes: &[u8]) -> bool {
+        if self.done {
+            return false;
+        }
+
+        self.buffer.extend_from_slice(bytes);
+        let protocols_before = self.protocols.len();
+
+        let mut remaining = self.buffer.as_slice();
+        loop {
+            let (length, rest) = match unsigned_varint::decode::usize(remaining) {
+                Ok(result) => result,
+                Err(unsigned_varint::decode::Error::Insufficient) => {
+                    break;
+                }
+                Err(_) => {
+                    self.done = true;
+                    break;
+                }
+            };
+            if rest.len() < length {
+                break;
+            }
+
+            let (message, rest) = rest.split_at(length);
+            remaining = rest;
+
+            let Some(message) = message.strip_suffix(b"\n") else {
+                // Not a negotiation message, negotiation is over and payload started
+                self.done = true;
+                break;
+            };
+            if message == MULTISTREAM_HEADER || message == b"na" || message == b"ls" {
+                continue;
+            }
+            match std::str::from_utf8(message) {
+                Ok(protocol) if protocol.starts_with('/') => {
+                    self.protocols.push(protocol.to_string());
+                }
+                _ => {
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+
+        let consumed = self.buffer.len() - remaining.len();
+        self.buffer.drain(..consumed);
+        if self.buffer.len() > MAX_SNIFFED_BYTES {
+            self.done = true;
+        }
+        if self.done {
+            self.buffer = Vec::new();
+        }
+
+        self.protocols.len() > protocols_before
+    }
+}
+
+/// Accounting state of a single substream.
+#[derive(Debug)]
+struct SubstreamAccounting {
+    peer_id: PeerId,
+    accounting: BandwidthAccounting,
+    protocol: Option<String>,
+    /// Bytes received before protocol was confirmed
+    pending_received: u64,
+    /// Bytes sent before protocol was confirmed
+    pending_sent: u64,
+    inbound_sniffer: ProtocolSniffer,
+    outbound_sniffer: ProtocolSniffer,
+}
+
+impl Drop for SubstreamAccounting {
+    fn drop(&mut self) {
+        if self.protocol.is_none() {
+            let now = Instant::now();
+            self.accounting.record_protocol(
+                UNKNOWN_PROTOCOL,
+                Direction::Inbound,
+                self.pending_received,
+                now,
+            );
+            self.accounting.record_protocol(
+                UNKNOWN_PROTOCOL,
+                Direction::Outbound,
+                self.pending_sent,
+                now,
+            );
+        }
+    }
+}
+
+impl SubstreamAccounting {
+    fn new(peer_id: PeerId, accounting: BandwidthAccounting) -> Self {
+        Self {
+            peer_id,
+            accounting,
+            protocol: None,
+            pending_received: 0,
+            pending_sent: 0,
+            inbound_sniffer: ProtocolSniffer::default(),
+            outbound_sniffer: ProtocolSniffer::default(),
+        }
+    }
+
+    fn on_bytes(&mut self, direction: Direction, bytes: &[u8]) {
+        let now = Instant::now();
+        let length = bytes.len() as u64;
+
+        if let Some(protocol) = &self.protocol {
+            self.accounting
+                .record(&self.peer_id, Some(protocol), direction, length, now);
+            return;
+        }
+
+        self.accounting
+            .record(&self.peer_id, None, direction, length, now);
+
+        let new_protocols = match direction {
+            Direction::Inbound => {
+                self.pending_received += length;
+                self.inbound_sniffer.feed(bytes)
+            }
+            Direction::Outbound => {
+                self.pending_sent += length;
+                self.outbound_sniffer.feed(bytes)
+            }
+        };
+
+        if !new_protocols {
+            return;
+        }
+
+        let confirmed = self
+            .inbound_sniffer
+            .protocols
+            .iter()
+            .find(|protocol| self.outbound_sniffer.protocols.contains(protocol))
+            .cloned();
+        if let Some(protocol) = confirmed {
+            self.accounting.record_protocol(
+                &protocol,
+                Direction::Inbound,
+                self.pending_received,
+                now,
+            );
+            self.accounting
+                .record_protocol(&protocol, Direction::Outbound, self.pending_sent, now);
+            self.pending_received = 0;
+            self.pending_sent = 0;
+            self.inbound_sniffer = ProtocolSniffer::default();
+            self.outbound_sniffer = ProtocolSniffer::default();
+            self.protocol.replace(protocol);
+        }
+    }
+}
+
+/// Substream that records bytes transferred through it.
+pub(crate) struct InstrumentedStream {
+    inner: SubstreamBox,
+    accounting: SubstreamAccounting,
+}
+
+impl AsyncRead for InstrumentedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let result = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(read)) = result {
+            this.accounting.on_bytes(Direction::Inbound, &buf[..read]);
+        }
+        result
+    }
+
+    fn poll_read_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let result = Pin::new(&mut this.inner).poll_read_vectored(cx, bufs);
+        if let Poll::Ready(Ok(read)) = result {
+            let mut remaining = read;
+            for buf in bufs.iter() {
+                let length = buf.len().min(remaining);
+                this.accounting.on_bytes(Direction::Inbound, &buf[..length]);
+                remaining -= length;
+                if remaining == 0 {
+                    break;
+                }
+            }
+        }
+        result
+    }
+}
+
+impl AsyncWrite for InstrumentedStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let result = Pin::new(&mut this.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = result {
+            this.accounting
+                .on_bytes(Direction::Outbound, &buf[..written]);
+        }
+        result
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let result = Pin::new(&mut this.inner).poll_write_vectored(cx, bufs);
+        if let Poll::Ready(Ok(written)) = result {
+            let mut remaining = written;
+            for buf in bufs {
+                let length = buf.len().min(remaining);
+                this.accounting
+                    .on_bytes(Direction::Outbound, &buf[..length]);
+                remaining -= length;
+                if remaining == 0 {
+                    break;
+                }
+            }
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+/// Stream muxer that wraps substreams with [`InstrumentedStream`].
+pub(crate) struct BandwidthMuxer {
+    inner: StreamMuxerBox,
+    peer_id: PeerId,
+    accounting: BandwidthAccounting,
+}
+
+impl BandwidthMuxer {
+    pub(crate) fn new(
+        inner: StreamMuxerBox,
+        peer_id: PeerId,
+        accounting: BandwidthAccounting,
+    ) -> Self {
+        Self {
+            inner,
+            peer_id,
+            accounting,
+        }
+    }
+
+    fn instrument(&self, substream: SubstreamBox) -> InstrumentedStream {
+        InstrumentedStream {
+            inner: substream,
+            accounting: SubstreamAccounting::new(self.peer_id, self.accounting.clone()),
+        }
+    }
+}
+
+impl StreamMuxer for BandwidthMuxer {
+    type Substream = InstrumentedStream;
+    type Error = io::Error;
+
+    fn poll_inbound(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let substream = futures::ready!(Pin::new(&mut self.inner).poll_inbound(cx))?;
+        Poll::Ready(Ok(self.instrument(substream)))
+    }
+
+    fn poll_outbound(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let substream = futures::ready!(Pin::new(&mut self.inner).poll_outbound(cx))?;
+        Poll::Ready(Ok(self.instrument(substream)))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<StreamMuxerEvent, Self::Error>> {
+        Pin::new(&mut self.inner).poll(cx)
+    }
+}
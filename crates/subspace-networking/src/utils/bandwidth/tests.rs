@@ -0,0 +1,123 @@
+use super::{
+    BandwidthAccounting, BandwidthUsage, Direction, ProtocolSniffer, SubstreamAccounting,
+    BANDWIDTH_WINDOW, UNKNOWN_PROTOCOL,
+};
+use libp2p::PeerId;
+use std::time::Instant;
+
+fn message(contents: &str) -> Vec<u8> {
+    let mut length_buffer = unsigned_varint::encode::usize_buffer();
+    let length = unsigned_varint::encode::usize(contents.len() + 1, &mut length_buffer);
+
+    let mut message = length.to_vec();
+    message.extend_from_slice(contents.as_bytes());
+    message.push(b'\n');
+    message
+}
+
+#[test]
+fn sniffer_extracts_protocols() {
+    let mut sniffer = ProtocolSniffer::default();
+
+    let mut bytes = message("/multistream/1.0.0");
+    bytes.extend(message("/subspace/piece/1"));
+    bytes.extend(message("/subspace/piece/0"));
+
+    // Fed in two parts to make sure partial messages are handled
+    let (head, tail) = bytes.split_at(5);
+    assert!(!sniffer.feed(head));
+    assert!(sniffer.feed(tail));
+    assert_eq!(
+        sniffer.protocols,
+        vec![
+            "/subspace/piece/1".to_string(),
+            "/subspace/piece/0".to_string()
+        ]
+    );
+    assert!(!sniffer.done);
+
+    // Payload after negotiation stops sniffing
+    assert!(!sniffer.feed(&[3, 1, 2, 3]));
+    assert!(sniffer.done);
+    assert!(!sniffer.feed(&message("/ipfs/ping/1.0.0")));
+}
+
+#[test]
+fn substream_protocol_confirmation() {
+    let accounting = BandwidthAccounting::default();
+    let peer_id = PeerId::random();
+
+    {
+        let mut substream = SubstreamAccounting::new(peer_id, accounting.clone());
+
+        let mut proposal = message("/multistream/1.0.0");
+        proposal.extend(message("/ipfs/ping/1.0.0"));
+        substream.on_bytes(Direction::Outbound, &proposal);
+        // Proposed, but not confirmed yet
+        assert!(accounting.stats().per_protocol.is_empty());
+
+        let mut confirmation = message("/multistream/1.0.0");
+        confirmation.extend(message("/ipfs/ping/1.0.0"));
+        substream.on_bytes(Direction::Inbound, &confirmation);
+        substream.on_bytes(Direction::Outbound, &[0; 32]);
+        substream.on_bytes(Direction::Inbound, &[0; 32]);
+
+        let stats = accounting.stats();
+        let expected = BandwidthUsage {
+            sent_total: proposal.len() as u64 + 32,
+            received_total: confirmation.len() as u64 + 32,
+            sent_in_window: proposal.len() as u64 + 32,
+            received_in_window: confirmation.len() as u64 + 32,
+        };
+        assert_eq!(stats.total, expected);
+        assert_eq!(stats.per_protocol.get("/ipfs/ping/1.0.0"), Some(&expected));
+        assert_eq!(stats.per_peer.get(&peer_id), Some(&expected));
+    }
+
+    {
+        let mut substream = SubstreamAccounting::new(peer_id, accounting.clone());
+
+        let mut proposal = message("/multistream/1.0.0");
+        proposal.extend(message("/unsupported/1"));
+        substream.on_bytes(Direction::Outbound, &proposal);
+        substream.on_bytes(Direction::Inbound, &message("na"));
+    }
+
+    // Substream that didn't complete negotiation is attributed to unknown protocol on drop
+    let stats = accounting.stats();
+    assert_eq!(stats.per_protocol.len(), 2);
+    assert!(stats.per_protocol.contains_key(UNKNOWN_PROTOCOL));
+    assert!(!stats.per_protocol.contains_key("/unsupported/1"));
+}
+
+#[test]
+fn rolling_window() {
+    let accounting = BandwidthAccounting::default();
+    let peer_id = PeerId::random();
+    let now = Instant::now();
+
+    accounting.record(&peer_id, Some("/a"), Direction::Inbound, 100, now);
+    accounting.record(
+        &peer_id,
+        Some("/a"),
+        Direction::Inbound,
+        10,
+        now + BANDWIDTH_WINDOW / 2,
+    );
+
+    let stats = accounting.stats_at(now + BANDWIDTH_WINDOW / 2);
+    assert_eq!(stats.total.received_total, 110);
+    assert_eq!(stats.total.received_in_window, 110);
+
+    let stats = accounting.stats_at(now + BANDWIDTH_WINDOW);
+    assert_eq!(stats.total.received_total, 110);
+    assert_eq!(stats.total.received_in_window, 10);
+    assert_eq!(stats.per_protocol["/a"].received_in_window, 10);
+    assert!(stats.per_peer.contains_key(&peer_id));
+
+    // Idle peers are pruned, but totals are retained for protocols
+    let stats = accounting.stats_at(now + BANDWIDTH_WINDOW * 2);
+    assert_eq!(stats.total.received_in_window, 0);
+    assert_eq!(stats.per_protocol["/a"].received_total, 110);
+    assert!(stats.per_peer.is_empty());
+}
@@ -0,0 +1,161 @@
+//! Aggregation of addresses peers observe us at.
+//!
+//! Every peer reports the address it sees our connection coming from during identify exchange.
+//! A single report can't be trusted (peer may lie or be behind the same NAT), but once enough
+//! distinct peers agree on the same IP address it is very likely our public IP. Votes are per IP
+//! rather than per full multiaddr, since for outgoing connections the port is ephemeral; public
+//! addresses are then derived from listen addresses by substituting the voted IP.
+//!
+//! Votes expire and are re-cast on every identify exchange, so when IP changes (residential
+//! farmers on dynamic addresses) the new IP eventually outvotes the old one.
+
+#[cfg(test)]
+mod tests;
+
+use libp2p::multiaddr::Protocol;
+use libp2p::{Multiaddr, PeerId};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+
+/// How long a vote stays valid, identify exchange is repeated every 5 minutes by default, so
+/// connected peers renew their votes well before expiration.
+pub(crate) const VOTE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Change of the public IP address determined by voting.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) struct PublicIpChange {
+    /// Previous public IP of the same family, if any
+    pub(crate) old: Option<IpAddr>,
+    /// New public IP
+    pub(crate) new: IpAddr,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Vote {
+    ip: IpAddr,
+    cast_at: Instant,
+}
+
+/// Aggregates IP addresses observed by peers and elects public IP addresses (one for IPv4 and one
+/// for IPv6) once enough distinct peers agree on them.
+#[derive(Debug)]
+pub(crate) struct ObservedAddresses {
+    /// Latest vote of each peer
+    votes: HashMap<PeerId, Vote>,
+    /// Number of distinct peers that need to agree on IP address before it is considered public
+    threshold: NonZeroUsize,
+    vote_ttl: Duration,
+    public_ipv4: Option<IpAddr>,
+    public_ipv6: Option<IpAddr>,
+}
+
+impl ObservedAddresses {
+    /// Create new instance that requires `threshold` distinct peers to agree on IP address, votes
+    /// are valid for `vote_ttl`
+    pub(crate) fn new(threshold: NonZeroUsize, vote_ttl: Duration) -> Self {
+        Self {
+            votes: HashMap::new(),
+            threshold,
+            vote_ttl,
+            public_ipv4: None,
+            public_ipv6: None,
+        }
+    }
+
+    /// Register address `peer_id` observed us at, replaces previous vote of the same peer.
+    ///
+    /// Returns change of the public IP if this vote changed the outcome of voting.
+    pub(crate) fn add_vote(
+        &mut self,
+        peer_id: PeerId,
+        observed_address: &Multiaddr,
+    ) -> Option<PublicIpChange> {
+        let ip = ip_address(observed_address)?;
+        let now = Instant::now();
+
+        self.votes
+            .retain(|_peer_id, vote| now.duration_since(vote.cast_at) < self.vote_ttl);
+        self.votes.insert(peer_id, Vote { ip, cast_at: now });
+
+        self.elect(ip.is_ipv4())
+    }
+
+    /// Remove vote of `peer_id`, for example when the last connection with it was closed.
+    ///
+    /// Already elected public IP is not revoked, it will be replaced once other IP gets more votes.
+    pub(crate) fn remove_votes(&mut self, peer_id: &PeerId) {
+        self.votes.remove(peer_id);
+    }
+
+    fn elect(&mut self, ipv4: bool) -> Option<PublicIpChange> {
+        let mut vote_counts = HashMap::<IpAddr, usize>::new();
+        for vote in self.votes.values() {
+            if vote.ip.is_ipv4() == ipv4 {
+                *vote_counts.entry(vote.ip).or_default() += 1;
+            }
+        }
+
+        let public_ip = if ipv4 {
+            &mut self.public_ipv4
+        } else {
+            &mut self.public_ipv6
+        };
+        let current_votes = public_ip
+            .and_then(|ip| vote_counts.get(&ip).copied())
+            .unwrap_or_default();
+
+        // Current public IP stays unless other IP has strictly more votes, this prevents flapping
+        // between IPs with the same number of votes
+        let (winner, winner_votes) = vote_counts
+            .into_iter()
+            .max_by_key(|&(ip, votes)| (votes, Some(ip) == *public_ip))?;
+
+        if winner_votes < self.threshold.get() || winner_votes <= current_votes {
+            return None;
+        }
+
+        let old = public_ip.replace(winner);
+
+        Some(PublicIpChange { old, new: winner })
+    }
+}
+
+/// IP address of the multiaddr, if it starts with one
+fn ip_address(address: &Multiaddr) -> Option<IpAddr> {
+    match address.iter().next()? {
+        Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+        Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+        _ => None,
+    }
+}
+
+/// Derive public address from listen address by substituting its IP with `public_ip`.
+///
+/// Returns `None` if listen address is not IP-based, is of a different IP family or already uses
+/// global IP address (in which case it doesn't need translation).
+pub(crate) fn translate_listen_address(
+    listen_address: &Multiaddr,
+    public_ip: IpAddr,
+) -> Option<Multiaddr> {
+    let mut protocols = listen_address.iter();
+    let is_translatable = match (protocols.next()?, public_ip) {
+        (Protocol::Ip4(ip), IpAddr::V4(_)) => !ip.is_global(),
+        (Protocol::Ip6(ip), IpAddr::V6(_)) => !ip.is_global(),
+        _ => false,
+    };
+    if !is_translatable {
+        return None;
+    }
+
+    let mut address = Multiaddr::from(public_ip);
+    for protocol in protocols {
+        if matches!(protocol, Protocol::P2p(_)) {
+            break;
+        }
+        address.push(protocol);
+    }
+
+    Some(address)
+}
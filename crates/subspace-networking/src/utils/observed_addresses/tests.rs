@@ -0,0 +1,145 @@
+use super::{translate_listen_address, ObservedAddresses, PublicIpChange, VOTE_TTL};
+use libp2p::{Multiaddr, PeerId};
+use std::net::IpAddr;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+const THRESHOLD: NonZeroUsize = NonZeroUsize::new(3).expect("Not zero; qed");
+
+fn observed(ip: &str) -> Multiaddr {
+    format!("/ip4/{ip}/tcp/{}", rand::random::<u16>())
+        .parse()
+        .unwrap()
+}
+
+fn ip(ip: &str) -> IpAddr {
+    ip.parse().unwrap()
+}
+
+#[test]
+fn public_ip_requires_threshold() {
+    let mut observed_addresses = ObservedAddresses::new(THRESHOLD, VOTE_TTL);
+    let peer_id = PeerId::random();
+
+    assert_eq!(
+        observed_addresses.add_vote(peer_id, &observed("1.1.1.1")),
+        None
+    );
+    // Repeated votes of the same peer count once
+    assert_eq!(
+        observed_addresses.add_vote(peer_id, &observed("1.1.1.1")),
+        None
+    );
+    assert_eq!(
+        observed_addresses.add_vote(PeerId::random(), &observed("1.1.1.1")),
+        None
+    );
+    assert_eq!(
+        observed_addresses.add_vote(PeerId::random(), &observed("1.1.1.1")),
+        Some(PublicIpChange {
+            old: None,
+            new: ip("1.1.1.1")
+        })
+    );
+    // Already elected
+    assert_eq!(
+        observed_addresses.add_vote(PeerId::random(), &observed("1.1.1.1")),
+        None
+    );
+
+    // Non-IP addresses are ignored
+    assert_eq!(
+        observed_addresses.add_vote(
+            PeerId::random(),
+            &"/dns4/example.com/tcp/1".parse().unwrap()
+        ),
+        None
+    );
+}
+
+#[test]
+fn public_ip_change() {
+    let mut observed_addresses = ObservedAddresses::new(THRESHOLD, VOTE_TTL);
+    let peers = (0..5).map(|_| PeerId::random()).collect::<Vec<_>>();
+
+    for peer_id in &peers[..3] {
+        observed_addresses.add_vote(*peer_id, &observed("1.1.1.1"));
+    }
+
+    // Peers that were disconnected don't vote anymore
+    observed_addresses.remove_votes(&peers[0]);
+
+    // Tie doesn't change public IP
+    assert_eq!(
+        observed_addresses.add_vote(peers[3], &observed("2.2.2.2")),
+        None
+    );
+    assert_eq!(
+        observed_addresses.add_vote(peers[4], &observed("2.2.2.2")),
+        None
+    );
+    // Peer re-voting for a new IP breaks the tie
+    assert_eq!(
+        observed_addresses.add_vote(peers[1], &observed("2.2.2.2")),
+        Some(PublicIpChange {
+            old: Some(ip("1.1.1.1")),
+            new: ip("2.2.2.2")
+        })
+    );
+
+    // IPv6 is elected independently
+    for peer_id in &peers[..2] {
+        assert_eq!(
+            observed_addresses.add_vote(*peer_id, &"/ip6/2606:4700::1/tcp/1".parse().unwrap()),
+            None
+        );
+    }
+    assert_eq!(
+        observed_addresses.add_vote(peers[2], &"/ip6/2606:4700::1/tcp/1".parse().unwrap()),
+        Some(PublicIpChange {
+            old: None,
+            new: ip("2606:4700::1")
+        })
+    );
+}
+
+#[test]
+fn votes_expire() {
+    let mut observed_addresses = ObservedAddresses::new(THRESHOLD, Duration::ZERO);
+
+    for _ in 0..5 {
+        assert_eq!(
+            observed_addresses.add_vote(PeerId::random(), &observed("1.1.1.1")),
+            None
+        );
+    }
+}
+
+#[test]
+fn listen_address_translation() {
+    let public_ip = ip("1.1.1.1");
+
+    assert_eq!(
+        translate_listen_address(&"/ip4/0.0.0.0/tcp/30433".parse().unwrap(), public_ip),
+        Some("/ip4/1.1.1.1/tcp/30433".parse().unwrap())
+    );
+    assert_eq!(
+        translate_listen_address(
+            &format!("/ip4/192.168.1.2/tcp/30433/p2p/{}", PeerId::random())
+                .parse()
+                .unwrap(),
+            public_ip
+        ),
+        Some("/ip4/1.1.1.1/tcp/30433".parse().unwrap())
+    );
+    // Already global
+    assert_eq!(
+        translate_listen_address(&"/ip4/8.8.8.8/tcp/30433".parse().unwrap(), public_ip),
+        None
+    );
+    // Different IP family
+    assert_eq!(
+        translate_listen_address(&"/ip6/::/tcp/30433".parse().unwrap(), public_ip),
+        None
+    );
+}
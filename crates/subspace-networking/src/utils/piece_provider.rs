@@ -1,6 +1,9 @@
 //! Provides methods to retrieve pieces from DSN.
 
 use crate::utils::multihash::ToMultihash;
+use crate::utils::piece_request_tickets::{
+    PieceRequestTicket, MAX_PIECE_REQUEST_TICKET_DIFFICULTY,
+};
 use crate::{Node, PieceByIndexRequest, PieceByIndexResponse};
 use async_trait::async_trait;
 use backoff::future::retry;
@@ -21,6 +24,10 @@ const GET_PIECE_INITIAL_INTERVAL: Duration = Duration::from_secs(5);
 const GET_PIECE_MAX_INTERVAL: Duration = Duration::from_secs(40);
 
 /// Validates piece against using its commitment.
+///
+/// Pieces are only passed to the validator after passing cheap structural check with
+/// [`PieceArray::check()`](subspace_core_primitives::PieceArray::check), such that obviously invalid
+/// pieces are rejected early.
 #[async_trait]
 pub trait PieceValidator: Sync + Send {
     /// Validates piece against using its commitment.
@@ -62,6 +69,7 @@ impl PieceValidator for NoPieceValidator {
 pub struct PieceProvider<PV> {
     node: Node,
     piece_validator: Option<PV>,
+    request_ticket_difficulty: Option<u8>,
 }
 
 impl<PV> fmt::Debug for PieceProvider<PV> {
@@ -79,9 +87,53 @@ where
         Self {
             node,
             piece_validator,
+            request_ticket_difficulty: None,
         }
     }
 
+    /// Attach tickets of specified difficulty (clamped to
+    /// [`MAX_PIECE_REQUEST_TICKET_DIFFICULTY`]) to piece requests that peers didn't serve without
+    /// ticket, such that they are served with priority by peers that throttle anonymous requests.
+    pub fn with_request_tickets(mut self, difficulty: u8) -> Self {
+        self.request_ticket_difficulty
+            .replace(difficulty.min(MAX_PIECE_REQUEST_TICKET_DIFFICULTY));
+        self
+    }
+
+    /// Piece request with ticket, `None` if tickets are not used.
+    ///
+    /// Ticket only depends on piece index, so it is solved once (on a blocking thread, since it is
+    /// CPU-intensive) and stored in `maybe_ticket` for requests to other peers.
+    async fn piece_request_with_ticket(
+        &self,
+        piece_index: PieceIndex,
+        maybe_ticket: &mut Option<PieceRequestTicket>,
+    ) -> Option<PieceByIndexRequest> {
+        let difficulty = self.request_ticket_difficulty?;
+
+        let ticket = match maybe_ticket {
+            Some(ticket) => *ticket,
+            None => {
+                let requester = self.node.id();
+                let ticket = tokio::task::spawn_blocking(move || {
+                    PieceRequestTicket::solve(&requester, piece_index, difficulty)
+                })
+                .await
+                .map_err(|error| {
+                    warn!(%piece_index, %error, "Failed to solve piece request ticket");
+                })
+                .ok()?;
+
+                *maybe_ticket.insert(ticket)
+            }
+        };
+
+        Some(PieceByIndexRequest {
+            piece_index,
+            ticket: Some(ticket),
+        })
+    }
+
     // Get from piece cache (L2)
     async fn get_piece_from_cache(&self, piece_index: PieceIndex) -> Option<Piece> {
         let key = piece_index.to_multihash();
@@ -91,17 +143,34 @@ where
 
         match get_providers_result {
             Ok(mut get_providers_stream) => {
+                let mut maybe_ticket = None;
                 while let Some(provider_id) = get_providers_stream.next().await {
                     trace!(%piece_index, %provider_id, "get_providers returned an item");
 
-                    let request_result = request_batch
-                        .send_generic_request(provider_id, PieceByIndexRequest { piece_index })
+                    let mut request_result = request_batch
+                        .send_generic_request(provider_id, PieceByIndexRequest::new(piece_index))
                         .await;
+                    // Peers that throttle anonymous requests don't serve requests without ticket
+                    if matches!(request_result, Ok(PieceByIndexResponse { piece: None })) {
+                        if let Some(request) = self
+                            .piece_request_with_ticket(piece_index, &mut maybe_ticket)
+                            .await
+                        {
+                            request_result = request_batch
+                                .send_generic_request(provider_id, request)
+                                .await;
+                        }
+                    }
 
                     match request_result {
                         Ok(PieceByIndexResponse { piece: Some(piece) }) => {
                             trace!(%provider_id, %piece_index, ?key, "Piece request succeeded.");
 
+                            if let Err(error) = piece.check() {
+                                debug!(%provider_id, %piece_index, ?key, %error, "Piece request returned invalid piece.");
+                                continue;
+                            }
+
                             if let Some(validator) = &self.piece_validator {
                                 return validator
                                     .validate_piece(provider_id, piece_index, piece)
@@ -189,15 +258,39 @@ where
         peer_id: PeerId,
         piece_index: PieceIndex,
     ) -> Option<Piece> {
-        let request_result = self
+        self.get_piece_from_peer_internal(peer_id, piece_index, &mut None)
+            .await
+    }
+
+    async fn get_piece_from_peer_internal(
+        &self,
+        peer_id: PeerId,
+        piece_index: PieceIndex,
+        maybe_ticket: &mut Option<PieceRequestTicket>,
+    ) -> Option<Piece> {
+        let mut request_result = self
             .node
-            .send_generic_request(peer_id, PieceByIndexRequest { piece_index })
+            .send_generic_request(peer_id, PieceByIndexRequest::new(piece_index))
             .await;
+        // Peers that throttle anonymous requests don't serve requests without ticket
+        if matches!(request_result, Ok(PieceByIndexResponse { piece: None })) {
+            if let Some(request) = self
+                .piece_request_with_ticket(piece_index, maybe_ticket)
+                .await
+            {
+                request_result = self.node.send_generic_request(peer_id, request).await;
+            }
+        }
 
         match request_result {
             Ok(PieceByIndexResponse { piece: Some(piece) }) => {
                 trace!(%peer_id, %piece_index, "Piece request succeeded.");
 
+                if let Err(error) = piece.check() {
+                    debug!(%peer_id, %piece_index, %error, "Piece request returned invalid piece.");
+                    return None;
+                }
+
                 if let Some(validator) = &self.piece_validator {
                     return validator.validate_piece(peer_id, piece_index, piece).await;
                 } else {
@@ -241,8 +334,11 @@ where
         if connected_peers.is_empty() {
             debug!(%piece_index, "Cannot acquire piece from no connected peers (DSN L1 lookup)");
         } else {
+            let mut maybe_ticket = None;
             for peer_id in connected_peers.iter() {
-                let maybe_piece = self.get_piece_from_peer(*peer_id, piece_index).await;
+                let maybe_piece = self
+                    .get_piece_from_peer_internal(*peer_id, piece_index, &mut maybe_ticket)
+                    .await;
 
                 if maybe_piece.is_some() {
                     trace!(%piece_index, %peer_id, "DSN L1 lookup from connected peers succeeded");
@@ -311,17 +407,33 @@ where
 
         match get_closest_peers_result {
             Ok(mut get_closest_peers_stream) => {
+                let mut maybe_ticket = None;
                 while let Some(peer_id) = get_closest_peers_stream.next().await {
                     trace!(%piece_index, %peer_id, %round, "get_closest_peers returned an item");
 
-                    let request_result = request_batch
-                        .send_generic_request(peer_id, PieceByIndexRequest { piece_index })
+                    let mut request_result = request_batch
+                        .send_generic_request(peer_id, PieceByIndexRequest::new(piece_index))
                         .await;
+                    // Peers that throttle anonymous requests don't serve requests without ticket
+                    if matches!(request_result, Ok(PieceByIndexResponse { piece: None })) {
+                        if let Some(request) = self
+                            .piece_request_with_ticket(piece_index, &mut maybe_ticket)
+                            .await
+                        {
+                            request_result =
+                                request_batch.send_generic_request(peer_id, request).await;
+                        }
+                    }
 
                     match request_result {
                         Ok(PieceByIndexResponse { piece: Some(piece) }) => {
                             trace!(%peer_id, %piece_index, ?key, %round,  "Piece request succeeded.");
 
+                            if let Err(error) = piece.check() {
+                                debug!(%peer_id, %piece_index, ?key, %round, %error, "Piece request returned invalid piece.");
+                                continue;
+                            }
+
                             if let Some(validator) = &self.piece_validator {
                                 return validator.validate_piece(peer_id, piece_index, piece).await;
                             } else {
@@ -0,0 +1,166 @@
+//! Request tickets for piece downloads.
+//!
+//! Serving pieces is not free, nodes that store pieces can optionally throttle requests of
+//! anonymous peers that are likely to be bulk scrapers. Peers that want their requests to be
+//! served with priority attach a ticket to piece requests: a small proof-of-work bound to the
+//! requesting peer and requested piece, such that it is cheap to produce for occasional plotting,
+//! but becomes expensive for downloading large portions of history. Trusted peers (like own
+//! plotting peers or gateways) don't need tickets at all.
+
+#[cfg(test)]
+mod tests;
+
+use crate::PieceByIndexRequest;
+use libp2p::PeerId;
+use parity_scale_codec::{Decode, Encode};
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use subspace_core_primitives::crypto::blake3_hash_list;
+use subspace_core_primitives::PieceIndex;
+use tracing::debug;
+
+/// Default difficulty (number of leading zero bits of ticket hash) of piece request tickets
+pub const DEFAULT_PIECE_REQUEST_TICKET_DIFFICULTY: u8 = 12;
+/// Max difficulty of piece request tickets, higher difficulties are clamped to this value since
+/// solving tickets would take too long otherwise
+pub const MAX_PIECE_REQUEST_TICKET_DIFFICULTY: u8 = 24;
+/// Default interval over which anonymous piece requests are counted
+const DEFAULT_ANONYMOUS_REQUESTS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Proof-of-work ticket attached to piece request
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct PieceRequestTicket {
+    /// Nonce that makes ticket hash satisfy difficulty
+    pub nonce: u64,
+}
+
+impl PieceRequestTicket {
+    /// Solve ticket for piece request of `requester` with specified difficulty (clamped to
+    /// [`MAX_PIECE_REQUEST_TICKET_DIFFICULTY`]).
+    ///
+    /// Takes `2^difficulty` hashes on average, which is CPU-intensive for higher difficulties, so
+    /// it should not be called from async context directly.
+    pub fn solve(requester: &PeerId, piece_index: PieceIndex, difficulty: u8) -> Self {
+        let requester = requester.to_bytes();
+        let difficulty = difficulty.min(MAX_PIECE_REQUEST_TICKET_DIFFICULTY);
+
+        (0..=u64::MAX)
+            .map(|nonce| Self { nonce })
+            .find(|ticket| ticket.work(&requester, piece_index) >= u32::from(difficulty))
+            .expect("Difficulty is at most 24 bits and there are 2^64 nonces; qed")
+    }
+
+    /// Check whether ticket is valid for piece request of `requester` with specified difficulty
+    /// (clamped to [`MAX_PIECE_REQUEST_TICKET_DIFFICULTY`])
+    pub fn is_valid(&self, requester: &PeerId, piece_index: PieceIndex, difficulty: u8) -> bool {
+        let difficulty = difficulty.min(MAX_PIECE_REQUEST_TICKET_DIFFICULTY);
+        self.work(&requester.to_bytes(), piece_index) >= u32::from(difficulty)
+    }
+
+    /// Number of leading zero bits of ticket hash
+    fn work(&self, requester: &[u8], piece_index: PieceIndex) -> u32 {
+        let hash = blake3_hash_list(&[
+            requester,
+            &piece_index.to_bytes(),
+            &self.nonce.to_le_bytes(),
+        ]);
+
+        let mut work = 0;
+        for byte in hash {
+            work += byte.leading_zeros();
+            if byte != 0 {
+                break;
+            }
+        }
+        work
+    }
+}
+
+/// Policy for serving piece requests
+#[derive(Debug, Clone)]
+pub struct PieceRequestPolicy {
+    /// Required difficulty of piece request tickets
+    pub ticket_difficulty: u8,
+    /// Peers whose requests are always served, regardless of tickets
+    pub trusted_peers: HashSet<PeerId>,
+    /// Max number of requests without valid ticket served within `anonymous_requests_interval`
+    /// (shared by all anonymous peers), `None` means anonymous requests are not limited
+    pub anonymous_requests_limit: Option<u32>,
+    /// Interval over which anonymous requests are counted
+    pub anonymous_requests_interval: Duration,
+}
+
+impl Default for PieceRequestPolicy {
+    fn default() -> Self {
+        Self {
+            ticket_difficulty: DEFAULT_PIECE_REQUEST_TICKET_DIFFICULTY,
+            trusted_peers: HashSet::new(),
+            anonymous_requests_limit: None,
+            anonymous_requests_interval: DEFAULT_ANONYMOUS_REQUESTS_INTERVAL,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct AnonymousRequests {
+    interval_start: Instant,
+    count: u32,
+}
+
+/// Decides which incoming piece requests should be served according to [`PieceRequestPolicy`]
+#[derive(Debug)]
+pub struct PieceRequestThrottle {
+    policy: PieceRequestPolicy,
+    anonymous_requests: Mutex<AnonymousRequests>,
+}
+
+impl PieceRequestThrottle {
+    /// Create new instance
+    pub fn new(policy: PieceRequestPolicy) -> Self {
+        Self {
+            policy,
+            anonymous_requests: Mutex::new(AnonymousRequests {
+                interval_start: Instant::now(),
+                count: 0,
+            }),
+        }
+    }
+
+    /// Returns `true` if request from `peer_id` should be served
+    pub fn admit(&self, peer_id: &PeerId, request: &PieceByIndexRequest) -> bool {
+        let Some(anonymous_requests_limit) = self.policy.anonymous_requests_limit else {
+            return true;
+        };
+
+        if self.policy.trusted_peers.contains(peer_id) {
+            return true;
+        }
+
+        if let Some(ticket) = &request.ticket {
+            if ticket.is_valid(peer_id, request.piece_index, self.policy.ticket_difficulty) {
+                return true;
+            }
+
+            debug!(%peer_id, piece_index = %request.piece_index, "Invalid piece request ticket");
+        }
+
+        let mut anonymous_requests = self.anonymous_requests.lock();
+        if anonymous_requests.interval_start.elapsed() >= self.policy.anonymous_requests_interval {
+            anonymous_requests.interval_start = Instant::now();
+            anonymous_requests.count = 0;
+        }
+
+        if anonymous_requests.count >= anonymous_requests_limit {
+            debug!(
+                %peer_id,
+                piece_index = %request.piece_index,
+                "Anonymous piece requests limit reached, ignoring request"
+            );
+            return false;
+        }
+
+        anonymous_requests.count += 1;
+        true
+    }
+}
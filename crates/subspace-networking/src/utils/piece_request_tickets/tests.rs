@@ -0,0 +1,94 @@
+use crate::utils::piece_request_tickets::{
+    PieceRequestPolicy, PieceRequestThrottle, PieceRequestTicket,
+};
+use crate::PieceByIndexRequest;
+use libp2p::PeerId;
+use parity_scale_codec::{Decode, Encode};
+use std::time::Duration;
+use subspace_core_primitives::PieceIndex;
+
+#[test]
+fn ticket_solve_and_verify() {
+    let requester = PeerId::random();
+    let piece_index = PieceIndex::from(10);
+    let difficulty = 8;
+
+    let ticket = PieceRequestTicket::solve(&requester, piece_index, difficulty);
+    assert!(ticket.is_valid(&requester, piece_index, difficulty));
+    // Ticket is bound to requester and piece index, with 8 bits of difficulty there is 1/256
+    // chance that ticket is accidentally valid for another requester or piece index, hence
+    // multiple are checked
+    assert!(std::iter::repeat_with(PeerId::random)
+        .take(4)
+        .any(|requester| !ticket.is_valid(&requester, piece_index, difficulty)));
+    assert!((11..15)
+        .map(PieceIndex::from)
+        .any(|piece_index| !ticket.is_valid(&requester, piece_index, difficulty)));
+}
+
+#[test]
+fn request_decoding_compatibility() {
+    let piece_index = PieceIndex::from(10);
+
+    // Request of older peer that doesn't know about tickets
+    let encoded = piece_index.encode();
+    assert_eq!(
+        PieceByIndexRequest::decode(&mut encoded.as_slice()).unwrap(),
+        PieceByIndexRequest::new(piece_index)
+    );
+
+    let request = PieceByIndexRequest {
+        piece_index,
+        ticket: Some(PieceRequestTicket { nonce: 42 }),
+    };
+    let encoded = request.encode();
+    assert_eq!(
+        PieceByIndexRequest::decode(&mut encoded.as_slice()).unwrap(),
+        request
+    );
+}
+
+#[test]
+fn throttle_anonymous_requests() {
+    let trusted_peer = PeerId::random();
+    let anonymous_peer = PeerId::random();
+    let difficulty = 4;
+    let throttle = PieceRequestThrottle::new(PieceRequestPolicy {
+        ticket_difficulty: difficulty,
+        trusted_peers: [trusted_peer].into(),
+        anonymous_requests_limit: Some(2),
+        anonymous_requests_interval: Duration::from_secs(3600),
+    });
+
+    let piece_index = PieceIndex::from(1);
+    let request = PieceByIndexRequest::new(piece_index);
+    assert!(throttle.admit(&anonymous_peer, &request));
+    assert!(throttle.admit(&anonymous_peer, &request));
+    // Limit reached
+    assert!(!throttle.admit(&anonymous_peer, &request));
+    assert!(!throttle.admit(&PeerId::random(), &request));
+
+    // Trusted peers are not limited
+    assert!(throttle.admit(&trusted_peer, &request));
+
+    // Requests with valid tickets are not limited
+    let request_with_ticket = PieceByIndexRequest {
+        piece_index,
+        ticket: Some(PieceRequestTicket::solve(
+            &anonymous_peer,
+            piece_index,
+            difficulty,
+        )),
+    };
+    assert!(throttle.admit(&anonymous_peer, &request_with_ticket));
+    // Ticket of another peer is not accepted
+    let other_peer = std::iter::repeat_with(PeerId::random)
+        .find(|peer_id| {
+            !request_with_ticket
+                .ticket
+                .unwrap()
+                .is_valid(peer_id, piece_index, difficulty)
+        })
+        .unwrap();
+    assert!(!throttle.admit(&other_peer, &request_with_ticket));
+}
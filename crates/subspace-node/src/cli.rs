@@ -20,6 +20,7 @@ use clap::Parser;
 use sc_chain_spec::GenericChainSpec;
 use sc_cli::SubstrateCli;
 use sc_service::ChainSpec;
+use sc_subspace_chain_specs::test_net::{test_net_chain_spec, TestNetConfig};
 
 /// Commands for working with a node.
 #[derive(Debug, Parser)]
@@ -103,6 +104,13 @@ impl SubstrateCli for SubspaceCliPlaceholder {
             "devnet" => chain_spec::devnet_config()?,
             "devnet-compiled" => chain_spec::devnet_config_compiled()?,
             "dev" => chain_spec::dev_config()?,
+            "test-net" => test_net_chain_spec(TestNetConfig::new(1))?,
+            id if id.starts_with("test-net-") => {
+                let farmers = id["test-net-".len()..]
+                    .parse()
+                    .map_err(|error| format!("Invalid number of test net farmers: {error}"))?;
+                test_net_chain_spec(TestNetConfig::new(farmers))?
+            }
             path => GenericChainSpec::from_json_file(std::path::PathBuf::from(path))?,
         };
 
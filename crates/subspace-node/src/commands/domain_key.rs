@@ -1,5 +1,5 @@
 use crate::commands::shared::{
-    derive_keypair, init_logger, store_key_in_keystore, KeystoreOptions,
+    derive_keypair, init_logger, store_key_in_keystore, KeystoreOptions, LogFormat,
 };
 use bip39::Mnemonic;
 use clap::Parser;
@@ -26,7 +26,7 @@ pub struct CreateDomainKeyOptions {
 }
 
 pub fn create_domain_key(options: CreateDomainKeyOptions) -> Result<(), Error> {
-    init_logger();
+    init_logger(LogFormat::Text);
 
     let CreateDomainKeyOptions {
         base_path,
@@ -63,6 +63,11 @@ pub fn create_domain_key(options: CreateDomainKeyOptions) -> Result<(), Error> {
 
     info!("Successfully generated and imported keypair!");
     info!("Public key: 0x{}", hex::encode(public_key.0));
+    info!(
+        "Use it when registering a new operator or as a new signing key of an existing operator \
+        with `rotate_operator_signing_key`, in which case keep the old key in keystore until the \
+        end of the current domain epoch"
+    );
     info!("Seed: \"{}\"", phrase.expose_secret());
     if has_password {
         info!("Password: as specified in CLI options");
@@ -94,7 +99,7 @@ pub struct InsertDomainKeyOptions {
 }
 
 pub fn insert_domain_key(options: InsertDomainKeyOptions) -> Result<(), Error> {
-    init_logger();
+    init_logger(LogFormat::Text);
 
     let InsertDomainKeyOptions {
         base_path,
@@ -8,11 +8,11 @@ use crate::commands::run::consensus::{
 use crate::commands::run::domain::{
     create_domain_configuration, run_evm_domain, DomainOptions, DomainStartOptions,
 };
-use crate::commands::shared::init_logger;
+use crate::commands::shared::{init_logger, LogFormat};
 use crate::{set_default_ss58_version, Error, PosTable};
 use clap::Parser;
 use cross_domain_message_gossip::GossipWorkerBuilder;
-use domain_client_operator::fetch_domain_bootstrap_info;
+use domain_client_operator::{fetch_domain_bootstrap_info, run_bundle_relay};
 use domain_runtime_primitives::opaque::Block as DomainBlock;
 use futures::FutureExt;
 use sc_cli::Signals;
@@ -34,6 +34,10 @@ pub struct RunOptions {
     #[clap(flatten)]
     consensus: ConsensusChainOptions,
 
+    /// Format of log output, `json` emits one structured event per line
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+
     /// Domain arguments
     ///
     /// The command-line arguments provided first will be passed to the embedded consensus node,
@@ -68,16 +72,17 @@ fn raise_fd_limit() {
 /// Default run command for node
 #[tokio::main]
 pub async fn run(run_options: RunOptions) -> Result<(), Error> {
-    let enable_color = init_logger().enable_color;
-    raise_fd_limit();
-
-    let signals = Signals::capture()?;
-
     let RunOptions {
         consensus,
+        log_format,
         domain_args,
     } = run_options;
 
+    let enable_color = init_logger(log_format).enable_color;
+    raise_fd_limit();
+
+    let signals = Signals::capture()?;
+
     let domain_options = (!domain_args.is_empty())
         .then(|| DomainOptions::parse_from(env::args().take(1).chain(domain_args)));
 
@@ -87,6 +92,7 @@ pub async fn run(run_options: RunOptions) -> Result<(), Error> {
         dev,
         pot_external_entropy,
         storage_monitor,
+        bundle_relay_domains,
         mut prometheus_configuration,
     } = create_consensus_chain_configuration(consensus, enable_color, domain_options.is_some())?;
 
@@ -157,6 +163,21 @@ pub async fn run(run_options: RunOptions) -> Result<(), Error> {
             sc_service::Error::Other(format!("Failed to start storage monitor: {error:?}"))
         })?;
 
+        if !bundle_relay_domains.is_empty() {
+            info!(?bundle_relay_domains, "Starting bundle relay");
+
+            consensus_chain_node.task_manager.spawn_handle().spawn(
+                "bundle-relay",
+                Some("domains"),
+                run_bundle_relay::<DomainBlock, Block, _, _>(
+                    bundle_relay_domains,
+                    consensus_chain_node.client.clone(),
+                    consensus_chain_node.transaction_pool.clone(),
+                )
+                .boxed(),
+            );
+        }
+
         // Run a domain
         if let Some(domain_configuration) = maybe_domain_configuration {
             let mut xdm_gossip_worker_builder = GossipWorkerBuilder::new();
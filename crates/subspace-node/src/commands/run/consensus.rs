@@ -9,14 +9,19 @@ use sc_cli::{
 };
 use sc_informant::OutputFormat;
 use sc_network::config::{MultiaddrWithPeerId, NonReservedPeerMode, SetConfig};
+use sc_proof_of_time::source::external_timekeeper::ExternalTimekeeperConfig;
 use sc_service::{BlocksPruning, Configuration, PruningMode};
 use sc_storage_monitor::StorageMonitorParams;
+use sc_subspace_chain_specs::test_net::{test_net_chain_spec, TestNetConfig};
 use sc_telemetry::TelemetryEndpoints;
-use std::collections::HashSet;
+use sp_domains::DomainId;
+use std::collections::{BTreeSet, HashSet};
 use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
+use subspace_core_primitives::{PublicKey, PUBLIC_KEY_LENGTH};
 use subspace_networking::libp2p::multiaddr::Protocol;
 use subspace_networking::libp2p::Multiaddr;
 use subspace_service::config::{
@@ -53,6 +58,15 @@ fn parse_timekeeper_cpu_cores(
     Ok(cpu_cores)
 }
 
+fn parse_external_timekeeper_public_key(
+    s: &str,
+) -> Result<PublicKey, Box<dyn std::error::Error + Send + Sync>> {
+    let mut public_key = [0; PUBLIC_KEY_LENGTH];
+    hex::decode_to_slice(s.trim_start_matches("0x"), &mut public_key)?;
+
+    Ok(PublicKey::from(public_key))
+}
+
 /// Options for Substrate networking
 #[derive(Debug, Parser)]
 struct SubstrateNetworkOptions {
@@ -162,6 +176,12 @@ struct DsnOptions {
     /// Known external addresses
     #[arg(long, alias = "dsn-external-address")]
     dsn_external_addresses: Vec<Multiaddr>,
+
+    /// Client-only DSN operation for resource-constrained machines: DSN doesn't listen for
+    /// incoming connections (`--dsn-listen-on` is ignored) and doesn't serve requests of other
+    /// peers, while sync from DSN works as usual.
+    #[arg(long, default_value_t = false)]
+    dsn_client_only: bool,
 }
 
 /// This mode specifies when the block's state (ie, storage) should be pruned (ie, removed) from
@@ -300,6 +320,22 @@ struct TimekeeperOptions {
     /// * `0,1,6-7` - use cores 0, 1, 6 and 7
     #[arg(long, default_value = "", value_parser = parse_timekeeper_cpu_cores, verbatim_doc_comment)]
     timekeeper_cpu_cores: HashSet<usize>,
+
+    /// Address (`host:port`) of external timekeeper to consume proofs of time from instead of
+    /// computing them locally, can be specified multiple times.
+    ///
+    /// Requires at least one `--external-timekeeper-public-key`.
+    #[arg(long)]
+    external_timekeeper: Vec<String>,
+
+    /// Hex-encoded public key of trusted external timekeeper, can be specified multiple times.
+    #[arg(long, value_parser = parse_external_timekeeper_public_key)]
+    external_timekeeper_public_key: Vec<PublicKey>,
+
+    /// Number of seconds without valid proofs from external timekeepers after which node falls
+    /// back to computing proofs of time locally.
+    #[arg(long, default_value_t = 10)]
+    external_timekeeper_fallback_timeout: u64,
 }
 
 /// Options for running a node
@@ -313,8 +349,9 @@ pub(super) struct ConsensusChainOptions {
 
     /// Specify the chain specification.
     ///
-    /// It can be one of the predefined ones (dev) or it can be a path to a file with the chainspec
-    /// (such as one exported by the `build-spec` subcommand).
+    /// It can be one of the predefined ones (dev, test-net or test-net-<farmers> for a local test
+    /// network with specified number of pre-funded test farmers) or it can be a path to a file with
+    /// the chainspec (such as one exported by the `build-spec` subcommand).
     #[arg(long)]
     chain: Option<String>,
 
@@ -401,6 +438,22 @@ pub(super) struct ConsensusChainOptions {
     #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
     sync_from_dsn: bool,
 
+    /// Stop authoring blocks (while still syncing) when node detects that its archived history
+    /// diverged from the history agreed on by DSN peers.
+    ///
+    /// This typically means that node missed a mandatory upgrade and is building on top of a dead
+    /// fork. Fork is always reported in logs, this option additionally prevents connected farmers
+    /// from wasting their effort on such fork.
+    #[arg(long, default_value_t = false)]
+    halt_authoring_on_fork: bool,
+
+    /// Validate bundles of specified domain using consensus chain state only and stop propagation
+    /// of invalid ones, can be specified multiple times.
+    ///
+    /// This allows node to act as bundle relay for a domain without running a domain node.
+    #[arg(long = "bundle-relay-domain", value_name = "DOMAIN_ID")]
+    bundle_relay_domains: Vec<DomainId>,
+
     /// Parameters used to create the storage monitor.
     #[clap(flatten)]
     storage_monitor: StorageMonitorParams,
@@ -422,6 +475,8 @@ pub(super) struct ConsensusChainConfiguration {
     /// External entropy, used initially when PoT chain starts to derive the first seed
     pub(super) pot_external_entropy: Vec<u8>,
     pub(super) storage_monitor: StorageMonitorParams,
+    /// Domains for which bundles are validated statelessly before propagation
+    pub(super) bundle_relay_domains: BTreeSet<DomainId>,
     pub(super) prometheus_configuration: Option<PrometheusConfiguration>,
 }
 
@@ -448,6 +503,8 @@ pub(super) fn create_consensus_chain_configuration(
         pot_external_entropy,
         mut dsn_options,
         sync_from_dsn,
+        halt_authoring_on_fork,
+        bundle_relay_domains,
         storage_monitor,
         mut timekeeper_options,
     } = consensus_node_options;
@@ -492,6 +549,13 @@ pub(super) fn create_consensus_chain_configuration(
         Some("devnet") => chain_spec::devnet_config()?,
         Some("devnet-compiled") => chain_spec::devnet_config_compiled()?,
         Some("dev") => chain_spec::dev_config()?,
+        Some("test-net") => test_net_chain_spec(TestNetConfig::new(1))?,
+        Some(id) if id.starts_with("test-net-") => {
+            let farmers = id["test-net-".len()..]
+                .parse()
+                .map_err(|error| format!("Invalid number of test net farmers: {error}"))?;
+            test_net_chain_spec(TestNetConfig::new(farmers))?
+        }
         Some(path) => GenericChainSpec::from_json_file(std::path::PathBuf::from(path))?,
         None => {
             return Err(Error::Other(
@@ -645,7 +709,30 @@ pub(super) fn create_consensus_chain_configuration(
             max_pending_out_connections: dsn_options.dsn_pending_out_connections,
             external_addresses: dsn_options.dsn_external_addresses,
             disable_bootstrap_on_start: dsn_options.dsn_disable_bootstrap_on_start,
+            client_only: dsn_options.dsn_client_only,
+        }
+    };
+
+    let external_timekeeper = if timekeeper_options.external_timekeeper.is_empty() {
+        None
+    } else {
+        if timekeeper_options.external_timekeeper_public_key.is_empty() {
+            return Err(Error::Other(
+                "--external-timekeeper requires at least one --external-timekeeper-public-key"
+                    .to_string(),
+            ));
         }
+
+        Some(ExternalTimekeeperConfig {
+            addresses: timekeeper_options.external_timekeeper,
+            trusted_public_keys: timekeeper_options
+                .external_timekeeper_public_key
+                .into_iter()
+                .collect(),
+            fallback_timeout: Duration::from_secs(
+                timekeeper_options.external_timekeeper_fallback_timeout,
+            ),
+        })
     };
 
     let substrate_registry = consensus_chain_config.prometheus_registry().cloned();
@@ -660,10 +747,13 @@ pub(super) fn create_consensus_chain_configuration(
             sync_from_dsn,
             is_timekeeper: timekeeper_options.timekeeper,
             timekeeper_cpu_cores: timekeeper_options.timekeeper_cpu_cores,
+            external_timekeeper,
+            halt_authoring_on_fork,
         },
         dev,
         pot_external_entropy,
         storage_monitor,
+        bundle_relay_domains: bundle_relay_domains.into_iter().collect(),
         prometheus_configuration: prometheus_listen_on.zip(substrate_registry).map(
             |(listen_on, substrate_registry)| PrometheusConfiguration {
                 listen_on,
@@ -32,6 +32,7 @@ use sp_core::crypto::SecretString;
 use sp_domains::{DomainId, DomainInstanceData, OperatorId, RuntimeType};
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use subspace_runtime::RuntimeApi as CRuntimeApi;
 use subspace_runtime_primitives::opaque::Block as CBlock;
@@ -121,6 +122,13 @@ pub(super) struct DomainOptions {
     #[clap(flatten)]
     pool_config: TransactionPoolParams,
 
+    /// Directory with runtimes that override on-chain domain runtimes with the same spec version.
+    ///
+    /// Can be used to substitute EVM domain runtime with a runtime built with `evm-tracing`
+    /// feature on RPC nodes that serve EVM tracing RPC methods.
+    #[arg(long)]
+    wasm_runtime_overrides: Option<PathBuf>,
+
     /// Additional args for domain.
     #[clap(raw = true)]
     additional_args: Vec<String>,
@@ -149,6 +157,7 @@ pub(super) fn create_domain_configuration(
         mut keystore_suri,
         keystore_options,
         pool_config,
+        wasm_runtime_overrides,
         additional_args,
     } = domain_options;
 
@@ -363,6 +372,7 @@ pub(super) fn create_domain_configuration(
         force_authoring: false,
         chain_spec: Box::new(chain_spec),
         informant_output_format: OutputFormat { enable_color },
+        wasm_runtime_overrides,
     };
 
     Ok(DomainConfiguration {
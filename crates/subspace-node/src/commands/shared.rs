@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use sc_cli::Error;
 use sc_keystore::LocalKeystore;
 use sp_core::crypto::{ExposeSecret, SecretString};
@@ -53,33 +53,57 @@ pub(super) fn store_key_in_keystore(
         .map_err(|()| Error::Application("Failed to insert key into keystore".to_string().into()))
 }
 
+/// Format of log output
+#[derive(Debug, Copy, Clone, Default, ValueEnum)]
+pub(super) enum LogFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// Structured JSON, one event per line
+    Json,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub(super) struct InitLoggerResult {
     pub(super) enable_color: bool,
 }
 
-pub(super) fn init_logger() -> InitLoggerResult {
+pub(super) fn init_logger(log_format: LogFormat) -> InitLoggerResult {
     // TODO: This is a hack to work around https://github.com/quinn-rs/quinn/issues/1750, should be
     //  removed once fixed upstream
     if env::var("RUST_LOG").is_err() {
         env::set_var("RUST_LOG", "info,quinn_udp=error");
     }
-    // TODO: Workaround for https://github.com/tokio-rs/tracing/issues/2214, also on
-    //  Windows terminal doesn't support the same colors as bash does
-    let enable_color = if cfg!(windows) {
-        false
-    } else {
-        supports_color::on(supports_color::Stream::Stderr).is_some()
+    let env_filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .from_env_lossy();
+    let (fmt_layer, enable_color) = match log_format {
+        LogFormat::Text => {
+            // TODO: Workaround for https://github.com/tokio-rs/tracing/issues/2214, also on
+            //  Windows terminal doesn't support the same colors as bash does
+            let enable_color = if cfg!(windows) {
+                false
+            } else {
+                supports_color::on(supports_color::Stream::Stderr).is_some()
+            };
+            let fmt_layer = fmt::layer()
+                .with_ansi(enable_color)
+                .with_filter(env_filter)
+                .boxed();
+
+            (fmt_layer, enable_color)
+        }
+        LogFormat::Json => {
+            let fmt_layer = fmt::layer()
+                .json()
+                .flatten_event(true)
+                .with_filter(env_filter)
+                .boxed();
+
+            (fmt_layer, false)
+        }
     };
-    tracing_subscriber::registry()
-        .with(
-            fmt::layer().with_ansi(enable_color).with_filter(
-                EnvFilter::builder()
-                    .with_default_directive(LevelFilter::INFO.into())
-                    .from_env_lossy(),
-            ),
-        )
-        .init();
+    tracing_subscriber::registry().with(fmt_layer).init();
 
     InitLoggerResult { enable_color }
 }
@@ -1,5 +1,6 @@
-use crate::commands::shared::init_logger;
+use crate::commands::shared::{init_logger, LogFormat};
 use clap::Parser;
+use std::io::{IsTerminal, Write};
 use std::path::PathBuf;
 use std::{fs, io};
 use tracing::info;
@@ -9,26 +10,74 @@ use tracing::info;
 pub struct WipeOptions {
     /// Base path where to store node files
     base_path: PathBuf,
+    /// Wipe only consensus chain database, keeping domains, network identity and known peers
+    #[arg(long)]
+    db_only: bool,
+    /// Do not ask for confirmation before deleting files
+    #[arg(long)]
+    yes: bool,
 }
 
-pub fn wipe(WipeOptions { base_path }: WipeOptions) -> Result<(), io::Error> {
-    init_logger();
+pub fn wipe(
+    WipeOptions {
+        base_path,
+        db_only,
+        yes,
+    }: WipeOptions,
+) -> Result<(), io::Error> {
+    init_logger(LogFormat::Text);
 
-    let paths = [
-        base_path.join("db"),
-        base_path.join("domains"),
-        base_path.join("network"),
-        // TODO: Following three are temporary workaround for wiping old chains, remove once enough time has passed
-        base_path.join("chains"),
-        base_path.join("domain-0"),
-        base_path.join("domain-1"),
-    ];
+    let paths = if db_only {
+        vec![base_path.join("db")]
+    } else {
+        vec![
+            base_path.join("db"),
+            base_path.join("domains"),
+            base_path.join("network"),
+            // TODO: Following three are temporary workaround for wiping old chains, remove once enough time has passed
+            base_path.join("chains"),
+            base_path.join("domain-0"),
+            base_path.join("domain-1"),
+        ]
+    };
+    let paths = paths
+        .into_iter()
+        .filter(|path| path.exists())
+        .collect::<Vec<_>>();
 
-    for path in paths {
-        if path.exists() {
-            info!("Removing {}", path.display());
-            fs::remove_dir_all(path)?;
+    if paths.is_empty() {
+        info!("Nothing to wipe");
+        return Ok(());
+    }
+
+    if !yes {
+        if !io::stdin().is_terminal() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Confirmation can't be requested without terminal, use `--yes` to wipe anyway",
+            ));
+        }
+
+        eprintln!("Following directories will be deleted:");
+        for path in &paths {
+            eprintln!("  {}", path.display());
         }
+        eprint!("Type `yes` to continue: ");
+        io::stderr().flush()?;
+
+        let mut confirmation = String::new();
+        io::stdin().read_line(&mut confirmation)?;
+        if confirmation.trim() != "yes" {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Wiping was not confirmed",
+            ));
+        }
+    }
+
+    for path in paths {
+        info!("Removing {}", path.display());
+        fs::remove_dir_all(path)?;
     }
 
     info!("Done");
@@ -1,8 +1,6 @@
 #[cfg(test)]
 mod tests;
 
-extern crate alloc;
-
 use crate::chiapos::table::types::{Metadata, Position, X, Y};
 pub use crate::chiapos::table::TablesCache;
 use crate::chiapos::table::{
@@ -11,8 +9,7 @@ use crate::chiapos::table::{
 };
 use crate::chiapos::utils::EvaluatableUsize;
 use crate::chiapos::{Challenge, Quality, Seed};
-use alloc::vec::Vec;
-use core::mem;
+use core::{array, mem};
 use sha2::{Digest, Sha256};
 
 /// Pick position in `table_number` based on challenge bits
@@ -275,7 +272,8 @@ where
 
     /// Verify proof of space for given seed and challenge.
     ///
-    /// Returns quality on successful verification.
+    /// Returns quality on successful verification. Doesn't allocate on the heap, such that it can
+    /// be used in constrained environments.
     pub(super) fn verify(
         seed: Seed,
         challenge: &Challenge,
@@ -291,35 +289,33 @@ where
                 .expect("Challenge is known to statically have enough bytes; qed"),
         ) >> (u32::BITS as usize - usize::from(K));
 
-        let ys_and_metadata = (0..64_usize)
-            .map(|offset| {
-                let mut pre_x_bytes = 0u64.to_be_bytes();
-                let offset_in_bits = usize::from(K) * offset;
-                let bytes_to_copy = (offset_in_bits % u8::BITS as usize + usize::from(K))
-                    .div_ceil(u8::BITS as usize);
-                // Copy full bytes that contain bits of `x`
-                pre_x_bytes[..bytes_to_copy].copy_from_slice(
-                    &proof_of_space[offset_in_bits / u8::BITS as usize..][..bytes_to_copy],
-                );
-                // Extract `pre_x` whose last `K` bits start with `x`
-                let pre_x = u64::from_be_bytes(pre_x_bytes)
-                    >> (u64::BITS as usize - (usize::from(K) + offset_in_bits % u8::BITS as usize));
-                // Convert to desired type and clear extra bits
-                let x = X::from(pre_x as u32 & (u32::MAX >> (u32::BITS as usize - usize::from(K))));
+        let ys_and_metadata = array::from_fn::<_, 64, _>(|offset| {
+            let mut pre_x_bytes = 0u64.to_be_bytes();
+            let offset_in_bits = usize::from(K) * offset;
+            let bytes_to_copy =
+                (offset_in_bits % u8::BITS as usize + usize::from(K)).div_ceil(u8::BITS as usize);
+            // Copy full bytes that contain bits of `x`
+            pre_x_bytes[..bytes_to_copy].copy_from_slice(
+                &proof_of_space[offset_in_bits / u8::BITS as usize..][..bytes_to_copy],
+            );
+            // Extract `pre_x` whose last `K` bits start with `x`
+            let pre_x = u64::from_be_bytes(pre_x_bytes)
+                >> (u64::BITS as usize - (usize::from(K) + offset_in_bits % u8::BITS as usize));
+            // Convert to desired type and clear extra bits
+            let x = X::from(pre_x as u32 & (u32::MAX >> (u32::BITS as usize - usize::from(K))));
 
-                let (partial_y, partial_y_offset) = partial_y::<K>(seed, x);
-                let y = compute_f1::<K>(x, &partial_y, partial_y_offset);
+            let (partial_y, partial_y_offset) = partial_y::<K>(seed, x);
+            let y = compute_f1::<K>(x, &partial_y, partial_y_offset);
 
-                (y, Metadata::from(x))
-            })
-            .collect::<Vec<_>>();
-
-        Self::collect_ys_and_metadata::<2, 1>(&ys_and_metadata)
-            .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<3, 2>(&ys_and_metadata))
-            .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<4, 3>(&ys_and_metadata))
-            .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<5, 4>(&ys_and_metadata))
-            .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<6, 5>(&ys_and_metadata))
-            .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<7, 6>(&ys_and_metadata))
+            (y, Metadata::from(x))
+        });
+
+        Self::collect_ys_and_metadata::<2, 1, 32>(&ys_and_metadata)
+            .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<3, 2, 16>(&ys_and_metadata))
+            .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<4, 3, 8>(&ys_and_metadata))
+            .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<5, 4, 4>(&ys_and_metadata))
+            .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<6, 5, 2>(&ys_and_metadata))
+            .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<7, 6, 1>(&ys_and_metadata))
             .filter(|ys_and_metadata| {
                 let (y, _metadata) = ys_and_metadata
                     .first()
@@ -363,26 +359,35 @@ where
             })
     }
 
-    fn collect_ys_and_metadata<const TABLE_NUMBER: u8, const PARENT_TABLE_NUMBER: u8>(
+    /// Matches pairs of entries of parent table into `N` entries of the next table, `None` is
+    /// returned if any of the pairs doesn't match
+    fn collect_ys_and_metadata<
+        const TABLE_NUMBER: u8,
+        const PARENT_TABLE_NUMBER: u8,
+        const N: usize,
+    >(
         ys_and_metadata: &[(Y, Metadata<K, PARENT_TABLE_NUMBER>)],
-    ) -> Option<Vec<(Y, Metadata<K, TABLE_NUMBER>)>>
+    ) -> Option<[(Y, Metadata<K, TABLE_NUMBER>); N]>
     where
         EvaluatableUsize<{ metadata_size_bytes(K, TABLE_NUMBER) }>: Sized,
         EvaluatableUsize<{ metadata_size_bytes(K, PARENT_TABLE_NUMBER) }>: Sized,
     {
-        ys_and_metadata
-            .array_chunks::<2>()
-            .map(|&[(left_y, left_metadata), (right_y, right_metadata)]| {
-                (num_matches(left_y, right_y) == 1).then_some(compute_fn::<
-                    K,
-                    TABLE_NUMBER,
-                    PARENT_TABLE_NUMBER,
-                >(
-                    left_y,
-                    left_metadata,
-                    right_metadata,
-                ))
-            })
-            .collect()
+        let mut output = [(Y::default(), Metadata::default()); N];
+
+        for (output, &[(left_y, left_metadata), (right_y, right_metadata)]) in
+            output.iter_mut().zip(ys_and_metadata.array_chunks::<2>())
+        {
+            if num_matches(left_y, right_y) != 1 {
+                return None;
+            }
+
+            *output = compute_fn::<K, TABLE_NUMBER, PARENT_TABLE_NUMBER>(
+                left_y,
+                left_metadata,
+                right_metadata,
+            );
+        }
+
+        Some(output)
     }
 }
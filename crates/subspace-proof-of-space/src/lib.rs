@@ -40,6 +40,9 @@ pub trait TableGenerator<T: Table>: fmt::Debug + Default + Clone + Send + Sized
     ///
     /// This implementation will trade efficiency of CPU and memory usage for lower latency, prefer
     /// [`Self::generate()`] unless lower latency is critical.
+    ///
+    /// Work is done in the rayon thread pool this is called from, no thread pools are created
+    /// here, so callers are expected to install it into a thread pool that fits their CPU budget.
     #[cfg(any(feature = "parallel", test))]
     fn generate_parallel(&mut self, seed: &PosSeed) -> T {
         self.generate(seed)
@@ -70,7 +73,10 @@ pub trait Table: Sized + Send + Sync + 'static {
     /// Try to find proof at `challenge_index` if it exists
     fn find_proof(&self, challenge_index: u32) -> Option<PosProof>;
 
-    /// Check whether proof created earlier is valid and return quality bytes if yes
+    /// Check whether proof created earlier is valid and return quality bytes if yes.
+    ///
+    /// Implementations must not allocate on the heap, such that solution verification doesn't
+    /// allocate either.
     fn is_proof_valid(seed: &PosSeed, challenge_index: u32, proof: &PosProof) -> bool;
 
     /// Returns a stateful table generator with better performance
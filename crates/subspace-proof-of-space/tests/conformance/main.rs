@@ -0,0 +1,184 @@
+//! Conformance of proof of space implementations with test vectors.
+//!
+//! Vectors are stored in `vectors/<table>.txt` as text, one vector per line, such that they can be
+//! consumed by alternative implementations without depending on this crate. Set
+//! `SUBSPACE_POS_<TABLE>_VECTORS` environment variable (like `SUBSPACE_POS_CHIA_VECTORS`) to check
+//! against vectors from a different file. Tables without vectors file are skipped.
+//!
+//! New vectors can be emitted with:
+//! ```bash
+//! cargo test -p subspace-proof-of-space --test conformance -- --ignored emit_vectors
+//! ```
+
+use std::path::PathBuf;
+use std::{env, fs};
+use subspace_core_primitives::crypto::blake3_hash;
+use subspace_core_primitives::{PosProof, PosSeed};
+use subspace_proof_of_space::chia::ChiaTable;
+use subspace_proof_of_space::shim::ShimTable;
+use subspace_proof_of_space::Table;
+
+/// Number of seeds in emitted vectors
+const EMITTED_SEEDS: u32 = 3;
+/// Challenge indices of emitted vectors
+const EMITTED_CHALLENGE_INDICES: [u32; 9] = [0, 1, 2, 3, 4, 5, 1_000, 65_535, u32::MAX];
+
+struct PosVector {
+    seed: PosSeed,
+    challenge_index: u32,
+    proof: Option<PosProof>,
+}
+
+fn vectors_path(table_name: &str) -> PathBuf {
+    env::var_os(format!(
+        "SUBSPACE_POS_{}_VECTORS",
+        table_name.to_ascii_uppercase()
+    ))
+    .map(PathBuf::from)
+    .unwrap_or_else(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join(format!("tests/conformance/vectors/{table_name}.txt"))
+    })
+}
+
+fn decode_hex<const N: usize>(s: &str) -> [u8; N] {
+    assert_eq!(s.len(), N * 2, "Invalid hex length of {s}");
+
+    let mut output = [0; N];
+    for (byte, chunk) in output.iter_mut().zip(s.as_bytes().chunks_exact(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(chunk).unwrap(), 16)
+            .unwrap_or_else(|error| panic!("Invalid hex {s}: {error}"));
+    }
+    output
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn parse_vectors(contents: &str) -> Vec<PosVector> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let [seed, challenge_index, proof] = line
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap_or_else(|_| panic!("Invalid vector: {line}"));
+
+            PosVector {
+                seed: PosSeed::from(decode_hex::<{ PosSeed::SIZE }>(seed)),
+                challenge_index: challenge_index.parse().expect("Invalid challenge index"),
+                proof: (proof != "-")
+                    .then(|| PosProof::from(decode_hex::<{ PosProof::SIZE }>(proof))),
+            }
+        })
+        .collect()
+}
+
+fn format_vector(vector: &PosVector) -> String {
+    format!(
+        "{} {} {}",
+        encode_hex(vector.seed.as_slice()),
+        vector.challenge_index,
+        vector
+            .proof
+            .map(|proof| encode_hex(proof.as_slice()))
+            .unwrap_or_else(|| "-".to_string())
+    )
+}
+
+fn check_vectors<T: Table>(table_name: &str) {
+    let path = vectors_path(table_name);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            eprintln!(
+                "Skipping {table_name} vectors, failed to read {}: {error}",
+                path.display()
+            );
+            return;
+        }
+    };
+    let vectors = parse_vectors(&contents);
+    assert!(!vectors.is_empty(), "No vectors in {}", path.display());
+
+    let mut table = None::<(PosSeed, T)>;
+    for vector in vectors {
+        // Vectors are grouped by seed, avoid re-generating the same table over and over again
+        if !matches!(&table, Some((seed, _)) if *seed == vector.seed) {
+            table.replace((vector.seed, T::generate(&vector.seed)));
+        }
+        let (_, table) = table.as_ref().expect("Just inserted; qed");
+
+        assert_eq!(
+            table.find_proof(vector.challenge_index),
+            vector.proof,
+            "Proof doesn't match {table_name} vector: {}",
+            format_vector(&vector)
+        );
+
+        if let Some(proof) = &vector.proof {
+            assert!(
+                T::is_proof_valid(&vector.seed, vector.challenge_index, proof),
+                "Verification failed for {table_name} vector: {}",
+                format_vector(&vector)
+            );
+
+            let mut tampered_proof = *proof;
+            tampered_proof[PosProof::SIZE - 1] ^= 1;
+            assert!(
+                !T::is_proof_valid(&vector.seed, vector.challenge_index, &tampered_proof),
+                "Verification of tampered proof succeeded for {table_name} vector: {}",
+                format_vector(&vector)
+            );
+        }
+    }
+}
+
+fn emit_vectors_for<T: Table>(table_name: &str) {
+    let mut contents = format!(
+        "# {table_name} proof of space conformance vectors\n\
+        #\n\
+        # Format: <seed hex> <challenge index> <proof hex or \"-\" if there is no proof>\n",
+    );
+
+    for seed_index in 0..EMITTED_SEEDS {
+        let seed = PosSeed::from(blake3_hash(
+            format!("subspace-pos-conformance-{seed_index}").as_bytes(),
+        ));
+        let table = T::generate(&seed);
+
+        for challenge_index in EMITTED_CHALLENGE_INDICES {
+            contents.push_str(&format_vector(&PosVector {
+                seed,
+                challenge_index,
+                proof: table.find_proof(challenge_index),
+            }));
+            contents.push('\n');
+        }
+    }
+
+    let path = vectors_path(table_name);
+    fs::write(&path, contents)
+        .unwrap_or_else(|error| panic!("Failed to write vectors to {}: {error}", path.display()));
+}
+
+#[test]
+fn chia_vectors() {
+    check_vectors::<ChiaTable>("chia");
+}
+
+#[test]
+fn shim_vectors() {
+    check_vectors::<ShimTable>("shim");
+}
+
+#[test]
+#[ignore = "Only used to emit new vectors"]
+fn emit_vectors() {
+    emit_vectors_for::<ChiaTable>("chia");
+    emit_vectors_for::<ShimTable>("shim");
+}
@@ -40,7 +40,7 @@ pub fn prove(seed: PotSeed, iterations: NonZeroU32) -> Result<PotCheckpoints, Po
     Ok(aes::create(
         seed,
         seed.key(),
-        iterations.get() / u32::from(PotCheckpoints::NUM_CHECKPOINTS.get()),
+        PotCheckpoints::checkpoint_iterations(iterations),
     ))
 }
 
@@ -0,0 +1,154 @@
+//! Conformance of proof of time implementation with test vectors.
+//!
+//! Vectors are stored in `vectors/pot.txt` as text, one vector per line, such that they can be
+//! consumed by alternative implementations without depending on this crate. Set
+//! `SUBSPACE_POT_VECTORS` environment variable to check against vectors from a different file.
+//!
+//! New vectors can be emitted with:
+//! ```bash
+//! SUBSPACE_POT_VECTORS=pot.txt cargo test -p subspace-proof-of-time --test conformance -- --ignored emit_vectors
+//! ```
+
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::{env, fs};
+use subspace_core_primitives::crypto::blake3_hash;
+use subspace_core_primitives::{PotCheckpoints, PotOutput, PotSeed};
+
+/// Iterations of emitted vectors
+const EMITTED_ITERATIONS: [u32; 5] = [16, 32, 160, 1_600, 16_000];
+
+struct PotVector {
+    seed: PotSeed,
+    iterations: NonZeroU32,
+    checkpoints: Vec<PotOutput>,
+}
+
+fn vectors_path() -> PathBuf {
+    env::var_os("SUBSPACE_POT_VECTORS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/conformance/vectors/pot.txt")
+        })
+}
+
+fn decode_hex<const N: usize>(s: &str) -> [u8; N] {
+    assert_eq!(s.len(), N * 2, "Invalid hex length of {s}");
+
+    let mut output = [0; N];
+    for (byte, chunk) in output.iter_mut().zip(s.as_bytes().chunks_exact(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(chunk).unwrap(), 16)
+            .unwrap_or_else(|error| panic!("Invalid hex {s}: {error}"));
+    }
+    output
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn parse_vectors(contents: &str) -> Vec<PotVector> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let [seed, iterations, checkpoints] = line
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap_or_else(|_| panic!("Invalid vector: {line}"));
+
+            PotVector {
+                seed: PotSeed::from(decode_hex::<{ PotSeed::SIZE }>(seed)),
+                iterations: iterations.parse().expect("Invalid iterations"),
+                checkpoints: checkpoints
+                    .split(',')
+                    .map(|checkpoint| {
+                        PotOutput::from(decode_hex::<{ PotOutput::SIZE }>(checkpoint))
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+fn format_vector(vector: &PotVector) -> String {
+    format!(
+        "{} {} {}",
+        encode_hex(vector.seed.as_ref()),
+        vector.iterations,
+        vector
+            .checkpoints
+            .iter()
+            .map(|checkpoint| encode_hex(checkpoint.as_ref()))
+            .collect::<Vec<_>>()
+            .join(",")
+    )
+}
+
+#[test]
+fn vectors() {
+    let path = vectors_path();
+    let contents = fs::read_to_string(&path)
+        .unwrap_or_else(|error| panic!("Failed to read vectors from {}: {error}", path.display()));
+    let vectors = parse_vectors(&contents);
+    assert!(!vectors.is_empty(), "No vectors in {}", path.display());
+
+    for vector in vectors {
+        let checkpoints = subspace_proof_of_time::prove(vector.seed, vector.iterations).unwrap();
+        assert_eq!(
+            checkpoints.as_slice(),
+            vector.checkpoints.as_slice(),
+            "Proving doesn't match vector: {}",
+            format_vector(&vector)
+        );
+
+        assert!(
+            subspace_proof_of_time::verify(vector.seed, vector.iterations, &vector.checkpoints)
+                .unwrap(),
+            "Verification failed for vector: {}",
+            format_vector(&vector)
+        );
+
+        let mut tampered_checkpoints = vector.checkpoints.clone();
+        let last_checkpoint = tampered_checkpoints.last_mut().unwrap();
+        *last_checkpoint = PotOutput::from(last_checkpoint.map(|byte| byte ^ 1));
+        assert!(
+            !subspace_proof_of_time::verify(vector.seed, vector.iterations, &tampered_checkpoints)
+                .unwrap(),
+            "Verification of tampered checkpoints succeeded for vector: {}",
+            format_vector(&vector)
+        );
+    }
+}
+
+#[test]
+#[ignore = "Only used to emit new vectors"]
+fn emit_vectors() {
+    let mut contents = String::from(
+        "# Proof of time conformance vectors\n\
+        #\n\
+        # Format: <seed hex> <iterations> <8 checkpoints hex, comma-separated>\n",
+    );
+
+    for (index, iterations) in EMITTED_ITERATIONS.into_iter().enumerate() {
+        let mut seed = PotSeed::default();
+        seed.copy_from_slice(
+            &blake3_hash(format!("subspace-pot-conformance-{index}").as_bytes())[..PotSeed::SIZE],
+        );
+        let iterations = NonZeroU32::new(iterations).unwrap();
+        let checkpoints: PotCheckpoints = subspace_proof_of_time::prove(seed, iterations).unwrap();
+
+        contents.push_str(&format_vector(&PotVector {
+            seed,
+            iterations,
+            checkpoints: checkpoints.to_vec(),
+        }));
+        contents.push('\n');
+    }
+
+    let path = vectors_path();
+    fs::write(&path, contents)
+        .unwrap_or_else(|error| panic!("Failed to write vectors to {}: {error}", path.display()));
+}
@@ -16,15 +16,22 @@
 //! Primitives for Subspace RPC.
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::num::NonZeroU32;
 use std::time::Duration;
 use subspace_core_primitives::{
-    Blake3Hash, PublicKey, RewardSignature, SlotNumber, Solution, SolutionRange,
+    Blake3Hash, BlockNumber, GlobalChallenge, HistorySize, PieceIndex, PublicKey, RewardSignature,
+    SBucket, SectorId, SegmentIndex, SlotNumber, Solution, SolutionRange,
 };
 use subspace_farmer_components::FarmerProtocolInfo;
 use subspace_networking::libp2p::Multiaddr;
+use subspace_networking::{BandwidthStats, BandwidthUsage};
 
 /// Defines a limit for number of segments that can be requested over RPC
 pub const MAX_SEGMENT_HEADERS_PER_REQUEST: usize = 1000;
+/// Defines a limit for number of solution range samples that can be requested over RPC
+pub const MAX_SOLUTION_RANGE_SAMPLES_PER_REQUEST: usize = 1000;
 
 /// Information necessary for farmer application
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,11 +57,17 @@ pub struct SlotInfo {
     /// Slot number
     pub slot_number: SlotNumber,
     /// Global slot challenge
-    pub global_challenge: Blake3Hash,
+    pub global_challenge: GlobalChallenge,
     /// Acceptable solution range for block authoring
     pub solution_range: SolutionRange,
     /// Acceptable solution range for voting
     pub voting_solution_range: SolutionRange,
+    /// Number of blocks waiting in node's import queue when slot arrived
+    #[serde(default)]
+    pub import_queue_depth: u32,
+    /// Number of slots between the slot of node's best block and this slot
+    #[serde(default)]
+    pub best_block_slot_lag: SlotNumber,
 }
 
 /// Response of a slot challenge consisting of an optional solution and
@@ -70,6 +83,35 @@ pub struct SolutionResponse {
     pub solution: Solution<PublicKey, PublicKey>,
 }
 
+/// JSON-RPC error code returned on solution submission when solution references history that node
+/// doesn't know about yet, error data is [`FutureHistorySize`]
+pub const FUTURE_HISTORY_SIZE_ERROR_CODE: i32 = 1000;
+
+/// Solution references history size that node doesn't know about yet.
+///
+/// This happens when farmer plots faster than node syncs, solutions for sectors with such history
+/// size can be submitted again once node's history size catches up.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FutureHistorySize {
+    /// History size solution was created for
+    pub solution_history_size: HistorySize,
+    /// Current history size known to the node
+    pub current_history_size: HistorySize,
+}
+
+impl fmt::Display for FutureHistorySize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Solution history size {} is ahead of node's history size {}",
+            self.solution_history_size, self.current_history_size
+        )
+    }
+}
+
+impl std::error::Error for FutureHistorySize {}
+
 /// Reward info that needs to be signed.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -92,3 +134,227 @@ pub struct RewardSignatureResponse {
     /// Pre-header or vote hash signature.
     pub signature: Option<RewardSignature>,
 }
+
+/// Outcome of an individual check of solution inspection.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SolutionCheckOutcome {
+    /// Check passed
+    Passed,
+    /// Check failed
+    Failed,
+    /// Check was not performed due to missing inputs or failure of a check it depends on
+    Skipped,
+}
+
+/// Report of solution inspection with outcome of every check and values computed along the way.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolutionInspection {
+    /// Whether solution is valid for block production (all checks passed)
+    pub valid_for_block: bool,
+    /// Whether solution is valid for voting (all checks passed, except it might be outside of
+    /// block solution range)
+    pub valid_for_vote: bool,
+    /// Sector ID derived from public key and sector index
+    pub sector_id: SectorId,
+    /// Sector slot challenge derived from sector ID and global challenge
+    #[serde(with = "hex::serde")]
+    pub sector_slot_challenge: Blake3Hash,
+    /// S-bucket that was supposed to be audited
+    pub s_bucket_audit_index: SBucket,
+    /// Whether proof of space is valid
+    pub proof_of_space: SolutionCheckOutcome,
+    /// Audit chunk derived from masked chunk, interpreted as solution range
+    pub audit_chunk: SolutionRange,
+    /// Distance between global challenge and audit chunk
+    pub solution_distance: SolutionRange,
+    /// Whether solution distance is within half of block solution range
+    pub solution_range: SolutionCheckOutcome,
+    /// Whether solution distance is within half of voting solution range
+    pub voting_solution_range: SolutionCheckOutcome,
+    /// Whether chunk belongs to the record according to record commitment and chunk witness
+    pub chunk_witness: SolutionCheckOutcome,
+    /// Whether piece offset is within the number of pieces in sector
+    pub piece_offset: SolutionCheckOutcome,
+    /// History size at which sector expires, if sector expiration was checked
+    pub expiration_history_size: Option<HistorySize>,
+    /// Whether sector is not yet expired
+    pub sector_expiration: SolutionCheckOutcome,
+    /// Index of the piece that solution was created for, if piece was checked
+    pub piece_index: Option<PieceIndex>,
+    /// Whether piece is part of the blockchain history according to segment commitment
+    pub piece: SolutionCheckOutcome,
+}
+
+/// Status of an object carried by a data-carrying extrinsic that is on its way to archival
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum ObjectStatus {
+    /// Extrinsic carrying the object is in the transaction pool
+    #[serde(rename_all = "camelCase")]
+    Pending {
+        /// Hash of the extrinsic
+        #[serde(with = "hex::serde")]
+        extrinsic_hash: [u8; 32],
+    },
+    /// Extrinsic carrying the object was included in the best chain
+    #[serde(rename_all = "camelCase")]
+    InBlock {
+        /// Hash of the block extrinsic was included in
+        #[serde(with = "hex::serde")]
+        block_hash: [u8; 32],
+        /// Number of the block extrinsic was included in
+        block_number: BlockNumber,
+        /// Hash of the extrinsic
+        #[serde(with = "hex::serde")]
+        extrinsic_hash: [u8; 32],
+        /// Index of the extrinsic within the block
+        extrinsic_index: u32,
+    },
+}
+
+/// Information about inclusion of segment header into the consensus chain, can be used to chart
+/// history growth over time or to estimate sector expiration in wall-clock terms
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentArchivalInfo {
+    /// Segment index
+    pub segment_index: SegmentIndex,
+    /// Number of the consensus block that included segment header
+    pub block_number: BlockNumber,
+    /// Timestamp (milliseconds since Unix epoch) of the consensus block that included segment
+    /// header
+    pub timestamp: u64,
+}
+
+/// Change of proof of time parameters scheduled by the consensus chain
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PotParametersChange {
+    /// Slot at which change of parameters takes effect
+    pub slot: SlotNumber,
+    /// New number of slot iterations
+    pub slot_iterations: NonZeroU32,
+    /// Entropy that will be injected at this slot
+    #[serde(with = "hex::serde")]
+    pub entropy: Blake3Hash,
+}
+
+/// Entropy collected for injection into proof of time chain, whose target slot is not known yet
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingPotEntropy {
+    /// Number of the block entropy was collected at
+    pub block_number: BlockNumber,
+    /// Collected entropy
+    #[serde(with = "hex::serde")]
+    pub entropy: Blake3Hash,
+}
+
+/// Proof of time parameters and their future schedule as of the best block, can be used to track
+/// proof of time difficulty trajectory
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PotSchedule {
+    /// Number of iterations for proof of time per slot
+    pub slot_iterations: NonZeroU32,
+    /// Scheduled changes of parameters in order of slots at which they take effect
+    pub scheduled_changes: Vec<PotParametersChange>,
+    /// Collected entropy that doesn't have target slot yet, in order of block numbers
+    pub pending_entropy: Vec<PendingPotEntropy>,
+    /// Interval, in blocks, between blocks that are sources of entropy
+    pub entropy_injection_interval: BlockNumber,
+    /// Interval, in entropy injection intervals, where to take entropy for injection from
+    pub entropy_injection_lookback_depth: u8,
+    /// Delay after block, in slots, when entropy injection takes effect
+    pub entropy_injection_delay: SlotNumber,
+}
+
+/// Solution range of a block in the canonical chain
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolutionRangeSample {
+    /// Block number
+    pub block_number: BlockNumber,
+    /// Slot block was produced in
+    pub slot_number: SlotNumber,
+    /// Solution range block was produced with
+    pub solution_range: SolutionRange,
+}
+
+/// Historical solution ranges along with chain parameters necessary to interpret them, can be used
+/// to estimate farming rewards and network growth
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolutionRangeHistory {
+    /// Slot duration
+    pub slot_duration: Duration,
+    /// Probability of a slot having a block, as a fraction
+    pub slot_probability: (u64, u64),
+    /// Max number of pieces in a sector
+    pub max_pieces_in_sector: u16,
+    /// Voting solution range as of the best block
+    pub voting_solution_range: SolutionRange,
+    /// Samples in order of block numbers, oldest first
+    pub samples: Vec<SolutionRangeSample>,
+}
+
+/// Bytes transferred over DSN, totals are since node start
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DsnBandwidthUsage {
+    /// Bytes sent since node start
+    pub sent_total: u64,
+    /// Bytes received since node start
+    pub received_total: u64,
+    /// Bytes sent during the window
+    pub sent_in_window: u64,
+    /// Bytes received during the window
+    pub received_in_window: u64,
+}
+
+impl From<BandwidthUsage> for DsnBandwidthUsage {
+    fn from(usage: BandwidthUsage) -> Self {
+        Self {
+            sent_total: usage.sent_total,
+            received_total: usage.received_total,
+            sent_in_window: usage.sent_in_window,
+            received_in_window: usage.received_in_window,
+        }
+    }
+}
+
+/// DSN bandwidth usage per libp2p protocol and per peer
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DsnBandwidth {
+    /// Duration of the rolling window `*_in_window` values are reported for
+    pub window: Duration,
+    /// Bandwidth usage of all protocols combined
+    pub total: DsnBandwidthUsage,
+    /// Bandwidth usage per libp2p protocol
+    pub per_protocol: BTreeMap<String, DsnBandwidthUsage>,
+    /// Bandwidth usage per peer ID, only includes peers that were active during the window
+    pub per_peer: BTreeMap<String, DsnBandwidthUsage>,
+}
+
+impl DsnBandwidth {
+    /// Create from DSN bandwidth stats collected over rolling `window`
+    pub fn new(stats: BandwidthStats, window: Duration) -> Self {
+        Self {
+            window,
+            total: stats.total.into(),
+            per_protocol: stats
+                .per_protocol
+                .into_iter()
+                .map(|(protocol, usage)| (protocol, usage.into()))
+                .collect(),
+            per_peer: stats
+                .per_peer
+                .into_iter()
+                .map(|(peer_id, usage)| (peer_id.to_string(), usage.into()))
+                .collect(),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Balances, Runtime, RuntimeCall, TransactionFees};
+use crate::{AppRegistry, Balances, CheckDataInclusion, Runtime, RuntimeCall, TransactionFees};
 use codec::Encode;
 use frame_support::traits::{Currency, ExistenceRequirement, Get, Imbalance, WithdrawReasons};
 use pallet_balances::NegativeImbalance;
@@ -16,11 +16,18 @@ impl Get<Balance> for TransactionByteFee {
 
 pub struct LiquidityInfo {
     storage_fee: Balance,
+    /// Size of data carried by the call in bytes if it is data-carrying
+    data_bytes: Option<u32>,
     imbalance: NegativeImbalance<Runtime>,
 }
 
 /// Implementation of [`pallet_transaction_payment::OnChargeTransaction`] that charges transaction
 /// fees and distributes storage/compute fees and tip separately.
+///
+/// Data-carrying extrinsics get a part of their compute fee rebated (see
+/// [`DataFeeRebate`](pallet_transaction_fees::Config::DataFeeRebate)), the rest of compute fee is
+/// accounted as storage fee. Applications registered in `pallet-app-registry` additionally get
+/// their fee discount on whatever remains of the fee (excluding tip) after the rebate.
 pub struct OnChargeTransaction;
 
 impl pallet_transaction_payment::OnChargeTransaction<Runtime> for OnChargeTransaction {
@@ -53,12 +60,15 @@ impl pallet_transaction_payment::OnChargeTransaction<Runtime> for OnChargeTransa
         let imbalance = withdraw_result.map_err(|_error| InvalidTransaction::Payment)?;
 
         // Separate storage fee while we have access to the call data structure to calculate it.
+        let call_size = call.encoded_size();
         let storage_fee = TransactionByteFee::get()
-            * Balance::try_from(call.encoded_size())
+            * Balance::try_from(call_size)
                 .expect("Size of the call never exceeds balance units; qed");
+        let data_bytes = CheckDataInclusion::data_size(call);
 
         Ok(Some(LiquidityInfo {
             storage_fee,
+            data_bytes,
             imbalance,
         }))
     }
@@ -73,15 +83,32 @@ impl pallet_transaction_payment::OnChargeTransaction<Runtime> for OnChargeTransa
     ) -> Result<(), TransactionValidityError> {
         if let Some(LiquidityInfo {
             storage_fee,
+            data_bytes,
             imbalance,
         }) = liquidity_info
         {
+            let (rebate, discount) = if data_bytes.is_some() {
+                let compute_fee = corrected_fee
+                    .saturating_sub(tip)
+                    .saturating_sub(storage_fee);
+                let rebate = TransactionFees::data_fee_rebate(compute_fee);
+                let discount = AppRegistry::fee_discount(who)
+                    .mul_floor(corrected_fee.saturating_sub(tip).saturating_sub(rebate));
+                (rebate, discount)
+            } else {
+                (Zero::zero(), Zero::zero())
+            };
             // Calculate how much refund we should return
-            let refund_amount = imbalance.peek().saturating_sub(corrected_fee);
+            let refund_amount = imbalance
+                .peek()
+                .saturating_sub(corrected_fee)
+                .saturating_add(rebate)
+                .saturating_add(discount);
             // Refund to the the account that paid the fees. If this fails, the account might have
             // dropped below the existential balance. In that case we don't refund anything.
             let refund_imbalance = Balances::deposit_into_existing(who, refund_amount)
                 .unwrap_or_else(|_| <Balances as Currency<AccountId>>::PositiveImbalance::zero());
+            let refund_imbalance_amount = refund_imbalance.peek();
             // Merge the imbalance caused by paying the fees and refunding parts of it again.
             let adjusted_paid = imbalance
                 .offset(refund_imbalance)
@@ -93,11 +120,23 @@ impl pallet_transaction_payment::OnChargeTransaction<Runtime> for OnChargeTransa
             // Split paid storage and compute fees so that they can be distributed separately.
             let (paid_storage_fee, paid_compute_fee) = fee.split(storage_fee);
 
-            TransactionFees::note_transaction_fees(
-                paid_storage_fee.peek(),
-                paid_compute_fee.peek(),
-                tip.peek(),
-            );
+            if let Some(data_bytes) = data_bytes {
+                // Refund might have failed, in which case nothing was actually rebated
+                let rebate = rebate.min(refund_imbalance_amount);
+                TransactionFees::note_data_fee_rebate(who.clone(), data_bytes, rebate);
+                // Whatever remains of compute fee after rebate is accounted as storage fee
+                TransactionFees::note_transaction_fees(
+                    paid_storage_fee.peek() + paid_compute_fee.peek(),
+                    Zero::zero(),
+                    tip.peek(),
+                );
+            } else {
+                TransactionFees::note_transaction_fees(
+                    paid_storage_fee.peek(),
+                    paid_compute_fee.peek(),
+                    tip.peek(),
+                );
+            }
         }
         Ok(())
     }
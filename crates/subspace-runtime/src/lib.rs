@@ -29,8 +29,8 @@ mod signed_extensions;
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use crate::fees::{OnChargeTransaction, TransactionByteFee};
-use crate::object_mapping::extract_block_object_mapping;
-pub use crate::signed_extensions::{CheckStorageAccess, DisablePallets};
+use crate::object_mapping::{extract_block_object_mapping, OBJECT_MAPPING_LIMITS};
+pub use crate::signed_extensions::{CheckDataInclusion, CheckStorageAccess, DisablePallets};
 use codec::{Decode, Encode, MaxEncodedLen};
 use core::mem;
 use core::num::NonZeroU64;
@@ -46,15 +46,16 @@ use frame_support::weights::constants::{RocksDbWeight, WEIGHT_REF_TIME_PER_SECON
 use frame_support::weights::{ConstantMultiplier, IdentityFee, Weight};
 use frame_support::{construct_runtime, parameter_types, PalletId};
 use frame_system::limits::{BlockLength, BlockWeights};
-use frame_system::EnsureNever;
+use frame_system::{EnsureNever, EnsureRoot};
 pub use pallet_subspace::{AllowAuthoringBy, EnableRewardsAt};
 use pallet_transporter::EndpointHandler;
 use scale_info::TypeInfo;
 use sp_api::impl_runtime_apis;
+use sp_app_registry::{AppQuota, RemainingQuota};
 use sp_consensus_slots::{Slot, SlotDuration};
 use sp_consensus_subspace::{
-    ChainConstants, EquivocationProof, FarmerPublicKey, PotParameters, SignedVote, SolutionRanges,
-    Vote,
+    ChainConstants, EquivocationProof, FarmerPublicKey, PotParameters, PotSchedule,
+    SegmentArchivalInfo, SignedVote, SolutionRanges, Vote,
 };
 use sp_core::crypto::{ByteArray, KeyTypeId};
 use sp_core::{OpaqueMetadata, H256};
@@ -65,7 +66,9 @@ use sp_domains::{
 };
 use sp_domains_fraud_proof::fraud_proof::FraudProof;
 use sp_messenger::endpoint::{Endpoint, EndpointHandler as EndpointHandlerT, EndpointId};
-use sp_messenger::messages::{BlockMessagesWithStorageKey, ChainId, CrossDomainMessage, MessageId};
+use sp_messenger::messages::{
+    BlockMessagesWithStorageKey, ChainId, CrossDomainMessage, DeadLetter, MessageId,
+};
 use sp_messenger_host_functions::{get_storage_key, StorageKeyRequest};
 use sp_mmr_primitives::{EncodableOpaqueLeaf, Proof};
 use sp_runtime::traits::{
@@ -78,10 +81,10 @@ use sp_std::marker::PhantomData;
 use sp_std::prelude::*;
 use sp_version::RuntimeVersion;
 use static_assertions::const_assert;
-use subspace_core_primitives::objects::BlockObjectMapping;
+use subspace_core_primitives::objects::{BlockObjectMapping, ObjectMappingLimits};
 use subspace_core_primitives::{
-    HistorySize, Piece, Randomness, Record, SegmentCommitment, SegmentHeader, SegmentIndex,
-    SlotNumber, SolutionRange, U256,
+    Blake3Hash, HistorySize, Piece, Randomness, Record, SegmentCommitment, SegmentHeader,
+    SegmentIndex, SlotNumber, SolutionRange, U256,
 };
 use subspace_runtime_primitives::{
     AccountId, Balance, BlockNumber, FindBlockRewardAddress, Hash, Moment, Nonce, Signature,
@@ -108,10 +111,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("subspace"),
     impl_name: create_runtime_str!("subspace"),
     authoring_version: 0,
-    spec_version: 2,
+    spec_version: 3,
     impl_version: 0,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 0,
+    transaction_version: 1,
     state_version: 0,
     extrinsic_state_version: 0,
 };
@@ -202,6 +205,17 @@ const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
 /// Maximum block length for non-`Normal` extrinsic is 5 MiB.
 const MAX_BLOCK_LENGTH: u32 = 5 * 1024 * 1024;
 
+/// Maximum number of bytes of data-carrying extrinsics (remarks) in a block is 2 MiB, which leaves
+/// room for other `Normal` extrinsics even when data submissions are abundant.
+const MAX_DATA_BYTES_PER_BLOCK: u32 = 2 * 1024 * 1024;
+
+/// Portion of compute fee rebated for data-carrying extrinsics, since their data is already paid
+/// for with per-byte storage fee.
+const DATA_FEE_REBATE: Perbill = Perbill::from_percent(50);
+
+/// Number of blocks in a day, used for daily data upload quotas of registered applications.
+const BLOCKS_PER_DAY: BlockNumber = (24 * 60 * 60 * 1000 / MILLISECS_PER_BLOCK) as BlockNumber;
+
 /// Computes the following:
 /// ```
 /// MAX * slot_probability / (pieces_in_sector * chunks / s_buckets) / sectors
@@ -351,6 +365,7 @@ impl pallet_subspace::Config for Runtime {
         OffencesSubspace,
         ConstU64<{ EQUIVOCATION_REPORT_LONGEVITY as u64 }>,
     >;
+    type UnixTime = Timestamp;
 
     type WeightInfo = pallet_subspace::weights::SubstrateWeight<Runtime>;
 }
@@ -427,6 +442,7 @@ parameter_types! {
     };
     pub BlockchainHistorySize: u128 = u128::from(Subspace::archived_history_size());
     pub DynamicCostOfStorage: bool = RuntimeConfigs::enable_dynamic_cost_of_storage();
+    pub const DataFeeRebate: Perbill = DATA_FEE_REBATE;
 }
 
 impl pallet_transaction_fees::Config for Runtime {
@@ -438,6 +454,15 @@ impl pallet_transaction_fees::Config for Runtime {
     type Currency = Balances;
     type FindBlockRewardAddress = Subspace;
     type DynamicCostOfStorage = DynamicCostOfStorage;
+    type MaxDataBytesPerBlock = ConstU32<MAX_DATA_BYTES_PER_BLOCK>;
+    type DataFeeRebate = DataFeeRebate;
+    type WeightInfo = ();
+}
+
+impl pallet_app_registry::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RegistrarOrigin = EnsureRoot<AccountId>;
+    type BlocksPerDay = ConstU32<BLOCKS_PER_DAY>;
     type WeightInfo = ();
 }
 
@@ -465,6 +490,7 @@ impl pallet_sudo::Config for Runtime {
 
 parameter_types! {
     pub const RelayConfirmationDepth: BlockNumber = 18;
+    pub const MaxDeadLetters: u32 = 100;
     pub const SelfChainId: ChainId = ChainId::Consensus;
 }
 
@@ -534,6 +560,9 @@ impl pallet_messenger::Config for Runtime {
     type MmrHash = mmr::Hash;
     type MmrProofVerifier = MmrProofVerifier;
     type StorageKeys = StorageKeys;
+    type ChainAllowlistOrigin = EnsureRoot<AccountId>;
+    type DeadLetterOrigin = EnsureRoot<AccountId>;
+    type MaxDeadLetters = MaxDeadLetters;
 }
 
 impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
@@ -583,6 +612,8 @@ parameter_types! {
     pub const DomainInstantiationDeposit: Balance = 100 * SSC;
     pub const MaxDomainNameLength: u32 = 32;
     pub const BlockTreePruningDepth: u32 = 14_400;
+    /// Roughly a week worth of domain blocks with 6 second block time
+    pub const ConfirmedDomainBlockMappingDepth: u32 = 100_800;
     pub const StakeWithdrawalLockingPeriod: DomainNumber = 14_400;
     // TODO: revisit these. For now epoch every 10 mins for a 6 second block and only 100 number of staking
     // operations allowed within each epoch.
@@ -594,6 +625,7 @@ parameter_types! {
     pub const DomainsPalletId: PalletId = PalletId(*b"domains_");
     pub const MaxInitialDomainAccounts: u32 = 10;
     pub const MinInitialDomainAccountBalance: Balance = SSC;
+    pub const MaxDomainTreasuryShare: Perbill = Perbill::from_percent(50);
 }
 
 // Minimum operator stake must be >= minimum nominator stake since operator is also a nominator.
@@ -634,6 +666,7 @@ impl pallet_domains::Config for Runtime {
     type MaxDomainNameLength = MaxDomainNameLength;
     type Share = Balance;
     type BlockTreePruningDepth = BlockTreePruningDepth;
+    type ConfirmedDomainBlockMappingDepth = ConfirmedDomainBlockMappingDepth;
     type StakeWithdrawalLockingPeriod = StakeWithdrawalLockingPeriod;
     type StakeEpochDuration = StakeEpochDuration;
     type TreasuryAccount = TreasuryAccount;
@@ -647,6 +680,7 @@ impl pallet_domains::Config for Runtime {
     type DomainsTransfersTracker = Transporter;
     type MaxInitialDomainAccounts = MaxInitialDomainAccounts;
     type MinInitialDomainAccountBalance = MinInitialDomainAccountBalance;
+    type MaxDomainTreasuryShare = MaxDomainTreasuryShare;
 }
 
 parameter_types! {
@@ -721,6 +755,7 @@ construct_runtime!(
 
         Domains: pallet_domains = 12,
         RuntimeConfigs: pallet_runtime_configs = 14,
+        AppRegistry: pallet_app_registry = 15,
 
         Vesting: orml_vesting = 13,
 
@@ -756,6 +791,7 @@ pub type SignedExtra = (
     pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
     CheckStorageAccess,
     DisablePallets,
+    CheckDataInclusion,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic =
@@ -821,6 +857,7 @@ mod benches {
     frame_benchmarking::define_benchmarks!(
         [frame_benchmarking, BaselineBench::<Runtime>]
         [frame_system, SystemBench::<Runtime>]
+        [pallet_app_registry, AppRegistry]
         [pallet_balances, Balances]
         [pallet_domains, Domains]
         [pallet_mmr, Mmr]
@@ -905,6 +942,25 @@ impl_runtime_apis! {
             // No pallets produce objects right now
             Vec::new()
         }
+
+        fn object_mapping_limits() -> ObjectMappingLimits {
+            OBJECT_MAPPING_LIMITS
+        }
+
+        fn extrinsic_object_hashes(_extrinsic: <Block as BlockT>::Extrinsic) -> Vec<Blake3Hash> {
+            // No pallets produce objects right now
+            Vec::new()
+        }
+    }
+
+    impl sp_app_registry::AppRegistryApi<Block, AccountId> for Runtime {
+        fn app_quota(account: AccountId) -> Option<AppQuota> {
+            AppRegistry::app_quota(&account)
+        }
+
+        fn remaining_quota(account: AccountId) -> Option<RemainingQuota> {
+            AppRegistry::remaining_quota(&account)
+        }
     }
 
     impl sp_consensus_subspace::SubspaceApi<Block, FarmerPublicKey> for Runtime {
@@ -998,6 +1054,14 @@ impl_runtime_apis! {
                 min_sector_lifetime: MinSectorLifetime::get(),
             }
         }
+
+        fn segment_archival_info(segment_index: SegmentIndex) -> Option<SegmentArchivalInfo> {
+            Subspace::segment_archival_info(segment_index)
+        }
+
+        fn pot_schedule() -> PotSchedule {
+            Subspace::pot_schedule()
+        }
     }
 
     impl sp_domains::DomainsApi<Block, DomainHeader> for Runtime {
@@ -1104,6 +1168,14 @@ impl_runtime_apis! {
         fn consensus_chain_byte_fee() -> Balance {
             DOMAIN_STORAGE_FEE_MULTIPLIER * TransactionFees::transaction_byte_fee()
         }
+
+        fn domain_treasury_balance(domain_id: DomainId) -> Balance {
+            Domains::domain_treasury_balance(domain_id)
+        }
+
+        fn confirmed_domain_block_consensus_number(domain_id: DomainId, domain_number: DomainNumber) -> Option<BlockNumber> {
+            Domains::confirmed_domain_block_consensus_number(domain_id, domain_number)
+        }
     }
 
     impl sp_domains::BundleProducerElectionApi<Block, Balance> for Runtime {
@@ -1114,6 +1186,10 @@ impl_runtime_apis! {
         fn operator(operator_id: OperatorId) -> Option<(OperatorPublicKey, Balance)> {
             Domains::operator(operator_id)
         }
+
+        fn operator_pending_signing_key(operator_id: OperatorId) -> Option<OperatorPublicKey> {
+            Domains::operator_pending_signing_key(operator_id)
+        }
     }
 
     impl sp_session::SessionKeys<Block> for Runtime {
@@ -1173,6 +1249,10 @@ impl_runtime_apis! {
         fn inbox_response_storage_key(message_id: MessageId) -> Vec<u8> {
             Messenger::inbox_response_storage_key(message_id)
         }
+
+        fn dead_letters() -> Vec<(ChainId, MessageId, DeadLetter<BlockNumber>)> {
+            Messenger::dead_letters()
+        }
     }
 
     impl sp_messenger::RelayerApi<Block, BlockNumber> for Runtime {
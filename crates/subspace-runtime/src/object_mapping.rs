@@ -2,11 +2,20 @@ use crate::{Block, Runtime, RuntimeCall};
 use codec::{Compact, CompactLen, Encode};
 use sp_std::iter::Peekable;
 use sp_std::prelude::*;
-use subspace_core_primitives::objects::{BlockObject, BlockObjectMapping};
+use subspace_core_primitives::objects::{
+    BlockObject, BlockObjectMapping, ObjectMappingLimits, ObjectMappingOverflowPolicy,
+};
 use subspace_runtime_primitives::Hash;
 
 const MAX_OBJECT_MAPPING_RECURSION_DEPTH: u16 = 5;
 
+/// Limits on object mapping of a single block, objects past the limits are not mapped
+pub(crate) const OBJECT_MAPPING_LIMITS: ObjectMappingLimits = ObjectMappingLimits {
+    max_objects: 1_000,
+    max_size: 64 * 1024,
+    overflow_policy: ObjectMappingOverflowPolicy::Truncate,
+};
+
 pub(crate) fn extract_utility_block_object_mapping<I: Iterator<Item = Hash>>(
     mut base_offset: u32,
     objects: &mut Vec<BlockObject>,
@@ -1,11 +1,19 @@
-use crate::{Runtime, RuntimeCall, RuntimeConfigs, Sudo};
+use crate::{
+    AppRegistry, Runtime, RuntimeCall, RuntimeConfigs, Sudo, TransactionFees, TransactionPayment,
+};
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
-use sp_runtime::traits::{DispatchInfoOf, SignedExtension};
+use sp_runtime::traits::{DispatchInfoOf, SignedExtension, Zero};
 use sp_runtime::transaction_validity::{
-    InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+    InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError,
+    ValidTransaction,
 };
 use sp_std::prelude::*;
+use subspace_runtime_primitives::Balance;
+
+/// Maximum depth of nested utility calls inspected when looking for data-carrying calls.
+const MAX_DATA_CALL_RECURSION_DEPTH: u16 = 5;
+
 /// Controls non-root access to feeds and object store
 #[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, Default, TypeInfo)]
 pub struct CheckStorageAccess;
@@ -107,3 +115,234 @@ impl SignedExtension for DisablePallets {
         }
     }
 }
+
+/// Size of arbitrary user data (remarks) carried by the call, possibly wrapped into utility calls,
+/// `None` if call doesn't carry any data.
+fn call_data_size(call: &RuntimeCall, recursion_depth_left: u16) -> Option<u32> {
+    if recursion_depth_left == 0 {
+        // Treat excessively nested calls as data-carrying to be on the safe side
+        return Some(u32::try_from(call.encoded_size()).unwrap_or(u32::MAX));
+    }
+
+    match call {
+        RuntimeCall::System(
+            frame_system::Call::remark { remark }
+            | frame_system::Call::remark_with_event { remark },
+        ) => Some(u32::try_from(remark.len()).unwrap_or(u32::MAX)),
+        RuntimeCall::Utility(
+            pallet_utility::Call::batch { calls }
+            | pallet_utility::Call::batch_all { calls }
+            | pallet_utility::Call::force_batch { calls },
+        ) => calls
+            .iter()
+            .filter_map(|call| call_data_size(call, recursion_depth_left - 1))
+            .reduce(u32::saturating_add),
+        RuntimeCall::Utility(
+            pallet_utility::Call::as_derivative { call, .. }
+            | pallet_utility::Call::dispatch_as { call, .. }
+            | pallet_utility::Call::with_weight { call, .. },
+        ) => call_data_size(call, recursion_depth_left - 1),
+        _ => None,
+    }
+}
+
+/// Limits the total size of data-carrying extrinsics in a block.
+///
+/// Data carried by extrinsics (remark payloads) is capped at
+/// [`MaxDataBytesPerBlock`](pallet_transaction_fees::Config::MaxDataBytesPerBlock) bytes per block
+/// regardless of remaining block weight, such that large data submissions can't crowd out
+/// transfers and other extrinsics. Extrinsics that don't fit into the current block are rejected
+/// with [`InvalidTransaction::ExhaustsResources`] during block building and stay in the
+/// transaction pool for subsequent blocks.
+///
+/// Data-carrying extrinsics get transaction pool priority proportional to their fee per byte of
+/// data, such that competing data submissions are ordered by what they pay for the scarce block
+/// data space. It is combined with tip-based priority of `ChargeTransactionPayment`.
+///
+/// Data-carrying extrinsics of applications registered in `pallet-app-registry` are additionally
+/// limited by per-block and per-day quotas of the application.
+#[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, Default, TypeInfo)]
+pub struct CheckDataInclusion;
+
+impl CheckDataInclusion {
+    /// Size of data carried by `call` in bytes, `None` if call doesn't carry data.
+    pub(crate) fn data_size(call: &RuntimeCall) -> Option<u32> {
+        call_data_size(call, MAX_DATA_CALL_RECURSION_DEPTH)
+    }
+
+    /// Priority of data-carrying extrinsic of `len` bytes with `data_size` bytes of data, which
+    /// is its fee (excluding tip) per byte of data.
+    fn priority(
+        info: &DispatchInfoOf<RuntimeCall>,
+        len: usize,
+        data_size: u32,
+    ) -> TransactionPriority {
+        let fee = TransactionPayment::compute_fee(
+            u32::try_from(len).unwrap_or(u32::MAX),
+            info,
+            Zero::zero(),
+        );
+        let fee_per_byte = fee / Balance::from(data_size.max(1));
+
+        TransactionPriority::try_from(fee_per_byte).unwrap_or(TransactionPriority::MAX)
+    }
+}
+
+impl SignedExtension for CheckDataInclusion {
+    const IDENTIFIER: &'static str = "CheckDataInclusion";
+    type AccountId = <Runtime as frame_system::Config>::AccountId;
+    type Call = <Runtime as frame_system::Config>::RuntimeCall;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> TransactionValidity {
+        let Some(data_size) = Self::data_size(call) else {
+            return Ok(ValidTransaction::default());
+        };
+
+        if !TransactionFees::data_fits_into_block(data_size)
+            || !AppRegistry::data_fits_into_quota(who, data_size)
+        {
+            return InvalidTransaction::ExhaustsResources.into();
+        }
+
+        Ok(ValidTransaction {
+            priority: Self::priority(info, len, data_size),
+            ..ValidTransaction::default()
+        })
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        if let Some(data_size) = Self::data_size(call) {
+            // Application quota is checked first, such that block data bytes are not accounted for
+            // extrinsic that is rejected afterwards
+            if !AppRegistry::has_remaining_quota(who, data_size)
+                || !TransactionFees::try_note_data_bytes(data_size)
+            {
+                return Err(InvalidTransaction::ExhaustsResources.into());
+            }
+            AppRegistry::note_data_bytes(who, data_size);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CheckDataInclusion;
+    use crate::{RuntimeCall, MAX_DATA_BYTES_PER_BLOCK};
+    use codec::Encode;
+    use frame_support::dispatch::DispatchInfo;
+    use frame_support::weights::Weight;
+    use sp_runtime::traits::SignedExtension;
+    use sp_runtime::transaction_validity::{
+        InvalidTransaction, TransactionValidity, TransactionValidityError,
+    };
+    use subspace_runtime_primitives::AccountId;
+
+    fn remark(size: u32) -> RuntimeCall {
+        RuntimeCall::System(frame_system::Call::remark {
+            remark: vec![0; size as usize],
+        })
+    }
+
+    fn non_data_call() -> RuntimeCall {
+        RuntimeCall::System(frame_system::Call::set_heap_pages { pages: 1 })
+    }
+
+    fn validate(call: &RuntimeCall, info: &DispatchInfo) -> TransactionValidity {
+        CheckDataInclusion.validate(&AccountId::new([0; 32]), call, info, call.encoded_size())
+    }
+
+    fn pre_dispatch(call: &RuntimeCall) -> Result<(), TransactionValidityError> {
+        CheckDataInclusion.pre_dispatch(
+            &AccountId::new([0; 32]),
+            call,
+            &DispatchInfo::default(),
+            call.encoded_size(),
+        )
+    }
+
+    #[test]
+    fn data_size_counts_only_data() {
+        assert_eq!(CheckDataInclusion::data_size(&remark(100)), Some(100));
+        assert_eq!(CheckDataInclusion::data_size(&non_data_call()), None);
+
+        let batch = RuntimeCall::Utility(pallet_utility::Call::batch_all {
+            calls: vec![remark(10), non_data_call(), remark(20)],
+        });
+        assert_eq!(CheckDataInclusion::data_size(&batch), Some(30));
+
+        let batch = RuntimeCall::Utility(pallet_utility::Call::batch {
+            calls: vec![non_data_call(), non_data_call()],
+        });
+        assert_eq!(CheckDataInclusion::data_size(&batch), None);
+    }
+
+    #[test]
+    fn validate_rejects_data_exceeding_block_cap() {
+        sp_io::TestExternalities::default().execute_with(|| {
+            let info = DispatchInfo::default();
+
+            assert!(validate(&remark(MAX_DATA_BYTES_PER_BLOCK), &info).is_ok());
+            assert_eq!(
+                validate(&remark(MAX_DATA_BYTES_PER_BLOCK + 1), &info),
+                Err(InvalidTransaction::ExhaustsResources.into())
+            );
+            assert!(validate(&non_data_call(), &info).is_ok());
+        });
+    }
+
+    #[test]
+    fn pre_dispatch_enforces_block_cap() {
+        sp_io::TestExternalities::default().execute_with(|| {
+            assert_eq!(pre_dispatch(&remark(MAX_DATA_BYTES_PER_BLOCK / 2)), Ok(()));
+            assert_eq!(pre_dispatch(&remark(MAX_DATA_BYTES_PER_BLOCK / 2)), Ok(()));
+            assert_eq!(
+                pre_dispatch(&remark(1)),
+                Err(InvalidTransaction::ExhaustsResources.into())
+            );
+            // Other extrinsics are not affected by the data cap
+            assert_eq!(pre_dispatch(&non_data_call()), Ok(()));
+        });
+    }
+
+    #[test]
+    fn priority_is_fee_per_data_byte() {
+        sp_io::TestExternalities::default().execute_with(|| {
+            let info = DispatchInfo::default();
+            let small_remark_priority = validate(&remark(100), &info).unwrap().priority;
+            let large_remark_priority = validate(&remark(100_000), &info).unwrap().priority;
+
+            // Fixed part of the fee is spread over more bytes of data
+            assert!(small_remark_priority > large_remark_priority);
+
+            // Higher fee for the same amount of data results in higher priority
+            let heavy_info = DispatchInfo {
+                weight: Weight::from_parts(1_000_000_000, 0),
+                ..DispatchInfo::default()
+            };
+            assert!(validate(&remark(100), &heavy_info).unwrap().priority > small_remark_priority);
+
+            // Extension doesn't affect priority of other extrinsics
+            assert_eq!(validate(&non_data_call(), &info).unwrap().priority, 0);
+        });
+    }
+}
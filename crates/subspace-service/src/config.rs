@@ -5,6 +5,7 @@ use sc_network::config::{
     MultiaddrWithPeerId, NetworkConfiguration, NodeKeyConfig, SetConfig, SyncMode, TransportConfig,
     DEFAULT_KADEMLIA_REPLICATION_FACTOR,
 };
+use sc_proof_of_time::source::external_timekeeper::ExternalTimekeeperConfig;
 use sc_service::config::{KeystoreConfig, OffchainWorkerConfig, PrometheusConfig};
 use sc_service::{
     BasePath, BlocksPruning, Configuration, DatabaseSource, PruningMode, RpcMethods,
@@ -248,6 +249,11 @@ pub struct SubspaceConfiguration {
     pub is_timekeeper: bool,
     /// CPU cores that timekeeper can use
     pub timekeeper_cpu_cores: HashSet<usize>,
+    /// Consume proofs of time from external timekeepers instead of computing them locally
+    pub external_timekeeper: Option<ExternalTimekeeperConfig>,
+    /// Stop authoring blocks (while still syncing) once node detects that its history diverged
+    /// from the history agreed on by the rest of the network
+    pub halt_authoring_on_fork: bool,
 }
 
 impl Deref for SubspaceConfiguration {
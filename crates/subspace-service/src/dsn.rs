@@ -3,6 +3,7 @@ use std::collections::HashSet;
 use std::fs;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
+use subspace_networking::bootstrap_node::BootstrapNodeConfig;
 use subspace_networking::libp2p::kad::Mode;
 use subspace_networking::libp2p::{identity, Multiaddr};
 use subspace_networking::utils::strip_peer_id;
@@ -14,6 +15,8 @@ use subspace_networking::{
 use thiserror::Error;
 use tracing::{error, trace};
 
+pub use subspace_networking::bootstrap_node::{BootstrapNode, BootstrapNodeError};
+
 /// Size of the LRU cache for peers.
 pub const KNOWN_PEERS_CACHE_SIZE: NonZeroUsize = NonZeroUsize::new(100).expect("Not zero; qed");
 
@@ -66,6 +69,10 @@ pub struct DsnConfig {
 
     /// Defines whether we should run blocking Kademlia bootstrap() operation before other requests.
     pub disable_bootstrap_on_start: bool,
+
+    /// Client-only operation: don't listen for incoming connections and don't serve requests, only
+    /// make outgoing requests (not applicable to dedicated bootstrap node).
+    pub client_only: bool,
 }
 
 pub(crate) fn create_dsn_instance(
@@ -119,9 +126,43 @@ pub(crate) fn create_dsn_instance(
         external_addresses: dsn_config.external_addresses,
         kademlia_mode: KademliaMode::Static(Mode::Client),
         disable_bootstrap_on_start: dsn_config.disable_bootstrap_on_start,
+        client_only: dsn_config.client_only,
 
         ..default_networking_config
     };
 
     subspace_networking::construct(networking_config).map_err(Into::into)
 }
+
+/// Create dedicated DSN bootstrap node (without consensus client) from DSN configuration.
+///
+/// Bootstrap node uses the same identity as configured for DSN, known peers are persisted in a
+/// separate directory under `network_path`, peers in `allowlist` are allowed to connect
+/// regardless of connection limits.
+pub fn create_bootstrap_node(
+    dsn_protocol_version: String,
+    dsn_config: DsnConfig,
+    allowlist: Vec<Multiaddr>,
+    prometheus_registry: Option<&mut Registry>,
+) -> Result<BootstrapNode, BootstrapNodeError> {
+    trace!("Subspace bootstrap node starting.");
+
+    BootstrapNode::new(
+        BootstrapNodeConfig {
+            protocol_version: dsn_protocol_version,
+            keypair: Some(dsn_config.keypair),
+            base_path: Some(dsn_config.network_path.join("bootstrap_node")),
+            listen_on: dsn_config.listen_on,
+            bootstrap_nodes: dsn_config.bootstrap_nodes,
+            reserved_peers: dsn_config.reserved_peers,
+            allowlist,
+            external_addresses: dsn_config.external_addresses,
+            allow_non_global_addresses_in_dht: dsn_config.allow_non_global_addresses_in_dht,
+            max_in_connections: dsn_config.max_in_connections,
+            max_out_connections: dsn_config.max_out_connections,
+            max_pending_in_connections: dsn_config.max_pending_in_connections,
+            max_pending_out_connections: dsn_config.max_pending_out_connections,
+        },
+        prometheus_registry,
+    )
+}
@@ -0,0 +1,45 @@
+//! Introspection of the DSN instance of the node.
+//!
+//! Exposes DSN bandwidth usage per libp2p protocol and per peer, which helps operators to find
+//! which protocols or peers are responsible for excessive traffic.
+
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use sc_rpc_api::DenyUnsafe;
+use subspace_networking::{Node, BANDWIDTH_WINDOW};
+use subspace_rpc_primitives::DsnBandwidth;
+
+/// Provides rpc methods for DSN introspection.
+#[rpc(server)]
+pub trait DsnIntrospectionRpcApi {
+    /// Bytes sent and received over DSN per libp2p protocol and per peer, both since node start
+    /// and during the recent rolling window
+    #[method(name = "subspace_dsnBandwidth")]
+    fn dsn_bandwidth(&self) -> RpcResult<DsnBandwidth>;
+}
+
+/// Implementation of [`DsnIntrospectionRpcApiServer`] backed by DSN [`Node`]
+#[derive(Debug)]
+pub struct DsnIntrospectionRpc {
+    node: Node,
+    deny_unsafe: DenyUnsafe,
+}
+
+impl DsnIntrospectionRpc {
+    /// Create new instance
+    pub fn new(node: Node, deny_unsafe: DenyUnsafe) -> Self {
+        Self { node, deny_unsafe }
+    }
+}
+
+impl DsnIntrospectionRpcApiServer for DsnIntrospectionRpc {
+    fn dsn_bandwidth(&self) -> RpcResult<DsnBandwidth> {
+        // Peer IDs of remote peers are not something to expose publicly
+        self.deny_unsafe.check_if_safe()?;
+
+        Ok(DsnBandwidth::new(
+            self.node.bandwidth_stats(),
+            BANDWIDTH_WINDOW,
+        ))
+    }
+}
@@ -0,0 +1,187 @@
+//! Detection of node's history diverging from the rest of the network.
+//!
+//! Segment headers commit to archived history, which is final and must be identical on all honest
+//! nodes. If the last segment header agreed on by the majority of DSN peers doesn't match the one
+//! stored locally, node is on a different fork than the rest of the network, which typically
+//! happens when node operator missed a mandatory upgrade. Such node will happily keep producing
+//! blocks that nobody else accepts, so the condition is reported loudly and block authoring can
+//! optionally be halted until the problem is resolved, while sync keeps running.
+//!
+//! DSN peers are cheap to create, so their opinion alone is not trusted: fork is only confirmed
+//! when consensus peers independently show that they follow a chain that local node doesn't know
+//! about.
+
+#[cfg(test)]
+mod tests;
+
+use crate::sync_from_dsn::segment_header_downloader::SegmentHeaderDownloader;
+use sc_client_api::AuxStore;
+use sc_consensus_subspace::archiver::SegmentHeadersStore;
+use sc_network_sync::SyncingService;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use subspace_networking::Node;
+use tracing::{debug, error, info, warn};
+
+/// Frequency with which local segment headers are compared with those known to DSN peers
+const FORK_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Min number of consensus peers on a chain unknown to local node required to confirm the fork
+/// reported by DSN peers
+const MIN_DIVERGED_CONSENSUS_PEERS: usize = 2;
+
+/// Outcome of a single fork check
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) enum ForkCheckOutcome {
+    /// Local history matches DSN peers
+    NoFork,
+    /// Local history doesn't match DSN peers and consensus peers confirm it
+    Fork,
+    /// Local history doesn't match DSN peers, but consensus peers don't confirm it
+    Unconfirmed,
+}
+
+/// Combine view of DSN peers with view of consensus peers.
+///
+/// `diverged_consensus_peers` is the number of consensus peers whose best block is not ahead of
+/// the local best block and yet unknown locally (they follow a different chain),
+/// `agreeing_consensus_peers` is the number of consensus peers whose best block is known locally.
+/// Consensus peers that are ahead of the local chain are not counted in either.
+pub(super) fn fork_check_outcome(
+    matches_dsn_peers: bool,
+    agreeing_consensus_peers: usize,
+    diverged_consensus_peers: usize,
+) -> ForkCheckOutcome {
+    if matches_dsn_peers {
+        ForkCheckOutcome::NoFork
+    } else if diverged_consensus_peers >= MIN_DIVERGED_CONSENSUS_PEERS
+        && diverged_consensus_peers > agreeing_consensus_peers
+    {
+        ForkCheckOutcome::Fork
+    } else {
+        ForkCheckOutcome::Unconfirmed
+    }
+}
+
+/// Periodically compares local segment headers with segment headers agreed on by DSN peers and
+/// cross-checks mismatches with consensus peers.
+///
+/// When confirmed mismatch is detected, error is logged on every check and `halt_authoring` is set
+/// if `halt_authoring_on_fork` is enabled. Flag is cleared again once local history matches the
+/// network.
+pub(super) async fn run_fork_detector<Block, Client, AS>(
+    segment_headers_store: SegmentHeadersStore<AS>,
+    node: Node,
+    client: Arc<Client>,
+    sync_service: Arc<SyncingService<Block>>,
+    halt_authoring_on_fork: bool,
+    halt_authoring: Arc<AtomicBool>,
+) where
+    Block: BlockT,
+    Client: HeaderBackend<Block>,
+    AS: AuxStore,
+{
+    let segment_header_downloader = SegmentHeaderDownloader::new(&node);
+
+    loop {
+        tokio::time::sleep(FORK_CHECK_INTERVAL).await;
+
+        let (network_segment_header, peers) =
+            match segment_header_downloader.get_last_segment_header().await {
+                Ok(Some(result)) => result,
+                Ok(None) => {
+                    debug!("No DSN peers to compare segment headers with, skipping fork check");
+                    continue;
+                }
+                Err(error) => {
+                    debug!(%error, "Failed to get last segment header from DSN peers");
+                    continue;
+                }
+            };
+
+        let segment_index = network_segment_header.segment_index();
+        let Some(local_segment_header) = segment_headers_store.get_segment_header(segment_index)
+        else {
+            // Node didn't archive this segment yet, nothing to compare with
+            debug!(%segment_index, "Segment is not archived locally yet, skipping fork check");
+            continue;
+        };
+
+        let matches_dsn_peers = local_segment_header == network_segment_header;
+        let (agreeing_consensus_peers, diverged_consensus_peers) = if matches_dsn_peers {
+            (0, 0)
+        } else {
+            match sync_service.peers_info().await {
+                Ok(consensus_peers) => {
+                    let best_number = client.info().best_number;
+                    consensus_peers.iter().fold(
+                        (0, 0),
+                        |(agreeing, diverged), (_peer_id, peer_info)| {
+                            if matches!(client.number(peer_info.best_hash), Ok(Some(_))) {
+                                (agreeing + 1, diverged)
+                            } else if peer_info.best_number <= best_number {
+                                (agreeing, diverged + 1)
+                            } else {
+                                // Peer is ahead, might be on the same chain
+                                (agreeing, diverged)
+                            }
+                        },
+                    )
+                }
+                Err(error) => {
+                    debug!(%error, "Failed to get consensus peers info");
+                    (0, 0)
+                }
+            }
+        };
+
+        match fork_check_outcome(
+            matches_dsn_peers,
+            agreeing_consensus_peers,
+            diverged_consensus_peers,
+        ) {
+            ForkCheckOutcome::NoFork => {
+                if halt_authoring.swap(false, Ordering::AcqRel) {
+                    info!(
+                        %segment_index,
+                        "Local history matches DSN peers again, resuming block authoring"
+                    );
+                }
+            }
+            ForkCheckOutcome::Fork => {
+                error!(
+                    %segment_index,
+                    local_segment_header_hash = ?local_segment_header.hash(),
+                    network_segment_header_hash = ?network_segment_header.hash(),
+                    dsn_peers_count = %peers.len(),
+                    %agreeing_consensus_peers,
+                    %diverged_consensus_peers,
+                    "FORK DETECTED: archived history of this node diverged from history agreed on \
+                    by the rest of the network, this usually means a mandatory upgrade was missed. \
+                    Blocks produced by this node are unlikely to be accepted by anyone else, \
+                    upgrade and resync the node!"
+                );
+
+                if halt_authoring_on_fork && !halt_authoring.swap(true, Ordering::AcqRel) {
+                    error!(
+                        "Block authoring halted due to detected fork, node will continue syncing"
+                    );
+                }
+            }
+            ForkCheckOutcome::Unconfirmed => {
+                warn!(
+                    %segment_index,
+                    local_segment_header_hash = ?local_segment_header.hash(),
+                    network_segment_header_hash = ?network_segment_header.hash(),
+                    dsn_peers_count = %peers.len(),
+                    %agreeing_consensus_peers,
+                    %diverged_consensus_peers,
+                    "Archived history of this node doesn't match DSN peers, but consensus peers \
+                    don't confirm the fork, ignoring"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::fork_detector::{fork_check_outcome, ForkCheckOutcome, MIN_DIVERGED_CONSENSUS_PEERS};
+
+#[test]
+fn fork_check() {
+    // Matching DSN peers is never a fork, regardless of consensus peers
+    assert_eq!(fork_check_outcome(true, 0, 0), ForkCheckOutcome::NoFork);
+    assert_eq!(fork_check_outcome(true, 0, 10), ForkCheckOutcome::NoFork);
+
+    // DSN peers alone are not trusted
+    assert_eq!(
+        fork_check_outcome(false, 0, 0),
+        ForkCheckOutcome::Unconfirmed
+    );
+    assert_eq!(
+        fork_check_outcome(false, 10, 0),
+        ForkCheckOutcome::Unconfirmed
+    );
+
+    // Not enough diverged consensus peers
+    assert_eq!(
+        fork_check_outcome(false, 0, MIN_DIVERGED_CONSENSUS_PEERS - 1),
+        ForkCheckOutcome::Unconfirmed
+    );
+    // Diverged consensus peers are not a majority
+    assert_eq!(
+        fork_check_outcome(
+            false,
+            MIN_DIVERGED_CONSENSUS_PEERS,
+            MIN_DIVERGED_CONSENSUS_PEERS
+        ),
+        ForkCheckOutcome::Unconfirmed
+    );
+
+    // Both DSN and majority of consensus peers follow a different chain
+    assert_eq!(
+        fork_check_outcome(false, 0, MIN_DIVERGED_CONSENSUS_PEERS),
+        ForkCheckOutcome::Fork
+    );
+    assert_eq!(
+        fork_check_outcome(
+            false,
+            MIN_DIVERGED_CONSENSUS_PEERS,
+            MIN_DIVERGED_CONSENSUS_PEERS + 1
+        ),
+        ForkCheckOutcome::Fork
+    );
+}
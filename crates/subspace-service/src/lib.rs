@@ -26,17 +26,22 @@
 
 pub mod config;
 pub mod dsn;
+pub mod dsn_introspection;
+mod fork_detector;
 mod metrics;
+mod network_bridge;
+pub mod object_status;
 pub mod rpc;
 pub mod sync_from_dsn;
 pub mod transaction_pool;
 
 use crate::config::{SubspaceConfiguration, SubspaceNetworking};
 use crate::dsn::{create_dsn_instance, DsnConfigurationError};
-use crate::metrics::NodeMetrics;
+use crate::metrics::{ImportLagMetrics, NodeMetrics};
+use crate::object_status::{run_object_status_indexer, ObjectStatusIndex};
 use crate::sync_from_dsn::piece_validator::SegmentCommitmentPieceValidator;
 use crate::transaction_pool::FullPool;
-use core::sync::atomic::{AtomicU32, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use cross_domain_message_gossip::xdm_gossip_peers_set_config;
 use domain_runtime_primitives::opaque::{Block as DomainBlock, Header as DomainHeader};
 use frame_system_rpc_runtime_api::AccountNonceApi;
@@ -647,7 +652,7 @@ type FullNode<RuntimeApi> = NewFull<FullClient<RuntimeApi>>;
 pub async fn new_full<PosTable, RuntimeApi>(
     mut config: SubspaceConfiguration,
     partial_components: PartialComponents<RuntimeApi>,
-    prometheus_registry: Option<&mut Registry>,
+    mut prometheus_registry: Option<&mut Registry>,
     enable_rpc_extensions: bool,
     block_proposal_slot_portion: SlotProportion,
 ) -> Result<FullNode<RuntimeApi>, Error>
@@ -706,7 +711,7 @@ where
             let (node, mut node_runner) = create_dsn_instance(
                 dsn_protocol_version,
                 dsn_config.clone(),
-                prometheus_registry,
+                prometheus_registry.as_deref_mut(),
             )?;
 
             info!("Subspace networking initialized: Node ID is {}", node.id());
@@ -815,21 +820,41 @@ where
             block_relay,
         })?;
 
+    let import_queue_depth = Arc::new(AtomicU32::new(0));
+    let import_lag_metrics = prometheus_registry
+        .as_deref_mut()
+        .map(ImportLagMetrics::new);
+
     task_manager.spawn_handle().spawn(
         "sync-target-follower",
         None,
         Box::pin({
+            let client = client.clone();
             let sync_service = sync_service.clone();
             let sync_target_block_number = Arc::clone(&sync_target_block_number);
+            let import_queue_depth = Arc::clone(&import_queue_depth);
 
             async move {
                 loop {
-                    let best_seen_block = sync_service
+                    let (best_seen_block, queued_blocks) = sync_service
                         .status()
                         .await
-                        .map(|status| status.best_seen_block.unwrap_or_default())
+                        .map(|status| {
+                            (
+                                status.best_seen_block.unwrap_or_default(),
+                                status.queued_blocks,
+                            )
+                        })
                         .unwrap_or_default();
                     sync_target_block_number.store(best_seen_block, Ordering::Relaxed);
+                    import_queue_depth.store(queued_blocks, Ordering::Relaxed);
+
+                    if let Some(import_lag_metrics) = &import_lag_metrics {
+                        import_lag_metrics.update(
+                            queued_blocks,
+                            best_seen_block.saturating_sub(client.info().best_number),
+                        );
+                    }
 
                     tokio::time::sleep(SYNC_TARGET_UPDATE_INTERVAL).await;
                 }
@@ -837,9 +862,38 @@ where
         }),
     );
 
+    task_manager.spawn_handle().spawn(
+        "network-bridge",
+        Some("subspace-networking"),
+        Box::pin({
+            let network_service = network_service.clone();
+            let node = node.clone();
+
+            async move {
+                network_bridge::run_network_bridge(network_service.as_ref(), &node).await;
+            }
+        }),
+    );
+
+    let halt_authoring = Arc::new(AtomicBool::new(false));
+
+    task_manager.spawn_handle().spawn(
+        "fork-detector",
+        Some("subspace-networking"),
+        fork_detector::run_fork_detector(
+            segment_headers_store.clone(),
+            node.clone(),
+            client.clone(),
+            sync_service.clone(),
+            config.halt_authoring_on_fork,
+            Arc::clone(&halt_authoring),
+        ),
+    );
+
     let sync_oracle = SubspaceSyncOracle::new(
         config.base.force_authoring,
         Arc::clone(&pause_sync),
+        halt_authoring,
         sync_service.clone(),
     );
 
@@ -910,25 +964,19 @@ where
             );
     }
 
-    if let Some(registry) = config.base.prometheus_registry() {
-        match NodeMetrics::new(
+    if let Some(registry) = prometheus_registry.as_deref_mut() {
+        let node_metrics = NodeMetrics::new(
             client.clone(),
             client.every_import_notification_stream(),
             registry,
-        ) {
-            Ok(node_metrics) => {
-                task_manager.spawn_handle().spawn(
-                    "node_metrics",
-                    None,
-                    Box::pin(async move {
-                        node_metrics.run().await;
-                    }),
-                );
-            }
-            Err(err) => {
-                error!("Failed to initialize node metrics: {err:?}");
-            }
-        }
+        );
+        task_manager.spawn_handle().spawn(
+            "node_metrics",
+            None,
+            Box::pin(async move {
+                node_metrics.run().await;
+            }),
+        );
     }
 
     let offchain_tx_pool_factory = OffchainTransactionPoolFactory::new(transaction_pool.clone());
@@ -975,6 +1023,7 @@ where
     let (pot_source_worker, pot_gossip_worker, pot_slot_info_stream) = PotSourceWorker::new(
         config.is_timekeeper,
         config.timekeeper_cpu_cores,
+        config.external_timekeeper,
         client.clone(),
         pot_verifier.clone(),
         network_service.clone(),
@@ -1016,6 +1065,7 @@ where
                 telemetry: telemetry.as_ref().map(|x| x.handle()),
                 offchain_tx_pool_factory,
                 pot_verifier,
+                import_queue_depth,
             });
 
         let create_inherent_data_providers = {
@@ -1068,6 +1118,19 @@ where
     // We replace the Substrate implementation of metrics server with our own.
     config.base.prometheus_config.take();
 
+    let object_status_index = ObjectStatusIndex::default();
+    if enable_rpc_extensions {
+        task_manager.spawn_handle().spawn(
+            "object-status-indexer",
+            Some("rpc"),
+            run_object_status_indexer(
+                client.clone(),
+                transaction_pool.clone(),
+                object_status_index.clone(),
+            ),
+        );
+    }
+
     let rpc_handlers = sc_service::spawn_tasks(SpawnTasksParams {
         network: network_service.clone(),
         client: client.clone(),
@@ -1082,6 +1145,8 @@ where
             let transaction_pool = transaction_pool.clone();
             let chain_spec = config.base.chain_spec.cloned_box();
             let backend = backend.clone();
+            let object_status_index = object_status_index.clone();
+            let dsn_node = node.clone();
 
             Box::new(move |deny_unsafe, subscription_executor| {
                 let deps = rpc::FullDeps {
@@ -1099,9 +1164,11 @@ where
                     sync_oracle: sync_oracle.clone(),
                     kzg: subspace_link.kzg().clone(),
                     backend: backend.clone(),
+                    object_status_index: object_status_index.clone(),
+                    dsn_node: dsn_node.clone(),
                 };
 
-                rpc::create_full(deps).map_err(Into::into)
+                rpc::create_full::<PosTable, _, _, _, _, _>(deps).map_err(Into::into)
             })
         } else {
             Box::new(|_, _| Ok(RpcModule::new(())))
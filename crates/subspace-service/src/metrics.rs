@@ -2,17 +2,20 @@
 
 use futures::StreamExt;
 use parity_scale_codec::Encode;
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::registry::{Registry, Unit};
 use sc_client_api::{BlockBackend, BlockImportNotification, ImportNotifications};
 use sp_runtime::traits::Block as BlockT;
 use std::sync::Arc;
-use substrate_prometheus_endpoint::{register, Counter, PrometheusError, Registry, U64};
+use subspace_metrics::MetricsComponent;
 
 pub struct NodeMetrics<Block: BlockT, Client> {
     client: Arc<Client>,
     block_import: ImportNotifications<Block>,
-    blocks: Counter<U64>,
-    extrinsics: Counter<U64>,
-    extrinsics_size: Counter<U64>,
+    blocks: Counter,
+    extrinsics: Counter,
+    extrinsics_size: Counter,
     _p: std::marker::PhantomData<Block>,
 }
 
@@ -24,31 +27,36 @@ where
     pub fn new(
         client: Arc<Client>,
         block_import: ImportNotifications<Block>,
-        registry: &Registry,
-    ) -> Result<Self, PrometheusError> {
-        Ok(Self {
+        registry: &mut Registry,
+    ) -> Self {
+        let sub_registry = MetricsComponent::Node.sub_registry(registry);
+
+        let blocks = Counter::default();
+        sub_registry.register("blocks", "Total number of imported blocks", blocks.clone());
+
+        let extrinsics = Counter::default();
+        sub_registry.register(
+            "extrinsics",
+            "Total number of extrinsics in the imported blocks",
+            extrinsics.clone(),
+        );
+
+        let extrinsics_size = Counter::default();
+        sub_registry.register_with_unit(
+            "extrinsics_size",
+            "Total extrinsic bytes in the imported blocks",
+            Unit::Bytes,
+            extrinsics_size.clone(),
+        );
+
+        Self {
             client,
             block_import,
-            blocks: register(
-                Counter::new("subspace_node_blocks", "Total number of imported blocks")?,
-                registry,
-            )?,
-            extrinsics: register(
-                Counter::new(
-                    "subspace_node_extrinsics",
-                    "Total number of extrinsics in the imported blocks",
-                )?,
-                registry,
-            )?,
-            extrinsics_size: register(
-                Counter::new(
-                    "subspace_node_extrinsics_size",
-                    "Total extrinsic bytes in the imported blocks",
-                )?,
-                registry,
-            )?,
+            blocks,
+            extrinsics,
+            extrinsics_size,
             _p: Default::default(),
-        })
+        }
     }
 
     pub async fn run(mut self) {
@@ -73,3 +81,39 @@ where
         self.extrinsics_size.inc_by(total_size as u64);
     }
 }
+
+/// Metrics of block import lag
+pub struct ImportLagMetrics {
+    import_queue_depth: Gauge,
+    blocks_behind: Gauge,
+}
+
+impl ImportLagMetrics {
+    pub fn new(registry: &mut Registry) -> Self {
+        let sub_registry = MetricsComponent::Node.sub_registry(registry);
+
+        let import_queue_depth = Gauge::default();
+        sub_registry.register(
+            "import_queue_depth",
+            "Number of blocks waiting in import queue",
+            import_queue_depth.clone(),
+        );
+
+        let blocks_behind = Gauge::default();
+        sub_registry.register(
+            "blocks_behind",
+            "Number of blocks between the best block and the best block seen on the network",
+            blocks_behind.clone(),
+        );
+
+        Self {
+            import_queue_depth,
+            blocks_behind,
+        }
+    }
+
+    pub fn update(&self, import_queue_depth: u32, blocks_behind: u32) {
+        self.import_queue_depth.set(i64::from(import_queue_depth));
+        self.blocks_behind.set(i64::from(blocks_behind));
+    }
+}
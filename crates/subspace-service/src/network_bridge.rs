@@ -0,0 +1,107 @@
+//! Bridge between Substrate networking and DSN.
+//!
+//! Node uses the same identity for both networking stacks, so remote nodes have the same peer ID
+//! in both of them. Bridge shares information about consensus peers with DSN, such that DSN can
+//! reuse already established relationships instead of discovering and connecting to unrelated
+//! peers, which reduces the number of connections on nodes with many peers.
+
+use sc_network::network_state::{NetworkState, PeerEndpoint};
+use sc_network::NetworkStatusProvider;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::Duration;
+use subspace_networking::libp2p::PeerId;
+use subspace_networking::Node;
+use tracing::{debug, trace};
+
+/// Frequency with which consensus peers are synchronized with DSN
+const NETWORK_BRIDGE_UPDATE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Runs bridge that marks peers connected over Substrate networking as preferred in DSN.
+///
+/// Returns when DSN node is shut down.
+pub(super) async fn run_network_bridge<NSP>(network_service: &NSP, node: &Node)
+where
+    NSP: NetworkStatusProvider,
+{
+    let mut preferred_peers = HashMap::<PeerId, HashSet<IpAddr>>::new();
+
+    loop {
+        match network_service.network_state().await {
+            Ok(network_state) => {
+                let consensus_peers = consensus_peers(network_state);
+
+                for peer_id in preferred_peers.keys() {
+                    if consensus_peers.contains_key(peer_id) {
+                        continue;
+                    }
+
+                    trace!(%peer_id, "Consensus peer disconnected, removing preferred DSN peer");
+                    if node.remove_preferred_peer(*peer_id).await.is_err() {
+                        debug!("DSN node was shut down, exiting network bridge");
+                        return;
+                    }
+                }
+
+                for (peer_id, ip_addresses) in &consensus_peers {
+                    if preferred_peers.get(peer_id) == Some(ip_addresses) {
+                        continue;
+                    }
+
+                    trace!(
+                        %peer_id,
+                        ?ip_addresses,
+                        "Consensus peer connected, adding preferred DSN peer"
+                    );
+                    if node
+                        .add_preferred_peer(*peer_id, ip_addresses.iter().copied().collect())
+                        .await
+                        .is_err()
+                    {
+                        debug!("DSN node was shut down, exiting network bridge");
+                        return;
+                    }
+                }
+
+                preferred_peers = consensus_peers;
+            }
+            Err(()) => {
+                debug!("Failed to get Substrate network state, networking was likely shut down");
+            }
+        }
+
+        tokio::time::sleep(NETWORK_BRIDGE_UPDATE_INTERVAL).await;
+    }
+}
+
+/// Extract connected consensus peers with their IP addresses from Substrate network state
+fn consensus_peers(network_state: NetworkState) -> HashMap<PeerId, HashSet<IpAddr>> {
+    network_state
+        .connected_peers
+        .into_iter()
+        .filter_map(|(peer_id, peer)| {
+            let peer_id = match PeerId::from_str(&peer_id) {
+                Ok(peer_id) => peer_id,
+                Err(error) => {
+                    debug!(%peer_id, %error, "Failed to parse consensus peer ID");
+                    return None;
+                }
+            };
+            let remote_address = match peer.endpoint {
+                PeerEndpoint::Dialing(address, _role_override) => address,
+                PeerEndpoint::Listening { send_back_addr, .. } => send_back_addr,
+            };
+            let ip_addresses = remote_address
+                .iter()
+                .filter_map(|protocol| match protocol {
+                    sc_network::multiaddr::Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+                    sc_network::multiaddr::Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+                    _ => None,
+                })
+                .collect();
+
+            Some((peer_id, ip_addresses))
+        })
+        .collect()
+}
@@ -0,0 +1,282 @@
+//! Status of data-carrying extrinsics by hashes of objects they carry.
+//!
+//! Upload tooling knows hashes of objects it has submitted, but not necessarily hashes of
+//! extrinsics that carry them. [`ObjectStatusIndex`] is maintained from transaction pool and best
+//! block import notifications and allows checking whether an object is still waiting in the
+//! transaction pool or was already included in a block and is on its way to archival.
+
+use futures::{stream, StreamExt};
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use parking_lot::Mutex;
+use sc_client_api::{BlockBackend, BlockImportNotification, BlockchainEvents};
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+use sp_api::{ApiError, ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::H256;
+use sp_objects::ObjectsApi;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use subspace_core_primitives::{Blake3Hash, BlockNumber};
+use subspace_rpc_primitives::ObjectStatus;
+use subspace_runtime_primitives::opaque::Block;
+use tracing::{debug, warn};
+
+/// Objects included in blocks are tracked for this many blocks, which is enough for them to be
+/// archived under normal conditions
+const IN_BLOCK_RETENTION_DEPTH: BlockNumber = 1024;
+
+#[derive(Debug, Default)]
+struct Inner {
+    statuses: HashMap<Blake3Hash, ObjectStatus>,
+    /// Objects of extrinsics that are in the transaction pool
+    pending_extrinsics: HashMap<H256, Vec<Blake3Hash>>,
+    /// Objects included in blocks, ordered by block number
+    in_block_objects: VecDeque<(BlockNumber, Vec<Blake3Hash>)>,
+}
+
+/// Index of object statuses by object hash
+#[derive(Debug, Default, Clone)]
+pub struct ObjectStatusIndex {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl ObjectStatusIndex {
+    /// Status of object with specified hash, `None` if object is unknown
+    pub fn object_status(&self, object_hash: &Blake3Hash) -> Option<ObjectStatus> {
+        self.inner.lock().statuses.get(object_hash).copied()
+    }
+
+    fn on_pending_extrinsic(&self, extrinsic_hash: H256, object_hashes: Vec<Blake3Hash>) {
+        let mut inner = self.inner.lock();
+
+        for object_hash in &object_hashes {
+            inner
+                .statuses
+                .entry(*object_hash)
+                // Object may have been already included by another extrinsic
+                .or_insert(ObjectStatus::Pending {
+                    extrinsic_hash: extrinsic_hash.into(),
+                });
+        }
+        inner
+            .pending_extrinsics
+            .insert(extrinsic_hash, object_hashes);
+    }
+
+    fn on_best_block<IsPending>(
+        &self,
+        block_hash: H256,
+        block_number: BlockNumber,
+        extrinsics: Vec<(H256, Vec<Blake3Hash>)>,
+        is_pending: IsPending,
+    ) where
+        IsPending: Fn(&H256) -> bool,
+    {
+        let mut inner = self.inner.lock();
+        let Inner {
+            statuses,
+            pending_extrinsics,
+            in_block_objects,
+        } = &mut *inner;
+
+        let mut block_objects = Vec::new();
+        for (extrinsic_index, (extrinsic_hash, object_hashes)) in extrinsics.into_iter().enumerate()
+        {
+            pending_extrinsics.remove(&extrinsic_hash);
+
+            for object_hash in object_hashes {
+                statuses.insert(
+                    object_hash,
+                    ObjectStatus::InBlock {
+                        block_hash: block_hash.into(),
+                        block_number,
+                        extrinsic_hash: extrinsic_hash.into(),
+                        extrinsic_index: extrinsic_index as u32,
+                    },
+                );
+                block_objects.push(object_hash);
+            }
+        }
+        if !block_objects.is_empty() {
+            in_block_objects.push_back((block_number, block_objects));
+        }
+
+        // Forget about extrinsics that were dropped from the transaction pool
+        pending_extrinsics.retain(|extrinsic_hash, object_hashes| {
+            if is_pending(extrinsic_hash) {
+                return true;
+            }
+
+            for object_hash in object_hashes {
+                if let Some(ObjectStatus::Pending {
+                    extrinsic_hash: pending_extrinsic_hash,
+                }) = statuses.get(object_hash)
+                {
+                    if pending_extrinsic_hash == extrinsic_hash.as_fixed_bytes() {
+                        statuses.remove(object_hash);
+                    }
+                }
+            }
+            false
+        });
+
+        // Forget about objects that were included long enough ago
+        while let Some((oldest_block_number, _)) = in_block_objects.front() {
+            if oldest_block_number + IN_BLOCK_RETENTION_DEPTH > block_number {
+                break;
+            }
+
+            let (_, object_hashes) = in_block_objects.pop_front().expect("Checked above; qed");
+            for object_hash in object_hashes {
+                if let Some(ObjectStatus::InBlock {
+                    block_number: included_at,
+                    ..
+                }) = statuses.get(&object_hash)
+                {
+                    if included_at + IN_BLOCK_RETENTION_DEPTH <= block_number {
+                        statuses.remove(&object_hash);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn extrinsic_object_hashes<Client>(
+    client: &Client,
+    at: H256,
+    extrinsic: <Block as BlockT>::Extrinsic,
+) -> Result<Vec<Blake3Hash>, ApiError>
+where
+    Client: ProvideRuntimeApi<Block>,
+    Client::Api: ObjectsApi<Block>,
+{
+    let runtime_api = client.runtime_api();
+    if !runtime_api.has_api_with::<dyn ObjectsApi<Block>, _>(at, |version| version >= 3)? {
+        return Ok(Vec::new());
+    }
+
+    runtime_api.extrinsic_object_hashes(at, extrinsic)
+}
+
+enum IndexerEvent {
+    PoolImport(H256),
+    BlockImport(BlockImportNotification<Block>),
+}
+
+/// Keep [`ObjectStatusIndex`] up to date with transaction pool and best chain, never returns
+pub async fn run_object_status_indexer<Client, Pool>(
+    client: Arc<Client>,
+    transaction_pool: Arc<Pool>,
+    index: ObjectStatusIndex,
+) where
+    Client: ProvideRuntimeApi<Block>
+        + BlockBackend<Block>
+        + HeaderBackend<Block>
+        + BlockchainEvents<Block>,
+    Client::Api: ObjectsApi<Block>,
+    Pool: TransactionPool<Block = Block, Hash = H256>,
+{
+    let mut events = stream::select(
+        transaction_pool
+            .import_notification_stream()
+            .map(IndexerEvent::PoolImport),
+        client
+            .import_notification_stream()
+            .map(IndexerEvent::BlockImport),
+    );
+
+    while let Some(event) = events.next().await {
+        match event {
+            IndexerEvent::PoolImport(extrinsic_hash) => {
+                let Some(transaction) = transaction_pool.ready_transaction(&extrinsic_hash) else {
+                    continue;
+                };
+                let best_hash = client.info().best_hash;
+
+                match extrinsic_object_hashes(&*client, best_hash, transaction.data().clone()) {
+                    Ok(object_hashes) => {
+                        if !object_hashes.is_empty() {
+                            debug!(
+                                ?extrinsic_hash,
+                                objects = %object_hashes.len(),
+                                "Tracking pending objects"
+                            );
+                            index.on_pending_extrinsic(extrinsic_hash, object_hashes);
+                        }
+                    }
+                    Err(error) => {
+                        warn!(%error, ?extrinsic_hash, "Failed to extract object hashes");
+                    }
+                }
+            }
+            IndexerEvent::BlockImport(block_import_notification) => {
+                if !block_import_notification.is_new_best {
+                    continue;
+                }
+
+                let block_hash = block_import_notification.hash;
+                let block_number = *block_import_notification.header.number();
+                let extrinsics = match client.block_body(block_hash) {
+                    Ok(Some(extrinsics)) => extrinsics,
+                    Ok(None) => {
+                        warn!(?block_hash, "Block body not found");
+                        continue;
+                    }
+                    Err(error) => {
+                        warn!(%error, ?block_hash, "Failed to get block body");
+                        continue;
+                    }
+                };
+
+                let mut extrinsics_objects = Vec::with_capacity(extrinsics.len());
+                for extrinsic in extrinsics {
+                    let extrinsic_hash = transaction_pool.hash_of(&extrinsic);
+                    match extrinsic_object_hashes(&*client, block_hash, extrinsic) {
+                        Ok(object_hashes) => {
+                            extrinsics_objects.push((extrinsic_hash, object_hashes));
+                        }
+                        Err(error) => {
+                            warn!(%error, ?extrinsic_hash, "Failed to extract object hashes");
+                            extrinsics_objects.push((extrinsic_hash, Vec::new()));
+                        }
+                    }
+                }
+
+                index.on_best_block(block_hash, block_number, extrinsics_objects, |hash| {
+                    transaction_pool.ready_transaction(hash).is_some()
+                });
+            }
+        }
+    }
+}
+
+/// Object status RPC API
+#[rpc(server)]
+pub trait ObjectStatusRpcApi {
+    /// Status of object with specified hash, `None` if object is not known to be in the
+    /// transaction pool or recently included in the best chain
+    #[method(name = "subspace_objectStatus")]
+    fn object_status(&self, object_hash: H256) -> RpcResult<Option<ObjectStatus>>;
+}
+
+/// Implementation of [`ObjectStatusRpcApiServer`] backed by [`ObjectStatusIndex`]
+#[derive(Debug)]
+pub struct ObjectStatusRpc {
+    index: ObjectStatusIndex,
+}
+
+impl ObjectStatusRpc {
+    /// Create new instance
+    pub fn new(index: ObjectStatusIndex) -> Self {
+        Self { index }
+    }
+}
+
+impl ObjectStatusRpcApiServer for ObjectStatusRpc {
+    fn object_status(&self, object_hash: H256) -> RpcResult<Option<ObjectStatus>> {
+        Ok(self.index.object_status(object_hash.as_fixed_bytes()))
+    }
+}
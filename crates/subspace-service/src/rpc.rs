@@ -21,6 +21,8 @@
 
 #![warn(missing_docs)]
 
+use crate::dsn_introspection::{DsnIntrospectionRpc, DsnIntrospectionRpcApiServer};
+use crate::object_status::{ObjectStatusIndex, ObjectStatusRpc, ObjectStatusRpcApiServer};
 use jsonrpsee::RpcModule;
 use mmr_rpc::{Mmr, MmrApiServer};
 use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
@@ -45,6 +47,8 @@ use std::sync::Arc;
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::BlockNumber;
 use subspace_networking::libp2p::Multiaddr;
+use subspace_networking::Node;
+use subspace_proof_of_space::Table;
 use subspace_runtime_primitives::opaque::Block;
 use subspace_runtime_primitives::{AccountId, Balance, Nonce};
 use substrate_frame_rpc_system::{System, SystemApiServer};
@@ -82,10 +86,14 @@ where
     pub kzg: Kzg,
     /// Backend used by the node.
     pub backend: Arc<B>,
+    /// Index of statuses of objects carried by data-carrying extrinsics.
+    pub object_status_index: ObjectStatusIndex,
+    /// DSN node, used for introspection.
+    pub dsn_node: Node,
 }
 
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P, SO, AS, B>(
+pub fn create_full<PosTable, C, P, SO, AS, B>(
     deps: FullDeps<C, P, SO, AS, B>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
@@ -102,6 +110,7 @@ where
         + SubspaceApi<Block, FarmerPublicKey>
         + mmr_rpc::MmrRuntimeApi<Block, <Block as sp_runtime::traits::Block>::Hash, BlockNumber>
         + ObjectsApi<Block>,
+    PosTable: Table,
     P: TransactionPool + 'static,
     SO: SyncOracle + Send + Sync + Clone + 'static,
     AS: AuxStore + Send + Sync + 'static,
@@ -123,6 +132,8 @@ where
         sync_oracle,
         kzg,
         backend,
+        object_status_index,
+        dsn_node,
     } = deps;
 
     let chain_name = chain_spec.name().to_string();
@@ -134,7 +145,7 @@ where
     module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
 
     module.merge(
-        SubspaceRpc::new(SubspaceRpcConfig {
+        SubspaceRpc::<PosTable, _, _, _, _>::new(SubspaceRpcConfig {
             client: client.clone(),
             subscription_executor,
             new_slot_notification_stream,
@@ -148,6 +159,8 @@ where
         })?
         .into_rpc(),
     )?;
+    module.merge(ObjectStatusRpc::new(object_status_index).into_rpc())?;
+    module.merge(DsnIntrospectionRpc::new(dsn_node, deny_unsafe).into_rpc())?;
     module.merge(
         Mmr::new(
             client,
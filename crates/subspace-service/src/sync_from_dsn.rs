@@ -1,6 +1,6 @@
 mod import_blocks;
 pub(super) mod piece_validator;
-mod segment_header_downloader;
+pub(crate) mod segment_header_downloader;
 
 use crate::sync_from_dsn::import_blocks::import_blocks_from_dsn;
 pub use crate::sync_from_dsn::import_blocks::DsnSyncPieceGetter;
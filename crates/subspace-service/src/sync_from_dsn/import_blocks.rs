@@ -358,7 +358,7 @@ where
         };
 
         segment_pieces
-            .get_mut(piece_index.position() as usize)
+            .get_mut(usize::from(piece_index.position()))
             .expect("Piece position is by definition within segment; qed")
             .replace(piece);
 
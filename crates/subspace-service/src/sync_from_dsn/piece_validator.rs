@@ -1,7 +1,6 @@
 use async_trait::async_trait;
 use sc_client_api::AuxStore;
 use sc_consensus_subspace::archiver::SegmentHeadersStore;
-use subspace_archiving::archiver::is_piece_valid;
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::{Piece, PieceIndex};
 use subspace_networking::libp2p::PeerId;
@@ -64,17 +63,19 @@ where
             let kzg = self.kzg.clone();
 
             move || {
-                is_piece_valid(&kzg, &piece, &segment_commitment, piece_index.position())
-                    .then_some(piece)
+                piece
+                    .verify(&kzg, &segment_commitment, piece_index)
+                    .map(|()| piece)
             }
         });
 
-        match is_valid_fut.await.unwrap_or_default() {
-            Some(piece) => Some(piece),
-            None => {
+        match is_valid_fut.await {
+            Ok(Ok(piece)) => Some(piece),
+            Ok(Err(error)) => {
                 warn!(
                     %piece_index,
                     %source_peer_id,
+                    %error,
                     "Received invalid piece from peer"
                 );
 
@@ -82,6 +83,10 @@ where
                 let _ = self.dsn_node.ban_peer(source_peer_id).await;
                 None
             }
+            Err(error) => {
+                error!(%piece_index, %error, "Piece validation task failed");
+                None
+            }
         }
     }
 }
@@ -17,24 +17,22 @@
 //! Verification primitives for Subspace.
 #![forbid(unsafe_code)]
 #![warn(rust_2018_idioms, missing_debug_implementations, missing_docs)]
-#![feature(array_chunks, portable_simd)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+use alloc::vec::Vec;
 use codec::{Decode, Encode, MaxEncodedLen};
-use core::mem;
-use core::simd::Simd;
 use schnorrkel::context::SigningContext;
 use schnorrkel::SignatureError;
 use sp_arithmetic::traits::SaturatedConversion;
-use subspace_archiving::archiver;
+use sp_arithmetic::{PerThing, Perbill};
 use subspace_core_primitives::crypto::kzg::{Commitment, Kzg, Witness};
-use subspace_core_primitives::crypto::{
-    blake3_254_hash_to_scalar, blake3_hash_list, blake3_hash_with_key, Scalar,
-};
+use subspace_core_primitives::crypto::{blake3_hash_list, Scalar};
 use subspace_core_primitives::{
-    Blake3Hash, BlockNumber, BlockWeight, HistorySize, PotOutput, PublicKey, Record,
-    RewardSignature, SectorId, SectorSlotChallenge, SegmentCommitment, SlotNumber, Solution,
-    SolutionRange,
+    Blake3Hash, BlockNumber, BlockWeight, GlobalChallenge, HistorySize, PieceIndex, PotOutput,
+    PublicKey, Record, RewardSignature, SBucket, SectorId, SectorSlotChallenge, SegmentCommitment,
+    SlotNumber, Solution, SolutionDistance, SolutionRange, SolutionShapeError,
 };
 use subspace_proof_of_space::Table;
 
@@ -42,6 +40,9 @@ use subspace_proof_of_space::Table;
 #[derive(Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
 pub enum Error {
+    /// Solution is structurally invalid
+    #[cfg_attr(feature = "thiserror", error("Invalid solution shape: {0}"))]
+    InvalidSolutionShape(SolutionShapeError),
     /// Invalid piece offset
     #[cfg_attr(feature = "thiserror", error("Piece verification failed"))]
     InvalidPieceOffset {
@@ -101,43 +102,19 @@ pub fn check_reward_signature(
     public_key.verify(reward_signing_context.bytes(hash), &signature)
 }
 
-/// Calculates solution distance for given parameters, is used as a primitive to check whether
-/// solution distance is within solution range (see [`is_within_solution_range()`]).
-fn calculate_solution_distance(
-    global_challenge: &Blake3Hash,
-    chunk: &[u8; 32],
-    sector_slot_challenge: &SectorSlotChallenge,
-) -> SolutionRange {
-    let audit_chunk = blake3_hash_with_key(sector_slot_challenge, chunk);
-    let audit_chunk_as_solution_range: SolutionRange = SolutionRange::from_le_bytes(
-        *audit_chunk
-            .array_chunks::<{ mem::size_of::<SolutionRange>() }>()
-            .next()
-            .expect("Solution range is smaller in size than global challenge; qed"),
-    );
-    let global_challenge_as_solution_range: SolutionRange = SolutionRange::from_le_bytes(
-        *global_challenge
-            .array_chunks::<{ mem::size_of::<SolutionRange>() }>()
-            .next()
-            .expect("Solution range is smaller in size than global challenge; qed"),
-    );
-    subspace_core_primitives::bidirectional_distance(
-        &global_challenge_as_solution_range,
-        &audit_chunk_as_solution_range,
-    )
-}
-
 /// Returns `Some(solution_distance)` if solution distance is within the solution range for provided
 /// parameters.
 pub fn is_within_solution_range(
-    global_challenge: &Blake3Hash,
+    global_challenge: &GlobalChallenge,
     chunk: &[u8; 32],
     sector_slot_challenge: &SectorSlotChallenge,
     solution_range: SolutionRange,
 ) -> Option<SolutionRange> {
     let solution_distance =
-        calculate_solution_distance(global_challenge, chunk, sector_slot_challenge);
-    (solution_distance <= solution_range / 2).then_some(solution_distance)
+        SolutionDistance::calculate(global_challenge, chunk, sector_slot_challenge);
+    solution_distance
+        .is_within(solution_range)
+        .then_some(solution_distance.into())
 }
 
 /// Parameters for checking piece validity
@@ -179,6 +156,9 @@ pub fn calculate_block_weight(solution_range: SolutionRange) -> BlockWeight {
 
 /// Verify whether solution is valid, returns solution distance that is `<= solution_range/2` on
 /// success.
+///
+/// Verification itself doesn't allocate on the heap, the only allocated data is KZG settings
+/// inside of `kzg` that are created by the caller once and reused for all verifications.
 pub fn verify_solution<'a, PosTable, FarmerPublicKey, RewardAddress>(
     solution: &'a Solution<FarmerPublicKey, RewardAddress>,
     slot: SlotNumber,
@@ -195,10 +175,12 @@ where
         piece_check_params,
     } = params;
 
-    let sector_id = SectorId::new(
-        PublicKey::from(&solution.public_key).hash(),
-        solution.sector_index,
-    );
+    // Reject obviously invalid solutions before expensive checks
+    solution
+        .verify_shape()
+        .map_err(Error::InvalidSolutionShape)?;
+
+    let sector_id = solution.sector_id();
 
     let global_randomness = proof_of_time.derive_global_randomness();
     let global_challenge = global_randomness.derive_global_challenge(slot);
@@ -214,18 +196,17 @@ where
         return Err(Error::InvalidProofOfSpace);
     };
 
-    let masked_chunk = (Simd::from(solution.chunk.to_bytes())
-        ^ Simd::from(solution.proof_of_space.hash()))
-    .to_array();
+    let masked_chunk =
+        Record::mask_chunk(&solution.chunk.to_bytes(), &solution.proof_of_space.hash());
 
     let solution_distance =
-        calculate_solution_distance(&global_challenge, &masked_chunk, &sector_slot_challenge);
+        SolutionDistance::calculate(&global_challenge, &masked_chunk, &sector_slot_challenge);
 
     // Check that solution is within solution range
-    if solution_distance > solution_range / 2 {
+    if !solution_distance.is_within(*solution_range) {
         return Err(Error::OutsideSolutionRange {
             half_solution_range: solution_range / 2,
-            solution_distance,
+            solution_distance: solution_distance.into(),
         });
     }
 
@@ -290,18 +271,212 @@ where
             .position();
 
         // Check that piece is part of the blockchain history
-        if !archiver::is_record_commitment_hash_valid(
-            kzg,
-            &blake3_254_hash_to_scalar(solution.record_commitment.as_ref()),
-            segment_commitment,
-            &solution.record_witness,
-            position,
-        ) {
-            return Err(Error::InvalidPiece);
+        solution
+            .record_witness
+            .verify(
+                kzg,
+                &solution.record_commitment,
+                segment_commitment,
+                position,
+            )
+            .map_err(|_error| Error::InvalidPiece)?;
+    }
+
+    Ok(solution_distance.into())
+}
+
+/// Outcome of an individual check of solution inspection.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CheckOutcome {
+    /// Check passed
+    Passed,
+    /// Check failed
+    Failed,
+    /// Check was not performed due to missing inputs or failure of a check it depends on
+    Skipped,
+}
+
+impl From<bool> for CheckOutcome {
+    #[inline]
+    fn from(passed: bool) -> Self {
+        if passed {
+            Self::Passed
+        } else {
+            Self::Failed
         }
     }
+}
+
+/// Report of solution inspection with outcome of every check and values computed along the way,
+/// see [`inspect_solution()`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SolutionInspectionReport {
+    /// Sector ID derived from public key and sector index
+    pub sector_id: SectorId,
+    /// Sector slot challenge derived from sector ID and global challenge
+    pub sector_slot_challenge: Blake3Hash,
+    /// S-bucket that was supposed to be audited
+    pub s_bucket_audit_index: SBucket,
+    /// Whether proof of space is valid
+    pub proof_of_space: CheckOutcome,
+    /// Audit chunk derived from masked chunk, interpreted as solution range
+    pub audit_chunk: SolutionRange,
+    /// Distance between global challenge and audit chunk, must be within half of solution range
+    pub solution_distance: SolutionRange,
+    /// Whether chunk belongs to the record according to record commitment and chunk witness
+    pub chunk_witness: CheckOutcome,
+    /// Whether piece offset is within the number of pieces in sector
+    pub piece_offset: CheckOutcome,
+    /// History size at which sector expires, if sector expiration was checked
+    pub expiration_history_size: Option<HistorySize>,
+    /// Whether sector is not yet expired
+    pub sector_expiration: CheckOutcome,
+    /// Index of the piece that solution was created for, if piece was checked
+    pub piece_index: Option<PieceIndex>,
+    /// Whether piece is part of the blockchain history according to segment commitment
+    pub piece: CheckOutcome,
+}
+
+impl SolutionInspectionReport {
+    /// Whether solution distance is within specified solution range
+    #[inline]
+    pub fn is_within_solution_range(&self, solution_range: SolutionRange) -> bool {
+        SolutionDistance::from(self.solution_distance).is_within(solution_range)
+    }
 
-    Ok(solution_distance)
+    /// Whether none of the checks failed, solution range check is not included, see
+    /// [`Self::is_within_solution_range()`]
+    pub fn is_valid(&self) -> bool {
+        [
+            self.proof_of_space,
+            self.chunk_witness,
+            self.piece_offset,
+            self.sector_expiration,
+            self.piece,
+        ]
+        .into_iter()
+        .all(|outcome| outcome != CheckOutcome::Failed)
+    }
+}
+
+/// Inspect solution for challenge derived from `global_challenge`, unlike [`verify_solution()`]
+/// doesn't stop at the first failed check and reports outcome of every check along with values
+/// computed in the process.
+///
+/// This is much slower than [`verify_solution()`] for invalid solutions and is meant for
+/// diagnostics only. Piece checks are skipped if `piece_check_params` is `None`.
+pub fn inspect_solution<'a, PosTable, FarmerPublicKey, RewardAddress>(
+    solution: &'a Solution<FarmerPublicKey, RewardAddress>,
+    global_challenge: &GlobalChallenge,
+    piece_check_params: Option<&PieceCheckParams>,
+    kzg: &Kzg,
+) -> SolutionInspectionReport
+where
+    PosTable: Table,
+    PublicKey: From<&'a FarmerPublicKey>,
+{
+    let sector_id = solution.sector_id();
+    let sector_slot_challenge = sector_id.derive_sector_slot_challenge(global_challenge);
+    let s_bucket_audit_index = sector_slot_challenge.s_bucket_audit_index();
+
+    let proof_of_space = CheckOutcome::from(PosTable::is_proof_valid(
+        &sector_id.derive_evaluation_seed(solution.piece_offset, solution.history_size),
+        s_bucket_audit_index.into(),
+        &solution.proof_of_space,
+    ));
+
+    let masked_chunk =
+        Record::mask_chunk(&solution.chunk.to_bytes(), &solution.proof_of_space.hash());
+
+    let chunk_witness = match (
+        Commitment::try_from(solution.record_commitment),
+        Witness::try_from(solution.chunk_witness),
+    ) {
+        (Ok(record_commitment), Ok(chunk_witness)) => CheckOutcome::from(kzg.verify(
+            &record_commitment,
+            Record::NUM_S_BUCKETS,
+            s_bucket_audit_index.into(),
+            &solution.chunk,
+            &chunk_witness,
+        )),
+        _ => CheckOutcome::Failed,
+    };
+
+    let mut report = SolutionInspectionReport {
+        sector_id,
+        sector_slot_challenge: *sector_slot_challenge,
+        s_bucket_audit_index,
+        proof_of_space,
+        audit_chunk: sector_slot_challenge.audit_chunk(&masked_chunk),
+        solution_distance: SolutionDistance::calculate(
+            global_challenge,
+            &masked_chunk,
+            &sector_slot_challenge,
+        )
+        .into(),
+        chunk_witness,
+        piece_offset: CheckOutcome::Skipped,
+        expiration_history_size: None,
+        sector_expiration: CheckOutcome::Skipped,
+        piece_index: None,
+        piece: CheckOutcome::Skipped,
+    };
+
+    let Some(PieceCheckParams {
+        max_pieces_in_sector,
+        segment_commitment,
+        recent_segments,
+        recent_history_fraction,
+        min_sector_lifetime,
+        current_history_size,
+        sector_expiration_check_segment_commitment,
+    }) = piece_check_params
+    else {
+        return report;
+    };
+
+    report.piece_offset =
+        CheckOutcome::from(u16::from(solution.piece_offset) < *max_pieces_in_sector);
+
+    if let Some(sector_expiration_check_segment_commitment) =
+        sector_expiration_check_segment_commitment
+    {
+        report.expiration_history_size = sector_id.derive_expiration_history_size(
+            solution.history_size,
+            sector_expiration_check_segment_commitment,
+            *min_sector_lifetime,
+        );
+        report.sector_expiration = CheckOutcome::from(
+            report
+                .expiration_history_size
+                .map(|expiration_history_size| expiration_history_size > *current_history_size)
+                .unwrap_or_default(),
+        );
+    }
+
+    if report.piece_offset == CheckOutcome::Passed {
+        let piece_index = sector_id.derive_piece_index(
+            solution.piece_offset,
+            solution.history_size,
+            *max_pieces_in_sector,
+            *recent_segments,
+            *recent_history_fraction,
+        );
+        report.piece_index.replace(piece_index);
+        report.piece = CheckOutcome::from(
+            solution
+                .record_witness
+                .verify(
+                    kzg,
+                    &solution.record_commitment,
+                    segment_commitment,
+                    piece_index.position(),
+                )
+                .is_ok(),
+        );
+    }
+
+    report
 }
 
 /// Derive proof of time entropy from chunk and proof of time for injection purposes.
@@ -347,3 +522,123 @@ pub fn derive_next_solution_range(
         current_solution_range.saturating_mul(4),
     )
 }
+
+/// Derive next solution range with exponential smoothing.
+///
+/// Solution range that would have resulted in expected number of blocks during the era is computed
+/// the same way as in [`derive_next_solution_range()`], but instead of switching to it right away,
+/// next solution range only moves `smoothing_factor` of the way towards it and is additionally
+/// limited to change by at most `max_adjustment` of the current solution range. This dampens
+/// oscillations of win rate after large changes of pledged space.
+pub fn derive_next_solution_range_v2(
+    start_slot: SlotNumber,
+    current_slot: SlotNumber,
+    slot_probability: (u64, u64),
+    current_solution_range: SolutionRange,
+    era_duration: BlockNumber,
+    smoothing_factor: Perbill,
+    max_adjustment: Perbill,
+) -> u64 {
+    let era_slot_count = current_slot - start_slot;
+
+    let target_solution_range = u64::saturated_from(
+        u128::from(current_solution_range)
+            .saturating_mul(u128::from(era_slot_count))
+            .saturating_mul(u128::from(slot_probability.0))
+            / u128::from(era_duration)
+            / u128::from(slot_probability.1),
+    );
+
+    let next_solution_range = if target_solution_range >= current_solution_range {
+        current_solution_range.saturating_add(
+            smoothing_factor.mul_floor(target_solution_range - current_solution_range),
+        )
+    } else {
+        current_solution_range
+            - smoothing_factor.mul_floor(current_solution_range - target_solution_range)
+    };
+
+    let max_change = max_adjustment.mul_floor(current_solution_range);
+    next_solution_range.clamp(
+        current_solution_range.saturating_sub(max_change),
+        current_solution_range.saturating_add(max_change),
+    )
+}
+
+/// Rewards issued in a block, included into block header digest such that light clients and
+/// explorers can learn about rewards without executing the block.
+///
+/// Reward address of the block author is not included since it is already present in the
+/// pre-digest of the same header.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct RewardsStatement<RewardAddress, Balance> {
+    /// Reward of the block author, `None` if block author was not rewarded (for instance due to
+    /// equivocation or rewards not being enabled yet)
+    pub block_reward: Option<Balance>,
+    /// Rewards of voters in the order in which votes were included in the block
+    pub vote_rewards: Vec<(RewardAddress, Balance)>,
+}
+
+/// Errors encountered during verification of [`RewardsStatement`]
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+pub enum RewardsStatementError {
+    /// Block reward doesn't match expected block reward
+    #[cfg_attr(feature = "thiserror", error("Invalid block reward"))]
+    InvalidBlockReward,
+    /// Vote reward doesn't match expected vote reward
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Invalid vote reward at position {position}")
+    )]
+    InvalidVoteReward {
+        /// Position of the vote reward in the statement
+        position: usize,
+    },
+    /// Statement contains vote rewards, but rewards are not enabled
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Vote rewards are present, but rewards are not enabled")
+    )]
+    UnexpectedVoteRewards,
+}
+
+/// Verify rewards statement against reward amounts of the chain, `rewards` is `None` if rewards
+/// are not enabled yet at the block to which statement belongs.
+///
+/// Block author reward may be missing even with rewards enabled since block author might have
+/// equivocated, which can't be checked without executing the block.
+pub fn verify_rewards_statement<RewardAddress, Balance>(
+    statement: &RewardsStatement<RewardAddress, Balance>,
+    rewards: Option<(Balance, Balance)>,
+) -> Result<(), RewardsStatementError>
+where
+    Balance: PartialEq,
+{
+    let Some((block_reward, vote_reward)) = rewards else {
+        if statement.block_reward.is_some() {
+            return Err(RewardsStatementError::InvalidBlockReward);
+        }
+        if !statement.vote_rewards.is_empty() {
+            return Err(RewardsStatementError::UnexpectedVoteRewards);
+        }
+
+        return Ok(());
+    };
+
+    if let Some(statement_block_reward) = &statement.block_reward {
+        if statement_block_reward != &block_reward {
+            return Err(RewardsStatementError::InvalidBlockReward);
+        }
+    }
+
+    if let Some(position) = statement
+        .vote_rewards
+        .iter()
+        .position(|(_reward_address, reward)| reward != &vote_reward)
+    {
+        return Err(RewardsStatementError::InvalidVoteReward { position });
+    }
+
+    Ok(())
+}
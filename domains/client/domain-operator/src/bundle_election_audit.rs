@@ -0,0 +1,139 @@
+//! Audit trail of the bundle producer election.
+//!
+//! Every time the operator solves the bundle producer election challenge for a slot, the inputs of
+//! the election (stakes, slot probability), the resulting threshold and the VRF output are
+//! recorded, so it is possible to explain afterwards why a bundle was or was not produced in a
+//! particular slot without re-deriving the election by hand.
+
+use parking_lot::Mutex;
+use sp_consensus_slots::Slot;
+use sp_domains::{DomainId, OperatorId};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use subspace_runtime_primitives::Balance;
+
+/// Number of most recent election attempts kept in memory.
+const RECENT_ELECTIONS_LIMIT: usize = 1024;
+
+/// Outcome of the bundle producer election in a particular slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleElectionOutcome {
+    /// VRF output was below the threshold, operator was elected to produce a bundle
+    Elected,
+    /// VRF output was not below the threshold
+    NotElected,
+    /// Operator is not registered (or not active anymore) according to the consensus runtime
+    OperatorNotRegistered,
+    /// Operator signing key is not available in the keystore, VRF signature can't be produced
+    SigningKeyUnavailable,
+}
+
+/// Bundle producer election attempt of an operator in a particular slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleElectionRecord {
+    /// Domain the election was for
+    pub domain_id: DomainId,
+    /// Slot the election was for
+    pub slot: Slot,
+    /// Operator that attempted to be elected
+    pub operator_id: OperatorId,
+    /// SCALE-encoded hash of the consensus block the stake and election parameters were read at
+    pub consensus_block_hash: Vec<u8>,
+    /// Stake of the operator, zero if operator is not registered
+    pub operator_stake: Balance,
+    /// Total stake of the domain
+    pub total_domain_stake: Balance,
+    /// Expected number of bundles per slot of the domain as a fraction
+    pub bundle_slot_probability: (u64, u64),
+    /// Threshold VRF output must be below of for operator to be elected
+    pub threshold: u128,
+    /// VRF output compared against the threshold, if VRF signature was produced
+    pub vrf_output: Option<u128>,
+    /// Outcome of the election
+    pub outcome: BundleElectionOutcome,
+}
+
+impl BundleElectionRecord {
+    /// Probability of the operator being elected in this slot given the threshold.
+    pub fn election_probability(&self) -> f64 {
+        self.threshold as f64 / u128::MAX as f64
+    }
+}
+
+/// Statistics of recent bundle producer election attempts of an operator.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BundleElectionStats {
+    /// Number of slots operator attempted to be elected in
+    pub attempts: u64,
+    /// Number of slots operator was elected in
+    pub elected: u64,
+    /// Expected number of slots operator should have been elected in according to thresholds
+    pub expected_elected: f64,
+    /// Number of attempts where operator was not registered
+    pub operator_not_registered: u64,
+    /// Number of attempts where operator signing key was not available in the keystore
+    pub signing_key_unavailable: u64,
+    /// Last slot operator attempted to be elected in
+    pub last_slot: Option<Slot>,
+    /// Last slot operator was elected in
+    pub last_elected_slot: Option<Slot>,
+}
+
+/// Shared in-memory audit trail of the most recent bundle producer election attempts.
+#[derive(Debug, Default, Clone)]
+pub struct BundleElectionAudit {
+    recent_elections: Arc<Mutex<VecDeque<BundleElectionRecord>>>,
+}
+
+impl BundleElectionAudit {
+    pub(crate) fn record(&self, record: BundleElectionRecord) {
+        let mut recent_elections = self.recent_elections.lock();
+        if recent_elections.len() == RECENT_ELECTIONS_LIMIT {
+            recent_elections.pop_front();
+        }
+        recent_elections.push_back(record);
+    }
+
+    /// Election attempt of `operator_id` in `slot`, `None` if operator didn't attempt to be
+    /// elected in that slot or attempt is too old and no longer retained.
+    pub fn election(&self, slot: Slot, operator_id: OperatorId) -> Option<BundleElectionRecord> {
+        self.recent_elections
+            .lock()
+            .iter()
+            .rev()
+            .find(|record| record.slot == slot && record.operator_id == operator_id)
+            .cloned()
+    }
+
+    /// Statistics of retained election attempts of `operator_id`.
+    pub fn stats(&self, operator_id: OperatorId) -> BundleElectionStats {
+        let mut stats = BundleElectionStats::default();
+
+        for record in self
+            .recent_elections
+            .lock()
+            .iter()
+            .filter(|record| record.operator_id == operator_id)
+        {
+            stats.attempts += 1;
+            stats.expected_elected += record.election_probability();
+            stats.last_slot.replace(record.slot);
+
+            match record.outcome {
+                BundleElectionOutcome::Elected => {
+                    stats.elected += 1;
+                    stats.last_elected_slot.replace(record.slot);
+                }
+                BundleElectionOutcome::NotElected => {}
+                BundleElectionOutcome::OperatorNotRegistered => {
+                    stats.operator_not_registered += 1;
+                }
+                BundleElectionOutcome::SigningKeyUnavailable => {
+                    stats.signing_key_unavailable += 1;
+                }
+            }
+        }
+
+        stats
+    }
+}
@@ -1,9 +1,14 @@
+use crate::bundle_election_audit::{
+    BundleElectionAudit, BundleElectionOutcome, BundleElectionRecord,
+};
+use crate::signing_key_rotation::SigningKeyRotationTracker;
+use codec::Encode;
 use sp_api::ProvideRuntimeApi;
 use sp_consensus_slots::Slot;
 use sp_core::bytes::to_hex;
 use sp_core::ByteArray;
 use sp_domains::bundle_producer_election::{
-    calculate_threshold, is_below_threshold, make_transcript, BundleProducerElectionParams,
+    calculate_threshold, make_transcript, vrf_output_value, BundleProducerElectionParams,
 };
 use sp_domains::{
     BundleProducerElectionApi, DomainId, OperatorId, OperatorPublicKey, ProofOfElection,
@@ -20,6 +25,8 @@ use tracing::log;
 pub(super) struct BundleProducerElectionSolver<Block, CBlock, CClient> {
     keystore: KeystorePtr,
     consensus_client: Arc<CClient>,
+    bundle_election_audit: BundleElectionAudit,
+    signing_key_rotation: SigningKeyRotationTracker,
     _phantom_data: PhantomData<(Block, CBlock)>,
 }
 
@@ -28,6 +35,8 @@ impl<Block, CBlock, CClient> Clone for BundleProducerElectionSolver<Block, CBloc
         Self {
             keystore: self.keystore.clone(),
             consensus_client: self.consensus_client.clone(),
+            bundle_election_audit: self.bundle_election_audit.clone(),
+            signing_key_rotation: self.signing_key_rotation.clone(),
             _phantom_data: self._phantom_data,
         }
     }
@@ -40,10 +49,16 @@ where
     CClient: ProvideRuntimeApi<CBlock>,
     CClient::Api: BundleProducerElectionApi<CBlock, Balance>,
 {
-    pub(super) fn new(keystore: KeystorePtr, consensus_client: Arc<CClient>) -> Self {
+    pub(super) fn new(
+        keystore: KeystorePtr,
+        consensus_client: Arc<CClient>,
+        bundle_election_audit: BundleElectionAudit,
+    ) -> Self {
         Self {
             keystore,
             consensus_client,
+            bundle_election_audit,
+            signing_key_rotation: SigningKeyRotationTracker::default(),
             _phantom_data: PhantomData,
         }
     }
@@ -74,51 +89,89 @@ where
             .derive_global_challenge(slot.into());
         let vrf_sign_data = make_transcript(domain_id, &global_challenge).into_sign_data();
 
-        // Ideally, we can already cache operator signing key since we do not allow changing such key
-        // in the protocol right now. Leaving this as is since we anyway need to need to fetch operator's
-        // latest stake and this also returns the signing key with it.
-        if let Some((operator_signing_key, operator_stake)) = self
+        let mut record = BundleElectionRecord {
+            domain_id,
+            slot,
+            operator_id,
+            consensus_block_hash: consensus_block_hash.encode(),
+            operator_stake: 0,
+            total_domain_stake,
+            bundle_slot_probability,
+            threshold: 0,
+            vrf_output: None,
+            outcome: BundleElectionOutcome::OperatorNotRegistered,
+        };
+
+        // Signing key is fetched in every slot since it can be rotated at the epoch boundary, this
+        // way bundle production switches to the new key as soon as it takes effect.
+        let maybe_operator = self
             .consensus_client
             .runtime_api()
-            .operator(consensus_block_hash, operator_id)?
-        {
-            if let Ok(maybe_vrf_signature) = Keystore::sr25519_vrf_sign(
-                &*self.keystore,
-                OperatorPublicKey::ID,
-                &operator_signing_key.clone().into(),
-                &vrf_sign_data,
-            ) {
-                if let Some(vrf_signature) = maybe_vrf_signature {
-                    let threshold = calculate_threshold(
-                        operator_stake,
-                        total_domain_stake,
-                        bundle_slot_probability,
-                    );
-
-                    if is_below_threshold(&vrf_signature.pre_output, threshold) {
-                        let proof_of_election = ProofOfElection {
-                            domain_id,
-                            slot_number: slot.into(),
-                            proof_of_time,
-                            vrf_signature,
-                            operator_id,
-                            consensus_block_hash,
-                        };
-                        return Ok(Some((proof_of_election, operator_signing_key)));
-                    }
-                } else {
-                    log::warn!(
-                            "Operator[{operator_id}]'s Signing key[{}] pair is not available in keystore.",
-                            to_hex(operator_signing_key.as_slice(), false)
-                        );
-                    return Ok(None);
-                }
-            }
-        } else {
+            .operator(consensus_block_hash, operator_id)?;
+        let Some((operator_signing_key, operator_stake)) = maybe_operator else {
             log::warn!("Operator[{operator_id}] is not registered on the Runtime",);
+            self.bundle_election_audit.record(record);
+            return Ok(None);
+        };
+
+        let maybe_pending_signing_key = self
+            .consensus_client
+            .runtime_api()
+            .operator_pending_signing_key(consensus_block_hash, operator_id)?;
+        self.signing_key_rotation.note_signing_keys(
+            &self.keystore,
+            operator_id,
+            &operator_signing_key,
+            maybe_pending_signing_key.as_ref(),
+        );
+
+        record.operator_stake = operator_stake;
+        record.threshold =
+            calculate_threshold(operator_stake, total_domain_stake, bundle_slot_probability);
+
+        let Ok(maybe_vrf_signature) = Keystore::sr25519_vrf_sign(
+            &*self.keystore,
+            OperatorPublicKey::ID,
+            &operator_signing_key.clone().into(),
+            &vrf_sign_data,
+        ) else {
+            return Ok(None);
+        };
+
+        let Some(vrf_signature) = maybe_vrf_signature else {
+            log::warn!(
+                "Operator[{operator_id}]'s Signing key[{}] pair is not available in keystore.",
+                to_hex(operator_signing_key.as_slice(), false)
+            );
+            record.outcome = BundleElectionOutcome::SigningKeyUnavailable;
+            self.bundle_election_audit.record(record);
+            return Ok(None);
+        };
+
+        let vrf_output = vrf_output_value(&vrf_signature.pre_output);
+        let is_elected = vrf_output < record.threshold;
+
+        record.vrf_output.replace(vrf_output);
+        record.outcome = if is_elected {
+            BundleElectionOutcome::Elected
+        } else {
+            BundleElectionOutcome::NotElected
+        };
+        self.bundle_election_audit.record(record);
+
+        if !is_elected {
             return Ok(None);
         }
 
-        Ok(None)
+        let proof_of_election = ProofOfElection {
+            domain_id,
+            slot_number: slot.into(),
+            proof_of_time,
+            vrf_signature,
+            operator_id,
+            consensus_block_hash,
+        };
+
+        Ok(Some((proof_of_election, operator_signing_key)))
     }
 }
@@ -0,0 +1,228 @@
+//! Stateless validation of bundles for relay nodes.
+//!
+//! Relay nodes participate in bundle propagation without running a domain node, they neither keep
+//! domain state nor execute domain blocks. Everything that can be checked about a bundle using
+//! consensus chain state only (operator signature, proof of election, extrinsics root and size) is
+//! checked against the best consensus block, bundles that fail validation are evicted from the
+//! local consensus transaction pool so they are not propagated any further.
+
+use crate::OpaqueBundleFor;
+use codec::Encode;
+use futures::StreamExt;
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+use sp_api::{ApiError, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_domains::bundle_producer_election::{check_proof_of_election, ProofOfElectionError};
+use sp_domains::{BundleProducerElectionApi, DomainId, DomainsApi, OperatorId};
+use sp_runtime::traits::{Block as BlockT, Hash as HashT, Header as HeaderT};
+use sp_runtime::RuntimeAppPublic;
+use std::collections::BTreeSet;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use subspace_runtime_primitives::Balance;
+use tracing::{debug, trace, warn};
+
+/// Errors of stateless bundle validation
+#[derive(Debug, thiserror::Error)]
+pub enum BundleRelayError {
+    /// Runtime API error
+    #[error("Runtime API error: {0}")]
+    RuntimeApi(#[from] ApiError),
+    /// Operator is not registered
+    #[error("Operator {0} is not registered")]
+    UnknownOperator(OperatorId),
+    /// Operator doesn't have stake in the current epoch of the domain
+    #[error("Operator {0} is not part of the current epoch")]
+    OperatorNotInEpoch(OperatorId),
+    /// Domain doesn't exist
+    #[error("Domain {0:?} doesn't exist")]
+    UnknownDomain(DomainId),
+    /// Bundle signature is not valid
+    #[error("Bundle signature is not valid")]
+    BadSignature,
+    /// Extrinsics root doesn't match extrinsics in the bundle
+    #[error("Extrinsics root doesn't match extrinsics in the bundle")]
+    InvalidExtrinsicsRoot,
+    /// Bundle is larger than the domain block
+    #[error("Bundle size {size} exceeds max domain block size {max_block_size}")]
+    BundleTooLarge {
+        /// Size of the bundle
+        size: u32,
+        /// Max domain block size
+        max_block_size: u32,
+    },
+    /// Proof of election is not valid
+    #[error("Proof of election is not valid: {0:?}")]
+    ProofOfElection(ProofOfElectionError),
+}
+
+/// Validates bundles using consensus chain state only
+pub struct StatelessBundleValidator<Block, CBlock, CClient> {
+    consensus_client: Arc<CClient>,
+    _phantom: PhantomData<(Block, CBlock)>,
+}
+
+impl<Block, CBlock, CClient> Clone for StatelessBundleValidator<Block, CBlock, CClient> {
+    fn clone(&self) -> Self {
+        Self {
+            consensus_client: Arc::clone(&self.consensus_client),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Block, CBlock, CClient> StatelessBundleValidator<Block, CBlock, CClient>
+where
+    Block: BlockT,
+    CBlock: BlockT,
+    CClient: HeaderBackend<CBlock> + ProvideRuntimeApi<CBlock>,
+    CClient::Api: DomainsApi<CBlock, Block::Header> + BundleProducerElectionApi<CBlock, Balance>,
+{
+    /// Create new instance
+    pub fn new(consensus_client: Arc<CClient>) -> Self {
+        Self {
+            consensus_client,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Validate bundle against consensus chain state at block `at`.
+    ///
+    /// Checks that require domain state (like validity of the execution receipt and extrinsics
+    /// themselves) are not done here, they are done by consensus runtime and domain operators.
+    pub fn validate(
+        &self,
+        at: CBlock::Hash,
+        opaque_bundle: &OpaqueBundleFor<Block, CBlock>,
+    ) -> Result<(), BundleRelayError> {
+        let domain_id = opaque_bundle.domain_id();
+        let operator_id = opaque_bundle.operator_id();
+        let sealed_header = &opaque_bundle.sealed_header;
+        let runtime_api = self.consensus_client.runtime_api();
+
+        let (signing_key, _current_stake) = runtime_api
+            .operator(at, operator_id)?
+            .ok_or(BundleRelayError::UnknownOperator(operator_id))?;
+
+        if !signing_key.verify(&sealed_header.pre_hash(), &sealed_header.signature) {
+            return Err(BundleRelayError::BadSignature);
+        }
+
+        let max_block_size = runtime_api
+            .domain_block_limit(at, domain_id)?
+            .ok_or(BundleRelayError::UnknownDomain(domain_id))?
+            .max_block_size;
+        let size = opaque_bundle.size();
+        if size > max_block_size {
+            return Err(BundleRelayError::BundleTooLarge {
+                size,
+                max_block_size,
+            });
+        }
+
+        let expected_extrinsics_root =
+            <<Block::Header as HeaderT>::Hashing as HashT>::ordered_trie_root(
+                opaque_bundle
+                    .extrinsics
+                    .iter()
+                    .map(|xt| xt.encode())
+                    .collect(),
+                sp_core::storage::StateVersion::V1,
+            );
+        if expected_extrinsics_root != opaque_bundle.extrinsics_root() {
+            return Err(BundleRelayError::InvalidExtrinsicsRoot);
+        }
+
+        let election_params = runtime_api
+            .bundle_producer_election_params(at, domain_id)?
+            .ok_or(BundleRelayError::UnknownDomain(domain_id))?;
+        // Election uses stake of the operator in the current epoch
+        let (current_epoch_operators, _next_epoch_operators) = runtime_api
+            .domain_operators(at, domain_id)?
+            .ok_or(BundleRelayError::UnknownDomain(domain_id))?;
+        let operator_stake = current_epoch_operators
+            .get(&operator_id)
+            .copied()
+            .ok_or(BundleRelayError::OperatorNotInEpoch(operator_id))?;
+
+        check_proof_of_election(
+            &signing_key,
+            election_params.bundle_slot_probability,
+            &sealed_header.header.proof_of_election,
+            operator_stake,
+            election_params.total_domain_stake,
+        )
+        .map_err(BundleRelayError::ProofOfElection)
+    }
+}
+
+/// Run bundle relay for specified domains.
+///
+/// Every bundle of relayed domains that enters consensus transaction pool is validated with
+/// [`StatelessBundleValidator`] against the best consensus block, invalid bundles are removed from
+/// the pool and thus not propagated further.
+pub async fn run_bundle_relay<Block, CBlock, CClient, TxPool>(
+    relayed_domains: BTreeSet<DomainId>,
+    consensus_client: Arc<CClient>,
+    transaction_pool: Arc<TxPool>,
+) where
+    Block: BlockT,
+    CBlock: BlockT,
+    CClient: HeaderBackend<CBlock> + ProvideRuntimeApi<CBlock> + Send + Sync + 'static,
+    CClient::Api: DomainsApi<CBlock, Block::Header> + BundleProducerElectionApi<CBlock, Balance>,
+    TxPool: TransactionPool<Block = CBlock> + 'static,
+{
+    let validator =
+        StatelessBundleValidator::<Block, CBlock, CClient>::new(Arc::clone(&consensus_client));
+    let mut import_notification_stream = transaction_pool.import_notification_stream();
+
+    while let Some(tx_hash) = import_notification_stream.next().await {
+        let Some(transaction) = transaction_pool.ready_transaction(&tx_hash) else {
+            continue;
+        };
+        let best_hash = consensus_client.info().best_hash;
+
+        let maybe_bundle = match consensus_client
+            .runtime_api()
+            .extract_bundle(best_hash, transaction.data().clone())
+        {
+            Ok(maybe_bundle) => maybe_bundle,
+            Err(error) => {
+                warn!(%error, "Failed to extract bundle from transaction");
+                continue;
+            }
+        };
+        let Some(opaque_bundle) = maybe_bundle else {
+            continue;
+        };
+
+        let domain_id = opaque_bundle.domain_id();
+        if !relayed_domains.contains(&domain_id) {
+            continue;
+        }
+
+        match validator.validate(best_hash, &opaque_bundle) {
+            Ok(()) => {
+                trace!(
+                    ?domain_id,
+                    operator_id = %opaque_bundle.operator_id(),
+                    ?tx_hash,
+                    "Relaying valid bundle"
+                );
+            }
+            Err(BundleRelayError::RuntimeApi(error)) => {
+                warn!(%error, ?tx_hash, "Failed to validate bundle");
+            }
+            Err(error) => {
+                debug!(
+                    %error,
+                    ?domain_id,
+                    operator_id = %opaque_bundle.operator_id(),
+                    ?tx_hash,
+                    "Invalid bundle, removing from transaction pool"
+                );
+                transaction_pool.remove_invalid(&[tx_hash]);
+            }
+        }
+    }
+}
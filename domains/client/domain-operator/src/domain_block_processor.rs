@@ -1,6 +1,9 @@
 use crate::aux_schema::BundleMismatchType;
 use crate::fraud_proof::FraudProofGenerator;
-use crate::utils::{DomainBlockImportNotification, DomainImportNotificationSinks};
+use crate::utils::{
+    DomainBlockImportNotification, DomainImportNotificationSinks, DomainReorgNotification,
+    DomainReorgNotificationSinks,
+};
 use crate::ExecutionReceiptFor;
 use codec::{Decode, Encode};
 use domain_block_builder::{BlockBuilder, BuiltBlock, RecordProof};
@@ -62,6 +65,7 @@ where
     pub(crate) domain_confirmation_depth: NumberFor<Block>,
     pub(crate) block_import: SharedBlockImport<Block>,
     pub(crate) import_notification_sinks: DomainImportNotificationSinks<Block, CBlock>,
+    pub(crate) reorg_notification_sinks: DomainReorgNotificationSinks<Block, CBlock>,
     pub(crate) consensus_network_sync_oracle: Arc<dyn SyncOracle + Send + Sync>,
 }
 
@@ -81,6 +85,7 @@ where
             domain_confirmation_depth: self.domain_confirmation_depth,
             block_import: self.block_import.clone(),
             import_notification_sinks: self.import_notification_sinks.clone(),
+            reorg_notification_sinks: self.reorg_notification_sinks.clone(),
             consensus_network_sync_oracle: self.consensus_network_sync_oracle.clone(),
         }
     }
@@ -227,44 +232,100 @@ where
                 let (common_block_number, common_block_hash) =
                     (route.common_block().number, route.common_block().hash);
 
-                // The `common_block` is smaller than the consensus block that the domain was created at, thus
-                // we can safely skip any consensus block that is smaller than `domain_created_at` and start at
-                // the consensus block `domain_created_at + 1`, which the first block that possibly contains bundle,
-                // and use the genesis domain block as the initial parent block.
-                if common_block_number <= self.domain_created_at {
-                    let consensus_imports = enacted
-                        .iter()
-                        .skip_while(|block| block.number <= self.domain_created_at)
-                        .cloned()
-                        .collect();
-                    return Ok(Some(PendingConsensusBlocks {
-                        initial_parent: (self.client.info().genesis_hash, Zero::zero()),
-                        consensus_imports,
-                    }));
+                // Get the domain block that is derived from the common consensus block and use it as
+                // the initial domain parent block.
+                //
+                // The mapping of the common block may be missing if the re-org is deeper than what
+                // the operator has processed on the new branch (i.e. the consensus blocks were
+                // imported as non-best and skipped), in which case walk back to the closest
+                // ancestor that has the mapping and re-derive domain blocks from there.
+                let mut consensus_imports = Vec::new();
+                let mut ancestor = HashAndNumber {
+                    hash: common_block_hash,
+                    number: common_block_number,
+                };
+                let initial_parent = loop {
+                    // The `common_block` (or its ancestor) is not larger than the consensus block
+                    // that the domain was created at, use the genesis domain block as the initial
+                    // parent block.
+                    if ancestor.number <= self.domain_created_at {
+                        break (self.client.info().genesis_hash, Zero::zero());
+                    }
+
+                    if let Some(domain_block_hash) =
+                        crate::aux_schema::best_domain_hash_for::<_, Block::Hash, _>(
+                            &*self.client,
+                            &ancestor.hash,
+                        )?
+                    {
+                        let parent_header =
+                            self.client.header(domain_block_hash)?.ok_or_else(|| {
+                                sp_blockchain::Error::Backend(format!(
+                                    "Domain block header for #{domain_block_hash:?} not found",
+                                ))
+                            })?;
+                        break (parent_header.hash(), *parent_header.number());
+                    }
+
+                    let ancestor_header =
+                        self.consensus_client
+                            .header(ancestor.hash)?
+                            .ok_or_else(|| {
+                                sp_blockchain::Error::Backend(format!(
+                                    "Consensus block header for #{},{} not found",
+                                    ancestor.number, ancestor.hash
+                                ))
+                            })?;
+                    let parent = HashAndNumber {
+                        hash: *ancestor_header.parent_hash(),
+                        number: ancestor.number.saturating_sub(One::one()),
+                    };
+                    consensus_imports.push(ancestor);
+                    ancestor = parent;
+                };
+                // Blocks were collected from the highest to the lowest
+                consensus_imports.reverse();
+
+                if !consensus_imports.is_empty() {
+                    tracing::warn!(
+                        "Domain block derived from consensus block #{common_block_number},{common_block_hash} \
+                        not found, re-deriving {} domain block(s) on top of #{},{}",
+                        consensus_imports.len(),
+                        initial_parent.1,
+                        initial_parent.0,
+                    );
                 }
 
-                // Get the domain block that is derived from the common consensus block and use it as
-                // the initial domain parent block
-                let domain_block_hash: Block::Hash = crate::aux_schema::best_domain_hash_for(
-                    &*self.client,
-                    &common_block_hash,
-                )?
-                    .ok_or_else(
-                        || {
-                            sp_blockchain::Error::Backend(format!(
-                                "Hash of domain block derived from consensus block #{common_block_number},{common_block_hash} not found"
-                            ))
-                        },
-                    )?;
-                let parent_header = self.client.header(domain_block_hash)?.ok_or_else(|| {
-                    sp_blockchain::Error::Backend(format!(
-                        "Domain block header for #{domain_block_hash:?} not found",
-                    ))
-                })?;
+                consensus_imports.extend(enacted.iter().cloned());
+                // Blocks at or below `domain_created_at` can't contain bundles
+                consensus_imports.retain(|block| block.number > self.domain_created_at);
+
+                tracing::info!(
+                    retracted = retracted.len(),
+                    enacted = enacted.len(),
+                    "Consensus chain re-org at #{common_block_number},{common_block_hash}, \
+                    rolling back best domain block #{best_number},{best_hash} to #{},{}",
+                    initial_parent.1,
+                    initial_parent.0,
+                );
+
+                let reorg_notification = DomainReorgNotification {
+                    common_consensus_block: (common_block_hash, common_block_number),
+                    retracted_consensus_blocks: retracted.iter().map(|block| block.hash).collect(),
+                    enacted_consensus_blocks: consensus_imports
+                        .iter()
+                        .map(|block| block.hash)
+                        .collect(),
+                    old_best_domain_block: (best_hash, best_number),
+                    new_domain_parent: initial_parent,
+                };
+                self.reorg_notification_sinks
+                    .lock()
+                    .retain(|sink| sink.unbounded_send(reorg_notification.clone()).is_ok());
 
                 Ok(Some(PendingConsensusBlocks {
-                    initial_parent: (parent_header.hash(), *parent_header.number()),
-                    consensus_imports: enacted.to_vec(),
+                    initial_parent,
+                    consensus_imports,
                 }))
             }
         }
@@ -1,5 +1,7 @@
+use crate::bundle_election_audit::BundleElectionAudit;
 use crate::bundle_producer_election_solver::BundleProducerElectionSolver;
 use crate::domain_bundle_proposer::DomainBundleProposer;
+use crate::external_bundles::{ExternalBundleOutcome, ExternalBundles};
 use crate::utils::OperatorSlotInfo;
 use crate::BundleSender;
 use codec::Decode;
@@ -41,6 +43,7 @@ where
     bundle_producer_election_solver: BundleProducerElectionSolver<Block, CBlock, CClient>,
     domain_bundle_proposer: DomainBundleProposer<Block, Client, CBlock, CClient, TransactionPool>,
     skip_empty_bundle_production: bool,
+    external_bundles: ExternalBundles,
 }
 
 impl<Block, CBlock, Client, CClient, TransactionPool> Clone
@@ -59,6 +62,7 @@ where
             bundle_producer_election_solver: self.bundle_producer_election_solver.clone(),
             domain_bundle_proposer: self.domain_bundle_proposer.clone(),
             skip_empty_bundle_production: self.skip_empty_bundle_production,
+            external_bundles: self.external_bundles.clone(),
         }
     }
 }
@@ -92,10 +96,13 @@ where
         bundle_sender: Arc<BundleSender<Block, CBlock>>,
         keystore: KeystorePtr,
         skip_empty_bundle_production: bool,
+        bundle_election_audit: BundleElectionAudit,
+        external_bundles: ExternalBundles,
     ) -> Self {
         let bundle_producer_election_solver = BundleProducerElectionSolver::<Block, CBlock, _>::new(
             keystore.clone(),
             consensus_client.clone(),
+            bundle_election_audit,
         );
         Self {
             domain_id,
@@ -106,6 +113,7 @@ where
             bundle_producer_election_solver,
             domain_bundle_proposer,
             skip_empty_bundle_production,
+            external_bundles,
         }
     }
 
@@ -162,10 +170,46 @@ where
                         "Error getting tx range: {error}"
                     )))
                 })?;
-            let (bundle_header, extrinsics) = self
-                .domain_bundle_proposer
-                .propose_bundle_at(proof_of_election, tx_range)
-                .await?;
+            let external_bundle_proposal = match self.external_bundles.take(slot) {
+                Some(external_bundle) => {
+                    let proposal = match decode_extrinsics::<Block>(&external_bundle.extrinsics) {
+                        Ok(extrinsics) => self.domain_bundle_proposer.propose_external_bundle_at(
+                            proof_of_election.clone(),
+                            tx_range,
+                            extrinsics,
+                        )?,
+                        Err(reason) => Err(reason),
+                    };
+
+                    match proposal {
+                        Ok(proposal) => {
+                            self.external_bundles
+                                .record_outcome(ExternalBundleOutcome::Submitted { slot });
+                            Some(proposal)
+                        }
+                        Err(reason) => {
+                            tracing::warn!(
+                                %slot,
+                                %reason,
+                                "Rejected external bundle, proposing bundle locally"
+                            );
+                            self.external_bundles
+                                .record_outcome(ExternalBundleOutcome::Rejected { slot, reason });
+                            None
+                        }
+                    }
+                }
+                None => None,
+            };
+
+            let (bundle_header, extrinsics) = match external_bundle_proposal {
+                Some(proposal) => proposal,
+                None => {
+                    self.domain_bundle_proposer
+                        .propose_bundle_at(proof_of_election, tx_range)
+                        .await?
+                }
+            };
 
             // if there are no extrinsics and no receipts to confirm, skip the bundle
             if self.skip_empty_bundle_production
@@ -226,3 +270,16 @@ where
         }
     }
 }
+
+fn decode_extrinsics<Block: BlockT>(
+    extrinsics: &[Vec<u8>],
+) -> Result<Vec<Block::Extrinsic>, String> {
+    extrinsics
+        .iter()
+        .enumerate()
+        .map(|(index, extrinsic)| {
+            Block::Extrinsic::decode(&mut extrinsic.as_slice())
+                .map_err(|error| format!("Failed to decode extrinsic {index}: {error}"))
+        })
+        .collect()
+}
@@ -82,8 +82,11 @@ impl<Block: BlockT, Client, CBlock: BlockT, CClient, TransactionPool> Clone
     }
 }
 
+type ProposeBundleHeader<Block, CBlock> =
+    BundleHeader<NumberFor<CBlock>, <CBlock as BlockT>::Hash, <Block as BlockT>::Header, Balance>;
+
 pub(super) type ProposeBundleOutput<Block, CBlock> = (
-    BundleHeader<NumberFor<CBlock>, <CBlock as BlockT>::Hash, <Block as BlockT>::Header, Balance>,
+    ProposeBundleHeader<Block, CBlock>,
     Vec<<Block as BlockT>::Extrinsic>,
 );
 
@@ -264,6 +267,115 @@ where
             }
         }
 
+        let header = self.build_bundle_header(
+            proof_of_election,
+            parent_number,
+            &extrinsics,
+            estimated_bundle_weight,
+        )?;
+
+        Ok((header, extrinsics))
+    }
+
+    /// Propose bundle with extrinsics constructed by an external builder.
+    ///
+    /// Unlike [`Self::propose_bundle_at`] extrinsics are not cherry-picked, the external bundle is
+    /// either accepted as a whole or rejected with the reason returned in the inner `Err`.
+    pub(crate) fn propose_external_bundle_at(
+        &mut self,
+        proof_of_election: ProofOfElection<CBlock::Hash>,
+        tx_range: U256,
+        extrinsics: Vec<Block::Extrinsic>,
+    ) -> sp_blockchain::Result<Result<ProposeBundleOutput<Block, CBlock>, String>> {
+        let parent_number = self.client.info().best_number;
+        let parent_hash = self.client.info().best_hash;
+
+        let bundle_vrf_hash = U256::from_be_bytes(proof_of_election.vrf_hash());
+        let domain_block_limit = self
+            .consensus_client
+            .runtime_api()
+            .domain_block_limit(self.consensus_client.info().best_hash, self.domain_id)?
+            .ok_or_else(|| {
+                sp_blockchain::Error::Application(
+                    format!("Domain block limit for {:?} not found", self.domain_id).into(),
+                )
+            })?;
+        let mut estimated_bundle_weight = Weight::default();
+        let mut bundle_size = 0u32;
+
+        {
+            let runtime_api_instance = self.client.runtime_api();
+            for (index, extrinsic) in extrinsics.iter().enumerate() {
+                let is_within_tx_range = runtime_api_instance.is_within_tx_range(
+                    parent_hash,
+                    extrinsic,
+                    &bundle_vrf_hash,
+                    &tx_range,
+                )?;
+                if !is_within_tx_range {
+                    return Ok(Err(format!("Extrinsic {index} is out of tx range")));
+                }
+
+                let tx_weight = runtime_api_instance
+                    .extrinsic_weight(parent_hash, extrinsic)
+                    .map_err(|error| {
+                        sp_blockchain::Error::Application(Box::from(format!(
+                            "Error getting extrinsic weight: {error}"
+                        )))
+                    })?;
+                estimated_bundle_weight = estimated_bundle_weight.saturating_add(tx_weight);
+                if estimated_bundle_weight.any_gt(domain_block_limit.max_block_weight) {
+                    return Ok(Err(format!(
+                        "Bundle weight exceeds domain block limit at extrinsic {index}"
+                    )));
+                }
+
+                bundle_size += extrinsic.encoded_size() as u32;
+                if bundle_size > domain_block_limit.max_block_size {
+                    return Ok(Err(format!(
+                        "Bundle size exceeds domain block limit at extrinsic {index}"
+                    )));
+                }
+
+                // Same double check as for local transactions, extrinsics are applied one by one
+                // so that later extrinsics are checked against state changes of earlier ones.
+                let transaction_validity_result =
+                    runtime_api_instance.execute_in_transaction(|api| {
+                        let transaction_validity_result = api.check_extrinsics_and_do_pre_dispatch(
+                            parent_hash,
+                            vec![extrinsic.clone()],
+                            parent_number,
+                            parent_hash,
+                        );
+                        if let Ok(Ok(_)) = transaction_validity_result {
+                            sp_api::TransactionOutcome::Commit(transaction_validity_result)
+                        } else {
+                            sp_api::TransactionOutcome::Rollback(transaction_validity_result)
+                        }
+                    })?;
+                if let Err(error) = transaction_validity_result {
+                    return Ok(Err(format!("Extrinsic {index} is invalid: {error:?}")));
+                }
+            }
+        }
+
+        let header = self.build_bundle_header(
+            proof_of_election,
+            parent_number,
+            &extrinsics,
+            estimated_bundle_weight,
+        )?;
+
+        Ok(Ok((header, extrinsics)))
+    }
+
+    fn build_bundle_header(
+        &self,
+        proof_of_election: ProofOfElection<CBlock::Hash>,
+        parent_number: NumberFor<Block>,
+        extrinsics: &[Block::Extrinsic],
+        estimated_bundle_weight: Weight,
+    ) -> sp_blockchain::Result<ProposeBundleHeader<Block, CBlock>> {
         let extrinsics_root = HeaderHashingFor::<Block::Header>::ordered_trie_root(
             extrinsics.iter().map(|xt| xt.encode()).collect(),
             sp_core::storage::StateVersion::V1,
@@ -271,14 +383,12 @@ where
 
         let receipt = self.load_bundle_receipt(parent_number)?;
 
-        let header = BundleHeader {
+        Ok(BundleHeader {
             proof_of_election,
             receipt,
             estimated_bundle_weight,
             bundle_extrinsics_root: extrinsics_root,
-        };
-
-        Ok((header, extrinsics))
+        })
     }
 
     /// Returns the receipt in the next domain bundle.
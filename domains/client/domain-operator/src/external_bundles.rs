@@ -0,0 +1,90 @@
+//! Externally constructed bundles.
+//!
+//! Specialized block builders can construct the contents of a domain bundle and hand it over to
+//! the operator, which validates it against the latest domain state once elected and seals it
+//! under its own proof of election instead of proposing a bundle from the local transaction pool
+//! (proposer/builder separation). If the external bundle turns out to be invalid, it is rejected
+//! as a whole and the operator falls back to proposing a bundle on its own.
+
+use parking_lot::Mutex;
+use sp_consensus_slots::Slot;
+use std::sync::Arc;
+
+/// Bundle contents constructed by an external builder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalBundle {
+    /// SCALE-encoded domain extrinsics in the order they should appear in the bundle
+    pub extrinsics: Vec<Vec<u8>>,
+    /// Last slot the bundle can be used in, `None` means it is used in the next slot operator is
+    /// elected in
+    pub valid_until_slot: Option<Slot>,
+}
+
+/// Outcome of the external bundle in a slot operator was elected in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExternalBundleOutcome {
+    /// External bundle was sealed and submitted
+    Submitted {
+        /// Slot bundle was submitted in
+        slot: Slot,
+    },
+    /// External bundle was invalid and operator proposed a bundle on its own
+    Rejected {
+        /// Slot bundle was rejected in
+        slot: Slot,
+        /// Reason of the rejection
+        reason: String,
+    },
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    pending: Option<ExternalBundle>,
+    last_outcome: Option<ExternalBundleOutcome>,
+}
+
+/// Shared queue of externally constructed bundles, at most one bundle is pending at a time.
+#[derive(Debug, Default, Clone)]
+pub struct ExternalBundles {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl ExternalBundles {
+    /// Submit external bundle to be used in the next slot operator is elected in, replaces
+    /// previously submitted bundle that wasn't used yet.
+    pub fn submit(&self, external_bundle: ExternalBundle) {
+        self.inner.lock().pending.replace(external_bundle);
+    }
+
+    /// External bundle that is pending to be used, if any.
+    pub fn pending(&self) -> Option<ExternalBundle> {
+        self.inner.lock().pending.clone()
+    }
+
+    /// Outcome of the most recently used external bundle.
+    pub fn last_outcome(&self) -> Option<ExternalBundleOutcome> {
+        self.inner.lock().last_outcome.clone()
+    }
+
+    /// Take pending external bundle for use in `slot`, bundles that are no longer valid are
+    /// discarded.
+    pub(crate) fn take(&self, slot: Slot) -> Option<ExternalBundle> {
+        let external_bundle = self.inner.lock().pending.take()?;
+
+        match external_bundle.valid_until_slot {
+            Some(valid_until_slot) if valid_until_slot < slot => {
+                tracing::debug!(
+                    %slot,
+                    %valid_until_slot,
+                    "Discarding expired external bundle"
+                );
+                None
+            }
+            _ => Some(external_bundle),
+        }
+    }
+
+    pub(crate) fn record_outcome(&self, outcome: ExternalBundleOutcome) {
+        self.inner.lock().last_outcome.replace(outcome);
+    }
+}
@@ -62,23 +62,35 @@
 #![feature(extract_if)]
 
 mod aux_schema;
+mod bundle_election_audit;
 mod bundle_processor;
 mod bundle_producer_election_solver;
+mod bundle_relay;
 mod domain_block_processor;
 pub mod domain_bundle_producer;
 pub mod domain_bundle_proposer;
 mod domain_worker;
+mod external_bundles;
 mod fetch_domain_bootstrap_info;
 mod fraud_proof;
 mod operator;
+mod signing_key_rotation;
 #[cfg(test)]
 mod tests;
 mod utils;
 
 pub use self::aux_schema::load_execution_receipt;
+pub use self::bundle_election_audit::{
+    BundleElectionAudit, BundleElectionOutcome, BundleElectionRecord, BundleElectionStats,
+};
+pub use self::bundle_relay::{run_bundle_relay, BundleRelayError, StatelessBundleValidator};
+pub use self::external_bundles::{ExternalBundle, ExternalBundleOutcome, ExternalBundles};
 pub use self::fetch_domain_bootstrap_info::{fetch_domain_bootstrap_info, BootstrapResult};
 pub use self::operator::Operator;
-pub use self::utils::{DomainBlockImportNotification, DomainImportNotifications, OperatorSlotInfo};
+pub use self::utils::{
+    DomainBlockImportNotification, DomainImportNotifications, DomainReorgNotification,
+    DomainReorgNotifications, OperatorSlotInfo,
+};
 pub use domain_worker::OpaqueBundleFor;
 use futures::channel::mpsc;
 use futures::Stream;
@@ -176,6 +188,8 @@ pub struct OperatorParams<
     pub domain_confirmation_depth: NumberFor<Block>,
     pub block_import: SharedBlockImport<Block>,
     pub skip_empty_bundle_production: bool,
+    pub bundle_election_audit: BundleElectionAudit,
+    pub external_bundles: ExternalBundles,
 }
 
 pub(crate) fn load_execution_receipt_by_domain_hash<Block, CBlock, Client>(
@@ -1,9 +1,13 @@
+use crate::bundle_election_audit::BundleElectionAudit;
 use crate::bundle_processor::BundleProcessor;
 use crate::domain_block_processor::{DomainBlockProcessor, ReceiptsChecker};
 use crate::domain_bundle_producer::DomainBundleProducer;
 use crate::domain_bundle_proposer::DomainBundleProposer;
+use crate::external_bundles::ExternalBundles;
 use crate::fraud_proof::FraudProofGenerator;
-use crate::{DomainImportNotifications, NewSlotNotification, OperatorParams};
+use crate::{
+    DomainImportNotifications, DomainReorgNotifications, NewSlotNotification, OperatorParams,
+};
 use futures::channel::mpsc;
 use futures::{FutureExt, Stream};
 use sc_client_api::{
@@ -38,6 +42,8 @@ where
     bundle_processor: BundleProcessor<Block, CBlock, Client, CClient, Backend, E>,
     domain_block_processor: DomainBlockProcessor<Block, CBlock, Client, CClient, Backend>,
     pub keystore: KeystorePtr,
+    pub bundle_election_audit: BundleElectionAudit,
+    pub external_bundles: ExternalBundles,
 }
 
 impl<Block, CBlock, Client, CClient, TransactionPool, Backend, E> Clone
@@ -56,6 +62,8 @@ where
             bundle_processor: self.bundle_processor.clone(),
             domain_block_processor: self.domain_block_processor.clone(),
             keystore: self.keystore.clone(),
+            bundle_election_audit: self.bundle_election_audit.clone(),
+            external_bundles: self.external_bundles.clone(),
         }
     }
 }
@@ -136,6 +144,8 @@ where
             params.bundle_sender,
             params.keystore.clone(),
             params.skip_empty_bundle_production,
+            params.bundle_election_audit.clone(),
+            params.external_bundles.clone(),
         );
 
         let fraud_proof_generator = FraudProofGenerator::new(
@@ -154,6 +164,7 @@ where
             domain_confirmation_depth: params.domain_confirmation_depth,
             block_import: params.block_import,
             import_notification_sinks: Default::default(),
+            reorg_notification_sinks: Default::default(),
             consensus_network_sync_oracle: params.consensus_network_sync_oracle.clone(),
         };
 
@@ -199,6 +210,8 @@ where
             bundle_processor,
             domain_block_processor,
             keystore: params.keystore,
+            bundle_election_audit: params.bundle_election_audit,
+            external_bundles: params.external_bundles,
         })
     }
 
@@ -221,6 +234,19 @@ where
         stream
     }
 
+    /// Get domain re-org notification stream.
+    ///
+    /// A notification is fired whenever a consensus chain re-org makes the operator roll back the
+    /// best domain block and re-derive domain blocks from the new best consensus branch.
+    pub fn reorg_notification_stream(&self) -> DomainReorgNotifications<Block, CBlock> {
+        let (sink, stream) = tracing_unbounded("mpsc_domain_reorg_notification_stream", 100);
+        self.domain_block_processor
+            .reorg_notification_sinks
+            .lock()
+            .push(sink);
+        stream
+    }
+
     /// Processes the bundles extracted from the consensus block.
     // TODO: Remove this whole method, `self.bundle_processor` as a property and fix
     // `set_new_code_should_work` test to do an actual runtime upgrade
@@ -0,0 +1,78 @@
+//! Operator signing key rotation tracking.
+//!
+//! Signing key of the operator is read from the consensus runtime in every slot, so once the key
+//! rotation scheduled with `rotate_operator_signing_key` takes effect at the epoch boundary, bundle
+//! production switches to the new key automatically, as long as the new key is in the keystore.
+//! The tracker makes the handover visible in logs and warns in advance if the new key is missing,
+//! which would otherwise only be noticed after the operator stops producing bundles.
+
+use parking_lot::Mutex;
+use sp_core::bytes::to_hex;
+use sp_core::ByteArray;
+use sp_domains::{OperatorId, OperatorPublicKey};
+use sp_keystore::{Keystore, KeystorePtr};
+use sp_runtime::RuntimeAppPublic;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+#[derive(Debug, Default)]
+struct SigningKeys {
+    /// Signing key observed during the last election
+    current: Option<OperatorPublicKey>,
+    /// Pending signing key that was already checked against the keystore
+    checked_pending: Option<OperatorPublicKey>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SigningKeyRotationTracker {
+    signing_keys: Arc<Mutex<SigningKeys>>,
+}
+
+impl SigningKeyRotationTracker {
+    /// Note signing keys of the operator as seen by the consensus runtime
+    pub(crate) fn note_signing_keys(
+        &self,
+        keystore: &KeystorePtr,
+        operator_id: OperatorId,
+        current: &OperatorPublicKey,
+        maybe_pending: Option<&OperatorPublicKey>,
+    ) {
+        let mut signing_keys = self.signing_keys.lock();
+
+        if let Some(previous) = signing_keys.current.replace(current.clone()) {
+            if previous != *current {
+                info!(
+                    "Operator[{operator_id}] signing key rotated from {} to {}, bundles are \
+                    signed with the new key from now on",
+                    to_hex(previous.as_slice(), false),
+                    to_hex(current.as_slice(), false)
+                );
+            }
+        }
+
+        let Some(pending) = maybe_pending else {
+            signing_keys.checked_pending.take();
+            return;
+        };
+
+        if signing_keys.checked_pending.as_ref() == Some(pending) {
+            return;
+        }
+        signing_keys.checked_pending.replace(pending.clone());
+
+        if keystore.has_keys(&[(pending.to_raw_vec(), OperatorPublicKey::ID)]) {
+            info!(
+                "Operator[{operator_id}] signing key rotation to {} is scheduled, new key is \
+                available in keystore",
+                to_hex(pending.as_slice(), false)
+            );
+        } else {
+            warn!(
+                "Operator[{operator_id}] signing key rotation to {} is scheduled, but new key is \
+                not available in keystore, bundles will not be produced after the end of the \
+                epoch until it is inserted",
+                to_hex(pending.as_slice(), false)
+            );
+        }
+    }
+}
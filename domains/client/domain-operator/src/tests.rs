@@ -3,7 +3,7 @@ use crate::domain_bundle_producer::DomainBundleProducer;
 use crate::domain_bundle_proposer::DomainBundleProposer;
 use crate::fraud_proof::{FraudProofGenerator, TraceDiffType};
 use crate::tests::TxPoolError::InvalidTransaction as TxPoolInvalidTransaction;
-use crate::OperatorSlotInfo;
+use crate::{BundleElectionOutcome, ExternalBundle, ExternalBundleOutcome, OperatorSlotInfo};
 use codec::{Decode, Encode};
 use domain_runtime_primitives::Hash;
 use domain_test_primitives::{OnchainStateApi, TimestampApi};
@@ -206,6 +206,8 @@ async fn test_domain_block_production() {
     .build_evm_node(Role::Authority, GENESIS_DOMAIN_ID, &mut ferdie)
     .await;
 
+    let mut alice_reorg_notification_stream = alice.operator.reorg_notification_stream();
+
     for i in 0..50 {
         let (tx, slot) = if i % 2 == 0 {
             // Produce bundle and include it in the primary block hence produce a domain block
@@ -253,6 +255,19 @@ async fn test_domain_block_production() {
     assert_eq!(alice.client.info().best_number, domain_block_number + 3);
     assert_eq!(alice.client.info().best_hash, fork_a_block_hash_3);
 
+    // Switching to fork B rolls back the best domain block
+    let reorg_notification = alice_reorg_notification_stream.next().await.unwrap();
+    assert_eq!(
+        reorg_notification.old_best_domain_block.1,
+        domain_block_number + 5
+    );
+    assert_eq!(
+        reorg_notification.new_domain_parent,
+        (fork_a_block_hash_3, domain_block_number + 3)
+    );
+    assert_eq!(reorg_notification.retracted_consensus_blocks.len(), 2);
+    assert_eq!(reorg_notification.enacted_consensus_blocks.len(), 3);
+
     // Fork C
     // Produce 10 more primary blocks and do not produce any domain block but because there are
     // more primary block on fork C, it will become the best fork of the domain chain
@@ -322,6 +337,7 @@ async fn test_processing_empty_consensus_block() {
         domain_confirmation_depth: 256u32,
         block_import: SharedBlockImport::new(alice.client.clone()),
         import_notification_sinks: Default::default(),
+        reorg_notification_sinks: Default::default(),
         consensus_network_sync_oracle: ferdie.sync_service.clone(),
     };
 
@@ -2965,6 +2981,8 @@ async fn stale_and_in_future_bundle_should_be_rejected() {
             Arc::new(bundle_sender),
             alice.operator.keystore.clone(),
             false,
+            Default::default(),
+            Default::default(),
         )
     };
 
@@ -3882,6 +3900,8 @@ async fn test_bad_receipt_chain() {
             Arc::new(bundle_sender),
             alice.operator.keystore.clone(),
             false,
+            Default::default(),
+            Default::default(),
         )
     };
 
@@ -4196,3 +4216,135 @@ async fn test_handle_duplicated_tx_with_diff_nonce_in_previous_bundle() {
     assert_eq!(alice.free_balance(Bob.to_account_id()), bob_pre_balance + 3);
     assert_eq!(alice.account_nonce(), nonce + 3);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_bundle_election_audit() {
+    let directory = TempDir::new().expect("Must be able to create temporary directory");
+
+    let mut builder = sc_cli::LoggerBuilder::new("");
+    builder.with_colors(false);
+    let _ = builder.init();
+
+    let tokio_handle = tokio::runtime::Handle::current();
+
+    // Start Ferdie
+    let mut ferdie = MockConsensusNode::run(
+        tokio_handle.clone(),
+        Ferdie,
+        BasePath::new(directory.path().join("ferdie")),
+    );
+
+    // Run Alice (a evm domain authority node)
+    let alice = domain_test_service::DomainNodeBuilder::new(
+        tokio_handle.clone(),
+        Alice,
+        BasePath::new(directory.path().join("alice")),
+    )
+    .build_evm_node(Role::Authority, GENESIS_DOMAIN_ID, &mut ferdie)
+    .await;
+
+    let operator_id = 0;
+    let bundle_election_audit = alice.operator.bundle_election_audit.clone();
+
+    let ((slot, _), bundle) = ferdie.produce_slot_and_wait_for_bundle_submission().await;
+    assert_eq!(
+        bundle.sealed_header.header.proof_of_election.operator_id,
+        operator_id
+    );
+
+    let record = bundle_election_audit
+        .election(slot, operator_id)
+        .expect("Election attempt must be recorded");
+    assert_eq!(record.domain_id, GENESIS_DOMAIN_ID);
+    assert_eq!(record.outcome, BundleElectionOutcome::Elected);
+    assert!(record.vrf_output.unwrap() < record.threshold);
+    assert!(record.operator_stake > 0);
+
+    // Unknown operator never attempts to be elected
+    assert!(bundle_election_audit
+        .election(slot, operator_id + 1)
+        .is_none());
+
+    let stats = bundle_election_audit.stats(operator_id);
+    assert!(stats.elected >= 1);
+    assert!(stats.attempts >= stats.elected);
+    assert_eq!(stats.last_elected_slot, Some(slot));
+    assert_eq!(stats.operator_not_registered, 0);
+    assert_eq!(stats.signing_key_unavailable, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_external_bundle_submission() {
+    let directory = TempDir::new().expect("Must be able to create temporary directory");
+
+    let mut builder = sc_cli::LoggerBuilder::new("");
+    builder.with_colors(false);
+    let _ = builder.init();
+
+    let tokio_handle = tokio::runtime::Handle::current();
+
+    // Start Ferdie
+    let mut ferdie = MockConsensusNode::run(
+        tokio_handle.clone(),
+        Ferdie,
+        BasePath::new(directory.path().join("ferdie")),
+    );
+
+    // Run Alice (a evm domain authority node)
+    let mut alice = domain_test_service::DomainNodeBuilder::new(
+        tokio_handle.clone(),
+        Alice,
+        BasePath::new(directory.path().join("alice")),
+    )
+    .build_evm_node(Role::Authority, GENESIS_DOMAIN_ID, &mut ferdie)
+    .await;
+
+    produce_blocks!(ferdie, alice, 3).await.unwrap();
+
+    let external_bundles = alice.operator.external_bundles.clone();
+
+    // Extrinsic that is not in the transaction pool ends up in the bundle
+    let tx = alice.construct_extrinsic(
+        alice.account_nonce(),
+        pallet_balances::Call::transfer_allow_death {
+            dest: Bob.to_account_id(),
+            value: 1,
+        },
+    );
+    external_bundles.submit(ExternalBundle {
+        extrinsics: vec![tx.encode()],
+        valid_until_slot: None,
+    });
+
+    let ((slot, _), bundle) = ferdie.produce_slot_and_wait_for_bundle_submission().await;
+    assert_eq!(bundle.extrinsics.len(), 1);
+    assert_eq!(bundle.extrinsics[0].encode(), tx.encode());
+    assert!(external_bundles.pending().is_none());
+    assert_eq!(
+        external_bundles.last_outcome(),
+        Some(ExternalBundleOutcome::Submitted { slot })
+    );
+
+    // Malformed external bundle is rejected and operator still produces a bundle on its own
+    external_bundles.submit(ExternalBundle {
+        extrinsics: vec![vec![1, 2, 3]],
+        valid_until_slot: None,
+    });
+
+    let ((slot, _), bundle) = ferdie.produce_slot_and_wait_for_bundle_submission().await;
+    assert!(bundle.extrinsics.is_empty());
+    assert!(matches!(
+        external_bundles.last_outcome(),
+        Some(ExternalBundleOutcome::Rejected { slot: rejected_slot, .. }) if rejected_slot == slot
+    ));
+
+    // Expired external bundle is discarded
+    external_bundles.submit(ExternalBundle {
+        extrinsics: vec![tx.encode()],
+        valid_until_slot: Some(slot),
+    });
+
+    let (_, bundle) = ferdie.produce_slot_and_wait_for_bundle_submission().await;
+    assert!(bundle.extrinsics.is_empty());
+    assert!(external_bundles.pending().is_none());
+}
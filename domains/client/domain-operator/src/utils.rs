@@ -38,3 +38,24 @@ pub struct DomainBlockImportNotification<Block: BlockT, CBlock: BlockT> {
     pub domain_block_hash: Block::Hash,
     pub consensus_block_hash: CBlock::Hash,
 }
+
+pub type DomainReorgNotificationSinks<Block, CBlock> =
+    Arc<Mutex<Vec<TracingUnboundedSender<DomainReorgNotification<Block, CBlock>>>>>;
+
+pub type DomainReorgNotifications<Block, CBlock> =
+    TracingUnboundedReceiver<DomainReorgNotification<Block, CBlock>>;
+
+/// Notification about the domain chain being re-derived due to a consensus chain re-org.
+#[derive(Clone, Debug)]
+pub struct DomainReorgNotification<Block: BlockT, CBlock: BlockT> {
+    /// The common ancestor of the retracted and enacted consensus branches.
+    pub common_consensus_block: (CBlock::Hash, NumberFor<CBlock>),
+    /// Consensus blocks that are no longer part of the best consensus chain.
+    pub retracted_consensus_blocks: Vec<CBlock::Hash>,
+    /// Consensus blocks that the domain blocks are going to be re-derived from, in order.
+    pub enacted_consensus_blocks: Vec<CBlock::Hash>,
+    /// The best domain block before the re-org.
+    pub old_best_domain_block: (Block::Hash, NumberFor<Block>),
+    /// The domain block that the re-derived domain blocks are built on top of.
+    pub new_domain_parent: (Block::Hash, NumberFor<Block>),
+}
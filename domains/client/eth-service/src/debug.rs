@@ -0,0 +1,658 @@
+//! Geth-compatible `debug_trace*` RPC methods.
+//!
+//! Transactions are re-executed by the runtime on top of the parent block state, which is
+//! expensive, so tracing is bounded in concurrency, time and size of the produced trace, and
+//! produced traces are cached.
+
+use fc_rpc::frontier_backend_client;
+use fc_rpc_core::types::BlockNumberOrHash;
+use jsonrpsee::core::{async_trait, Error as JsonRpseeError, RpcResult};
+use jsonrpsee::proc_macros::rpc;
+use lru::LruCache;
+use parity_scale_codec::{Decode, Encode};
+use parking_lot::Mutex;
+use sc_client_api::{AuxStore, BlockBackend};
+use sc_rpc::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::hexdisplay::HexDisplay;
+use sp_core::{Bytes, H160, H256, U256};
+use sp_evm_tracing::{
+    CallFrame, CallType, EvmTracingApi, StructLog, TraceLimits, TraceOutput, TracerConfig,
+    TracingError, TransactionTrace,
+};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// Prefix of the keys of traces persisted in aux storage
+const TRACE_CACHE_KEY_PREFIX: &[u8] = b"evm_trace";
+/// Name of the call tracer in geth
+const CALL_TRACER: &str = "callTracer";
+
+/// Bounds and caching of the EVM tracing.
+#[derive(Debug, Clone)]
+pub struct EvmTracingConfig {
+    /// Maximum number of transactions or blocks traced concurrently
+    pub max_concurrent_traces: NonZeroUsize,
+    /// Time after which tracing request fails
+    pub timeout: Duration,
+    /// Maximum approximate size of a single transaction or block trace in bytes
+    pub max_trace_size: u32,
+    /// Maximum number of executed opcodes in a single transaction or block trace
+    pub max_trace_steps: u32,
+    /// Number of traces kept in memory
+    pub cache_size: NonZeroUsize,
+    /// Whether to persist traces in aux storage
+    pub persistent_cache: bool,
+}
+
+/// Tracing options, subset of geth's options.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceParams {
+    /// Tracer to use, struct logger is used if not specified
+    pub tracer: Option<String>,
+    /// Don't capture storage with struct logger
+    #[serde(default)]
+    pub disable_storage: bool,
+    /// Don't capture stack with struct logger
+    #[serde(default)]
+    pub disable_stack: bool,
+    /// Capture memory with struct logger
+    #[serde(default)]
+    pub enable_memory: bool,
+}
+
+impl TryFrom<Option<TraceParams>> for TracerConfig {
+    type Error = JsonRpseeError;
+
+    fn try_from(params: Option<TraceParams>) -> Result<Self, Self::Error> {
+        let params = params.unwrap_or_default();
+
+        match params.tracer.as_deref() {
+            None => Ok(TracerConfig::StructLogger {
+                disable_storage: params.disable_storage,
+                disable_memory: !params.enable_memory,
+                disable_stack: params.disable_stack,
+            }),
+            Some(CALL_TRACER) => Ok(TracerConfig::CallTracer),
+            Some(tracer) => Err(JsonRpseeError::Custom(format!(
+                "Tracer {tracer} is not supported, supported tracers are {CALL_TRACER} and default \
+                struct logger"
+            ))),
+        }
+    }
+}
+
+/// Call frame produced by the call tracer.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrameInfo {
+    /// Type of the call, `CALL`, `CREATE`, etc.
+    #[serde(rename = "type")]
+    pub call_type: &'static str,
+    /// Caller
+    pub from: H160,
+    /// Callee or created contract
+    pub to: H160,
+    /// Transferred value
+    pub value: U256,
+    /// Gas available to the call
+    pub gas: U256,
+    /// Gas used by the call
+    pub gas_used: U256,
+    /// Call input or init code
+    pub input: Bytes,
+    /// Return value
+    pub output: Bytes,
+    /// Error message if call did not succeed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Nested calls
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallFrameInfo>,
+}
+
+impl From<CallFrame> for CallFrameInfo {
+    fn from(frame: CallFrame) -> Self {
+        Self {
+            call_type: match frame.call_type {
+                CallType::Call => "CALL",
+                CallType::StaticCall => "STATICCALL",
+                CallType::DelegateCall => "DELEGATECALL",
+                CallType::Create => "CREATE",
+                CallType::Create2 => "CREATE2",
+                CallType::SelfDestruct => "SELFDESTRUCT",
+            },
+            from: frame.from,
+            to: frame.to,
+            value: frame.value,
+            gas: frame.gas.into(),
+            gas_used: frame.gas_used.into(),
+            input: frame.input.into(),
+            output: frame.output.into(),
+            error: frame
+                .error
+                .map(|error| String::from_utf8_lossy(&error).into_owned()),
+            calls: frame.calls.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Executed opcode produced by the struct logger.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogInfo {
+    /// Program counter
+    pub pc: u64,
+    /// Opcode name
+    pub op: String,
+    /// Gas remaining before executing the opcode
+    pub gas: u64,
+    /// Cost of the opcode
+    pub gas_cost: u64,
+    /// Call depth, starting from 1
+    pub depth: u32,
+    /// Stack before executing the opcode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<U256>>,
+    /// Memory before executing the opcode in 32 bytes words
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<String>>,
+    /// Storage of the contract accessed so far
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<String, String>>,
+}
+
+impl From<StructLog> for StructLogInfo {
+    fn from(struct_log: StructLog) -> Self {
+        Self {
+            pc: struct_log.pc,
+            op: opcode_name(struct_log.op),
+            gas: struct_log.gas,
+            gas_cost: struct_log.gas_cost,
+            depth: struct_log.depth,
+            stack: struct_log.stack.map(|stack| {
+                stack
+                    .into_iter()
+                    .map(|item| U256::from_big_endian(item.as_bytes()))
+                    .collect()
+            }),
+            memory: struct_log.memory.map(|memory| {
+                memory
+                    .chunks(32)
+                    .map(|word| HexDisplay::from(&word).to_string())
+                    .collect()
+            }),
+            storage: struct_log.storage.map(|storage| {
+                storage
+                    .into_iter()
+                    .map(|(key, value)| {
+                        (
+                            HexDisplay::from(&key.as_bytes()).to_string(),
+                            HexDisplay::from(&value.as_bytes()).to_string(),
+                        )
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// Output of the struct logger.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLoggerInfo {
+    /// Gas used by the transaction
+    pub gas: u64,
+    /// Whether transaction failed
+    pub failed: bool,
+    /// Return value of the transaction
+    pub return_value: String,
+    /// Executed opcodes
+    pub struct_logs: Vec<StructLogInfo>,
+}
+
+/// Trace of a transaction.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum TraceInfo {
+    /// Output of the call tracer, `null` if transaction didn't reach the EVM
+    Call(Option<CallFrameInfo>),
+    /// Output of the struct logger
+    StructLogger(StructLoggerInfo),
+}
+
+impl From<TraceOutput> for TraceInfo {
+    fn from(output: TraceOutput) -> Self {
+        match output {
+            TraceOutput::Call(frame) => Self::Call(frame.map(Into::into)),
+            TraceOutput::StructLogs {
+                gas,
+                failed,
+                return_value,
+                struct_logs,
+            } => Self::StructLogger(StructLoggerInfo {
+                gas,
+                failed,
+                return_value: HexDisplay::from(&return_value).to_string(),
+                struct_logs: struct_logs.into_iter().map(Into::into).collect(),
+            }),
+        }
+    }
+}
+
+/// Trace of a transaction in the block.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTransactionTraceInfo {
+    /// Hash of the transaction
+    pub tx_hash: H256,
+    /// Trace of the transaction
+    pub result: TraceInfo,
+}
+
+/// Provides geth-compatible rpc methods for tracing EVM transactions.
+#[rpc(server)]
+pub trait EvmDebugApi {
+    /// Re-execute transaction and return its trace
+    #[method(name = "debug_traceTransaction")]
+    async fn trace_transaction(
+        &self,
+        transaction_hash: H256,
+        params: Option<TraceParams>,
+    ) -> RpcResult<TraceInfo>;
+
+    /// Re-execute block and return traces of all its transactions
+    #[method(name = "debug_traceBlockByNumber")]
+    async fn trace_block_by_number(
+        &self,
+        number: BlockNumberOrHash,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Vec<BlockTransactionTraceInfo>>;
+
+    /// Re-execute block and return traces of all its transactions
+    #[method(name = "debug_traceBlockByHash")]
+    async fn trace_block_by_hash(
+        &self,
+        hash: H256,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Vec<BlockTransactionTraceInfo>>;
+}
+
+type TraceCacheKey<Hash> = (Hash, Option<H256>, TracerConfig);
+
+/// Implements the [`EvmDebugApiServer`] RPC trait for tracing EVM transactions.
+pub struct EvmDebug<Block: BlockT, Client> {
+    client: Arc<Client>,
+    frontier_backend: Arc<fc_db::kv::Backend<Block>>,
+    config: EvmTracingConfig,
+    semaphore: Arc<Semaphore>,
+    cache: Mutex<LruCache<TraceCacheKey<Block::Hash>, Arc<Vec<TransactionTrace>>>>,
+    deny_unsafe: DenyUnsafe,
+}
+
+impl<Block, Client> EvmDebug<Block, Client>
+where
+    Block: BlockT<Hash = H256>,
+    Client: ProvideRuntimeApi<Block>
+        + HeaderBackend<Block>
+        + BlockBackend<Block>
+        + AuxStore
+        + Send
+        + Sync
+        + 'static,
+    Client::Api: Core<Block> + EvmTracingApi<Block>,
+{
+    /// Creates a new instance of the `EvmDebug` handler.
+    pub fn new(
+        client: Arc<Client>,
+        frontier_backend: Arc<fc_db::kv::Backend<Block>>,
+        config: EvmTracingConfig,
+        deny_unsafe: DenyUnsafe,
+    ) -> Self {
+        Self {
+            client,
+            frontier_backend,
+            semaphore: Arc::new(Semaphore::new(config.max_concurrent_traces.get())),
+            cache: Mutex::new(LruCache::new(config.cache_size)),
+            config,
+            deny_unsafe,
+        }
+    }
+
+    async fn trace_block(
+        &self,
+        number: BlockNumberOrHash,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Vec<BlockTransactionTraceInfo>> {
+        self.deny_unsafe.check_if_safe()?;
+        let tracer = TracerConfig::try_from(params)?;
+
+        let block_id = frontier_backend_client::native_block_id(
+            self.client.as_ref(),
+            self.frontier_backend.as_ref(),
+            Some(number),
+        )
+        .await?
+        .ok_or_else(|| JsonRpseeError::Custom("Block not found".to_string()))?;
+        let block_hash = self
+            .client
+            .expect_block_hash_from_id(&block_id)
+            .map_err(|error| JsonRpseeError::Custom(error.to_string()))?;
+
+        let traces = self.traces(block_hash, None, tracer).await?;
+
+        Ok(traces
+            .iter()
+            .cloned()
+            .map(|trace| BlockTransactionTraceInfo {
+                tx_hash: trace.transaction_hash,
+                result: trace.output.into(),
+            })
+            .collect())
+    }
+
+    /// Traces of the block or a single transaction in it, from cache if available
+    async fn traces(
+        &self,
+        block_hash: Block::Hash,
+        transaction_hash: Option<H256>,
+        tracer: TracerConfig,
+    ) -> RpcResult<Arc<Vec<TransactionTrace>>> {
+        let cache_key = (block_hash, transaction_hash, tracer);
+        if let Some(traces) = self.cache.lock().get(&cache_key) {
+            return Ok(Arc::clone(traces));
+        }
+
+        let aux_key = (TRACE_CACHE_KEY_PREFIX, cache_key).encode();
+        if self.config.persistent_cache {
+            let maybe_traces = self
+                .client
+                .get_aux(&aux_key)
+                .map_err(|error| JsonRpseeError::Custom(error.to_string()))?
+                .and_then(|encoded| Vec::<TransactionTrace>::decode(&mut encoded.as_slice()).ok());
+            if let Some(traces) = maybe_traces {
+                let traces = Arc::new(traces);
+                self.cache.lock().put(cache_key, Arc::clone(&traces));
+                return Ok(traces);
+            }
+        }
+
+        let client = Arc::clone(&self.client);
+        let semaphore = Arc::clone(&self.semaphore);
+        let limits = TraceLimits {
+            max_size: self.config.max_trace_size,
+            max_steps: self.config.max_trace_steps,
+        };
+        let tracing = async move {
+            let permit = semaphore
+                .acquire_owned()
+                .await
+                .map_err(|error| error.to_string())?;
+
+            // Runtime call can't be interrupted, permit is held until it finishes even if request
+            // times out, so the number of concurrent traces stays bounded
+            tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                execute_traces::<Block, _>(
+                    client.as_ref(),
+                    block_hash,
+                    transaction_hash,
+                    tracer,
+                    limits,
+                )
+            })
+            .await
+            .map_err(|error| error.to_string())?
+        };
+
+        let traces = tokio::time::timeout(self.config.timeout, tracing)
+            .await
+            .map_err(|_| {
+                JsonRpseeError::Custom(format!(
+                    "Tracing timed out after {}s",
+                    self.config.timeout.as_secs_f32()
+                ))
+            })?
+            .map_err(JsonRpseeError::Custom)?;
+
+        if traces.iter().any(|trace| trace.truncated) {
+            return Err(JsonRpseeError::Custom(format!(
+                "Trace exceeds limit of {} bytes or {} steps, try disabling memory, stack or \
+                storage capture or using {CALL_TRACER}",
+                limits.max_size, limits.max_steps
+            )));
+        }
+
+        if self.config.persistent_cache {
+            self.client
+                .insert_aux(&[(aux_key.as_slice(), traces.encode().as_slice())], &[])
+                .map_err(|error| JsonRpseeError::Custom(error.to_string()))?;
+        }
+
+        let traces = Arc::new(traces);
+        self.cache.lock().put(cache_key, Arc::clone(&traces));
+
+        Ok(traces)
+    }
+}
+
+#[async_trait]
+impl<Block, Client> EvmDebugApiServer for EvmDebug<Block, Client>
+where
+    Block: BlockT<Hash = H256>,
+    Client: ProvideRuntimeApi<Block>
+        + HeaderBackend<Block>
+        + BlockBackend<Block>
+        + AuxStore
+        + Send
+        + Sync
+        + 'static,
+    Client::Api: Core<Block> + EvmTracingApi<Block>,
+{
+    async fn trace_transaction(
+        &self,
+        transaction_hash: H256,
+        params: Option<TraceParams>,
+    ) -> RpcResult<TraceInfo> {
+        self.deny_unsafe.check_if_safe()?;
+        let tracer = TracerConfig::try_from(params)?;
+
+        let transaction_not_found =
+            || JsonRpseeError::Custom(format!("Transaction {transaction_hash:?} not found"));
+        let (ethereum_block_hash, _transaction_index) = frontier_backend_client::load_transactions(
+            self.client.as_ref(),
+            self.frontier_backend.as_ref(),
+            transaction_hash,
+            true,
+        )
+        .await?
+        .ok_or_else(transaction_not_found)?;
+        let block_hash = frontier_backend_client::load_hash(
+            self.client.as_ref(),
+            self.frontier_backend.as_ref(),
+            ethereum_block_hash,
+        )
+        .await?
+        .ok_or_else(transaction_not_found)?;
+
+        let traces = self
+            .traces(block_hash, Some(transaction_hash), tracer)
+            .await?;
+
+        traces
+            .first()
+            .map(|trace| trace.output.clone().into())
+            .ok_or_else(transaction_not_found)
+    }
+
+    async fn trace_block_by_number(
+        &self,
+        number: BlockNumberOrHash,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Vec<BlockTransactionTraceInfo>> {
+        self.trace_block(number, params).await
+    }
+
+    async fn trace_block_by_hash(
+        &self,
+        hash: H256,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Vec<BlockTransactionTraceInfo>> {
+        self.trace_block(
+            BlockNumberOrHash::Hash {
+                hash,
+                require_canonical: false,
+            },
+            params,
+        )
+        .await
+    }
+}
+
+/// Re-execute block on top of its parent state and trace the transaction or all transactions in
+/// the block
+fn execute_traces<Block, Client>(
+    client: &Client,
+    block_hash: Block::Hash,
+    transaction_hash: Option<H256>,
+    tracer: TracerConfig,
+    limits: TraceLimits,
+) -> Result<Vec<TransactionTrace>, String>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockBackend<Block>,
+    Client::Api: Core<Block> + EvmTracingApi<Block>,
+{
+    let header = client
+        .header(block_hash)
+        .map_err(|error| error.to_string())?
+        .ok_or_else(|| format!("Header of block {block_hash} not found"))?;
+    let extrinsics = client
+        .block_body(block_hash)
+        .map_err(|error| error.to_string())?
+        .ok_or_else(|| format!("Body of block {block_hash} not found"))?;
+    let parent_hash = *header.parent_hash();
+
+    let runtime_api = client.runtime_api();
+    runtime_api
+        .initialize_block(parent_hash, &header)
+        .map_err(|error| error.to_string())?;
+
+    match transaction_hash {
+        Some(transaction_hash) => runtime_api
+            .trace_transaction(parent_hash, extrinsics, transaction_hash, tracer, limits)
+            .map_err(|error| error.to_string())?
+            .map_err(tracing_error_to_string)?
+            .map(|trace| vec![trace])
+            .ok_or_else(|| {
+                format!("Transaction {transaction_hash:?} not found in block {block_hash}")
+            }),
+        None => runtime_api
+            .trace_block(parent_hash, extrinsics, tracer, limits)
+            .map_err(|error| error.to_string())?
+            .map_err(tracing_error_to_string),
+    }
+}
+
+fn tracing_error_to_string(error: TracingError) -> String {
+    match error {
+        TracingError::TracerNotAvailable => "Runtime is built without EVM tracer, runtime built \
+            with `evm-tracing` feature needs to be used with `--wasm-runtime-overrides`"
+            .to_string(),
+    }
+}
+
+/// Geth's name of the opcode
+fn opcode_name(opcode: u8) -> String {
+    let name = match opcode {
+        0x00 => "STOP",
+        0x01 => "ADD",
+        0x02 => "MUL",
+        0x03 => "SUB",
+        0x04 => "DIV",
+        0x05 => "SDIV",
+        0x06 => "MOD",
+        0x07 => "SMOD",
+        0x08 => "ADDMOD",
+        0x09 => "MULMOD",
+        0x0a => "EXP",
+        0x0b => "SIGNEXTEND",
+        0x10 => "LT",
+        0x11 => "GT",
+        0x12 => "SLT",
+        0x13 => "SGT",
+        0x14 => "EQ",
+        0x15 => "ISZERO",
+        0x16 => "AND",
+        0x17 => "OR",
+        0x18 => "XOR",
+        0x19 => "NOT",
+        0x1a => "BYTE",
+        0x1b => "SHL",
+        0x1c => "SHR",
+        0x1d => "SAR",
+        0x20 => "KECCAK256",
+        0x30 => "ADDRESS",
+        0x31 => "BALANCE",
+        0x32 => "ORIGIN",
+        0x33 => "CALLER",
+        0x34 => "CALLVALUE",
+        0x35 => "CALLDATALOAD",
+        0x36 => "CALLDATASIZE",
+        0x37 => "CALLDATACOPY",
+        0x38 => "CODESIZE",
+        0x39 => "CODECOPY",
+        0x3a => "GASPRICE",
+        0x3b => "EXTCODESIZE",
+        0x3c => "EXTCODECOPY",
+        0x3d => "RETURNDATASIZE",
+        0x3e => "RETURNDATACOPY",
+        0x3f => "EXTCODEHASH",
+        0x40 => "BLOCKHASH",
+        0x41 => "COINBASE",
+        0x42 => "TIMESTAMP",
+        0x43 => "NUMBER",
+        0x44 => "DIFFICULTY",
+        0x45 => "GASLIMIT",
+        0x46 => "CHAINID",
+        0x47 => "SELFBALANCE",
+        0x48 => "BASEFEE",
+        0x50 => "POP",
+        0x51 => "MLOAD",
+        0x52 => "MSTORE",
+        0x53 => "MSTORE8",
+        0x54 => "SLOAD",
+        0x55 => "SSTORE",
+        0x56 => "JUMP",
+        0x57 => "JUMPI",
+        0x58 => "PC",
+        0x59 => "MSIZE",
+        0x5a => "GAS",
+        0x5b => "JUMPDEST",
+        0x5f => "PUSH0",
+        0x60..=0x7f => return format!("PUSH{}", opcode - 0x5f),
+        0x80..=0x8f => return format!("DUP{}", opcode - 0x7f),
+        0x90..=0x9f => return format!("SWAP{}", opcode - 0x8f),
+        0xa0..=0xa4 => return format!("LOG{}", opcode - 0xa0),
+        0xf0 => "CREATE",
+        0xf1 => "CALL",
+        0xf2 => "CALLCODE",
+        0xf3 => "RETURN",
+        0xf4 => "DELEGATECALL",
+        0xf5 => "CREATE2",
+        0xfa => "STATICCALL",
+        0xfd => "REVERT",
+        0xfe => "INVALID",
+        0xff => "SELFDESTRUCT",
+        _ => return format!("opcode {opcode:#04x} not defined"),
+    };
+
+    name.to_string()
+}
@@ -1,5 +1,6 @@
 #![warn(rust_2018_idioms)]
 
+pub mod debug;
 pub mod provider;
 pub(crate) mod rpc;
 mod service;
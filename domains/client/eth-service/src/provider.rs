@@ -24,8 +24,10 @@ use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_core::traits::SpawnEssentialNamed;
 use sp_core::H256;
+use sp_evm_tracing::EvmTracingApi;
 use sp_inherents::CreateInherentDataProviders;
-use sp_runtime::traits::Block as BlockT;
+use sp_messenger::MessengerApi;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
 use std::error::Error;
 use std::fmt::{Debug, Display};
 use std::marker::PhantomData;
@@ -93,7 +95,10 @@ where
     Client::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>
         + EthereumRuntimeRPCApi<Block>
         + AccountNonceApi<Block, AccountId, Nonce>
-        + ConvertTransactionRuntimeApi<Block>,
+        + ConvertTransactionRuntimeApi<Block>
+        + Core<Block>
+        + EvmTracingApi<Block>
+        + MessengerApi<Block, NumberFor<Block>>,
     Client::Api: BlockBuilder<Block>,
     Client::Api: EthereumRuntimeRPCApi<Block>,
     CT: ConvertTransaction<<Block as BlockT>::Extrinsic> + Clone + Default + Send + Sync + 'static,
@@ -149,6 +154,7 @@ where
             execute_gas_limit_multiplier: self.eth_config.execute_gas_limit_multiplier,
             forced_parent_hashes: None,
             pending_inherent_data_provider: full_deps.create_inherent_data_provider,
+            evm_tracing: self.eth_config.evm_tracing_config(),
         })
     }
 
@@ -1,3 +1,4 @@
+use crate::debug::{EvmDebug, EvmDebugApiServer, EvmTracingConfig};
 use domain_service::rpc::FullDeps;
 use fc_mapping_sync::{EthereumBlockNotification, EthereumBlockNotificationSinks};
 use fc_rpc::{
@@ -11,14 +12,16 @@ use fp_rpc::{ConvertTransaction, ConvertTransactionRuntimeApi, EthereumRuntimeRP
 use jsonrpsee::RpcModule;
 use sc_client_api::backend::{Backend, StorageProvider};
 use sc_client_api::client::BlockchainEvents;
+use sc_client_api::{AuxStore, BlockBackend};
 use sc_network_sync::SyncingService;
 use sc_rpc::SubscriptionTaskExecutor;
 use sc_transaction_pool::ChainApi;
 use sc_transaction_pool_api::TransactionPool;
-use sp_api::{CallApiAt, ProvideRuntimeApi};
+use sp_api::{CallApiAt, Core, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_core::H256;
+use sp_evm_tracing::EvmTracingApi;
 use sp_inherents::CreateInherentDataProviders;
 use sp_runtime::traits::Block as BlockT;
 use std::collections::BTreeMap;
@@ -68,6 +71,8 @@ pub struct EthDeps<Client, TxPool, CA: ChainApi, CT, Block: BlockT, BE, CIDP> {
     pub forced_parent_hashes: Option<BTreeMap<H256, H256>>,
     /// Pending inherent data provider
     pub pending_inherent_data_provider: CIDP,
+    /// Tracing configuration, tracing RPC is disabled if `None`
+    pub evm_tracing: Option<EvmTracingConfig>,
 }
 
 impl<Client, TxPool, CA: ChainApi, CT: Clone, Block: BlockT, BE, CIDP: Clone> Clone
@@ -89,6 +94,7 @@ impl<Client, TxPool, CA: ChainApi, CT: Clone, Block: BlockT, BE, CIDP: Clone> Cl
             execute_gas_limit_multiplier: self.execute_gas_limit_multiplier,
             forced_parent_hashes: self.forced_parent_hashes.clone(),
             pending_inherent_data_provider: self.pending_inherent_data_provider.clone(),
+            evm_tracing: self.evm_tracing.clone(),
         }
     }
 }
@@ -105,12 +111,19 @@ pub(crate) fn create_eth_rpc<Client, BE, TxPool, CA, CT, Block, EC, CIDP>(
 where
     Block: BlockT<Hash = H256>,
     Client: CallApiAt<Block> + ProvideRuntimeApi<Block>,
-    Client::Api:
-        BlockBuilderApi<Block> + EthereumRuntimeRPCApi<Block> + ConvertTransactionRuntimeApi<Block>,
+    Client::Api: BlockBuilderApi<Block>
+        + EthereumRuntimeRPCApi<Block>
+        + ConvertTransactionRuntimeApi<Block>
+        + Core<Block>
+        + EvmTracingApi<Block>,
     Client: BlockchainEvents<Block> + 'static,
     Client: HeaderBackend<Block>
         + HeaderMetadata<Block, Error = BlockChainError>
-        + StorageProvider<Block, BE>,
+        + StorageProvider<Block, BE>
+        + BlockBackend<Block>
+        + AuxStore
+        + Send
+        + Sync,
     BE: Backend<Block> + 'static,
     TxPool: TransactionPool<Block = Block> + 'static,
     CA: ChainApi<Block = Block> + 'static,
@@ -133,6 +146,7 @@ where
         execute_gas_limit_multiplier,
         forced_parent_hashes,
         pending_inherent_data_provider,
+        evm_tracing,
     } = deps;
 
     let FullDeps {
@@ -142,6 +156,7 @@ where
         network,
         sync,
         is_authority,
+        deny_unsafe,
         ..
     } = full_deps;
 
@@ -173,6 +188,18 @@ where
         .into_rpc(),
     )?;
 
+    if let Some(evm_tracing) = evm_tracing {
+        io.merge(
+            EvmDebug::new(
+                client.clone(),
+                frontier_backend.clone(),
+                evm_tracing,
+                deny_unsafe,
+            )
+            .into_rpc(),
+        )?;
+    }
+
     if let Some(filter_pool) = filter_pool {
         io.merge(
             EthFilter::new(
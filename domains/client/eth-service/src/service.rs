@@ -1,3 +1,4 @@
+use crate::debug::EvmTracingConfig;
 use fc_mapping_sync::kv::MappingSyncWorker;
 use fc_mapping_sync::SyncStrategy;
 use fc_rpc::{EthTask, OverrideHandle};
@@ -11,6 +12,7 @@ use sp_blockchain::HeaderBackend;
 use sp_core::traits::SpawnEssentialNamed;
 use sp_runtime::traits::{Block as BlockT, NumberFor, Zero};
 use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
@@ -44,6 +46,50 @@ pub struct EthConfiguration {
     /// Size in bytes of the LRU cache for transactions statuses data.
     #[arg(long, default_value = "50")]
     pub eth_statuses_cache: usize,
+
+    /// Enable `debug_traceTransaction`, `debug_traceBlockByNumber` and `debug_traceBlockByHash`
+    /// RPC methods.
+    #[arg(long)]
+    pub enable_evm_tracing: bool,
+
+    /// Maximum number of transactions or blocks traced concurrently.
+    #[arg(long, default_value = "2")]
+    pub evm_tracing_max_concurrent: NonZeroUsize,
+
+    /// Time in seconds after which tracing request fails.
+    #[arg(long, default_value = "30")]
+    pub evm_tracing_timeout: u64,
+
+    /// Maximum size of a single transaction or block trace in MiB.
+    #[arg(long, default_value = "64")]
+    pub evm_tracing_max_size: u32,
+
+    /// Maximum number of executed opcodes in a single transaction or block trace.
+    #[arg(long, default_value = "10000000")]
+    pub evm_tracing_max_steps: u32,
+
+    /// Number of traces kept in memory.
+    #[arg(long, default_value = "64")]
+    pub evm_tracing_cache_size: NonZeroUsize,
+
+    /// Persist produced traces in the database, so they survive restarts. Persisted traces are
+    /// never pruned.
+    #[arg(long)]
+    pub evm_tracing_persistent_cache: bool,
+}
+
+impl EthConfiguration {
+    /// Tracing configuration, `None` if tracing is not enabled
+    pub(crate) fn evm_tracing_config(&self) -> Option<EvmTracingConfig> {
+        self.enable_evm_tracing.then(|| EvmTracingConfig {
+            max_concurrent_traces: self.evm_tracing_max_concurrent,
+            timeout: Duration::from_secs(self.evm_tracing_timeout),
+            max_trace_size: self.evm_tracing_max_size.saturating_mul(1024 * 1024),
+            max_trace_steps: self.evm_tracing_max_steps,
+            cache_size: self.evm_tracing_cache_size,
+            persistent_cache: self.evm_tracing_persistent_cache,
+        })
+    }
 }
 
 pub(crate) struct FrontierPartialComponents {
@@ -5,11 +5,13 @@ use crate::Pallet as Messenger;
 use frame_benchmarking::v2::*;
 use frame_support::assert_ok;
 use frame_support::traits::Get;
+use frame_support::weights::Weight;
 use frame_system::RawOrigin;
 use sp_messenger::endpoint::{Endpoint, EndpointRequest};
 use sp_messenger::messages::{
-    ConsensusChainMmrLeafProof, CrossDomainMessage, InitiateChannelParams, Message,
-    MessageWeightTag, Payload, Proof, RequestResponse, VersionedPayload,
+    ChainAllowlistUpdate, ConsensusChainMmrLeafProof, CrossDomainMessage, DeadLetter,
+    DeadLetterReason, InitiateChannelParams, Message, MessageWeightTag, Payload, Proof,
+    RequestResponse, VersionedPayload,
 };
 use sp_mmr_primitives::{EncodableOpaqueLeaf, Proof as MmrProof};
 use sp_trie::StorageProof;
@@ -202,6 +204,126 @@ mod benchmarks {
         );
     }
 
+    // Benchmark the worst case where the allowlist update is also reported to the consensus chain
+    #[benchmark]
+    fn update_chain_allowlist() -> Result<(), BenchmarkError> {
+        let origin = T::ChainAllowlistOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let chain_id: ChainId = u32::MAX.into();
+        assert_ne!(T::SelfChainId::get(), chain_id);
+        let consensus_channel = if T::SelfChainId::get().is_consensus_chain() {
+            None
+        } else {
+            Some(open_channel::<T>(
+                ChainId::Consensus,
+                dummy_channel_params::<T>(),
+            ))
+        };
+
+        #[extrinsic_call]
+        _(
+            origin as T::RuntimeOrigin,
+            ChainAllowlistUpdate::Add(chain_id),
+        );
+
+        assert!(Messenger::<T>::is_chain_allowed(&chain_id));
+        if let Some(channel_id) = consensus_channel {
+            let channel =
+                Channels::<T>::get(ChainId::Consensus, channel_id).expect("channel should exist");
+            assert!(Outbox::<T>::get((
+                ChainId::Consensus,
+                channel_id,
+                channel.next_outbox_nonce - 1
+            ))
+            .is_some());
+        }
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn do_update_domain_chain_allowlist() -> Result<(), BenchmarkError> {
+        // Domain allowlists are only mirrored on the consensus chain
+        if !T::SelfChainId::get().is_consensus_chain() {
+            return Err(BenchmarkError::Weightless);
+        }
+        let domain_chain_id: ChainId = u32::MAX.into();
+        let update = ChainAllowlistUpdate::Add(ChainId::Consensus);
+
+        #[block]
+        {
+            assert_ok!(Messenger::<T>::do_update_domain_chain_allowlist(
+                domain_chain_id,
+                update
+            ));
+        }
+
+        assert!(
+            DomainChainAllowlists::<T>::get(sp_domains::DomainId::new(u32::MAX))
+                .expect("allowlist should exist")
+                .contains(&ChainId::Consensus)
+        );
+
+        Ok(())
+    }
+
+    // Benchmark the case where there is no handler for the dead letter endpoint and the dead
+    // letter is stored again with updated failure reason, the weight of the endpoint handler
+    // itself is added separately according to the weight limit
+    #[benchmark]
+    fn retry_dead_letter() -> Result<(), BenchmarkError> {
+        let origin =
+            T::DeadLetterOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let (chain_id, message_id) = dummy_dead_letter::<T>();
+
+        #[extrinsic_call]
+        _(
+            origin as T::RuntimeOrigin,
+            chain_id,
+            message_id,
+            Weight::zero(),
+        );
+
+        assert_eq!(
+            DeadLetters::<T>::get((chain_id, message_id)).map(|dead_letter| dead_letter.reason),
+            Some(DeadLetterReason::NoMessageHandler)
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn discard_dead_letter() -> Result<(), BenchmarkError> {
+        let origin =
+            T::DeadLetterOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let (chain_id, message_id) = dummy_dead_letter::<T>();
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, chain_id, message_id);
+
+        assert!(!DeadLetters::<T>::contains_key((chain_id, message_id)));
+
+        Ok(())
+    }
+
+    fn dummy_dead_letter<T: Config>() -> (ChainId, MessageId) {
+        let chain_id: ChainId = u32::MAX.into();
+        let message_id = (ChannelId::zero(), Nonce::zero());
+        DeadLetters::<T>::insert(
+            (chain_id, message_id),
+            DeadLetter {
+                request: EndpointRequest {
+                    src_endpoint: Endpoint::Id(u64::MAX),
+                    dst_endpoint: Endpoint::Id(u64::MAX),
+                    payload: vec![],
+                },
+                reason: DeadLetterReason::NoMessageHandler,
+                failed_at: Default::default(),
+            },
+        );
+        (chain_id, message_id)
+    }
+
     fn dummy_channel_params<T: Config>() -> InitiateChannelParams<BalanceOf<T>> {
         let fee_model = FeeModel {
             relay_fee: 1u32.into(),
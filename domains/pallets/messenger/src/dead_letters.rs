@@ -0,0 +1,113 @@
+use crate::{Config, DeadLetters, Error, Event, Pallet};
+use frame_support::ensure;
+use frame_support::weights::Weight;
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_messenger::endpoint::EndpointRequest;
+use sp_messenger::messages::{ChainId, DeadLetter, DeadLetterReason, MessageId};
+use sp_runtime::traits::Get;
+use sp_runtime::DispatchResult;
+use sp_std::vec::Vec;
+
+impl<T: Config> Pallet<T> {
+    /// Adds an inbound message that failed to be handled to the dead letters.
+    /// The message is only logged if the dead letters are full.
+    pub(crate) fn store_dead_letter(
+        src_chain_id: ChainId,
+        message_id: MessageId,
+        request: EndpointRequest,
+        reason: DeadLetterReason,
+    ) {
+        if DeadLetters::<T>::count() >= T::MaxDeadLetters::get() {
+            log::warn!(
+                "Dead letters are full, dropping failed message {:?} from {:?}: {:?}",
+                message_id,
+                src_chain_id,
+                reason,
+            );
+            return;
+        }
+
+        DeadLetters::<T>::insert(
+            (src_chain_id, message_id),
+            DeadLetter {
+                request,
+                reason: reason.clone(),
+                failed_at: frame_system::Pallet::<T>::block_number(),
+            },
+        );
+
+        Self::deposit_event(Event::MessageDeadLettered {
+            chain_id: src_chain_id,
+            message_id,
+            reason,
+        });
+    }
+
+    pub(crate) fn do_retry_dead_letter(
+        src_chain_id: ChainId,
+        message_id: MessageId,
+        weight_limit: Weight,
+    ) -> DispatchResult {
+        let mut dead_letter = DeadLetters::<T>::get((src_chain_id, message_id))
+            .ok_or(Error::<T>::MissingDeadLetter)?;
+
+        let result = match T::get_endpoint_handler(&dead_letter.request.dst_endpoint) {
+            Some(endpoint_handler) => {
+                ensure!(
+                    endpoint_handler.message_weight().all_lte(weight_limit),
+                    Error::<T>::DeadLetterWeightLimitExceeded
+                );
+
+                endpoint_handler
+                    .message(src_chain_id, message_id, dead_letter.request.clone())
+                    .map(|_| ())
+                    .map_err(DeadLetterReason::HandlerFailed)
+            }
+            None => Err(DeadLetterReason::NoMessageHandler),
+        };
+
+        match &result {
+            Ok(()) => {
+                DeadLetters::<T>::remove((src_chain_id, message_id));
+            }
+            Err(reason) => {
+                dead_letter.reason = reason.clone();
+                dead_letter.failed_at = frame_system::Pallet::<T>::block_number();
+                DeadLetters::<T>::insert((src_chain_id, message_id), dead_letter);
+            }
+        }
+
+        Self::deposit_event(Event::DeadLetterRetried {
+            chain_id: src_chain_id,
+            message_id,
+            result,
+        });
+        Ok(())
+    }
+
+    pub(crate) fn do_discard_dead_letter(
+        src_chain_id: ChainId,
+        message_id: MessageId,
+    ) -> DispatchResult {
+        ensure!(
+            DeadLetters::<T>::contains_key((src_chain_id, message_id)),
+            Error::<T>::MissingDeadLetter
+        );
+        DeadLetters::<T>::remove((src_chain_id, message_id));
+
+        Self::deposit_event(Event::DeadLetterDiscarded {
+            chain_id: src_chain_id,
+            message_id,
+        });
+        Ok(())
+    }
+
+    /// Returns all the dead letters together with the source chain and message id.
+    pub fn dead_letters() -> Vec<(ChainId, MessageId, DeadLetter<BlockNumberFor<T>>)> {
+        DeadLetters::<T>::iter()
+            .map(|((src_chain_id, message_id), dead_letter)| {
+                (src_chain_id, message_id, dead_letter)
+            })
+            .collect()
+    }
+}
@@ -28,8 +28,10 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod placeholder_weights;
 pub mod weights;
 
+mod dead_letters;
 mod fees;
 mod messages;
 
@@ -97,6 +99,7 @@ pub struct ValidatedRelayMessage<Balance> {
 
 #[frame_support::pallet]
 mod pallet {
+    use crate::placeholder_weights::PlaceholderWeightInfo;
     use crate::weights::WeightInfo;
     use crate::{
         BalanceOf, Channel, ChannelId, ChannelState, FeeModel, Nonce, OutboxMessageResult,
@@ -108,15 +111,18 @@ mod pallet {
     use frame_system::pallet_prelude::*;
     use sp_core::storage::StorageKey;
     use sp_domains::proof_provider_and_verifier::{StorageProofVerifier, VerificationError};
+    use sp_domains::DomainId;
     use sp_messenger::endpoint::{Endpoint, EndpointHandler, EndpointRequest, Sender};
     use sp_messenger::messages::{
-        ChainId, CrossDomainMessage, InitiateChannelParams, Message, MessageId, MessageWeightTag,
-        Payload, ProtocolMessageRequest, RequestResponse, VersionedPayload,
+        ChainAllowlistUpdate, ChainId, CrossDomainMessage, DeadLetter, DeadLetterReason,
+        InitiateChannelParams, Message, MessageId, MessageWeightTag, Payload,
+        ProtocolMessageRequest, RequestResponse, VersionedPayload,
     };
     use sp_messenger::{MmrProofVerifier, OnXDMRewards, StorageKeys};
     use sp_mmr_primitives::EncodableOpaqueLeaf;
     use sp_runtime::ArithmeticError;
     use sp_std::boxed::Box;
+    use sp_std::collections::btree_set::BTreeSet;
     use sp_std::vec::Vec;
 
     #[pallet::config]
@@ -132,7 +138,7 @@ mod pallet {
         /// Confirmation depth for XDM coming from chains.
         type ConfirmationDepth: Get<BlockNumberFor<Self>>;
         /// Weight information for extrinsics in this pallet.
-        type WeightInfo: WeightInfo;
+        type WeightInfo: WeightInfo + PlaceholderWeightInfo;
         /// Weight to fee conversion.
         type WeightToFee: WeightToFee<Balance = BalanceOf<Self>>;
         /// Handle XDM rewards.
@@ -143,6 +149,15 @@ mod pallet {
         type MmrProofVerifier: MmrProofVerifier<Self::MmrHash, StateRootOf<Self>>;
         /// Storage key provider.
         type StorageKeys: StorageKeys;
+        /// Origin allowed to update the chain allowlist.
+        /// Consensus chain uses root while domains can use their own governance origin.
+        type ChainAllowlistOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+        /// Origin allowed to retry or discard dead letters.
+        type DeadLetterOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+        /// Maximum number of dead letters kept, failed messages are not kept once the limit is
+        /// reached.
+        #[pallet::constant]
+        type MaxDeadLetters: Get<u32>;
     }
 
     /// Pallet messenger used to communicate between chains and other blockchains.
@@ -229,6 +244,31 @@ mod pallet {
     pub(super) type BlockMessages<T: Config> =
         StorageValue<_, crate::messages::BlockMessages, OptionQuery>;
 
+    /// Chains this chain accepts new channels from.
+    /// If not set, channels from any chain are accepted.
+    #[pallet::storage]
+    #[pallet::getter(fn chain_allowlist)]
+    pub(super) type ChainAllowlist<T: Config> = StorageValue<_, BTreeSet<ChainId>, OptionQuery>;
+
+    /// Mirror of the domains' chain allowlists, as reported by the domains.
+    /// Only populated on the consensus chain.
+    #[pallet::storage]
+    #[pallet::getter(fn domain_chain_allowlists)]
+    pub(super) type DomainChainAllowlists<T: Config> =
+        StorageMap<_, Identity, DomainId, BTreeSet<ChainId>, OptionQuery>;
+
+    /// Inbound endpoint messages that failed to be handled, keyed by the source chain and
+    /// message id. Bounded by `MaxDeadLetters`.
+    #[pallet::storage]
+    #[pallet::getter(fn dead_letter)]
+    pub(super) type DeadLetters<T: Config> = CountedStorageMap<
+        _,
+        Identity,
+        (ChainId, MessageId),
+        DeadLetter<BlockNumberFor<T>>,
+        OptionQuery,
+    >;
+
     /// `pallet-messenger` events
     #[pallet::event]
     #[pallet::generate_deposit(pub (super) fn deposit_event)]
@@ -296,6 +336,38 @@ mod pallet {
             channel_id: ChannelId,
             nonce: Nonce,
         },
+
+        /// Emits when the chain allowlist of this chain is updated.
+        ChainAllowlistUpdated { update: ChainAllowlistUpdate },
+
+        /// Emits when a domain reported an update to its chain allowlist.
+        DomainChainAllowlistUpdated {
+            domain_id: DomainId,
+            update: ChainAllowlistUpdate,
+        },
+
+        /// Emits when an inbound message failed to be handled and was added to the dead letters.
+        MessageDeadLettered {
+            /// Source chain ID.
+            chain_id: ChainId,
+            message_id: MessageId,
+            reason: DeadLetterReason,
+        },
+
+        /// Emits when a dead letter was retried, `Ok` means it was handled and removed.
+        DeadLetterRetried {
+            /// Source chain ID.
+            chain_id: ChainId,
+            message_id: MessageId,
+            result: Result<(), DeadLetterReason>,
+        },
+
+        /// Emits when a dead letter was discarded.
+        DeadLetterDiscarded {
+            /// Source chain ID.
+            chain_id: ChainId,
+            message_id: MessageId,
+        },
     }
 
     #[pallet::validate_unsigned]
@@ -436,6 +508,21 @@ mod pallet {
 
         /// Emite when the there is balance overflow
         BalanceOverflow,
+
+        /// Emits when the chain is not in the chain allowlist.
+        ChainNotAllowed,
+
+        /// Emits when the chain is already present in the chain allowlist.
+        ChainAlreadyAllowed,
+
+        /// Emits when the chain allowlist update is not reported by a domain.
+        InvalidAllowlistUpdateSource,
+
+        /// Emits when there is no dead letter for the given message.
+        MissingDeadLetter,
+
+        /// Emits when the weight of handling a dead letter exceeds the given weight limit.
+        DeadLetterWeightLimitExceeded,
     }
 
     #[pallet::hooks]
@@ -460,6 +547,10 @@ mod pallet {
             params: InitiateChannelParams<BalanceOf<T>>,
         ) -> DispatchResult {
             ensure_root(origin)?;
+            ensure!(
+                Self::is_chain_allowed(&dst_chain_id),
+                Error::<T>::ChainNotAllowed
+            );
             // TODO(ved): fee for channel open
 
             // initiate the channel config
@@ -527,6 +618,67 @@ mod pallet {
             Self::process_outbox_message_responses(outbox_resp_msg, msg.weight_tag)?;
             Ok(())
         }
+
+        /// Updates the list of chains this chain accepts new channels from.
+        /// Existing channels are not affected.
+        /// On a domain, the update is also reported to the consensus chain if there is
+        /// an open channel to it.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::update_chain_allowlist())]
+        pub fn update_chain_allowlist(
+            origin: OriginFor<T>,
+            update: ChainAllowlistUpdate,
+        ) -> DispatchResult {
+            T::ChainAllowlistOrigin::ensure_origin(origin)?;
+            Self::do_update_chain_allowlist(update)?;
+
+            if T::SelfChainId::get().maybe_domain_chain().is_some()
+                && let Some((channel_id, _)) = Self::get_open_channel_for_chain(ChainId::Consensus)
+            {
+                Self::new_outbox_message(
+                    T::SelfChainId::get(),
+                    ChainId::Consensus,
+                    channel_id,
+                    VersionedPayload::V0(Payload::Protocol(RequestResponse::Request(
+                        ProtocolMessageRequest::ChainAllowlistUpdate(update),
+                    ))),
+                )?;
+            }
+
+            Ok(())
+        }
+
+        /// Retries handling of a dead letter with the current endpoint handler.
+        /// The dead letter is removed if it is handled successfully, otherwise its failure reason
+        /// is updated.
+        ///
+        /// NOTE: the source chain already received the failure response for the message, so the
+        /// result of the retry is not relayed back and the source chain won't revert or repeat
+        /// any side effects it applied on failure (e.g. refunds of transfers), governance must
+        /// account for this before retrying.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::retry_dead_letter().saturating_add(*weight_limit))]
+        pub fn retry_dead_letter(
+            origin: OriginFor<T>,
+            chain_id: ChainId,
+            message_id: MessageId,
+            weight_limit: Weight,
+        ) -> DispatchResult {
+            T::DeadLetterOrigin::ensure_origin(origin)?;
+            Self::do_retry_dead_letter(chain_id, message_id, weight_limit)
+        }
+
+        /// Discards a dead letter without handling it.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::discard_dead_letter())]
+        pub fn discard_dead_letter(
+            origin: OriginFor<T>,
+            chain_id: ChainId,
+            message_id: MessageId,
+        ) -> DispatchResult {
+            T::DeadLetterOrigin::ensure_origin(origin)?;
+            Self::do_discard_dead_letter(chain_id, message_id)
+        }
     }
 
     impl<T: Config> Sender<T::AccountId> for Pallet<T> {
@@ -582,6 +734,9 @@ mod pallet {
             match weight_tag {
                 MessageWeightTag::ProtocolChannelOpen => T::WeightInfo::do_open_channel(),
                 MessageWeightTag::ProtocolChannelClose => T::WeightInfo::do_close_channel(),
+                MessageWeightTag::ProtocolChainAllowlistUpdate => {
+                    T::WeightInfo::do_update_domain_chain_allowlist()
+                }
                 MessageWeightTag::EndpointRequest(endpoint) => {
                     T::get_endpoint_handler(endpoint)
                         .map(|endpoint_handler| endpoint_handler.message_weight())
@@ -619,6 +774,70 @@ mod pallet {
             None
         }
 
+        /// Returns true if new channels from/to the given chain are allowed.
+        pub fn is_chain_allowed(chain_id: &ChainId) -> bool {
+            ChainAllowlist::<T>::get()
+                .map(|allowlist| allowlist.contains(chain_id))
+                .unwrap_or(true)
+        }
+
+        pub(crate) fn do_update_chain_allowlist(update: ChainAllowlistUpdate) -> DispatchResult {
+            ChainAllowlist::<T>::try_mutate(|maybe_allowlist| -> DispatchResult {
+                let updated = Self::apply_chain_allowlist_update(maybe_allowlist, update);
+                match update {
+                    ChainAllowlistUpdate::Add(_) => {
+                        ensure!(updated, Error::<T>::ChainAlreadyAllowed)
+                    }
+                    ChainAllowlistUpdate::Remove(_) => {
+                        ensure!(updated, Error::<T>::ChainNotAllowed)
+                    }
+                }
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::ChainAllowlistUpdated { update });
+            Ok(())
+        }
+
+        /// Records the chain allowlist update reported by a domain.
+        /// The mirror is updated leniently so that it converges with the domain's allowlist
+        /// even if some earlier updates were not reported.
+        pub(crate) fn do_update_domain_chain_allowlist(
+            src_chain_id: ChainId,
+            update: ChainAllowlistUpdate,
+        ) -> DispatchResult {
+            ensure!(
+                T::SelfChainId::get().is_consensus_chain(),
+                Error::<T>::InvalidMessageDestination
+            );
+            let domain_id = src_chain_id
+                .maybe_domain_chain()
+                .ok_or(Error::<T>::InvalidAllowlistUpdateSource)?;
+
+            DomainChainAllowlists::<T>::mutate(domain_id, |maybe_allowlist| {
+                Self::apply_chain_allowlist_update(maybe_allowlist, update)
+            });
+
+            Self::deposit_event(Event::DomainChainAllowlistUpdated { domain_id, update });
+            Ok(())
+        }
+
+        /// Applies the update to the allowlist, returns `false` if the allowlist is unchanged.
+        fn apply_chain_allowlist_update(
+            maybe_allowlist: &mut Option<BTreeSet<ChainId>>,
+            update: ChainAllowlistUpdate,
+        ) -> bool {
+            match update {
+                ChainAllowlistUpdate::Add(chain_id) => maybe_allowlist
+                    .get_or_insert_with(BTreeSet::new)
+                    .insert(chain_id),
+                ChainAllowlistUpdate::Remove(chain_id) => maybe_allowlist
+                    .as_mut()
+                    .map(|allowlist| allowlist.remove(&chain_id))
+                    .unwrap_or(false),
+            }
+        }
+
         /// Opens an initiated channel.
         pub(crate) fn do_open_channel(chain_id: ChainId, channel_id: ChannelId) -> DispatchResult {
             Channels::<T>::try_mutate(chain_id, channel_id, |maybe_channel| -> DispatchResult {
@@ -747,7 +966,15 @@ mod pallet {
                 match msg.payload {
                     VersionedPayload::V0(Payload::Protocol(RequestResponse::Request(
                         ProtocolMessageRequest::ChannelOpen(_),
-                    ))) => {}
+                    ))) => {
+                        if !Self::is_chain_allowed(&msg.src_chain_id) {
+                            log::error!(
+                                "Channel open request from chain not in allowlist: {:?}",
+                                msg.src_chain_id
+                            );
+                            return Err(InvalidTransaction::Call.into());
+                        }
+                    }
                     _ => {
                         log::error!("Unexpected call instead of channel open request: {:?}", msg,);
                         return Err(InvalidTransaction::Call.into());
@@ -6,9 +6,9 @@ use codec::{Decode, Encode};
 use frame_support::ensure;
 use scale_info::TypeInfo;
 use sp_messenger::messages::{
-    BlockMessageWithStorageKey, BlockMessagesWithStorageKey, ChainId, Message, MessageId,
-    MessageWeightTag, Payload, ProtocolMessageRequest, ProtocolMessageResponse, RequestResponse,
-    VersionedPayload,
+    BlockMessageWithStorageKey, BlockMessagesWithStorageKey, ChainId, DeadLetterReason, Message,
+    MessageId, MessageWeightTag, Payload, ProtocolMessageRequest, ProtocolMessageResponse,
+    RequestResponse, VersionedPayload,
 };
 use sp_runtime::traits::Get;
 use sp_runtime::{ArithmeticError, DispatchError, DispatchResult};
@@ -133,8 +133,24 @@ impl<T: Config> Pallet<T> {
                         &req.src_endpoint,
                     )?;
 
-                    endpoint_handler.message(dst_chain_id, (channel_id, nonce), req)
+                    let response =
+                        endpoint_handler.message(dst_chain_id, (channel_id, nonce), req.clone());
+                    if let Err(err) = response {
+                        Self::store_dead_letter(
+                            dst_chain_id,
+                            (channel_id, nonce),
+                            req,
+                            DeadLetterReason::HandlerFailed(err),
+                        );
+                    }
+                    response
                 } else {
+                    Self::store_dead_letter(
+                        dst_chain_id,
+                        (channel_id, nonce),
+                        req,
+                        DeadLetterReason::NoMessageHandler,
+                    );
                     Err(Error::<T>::NoMessageHandler.into())
                 };
 
@@ -224,6 +240,12 @@ impl<T: Config> Pallet<T> {
                 }
                 Self::do_close_channel(chain_id, channel_id)
             }
+            ProtocolMessageRequest::ChainAllowlistUpdate(update) => {
+                if weight_tag != &MessageWeightTag::ProtocolChainAllowlistUpdate {
+                    return Err(Error::<T>::WeightTagNotMatch.into());
+                }
+                Self::do_update_domain_chain_allowlist(chain_id, update)
+            }
         }
     }
 
@@ -83,6 +83,11 @@ macro_rules! impl_runtime {
 
         parameter_types! {
             pub SelfChainId: ChainId = $chain_id.into();
+            pub const MaxDeadLetters: u32 = 2;
+        }
+
+        frame_support::ord_parameter_types! {
+            pub const DomainGovernance: AccountId = 100;
         }
 
         impl crate::Config for $runtime {
@@ -96,6 +101,12 @@ macro_rules! impl_runtime {
             type MmrHash = H256;
             type MmrProofVerifier = ();
             type StorageKeys = ();
+            type ChainAllowlistOrigin = frame_support::traits::EitherOfDiverse<
+                frame_system::EnsureRoot<AccountId>,
+                frame_system::EnsureSignedBy<DomainGovernance, AccountId>,
+            >;
+            type DeadLetterOrigin = frame_system::EnsureRoot<AccountId>;
+            type MaxDeadLetters = MaxDeadLetters;
             /// function to fetch endpoint response handler by Endpoint.
             fn get_endpoint_handler(
                 #[allow(unused_variables)] endpoint: &Endpoint,
@@ -113,6 +124,7 @@ macro_rules! impl_runtime {
                         100 => Some(Box::new(pallet_transporter::EndpointHandler(
                             PhantomData::<$runtime>,
                         ))),
+                        200 => None,
                         _ => Some(Box::new(MockEndpoint {})),
                     },
                 }
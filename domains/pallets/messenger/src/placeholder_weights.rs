@@ -0,0 +1,88 @@
+//! Placeholder weights for pallet_messenger.
+//!
+//! Chain allowlist updates and dead letters were added after weights in [`crate::weights`] were last generated.
+//! Values below are conservative estimates picked by hand, they were NOT measured. Benchmarks
+//! covering them exist, this module must be removed once weights are regenerated with
+//! `subspace-node benchmark pallet`.
+
+use crate::weights::SubstrateWeight;
+use frame_support::traits::Get;
+use frame_support::weights::constants::RocksDbWeight;
+use frame_support::weights::{RuntimeDbWeight, Weight};
+
+/// Weight functions of pallet_messenger that are not benchmarked yet.
+pub trait PlaceholderWeightInfo {
+    /// Weight of `update_chain_allowlist` call
+    fn update_chain_allowlist() -> Weight;
+    /// Weight of processing incoming domain chain allowlist update
+    fn do_update_domain_chain_allowlist() -> Weight;
+    /// Weight of `retry_dead_letter` call, excluding the endpoint handler
+    fn retry_dead_letter() -> Weight;
+    /// Weight of `discard_dead_letter` call
+    fn discard_dead_letter() -> Weight;
+}
+
+/// Updates `ChainAllowlist` and sends allowlist update to the domain, which touches channel,
+/// outbox and block messages storage
+fn update_chain_allowlist<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+    Weight::from_parts(40_000_000, 17_500)
+        .saturating_add(DbWeight::get().reads(6))
+        .saturating_add(DbWeight::get().writes(5))
+}
+
+/// Reads and writes `DomainChainAllowlists`
+fn do_update_domain_chain_allowlist<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+    Weight::from_parts(10_000_000, 3_700)
+        .saturating_add(DbWeight::get().reads(1))
+        .saturating_add(DbWeight::get().writes(1))
+}
+
+/// Reads and removes or updates `DeadLetters` together with its counter
+fn retry_dead_letter<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+    Weight::from_parts(15_000_000, 3_800)
+        .saturating_add(DbWeight::get().reads(2))
+        .saturating_add(DbWeight::get().writes(2))
+}
+
+/// Reads and removes `DeadLetters` together with its counter
+fn discard_dead_letter<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+    Weight::from_parts(15_000_000, 3_800)
+        .saturating_add(DbWeight::get().reads(2))
+        .saturating_add(DbWeight::get().writes(2))
+}
+
+impl<T: frame_system::Config> PlaceholderWeightInfo for SubstrateWeight<T> {
+    fn update_chain_allowlist() -> Weight {
+        update_chain_allowlist::<T::DbWeight>()
+    }
+
+    fn do_update_domain_chain_allowlist() -> Weight {
+        do_update_domain_chain_allowlist::<T::DbWeight>()
+    }
+
+    fn retry_dead_letter() -> Weight {
+        retry_dead_letter::<T::DbWeight>()
+    }
+
+    fn discard_dead_letter() -> Weight {
+        discard_dead_letter::<T::DbWeight>()
+    }
+}
+
+impl PlaceholderWeightInfo for () {
+    fn update_chain_allowlist() -> Weight {
+        update_chain_allowlist::<RocksDbWeight>()
+    }
+
+    fn do_update_domain_chain_allowlist() -> Weight {
+        do_update_domain_chain_allowlist::<RocksDbWeight>()
+    }
+
+    fn retry_dead_letter() -> Weight {
+        retry_dead_letter::<RocksDbWeight>()
+    }
+
+    fn discard_dead_letter() -> Weight {
+        discard_dead_letter::<RocksDbWeight>()
+    }
+}
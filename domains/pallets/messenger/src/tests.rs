@@ -6,17 +6,19 @@ use crate::mock::{
     AccountId, Balance, TestExternalities,
 };
 use crate::{
-    Channel, ChannelId, ChannelState, Channels, Error, FeeModel, Inbox, InboxResponses, Nonce,
-    Outbox, OutboxMessageResult, OutboxResponses, U256,
+    Channel, ChannelId, ChannelState, Channels, DeadLetters, Error, FeeModel, Inbox,
+    InboxResponses, Nonce, Outbox, OutboxMessageResult, OutboxResponses, U256,
 };
-use frame_support::{assert_err, assert_ok};
+use frame_support::weights::Weight;
+use frame_support::{assert_err, assert_noop, assert_ok};
 use pallet_transporter::Location;
 use sp_core::storage::StorageKey;
 use sp_core::{Blake2Hasher, H256};
 use sp_domains::proof_provider_and_verifier::{StorageProofVerifier, VerificationError};
 use sp_messenger::endpoint::{Endpoint, EndpointPayload, EndpointRequest, Sender};
 use sp_messenger::messages::{
-    ChainId, ConsensusChainMmrLeafProof, CrossDomainMessage, InitiateChannelParams, Payload, Proof,
+    ChainAllowlistUpdate, ChainId, ConsensusChainMmrLeafProof, CrossDomainMessage,
+    DeadLetterReason, InitiateChannelParams, Message, MessageWeightTag, Payload, Proof,
     ProtocolMessageRequest, RequestResponse, VersionedPayload,
 };
 use sp_mmr_primitives::{EncodableOpaqueLeaf, Proof as MmrProof};
@@ -185,6 +187,94 @@ fn test_close_open_channel() {
     });
 }
 
+#[test]
+fn test_update_chain_allowlist() {
+    new_chain_a_ext().execute_with(|| {
+        let chain_id: ChainId = 2.into();
+        assert!(Messenger::is_chain_allowed(&chain_id));
+
+        assert_noop!(
+            Messenger::update_chain_allowlist(
+                RuntimeOrigin::signed(1),
+                ChainAllowlistUpdate::Add(chain_id)
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Messenger::update_chain_allowlist(
+                RuntimeOrigin::root(),
+                ChainAllowlistUpdate::Remove(chain_id)
+            ),
+            Error::<Runtime>::ChainNotAllowed
+        );
+
+        // once the allowlist is set, only listed chains are allowed
+        assert_ok!(Messenger::update_chain_allowlist(
+            RuntimeOrigin::root(),
+            ChainAllowlistUpdate::Add(ChainId::Consensus)
+        ));
+        System::assert_last_event(RuntimeEvent::Messenger(
+            crate::Event::<Runtime>::ChainAllowlistUpdated {
+                update: ChainAllowlistUpdate::Add(ChainId::Consensus),
+            },
+        ));
+        assert!(Messenger::is_chain_allowed(&ChainId::Consensus));
+        assert!(!Messenger::is_chain_allowed(&chain_id));
+        assert_err!(
+            Messenger::initiate_channel(RuntimeOrigin::root(), chain_id, Default::default()),
+            Error::<Runtime>::ChainNotAllowed
+        );
+
+        assert_ok!(Messenger::update_chain_allowlist(
+            RuntimeOrigin::root(),
+            ChainAllowlistUpdate::Add(chain_id)
+        ));
+        assert_noop!(
+            Messenger::update_chain_allowlist(
+                RuntimeOrigin::root(),
+                ChainAllowlistUpdate::Add(chain_id)
+            ),
+            Error::<Runtime>::ChainAlreadyAllowed
+        );
+        create_channel(chain_id, U256::zero(), Default::default());
+
+        // domain governance can manage the allowlist without root
+        assert_ok!(Messenger::update_chain_allowlist(
+            RuntimeOrigin::signed(100),
+            ChainAllowlistUpdate::Remove(chain_id)
+        ));
+        assert!(!Messenger::is_chain_allowed(&chain_id));
+    });
+}
+
+#[test]
+fn test_chain_allowlist_update_reported_to_consensus() {
+    new_chain_a_ext().execute_with(|| {
+        let channel_id = U256::zero();
+        create_channel(ChainId::Consensus, channel_id, Default::default());
+        assert_ok!(Messenger::do_open_channel(ChainId::Consensus, channel_id));
+
+        let update = ChainAllowlistUpdate::Add(2.into());
+        assert_ok!(Messenger::update_chain_allowlist(
+            RuntimeOrigin::root(),
+            update
+        ));
+        let msg = Outbox::<Runtime>::get((ChainId::Consensus, channel_id, Nonce::one())).unwrap();
+        assert_eq!(
+            msg.payload,
+            VersionedPayload::V0(Payload::Protocol(RequestResponse::Request(
+                ProtocolMessageRequest::ChainAllowlistUpdate(update)
+            )))
+        );
+
+        // domains do not accept allowlist updates from other chains
+        assert_err!(
+            Messenger::do_update_domain_chain_allowlist(2.into(), update),
+            Error::<Runtime>::InvalidMessageDestination
+        );
+    });
+}
+
 #[test]
 #[ignore]
 fn test_storage_proof_verification_invalid() {
@@ -821,3 +911,160 @@ fn test_transport_funds_between_chains_failed_no_open_channel() {
         assert_err!(res, crate::Error::<chain_a::Runtime>::NoOpenChannel);
     });
 }
+
+#[test]
+fn test_dead_letters() {
+    new_chain_a_ext().execute_with(|| {
+        let chain_id: ChainId = 2.into();
+        let channel_id = U256::zero();
+        create_channel(chain_id, channel_id, Default::default());
+        assert_ok!(Messenger::do_open_channel(chain_id, channel_id));
+
+        let inbox_message = |nonce: Nonce, req: EndpointRequest| Message {
+            src_chain_id: chain_id,
+            dst_chain_id: 1.into(),
+            channel_id,
+            nonce,
+            payload: VersionedPayload::V0(Payload::Endpoint(RequestResponse::Request(req))),
+            last_delivered_message_response_nonce: None,
+        };
+
+        // failed endpoint handler
+        let failed_req = EndpointRequest {
+            src_endpoint: Endpoint::Id(100),
+            dst_endpoint: Endpoint::Id(100),
+            payload: vec![],
+        };
+        let failed_message_id = (channel_id, Nonce::zero());
+        let failed_reason = DeadLetterReason::HandlerFailed(
+            pallet_transporter::Error::<Runtime>::InvalidPayload.into(),
+        );
+        assert_ok!(Messenger::process_inbox_messages(
+            inbox_message(Nonce::zero(), failed_req.clone()),
+            MessageWeightTag::EndpointRequest(Endpoint::Id(100)),
+        ));
+        System::assert_last_event(RuntimeEvent::Messenger(
+            crate::Event::<Runtime>::MessageDeadLettered {
+                chain_id,
+                message_id: failed_message_id,
+                reason: failed_reason.clone(),
+            },
+        ));
+        let dead_letter = Messenger::dead_letter((chain_id, failed_message_id)).unwrap();
+        assert_eq!(dead_letter.request, failed_req);
+        assert_eq!(dead_letter.reason, failed_reason);
+
+        // missing endpoint handler
+        let unhandled_req = EndpointRequest {
+            src_endpoint: Endpoint::Id(200),
+            dst_endpoint: Endpoint::Id(200),
+            payload: vec![1, 2, 3, 4],
+        };
+        let unhandled_message_id = (channel_id, Nonce::one());
+        assert_ok!(Messenger::process_inbox_messages(
+            inbox_message(Nonce::one(), unhandled_req),
+            MessageWeightTag::EndpointRequest(Endpoint::Id(200)),
+        ));
+        assert_eq!(
+            Messenger::dead_letter((chain_id, unhandled_message_id))
+                .unwrap()
+                .reason,
+            DeadLetterReason::NoMessageHandler
+        );
+        assert_eq!(Messenger::dead_letters().len(), 2);
+
+        // dead letters are full, further failed messages are not kept
+        let dropped_message_id = (channel_id, U256::from(2));
+        Messenger::store_dead_letter(
+            chain_id,
+            dropped_message_id,
+            failed_req,
+            DeadLetterReason::NoMessageHandler,
+        );
+        assert!(Messenger::dead_letter((chain_id, dropped_message_id)).is_none());
+        assert_eq!(DeadLetters::<Runtime>::count(), 2);
+
+        // only governance can retry or discard dead letters
+        assert_noop!(
+            Messenger::retry_dead_letter(
+                RuntimeOrigin::signed(1),
+                chain_id,
+                unhandled_message_id,
+                Weight::zero()
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Messenger::discard_dead_letter(RuntimeOrigin::signed(1), chain_id, failed_message_id),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        // retry that fails again keeps the dead letter
+        System::set_block_number(2);
+        assert_ok!(Messenger::retry_dead_letter(
+            RuntimeOrigin::root(),
+            chain_id,
+            unhandled_message_id,
+            Weight::zero()
+        ));
+        System::assert_last_event(RuntimeEvent::Messenger(
+            crate::Event::<Runtime>::DeadLetterRetried {
+                chain_id,
+                message_id: unhandled_message_id,
+                result: Err(DeadLetterReason::NoMessageHandler),
+            },
+        ));
+        assert_eq!(
+            Messenger::dead_letter((chain_id, unhandled_message_id))
+                .unwrap()
+                .failed_at,
+            2
+        );
+
+        // retry that succeeds removes the dead letter
+        DeadLetters::<Runtime>::mutate((chain_id, unhandled_message_id), |maybe_dead_letter| {
+            maybe_dead_letter.as_mut().unwrap().request.dst_endpoint = Endpoint::Id(1);
+        });
+        assert_ok!(Messenger::retry_dead_letter(
+            RuntimeOrigin::root(),
+            chain_id,
+            unhandled_message_id,
+            Weight::zero()
+        ));
+        System::assert_last_event(RuntimeEvent::Messenger(
+            crate::Event::<Runtime>::DeadLetterRetried {
+                chain_id,
+                message_id: unhandled_message_id,
+                result: Ok(()),
+            },
+        ));
+        assert!(Messenger::dead_letter((chain_id, unhandled_message_id)).is_none());
+
+        // discard
+        assert_ok!(Messenger::discard_dead_letter(
+            RuntimeOrigin::root(),
+            chain_id,
+            failed_message_id
+        ));
+        System::assert_last_event(RuntimeEvent::Messenger(
+            crate::Event::<Runtime>::DeadLetterDiscarded {
+                chain_id,
+                message_id: failed_message_id,
+            },
+        ));
+        assert!(Messenger::dead_letters().is_empty());
+        assert_noop!(
+            Messenger::discard_dead_letter(RuntimeOrigin::root(), chain_id, failed_message_id),
+            Error::<Runtime>::MissingDeadLetter
+        );
+        assert_noop!(
+            Messenger::retry_dead_letter(
+                RuntimeOrigin::root(),
+                chain_id,
+                failed_message_id,
+                Weight::zero()
+            ),
+            Error::<Runtime>::MissingDeadLetter
+        );
+    });
+}
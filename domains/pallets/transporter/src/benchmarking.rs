@@ -8,7 +8,7 @@ use frame_system::RawOrigin;
 use sp_messenger::endpoint::{
     Endpoint, EndpointHandler as EndpointHandlerT, EndpointRequest, Sender,
 };
-use sp_runtime::traits::Convert;
+use sp_runtime::traits::{Bounded, Convert};
 use sp_runtime::DispatchError;
 use sp_std::marker::PhantomData;
 
@@ -21,6 +21,9 @@ const SEED: u32 = 0;
 mod benchmarks {
     use super::*;
 
+    /// Benchmark `transfer` extrinsic with the worst possible conditions:
+    /// - There is a transfer limit with the destination chain that needs to be checked and the
+    ///   transfer accounted in the block volume
     #[benchmark]
     fn transfer() {
         let sender: T::AccountId = account("sender", 1, SEED);
@@ -36,6 +39,7 @@ mod benchmarks {
 
         T::Currency::make_free_balance_be(&sender, amount + T::Currency::minimum_balance());
         assert_ok!(T::Sender::unchecked_open_channel(dst_chain_id));
+        TransferLimits::<T>::insert(dst_chain_id, unbounded_transfer_limit::<T>());
 
         #[extrinsic_call]
         _(RawOrigin::Signed(sender.clone()), location, amount);
@@ -44,8 +48,15 @@ mod benchmarks {
             T::Currency::free_balance(&sender),
             T::Currency::minimum_balance()
         );
+        assert_eq!(
+            BlockTransferVolume::<T>::get().get(&dst_chain_id),
+            Some(&amount)
+        );
     }
 
+    /// Benchmark `message` with the worst possible conditions:
+    /// - There is a transfer limit with the source chain that needs to be checked and the
+    ///   transfer accounted in the block volume
     #[benchmark]
     fn message() {
         let sender: T::AccountId = account("sender", 1, SEED);
@@ -69,6 +80,7 @@ mod benchmarks {
             payload: transfer_obj.encode(),
         };
         let message_id = MessageIdOf::<T>::default();
+        TransferLimits::<T>::insert(dst_chain_id, unbounded_transfer_limit::<T>());
 
         #[block]
         {
@@ -120,6 +132,40 @@ mod benchmarks {
         }
     }
 
+    #[benchmark]
+    fn set_transfer_limit() {
+        let chain_id: ChainId = u32::MAX.into();
+        let limit = TransferLimit {
+            max_transfer: 100u32.into(),
+            max_block_volume: 1000u32.into(),
+        };
+
+        #[extrinsic_call]
+        _(RawOrigin::Root, chain_id, Some(limit));
+
+        assert_eq!(TransferLimits::<T>::get(chain_id), Some(limit));
+    }
+
+    #[benchmark]
+    fn resume_transfers() {
+        let chain_id: ChainId = u32::MAX.into();
+        PausedChains::<T>::insert(chain_id, frame_system::Pallet::<T>::block_number());
+
+        #[extrinsic_call]
+        _(RawOrigin::Root, chain_id);
+
+        assert!(!PausedChains::<T>::contains_key(chain_id));
+    }
+
+    /// Transfer limit that is never exceeded, such that limits are checked without rejecting
+    /// the transfer.
+    fn unbounded_transfer_limit<T: Config>() -> TransferLimit<BalanceOf<T>> {
+        TransferLimit {
+            max_transfer: BalanceOf::<T>::max_value(),
+            max_block_volume: BalanceOf::<T>::max_value(),
+        }
+    }
+
     impl_benchmark_test_suite!(
         Transporter,
         crate::mock::new_test_ext(),
@@ -40,6 +40,7 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod placeholder_weights;
 pub mod weights;
 
 /// Location that either sends or receives transfers between chains.
@@ -62,6 +63,22 @@ pub struct Transfer<Balance> {
     pub receiver: Location,
 }
 
+/// Limits on value moved between this chain and another chain.
+///
+/// Limits are a safety net for the period while fraud proofs may still be submitted against a
+/// suspected compromised domain, they bound the amount of funds that can leave or enter this
+/// chain before governance reacts.
+#[derive(Debug, Encode, Decode, Copy, Clone, Eq, PartialEq, TypeInfo)]
+pub struct TransferLimit<Balance> {
+    /// Maximum amount of a single transfer.
+    pub max_transfer: Balance,
+    /// Maximum total amount of transfers in both directions within a single block.
+    ///
+    /// Incoming transfers exceeding this threshold trip the circuit breaker and pause all
+    /// transfers with the chain until governance resumes them.
+    pub max_block_volume: Balance,
+}
+
 /// Balance type used by the pallet.
 pub(crate) type BalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -72,8 +89,11 @@ type MessageIdOf<T> = <<T as Config>::Sender as sp_messenger::endpoint::Sender<
 
 #[frame_support::pallet]
 mod pallet {
+    use crate::placeholder_weights::PlaceholderWeightInfo;
     use crate::weights::WeightInfo;
-    use crate::{BalanceOf, Location, MessageIdOf, MultiAccountId, Transfer, TryConvertBack};
+    use crate::{
+        BalanceOf, Location, MessageIdOf, MultiAccountId, Transfer, TransferLimit, TryConvertBack,
+    };
     use codec::{Decode, Encode};
     use frame_support::pallet_prelude::*;
     use frame_support::traits::{Currency, ExistenceRequirement, WithdrawReasons};
@@ -85,7 +105,8 @@ mod pallet {
         EndpointResponse, Sender,
     };
     use sp_messenger::messages::ChainId;
-    use sp_runtime::traits::Convert;
+    use sp_runtime::traits::{CheckedAdd, Convert};
+    use sp_std::collections::btree_map::BTreeMap;
     use sp_std::vec;
     use sp_std::vec::Vec;
 
@@ -110,7 +131,7 @@ mod pallet {
         type AccountIdConverter: TryConvertBack<Self::AccountId, MultiAccountId>;
 
         /// Weight information for extrinsics in this pallet.
-        type WeightInfo: WeightInfo;
+        type WeightInfo: WeightInfo + PlaceholderWeightInfo;
     }
 
     /// Pallet transporter to move funds between chains.
@@ -156,6 +177,26 @@ mod pallet {
     pub(super) type CancelledTransfers<T: Config> =
         StorageDoubleMap<_, Identity, ChainId, Identity, ChainId, BalanceOf<T>, ValueQuery>;
 
+    /// Transfer limits with other chains, transfers with chains without limits are unrestricted.
+    #[pallet::storage]
+    #[pallet::getter(fn transfer_limits)]
+    pub(super) type TransferLimits<T: Config> =
+        StorageMap<_, Identity, ChainId, TransferLimit<BalanceOf<T>>, OptionQuery>;
+
+    /// A temporary storage that tracks total volume of transfers with other chains in this block.
+    /// Clears on on_initialize for every block.
+    #[pallet::storage]
+    #[pallet::getter(fn block_transfer_volume)]
+    pub(super) type BlockTransferVolume<T: Config> =
+        StorageValue<_, BTreeMap<ChainId, BalanceOf<T>>, ValueQuery>;
+
+    /// Chains with which transfers are paused by the circuit breaker and the block at which they
+    /// were paused.
+    #[pallet::storage]
+    #[pallet::getter(fn paused_chains)]
+    pub(super) type PausedChains<T: Config> =
+        StorageMap<_, Identity, ChainId, BlockNumberFor<T>, OptionQuery>;
+
     /// Events emitted by pallet-transporter.
     #[pallet::event]
     #[pallet::generate_deposit(pub (super) fn deposit_event)]
@@ -193,6 +234,29 @@ mod pallet {
             /// Id of the transfer.
             message_id: MessageIdOf<T>,
         },
+
+        /// Emits when transfer limit with a chain was updated.
+        TransferLimitUpdated {
+            /// Chain the limit applies to.
+            chain_id: ChainId,
+            /// New limit, `None` if limit was removed.
+            limit: Option<TransferLimit<BalanceOf<T>>>,
+        },
+
+        /// Emits when circuit breaker paused transfers with a chain.
+        TransfersPaused {
+            /// Chain transfers with which were paused.
+            chain_id: ChainId,
+            /// Volume of transfers with the chain accepted in this block before the circuit breaker
+            /// tripped.
+            block_volume: BalanceOf<T>,
+        },
+
+        /// Emits when governance resumed transfers with a chain.
+        TransfersResumed {
+            /// Chain transfers with which were resumed.
+            chain_id: ChainId,
+        },
     }
 
     /// Errors emitted by pallet-transporter.
@@ -220,6 +284,12 @@ mod pallet {
         BalanceUnderflow,
         /// Emits when domain balance is already initialized
         DomainBalanceAlreadyInitialized,
+        /// Emits when transfers with the chain are paused by the circuit breaker.
+        TransfersPaused,
+        /// Emits when transfer exceeds transfer limit with the chain.
+        TransferLimitExceeded,
+        /// Emits when trying to resume transfers with a chain that is not paused.
+        TransfersNotPaused,
     }
 
     #[pallet::call]
@@ -227,13 +297,23 @@ mod pallet {
         /// Initiates transfer of funds from account on src_chain to account on dst_chain.
         /// Funds are burned on src_chain first and are minted on dst_chain using Messenger.
         #[pallet::call_index(0)]
-        #[pallet::weight(T::WeightInfo::transfer())]
+        #[pallet::weight(
+            T::WeightInfo::transfer().saturating_add(T::WeightInfo::transfer_limit_checks())
+        )]
         pub fn transfer(
             origin: OriginFor<T>,
             dst_location: Location,
             amount: BalanceOf<T>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
+            let dst_chain_id = dst_location.chain_id;
+
+            // outgoing transfers never trip the circuit breaker since they are initiated by users
+            // of this chain, they are simply rejected when exceeding the limits
+            ensure!(
+                Self::check_transfer_limit(dst_chain_id, amount)?,
+                Error::<T>::TransferLimitExceeded
+            );
 
             // burn transfer amount
             let _imbalance = T::Currency::withdraw(
@@ -245,7 +325,6 @@ mod pallet {
             .map_err(|_| Error::<T>::LowBalance)?;
 
             // initiate transfer
-            let dst_chain_id = dst_location.chain_id;
             let transfer = Transfer {
                 amount,
                 sender: Location {
@@ -285,13 +364,45 @@ mod pallet {
 
             Ok(())
         }
+
+        /// Set or remove limits on transfers with the chain.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::set_transfer_limit())]
+        pub fn set_transfer_limit(
+            origin: OriginFor<T>,
+            chain_id: ChainId,
+            limit: Option<TransferLimit<BalanceOf<T>>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            TransferLimits::<T>::set(chain_id, limit);
+            Self::deposit_event(Event::<T>::TransferLimitUpdated { chain_id, limit });
+
+            Ok(())
+        }
+
+        /// Resume transfers with the chain that were paused by the circuit breaker.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::resume_transfers())]
+        pub fn resume_transfers(origin: OriginFor<T>, chain_id: ChainId) -> DispatchResult {
+            ensure_root(origin)?;
+
+            ensure!(
+                PausedChains::<T>::take(chain_id).is_some(),
+                Error::<T>::TransfersNotPaused
+            );
+            Self::deposit_event(Event::<T>::TransfersResumed { chain_id });
+
+            Ok(())
+        }
     }
 
     #[pallet::hooks]
     impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
         fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
             ChainTransfers::<T>::set(Default::default());
-            T::DbWeight::get().writes(1)
+            BlockTransferVolume::<T>::kill();
+            T::DbWeight::get().writes(2)
         }
     }
 
@@ -300,6 +411,54 @@ mod pallet {
             use frame_support::storage::generator::StorageValue;
             ChainTransfers::<T>::storage_value_final_key().to_vec()
         }
+
+        /// Check transfer against limits with the chain and account it in the block volume.
+        ///
+        /// Returns `false` if transfer exceeds the limits, in which case it is not accounted.
+        /// Fails if transfers with the chain are paused.
+        pub(crate) fn check_transfer_limit(
+            chain_id: ChainId,
+            amount: BalanceOf<T>,
+        ) -> Result<bool, Error<T>> {
+            ensure!(
+                !PausedChains::<T>::contains_key(chain_id),
+                Error::<T>::TransfersPaused
+            );
+
+            let Some(limit) = TransferLimits::<T>::get(chain_id) else {
+                return Ok(true);
+            };
+
+            if amount > limit.max_transfer {
+                return Ok(false);
+            }
+
+            BlockTransferVolume::<T>::try_mutate(|block_volume| {
+                let chain_volume = block_volume.entry(chain_id).or_default();
+                let new_chain_volume = chain_volume
+                    .checked_add(&amount)
+                    .ok_or(Error::<T>::BalanceOverflow)?;
+
+                if new_chain_volume > limit.max_block_volume {
+                    return Ok(false);
+                }
+
+                *chain_volume = new_chain_volume;
+                Ok(true)
+            })
+        }
+
+        /// Pause all transfers with the chain until governance resumes them.
+        pub(crate) fn trip_circuit_breaker(chain_id: ChainId) {
+            PausedChains::<T>::insert(chain_id, frame_system::Pallet::<T>::block_number());
+            Self::deposit_event(Event::<T>::TransfersPaused {
+                chain_id,
+                block_volume: BlockTransferVolume::<T>::get()
+                    .get(&chain_id)
+                    .copied()
+                    .unwrap_or_default(),
+            });
+        }
     }
 
     /// Endpoint handler implementation for pallet transporter.
@@ -349,7 +508,7 @@ mod pallet {
         }
 
         fn message_weight(&self) -> Weight {
-            T::WeightInfo::message()
+            T::WeightInfo::message().saturating_add(T::WeightInfo::transfer_limit_checks())
         }
 
         fn message_response(
@@ -567,6 +726,13 @@ impl<T: Config> Pallet<T> {
         message_id: MessageIdOf<T>,
         req: Transfer<BalanceOf<T>>,
     ) -> EndpointResponse {
+        // incoming transfers exceeding limits are a sign of compromised source chain, pause all
+        // transfers with it until the situation is resolved by governance
+        if !Self::check_transfer_limit(src_chain_id, req.amount)? {
+            Self::trip_circuit_breaker(src_chain_id);
+            return Err(Error::<T>::TransferLimitExceeded.into());
+        }
+
         // mint the funds to dst_account
         let account_id = T::AccountIdConverter::try_convert_back(req.receiver.account_id)
             .ok_or(Error::<T>::InvalidAccountId)?;
@@ -0,0 +1,70 @@
+//! Placeholder weights for pallet_transporter.
+//!
+//! Transfer limits and circuit breaker were added after weights in [`crate::weights`] were last
+//! generated. Values below are conservative estimates picked by hand, they were NOT measured.
+//! Benchmarks covering them exist, this module must be removed once weights are regenerated with
+//! `subspace-node benchmark pallet`.
+
+use crate::weights::SubstrateWeight;
+use frame_support::traits::Get;
+use frame_support::weights::constants::RocksDbWeight;
+use frame_support::weights::{RuntimeDbWeight, Weight};
+
+/// Weight functions of pallet_transporter that are not benchmarked yet.
+pub trait PlaceholderWeightInfo {
+    /// Transfer limit checks and block volume accounting performed on top of
+    /// [`WeightInfo::transfer()`](crate::weights::WeightInfo::transfer) and
+    /// [`WeightInfo::message()`](crate::weights::WeightInfo::message)
+    fn transfer_limit_checks() -> Weight;
+    /// Weight of `set_transfer_limit` call
+    fn set_transfer_limit() -> Weight;
+    /// Weight of `resume_transfers` call
+    fn resume_transfers() -> Weight;
+}
+
+/// Reads `PausedChains`, `TransferLimits` and `BlockTransferVolume`, writes `BlockTransferVolume`
+fn transfer_limit_checks<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+    Weight::from_parts(10_000_000, 3_000)
+        .saturating_add(DbWeight::get().reads(3))
+        .saturating_add(DbWeight::get().writes(1))
+}
+
+/// Writes `TransferLimits`
+fn set_transfer_limit<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+    Weight::from_parts(10_000_000, 0).saturating_add(DbWeight::get().writes(1))
+}
+
+/// Reads and writes `PausedChains`
+fn resume_transfers<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+    Weight::from_parts(15_000_000, 3_600)
+        .saturating_add(DbWeight::get().reads(1))
+        .saturating_add(DbWeight::get().writes(1))
+}
+
+impl<T: frame_system::Config> PlaceholderWeightInfo for SubstrateWeight<T> {
+    fn transfer_limit_checks() -> Weight {
+        transfer_limit_checks::<T::DbWeight>()
+    }
+
+    fn set_transfer_limit() -> Weight {
+        set_transfer_limit::<T::DbWeight>()
+    }
+
+    fn resume_transfers() -> Weight {
+        resume_transfers::<T::DbWeight>()
+    }
+}
+
+impl PlaceholderWeightInfo for () {
+    fn transfer_limit_checks() -> Weight {
+        transfer_limit_checks::<RocksDbWeight>()
+    }
+
+    fn set_transfer_limit() -> Weight {
+        set_transfer_limit::<RocksDbWeight>()
+    }
+
+    fn resume_transfers() -> Weight {
+        resume_transfers::<RocksDbWeight>()
+    }
+}
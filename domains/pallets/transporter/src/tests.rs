@@ -2,10 +2,11 @@ use crate::mock::{
     new_test_ext, AccountId, Balance, Balances, MockAccountIdConverter, MockRuntime, RuntimeEvent,
     RuntimeOrigin, SelfChainId, SelfEndpointId, System, Transporter, USER_ACCOUNT,
 };
-use crate::{EndpointHandler, Error, Location, Transfer};
+use crate::{EndpointHandler, Error, Location, Transfer, TransferLimit};
 use codec::Encode;
 use frame_support::dispatch::DispatchResult;
-use frame_support::{assert_err, assert_ok};
+use frame_support::traits::Hooks;
+use frame_support::{assert_err, assert_noop, assert_ok};
 use sp_messenger::endpoint::{
     Endpoint, EndpointHandler as EndpointHandlerT, EndpointRequest, EndpointResponse,
 };
@@ -319,3 +320,163 @@ fn test_receive_incoming_transfer() {
         assert_eq!(total_balance, 1500);
     })
 }
+
+fn incoming_transfer(src_chain_id: ChainId, receiver: AccountId, amount: Balance) -> Vec<u8> {
+    Transfer {
+        amount,
+        sender: Location {
+            chain_id: src_chain_id,
+            account_id: MockAccountIdConverter::convert(0),
+        },
+        receiver: Location {
+            chain_id: SelfChainId::get(),
+            account_id: MockAccountIdConverter::convert(receiver),
+        },
+    }
+    .encode()
+}
+
+#[test]
+fn test_outgoing_transfer_limits() {
+    new_test_ext().execute_with(|| {
+        let account = USER_ACCOUNT;
+        let dst_chain_id: ChainId = 1.into();
+        let dst_location = Location {
+            chain_id: dst_chain_id,
+            account_id: MockAccountIdConverter::convert(account),
+        };
+        let limit = TransferLimit {
+            max_transfer: 300,
+            max_block_volume: 500,
+        };
+
+        assert_noop!(
+            Transporter::set_transfer_limit(
+                RuntimeOrigin::signed(account),
+                dst_chain_id,
+                Some(limit)
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(Transporter::set_transfer_limit(
+            RuntimeOrigin::root(),
+            dst_chain_id,
+            Some(limit)
+        ));
+
+        // single transfer is too large
+        assert_noop!(
+            Transporter::transfer(RuntimeOrigin::signed(account), dst_location.clone(), 400),
+            Error::<MockRuntime>::TransferLimitExceeded
+        );
+
+        // block volume is exceeded, but outgoing transfers don't trip circuit breaker
+        assert_ok!(Transporter::transfer(
+            RuntimeOrigin::signed(account),
+            dst_location.clone(),
+            300
+        ));
+        assert_noop!(
+            Transporter::transfer(RuntimeOrigin::signed(account), dst_location.clone(), 300),
+            Error::<MockRuntime>::TransferLimitExceeded
+        );
+        assert_eq!(Transporter::paused_chains(dst_chain_id), None);
+
+        // volume is reset in the next block
+        System::set_block_number(2);
+        Transporter::on_initialize(2);
+        assert_ok!(Transporter::transfer(
+            RuntimeOrigin::signed(account),
+            dst_location.clone(),
+            300
+        ));
+
+        // removing limit allows any transfers
+        assert_ok!(Transporter::set_transfer_limit(
+            RuntimeOrigin::root(),
+            dst_chain_id,
+            None
+        ));
+        assert_ok!(Transporter::transfer(
+            RuntimeOrigin::signed(account),
+            dst_location,
+            400
+        ));
+        assert_eq!(Balances::free_balance(account), 0);
+    })
+}
+
+#[test]
+fn test_incoming_transfers_circuit_breaker() {
+    new_test_ext().execute_with(|| {
+        let receiver = 2;
+        let src_chain_id: ChainId = 100.into();
+        assert_ok!(Transporter::set_transfer_limit(
+            RuntimeOrigin::root(),
+            src_chain_id,
+            Some(TransferLimit {
+                max_transfer: 300,
+                max_block_volume: 500,
+            })
+        ));
+
+        assert_ok!(submit_transfer(
+            src_chain_id,
+            incoming_transfer(src_chain_id, receiver, 300)
+        ));
+        assert_eq!(Balances::free_balance(receiver), 300);
+
+        // exceeding block volume trips circuit breaker
+        assert_err!(
+            submit_transfer(src_chain_id, incoming_transfer(src_chain_id, receiver, 300)),
+            Error::<MockRuntime>::TransferLimitExceeded
+        );
+        assert_eq!(Balances::free_balance(receiver), 300);
+        assert_eq!(Transporter::paused_chains(src_chain_id), Some(1));
+        System::assert_has_event(RuntimeEvent::Transporter(
+            crate::Event::<MockRuntime>::TransfersPaused {
+                chain_id: src_chain_id,
+                block_volume: 300,
+            },
+        ));
+
+        // all transfers with paused chain are rejected, even in the following blocks
+        System::set_block_number(2);
+        Transporter::on_initialize(2);
+        assert_err!(
+            submit_transfer(src_chain_id, incoming_transfer(src_chain_id, receiver, 100)),
+            Error::<MockRuntime>::TransfersPaused
+        );
+        assert_noop!(
+            Transporter::transfer(
+                RuntimeOrigin::signed(USER_ACCOUNT),
+                Location {
+                    chain_id: src_chain_id,
+                    account_id: MockAccountIdConverter::convert(receiver),
+                },
+                100
+            ),
+            Error::<MockRuntime>::TransfersPaused
+        );
+
+        // only governance can resume transfers
+        assert_noop!(
+            Transporter::resume_transfers(RuntimeOrigin::signed(USER_ACCOUNT), src_chain_id),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(Transporter::resume_transfers(
+            RuntimeOrigin::root(),
+            src_chain_id
+        ));
+        assert_noop!(
+            Transporter::resume_transfers(RuntimeOrigin::root(), src_chain_id),
+            Error::<MockRuntime>::TransfersNotPaused
+        );
+
+        assert_ok!(submit_transfer(
+            src_chain_id,
+            incoming_transfer(src_chain_id, receiver, 100)
+        ));
+        assert_eq!(Balances::free_balance(receiver), 400);
+    })
+}
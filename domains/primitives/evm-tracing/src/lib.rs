@@ -0,0 +1,175 @@
+//! Primitives and runtime API for tracing transactions of EVM domains.
+//!
+//! Transactions are re-executed by the runtime on top of the parent block state with tracer
+//! attached to the EVM, trace size and number of traced steps are bounded so that tracing can't
+//! exhaust memory or CPU of the runtime or the node.
+//!
+//! Tracer is only available with `tracer` feature, which compiles tracing hooks into the EVM, hence
+//! it is only enabled in runtimes dedicated to tracing.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "tracer")]
+pub mod tracer;
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_core::{H160, H256, U256};
+use sp_std::collections::btree_map::BTreeMap;
+use sp_std::vec::Vec;
+
+/// Tracer to attach to the EVM.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Encode, Decode, TypeInfo)]
+pub enum TracerConfig {
+    /// Tree of calls and contract creations made by the transaction
+    CallTracer,
+    /// Log of every executed opcode (geth's default "struct logger")
+    StructLogger {
+        /// Don't capture storage accessed by `SLOAD`/`SSTORE`
+        disable_storage: bool,
+        /// Don't capture memory
+        disable_memory: bool,
+        /// Don't capture stack
+        disable_stack: bool,
+    },
+}
+
+/// Bounds of the tracing.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct TraceLimits {
+    /// Approximate size of the trace in bytes after which trace is truncated
+    pub max_size: u32,
+    /// Number of executed opcodes after which trace is truncated
+    pub max_steps: u32,
+}
+
+/// Type of the call frame.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum CallType {
+    /// `CALL` or top-level call of the transaction
+    Call,
+    /// `STATICCALL`
+    StaticCall,
+    /// `DELEGATECALL` or `CALLCODE`
+    DelegateCall,
+    /// `CREATE` or top-level contract creation
+    Create,
+    /// `CREATE2`
+    Create2,
+    /// `SELFDESTRUCT`
+    SelfDestruct,
+}
+
+/// Single call (or contract creation) made during transaction execution.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct CallFrame {
+    /// Type of the call
+    pub call_type: CallType,
+    /// Caller
+    pub from: H160,
+    /// Callee or created contract
+    pub to: H160,
+    /// Transferred value
+    pub value: U256,
+    /// Gas available to the call
+    pub gas: u64,
+    /// Gas used by the call
+    pub gas_used: u64,
+    /// Call input or init code
+    pub input: Vec<u8>,
+    /// Return value
+    pub output: Vec<u8>,
+    /// Error message if call did not succeed
+    pub error: Option<Vec<u8>>,
+    /// Nested calls
+    pub calls: Vec<CallFrame>,
+}
+
+/// Single executed opcode.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct StructLog {
+    /// Program counter
+    pub pc: u64,
+    /// Opcode
+    pub op: u8,
+    /// Gas remaining before executing the opcode
+    pub gas: u64,
+    /// Cost of the opcode
+    pub gas_cost: u64,
+    /// Call depth, starting from 1
+    pub depth: u32,
+    /// Stack before executing the opcode, if captured
+    pub stack: Option<Vec<H256>>,
+    /// Memory before executing the opcode, if captured
+    pub memory: Option<Vec<u8>>,
+    /// Storage of the contract accessed so far, present for `SLOAD`/`SSTORE` if captured
+    pub storage: Option<BTreeMap<H256, H256>>,
+}
+
+/// Output of the tracer.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum TraceOutput {
+    /// Output of [`TracerConfig::CallTracer`], `None` if transaction didn't reach the EVM
+    Call(Option<CallFrame>),
+    /// Output of [`TracerConfig::StructLogger`]
+    StructLogs {
+        /// Gas used by the transaction
+        gas: u64,
+        /// Whether transaction failed
+        failed: bool,
+        /// Return value of the transaction
+        return_value: Vec<u8>,
+        /// Executed opcodes
+        struct_logs: Vec<StructLog>,
+    },
+}
+
+/// Trace of a single transaction.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct TransactionTrace {
+    /// Hash of the Ethereum transaction
+    pub transaction_hash: H256,
+    /// Tracer output
+    pub output: TraceOutput,
+    /// Whether trace is incomplete because it exceeded limits
+    pub truncated: bool,
+}
+
+/// Error of the tracing runtime API.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum TracingError {
+    /// Runtime is built without tracer, runtime built with tracer needs to be used instead
+    TracerNotAvailable,
+}
+
+sp_api::decl_runtime_apis! {
+    /// API for tracing Ethereum transactions.
+    ///
+    /// Block must be initialized with `Core::initialize_block` in the same runtime API instance
+    /// beforehand, methods then apply extrinsics of the block in order.
+    ///
+    /// Methods return [`TracingError::TracerNotAvailable`] unless runtime is built with tracer.
+    pub trait EvmTracingApi {
+        /// Apply extrinsics until transaction with `transaction_hash` and trace it, `None` if
+        /// there is no such transaction among `extrinsics`.
+        ///
+        /// Trace is truncated after reaching `limits`.
+        fn trace_transaction(
+            extrinsics: Vec<Block::Extrinsic>,
+            transaction_hash: H256,
+            tracer: TracerConfig,
+            limits: TraceLimits,
+        ) -> Result<Option<TransactionTrace>, TracingError>;
+
+        /// Apply all extrinsics and trace every Ethereum transaction among them.
+        ///
+        /// `limits` apply to all traces combined, the last returned trace is truncated and the
+        /// rest of extrinsics is not applied once limits are reached.
+        fn trace_block(
+            extrinsics: Vec<Block::Extrinsic>,
+            tracer: TracerConfig,
+            limits: TraceLimits,
+        ) -> Result<Vec<TransactionTrace>, TracingError>;
+    }
+}
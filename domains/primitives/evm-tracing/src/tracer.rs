@@ -0,0 +1,508 @@
+//! Tracers attached to the EVM through its event listeners.
+//!
+//! EVM, its interpreter and gasometer each emit their own events, all of them are routed into a
+//! single shared tracer state, events are emitted sequentially during execution, so the latest
+//! gasometer snapshot always belongs to the currently executing frame.
+
+use crate::{CallFrame, CallType, StructLog, TraceLimits, TraceOutput, TracerConfig};
+use alloc::format;
+use evm::tracing::{Event as EvmEvent, EventListener as EvmEventListener};
+use evm::{CreateScheme, ExitReason, Opcode};
+use evm_gasometer::tracing::{
+    Event as GasometerEvent, EventListener as GasometerEventListener, Snapshot,
+};
+use evm_runtime::tracing::{Event as RuntimeEvent, EventListener as RuntimeEventListener};
+use sp_core::{H160, H256, U256};
+use sp_std::cell::RefCell;
+use sp_std::collections::btree_map::BTreeMap;
+use sp_std::rc::Rc;
+use sp_std::vec::Vec;
+
+/// Approximate size of the struct log or call frame excluding variable length data
+const ENTRY_OVERHEAD: usize = 64;
+
+/// Result of tracing.
+#[derive(Debug)]
+pub struct Trace {
+    /// Tracer output
+    pub output: TraceOutput,
+    /// Approximate size of the trace in bytes
+    pub size: u32,
+    /// Number of executed opcodes
+    pub steps: u32,
+    /// Whether trace is incomplete because it exceeded limits
+    pub truncated: bool,
+}
+
+/// Execute `f` with tracer according to `config` attached to the EVM.
+///
+/// Recording stops once trace reaches `limits`, execution itself is not affected by the tracer.
+pub fn trace<F>(config: TracerConfig, limits: TraceLimits, f: F) -> Trace
+where
+    F: FnOnce(),
+{
+    let tracer = match config {
+        TracerConfig::CallTracer => Tracer::Call(CallTracer::default()),
+        TracerConfig::StructLogger {
+            disable_storage,
+            disable_memory,
+            disable_stack,
+        } => Tracer::StructLogger(StructLogger {
+            disable_storage,
+            disable_memory,
+            disable_stack,
+            ..StructLogger::default()
+        }),
+    };
+    let state = Rc::new(RefCell::new(TracerState {
+        tracer,
+        limits: Limits {
+            remaining_size: limits.max_size as usize,
+            used_size: 0,
+            remaining_steps: limits.max_steps,
+            used_steps: 0,
+            exceeded: false,
+        },
+    }));
+
+    {
+        let mut evm_listener = Listener(Rc::clone(&state));
+        let mut runtime_listener = Listener(Rc::clone(&state));
+        let mut gasometer_listener = Listener(Rc::clone(&state));
+
+        evm::tracing::using(&mut evm_listener, || {
+            evm_runtime::tracing::using(&mut runtime_listener, || {
+                evm_gasometer::tracing::using(&mut gasometer_listener, f)
+            })
+        });
+    }
+
+    let TracerState { tracer, limits } = Rc::try_unwrap(state)
+        .map(RefCell::into_inner)
+        .ok()
+        .expect("Listeners were dropped above; qed");
+
+    Trace {
+        output: match tracer {
+            Tracer::Call(tracer) => TraceOutput::Call(tracer.result),
+            Tracer::StructLogger(tracer) => TraceOutput::StructLogs {
+                gas: tracer.gas_used,
+                failed: tracer.failed,
+                return_value: tracer.return_value,
+                struct_logs: tracer.struct_logs,
+            },
+        },
+        size: limits.used_size as u32,
+        steps: limits.used_steps,
+        truncated: limits.exceeded,
+    }
+}
+
+struct Limits {
+    remaining_size: usize,
+    used_size: usize,
+    remaining_steps: u32,
+    used_steps: u32,
+    exceeded: bool,
+}
+
+impl Limits {
+    /// Account for `size` more bytes of the trace, returns `false` if limits are exceeded, in which
+    /// case nothing should be recorded anymore
+    fn consume(&mut self, size: usize) -> bool {
+        if self.exceeded || size > self.remaining_size {
+            self.exceeded = true;
+            return false;
+        }
+
+        self.remaining_size -= size;
+        self.used_size += size;
+        true
+    }
+
+    /// Account for one more executed opcode, limits become exceeded once there are no steps left
+    fn step(&mut self) {
+        self.used_steps = self.used_steps.saturating_add(1);
+        match self.remaining_steps.checked_sub(1) {
+            Some(remaining_steps) => {
+                self.remaining_steps = remaining_steps;
+            }
+            None => {
+                self.exceeded = true;
+            }
+        }
+    }
+}
+
+enum Tracer {
+    Call(CallTracer),
+    StructLogger(StructLogger),
+}
+
+struct TracerState {
+    tracer: Tracer,
+    limits: Limits,
+}
+
+struct Listener(Rc<RefCell<TracerState>>);
+
+impl EvmEventListener for Listener {
+    fn event(&mut self, event: EvmEvent<'_>) {
+        let TracerState { tracer, limits } = &mut *self.0.borrow_mut();
+        match tracer {
+            Tracer::Call(tracer) => tracer.evm_event(event, limits),
+            Tracer::StructLogger(tracer) => tracer.evm_event(event),
+        }
+    }
+}
+
+impl RuntimeEventListener for Listener {
+    fn event(&mut self, event: RuntimeEvent<'_>) {
+        let TracerState { tracer, limits } = &mut *self.0.borrow_mut();
+        // Steps are counted for every tracer, such that tracing of long-running transactions stops
+        // even if nothing is recorded for them
+        if matches!(event, RuntimeEvent::Step { .. }) {
+            limits.step();
+        }
+        if let Tracer::StructLogger(tracer) = tracer {
+            tracer.runtime_event(event, limits);
+        }
+    }
+}
+
+impl GasometerEventListener for Listener {
+    fn event(&mut self, event: GasometerEvent) {
+        let TracerState { tracer, .. } = &mut *self.0.borrow_mut();
+        match tracer {
+            Tracer::Call(tracer) => {
+                if let Some(snapshot) = gasometer_snapshot(&event) {
+                    tracer.used_gas = snapshot.used_gas;
+                }
+            }
+            Tracer::StructLogger(tracer) => tracer.gasometer_event(event),
+        }
+    }
+}
+
+fn gasometer_snapshot(event: &GasometerEvent) -> Option<Snapshot> {
+    match event {
+        GasometerEvent::RecordCost { snapshot, .. }
+        | GasometerEvent::RecordRefund { snapshot, .. }
+        | GasometerEvent::RecordStipend { snapshot, .. }
+        | GasometerEvent::RecordDynamicCost { snapshot, .. }
+        | GasometerEvent::RecordTransaction { snapshot, .. } => *snapshot,
+    }
+}
+
+fn error_message(reason: &ExitReason) -> Option<Vec<u8>> {
+    match reason {
+        ExitReason::Succeed(_) => None,
+        // Same message as in geth
+        ExitReason::Revert(_) => Some(b"execution reverted".to_vec()),
+        ExitReason::Error(error) => Some(format!("{error:?}").into_bytes()),
+        ExitReason::Fatal(error) => Some(format!("{error:?}").into_bytes()),
+    }
+}
+
+#[derive(Default)]
+struct CallTracer {
+    /// Frames that are currently executing, the first one is the top-level call
+    frames: Vec<CallFrame>,
+    /// Number of currently executing frames that were not recorded due to limits
+    skipped_frames: usize,
+    /// Gas used according to the latest gasometer snapshot
+    used_gas: u64,
+    /// Top-level frame once it has finished
+    result: Option<CallFrame>,
+}
+
+impl CallTracer {
+    fn evm_event(&mut self, event: EvmEvent<'_>, limits: &mut Limits) {
+        match event {
+            EvmEvent::Call {
+                code_address,
+                transfer,
+                input,
+                target_gas,
+                is_static,
+                context,
+            }
+            | EvmEvent::PrecompileSubcall {
+                code_address,
+                transfer,
+                input,
+                target_gas,
+                is_static,
+                context,
+            } => {
+                let (call_type, from, to) = if is_static {
+                    (CallType::StaticCall, context.caller, context.address)
+                } else if code_address != context.address {
+                    // Code of another contract is executed in the context of the caller
+                    (CallType::DelegateCall, context.address, code_address)
+                } else {
+                    (CallType::Call, context.caller, context.address)
+                };
+                let value = transfer
+                    .as_ref()
+                    .map_or_else(U256::zero, |transfer| transfer.value);
+
+                self.enter(
+                    call_type,
+                    from,
+                    to,
+                    value,
+                    target_gas.unwrap_or_default(),
+                    input,
+                    limits,
+                );
+            }
+            EvmEvent::Create {
+                caller,
+                address,
+                scheme,
+                value,
+                init_code,
+                target_gas,
+            } => {
+                let call_type = match scheme {
+                    CreateScheme::Create2 { .. } => CallType::Create2,
+                    CreateScheme::Legacy { .. } | CreateScheme::Fixed(_) => CallType::Create,
+                };
+
+                self.enter(
+                    call_type,
+                    caller,
+                    address,
+                    value,
+                    target_gas.unwrap_or_default(),
+                    init_code,
+                    limits,
+                );
+            }
+            EvmEvent::Suicide {
+                address,
+                target,
+                balance,
+            } => {
+                if self.skipped_frames > 0 || !limits.consume(ENTRY_OVERHEAD) {
+                    return;
+                }
+
+                if let Some(frame) = self.frames.last_mut() {
+                    frame.calls.push(CallFrame {
+                        call_type: CallType::SelfDestruct,
+                        from: address,
+                        to: target,
+                        value: balance,
+                        gas: 0,
+                        gas_used: 0,
+                        input: Vec::new(),
+                        output: Vec::new(),
+                        error: None,
+                        calls: Vec::new(),
+                    });
+                }
+            }
+            EvmEvent::Exit {
+                reason,
+                return_value,
+            } => {
+                if self.skipped_frames > 0 {
+                    self.skipped_frames -= 1;
+                    return;
+                }
+
+                let Some(mut frame) = self.frames.pop() else {
+                    return;
+                };
+                frame.gas_used = self.used_gas;
+                frame.error = error_message(reason);
+                if limits.consume(return_value.len()) {
+                    frame.output = return_value.to_vec();
+                }
+
+                match self.frames.last_mut() {
+                    Some(parent) => parent.calls.push(frame),
+                    None => self.result = Some(frame),
+                }
+            }
+            EvmEvent::TransactCall { .. }
+            | EvmEvent::TransactCreate { .. }
+            | EvmEvent::TransactCreate2 { .. } => {
+                // Top-level frame is created by the call or create event that follows
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn enter(
+        &mut self,
+        call_type: CallType,
+        from: H160,
+        to: H160,
+        value: U256,
+        gas: u64,
+        input: &[u8],
+        limits: &mut Limits,
+    ) {
+        if self.skipped_frames > 0 || !limits.consume(ENTRY_OVERHEAD + input.len()) {
+            self.skipped_frames += 1;
+            return;
+        }
+
+        self.frames.push(CallFrame {
+            call_type,
+            from,
+            to,
+            value,
+            gas,
+            gas_used: 0,
+            input: input.to_vec(),
+            output: Vec::new(),
+            error: None,
+            calls: Vec::new(),
+        });
+    }
+}
+
+#[derive(Default)]
+struct StructLogger {
+    disable_storage: bool,
+    disable_memory: bool,
+    disable_stack: bool,
+    struct_logs: Vec<StructLog>,
+    /// Storage accessed so far by each of the currently executing frames
+    storage: Vec<BTreeMap<H256, H256>>,
+    /// Gas remaining according to the latest gasometer snapshot
+    gas: u64,
+    /// Gas used according to the latest gasometer snapshot
+    gas_used: u64,
+    /// Memory gas according to the latest gasometer snapshot
+    memory_gas: u64,
+    /// Whether the last struct log is still waiting for its gas cost to be recorded
+    pending_cost: bool,
+    failed: bool,
+    return_value: Vec<u8>,
+}
+
+impl StructLogger {
+    fn evm_event(&mut self, event: EvmEvent<'_>) {
+        match event {
+            EvmEvent::Call { target_gas, .. }
+            | EvmEvent::PrecompileSubcall { target_gas, .. }
+            | EvmEvent::Create { target_gas, .. } => {
+                self.storage.push(BTreeMap::new());
+                if let Some(target_gas) = target_gas {
+                    self.gas = target_gas;
+                }
+                self.pending_cost = false;
+            }
+            EvmEvent::Exit {
+                reason,
+                return_value,
+            } => {
+                self.storage.pop();
+                self.pending_cost = false;
+                if self.storage.is_empty() {
+                    self.failed = !reason.is_succeed();
+                    self.return_value = return_value.to_vec();
+                }
+            }
+            EvmEvent::Suicide { .. }
+            | EvmEvent::TransactCall { .. }
+            | EvmEvent::TransactCreate { .. }
+            | EvmEvent::TransactCreate2 { .. } => {}
+        }
+    }
+
+    fn runtime_event(&mut self, event: RuntimeEvent<'_>, limits: &mut Limits) {
+        match event {
+            RuntimeEvent::Step {
+                opcode,
+                position,
+                stack,
+                memory,
+                ..
+            } => {
+                self.pending_cost = false;
+
+                let stack = (!self.disable_stack).then(|| stack.data().clone());
+                let memory = (!self.disable_memory).then(|| memory.data().clone());
+                let size = ENTRY_OVERHEAD
+                    + stack
+                        .as_ref()
+                        .map_or(0, |stack| stack.len() * H256::len_bytes())
+                    + memory.as_ref().map_or(0, Vec::len);
+                if !limits.consume(size) {
+                    return;
+                }
+
+                self.struct_logs.push(StructLog {
+                    pc: position.as_ref().map_or(0, |&pc| pc as u64),
+                    op: opcode.0,
+                    gas: self.gas,
+                    gas_cost: 0,
+                    depth: self.storage.len() as u32,
+                    stack,
+                    memory,
+                    storage: None,
+                });
+                self.pending_cost = true;
+            }
+            RuntimeEvent::SLoad { index, value, .. }
+            | RuntimeEvent::SStore { index, value, .. } => {
+                if self.disable_storage {
+                    return;
+                }
+                let Some(storage) = self.storage.last_mut() else {
+                    return;
+                };
+                storage.insert(index, value);
+
+                let depth = self.storage.len() as u32;
+                let Some(struct_log) = self.struct_logs.last_mut() else {
+                    return;
+                };
+                let is_storage_access =
+                    struct_log.op == Opcode::SLOAD.0 || struct_log.op == Opcode::SSTORE.0;
+                if struct_log.depth == depth
+                    && is_storage_access
+                    && limits.consume(storage.len() * H256::len_bytes() * 2)
+                {
+                    struct_log.storage = Some(storage.clone());
+                }
+            }
+            RuntimeEvent::StepResult { .. } => {}
+        }
+    }
+
+    fn gasometer_event(&mut self, event: GasometerEvent) {
+        let cost = match &event {
+            GasometerEvent::RecordCost { cost, .. } => Some(*cost),
+            GasometerEvent::RecordDynamicCost {
+                gas_cost,
+                memory_gas,
+                ..
+            } => Some(gas_cost + memory_gas.saturating_sub(self.memory_gas)),
+            GasometerEvent::RecordRefund { .. }
+            | GasometerEvent::RecordStipend { .. }
+            | GasometerEvent::RecordTransaction { .. } => None,
+        };
+
+        if let Some(cost) = cost {
+            if self.pending_cost {
+                if let Some(struct_log) = self.struct_logs.last_mut() {
+                    struct_log.gas_cost = cost;
+                }
+                self.pending_cost = false;
+            }
+        }
+
+        if let Some(snapshot) = gasometer_snapshot(&event) {
+            self.gas = snapshot.gas_limit.saturating_sub(snapshot.used_gas);
+            self.gas_used = snapshot.used_gas;
+            self.memory_gas = snapshot.memory_gas;
+        }
+    }
+}
@@ -21,7 +21,7 @@ pub mod endpoint;
 pub mod messages;
 
 use codec::{Decode, Encode};
-use messages::{BlockMessagesWithStorageKey, CrossDomainMessage, MessageId};
+use messages::{BlockMessagesWithStorageKey, CrossDomainMessage, DeadLetter, MessageId};
 use sp_domains::{ChainId, DomainId};
 use sp_mmr_primitives::{EncodableOpaqueLeaf, Proof};
 use sp_std::vec::Vec;
@@ -113,6 +113,7 @@ sp_api::decl_runtime_apis! {
     }
 
     /// Api to provide XDM extraction from Runtime Calls.
+    #[api_version(2)]
     pub trait MessengerApi<BlockNumber> where BlockNumber: Encode + Decode{
         /// Returns `Some(true)` if valid XDM or `Some(false)` if not
         /// Returns None if this is not an XDM
@@ -129,5 +130,10 @@ sp_api::decl_runtime_apis! {
 
         /// Returns storage key for inbox response for a given message_id.
         fn inbox_response_storage_key(message_id: MessageId) -> Vec<u8>;
+
+        /// Returns inbound messages that failed to be handled and are waiting to be retried or
+        /// discarded, together with the source chain and message id.
+        #[api_version(2)]
+        fn dead_letters() -> Vec<(ChainId, MessageId, DeadLetter<BlockNumber>)>;
     }
 }
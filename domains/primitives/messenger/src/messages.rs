@@ -32,6 +32,15 @@ pub struct InitiateChannelParams<Balance> {
     pub fee_model: FeeModel<Balance>,
 }
 
+/// Update to the list of chains a chain accepts channels from.
+#[derive(Debug, Encode, Decode, Clone, Copy, Eq, PartialEq, TypeInfo)]
+pub enum ChainAllowlistUpdate {
+    /// Allow channels from the given chain.
+    Add(ChainId),
+    /// Stop accepting new channels from the given chain.
+    Remove(ChainId),
+}
+
 /// Defines protocol requests performed on chains.
 #[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
 pub enum ProtocolMessageRequest<Balance> {
@@ -39,6 +48,8 @@ pub enum ProtocolMessageRequest<Balance> {
     ChannelOpen(InitiateChannelParams<Balance>),
     /// Request to close an open channel with foreign chain.
     ChannelClose,
+    /// Notification of a domain's chain allowlist update, sent to the consensus chain.
+    ChainAllowlistUpdate(ChainAllowlistUpdate),
 }
 
 /// Defines protocol requests performed on chains.
@@ -75,6 +86,7 @@ pub enum MessageWeightTag {
     EndpointResponse(Endpoint),
     #[default]
     None,
+    ProtocolChainAllowlistUpdate,
 }
 
 impl MessageWeightTag {
@@ -87,6 +99,9 @@ impl MessageWeightTag {
             VersionedPayload::V0(Payload::Protocol(RequestResponse::Request(
                 ProtocolMessageRequest::ChannelClose,
             ))) => MessageWeightTag::ProtocolChannelClose,
+            VersionedPayload::V0(Payload::Protocol(RequestResponse::Request(
+                ProtocolMessageRequest::ChainAllowlistUpdate(_),
+            ))) => MessageWeightTag::ProtocolChainAllowlistUpdate,
             VersionedPayload::V0(Payload::Endpoint(RequestResponse::Request(endpoint_req))) => {
                 MessageWeightTag::EndpointRequest(endpoint_req.dst_endpoint.clone())
             }
@@ -131,6 +146,28 @@ pub struct Message<Balance> {
     pub last_delivered_message_response_nonce: Option<Nonce>,
 }
 
+/// Reason an inbound endpoint message was moved to the dead-letter queue.
+#[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+pub enum DeadLetterReason {
+    /// There is no handler for the destination endpoint.
+    NoMessageHandler,
+    /// Destination endpoint handler returned an error.
+    HandlerFailed(DispatchError),
+}
+
+/// Inbound endpoint message that could not be handled on this chain.
+///
+/// Dead letters are kept until they are either retried successfully or discarded by governance.
+#[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+pub struct DeadLetter<BlockNumber> {
+    /// Request of the failed message.
+    pub request: EndpointRequest,
+    /// Reason of the most recent failure.
+    pub reason: DeadLetterReason,
+    /// Block number at which the most recent failure happened.
+    pub failed_at: BlockNumber,
+}
+
 /// Consensus chain MMR leaf and its Proof at specific block
 #[derive(Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
 pub struct ConsensusChainMmrLeafProof<BlockHash, MmrHash> {
@@ -25,13 +25,14 @@ use frame_support::dispatch::{DispatchClass, DispatchInfo, GetDispatchInfo};
 use frame_support::inherent::ProvideInherent;
 use frame_support::traits::fungible::Credit;
 use frame_support::traits::{
-    ConstU16, ConstU32, ConstU64, Currency, Everything, FindAuthor, Imbalance, OnFinalize,
-    OnUnbalanced,
+    ConstU16, ConstU32, ConstU64, Currency, EitherOfDiverse, Everything, FindAuthor, Imbalance,
+    OnFinalize, OnUnbalanced, SortedMembers,
 };
 use frame_support::weights::constants::{ParityDbWeight, WEIGHT_REF_TIME_PER_SECOND};
 use frame_support::weights::{ConstantMultiplier, IdentityFee, Weight};
 use frame_support::{construct_runtime, parameter_types};
 use frame_system::limits::{BlockLength, BlockWeights};
+use frame_system::{EnsureRoot, EnsureSignedBy};
 use pallet_block_fees::fees::OnChargeDomainTransaction;
 use pallet_ethereum::Call::transact;
 use pallet_ethereum::{PostLogContent, Transaction as EthereumTransaction, TransactionStatus};
@@ -45,7 +46,9 @@ use sp_core::crypto::KeyTypeId;
 use sp_core::{Get, OpaqueMetadata, H160, H256, U256};
 use sp_domains::{DomainId, Transfers};
 use sp_messenger::endpoint::{Endpoint, EndpointHandler as EndpointHandlerT, EndpointId};
-use sp_messenger::messages::{BlockMessagesWithStorageKey, ChainId, CrossDomainMessage, MessageId};
+use sp_messenger::messages::{
+    BlockMessagesWithStorageKey, ChainId, CrossDomainMessage, DeadLetter, MessageId,
+};
 use sp_messenger_host_functions::{get_storage_key, StorageKeyRequest};
 use sp_mmr_primitives::{EncodableOpaqueLeaf, Proof};
 use sp_runtime::generic::Era;
@@ -398,6 +401,7 @@ impl pallet_sudo::Config for Runtime {
 
 parameter_types! {
     pub const RelayConfirmationDepth: BlockNumber = 18;
+    pub const MaxDeadLetters: u32 = 100;
     pub SelfChainId: ChainId = SelfDomainId::self_domain_id().into();
 }
 
@@ -448,6 +452,16 @@ impl sp_messenger::StorageKeys for StorageKeys {
     }
 }
 
+/// Domain governance, i.e. the sudo account of the domain, which manages domain specific
+/// settings like the messenger chain allowlist without going through the consensus chain.
+pub struct DomainGovernance;
+
+impl SortedMembers<AccountId> for DomainGovernance {
+    fn sorted_members() -> Vec<AccountId> {
+        Sudo::key().into_iter().collect()
+    }
+}
+
 impl pallet_messenger::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type SelfChainId = SelfChainId;
@@ -468,6 +482,10 @@ impl pallet_messenger::Config for Runtime {
     type MmrHash = MmrHash;
     type MmrProofVerifier = MmrProofVerifier;
     type StorageKeys = StorageKeys;
+    type ChainAllowlistOrigin =
+        EitherOfDiverse<EnsureRoot<AccountId>, EnsureSignedBy<DomainGovernance, AccountId>>;
+    type DeadLetterOrigin = EnsureRoot<AccountId>;
+    type MaxDeadLetters = MaxDeadLetters;
 }
 
 impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
@@ -765,6 +783,8 @@ mod benches {
         [frame_benchmarking, BaselineBench::<Runtime>]
         [frame_system, SystemBench::<Runtime>]
         [domain_pallet_executive, ExecutivePallet]
+        [pallet_messenger, Messenger]
+        [pallet_transporter, Transporter]
     );
 }
 
@@ -804,6 +824,79 @@ fn check_transaction_and_do_pre_dispatch_inner(
     }
 }
 
+/// Hash of the Ethereum transaction if extrinsic is one
+#[cfg(feature = "evm-tracing")]
+fn ethereum_transaction_hash(extrinsic: &<Block as BlockT>::Extrinsic) -> Option<H256> {
+    match &extrinsic.0.function {
+        RuntimeCall::Ethereum(transact { transaction }) => Some(transaction.hash()),
+        _ => None,
+    }
+}
+
+/// Apply extrinsics until Ethereum transaction with `transaction_hash` and trace it
+#[cfg(feature = "evm-tracing")]
+fn trace_transaction(
+    extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+    transaction_hash: H256,
+    tracer: sp_evm_tracing::TracerConfig,
+    limits: sp_evm_tracing::TraceLimits,
+) -> Option<sp_evm_tracing::TransactionTrace> {
+    for extrinsic in extrinsics {
+        if ethereum_transaction_hash(&extrinsic) == Some(transaction_hash) {
+            let trace = sp_evm_tracing::tracer::trace(tracer, limits, || {
+                let _ = Executive::apply_extrinsic(extrinsic);
+            });
+
+            return Some(sp_evm_tracing::TransactionTrace {
+                transaction_hash,
+                output: trace.output,
+                truncated: trace.truncated,
+            });
+        }
+
+        let _ = Executive::apply_extrinsic(extrinsic);
+    }
+
+    None
+}
+
+/// Apply all extrinsics and trace every Ethereum transaction among them
+#[cfg(feature = "evm-tracing")]
+fn trace_block(
+    extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+    tracer: sp_evm_tracing::TracerConfig,
+    limits: sp_evm_tracing::TraceLimits,
+) -> Vec<sp_evm_tracing::TransactionTrace> {
+    let mut remaining_limits = limits;
+    let mut traces = Vec::new();
+
+    for extrinsic in extrinsics {
+        let Some(transaction_hash) = ethereum_transaction_hash(&extrinsic) else {
+            let _ = Executive::apply_extrinsic(extrinsic);
+            continue;
+        };
+
+        let trace = sp_evm_tracing::tracer::trace(tracer, remaining_limits, || {
+            let _ = Executive::apply_extrinsic(extrinsic);
+        });
+        remaining_limits.max_size = remaining_limits.max_size.saturating_sub(trace.size);
+        remaining_limits.max_steps = remaining_limits.max_steps.saturating_sub(trace.steps);
+
+        traces.push(sp_evm_tracing::TransactionTrace {
+            transaction_hash,
+            output: trace.output,
+            truncated: trace.truncated,
+        });
+
+        // Block trace is incomplete anyway, no need to execute the rest of the block
+        if trace.truncated {
+            break;
+        }
+    }
+
+    traces
+}
+
 impl_runtime_apis! {
     impl sp_api::Core<Block> for Runtime {
         fn version() -> RuntimeVersion {
@@ -1058,6 +1151,10 @@ impl_runtime_apis! {
         fn inbox_response_storage_key(message_id: MessageId) -> Vec<u8> {
             Messenger::inbox_response_storage_key(message_id)
         }
+
+        fn dead_letters() -> Vec<(ChainId, MessageId, DeadLetter<BlockNumber>)> {
+            Messenger::dead_letters()
+        }
     }
 
     impl sp_messenger::RelayerApi<Block, BlockNumber> for Runtime {
@@ -1266,6 +1363,39 @@ impl_runtime_apis! {
         }
     }
 
+    impl sp_evm_tracing::EvmTracingApi<Block> for Runtime {
+        fn trace_transaction(
+            extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+            transaction_hash: H256,
+            tracer: sp_evm_tracing::TracerConfig,
+            limits: sp_evm_tracing::TraceLimits,
+        ) -> Result<Option<sp_evm_tracing::TransactionTrace>, sp_evm_tracing::TracingError> {
+            #[cfg(feature = "evm-tracing")]
+            return Ok(trace_transaction(extrinsics, transaction_hash, tracer, limits));
+
+            #[cfg(not(feature = "evm-tracing"))]
+            {
+                let _ = (extrinsics, transaction_hash, tracer, limits);
+                Err(sp_evm_tracing::TracingError::TracerNotAvailable)
+            }
+        }
+
+        fn trace_block(
+            extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+            tracer: sp_evm_tracing::TracerConfig,
+            limits: sp_evm_tracing::TraceLimits,
+        ) -> Result<Vec<sp_evm_tracing::TransactionTrace>, sp_evm_tracing::TracingError> {
+            #[cfg(feature = "evm-tracing")]
+            return Ok(trace_block(extrinsics, tracer, limits));
+
+            #[cfg(not(feature = "evm-tracing"))]
+            {
+                let _ = (extrinsics, tracer, limits);
+                Err(sp_evm_tracing::TracingError::TracerNotAvailable)
+            }
+        }
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     impl frame_benchmarking::Benchmark<Block> for Runtime {
         fn benchmark_metadata(extra: bool) -> (
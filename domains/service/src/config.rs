@@ -96,6 +96,8 @@ pub struct SubstrateConfiguration {
     pub chain_spec: Box<dyn ChainSpec>,
     /// Configuration of the output format that the informant uses.
     pub informant_output_format: sc_informant::OutputFormat,
+    /// Directory with runtimes that override on-chain runtimes with the same spec version
+    pub wasm_runtime_overrides: Option<PathBuf>,
 }
 
 impl From<SubstrateConfiguration> for Configuration {
@@ -157,7 +159,7 @@ impl From<SubstrateConfiguration> for Configuration {
             state_pruning: configuration.state_pruning,
             blocks_pruning: configuration.blocks_pruning,
             wasm_method: Default::default(),
-            wasm_runtime_overrides: None,
+            wasm_runtime_overrides: configuration.wasm_runtime_overrides,
             rpc_addr: Some(configuration.rpc_options.listen_on),
             rpc_methods: configuration.rpc_options.methods,
             rpc_max_connections: configuration.rpc_options.max_connections,
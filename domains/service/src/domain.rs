@@ -4,7 +4,9 @@ use crate::{FullBackend, FullClient};
 use cross_domain_message_gossip::ChainTxPoolMsg;
 use domain_client_block_preprocessor::inherents::CreateInherentDataProvider;
 use domain_client_message_relayer::GossipMessageSink;
-use domain_client_operator::{Operator, OperatorParams, OperatorStreams};
+use domain_client_operator::{
+    BundleElectionAudit, ExternalBundles, Operator, OperatorParams, OperatorStreams,
+};
 use domain_runtime_primitives::opaque::{Block, Header};
 use domain_runtime_primitives::{Balance, Hash};
 use futures::channel::mpsc;
@@ -361,6 +363,8 @@ where
     let is_authority = domain_config.role.is_authority();
     let domain_state_pruning = domain_config.state_pruning.clone().unwrap_or_default();
     domain_config.rpc_id_provider = provider.rpc_id();
+    let bundle_election_audit = BundleElectionAudit::default();
+    let external_bundles = ExternalBundles::default();
     let rpc_builder = {
         let deps = crate::rpc::FullDeps {
             client: client.clone(),
@@ -385,6 +389,9 @@ where
                 None,
                 domain_id,
             ),
+            domain_id,
+            bundle_election_audit: bundle_election_audit.clone(),
+            external_bundles: external_bundles.clone(),
         };
 
         let spawn_essential = task_manager.spawn_essential_handle();
@@ -446,6 +453,8 @@ where
             domain_confirmation_depth,
             block_import,
             skip_empty_bundle_production,
+            bundle_election_audit,
+            external_bundles,
         },
     )
     .await?;
@@ -13,7 +13,8 @@ use sp_api::{ApiExt, ConstructRuntimeApi, Core, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_core::traits::SpawnEssentialNamed;
-use sp_runtime::traits::Block as BlockT;
+use sp_messenger::MessengerApi;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
 use std::error::Error;
 use std::fmt::{Debug, Display};
 use std::sync::Arc;
@@ -89,7 +90,8 @@ where
         + 'static,
     Client::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>
         + AccountNonceApi<Block, AccountId, Nonce>
-        + BlockBuilder<Block>,
+        + BlockBuilder<Block>
+        + MessengerApi<Block, NumberFor<Block>>,
     TxPool: TransactionPool<Block = Block> + Sync + Send + 'static,
     CA: ChainApi<Block = Block> + 'static,
     BE: Backend<Block> + 'static,
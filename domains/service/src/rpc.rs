@@ -5,6 +5,18 @@
 
 #![warn(missing_docs)]
 
+mod bundle_election;
+mod dead_letters;
+mod external_bundle;
+
+pub use self::bundle_election::{
+    BundleElection, BundleElectionApiServer, ElectionInfo, ElectionOutcome, ElectionStats,
+};
+pub use self::dead_letters::{
+    DeadLetterFailure, DeadLetterInfo, DeadLetters, DeadLettersApiServer,
+};
+pub use self::external_bundle::{ExternalBundleApiServer, ExternalBundleRpc, ExternalBundleStatus};
+use domain_client_operator::{BundleElectionAudit, ExternalBundles};
 use domain_runtime_primitives::{Balance, Nonce};
 use jsonrpsee::RpcModule;
 use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
@@ -21,7 +33,9 @@ use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_core::{Decode, Encode};
-use sp_runtime::traits::Block as BlockT;
+use sp_domains::DomainId;
+use sp_messenger::MessengerApi;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
 use std::fmt::{Debug, Display};
 use std::sync::Arc;
 use substrate_frame_rpc_system::{System, SystemApiServer};
@@ -55,6 +69,12 @@ pub struct FullDeps<Block: BlockT, Client, TP, CA: ChainApi, BE, CIDP> {
     pub task_spawner: SpawnTaskHandle,
     /// Create inherent data provider
     pub create_inherent_data_provider: CIDP,
+    /// Domain the node is running
+    pub domain_id: DomainId,
+    /// Audit trail of the bundle producer election of the local operator
+    pub bundle_election_audit: BundleElectionAudit,
+    /// Bundles constructed by external builders for the local operator
+    pub external_bundles: ExternalBundles,
 }
 
 impl<Block: BlockT, Client, TP, CA: ChainApi, BE, CIDP: Clone> Clone
@@ -75,6 +95,9 @@ impl<Block: BlockT, Client, TP, CA: ChainApi, BE, CIDP: Clone> Clone
             prometheus_registry: self.prometheus_registry.clone(),
             database_source: self.database_source.clone(),
             create_inherent_data_provider: self.create_inherent_data_provider.clone(),
+            domain_id: self.domain_id,
+            bundle_election_audit: self.bundle_election_audit.clone(),
+            external_bundles: self.external_bundles.clone(),
         }
     }
 }
@@ -95,7 +118,8 @@ where
         + 'static,
     Client::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>
         + substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>
-        + BlockBuilder<Block>,
+        + BlockBuilder<Block>
+        + MessengerApi<Block, NumberFor<Block>>,
     P: TransactionPool + Sync + Send + 'static,
     CA: ChainApi,
     AccountId: DeserializeOwned + Encode + Debug + Decode + Display + Clone + Sync + Send + 'static,
@@ -106,6 +130,9 @@ where
         pool,
         chain_spec,
         deny_unsafe,
+        domain_id,
+        bundle_election_audit,
+        external_bundles,
         ..
     } = deps;
 
@@ -115,7 +142,12 @@ where
     module.merge(ChainSpec::new(chain_name, genesis_hash, properties).into_rpc())?;
 
     module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
-    module.merge(TransactionPayment::new(client).into_rpc())?;
+    module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+    module.merge(BundleElection::new(domain_id, bundle_election_audit, deny_unsafe).into_rpc())?;
+    module.merge(
+        ExternalBundleRpc::<Block>::new(domain_id, external_bundles, deny_unsafe).into_rpc(),
+    )?;
+    module.merge(DeadLetters::new(client).into_rpc())?;
 
     Ok(module)
 }
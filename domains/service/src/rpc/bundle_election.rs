@@ -0,0 +1,202 @@
+//! RPC for auditing the bundle producer election of the local operator.
+
+use domain_client_operator::{
+    BundleElectionAudit, BundleElectionOutcome, BundleElectionRecord, BundleElectionStats,
+};
+use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
+use jsonrpsee::proc_macros::rpc;
+use sc_rpc::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use sp_domains::{DomainId, OperatorId};
+use subspace_runtime_primitives::Balance;
+
+/// Outcome of the bundle producer election in a slot.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ElectionOutcome {
+    /// Operator was elected to produce a bundle
+    Elected,
+    /// VRF output was not below the threshold
+    NotElected,
+    /// Operator is not registered according to the consensus runtime
+    OperatorNotRegistered,
+    /// Operator signing key is not available in the keystore
+    SigningKeyUnavailable,
+}
+
+impl From<BundleElectionOutcome> for ElectionOutcome {
+    fn from(outcome: BundleElectionOutcome) -> Self {
+        match outcome {
+            BundleElectionOutcome::Elected => Self::Elected,
+            BundleElectionOutcome::NotElected => Self::NotElected,
+            BundleElectionOutcome::OperatorNotRegistered => Self::OperatorNotRegistered,
+            BundleElectionOutcome::SigningKeyUnavailable => Self::SigningKeyUnavailable,
+        }
+    }
+}
+
+/// Inputs and result of the bundle producer election of an operator in a slot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElectionInfo {
+    /// Domain the election was for
+    pub domain_id: DomainId,
+    /// Slot the election was for
+    pub slot: u64,
+    /// Operator that attempted to be elected
+    pub operator_id: OperatorId,
+    /// Consensus block the stake and election parameters were read at
+    pub consensus_block_hash: Bytes,
+    /// Stake of the operator
+    pub operator_stake: Balance,
+    /// Total stake of the domain
+    pub total_domain_stake: Balance,
+    /// Expected number of bundles per slot as a fraction
+    pub bundle_slot_probability: (u64, u64),
+    /// Threshold VRF output must be below of for operator to be elected
+    pub threshold: u128,
+    /// VRF output compared against the threshold, absent if VRF signature was not produced
+    pub vrf_output: Option<u128>,
+    /// Probability of being elected in this slot
+    pub election_probability: f64,
+    /// Outcome of the election
+    pub outcome: ElectionOutcome,
+}
+
+impl From<BundleElectionRecord> for ElectionInfo {
+    fn from(record: BundleElectionRecord) -> Self {
+        let election_probability = record.election_probability();
+
+        Self {
+            domain_id: record.domain_id,
+            slot: record.slot.into(),
+            operator_id: record.operator_id,
+            consensus_block_hash: record.consensus_block_hash.into(),
+            operator_stake: record.operator_stake,
+            total_domain_stake: record.total_domain_stake,
+            bundle_slot_probability: record.bundle_slot_probability,
+            threshold: record.threshold,
+            vrf_output: record.vrf_output,
+            election_probability,
+            outcome: record.outcome.into(),
+        }
+    }
+}
+
+/// Statistics of recent bundle producer election attempts of an operator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElectionStats {
+    /// Number of slots operator attempted to be elected in
+    pub attempts: u64,
+    /// Number of slots operator was elected in
+    pub elected: u64,
+    /// Expected number of slots operator should have been elected in
+    pub expected_elected: f64,
+    /// Number of attempts where operator was not registered
+    pub operator_not_registered: u64,
+    /// Number of attempts where operator signing key was not available in the keystore
+    pub signing_key_unavailable: u64,
+    /// Last slot operator attempted to be elected in
+    pub last_slot: Option<u64>,
+    /// Last slot operator was elected in
+    pub last_elected_slot: Option<u64>,
+}
+
+impl From<BundleElectionStats> for ElectionStats {
+    fn from(stats: BundleElectionStats) -> Self {
+        Self {
+            attempts: stats.attempts,
+            elected: stats.elected,
+            expected_elected: stats.expected_elected,
+            operator_not_registered: stats.operator_not_registered,
+            signing_key_unavailable: stats.signing_key_unavailable,
+            last_slot: stats.last_slot.map(Into::into),
+            last_elected_slot: stats.last_elected_slot.map(Into::into),
+        }
+    }
+}
+
+/// Provides rpc methods for auditing the bundle producer election of the local operator.
+#[rpc(server)]
+pub trait BundleElectionApi {
+    /// Inputs and result of the bundle producer election of the operator in the slot, `None` if
+    /// local operator didn't attempt to be elected in that slot or attempt is no longer retained
+    #[method(name = "domains_bundleElectionAudit")]
+    fn bundle_election_audit(
+        &self,
+        domain_id: DomainId,
+        slot: u64,
+        operator_id: OperatorId,
+    ) -> RpcResult<Option<ElectionInfo>>;
+
+    /// Statistics of recent bundle producer election attempts of the operator
+    #[method(name = "domains_bundleElectionStats")]
+    fn bundle_election_stats(
+        &self,
+        domain_id: DomainId,
+        operator_id: OperatorId,
+    ) -> RpcResult<ElectionStats>;
+}
+
+/// Implements the [`BundleElectionApiServer`] RPC trait for auditing the bundle producer election.
+pub struct BundleElection {
+    domain_id: DomainId,
+    bundle_election_audit: BundleElectionAudit,
+    deny_unsafe: DenyUnsafe,
+}
+
+impl BundleElection {
+    /// Creates a new instance of the `BundleElection` handler.
+    pub fn new(
+        domain_id: DomainId,
+        bundle_election_audit: BundleElectionAudit,
+        deny_unsafe: DenyUnsafe,
+    ) -> Self {
+        Self {
+            domain_id,
+            bundle_election_audit,
+            deny_unsafe,
+        }
+    }
+
+    fn check_domain_id(&self, domain_id: DomainId) -> RpcResult<()> {
+        if domain_id != self.domain_id {
+            return Err(JsonRpseeError::Custom(format!(
+                "Node is running domain {}, not domain {domain_id}",
+                self.domain_id
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl BundleElectionApiServer for BundleElection {
+    fn bundle_election_audit(
+        &self,
+        domain_id: DomainId,
+        slot: u64,
+        operator_id: OperatorId,
+    ) -> RpcResult<Option<ElectionInfo>> {
+        self.deny_unsafe.check_if_safe()?;
+        self.check_domain_id(domain_id)?;
+
+        Ok(self
+            .bundle_election_audit
+            .election(slot.into(), operator_id)
+            .map(ElectionInfo::from))
+    }
+
+    fn bundle_election_stats(
+        &self,
+        domain_id: DomainId,
+        operator_id: OperatorId,
+    ) -> RpcResult<ElectionStats> {
+        self.deny_unsafe.check_if_safe()?;
+        self.check_domain_id(domain_id)?;
+
+        Ok(self.bundle_election_audit.stats(operator_id).into())
+    }
+}
@@ -0,0 +1,140 @@
+//! RPC for inspecting inbound messages that failed to be handled by the messenger.
+
+use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
+use jsonrpsee::proc_macros::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_messenger::endpoint::{Endpoint, EndpointId};
+use sp_messenger::messages::{ChainId, ChannelId, DeadLetter, DeadLetterReason, MessageId, Nonce};
+use sp_messenger::MessengerApi;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Reason the message failed to be handled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeadLetterFailure {
+    /// There is no handler for the destination endpoint
+    NoMessageHandler,
+    /// Destination endpoint handler returned an error
+    HandlerFailed(String),
+}
+
+impl From<DeadLetterReason> for DeadLetterFailure {
+    fn from(reason: DeadLetterReason) -> Self {
+        match reason {
+            DeadLetterReason::NoMessageHandler => Self::NoMessageHandler,
+            DeadLetterReason::HandlerFailed(error) => Self::HandlerFailed(format!("{error:?}")),
+        }
+    }
+}
+
+/// Inbound message that failed to be handled and is waiting to be retried or discarded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterInfo<BlockNumber> {
+    /// Chain the message was sent from
+    pub src_chain_id: ChainId,
+    /// Channel the message was sent through
+    pub channel_id: ChannelId,
+    /// Nonce of the message within the channel
+    pub nonce: Nonce,
+    /// Endpoint that sent the message
+    pub src_endpoint: EndpointId,
+    /// Endpoint the message was destined for
+    pub dst_endpoint: EndpointId,
+    /// Payload of the message
+    pub payload: Bytes,
+    /// Reason of the most recent failure
+    pub failure: DeadLetterFailure,
+    /// Block number of the most recent failure
+    pub failed_at: BlockNumber,
+}
+
+impl<BlockNumber> From<(ChainId, MessageId, DeadLetter<BlockNumber>)>
+    for DeadLetterInfo<BlockNumber>
+{
+    fn from(
+        (src_chain_id, (channel_id, nonce), dead_letter): (
+            ChainId,
+            MessageId,
+            DeadLetter<BlockNumber>,
+        ),
+    ) -> Self {
+        let Endpoint::Id(src_endpoint) = dead_letter.request.src_endpoint;
+        let Endpoint::Id(dst_endpoint) = dead_letter.request.dst_endpoint;
+
+        Self {
+            src_chain_id,
+            channel_id,
+            nonce,
+            src_endpoint,
+            dst_endpoint,
+            payload: dead_letter.request.payload.into(),
+            failure: dead_letter.reason.into(),
+            failed_at: dead_letter.failed_at,
+        }
+    }
+}
+
+/// Provides rpc methods for inspecting dead letters of the messenger.
+#[rpc(server)]
+pub trait DeadLettersApi<BlockHash, BlockNumber> {
+    /// Inbound messages that failed to be handled and are waiting to be retried or discarded by
+    /// governance, at the specified block or best block if not specified
+    #[method(name = "messenger_deadLetters")]
+    fn dead_letters(&self, at: Option<BlockHash>) -> RpcResult<Vec<DeadLetterInfo<BlockNumber>>>;
+}
+
+/// Implements the [`DeadLettersApiServer`] RPC trait for inspecting dead letters.
+pub struct DeadLetters<Block, Client> {
+    client: Arc<Client>,
+    _phantom: PhantomData<Block>,
+}
+
+impl<Block, Client> DeadLetters<Block, Client> {
+    /// Creates a new instance of the `DeadLetters` handler.
+    pub fn new(client: Arc<Client>) -> Self {
+        Self {
+            client,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Block, Client> DeadLettersApiServer<Block::Hash, NumberFor<Block>>
+    for DeadLetters<Block, Client>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    Client::Api: MessengerApi<Block, NumberFor<Block>>,
+{
+    fn dead_letters(
+        &self,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Vec<DeadLetterInfo<NumberFor<Block>>>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let runtime_api = self.client.runtime_api();
+
+        let supported = runtime_api
+            .has_api_with::<dyn MessengerApi<Block, NumberFor<Block>>, _>(at, |version| {
+                version >= 2
+            })
+            .map_err(|error| {
+                JsonRpseeError::Custom(format!("Failed to get runtime API version: {error}"))
+            })?;
+        if !supported {
+            return Err(JsonRpseeError::Custom(
+                "Runtime doesn't support dead letters".to_string(),
+            ));
+        }
+
+        runtime_api
+            .dead_letters(at)
+            .map(|dead_letters| dead_letters.into_iter().map(Into::into).collect())
+            .map_err(|error| JsonRpseeError::Custom(format!("Failed to get dead letters: {error}")))
+    }
+}
@@ -0,0 +1,148 @@
+//! RPC for submitting bundles constructed by external builders to the local operator.
+
+use domain_client_operator::{ExternalBundle, ExternalBundleOutcome, ExternalBundles};
+use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
+use jsonrpsee::proc_macros::rpc;
+use parity_scale_codec::Decode;
+use sc_rpc::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use sp_domains::DomainId;
+use sp_runtime::traits::Block as BlockT;
+use std::marker::PhantomData;
+
+/// Outcome of the most recently used external bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExternalBundleStatus {
+    /// External bundle was sealed and submitted in the slot
+    Submitted {
+        /// Slot bundle was submitted in
+        slot: u64,
+    },
+    /// External bundle was invalid and operator proposed a bundle on its own
+    Rejected {
+        /// Slot bundle was rejected in
+        slot: u64,
+        /// Reason of the rejection
+        reason: String,
+    },
+}
+
+impl From<ExternalBundleOutcome> for ExternalBundleStatus {
+    fn from(outcome: ExternalBundleOutcome) -> Self {
+        match outcome {
+            ExternalBundleOutcome::Submitted { slot } => Self::Submitted { slot: slot.into() },
+            ExternalBundleOutcome::Rejected { slot, reason } => Self::Rejected {
+                slot: slot.into(),
+                reason,
+            },
+        }
+    }
+}
+
+/// Provides rpc methods for proposer/builder separation, where bundle contents are constructed by
+/// an external builder and sealed by the local operator.
+#[rpc(server)]
+pub trait ExternalBundleApi {
+    /// Submit SCALE-encoded domain extrinsics to be used as the contents of the next bundle local
+    /// operator is elected to produce, replaces previously submitted bundle that wasn't used yet.
+    ///
+    /// Bundle is discarded if operator is not elected until `valid_until_slot` (inclusive).
+    #[method(name = "domains_submitExternalBundle")]
+    fn submit_external_bundle(
+        &self,
+        domain_id: DomainId,
+        extrinsics: Vec<Bytes>,
+        valid_until_slot: Option<u64>,
+    ) -> RpcResult<()>;
+
+    /// Outcome of the most recently used external bundle, `None` if no external bundle was used
+    /// yet
+    #[method(name = "domains_externalBundleStatus")]
+    fn external_bundle_status(
+        &self,
+        domain_id: DomainId,
+    ) -> RpcResult<Option<ExternalBundleStatus>>;
+}
+
+/// Implements the [`ExternalBundleApiServer`] RPC trait for submitting external bundles.
+pub struct ExternalBundleRpc<Block> {
+    domain_id: DomainId,
+    external_bundles: ExternalBundles,
+    deny_unsafe: DenyUnsafe,
+    _phantom: PhantomData<Block>,
+}
+
+impl<Block> ExternalBundleRpc<Block> {
+    /// Creates a new instance of the `ExternalBundleRpc` handler.
+    pub fn new(
+        domain_id: DomainId,
+        external_bundles: ExternalBundles,
+        deny_unsafe: DenyUnsafe,
+    ) -> Self {
+        Self {
+            domain_id,
+            external_bundles,
+            deny_unsafe,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn check_domain_id(&self, domain_id: DomainId) -> RpcResult<()> {
+        if domain_id != self.domain_id {
+            return Err(JsonRpseeError::Custom(format!(
+                "Node is running domain {}, not domain {domain_id}",
+                self.domain_id
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl<Block> ExternalBundleApiServer for ExternalBundleRpc<Block>
+where
+    Block: BlockT,
+{
+    fn submit_external_bundle(
+        &self,
+        domain_id: DomainId,
+        extrinsics: Vec<Bytes>,
+        valid_until_slot: Option<u64>,
+    ) -> RpcResult<()> {
+        self.deny_unsafe.check_if_safe()?;
+        self.check_domain_id(domain_id)?;
+
+        // Reject malformed extrinsics early, the rest of validation happens against the latest
+        // domain state once operator is elected
+        for (index, extrinsic) in extrinsics.iter().enumerate() {
+            Block::Extrinsic::decode(&mut extrinsic.as_ref()).map_err(|error| {
+                JsonRpseeError::Custom(format!("Failed to decode extrinsic {index}: {error}"))
+            })?;
+        }
+
+        self.external_bundles.submit(ExternalBundle {
+            extrinsics: extrinsics
+                .into_iter()
+                .map(|extrinsic| extrinsic.0)
+                .collect(),
+            valid_until_slot: valid_until_slot.map(Into::into),
+        });
+
+        Ok(())
+    }
+
+    fn external_bundle_status(
+        &self,
+        domain_id: DomainId,
+    ) -> RpcResult<Option<ExternalBundleStatus>> {
+        self.deny_unsafe.check_if_safe()?;
+        self.check_domain_id(domain_id)?;
+
+        Ok(self
+            .external_bundles
+            .last_outcome()
+            .map(ExternalBundleStatus::from))
+    }
+}
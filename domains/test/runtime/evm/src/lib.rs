@@ -23,12 +23,14 @@ use fp_self_contained::{CheckedSignature, SelfContainedCall};
 use frame_support::dispatch::{DispatchClass, DispatchInfo, GetDispatchInfo};
 use frame_support::inherent::ProvideInherent;
 use frame_support::traits::{
-    ConstU16, ConstU32, ConstU64, Currency, Everything, FindAuthor, Imbalance, OnFinalize,
+    ConstU16, ConstU32, ConstU64, Currency, EitherOfDiverse, Everything, FindAuthor, Imbalance,
+    OnFinalize, SortedMembers,
 };
 use frame_support::weights::constants::{ParityDbWeight, WEIGHT_REF_TIME_PER_SECOND};
 use frame_support::weights::{ConstantMultiplier, IdentityFee, Weight};
 use frame_support::{construct_runtime, parameter_types};
 use frame_system::limits::{BlockLength, BlockWeights};
+use frame_system::{EnsureRoot, EnsureSignedBy};
 use pallet_block_fees::fees::OnChargeDomainTransaction;
 use pallet_ethereum::Call::transact;
 use pallet_ethereum::{PostLogContent, Transaction as EthereumTransaction, TransactionStatus};
@@ -43,7 +45,7 @@ use sp_core::{Get, OpaqueMetadata, H160, H256, U256};
 use sp_domains::{DomainId, Transfers};
 use sp_messenger::endpoint::{Endpoint, EndpointHandler as EndpointHandlerT, EndpointId};
 use sp_messenger::messages::{
-    BlockMessagesWithStorageKey, ChainId, ChannelId, CrossDomainMessage, MessageId,
+    BlockMessagesWithStorageKey, ChainId, ChannelId, CrossDomainMessage, DeadLetter, MessageId,
 };
 use sp_messenger_host_functions::{get_storage_key, StorageKeyRequest};
 use sp_mmr_primitives::{EncodableOpaqueLeaf, Proof};
@@ -387,6 +389,7 @@ impl pallet_sudo::Config for Runtime {
 parameter_types! {
     pub const StateRootsBound: u32 = 50;
     pub const RelayConfirmationDepth: BlockNumber = 1;
+    pub const MaxDeadLetters: u32 = 100;
     pub SelfChainId: ChainId = SelfDomainId::self_domain_id().into();
 }
 
@@ -437,6 +440,16 @@ impl sp_messenger::StorageKeys for StorageKeys {
     }
 }
 
+/// Domain governance, i.e. the sudo account of the domain, which manages domain specific
+/// settings like the messenger chain allowlist without going through the consensus chain.
+pub struct DomainGovernance;
+
+impl SortedMembers<AccountId> for DomainGovernance {
+    fn sorted_members() -> Vec<AccountId> {
+        Sudo::key().into_iter().collect()
+    }
+}
+
 impl pallet_messenger::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type SelfChainId = SelfChainId;
@@ -457,6 +470,10 @@ impl pallet_messenger::Config for Runtime {
     type MmrHash = MmrHash;
     type MmrProofVerifier = MmrProofVerifier;
     type StorageKeys = StorageKeys;
+    type ChainAllowlistOrigin =
+        EitherOfDiverse<EnsureRoot<AccountId>, EnsureSignedBy<DomainGovernance, AccountId>>;
+    type DeadLetterOrigin = EnsureRoot<AccountId>;
+    type MaxDeadLetters = MaxDeadLetters;
 }
 
 impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
@@ -1032,6 +1049,10 @@ impl_runtime_apis! {
         fn inbox_response_storage_key(message_id: MessageId) -> Vec<u8> {
             Messenger::inbox_response_storage_key(message_id)
         }
+
+        fn dead_letters() -> Vec<(ChainId, MessageId, DeadLetter<BlockNumber>)> {
+            Messenger::dead_letters()
+        }
     }
 
     impl sp_messenger::RelayerApi<Block, BlockNumber> for Runtime {
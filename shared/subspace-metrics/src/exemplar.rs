@@ -0,0 +1,27 @@
+//! Exemplars linking metric observations to traces.
+
+use prometheus_client::encoding::EncodeLabelSet;
+use prometheus_client::metrics::exemplar::HistogramWithExemplars;
+use tracing::Span;
+
+/// Histogram that attaches ID of the span in which observation was made as an exemplar.
+pub type TracedHistogram = HistogramWithExemplars<TraceExemplar>;
+
+/// Exemplar with ID of the span in which observation was made.
+///
+/// `tracing` span IDs are only unique within a process and are not trace IDs of a distributed
+/// tracing system, hence the label is `span_id` rather than `trace_id`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct TraceExemplar {
+    /// Hex-encoded span ID
+    pub span_id: String,
+}
+
+impl TraceExemplar {
+    /// Exemplar for the current span, `None` if there is no current span.
+    pub fn current() -> Option<Self> {
+        Span::current().id().map(|id| Self {
+            span_id: format!("{:016x}", id.into_u64()),
+        })
+    }
+}
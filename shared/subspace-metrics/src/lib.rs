@@ -3,19 +3,106 @@
 //! used: `prometheus-client` (official library) and TiKV's `prometheus` client (used by Substrate).
 //! The module exposes a web server endpoint at "/metrics" that outputs metrics in Prometheus
 //! format. It adapts metrics from either or both of those libraries.
-
+//!
+//! The server is behind `server` feature (enabled by default), which allows any binary (node,
+//! farmer, gateway, relayer, bootstrap node) to embed it.
+//!
+//! # Naming
+//!
+//! All metrics registered with `prometheus-client` follow `subspace_<component>_<name>` scheme,
+//! where `<component>` is one of [`MetricsComponent`] and `<name>` is a snake case name of the
+//! metric without units (units are appended by `prometheus-client` when registered with a unit).
+//! Use [`MetricsComponent::sub_registry`] to get a registry with the right prefix, labels like
+//! `farm_id` are added on individual metrics.
+//!
+//! Histograms that measure latency of operations that happen within a span should be
+//! [`TracedHistogram`]s, such that exemplars link observations to the corresponding span ID.
+//!
+//! # Migration
+//!
+//! Metrics that existed before the naming scheme was introduced were renamed, dashboards and
+//! alerts need to be updated accordingly:
+//! * DSN: `subspace_<name>` became `subspace_dsn_<name>` (`established_connections`,
+//!   `publicly_reachable`, `bandwidth_bytes`)
+//! * Node: block import metrics moved from Substrate registry to `prometheus-client`, so counters
+//!   got `_total` suffix: `subspace_node_blocks_total`, `subspace_node_extrinsics_total` and
+//!   `subspace_node_extrinsics_size_bytes_total`, `subspace_node_import_queue_depth` and
+//!   `subspace_node_blocks_behind` kept their names
+//! * Farmer thread pools: `thread_pool_<name>` became `subspace_farmer_thread_pool_<name>`
+
+mod exemplar;
+
+pub use crate::exemplar::{TraceExemplar, TracedHistogram};
+#[cfg(feature = "server")]
 use actix_web::http::StatusCode;
+#[cfg(feature = "server")]
 use actix_web::web::Data;
+#[cfg(feature = "server")]
 use actix_web::{get, App, HttpResponse, HttpServer};
-use prometheus::{Registry as SubstrateRegistry, TextEncoder};
+use prometheus::Registry as SubstrateRegistry;
+#[cfg(feature = "server")]
+use prometheus::TextEncoder;
+#[cfg(feature = "server")]
 use prometheus_client::encoding::text::encode;
 use prometheus_client::registry::Registry as PrometheusClientRegistry;
+#[cfg(feature = "server")]
 use std::error::Error;
+#[cfg(feature = "server")]
 use std::future::Future;
+#[cfg(feature = "server")]
 use std::io::ErrorKind;
+#[cfg(feature = "server")]
 use std::net::SocketAddr;
+#[cfg(feature = "server")]
 use tracing::{error, info, warn};
 
+/// Prefix shared by all metrics registered with `prometheus-client`.
+pub const METRICS_PREFIX: &str = "subspace";
+
+/// Component of Subspace Network that exposes metrics, determines the prefix of metric names.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MetricsComponent {
+    /// Consensus or domain node
+    Node,
+    /// Farmer
+    Farmer,
+    /// Distributed storage network (networking stack used by all other components)
+    Dsn,
+    /// Gateway
+    Gateway,
+    /// Cross-domain message relayer
+    Relayer,
+    /// DSN bootstrap node
+    BootstrapNode,
+}
+
+impl MetricsComponent {
+    /// Name of the component as used in metric names.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Node => "node",
+            Self::Farmer => "farmer",
+            Self::Dsn => "dsn",
+            Self::Gateway => "gateway",
+            Self::Relayer => "relayer",
+            Self::BootstrapNode => "bootstrap_node",
+        }
+    }
+
+    /// Prefix of metric names of this component (`subspace_<component>`).
+    pub fn prefix(&self) -> String {
+        format!("{METRICS_PREFIX}_{}", self.name())
+    }
+
+    /// Returns sub-registry for metrics of this component.
+    pub fn sub_registry<'a>(
+        &self,
+        registry: &'a mut PrometheusClientRegistry,
+    ) -> &'a mut PrometheusClientRegistry {
+        registry.sub_registry_with_prefix(self.prefix())
+    }
+}
+
 /// Metrics registry adapter for prometheus-client and Substrate frameworks.
 /// It specifies which metrics registry or registries are in use.
 pub enum RegistryAdapter {
@@ -27,6 +114,7 @@ pub enum RegistryAdapter {
     Both(PrometheusClientRegistry, SubstrateRegistry),
 }
 
+#[cfg(feature = "server")]
 #[get("/metrics")]
 async fn metrics(registry: Data<RegistryAdapter>) -> Result<HttpResponse, Box<dyn Error>> {
     let mut encoded_metrics = String::new();
@@ -52,6 +140,7 @@ async fn metrics(registry: Data<RegistryAdapter>) -> Result<HttpResponse, Box<dy
 }
 
 /// Start prometheus metrics server on the provided address.
+#[cfg(feature = "server")]
 pub fn start_prometheus_metrics_server(
     mut endpoints: Vec<SocketAddr>,
     registry: RegistryAdapter,
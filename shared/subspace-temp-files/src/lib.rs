@@ -0,0 +1,264 @@
+//! Shutdown-safe temporary files.
+//!
+//! Files like cache metadata and identity are written to a temporary file first and then
+//! atomically renamed over the target, such that target is never partially written. Temporary
+//! files are removed when dropped, but if the process crashes or is killed in the middle of writing
+//! they would be left on disk forever, which is especially painful when they are large.
+//!
+//! [`TempFileManager`] registers every temporary file in a manifest before the file is created and
+//! removes files from previous runs that are still in the manifest when opened again on startup.
+
+#[cfg(test)]
+mod tests;
+
+use parking_lot::Mutex;
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{fs, io};
+use tracing::{debug, info, warn};
+
+/// Extension appended to the target file name to get the name of the temporary file
+const TEMP_FILE_EXTENSION: &str = "tmp";
+
+#[derive(Debug)]
+struct Inner {
+    manifest_path: PathBuf,
+    /// Temporary files that currently exist, in sync with manifest on disk
+    files: Mutex<BTreeSet<PathBuf>>,
+}
+
+/// Manager of temporary files in a directory, removes orphaned temporary files from previous runs
+/// when opened.
+#[derive(Debug, Clone)]
+pub struct TempFileManager {
+    inner: Arc<Inner>,
+}
+
+impl TempFileManager {
+    /// Name of the manifest file in the directory.
+    pub const MANIFEST_FILE_NAME: &'static str = "temp_files.json";
+
+    /// Open manager for `directory`, temporary files left from previous runs (due to crash or
+    /// forceful shutdown) are removed.
+    ///
+    /// Only one manager should be opened for a directory at a time.
+    pub fn open(directory: &Path) -> io::Result<Self> {
+        let manifest_path = directory.join(Self::MANIFEST_FILE_NAME);
+
+        for orphan in Self::read_manifest(&manifest_path) {
+            match fs::metadata(&orphan) {
+                Ok(metadata) => {
+                    info!(
+                        path = %orphan.display(),
+                        size = %metadata.len(),
+                        "Removing orphaned temporary file"
+                    );
+                    fs::remove_file(&orphan)?;
+                }
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                    // Crashed after the file was renamed or removed, but before manifest update
+                }
+                Err(error) => {
+                    return Err(error);
+                }
+            }
+        }
+
+        let files = BTreeSet::new();
+        Self::write_manifest(&manifest_path, &files)?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                manifest_path,
+                files: Mutex::new(files),
+            }),
+        })
+    }
+
+    /// Path of the temporary file for `target`.
+    pub fn temp_path(target: &Path) -> PathBuf {
+        let mut path = OsString::from(target.as_os_str());
+        path.push(".");
+        path.push(TEMP_FILE_EXTENSION);
+        PathBuf::from(path)
+    }
+
+    /// Create temporary file that will replace `target` once persisted, previous temporary file
+    /// for the same target is truncated.
+    pub fn create(&self, target: &Path) -> io::Result<TempFile> {
+        let path = Self::temp_path(target);
+
+        self.register(&path)?;
+
+        let file = match OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+        {
+            Ok(file) => file,
+            Err(error) => {
+                if let Err(error) = self.unregister(&path) {
+                    warn!(%error, path = %path.display(), "Failed to unregister temporary file");
+                }
+                return Err(error);
+            }
+        };
+
+        Ok(TempFile {
+            file,
+            path,
+            target: target.to_path_buf(),
+            manager: self.clone(),
+            persisted: false,
+        })
+    }
+
+    /// Atomically replace contents of `target` with `contents`, target will either have old or
+    /// new contents, but never partially written ones.
+    pub fn write_atomically(&self, target: &Path, contents: &[u8]) -> io::Result<()> {
+        let mut temp_file = self.create(target)?;
+        temp_file.write_all(contents)?;
+        temp_file.persist()
+    }
+
+    /// Temporary files that currently exist.
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.inner.files.lock().iter().cloned().collect()
+    }
+
+    fn register(&self, path: &Path) -> io::Result<()> {
+        let mut files = self.inner.files.lock();
+        if files.insert(path.to_path_buf()) {
+            if let Err(error) = Self::write_manifest(&self.inner.manifest_path, &files) {
+                files.remove(path);
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn unregister(&self, path: &Path) -> io::Result<()> {
+        let mut files = self.inner.files.lock();
+        if files.remove(path) {
+            Self::write_manifest(&self.inner.manifest_path, &files)?;
+        }
+
+        Ok(())
+    }
+
+    fn read_manifest(manifest_path: &Path) -> Vec<PathBuf> {
+        let bytes = match fs::read(manifest_path) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                if error.kind() != io::ErrorKind::NotFound {
+                    warn!(
+                        %error,
+                        path = %manifest_path.display(),
+                        "Failed to read temporary files manifest, ignoring"
+                    );
+                }
+                return Vec::new();
+            }
+        };
+
+        serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+            warn!(
+                %error,
+                path = %manifest_path.display(),
+                "Temporary files manifest is corrupted, ignoring"
+            );
+            Vec::new()
+        })
+    }
+
+    /// Manifest itself is replaced atomically, such that it is never partially written
+    fn write_manifest(manifest_path: &Path, files: &BTreeSet<PathBuf>) -> io::Result<()> {
+        let bytes = serde_json::to_vec(files)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        let new_manifest_path = manifest_path.with_extension("json.new");
+
+        {
+            let mut file = File::create(&new_manifest_path)?;
+            file.write_all(&bytes)?;
+            file.sync_all()?;
+        }
+
+        fs::rename(new_manifest_path, manifest_path)
+    }
+}
+
+/// Temporary file created by [`TempFileManager::create()`], removed on drop unless persisted with
+/// [`TempFile::persist()`].
+#[derive(Debug)]
+pub struct TempFile {
+    file: File,
+    path: PathBuf,
+    target: PathBuf,
+    manager: TempFileManager,
+    persisted: bool,
+}
+
+impl Deref for TempFile {
+    type Target = File;
+
+    fn deref(&self) -> &Self::Target {
+        &self.file
+    }
+}
+
+impl DerefMut for TempFile {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.file
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if self.persisted {
+            return;
+        }
+
+        if let Err(error) = fs::remove_file(&self.path) {
+            if error.kind() != io::ErrorKind::NotFound {
+                warn!(%error, path = %self.path.display(), "Failed to remove temporary file");
+                // Keep it in the manifest so it is removed on next startup
+                return;
+            }
+        }
+
+        if let Err(error) = self.manager.unregister(&self.path) {
+            warn!(%error, path = %self.path.display(), "Failed to unregister temporary file");
+        }
+    }
+}
+
+impl TempFile {
+    /// Path of the temporary file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Flush temporary file to disk and atomically rename it over the target.
+    pub fn persist(mut self) -> io::Result<()> {
+        self.file.sync_all()?;
+        fs::rename(&self.path, &self.target)?;
+        self.persisted = true;
+
+        debug!(target = %self.target.display(), "Persisted temporary file");
+
+        // Already renamed, failure to update manifest is harmless since missing files are skipped
+        // on startup
+        if let Err(error) = self.manager.unregister(&self.path) {
+            warn!(%error, path = %self.path.display(), "Failed to unregister temporary file");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::TempFileManager;
+use std::fs;
+use std::io::Write;
+use tempfile::tempdir;
+
+#[test]
+fn write_atomically() {
+    let directory = tempdir().unwrap();
+    let target = directory.path().join("metadata.bin");
+
+    let manager = TempFileManager::open(directory.path()).unwrap();
+    manager.write_atomically(&target, &[1, 2, 3]).unwrap();
+    manager.write_atomically(&target, &[4, 5]).unwrap();
+
+    assert_eq!(fs::read(&target).unwrap(), vec![4, 5]);
+    assert!(!TempFileManager::temp_path(&target).exists());
+    assert!(manager.files().is_empty());
+}
+
+#[test]
+fn removed_on_drop() {
+    let directory = tempdir().unwrap();
+    let target = directory.path().join("metadata.bin");
+
+    let manager = TempFileManager::open(directory.path()).unwrap();
+    let mut temp_file = manager.create(&target).unwrap();
+    temp_file.write_all(&[1, 2, 3]).unwrap();
+    let temp_path = temp_file.path().to_path_buf();
+    assert!(temp_path.exists());
+    assert_eq!(manager.files(), vec![temp_path.clone()]);
+
+    drop(temp_file);
+
+    assert!(!temp_path.exists());
+    assert!(!target.exists());
+    assert!(manager.files().is_empty());
+}
+
+#[test]
+fn orphans_removed_on_open() {
+    let directory = tempdir().unwrap();
+    let target = directory.path().join("sector.bin");
+    let unrelated = directory.path().join("unrelated.tmp");
+    fs::write(&unrelated, [0]).unwrap();
+
+    let temp_path = {
+        let manager = TempFileManager::open(directory.path()).unwrap();
+        let mut temp_file = manager.create(&target).unwrap();
+        temp_file.write_all(&[1, 2, 3]).unwrap();
+        let temp_path = temp_file.path().to_path_buf();
+        // Simulate crash, temp file is neither persisted nor removed
+        std::mem::forget(temp_file);
+        temp_path
+    };
+    assert!(temp_path.exists());
+
+    let manager = TempFileManager::open(directory.path()).unwrap();
+    assert!(!temp_path.exists());
+    assert!(manager.files().is_empty());
+    // Files that were not registered are not touched
+    assert!(unrelated.exists());
+
+    // Corrupted manifest doesn't prevent opening
+    fs::write(
+        directory.path().join(TempFileManager::MANIFEST_FILE_NAME),
+        b"garbage",
+    )
+    .unwrap();
+    TempFileManager::open(directory.path()).unwrap();
+}
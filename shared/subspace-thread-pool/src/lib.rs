@@ -0,0 +1,430 @@
+//! Management of thread pools shared by Subspace components.
+//!
+//! Components like farming, plotting and archiving used to create rayon thread pools on their own,
+//! each sized as if it had the whole machine for itself, which oversubscribed CPU cores.
+//! [`ThreadPoolManager`] creates thread pools against a single CPU budget, names threads
+//! consistently, allows to set up threads (for example to pin them to CPU cores) and exposes
+//! utilization metrics.
+//!
+//! Libraries like erasure coding and proof-of-space table generation don't create thread pools,
+//! they parallelize with rayon within whichever managed thread pool they are called from.
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+use crate::metrics::{PoolMetrics, ThreadPoolMetrics};
+use parking_lot::Mutex;
+use prometheus_client::registry::Registry;
+use rayon::{ThreadBuilder, ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock, Weak};
+use std::time::{Duration, Instant};
+use std::{fmt, thread};
+use tracing::warn;
+
+/// Wraps the body of every thread of the pool, allows to set up thread environment (pin thread to
+/// CPU cores, enter async runtime context, etc.) before running the thread.
+///
+/// Wrapper must call provided function exactly once.
+pub type ThreadWrapper = Arc<dyn Fn(&mut dyn FnMut()) + Send + Sync>;
+
+/// Thread pool configuration.
+#[derive(Clone)]
+pub struct ThreadPoolConfig {
+    name: String,
+    threads: NonZeroUsize,
+    group: Option<String>,
+    thread_wrapper: Option<ThreadWrapper>,
+}
+
+impl fmt::Debug for ThreadPoolConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadPoolConfig")
+            .field("name", &self.name)
+            .field("threads", &self.threads)
+            .field("group", &self.group)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ThreadPoolConfig {
+    /// Thread pool with `threads` threads, threads will be named `{name}.{thread_index}`
+    pub fn new(name: impl Into<String>, threads: NonZeroUsize) -> Self {
+        Self {
+            name: name.into(),
+            threads,
+            group: None,
+            thread_wrapper: None,
+        }
+    }
+
+    /// Put thread pool into budget group.
+    ///
+    /// Thread pools in the same group share CPU budget reservation (only the largest thread pool
+    /// of the group is accounted for), which is meant for thread pools that are not busy at the
+    /// same time, like plotting and replotting. By default, thread pool has a group of its own.
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Wrap every thread of the thread pool, see [`ThreadWrapper`]
+    pub fn thread_wrapper(mut self, thread_wrapper: ThreadWrapper) -> Self {
+        self.thread_wrapper = Some(thread_wrapper);
+        self
+    }
+}
+
+/// Utilization of a managed thread pool.
+#[derive(Debug, Clone)]
+pub struct ThreadPoolUtilization {
+    /// Name of the thread pool
+    pub name: String,
+    /// Number of threads in the thread pool
+    pub threads: usize,
+    /// Number of tasks currently running in the thread pool
+    pub active_tasks: usize,
+    /// Total time spent running tasks in the thread pool
+    pub task_time: Duration,
+}
+
+#[derive(Debug)]
+struct Reservation {
+    pool_id: u64,
+    threads: usize,
+}
+
+#[derive(Debug, Default)]
+struct Budget {
+    groups: HashMap<String, Vec<Reservation>>,
+    next_pool_id: u64,
+}
+
+impl Budget {
+    fn group_reserved(&self, group: &str) -> usize {
+        self.groups
+            .get(group)
+            .and_then(|reservations| reservations.iter().map(|r| r.threads).max())
+            .unwrap_or_default()
+    }
+
+    fn reserved(&self) -> usize {
+        self.groups
+            .keys()
+            .map(|group| self.group_reserved(group))
+            .sum()
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    cpu_budget: NonZeroUsize,
+    budget: Mutex<Budget>,
+    pools: Mutex<Vec<Weak<PoolStats>>>,
+    metrics: Mutex<Option<ThreadPoolMetrics>>,
+}
+
+/// Creates thread pools against a shared CPU budget.
+///
+/// Thread pools that would exceed the budget are shrunk to fit into what is left of it (but are
+/// never smaller than one thread), budget reservation is released when thread pool is dropped.
+#[derive(Debug, Clone)]
+pub struct ThreadPoolManager {
+    inner: Arc<Inner>,
+}
+
+impl ThreadPoolManager {
+    /// Create new manager with a budget of `cpu_budget` threads
+    pub fn new(cpu_budget: NonZeroUsize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                cpu_budget,
+                budget: Mutex::default(),
+                pools: Mutex::default(),
+                metrics: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Create new manager with a budget equal to the number of logical CPU cores
+    pub fn with_available_parallelism() -> Self {
+        Self::new(thread::available_parallelism().unwrap_or(NonZeroUsize::MIN))
+    }
+
+    /// Process-wide manager with a budget equal to the number of logical CPU cores, for components
+    /// that are not given a manager explicitly
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<ThreadPoolManager> = OnceLock::new();
+
+        GLOBAL.get_or_init(Self::with_available_parallelism)
+    }
+
+    /// CPU budget in threads
+    pub fn cpu_budget(&self) -> NonZeroUsize {
+        self.inner.cpu_budget
+    }
+
+    /// Number of threads currently reserved by thread pools
+    pub fn reserved_threads(&self) -> usize {
+        self.inner.budget.lock().reserved()
+    }
+
+    /// Register thread pool metrics, only thread pools created afterwards are reported
+    pub fn register_metrics(&self, registry: &mut Registry) {
+        self.inner
+            .metrics
+            .lock()
+            .replace(ThreadPoolMetrics::new(registry));
+    }
+
+    /// Utilization of thread pools that are currently alive
+    pub fn utilization(&self) -> Vec<ThreadPoolUtilization> {
+        let mut pools = self.inner.pools.lock();
+        pools.retain(|stats| stats.strong_count() > 0);
+
+        pools
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|stats| stats.utilization())
+            .collect()
+    }
+
+    /// Create thread pool according to provided config
+    pub fn build(
+        &self,
+        config: ThreadPoolConfig,
+    ) -> Result<ManagedThreadPool, ThreadPoolBuildError> {
+        let ThreadPoolConfig {
+            name,
+            threads: requested_threads,
+            group,
+            thread_wrapper,
+        } = config;
+        let group = group.unwrap_or_else(|| name.clone());
+
+        let reservation = {
+            let mut budget = self.inner.budget.lock();
+
+            let reserved_by_others = budget.reserved() - budget.group_reserved(&group);
+            let available = self
+                .inner
+                .cpu_budget
+                .get()
+                .saturating_sub(reserved_by_others);
+            let threads = if requested_threads.get() > available {
+                let threads = available.max(1);
+                warn!(
+                    %name,
+                    %requested_threads,
+                    %threads,
+                    cpu_budget = %self.inner.cpu_budget,
+                    "Thread pool doesn't fit into CPU budget, shrinking it"
+                );
+                threads
+            } else {
+                requested_threads.get()
+            };
+
+            let pool_id = budget.next_pool_id;
+            budget.next_pool_id += 1;
+            budget
+                .groups
+                .entry(group.clone())
+                .or_default()
+                .push(Reservation { pool_id, threads });
+
+            BudgetReservation {
+                inner: Arc::clone(&self.inner),
+                group,
+                pool_id,
+                threads,
+            }
+        };
+
+        let thread_pool = ThreadPoolBuilder::new()
+            .thread_name({
+                let name = name.clone();
+                move |thread_index| format!("{name}.{thread_index}")
+            })
+            .num_threads(reservation.threads)
+            .spawn_handler(move |thread: ThreadBuilder| {
+                let mut builder = thread::Builder::new();
+                if let Some(name) = thread.name() {
+                    builder = builder.name(name.to_owned());
+                }
+                if let Some(stack_size) = thread.stack_size() {
+                    builder = builder.stack_size(stack_size);
+                }
+
+                let thread_wrapper = thread_wrapper.clone();
+                builder.spawn(move || match thread_wrapper {
+                    Some(thread_wrapper) => {
+                        let mut thread = Some(thread);
+                        thread_wrapper(&mut || {
+                            if let Some(thread) = thread.take() {
+                                thread.run();
+                            }
+                        });
+                    }
+                    None => thread.run(),
+                })?;
+
+                Ok(())
+            })
+            .build()?;
+
+        let metrics = self
+            .inner
+            .metrics
+            .lock()
+            .as_ref()
+            .map(|metrics| metrics.pool_metrics(&name));
+        if let Some(metrics) = &metrics {
+            metrics.threads.set(reservation.threads as i64);
+        }
+        let stats = Arc::new(PoolStats {
+            name,
+            threads: reservation.threads,
+            active_tasks: AtomicUsize::new(0),
+            task_time_nanos: AtomicU64::new(0),
+            metrics,
+        });
+        self.inner.pools.lock().push(Arc::downgrade(&stats));
+
+        Ok(ManagedThreadPool {
+            thread_pool,
+            stats,
+            _reservation: reservation,
+        })
+    }
+}
+
+/// Reservation of the CPU budget, released on drop.
+#[derive(Debug)]
+struct BudgetReservation {
+    inner: Arc<Inner>,
+    group: String,
+    pool_id: u64,
+    threads: usize,
+}
+
+impl Drop for BudgetReservation {
+    fn drop(&mut self) {
+        let mut budget = self.inner.budget.lock();
+        if let Some(reservations) = budget.groups.get_mut(&self.group) {
+            reservations.retain(|reservation| reservation.pool_id != self.pool_id);
+            if reservations.is_empty() {
+                budget.groups.remove(&self.group);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PoolStats {
+    name: String,
+    threads: usize,
+    active_tasks: AtomicUsize,
+    task_time_nanos: AtomicU64,
+    metrics: Option<PoolMetrics>,
+}
+
+impl Drop for PoolStats {
+    fn drop(&mut self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.threads.set(0);
+        }
+    }
+}
+
+impl PoolStats {
+    fn task_started(self: &Arc<Self>) -> ActiveTask {
+        self.active_tasks.fetch_add(1, Ordering::Relaxed);
+        if let Some(metrics) = &self.metrics {
+            metrics.active_tasks.inc();
+        }
+
+        ActiveTask {
+            stats: Arc::clone(self),
+            started_at: Instant::now(),
+        }
+    }
+
+    fn utilization(&self) -> ThreadPoolUtilization {
+        ThreadPoolUtilization {
+            name: self.name.clone(),
+            threads: self.threads,
+            active_tasks: self.active_tasks.load(Ordering::Relaxed),
+            task_time: Duration::from_nanos(self.task_time_nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Accounts for task running in the thread pool until dropped
+struct ActiveTask {
+    stats: Arc<PoolStats>,
+    started_at: Instant,
+}
+
+impl Drop for ActiveTask {
+    fn drop(&mut self) {
+        let elapsed = self.started_at.elapsed();
+
+        self.stats.active_tasks.fetch_sub(1, Ordering::Relaxed);
+        self.stats
+            .task_time_nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+        if let Some(metrics) = &self.stats.metrics {
+            metrics.active_tasks.dec();
+            metrics.task_time.inc_by(elapsed.as_secs_f64());
+        }
+    }
+}
+
+/// Thread pool created by [`ThreadPoolManager`].
+#[derive(Debug)]
+pub struct ManagedThreadPool {
+    thread_pool: ThreadPool,
+    stats: Arc<PoolStats>,
+    _reservation: BudgetReservation,
+}
+
+impl ManagedThreadPool {
+    /// Name of the thread pool
+    pub fn name(&self) -> &str {
+        &self.stats.name
+    }
+
+    /// Number of threads in the thread pool, may be smaller than requested due to CPU budget
+    pub fn current_num_threads(&self) -> usize {
+        self.thread_pool.current_num_threads()
+    }
+
+    /// Execute `op` within the thread pool, see [`ThreadPool::install()`]
+    pub fn install<OP, R>(&self, op: OP) -> R
+    where
+        OP: FnOnce() -> R + Send,
+        R: Send,
+    {
+        let _active_task = self.stats.task_started();
+
+        self.thread_pool.install(op)
+    }
+
+    /// Spawn `op` in the thread pool, see [`ThreadPool::spawn()`]
+    pub fn spawn<OP>(&self, op: OP)
+    where
+        OP: FnOnce() + Send + 'static,
+    {
+        let active_task = self.stats.task_started();
+
+        self.thread_pool.spawn(move || {
+            let _active_task = active_task;
+
+            op();
+        });
+    }
+}
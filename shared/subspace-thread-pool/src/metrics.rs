@@ -0,0 +1,69 @@
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::registry::{Registry, Unit};
+use std::sync::atomic::{AtomicI64, AtomicU64};
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Debug, Clone)]
+pub(crate) struct ThreadPoolMetrics {
+    threads: Family<Labels, Gauge<i64, AtomicI64>>,
+    active_tasks: Family<Labels, Gauge<i64, AtomicI64>>,
+    task_time: Family<Labels, Counter<f64, AtomicU64>>,
+}
+
+impl ThreadPoolMetrics {
+    pub(crate) fn new(registry: &mut Registry) -> Self {
+        let sub_registry = registry.sub_registry_with_prefix("thread_pool");
+
+        let threads = Family::<_, _>::new_with_constructor(Gauge::<_, _>::default);
+
+        sub_registry.register(
+            "threads",
+            "Number of threads in the thread pool",
+            threads.clone(),
+        );
+
+        let active_tasks = Family::<_, _>::new_with_constructor(Gauge::<_, _>::default);
+
+        sub_registry.register(
+            "active_tasks",
+            "Number of tasks currently running in the thread pool",
+            active_tasks.clone(),
+        );
+
+        let task_time = Family::<_, _>::new_with_constructor(Counter::<_, _>::default);
+
+        sub_registry.register_with_unit(
+            "task_time",
+            "Total time spent running tasks in the thread pool",
+            Unit::Seconds,
+            task_time.clone(),
+        );
+
+        Self {
+            threads,
+            active_tasks,
+            task_time,
+        }
+    }
+
+    pub(crate) fn pool_metrics(&self, name: &str) -> PoolMetrics {
+        let labels = vec![("pool".to_string(), name.to_string())];
+
+        PoolMetrics {
+            threads: self.threads.get_or_create(&labels).clone(),
+            active_tasks: self.active_tasks.get_or_create(&labels).clone(),
+            task_time: self.task_time.get_or_create(&labels).clone(),
+        }
+    }
+}
+
+/// Metrics of a single thread pool, share state with corresponding metric families
+#[derive(Debug, Clone)]
+pub(crate) struct PoolMetrics {
+    pub(crate) threads: Gauge<i64, AtomicI64>,
+    pub(crate) active_tasks: Gauge<i64, AtomicI64>,
+    pub(crate) task_time: Counter<f64, AtomicU64>,
+}
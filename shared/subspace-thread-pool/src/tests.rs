@@ -0,0 +1,95 @@
+use crate::{ThreadPoolConfig, ThreadPoolManager};
+use std::cell::Cell;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::thread;
+
+fn threads(threads: usize) -> NonZeroUsize {
+    NonZeroUsize::new(threads).unwrap()
+}
+
+#[test]
+fn cpu_budget() {
+    let manager = ThreadPoolManager::new(threads(4));
+
+    let first = manager
+        .build(ThreadPoolConfig::new("first", threads(3)))
+        .unwrap();
+    assert_eq!(first.current_num_threads(), 3);
+    assert_eq!(manager.reserved_threads(), 3);
+
+    // Doesn't fit, shrunk to what is left
+    let second = manager
+        .build(ThreadPoolConfig::new("second", threads(3)))
+        .unwrap();
+    assert_eq!(second.current_num_threads(), 1);
+    assert_eq!(manager.reserved_threads(), 4);
+
+    // Budget is exhausted, but thread pool still gets one thread
+    let third = manager
+        .build(ThreadPoolConfig::new("third", threads(2)))
+        .unwrap();
+    assert_eq!(third.current_num_threads(), 1);
+
+    drop(first);
+    drop(third);
+    assert_eq!(manager.reserved_threads(), 1);
+
+    let fourth = manager
+        .build(ThreadPoolConfig::new("fourth", threads(3)))
+        .unwrap();
+    assert_eq!(fourth.current_num_threads(), 3);
+}
+
+#[test]
+fn budget_groups() {
+    let manager = ThreadPoolManager::new(threads(4));
+
+    let plotting = manager
+        .build(ThreadPoolConfig::new("plotting", threads(4)).group("plotting"))
+        .unwrap();
+    let replotting = manager
+        .build(ThreadPoolConfig::new("replotting", threads(2)).group("plotting"))
+        .unwrap();
+    assert_eq!(plotting.current_num_threads(), 4);
+    assert_eq!(replotting.current_num_threads(), 2);
+    assert_eq!(manager.reserved_threads(), 4);
+
+    drop(plotting);
+    assert_eq!(manager.reserved_threads(), 2);
+}
+
+#[test]
+fn threads_setup() {
+    thread_local! {
+        static WRAPPED: Cell<bool> = const { Cell::new(false) };
+    }
+
+    let manager = ThreadPoolManager::new(threads(2));
+    let thread_pool = manager
+        .build(
+            ThreadPoolConfig::new("test-0", threads(2)).thread_wrapper(Arc::new(|run_thread| {
+                WRAPPED.set(true);
+                run_thread();
+            })),
+        )
+        .unwrap();
+
+    let (thread_name, wrapped) = thread_pool.install(|| {
+        (
+            thread::current().name().map(ToOwned::to_owned),
+            WRAPPED.get(),
+        )
+    });
+    assert!(thread_name.unwrap().starts_with("test-0."));
+    assert!(wrapped);
+
+    let utilization = manager.utilization();
+    assert_eq!(utilization.len(), 1);
+    assert_eq!(utilization[0].name, "test-0");
+    assert_eq!(utilization[0].threads, 2);
+    assert_eq!(utilization[0].active_tasks, 0);
+
+    drop(thread_pool);
+    assert!(manager.utilization().is_empty());
+}
@@ -33,6 +33,7 @@ use sp_core::{Decode, Encode};
 use std::num::{NonZeroU64, NonZeroUsize};
 use std::slice;
 use std::sync::Arc;
+use subspace_archiving::archiver::{Archiver, NewArchivedSegment};
 use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
 use subspace_core_primitives::objects::BlockObjectMapping;
 use subspace_core_primitives::{
@@ -47,8 +48,8 @@ use subspace_runtime_primitives::opaque::Block;
 use subspace_service::{FullClient, NewFull};
 use zeroize::Zeroizing;
 
-// Smaller value for testing purposes
-const MAX_PIECES_IN_SECTOR: u16 = 32;
+/// Max pieces in sector, smaller value for testing purposes
+pub const MAX_PIECES_IN_SECTOR: u16 = 32;
 
 /// The client type being used by the test service.
 pub type Client = FullClient<subspace_test_runtime::RuntimeApi>;
@@ -131,14 +132,17 @@ async fn start_farming<PosTable, Client>(
 
     std::thread::spawn({
         let keypair = keypair.clone();
+        let kzg = kzg.clone();
         let erasure_coding = erasure_coding.clone();
 
         move || {
+            let archived_segment = archive_genesis_segment(client.as_ref(), &kzg);
             let (sector, sector_metadata, table_generator) =
-                block_on(plot_one_segment::<PosTable, _>(
-                    client.as_ref(),
+                block_on(plot_one_segment::<PosTable>(
+                    &archived_segment,
                     &keypair,
                     MAX_PIECES_IN_SECTOR,
+                    &kzg,
                     &erasure_coding,
                     table_generator,
                 ));
@@ -192,31 +196,38 @@ async fn start_farming<PosTable, Client>(
     }
 }
 
-async fn plot_one_segment<PosTable, Client>(
-    client: &Client,
-    keypair: &schnorrkel::Keypair,
-    pieces_in_sector: u16,
-    erasure_coding: &ErasureCoding,
-    mut table_generator: PosTable::Generator,
-) -> (Vec<u8>, PlottedSector, PosTable::Generator)
+/// Archive genesis block of the chain, which always produces the first segment
+pub fn archive_genesis_segment<Client>(client: &Client, kzg: &Kzg) -> NewArchivedSegment
 where
-    PosTable: Table,
     Client: BlockBackend<Block> + HeaderBackend<Block>,
 {
-    let kzg = Kzg::new(embedded_kzg_settings());
-    let mut archiver = subspace_archiving::archiver::Archiver::new(kzg.clone())
-        .expect("Incorrect parameters for archiver");
+    let mut archiver = Archiver::new(kzg.clone()).expect("Incorrect parameters for archiver");
 
     let genesis_block = client.block(client.info().genesis_hash).unwrap().unwrap();
-    let archived_segment = archiver
+    archiver
         .add_block(
             encode_block(genesis_block),
             BlockObjectMapping::default(),
             true,
         )
+        .expect("Archiver is not streaming any blocks; qed")
         .into_iter()
         .next()
-        .expect("First block is always producing one segment; qed");
+        .expect("First block is always producing one segment; qed")
+}
+
+/// Plot one sector in memory using pieces of the archived segment
+pub async fn plot_one_segment<PosTable>(
+    archived_segment: &NewArchivedSegment,
+    keypair: &schnorrkel::Keypair,
+    pieces_in_sector: u16,
+    kzg: &Kzg,
+    erasure_coding: &ErasureCoding,
+    mut table_generator: PosTable::Generator,
+) -> (Vec<u8>, PlottedSector, PosTable::Generator)
+where
+    PosTable: Table,
+{
     let history_size = HistorySize::from(SegmentIndex::ZERO);
     let mut sector = Vec::new();
     let mut sector_metadata = Vec::new();
@@ -239,7 +250,7 @@ where
         piece_getter: &archived_segment.pieces,
         piece_getter_retry_policy: PieceGetterRetryPolicy::default(),
         farmer_protocol_info,
-        kzg: &kzg,
+        kzg,
         erasure_coding,
         pieces_in_sector,
         sector_output: &mut sector,
@@ -0,0 +1,96 @@
+//! Small in-process DSN.
+
+use futures::channel::oneshot;
+use parking_lot::Mutex;
+use sc_service::SpawnTaskHandle;
+use std::sync::Arc;
+use subspace_core_primitives::{ArchivedHistorySegment, Piece};
+use subspace_networking::libp2p::multiaddr::Protocol;
+use subspace_networking::libp2p::Multiaddr;
+use subspace_networking::{Config, Node, PieceByIndexRequestHandler, PieceByIndexResponse};
+use tracing::debug;
+
+/// DSN consisting of a few nodes listening on localhost, all of which serve pieces of the archived
+/// history known to the harness.
+#[derive(Debug)]
+pub struct TestDsn {
+    /// DSN nodes, every node after the first one uses the first node for bootstrapping
+    pub nodes: Vec<Node>,
+    /// Address of the first node, can be used for bootstrapping additional nodes
+    pub bootstrap_address: Multiaddr,
+}
+
+impl TestDsn {
+    pub(crate) async fn start(
+        num_nodes: usize,
+        archived_history: Arc<ArchivedHistorySegment>,
+        spawn_handle: SpawnTaskHandle,
+    ) -> Self {
+        assert!(num_nodes > 0, "DSN must have at least one node");
+
+        let mut nodes = Vec::with_capacity(num_nodes);
+        let mut bootstrap_address = None::<Multiaddr>;
+
+        for _ in 0..num_nodes {
+            let config = Config {
+                listen_on: vec!["/ip4/127.0.0.1/tcp/0"
+                    .parse()
+                    .expect("Correct multiaddr; qed")],
+                allow_non_global_addresses_in_dht: true,
+                request_response_protocols: vec![PieceByIndexRequestHandler::create({
+                    let archived_history = Arc::clone(&archived_history);
+
+                    move |_, request| {
+                        let piece = usize::try_from(u64::from(request.piece_index))
+                            .ok()
+                            .and_then(|position| archived_history.get(position))
+                            .map(Piece::from);
+
+                        async move { Some(PieceByIndexResponse { piece }) }
+                    }
+                })],
+                bootstrap_addresses: bootstrap_address.iter().cloned().collect(),
+                ..Config::default()
+            };
+
+            let (node, mut node_runner) =
+                subspace_networking::construct(config).expect("Networking stack creation failed");
+
+            let (address_sender, address_receiver) = oneshot::channel();
+            let on_new_listener_handler = node.on_new_listener(Arc::new({
+                let address_sender = Mutex::new(Some(address_sender));
+
+                move |address| {
+                    if matches!(address.iter().next(), Some(Protocol::Ip4(_))) {
+                        if let Some(address_sender) = address_sender.lock().take() {
+                            let _ = address_sender.send(address.clone());
+                        }
+                    }
+                }
+            }));
+
+            spawn_handle.spawn("dsn-node", Some("subspace-test-harness"), async move {
+                node_runner.run().await;
+            });
+
+            let address = address_receiver
+                .await
+                .expect("Node runner is running and will report listen address; qed");
+            drop(on_new_listener_handler);
+            debug!(peer_id = %node.id(), %address, "Started DSN node");
+
+            if bootstrap_address.is_none() {
+                bootstrap_address.replace(address.with(Protocol::P2p(node.id())));
+            } else if let Err(error) = node.bootstrap().await {
+                debug!(%error, "Failed to bootstrap DSN node");
+            }
+
+            nodes.push(node);
+        }
+
+        Self {
+            nodes,
+            bootstrap_address: bootstrap_address.expect("At least one node was started; qed"),
+        }
+    }
+}
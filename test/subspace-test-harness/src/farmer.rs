@@ -0,0 +1,127 @@
+//! Mini-farmer with a single sector plotted in memory.
+
+use futures::channel::mpsc;
+use futures::StreamExt;
+use sp_consensus_slots::Slot;
+use subspace_archiving::archiver::NewArchivedSegment;
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::{PosSeed, PublicKey, Solution, SolutionRange};
+use subspace_erasure_coding::ErasureCoding;
+use subspace_farmer_components::auditing::audit_sector_sync;
+use subspace_proof_of_space::shim::ShimTable;
+use subspace_proof_of_space::{Table, TableGenerator};
+use subspace_test_client::{plot_one_segment, MAX_PIECES_IN_SECTOR};
+use subspace_test_service::MockConsensusNode;
+use tracing::{debug, warn};
+
+/// Proof of space table used by the mini-farmer, mock consensus node doesn't verify solutions, so
+/// the cheapest table is used
+type PosTable = ShimTable;
+
+/// Farmer that plots a single sector from the first archived segment in memory and audits it on
+/// every slot notified by the consensus node.
+///
+/// Solution range is ignored, every slot produces a solution, which makes it easy to exercise
+/// proving code paths deterministically in tests.
+#[derive(Debug)]
+pub struct MiniFarmer {
+    public_key: PublicKey,
+    solutions_receiver: mpsc::UnboundedReceiver<(Slot, Solution<PublicKey, PublicKey>)>,
+}
+
+impl MiniFarmer {
+    pub(crate) async fn start(
+        consensus: &mut MockConsensusNode,
+        archived_segment: &NewArchivedSegment,
+        kzg: &Kzg,
+        erasure_coding: &ErasureCoding,
+    ) -> Self {
+        let keypair = schnorrkel::Keypair::generate();
+        let public_key = PublicKey::from(keypair.public.to_bytes());
+
+        let (sector, plotted_sector, mut table_generator) = plot_one_segment::<PosTable>(
+            archived_segment,
+            &keypair,
+            MAX_PIECES_IN_SECTOR,
+            kzg,
+            erasure_coding,
+            PosTable::generator(),
+        )
+        .await;
+
+        let mut slot_notifications = consensus.new_slot_notification_stream();
+        let (solutions_sender, solutions_receiver) = mpsc::unbounded();
+
+        consensus.task_manager.spawn_handle().spawn_blocking(
+            "mini-farmer",
+            Some("subspace-test-harness"),
+            {
+                let kzg = kzg.clone();
+                let erasure_coding = erasure_coding.clone();
+
+                async move {
+                    while let Some((slot, proof_of_time)) = slot_notifications.next().await {
+                        let global_challenge = proof_of_time
+                            .derive_global_randomness()
+                            .derive_global_challenge(slot.into());
+                        let audit_result = match audit_sector_sync(
+                            &public_key,
+                            &global_challenge,
+                            SolutionRange::MAX,
+                            &sector,
+                            &plotted_sector.sector_metadata,
+                        ) {
+                            Ok(Some(audit_result)) => audit_result,
+                            Ok(None) => {
+                                debug!(%slot, "No solution candidates");
+                                continue;
+                            }
+                            Err(error) => {
+                                warn!(%error, %slot, "Failed to audit sector");
+                                continue;
+                            }
+                        };
+
+                        let maybe_solution = audit_result
+                            .solution_candidates
+                            .into_solutions(&public_key, &kzg, &erasure_coding, |seed: &PosSeed| {
+                                table_generator.generate(seed)
+                            })
+                            .ok()
+                            .and_then(|mut solutions| solutions.next());
+
+                        match maybe_solution {
+                            Some(Ok(solution)) => {
+                                if solutions_sender.unbounded_send((slot, solution)).is_err() {
+                                    break;
+                                }
+                            }
+                            Some(Err(error)) => {
+                                warn!(%error, %slot, "Failed to prove solution");
+                            }
+                            None => {
+                                debug!(%slot, "No solutions");
+                            }
+                        }
+                    }
+                }
+            },
+        );
+
+        Self {
+            public_key,
+            solutions_receiver,
+        }
+    }
+
+    /// Public key of the farmer
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Wait for the next solution, solutions are produced in response to slots notified by the
+    /// consensus node
+    pub async fn next_solution(&mut self) -> Option<(Slot, Solution<PublicKey, PublicKey>)> {
+        self.solutions_receiver.next().await
+    }
+}
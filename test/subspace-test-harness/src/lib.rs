@@ -0,0 +1,233 @@
+// Copyright (C) 2023 Subspace Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! End-to-end test harness.
+//!
+//! Spins up in-process a consensus node with mocked (and thus instant) proof of time, optionally
+//! together with a small DSN, a mini-farmer with reduced parameters and an EVM domain operator,
+//! all wired together and exposed as [`TestNetwork`].
+//!
+//! ```ignore
+//! let mut network = TestNetworkBuilder::new(tokio_handle, base_path)
+//!     .dsn_nodes(2)
+//!     .with_farmer()
+//!     .with_domain_operator(GENESIS_DOMAIN_ID)
+//!     .build()
+//!     .await;
+//!
+//! network.produce_blocks(3).await?;
+//! ```
+
+#![warn(missing_docs, unused_crate_dependencies)]
+
+mod dsn;
+mod farmer;
+#[cfg(test)]
+mod tests;
+
+pub use crate::dsn::TestDsn;
+pub use crate::farmer::MiniFarmer;
+use domain_test_service::{DomainNodeBuilder, EcdsaKeyring, EvmDomainNode};
+use sc_service::{BasePath, Role};
+use sp_consensus_slots::Slot;
+use sp_domains::DomainId;
+use sp_keyring::Sr25519Keyring;
+use std::error::Error;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use subspace_archiving::archiver::NewArchivedSegment;
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::{PotOutput, Record};
+use subspace_erasure_coding::ErasureCoding;
+use subspace_test_client::archive_genesis_segment;
+use subspace_test_service::MockConsensusNode;
+
+/// A builder to create a [`TestNetwork`].
+pub struct TestNetworkBuilder {
+    tokio_handle: tokio::runtime::Handle,
+    base_path: BasePath,
+    consensus_key: Sr25519Keyring,
+    dsn_nodes: usize,
+    farmer: bool,
+    domain_operator: Option<(DomainId, EcdsaKeyring)>,
+}
+
+impl TestNetworkBuilder {
+    /// Create a new instance of `Self`.
+    ///
+    /// `tokio_handle` - The tokio handler to use.
+    /// `base_path` - Where databases of all nodes will be stored.
+    pub fn new(tokio_handle: tokio::runtime::Handle, base_path: BasePath) -> Self {
+        Self {
+            tokio_handle,
+            base_path,
+            consensus_key: Sr25519Keyring::Ferdie,
+            dsn_nodes: 0,
+            farmer: false,
+            domain_operator: None,
+        }
+    }
+
+    /// Key used by consensus node, `Ferdie` by default.
+    pub fn consensus_key(mut self, key: Sr25519Keyring) -> Self {
+        self.consensus_key = key;
+        self
+    }
+
+    /// Start DSN with specified number of nodes, no DSN is started by default.
+    pub fn dsn_nodes(mut self, dsn_nodes: usize) -> Self {
+        self.dsn_nodes = dsn_nodes;
+        self
+    }
+
+    /// Start a [`MiniFarmer`].
+    pub fn with_farmer(mut self) -> Self {
+        self.farmer = true;
+        self
+    }
+
+    /// Start EVM domain operator for `domain_id` using `Alice` key.
+    pub fn with_domain_operator(self, domain_id: DomainId) -> Self {
+        self.with_domain_operator_key(domain_id, EcdsaKeyring::Alice)
+    }
+
+    /// Start EVM domain operator for `domain_id` using specified key.
+    pub fn with_domain_operator_key(mut self, domain_id: DomainId, key: EcdsaKeyring) -> Self {
+        self.domain_operator.replace((domain_id, key));
+        self
+    }
+
+    /// Start all configured components and wire them together.
+    pub async fn build(self) -> TestNetwork {
+        let Self {
+            tokio_handle,
+            base_path,
+            consensus_key,
+            dsn_nodes,
+            farmer,
+            domain_operator,
+        } = self;
+
+        let mut consensus = MockConsensusNode::run(
+            tokio_handle.clone(),
+            consensus_key,
+            BasePath::new(base_path.path().join("consensus")),
+        );
+
+        let kzg = Kzg::new(embedded_kzg_settings());
+        let erasure_coding = ErasureCoding::new(
+            NonZeroUsize::new(Record::NUM_S_BUCKETS.next_power_of_two().ilog2() as usize)
+                .expect("Not zero; qed"),
+        )
+        .expect("Correct erasure coding parameters; qed");
+        let archived_segment = Arc::new(archive_genesis_segment(consensus.client.as_ref(), &kzg));
+
+        let dsn = if dsn_nodes > 0 {
+            Some(
+                TestDsn::start(
+                    dsn_nodes,
+                    Arc::new(archived_segment.pieces.clone()),
+                    consensus.task_manager.spawn_handle(),
+                )
+                .await,
+            )
+        } else {
+            None
+        };
+
+        let farmer = if farmer {
+            Some(MiniFarmer::start(&mut consensus, &archived_segment, &kzg, &erasure_coding).await)
+        } else {
+            None
+        };
+
+        let domain_operator = match domain_operator {
+            Some((domain_id, key)) => Some(
+                DomainNodeBuilder::new(
+                    tokio_handle,
+                    key,
+                    BasePath::new(base_path.path().join("domain-operator")),
+                )
+                .build_evm_node(Role::Authority, domain_id, &mut consensus)
+                .await,
+            ),
+            None => None,
+        };
+
+        TestNetwork {
+            consensus,
+            archived_segment,
+            kzg,
+            erasure_coding,
+            dsn,
+            farmer,
+            domain_operator,
+        }
+    }
+}
+
+/// Network of in-process components started by [`TestNetworkBuilder`].
+pub struct TestNetwork {
+    /// Consensus node with mocked proof of time, slots are produced on demand
+    pub consensus: MockConsensusNode,
+    /// First archived segment of the chain, pieces of which are served by DSN and plotted by
+    /// farmer
+    pub archived_segment: Arc<NewArchivedSegment>,
+    /// KZG instance
+    pub kzg: Kzg,
+    /// Erasure coding instance
+    pub erasure_coding: ErasureCoding,
+    /// DSN, if requested
+    pub dsn: Option<TestDsn>,
+    /// Farmer, if requested
+    pub farmer: Option<MiniFarmer>,
+    /// Domain operator, if requested
+    pub domain_operator: Option<EvmDomainNode>,
+}
+
+impl TestNetwork {
+    /// Produce a new slot and notify all slot subscribers (farmer and domain operator) about it,
+    /// without producing a block.
+    pub async fn produce_slot(&mut self) -> (Slot, PotOutput) {
+        let slot = self.consensus.produce_slot();
+        self.consensus
+            .notify_new_slot_and_wait_for_bundle(slot)
+            .await;
+        slot
+    }
+
+    /// Produce `n` consensus blocks, every slot is notified to slot subscribers first.
+    ///
+    /// When domain operator is running, waits for bundles to be included and for domain blocks to
+    /// be produced as well.
+    pub async fn produce_blocks(&mut self, n: u64) -> Result<(), Box<dyn Error>> {
+        match &self.domain_operator {
+            Some(domain_operator) => {
+                let domain_blocks = domain_operator.wait_for_blocks(n as usize);
+                self.consensus.produce_blocks_with_bundles(n).await?;
+                domain_blocks.await;
+            }
+            None => {
+                for _ in 0..n {
+                    let slot = self.produce_slot().await;
+                    self.consensus.produce_block_with_slot(slot).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
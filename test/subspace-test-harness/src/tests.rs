@@ -0,0 +1,43 @@
+use crate::TestNetworkBuilder;
+use sc_service::BasePath;
+use subspace_core_primitives::{Piece, PieceIndex};
+use subspace_networking::PieceByIndexRequest;
+use tempfile::TempDir;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn farmer_and_dsn() {
+    let directory = TempDir::new().expect("Must be able to create temporary directory");
+
+    let mut network = TestNetworkBuilder::new(
+        tokio::runtime::Handle::current(),
+        BasePath::new(directory.path()),
+    )
+    .dsn_nodes(2)
+    .with_farmer()
+    .build()
+    .await;
+
+    network.produce_blocks(2).await.unwrap();
+
+    let public_key = *network.farmer.as_ref().unwrap().public_key();
+    let (slot, solution) = network
+        .farmer
+        .as_mut()
+        .unwrap()
+        .next_solution()
+        .await
+        .unwrap();
+    assert_eq!(u64::from(slot), 1);
+    assert_eq!(solution.public_key, public_key);
+
+    let dsn = network.dsn.as_ref().unwrap();
+    let piece_index = PieceIndex::from(1);
+    let response = dsn.nodes[1]
+        .send_generic_request(dsn.nodes[0].id(), PieceByIndexRequest::new(piece_index))
+        .await
+        .unwrap();
+    assert_eq!(
+        response.piece,
+        Some(Piece::from(&network.archived_segment.pieces[1]))
+    );
+}
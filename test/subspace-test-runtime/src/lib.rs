@@ -39,7 +39,7 @@ use frame_support::weights::constants::{RocksDbWeight, WEIGHT_REF_TIME_PER_SECON
 use frame_support::weights::{ConstantMultiplier, IdentityFee, Weight};
 use frame_support::{construct_runtime, parameter_types, PalletId};
 use frame_system::limits::{BlockLength, BlockWeights};
-use frame_system::EnsureNever;
+use frame_system::{EnsureNever, EnsureRoot};
 use pallet_balances::NegativeImbalance;
 pub use pallet_subspace::{AllowAuthoringBy, EnableRewardsAt};
 use pallet_transporter::EndpointHandler;
@@ -47,8 +47,8 @@ use scale_info::TypeInfo;
 use sp_api::impl_runtime_apis;
 use sp_consensus_slots::{Slot, SlotDuration};
 use sp_consensus_subspace::{
-    ChainConstants, EquivocationProof, FarmerPublicKey, PotParameters, SignedVote, SolutionRanges,
-    Vote,
+    ChainConstants, EquivocationProof, FarmerPublicKey, PotParameters, PotSchedule,
+    SegmentArchivalInfo, SignedVote, SolutionRanges, Vote,
 };
 use sp_core::crypto::{ByteArray, KeyTypeId};
 use sp_core::{OpaqueMetadata, H256};
@@ -59,7 +59,9 @@ use sp_domains::{
 };
 use sp_domains_fraud_proof::fraud_proof::FraudProof;
 use sp_messenger::endpoint::{Endpoint, EndpointHandler as EndpointHandlerT, EndpointId};
-use sp_messenger::messages::{BlockMessagesWithStorageKey, ChainId, CrossDomainMessage, MessageId};
+use sp_messenger::messages::{
+    BlockMessagesWithStorageKey, ChainId, CrossDomainMessage, DeadLetter, MessageId,
+};
 use sp_messenger_host_functions::{get_storage_key, StorageKeyRequest};
 use sp_mmr_primitives::{EncodableOpaqueLeaf, Proof};
 use sp_runtime::traits::{
@@ -76,10 +78,12 @@ use sp_std::marker::PhantomData;
 use sp_std::prelude::*;
 use sp_version::RuntimeVersion;
 use static_assertions::const_assert;
-use subspace_core_primitives::objects::{BlockObject, BlockObjectMapping};
+use subspace_core_primitives::objects::{
+    BlockObject, BlockObjectMapping, ObjectMappingLimits, ObjectMappingOverflowPolicy,
+};
 use subspace_core_primitives::{
-    HistorySize, Piece, Randomness, SegmentCommitment, SegmentHeader, SegmentIndex, SlotNumber,
-    SolutionRange, U256,
+    Blake3Hash, HistorySize, Piece, Randomness, SegmentCommitment, SegmentHeader, SegmentIndex,
+    SlotNumber, SolutionRange, U256,
 };
 use subspace_runtime_primitives::{
     AccountId, Balance, BlockNumber, Hash, Moment, Nonce, Signature, MIN_REPLICATION_FACTOR,
@@ -190,6 +194,13 @@ const MAX_BLOCK_LENGTH: u32 = 5 * 1024 * 1024;
 
 const MAX_OBJECT_MAPPING_RECURSION_DEPTH: u16 = 5;
 
+/// Limits on object mapping of a single block, objects past the limits are not mapped
+const OBJECT_MAPPING_LIMITS: ObjectMappingLimits = ObjectMappingLimits {
+    max_objects: 1_000,
+    max_size: 64 * 1024,
+    overflow_policy: ObjectMappingOverflowPolicy::Truncate,
+};
+
 parameter_types! {
     pub const Version: RuntimeVersion = VERSION;
     pub const BlockHashCount: BlockNumber = 2400;
@@ -295,6 +306,7 @@ impl pallet_subspace::Config for Runtime {
         OffencesSubspace,
         ConstU64<{ EQUIVOCATION_REPORT_LONGEVITY as u64 }>,
     >;
+    type UnixTime = Timestamp;
 
     type WeightInfo = ();
 }
@@ -397,6 +409,9 @@ impl pallet_transaction_fees::Config for Runtime {
     type Currency = Balances;
     type FindBlockRewardAddress = Subspace;
     type DynamicCostOfStorage = ConstBool<false>;
+    type MaxDataBytesPerBlock = ConstU32<MAX_BLOCK_LENGTH>;
+    // No rebates, fees are charged in full
+    type DataFeeRebate = ();
     type WeightInfo = ();
 }
 
@@ -521,6 +536,7 @@ impl pallet_sudo::Config for Runtime {
 
 parameter_types! {
     pub const RelayConfirmationDepth: BlockNumber = 18;
+    pub const MaxDeadLetters: u32 = 100;
     pub SelfChainId: ChainId = ChainId::Consensus;
 }
 
@@ -580,6 +596,9 @@ impl pallet_messenger::Config for Runtime {
     type MmrHash = mmr::Hash;
     type MmrProofVerifier = MmrProofVerifier;
     type StorageKeys = StorageKeys;
+    type ChainAllowlistOrigin = EnsureRoot<AccountId>;
+    type DeadLetterOrigin = EnsureRoot<AccountId>;
+    type MaxDeadLetters = MaxDeadLetters;
 }
 
 impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
@@ -624,6 +643,7 @@ parameter_types! {
     pub const DomainInstantiationDeposit: Balance = 100 * SSC;
     pub const MaxDomainNameLength: u32 = 32;
     pub const BlockTreePruningDepth: u32 = 16;
+    pub const ConfirmedDomainBlockMappingDepth: u32 = 256;
     pub const StakeWithdrawalLockingPeriod: BlockNumber = 20;
     pub const StakeEpochDuration: DomainNumber = 5;
     pub TreasuryAccount: AccountId = PalletId(*b"treasury").into_account_truncating();
@@ -633,6 +653,7 @@ parameter_types! {
     pub const DomainsPalletId: PalletId = PalletId(*b"domains_");
     pub const MaxInitialDomainAccounts: u32 = 20;
     pub const MinInitialDomainAccountBalance: Balance = SSC;
+    pub const MaxDomainTreasuryShare: Perbill = Perbill::from_percent(50);
 }
 
 // Minimum operator stake must be >= minimum nominator stake since operator is also a nominator.
@@ -669,6 +690,7 @@ impl pallet_domains::Config for Runtime {
     type MaxDomainNameLength = MaxDomainNameLength;
     type Share = Balance;
     type BlockTreePruningDepth = BlockTreePruningDepth;
+    type ConfirmedDomainBlockMappingDepth = ConfirmedDomainBlockMappingDepth;
     type StakeWithdrawalLockingPeriod = StakeWithdrawalLockingPeriod;
     type StakeEpochDuration = StakeEpochDuration;
     type TreasuryAccount = TreasuryAccount;
@@ -683,6 +705,7 @@ impl pallet_domains::Config for Runtime {
     type DomainsTransfersTracker = Transporter;
     type MaxInitialDomainAccounts = MaxInitialDomainAccounts;
     type MinInitialDomainAccountBalance = MinInitialDomainAccountBalance;
+    type MaxDomainTreasuryShare = MaxDomainTreasuryShare;
 }
 
 parameter_types! {
@@ -1097,6 +1120,15 @@ impl_runtime_apis! {
             // No pallets produce objects right now
             Vec::new()
         }
+
+        fn object_mapping_limits() -> ObjectMappingLimits {
+            OBJECT_MAPPING_LIMITS
+        }
+
+        fn extrinsic_object_hashes(_extrinsic: <Block as BlockT>::Extrinsic) -> Vec<Blake3Hash> {
+            // No pallets produce objects right now
+            Vec::new()
+        }
     }
 
     impl sp_consensus_subspace::SubspaceApi<Block, FarmerPublicKey> for Runtime {
@@ -1190,6 +1222,14 @@ impl_runtime_apis! {
                 min_sector_lifetime: MinSectorLifetime::get(),
             }
         }
+
+        fn segment_archival_info(segment_index: SegmentIndex) -> Option<SegmentArchivalInfo> {
+            Subspace::segment_archival_info(segment_index)
+        }
+
+        fn pot_schedule() -> PotSchedule {
+            Subspace::pot_schedule()
+        }
     }
 
     impl sp_domains::DomainsApi<Block, DomainHeader> for Runtime {
@@ -1297,6 +1337,14 @@ impl_runtime_apis! {
         fn consensus_chain_byte_fee() -> Balance {
             DOMAIN_STORAGE_FEE_MULTIPLIER * TransactionFees::transaction_byte_fee()
         }
+
+        fn domain_treasury_balance(domain_id: DomainId) -> Balance {
+            Domains::domain_treasury_balance(domain_id)
+        }
+
+        fn confirmed_domain_block_consensus_number(domain_id: DomainId, domain_number: DomainNumber) -> Option<BlockNumber> {
+            Domains::confirmed_domain_block_consensus_number(domain_id, domain_number)
+        }
     }
 
     impl sp_domains::BundleProducerElectionApi<Block, Balance> for Runtime {
@@ -1307,6 +1355,10 @@ impl_runtime_apis! {
         fn operator(operator_id: OperatorId) -> Option<(OperatorPublicKey, Balance)> {
             Domains::operator(operator_id)
         }
+
+        fn operator_pending_signing_key(operator_id: OperatorId) -> Option<OperatorPublicKey> {
+            Domains::operator_pending_signing_key(operator_id)
+        }
     }
 
     impl sp_session::SessionKeys<Block> for Runtime {
@@ -1366,6 +1418,10 @@ impl_runtime_apis! {
         fn inbox_response_storage_key(message_id: MessageId) -> Vec<u8> {
             Messenger::inbox_response_storage_key(message_id)
         }
+
+        fn dead_letters() -> Vec<(ChainId, MessageId, DeadLetter<BlockNumber>)> {
+            Messenger::dead_letters()
+        }
     }
 
     impl sp_messenger::RelayerApi<Block, BlockNumber> for Runtime {